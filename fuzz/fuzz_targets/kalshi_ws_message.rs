@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use white_shark::fixtures::decoded_fields;
+use white_shark::recorder::FrameSource;
+
+// Parses the envelope and the typed payload, and applies snapshots to an
+// orderbook, mirroring what the message handler does with a text frame.
+fuzz_target!(|data: &[u8]| {
+    let _ = decoded_fields(FrameSource::KalshiJson, data);
+});
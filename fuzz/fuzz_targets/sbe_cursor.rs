@@ -0,0 +1,31 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use white_shark::exchanges::binance::sbe::utils::{read_group_size, read_group_size16, SbeCursor};
+
+// The first bytes pick a sequence of cursor operations, the rest is the buffer
+// they run against.
+fuzz_target!(|data: &[u8]| {
+    if data.is_empty() {
+        return;
+    }
+    let op_count = (data[0] as usize % 16).min(data.len() - 1);
+    let (ops, payload) = data[1..].split_at(op_count);
+
+    let mut cursor = SbeCursor::new(payload);
+    for op in ops {
+        let _ = match op % 9 {
+            0 => cursor.read_u8().map(drop),
+            1 => cursor.read_i8().map(drop),
+            2 => cursor.read_u16_le().map(drop),
+            3 => cursor.read_u32_le().map(drop),
+            4 => cursor.read_i64_le().map(drop),
+            5 => cursor.read_var_string8().map(drop),
+            6 => cursor.skip((op >> 4) as usize),
+            7 => read_group_size(&mut cursor).map(drop),
+            _ => read_group_size16(&mut cursor).map(drop),
+        };
+        assert!(cursor.position() <= payload.len());
+        assert_eq!(cursor.remaining(), payload.len() - cursor.position());
+    }
+});
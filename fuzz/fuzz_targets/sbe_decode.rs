@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use white_shark::exchanges::binance::sbe::decoder::SbeDecoder;
+use white_shark::exchanges::binance::sbe::messages::SbeMessage;
+
+fuzz_target!(|data: &[u8]| {
+    let decoder = SbeDecoder::new();
+    if let Ok(msg) = decoder.decode(data) {
+        let _ = msg.symbol();
+        let _ = msg.timestamp();
+        if let SbeMessage::DepthSnapshot(depth) = &msg {
+            let _ = depth.bids.sum_qtys_top5_top10_all();
+            let _ = depth.asks.sum_qtys_top5_top10_all();
+        }
+    }
+});
@@ -1,27 +1,498 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use tracing::{error, info};
+use tokio::sync::mpsc;
+#[cfg(feature = "sbe")]
+use tokio::sync::watch;
+use tracing::{error, info, warn};
 
-use crate::config::Config;
-use crate::db::main::Db;
+#[cfg(feature = "archive")]
+use crate::archive::Archiver;
+use crate::calendar;
+#[cfg(feature = "sbe")]
+use crate::config::BinanceConfig;
+use crate::config::{Config, Persistence};
+use crate::db::main::{BufferedWriter, Db};
+#[cfg(feature = "db")]
+use crate::db::maintenance::Maintenance;
+use crate::encryption;
 use crate::error::Result;
+#[cfg(feature = "sbe")]
+use crate::exchanges::binance::alerts::ImbalanceAlertWriter;
+#[cfg(feature = "sbe")]
+use crate::exchanges::binance::client::BinanceClient;
+#[cfg(feature = "sbe")]
+use crate::exchanges::binance::sbe::events::depth::ImbalanceAlert;
+#[cfg(feature = "sbe")]
+use crate::exchanges::depth::{self, BinanceDepth, DepthVenue, ImbalanceDetector, OkxDepth};
 use crate::exchanges::kalshi::KalshiClient;
+use crate::exchanges::okx::OkxClient;
+use crate::exchanges::polymarket::PolymarketClient;
+use crate::exchanges::MarketEvent;
+use crate::heartbeat::{Heartbeat, HeartbeatHandle};
+use crate::instruments;
+use crate::latency_race::LatencyRace;
+use crate::leader::{LeaderElector, LeaderHandle};
+use crate::monitor::MonitorWriter;
+use crate::metrics::MetricsPusher;
+use crate::notify::discord::DiscordNotifier;
+use crate::notify::slack::SlackNotifier;
+use crate::notify::webhook::JsonWebhookNotifier;
+use crate::notify;
+use crate::recorder::Recorder;
+use crate::rules::RuleEngine;
+use crate::reload::ConfigReloader;
+use crate::self_test;
+#[cfg(feature = "dashboard")]
+use crate::server::dashboard::{self, DashboardState};
+#[cfg(feature = "grpc")]
+use crate::server::FlightServer;
+#[cfg(feature = "http")]
+use crate::server::HttpServer;
+use crate::shutdown;
+use crate::signals::SignalEngine;
+#[cfg(feature = "duckdb")]
+use crate::sinks::DuckDbStore;
+#[cfg(feature = "parquet")]
+use crate::sinks::ParquetWriter;
+#[cfg(feature = "zmq")]
+use crate::sinks::ZmqPublisher;
+use crate::sinks::{CsvExporter, InfluxWriter, SINK_CHANNEL_BUFFER};
+#[cfg(feature = "sbe")]
+use crate::state::KalshiState;
+use crate::state::SessionStats;
+use crate::status;
+use crate::systemd;
+use crate::tags;
+use crate::timezone;
+#[cfg(feature = "sbe")]
+use crate::trader::venue::BinanceMarket;
+
+/// Time allowed for closing connections and flushing writers once stopped,
+/// inside systemd's `TimeoutStopSec`.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(20);
 
 pub async fn run(config: Config) -> Result<()> {
     info!("🦈 Started");
     info!("================================");
 
-    let db = Arc::new(Db::new(&config.database.url).await?);
+    timezone::init(config.timezone);
+    if !config.tags.is_default() {
+        info!("🏷️ Account {}, strategy {}", config.tags.account, config.tags.strategy);
+    }
+    tags::init(config.tags.clone());
+    if config.encryption.is_some() {
+        info!("🔒 Encrypting recordings and reports at rest");
+    }
+    encryption::init(config.encryption.clone());
+    if let Some(stats) = config.stats {
+        SessionStats::spawn_logger(Duration::from_secs(stats.interval_mins * 60));
+    }
+    if let Some(calendar_config) = &config.calendar {
+        calendar::spawn(calendar_config.clone());
+    }
+    if let Some(self_test_config) = config.self_test {
+        self_test::run(&config, self_test_config).await?;
+    }
+    for (symbol, precision) in &config.precision_overrides {
+        instruments::registry().register(symbol, *precision);
+    }
+    for (binance_symbol, kalshi_series) in &config.symbol_map {
+        info!("🔗 {} -> {}", binance_symbol, kalshi_series);
+        instruments::registry().link(binance_symbol, kalshi_series);
+    }
+
+    if let Some(push_config) = &config.metrics_push {
+        MetricsPusher::spawn(push_config);
+    }
+
+    let db = match &config.database {
+        Some(database_config) => Some(Arc::new(Db::new(&database_config.url).await?)),
+        None => {
+            warn!("DATABASE_URL not set, ticks and settlements will not be persisted");
+            None
+        }
+    };
+
+    let leader = match (&config.leader, &db) {
+        (Some(leader_config), Some(db)) => LeaderElector::spawn(leader_config, db.clone()).await?,
+        _ => LeaderHandle::always(),
+    };
+
+    let persist = config
+        .database
+        .as_ref()
+        .map(|database_config| database_config.persist)
+        .unwrap_or_default();
+    let market_data_tx = match (&config.database, &db) {
+        (Some(database_config), Some(db)) if persist.market_data != Persistence::Off => {
+            Some(BufferedWriter::spawn(db.clone(), database_config))
+        }
+        _ => None,
+    };
+
+    #[cfg(feature = "grpc")]
+    if let (Some(flight_config), Some(db)) = (&config.flight, &db) {
+        FlightServer::spawn(flight_config, db.clone())?;
+    }
+    #[cfg(not(feature = "grpc"))]
+    if config.flight.is_some() {
+        warn!("FLIGHT_BIND_ADDR set but built without the grpc feature, Flight server disabled");
+    }
 
+    if let Some(shard) = &config.shard {
+        info!("🧩 Running shard {} of {}", shard.index, shard.count);
+    }
     info!("Kalshi symbols: {:?}", config.kalshi.tracked_symbols);
 
+    let mut sink_txs = Vec::new();
+    #[cfg(feature = "zmq")]
+    if let Some(zmq_config) = &config.zmq {
+        sink_txs.push(ZmqPublisher::spawn(zmq_config)?);
+    }
+    #[cfg(not(feature = "zmq"))]
+    if config.zmq.is_some() {
+        warn!("ZMQ_PUB_ENDPOINT set but built without the zmq feature, ZeroMQ sink disabled");
+    }
+    if let Some(influx_config) = &config.influx {
+        sink_txs.push(InfluxWriter::spawn(influx_config));
+    }
+    if let Some(csv_config) = &config.csv_export {
+        sink_txs.push(CsvExporter::spawn(csv_config, leader.clone()));
+    }
+    #[cfg(feature = "parquet")]
+    if let Some(parquet_config) = &config.parquet {
+        sink_txs.push(ParquetWriter::spawn(parquet_config)?);
+    }
+    #[cfg(not(feature = "parquet"))]
+    if config.parquet.is_some() {
+        warn!("PARQUET_DIR set but built without the parquet feature, Parquet sink disabled");
+    }
+    #[cfg(feature = "duckdb")]
+    if let Some(duckdb_config) = &config.duckdb {
+        sink_txs.push(DuckDbStore::spawn_writer(duckdb_config)?);
+    }
+    #[cfg(not(feature = "duckdb"))]
+    if config.duckdb.is_some() {
+        warn!("DUCKDB_PATH set but built without the duckdb feature, DuckDB sink disabled");
+    }
+    if let Some(race_config) = config.latency_race {
+        sink_txs.push(LatencyRace::spawn(race_config, db.clone(), persist.latency_races));
+    }
+    let recorder = match &config.recorder {
+        Some(recorder_config) => Some(Recorder::spawn(recorder_config)?),
+        None => None,
+    };
+    if let (Some(recorder_config), Some(handle)) = (&config.recorder, &recorder) {
+        if recorder_config.events {
+            sink_txs.push(handle.event_sink());
+        }
+    }
+
+    #[cfg(feature = "archive")]
+    if let Some(archive_config) = &config.archive {
+        Archiver::new(archive_config.clone())?.spawn();
+    }
+    #[cfg(not(feature = "archive"))]
+    if config.archive.is_some() {
+        warn!("ARCHIVE_URL set but built without the archive feature, archival disabled");
+    }
+
+    #[cfg(feature = "db")]
+    let maintenance = config.database.as_ref().and_then(|database_config| database_config.maintenance);
+    #[cfg(feature = "db")]
+    if let (Some(maintenance_config), Some(db)) = (maintenance, &db) {
+        Maintenance::new(db.clone(), maintenance_config).spawn(leader.clone());
+    }
+
+    let heartbeat = match &config.heartbeat {
+        Some(heartbeat_config) => Heartbeat::spawn(heartbeat_config),
+        None => HeartbeatHandle::disabled(),
+    };
+    systemd::spawn_watchdog(heartbeat.clone());
+
     let kalshi_config = config.kalshi.clone();
-    let mut kalshi_client = KalshiClient::new(kalshi_config, db)?;
+    let mut kalshi_client =
+        KalshiClient::new(kalshi_config, db.clone(), sink_txs.clone())?
+            .with_market_data_writer(market_data_tx)
+            .with_recorder(recorder.clone())
+            .with_chaos(config.chaos.clone())
+            .with_leader(leader.clone())
+            .with_heartbeat(heartbeat);
+    let kalshi_reload_tx = kalshi_client.reload_handle();
+
+    if let (Some(database_config), Some(db)) = (&config.database, &db) {
+        if database_config.monitor_window_secs > 0 {
+            let monitor_tx = MonitorWriter::spawn(
+                db.clone(),
+                kalshi_client.shared_state(),
+                database_config.monitor_window_secs,
+                leader.clone(),
+            );
+            kalshi_client = kalshi_client.with_sink(monitor_tx.clone());
+            sink_txs.push(monitor_tx);
+        }
+    }
+
+    if let Some(signals_config) = &config.signals {
+        // Its alerts go to the other sinks, never back into the engine
+        let alerts_tx = fan_out("signal_alerts", sink_txs.clone(), leader.clone());
+        let signals_tx = SignalEngine::new(signals_config)
+            .with_events(Some(alerts_tx))
+            .with_kalshi(Some(kalshi_client.shared_state()))
+            .spawn();
+        kalshi_client = kalshi_client.with_sink(signals_tx.clone());
+        sink_txs.push(signals_tx);
+    }
+
+    // Like the Kalshi client's, the other feeds reach the sinks only while
+    // this instance leads; their imbalance detectors see every event
+    let market_sinks = fan_out("market_sinks", sink_txs, leader.clone());
+    // Without the Binance client nothing adds OKX detectors
+    #[cfg_attr(not(feature = "sbe"), allow(unused_mut))]
+    let mut okx_sinks = vec![market_sinks.clone()];
+    let polymarket_sinks = vec![market_sinks.clone()];
+
+    if let Some(slack_config) = &config.slack {
+        notify::spawn(SlackNotifier::new(&slack_config.webhook_url), slack_config, leader.clone());
+    }
+    if let Some(discord_config) = &config.discord {
+        let notifier = DiscordNotifier::new(&discord_config.webhook_url, Some(kalshi_client.shared_state()));
+        notify::spawn(notifier, discord_config, leader.clone());
+    }
+    if let Some(webhook_config) = &config.webhook {
+        notify::spawn(JsonWebhookNotifier::new(webhook_config), &webhook_config.target, leader.clone());
+    }
+
+    #[cfg(feature = "sbe")]
+    let (binance_state, binance_reload_tx) = match &config.binance {
+        Some(binance_config) => {
+            info!("Binance symbols: {:?}", binance_config.tracked_symbols);
+            let alert_tx = db
+                .as_ref()
+                .filter(|_| persist.imbalance_alerts != Persistence::Off)
+                .map(|db| {
+                    ImbalanceAlertWriter::spawn(
+                        db.clone(),
+                        Some(kalshi_client.shared_state()),
+                        persist.imbalance_alerts,
+                        leader.clone(),
+                    )
+                });
+            let mut binance_client = BinanceClient::new(binance_config.clone())
+                .with_recorder(recorder)
+                .with_chaos(config.chaos.clone());
+            let state = binance_client.shared_state();
+            let reload_tx = binance_client.reload_handle();
+            let config_watch = binance_client.config_watch();
+            let source = BinanceDepth::new(binance_config.market, Some(state.clone()));
+            let mut binance_sinks = vec![market_sinks.clone()];
+            binance_sinks.push(
+                ImbalanceDetector::new(source, config_watch.clone())
+                    .with_alerts(alert_tx.clone())
+                    .with_events(Some(market_sinks.clone()))
+                    .with_kalshi(Some(kalshi_client.shared_state()))
+                    .spawn(),
+            );
+            let symbols = binance_config.tracked_symbols.clone();
+            let event_tx = fan_out("binance_events", binance_sinks, LeaderHandle::always());
+            let handle = tokio::spawn(async move {
+                if let Err(e) = binance_client.start(&symbols, event_tx).await {
+                    error!("Binance client error: {}", e);
+                }
+            });
+            shutdown::coordinator().track("binance client", handle);
+            spawn_reference_depth(
+                binance_config,
+                config_watch,
+                config.okx.is_some(),
+                alert_tx,
+                kalshi_client.shared_state(),
+                market_sinks,
+                &mut okx_sinks,
+            );
+            (Some(state), Some(reload_tx))
+        }
+        None => (None, None),
+    };
+    #[cfg(not(feature = "sbe"))]
+    let (binance_state, binance_reload_tx) = {
+        if config.binance.is_some() {
+            warn!("Binance configured but built without the sbe feature, Binance client disabled");
+        }
+        (None, None)
+    };
+
+    if let Some(okx_config) = &config.okx {
+        info!("OKX symbols: {:?}", okx_config.tracked_symbols);
+        let mut okx_client = OkxClient::new(okx_config.clone());
+        let event_tx = fan_out("okx_events", okx_sinks, LeaderHandle::always());
+        let handle = tokio::spawn(async move {
+            if let Err(e) = okx_client.start(event_tx).await {
+                error!("OKX client error: {}", e);
+            }
+        });
+        shutdown::coordinator().track("okx client", handle);
+    }
+
+    let polymarket_state = match &config.polymarket {
+        Some(polymarket_config) => {
+            let mut polymarket_client = PolymarketClient::new(polymarket_config.clone());
+            let state = polymarket_client.shared_state();
+            let event_tx = fan_out("polymarket_events", polymarket_sinks, LeaderHandle::always());
+            let handle = tokio::spawn(async move {
+                if let Err(e) = polymarket_client.start(event_tx).await {
+                    error!("Polymarket client error: {}", e);
+                }
+            });
+            shutdown::coordinator().track("polymarket client", handle);
+            Some(state)
+        }
+        None => None,
+    };
+
+    ConfigReloader::spawn(config.clone(), kalshi_reload_tx, binance_reload_tx);
 
-    if let Err(e) = kalshi_client.start().await {
-        error!("Kalshi client error: {}", e);
+    if let Some(rules_config) = &config.rules {
+        RuleEngine::spawn(
+            rules_config.clone(),
+            kalshi_client.shared_state(),
+            binance_state.clone(),
+            kalshi_client.positions(),
+            config.kalshi.exposure.volatility,
+        );
     }
 
+    #[cfg(feature = "http")]
+    if let Some(server_config) = &config.server {
+        #[cfg(feature = "dashboard")]
+        let router = HttpServer::router().merge(dashboard::router(DashboardState {
+            kalshi: kalshi_client.shared_state(),
+            binance: binance_state,
+            polymarket: polymarket_state,
+            portfolio: kalshi_client.portfolio(),
+        }));
+        #[cfg(not(feature = "dashboard"))]
+        let router = {
+            let _ = (binance_state, polymarket_state);
+            HttpServer::router()
+        };
+        HttpServer::serve(server_config, router).await?;
+    }
+    #[cfg(not(feature = "http"))]
+    {
+        if config.server.is_some() {
+            warn!("SERVER_BIND_ADDR set but built without the http feature, HTTP server disabled");
+        }
+        let _ = (binance_state, polymarket_state);
+    }
+
+    systemd::notify_ready();
+
+    let stopped = tokio::select! {
+        result = kalshi_client.start() => {
+            if let Err(e) = result {
+                error!("Kalshi client error: {}", e);
+            }
+            false
+        }
+        _ = shutdown::signal() => true,
+    };
+
+    if stopped {
+        info!("🛑 Stop requested, shutting down...");
+        systemd::notify_stopping();
+    }
+    shutdown::coordinator().trigger();
+    if let Err(e) = kalshi_client.disconnect().await {
+        warn!("Error disconnecting Kalshi client: {}", e);
+    }
+    shutdown::coordinator().drain(SHUTDOWN_TIMEOUT).await;
+    info!("👋 Shut down");
+
     Ok(())
 }
 
+/// Starts imbalance detection for the pairs `IMBALANCE_SOURCES` moves off
+/// the main Binance market: a second Binance client streams the other
+/// market's books into a detector, and OKX books are fed to one through
+/// `okx_sinks`. Both follow the main client's config as reloaded and
+/// publish their alerts to `market_sinks`.
+#[cfg(feature = "sbe")]
+fn spawn_reference_depth(
+    binance_config: &BinanceConfig,
+    config_watch: watch::Receiver<BinanceConfig>,
+    okx_enabled: bool,
+    alert_tx: Option<mpsc::Sender<ImbalanceAlert>>,
+    kalshi: Arc<KalshiState>,
+    market_sinks: mpsc::Sender<MarketEvent>,
+    okx_sinks: &mut Vec<mpsc::Sender<MarketEvent>>,
+) {
+    let sources = &binance_config.depth_sources;
+    let other_market = match binance_config.market {
+        BinanceMarket::Spot => BinanceMarket::Futures,
+        BinanceMarket::Futures => BinanceMarket::Spot,
+    };
+    let pairs = depth::pairs_on(sources, DepthVenue::binance(other_market));
+    if !pairs.is_empty() {
+        let reference_config = BinanceConfig {
+            market: other_market,
+            tracked_symbols: pairs.clone(),
+            kline_backfill: None,
+            local_book: false,
+            ..binance_config.clone()
+        };
+        let mut client = BinanceClient::new(reference_config);
+        let source = BinanceDepth::new(other_market, Some(client.shared_state()));
+        let detector = ImbalanceDetector::new(source, config_watch.clone())
+            .with_alerts(alert_tx.clone())
+            .with_events(Some(market_sinks.clone()))
+            .with_kalshi(Some(kalshi.clone()))
+            .spawn();
+        let event_tx = fan_out("binance_reference_events", vec![detector], LeaderHandle::always());
+        let handle = tokio::spawn(async move {
+            if let Err(e) = client.start(&pairs, event_tx).await {
+                error!("Binance reference client error: {}", e);
+            }
+        });
+        shutdown::coordinator().track("binance reference client", handle);
+    }
+
+    let pairs = depth::pairs_on(sources, DepthVenue::Okx);
+    if pairs.is_empty() {
+        return;
+    }
+    if !okx_enabled {
+        warn!("IMBALANCE_SOURCES assigns {:?} to OKX, but OKX_TRACKED_SYMBOLS is not set", pairs);
+        return;
+    }
+    let detector = ImbalanceDetector::new(OkxDepth, config_watch)
+        .with_alerts(alert_tx)
+        .with_events(Some(market_sinks))
+        .with_kalshi(Some(kalshi));
+    okx_sinks.push(detector.spawn());
+}
+
+/// One sender feeding every sink, for producers that take a single channel.
+/// Events are dropped unless `leader` says this instance leads.
+fn fan_out(
+    queue: &'static str,
+    sink_txs: Vec<mpsc::Sender<MarketEvent>>,
+    leader: LeaderHandle,
+) -> mpsc::Sender<MarketEvent> {
+    let (tx, mut rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+    status::board().register_queue(queue, &tx);
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if !leader.is_leader() {
+                continue;
+            }
+            for sink_tx in &sink_txs {
+                if let Err(e) = sink_tx.try_send(event.clone()) {
+                    error!("Failed to queue sink update: {}", e);
+                }
+            }
+        }
+    });
+    tx
+}
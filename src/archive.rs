@@ -0,0 +1,198 @@
+//! Archival of finished recordings and exports to S3 or GCS.
+//!
+//! Every `interval_secs` the archiver walks the configured directories and
+//! uploads each file that has not been modified for `min_age_secs`, i.e.
+//! one the recorder or an export has rotated away from, to
+//! `<prefix>/<dir name>/<file name>`. Files already stored with the same
+//! size are skipped, so a restart does not upload everything again. Local
+//! copies are removed `keep_local_hours` after their last write once
+//! archived, and objects older than `retention_days` are expired.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
+use futures_util::StreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info, warn};
+
+use crate::config::ArchiveConfig;
+use crate::error::{Error, Result};
+use crate::metrics::{self, names};
+
+/// Counts from one archival pass.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArchiveReport {
+    pub uploaded: usize,
+    pub bytes: u64,
+    pub removed_local: usize,
+    pub expired: usize,
+}
+
+pub struct Archiver {
+    config: ArchiveConfig,
+    store: Arc<dyn ObjectStore>,
+    prefix: String,
+}
+
+impl Archiver {
+    pub fn new(config: ArchiveConfig) -> Result<Self> {
+        let (scheme, rest) = config
+            .url
+            .split_once("://")
+            .ok_or_else(|| Error::Config(format!("Invalid ARCHIVE_URL '{}'", config.url)))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        let store: Arc<dyn ObjectStore> = match scheme {
+            "s3" => Arc::new(AmazonS3Builder::from_env().with_bucket_name(bucket).build()?),
+            "gs" => Arc::new(GoogleCloudStorageBuilder::from_env().with_bucket_name(bucket).build()?),
+            _ => return Err(Error::Config(format!("Invalid ARCHIVE_URL '{}'", config.url))),
+        };
+        Ok(Self {
+            prefix: prefix.trim_matches('/').to_string(),
+            config,
+            store,
+        })
+    }
+
+    pub fn spawn(self) {
+        info!("🗃️ Archiving {:?} to {}", self.config.dirs, self.config.url);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+            loop {
+                interval.tick().await;
+                match self.run_once().await {
+                    Ok(report) if report.uploaded + report.removed_local + report.expired > 0 => info!(
+                        "🗃️ Archived {} files ({} bytes), removed {} local copies, expired {} objects",
+                        report.uploaded, report.bytes, report.removed_local, report.expired
+                    ),
+                    Ok(_) => {}
+                    Err(e) => error!("Archival failed: {}", e),
+                }
+            }
+        });
+    }
+
+    pub async fn run_once(&self) -> Result<ArchiveReport> {
+        let mut report = ArchiveReport::default();
+        for dir in &self.config.dirs {
+            for (path, age) in finished_files(Path::new(dir), self.config.min_age_secs)? {
+                let location = self.location(dir, &path);
+                let archived = match self.archive(&path, &location).await {
+                    Ok(Some(bytes)) => {
+                        report.uploaded += 1;
+                        report.bytes += bytes;
+                        metrics::add(names::ARCHIVE_UPLOADED_BYTES_TOTAL, &[], bytes);
+                        metrics::inc(names::ARCHIVE_FILES_TOTAL, &[("action", "uploaded")]);
+                        true
+                    }
+                    Ok(None) => true,
+                    Err(e) => {
+                        warn!("Failed to archive {}: {}", path.display(), e);
+                        metrics::inc(names::ARCHIVE_FILES_TOTAL, &[("action", "failed")]);
+                        false
+                    }
+                };
+
+                let expired_locally = self
+                    .config
+                    .keep_local_hours
+                    .is_some_and(|hours| age >= Duration::from_secs(hours * 3600));
+                if archived && expired_locally {
+                    std::fs::remove_file(&path)?;
+                    report.removed_local += 1;
+                    metrics::inc(names::ARCHIVE_FILES_TOTAL, &[("action", "removed_local")]);
+                }
+            }
+        }
+        if let Some(days) = self.config.retention_days {
+            report.expired = self.expire(days).await?;
+        }
+        Ok(report)
+    }
+
+    fn location(&self, dir: &str, path: &Path) -> ObjectPath {
+        let dir_name = Path::new(dir)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let parts: Vec<&str> = [self.prefix.as_str(), dir_name.as_str(), file_name.as_str()]
+            .into_iter()
+            .filter(|p| !p.is_empty())
+            .collect();
+        ObjectPath::from(parts.join("/"))
+    }
+
+    /// Uploads `path` unless an object of the same size is already there;
+    /// returns the bytes uploaded, `None` when skipped.
+    async fn archive(&self, path: &Path, location: &ObjectPath) -> Result<Option<u64>> {
+        let size = std::fs::metadata(path)?.len();
+        match self.store.head(location).await {
+            Ok(meta) if meta.size as u64 == size => return Ok(None),
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let (id, mut writer) = self.store.put_multipart(location).await?;
+        let upload = async {
+            let mut file = tokio::fs::File::open(path).await?;
+            tokio::io::copy(&mut file, &mut writer).await?;
+            writer.shutdown().await?;
+            Ok::<_, Error>(())
+        };
+        if let Err(e) = upload.await {
+            let _ = self.store.abort_multipart(location, &id).await;
+            return Err(e);
+        }
+        Ok(Some(size))
+    }
+
+    /// Deletes archived objects older than `days`.
+    async fn expire(&self, days: u64) -> Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+        let prefix = (!self.prefix.is_empty()).then(|| ObjectPath::from(self.prefix.as_str()));
+        let mut listing = self.store.list(prefix.as_ref());
+        let mut expired = Vec::new();
+        while let Some(meta) = listing.next().await {
+            let meta = meta?;
+            if meta.last_modified < cutoff {
+                expired.push(meta.location);
+            }
+        }
+        drop(listing);
+
+        for location in &expired {
+            self.store.delete(location).await?;
+            metrics::inc(names::ARCHIVE_FILES_TOTAL, &[("action", "expired")]);
+        }
+        Ok(expired.len())
+    }
+}
+
+/// Files directly in `dir` untouched for at least `min_age_secs`, with how
+/// long ago they were last modified.
+fn finished_files(dir: &Path, min_age_secs: u64) -> Result<Vec<(PathBuf, Duration)>> {
+    let now = SystemTime::now();
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let age = now.duration_since(metadata.modified()?).unwrap_or_default();
+        if age >= Duration::from_secs(min_age_secs) {
+            files.push((entry.path(), age));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
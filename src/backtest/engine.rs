@@ -1,9 +1,12 @@
-use std::{fs::File, io::{BufRead, BufReader, Write}};
+use std::{fs::File, io::{BufRead, BufReader}};
 
 use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Timelike, Utc};
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::config::ReferencePriceConfig;
 use crate::db::main::{Db, MarketDataRow};
+use crate::encryption;
+use crate::exchanges::kalshi::reference::{ReferencePricer, YesQuote};
 
 #[derive(Debug, Clone, Copy)]
 pub enum TradeSide {
@@ -41,6 +44,9 @@ pub struct BacktestEngine {
     asset: Option<String>,
     last_yes_ask: Option<f64>,
     last_no_ask: Option<f64>,
+    /// Last YES reference price, used to mark unmatched contracts.
+    last_reference: Option<f64>,
+    reference: ReferencePricer,
     market_end: Option<DateTime<Utc>>,
 }
 
@@ -74,6 +80,11 @@ impl BacktestEngine {
             asset: None,
             last_yes_ask: None,
             last_no_ask: None,
+            last_reference: None,
+            reference: ReferencePricer::new(ReferencePriceConfig::from_env().unwrap_or_else(|e| {
+                warn!("{}, using the default reference price", e);
+                ReferencePriceConfig::default()
+            })),
             market_end: None,
         }
     }
@@ -230,6 +241,25 @@ impl BacktestEngine {
         }
 
         self.set_last_asks(tick.yes_ask, tick.no_ask);
+        // Stored ticks carry no sizes or trades, so this is the mid (or the
+        // last trade falling back to it) whatever the method.
+        let quote = YesQuote { bid: tick.yes_bid, bid_qty: 0.0, ask: tick.yes_ask, ask_qty: 0.0 };
+        if let Some(reference) = self.reference.price(quote, None, tick.timestamp) {
+            self.last_reference = Some(reference);
+        }
+    }
+
+    /// Unrealized P&L of the unmatched contracts at the last reference price.
+    pub fn residual_mark(&self) -> Option<f64> {
+        let reference = self.last_reference?;
+        let imbalance = self.get_contract_diff();
+        if imbalance > 0.0 {
+            Some(imbalance * (reference - self.calculate_avg_yes_price()?))
+        } else if imbalance < 0.0 {
+            Some(-imbalance * ((1.0 - reference) - self.calculate_avg_no_price()?))
+        } else {
+            Some(0.0)
+        }
     }
 
     pub fn reset(&mut self) {
@@ -240,6 +270,7 @@ impl BacktestEngine {
         self.filled_no_orders = Vec::new();
         self.last_no_ask = None;
         self.last_yes_ask = None;
+        self.last_reference = None;
         self.market_end = None;
     }
 
@@ -284,6 +315,14 @@ impl BacktestEngine {
             let residual_side = if imbalance > 0.0 { "YES" } else { "NO" };
             let residual_avg = if imbalance > 0.0 { avg_yes } else { avg_no };
             info!("Residual: {:.1} {} contracts @ avg ${:.2} (need resolution)", residual, residual_side, residual_avg);
+            if let Some(mark) = self.residual_mark() {
+                info!(
+                    "Residual marked at {} reference ${:.2}: ${:.2}",
+                    self.reference.method().as_str(),
+                    self.last_reference.unwrap_or(0.0),
+                    mark
+                );
+            }
         }
 
         info!("Total fills: {} YES + {} NO", self.filled_yes_orders.len(), self.filled_no_orders.len());
@@ -298,7 +337,7 @@ impl BacktestEngine {
         path: &str,
         ticker: &str,
         total_rows_processed: usize
-    ) -> std::io::Result<()> {
+    ) -> crate::error::Result<()> {
         let total_yes = self.filled_yes_contracts();
         let total_no = self.filled_no_contracts();
         let avg_yes = self.calculate_avg_yes_price().unwrap_or(0.0);
@@ -308,9 +347,9 @@ impl BacktestEngine {
         let spread_pnl = matched * (1.0 - avg_sum);
         let imbalance = self.get_contract_diff();
 
-        let header = "ticker,total_rows,yes_contracts,no_contracts,avg_yes,avg_no,avg_sum,matched,spread_pnl,imbalance,last_yes_ask,last_no_ask\n";
+        let header = "ticker,total_rows,yes_contracts,no_contracts,avg_yes,avg_no,avg_sum,matched,spread_pnl,imbalance,last_yes_ask,last_no_ask,reference_yes,residual_mark\n";
         let row = format!(
-            "{},{},{:.1},{:.1},{:.2},{:.2},{:.2},{:.1},{:.2},{:.1},{},{}\n",
+            "{},{},{:.1},{:.1},{:.2},{:.2},{:.2},{:.1},{:.2},{:.1},{},{},{},{}\n",
             escape_csv_field(ticker),
             total_rows_processed,
             total_yes,
@@ -323,18 +362,11 @@ impl BacktestEngine {
             imbalance,
             self.last_yes_ask.map(|t| t.to_string()).unwrap_or_default(),
             self.last_no_ask.map(|t| t.to_string()).unwrap_or_default(),
+            self.last_reference.map(|r| format!("{:.4}", r)).unwrap_or_default(),
+            self.residual_mark().map(|m| format!("{:.2}", m)).unwrap_or_default(),
         );
 
-        let exists = std::path::Path::new(path).exists();
-        let mut f = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)?;
-        if !exists {
-            f.write_all(header.as_bytes())?;
-        }
-        f.write_all(row.as_bytes())?;
-        Ok(())
+        encryption::append_report(path, header, &row)
     }
 
     pub async fn run(&mut self) {
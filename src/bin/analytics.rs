@@ -0,0 +1,136 @@
+use std::env;
+
+use white_shark::instruments;
+use white_shark::sinks::DuckDbStore;
+use white_shark::timezone;
+
+const DEFAULT_BUCKET_SECS: i64 = 30;
+const DEFAULT_RESAMPLE_SECS: i64 = 1;
+const DEFAULT_HORIZON_SECS: i64 = 60;
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: analytics <DUCKDB_PATH> <summary|odds-curve [BUCKET_SECS]|final-odds|alert-outcomes [HORIZON_SECS]|resample [INTERVAL_SECS]>"
+    );
+    std::process::exit(1);
+}
+
+fn fmt_micros(micros: i64) -> String {
+    timezone::format_micros(micros)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        usage();
+    }
+
+    if let Ok(spec) = env::var("INSTRUMENT_PRECISION") {
+        for (symbol, precision) in instruments::parse_overrides(&spec).expect("Invalid INSTRUMENT_PRECISION") {
+            instruments::registry().register(&symbol, precision);
+        }
+    }
+
+    let store = DuckDbStore::open(&args[0]).expect("Failed to open DuckDB file");
+
+    match args[1].as_str() {
+        "summary" => {
+            let rows = store.ticker_summary().expect("summary query failed");
+            println!("ticker,asset,rows,first,last");
+            for r in rows {
+                println!(
+                    "{},{},{},{},{}",
+                    r.ticker,
+                    r.asset,
+                    r.rows,
+                    fmt_micros(r.first_micros),
+                    fmt_micros(r.last_micros)
+                );
+            }
+        }
+        "odds-curve" => {
+            let bucket_secs = args
+                .get(2)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_BUCKET_SECS);
+            let points = store.odds_curve(bucket_secs).expect("odds curve query failed");
+            println!("asset,seconds_to_close,avg_yes_ask,avg_yes_bid,samples");
+            for p in points {
+                let precision = instruments::registry().kalshi(&p.asset);
+                println!(
+                    "{},{},{},{},{}",
+                    p.asset,
+                    p.seconds_to_close,
+                    precision.price(p.avg_yes_ask),
+                    precision.price(p.avg_yes_bid),
+                    p.samples
+                );
+            }
+        }
+        "final-odds" => {
+            let rows = store.final_odds().expect("final odds query failed");
+            println!("ticker,asset,yes_bid,yes_ask,seconds_to_close");
+            for r in rows {
+                let precision = instruments::registry().kalshi(&r.ticker);
+                println!(
+                    "{},{},{},{},{}",
+                    r.ticker,
+                    r.asset,
+                    precision.price(r.yes_bid),
+                    precision.price(r.yes_ask),
+                    r.seconds_to_close.map(|s| s.to_string()).unwrap_or_default()
+                );
+            }
+        }
+        "alert-outcomes" => {
+            let horizon_secs = args
+                .get(2)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_HORIZON_SECS);
+            let rows = store.alert_outcomes(horizon_secs).expect("alert outcomes query failed");
+            println!("event_id,time,symbol,direction,ticker,yes_at_alert,yes_after,yes_final,result");
+            for r in rows {
+                let precision = instruments::registry().kalshi(&r.ticker);
+                let price = |v: Option<f64>| v.map(|v| precision.price(v)).unwrap_or_default();
+                println!(
+                    "{},{},{},{},{},{},{},{},{}",
+                    r.event_id,
+                    fmt_micros(r.ts_micros),
+                    r.symbol,
+                    r.direction.unwrap_or_default(),
+                    r.ticker,
+                    price(r.yes_at_alert),
+                    price(r.yes_after),
+                    price(r.yes_final),
+                    r.result.unwrap_or_default()
+                );
+            }
+        }
+        "resample" => {
+            let interval_secs = args
+                .get(2)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_RESAMPLE_SECS);
+            let bars = store.resample_odds(interval_secs).expect("resample query failed");
+            store
+                .store_odds_bars(interval_secs, &bars)
+                .expect("Failed to store odds bars");
+            println!("bucket,ticker,asset,yes_bid,yes_ask,no_bid,no_ask,samples");
+            for b in bars {
+                let precision = instruments::registry().kalshi(&b.ticker);
+                println!(
+                    "{},{},{},{},{},{},{},{}",
+                    fmt_micros(b.bucket_start.timestamp_micros()),
+                    b.ticker,
+                    b.asset,
+                    precision.price(b.yes_bid),
+                    precision.price(b.yes_ask),
+                    precision.price(b.no_bid),
+                    precision.price(b.no_ask),
+                    b.samples
+                );
+            }
+        }
+        _ => usage(),
+    }
+}
@@ -1,12 +1,14 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 use std::fs;
-use std::io::Write;
 
 use chrono::DateTime;
 use serde::Deserialize;
 use tracing::{info, warn};
 
+use white_shark::encryption;
 use white_shark::logging::init;
+use white_shark::timezone;
 
 const USER_ADDRESS: &str = "0x6031b6eed1c97e853c6e0f03ad3ce3529351f96d";
 const PAGE_LIMIT: u64 = 1000;
@@ -229,8 +231,8 @@ async fn main() -> anyhow::Result<()> {
 
     for (ticker, activities) in &markets {
         let path = format!("data/{}.csv", ticker);
-        let mut file = fs::File::create(&path)?;
-        writeln!(file, "outcome,price,size,date")?;
+        let mut csv = String::new();
+        writeln!(csv, "outcome,price,size,date")?;
 
         let mut total_up: f64 = 0.0;
         let mut total_down: f64 = 0.0;
@@ -241,7 +243,7 @@ async fn main() -> anyhow::Result<()> {
 
         for a in activities {
             let date = DateTime::from_timestamp(a.timestamp, 0)
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                .map(timezone::format)
                 .unwrap_or_else(|| a.timestamp.to_string());
 
             match a.outcome.as_str() {
@@ -258,7 +260,7 @@ async fn main() -> anyhow::Result<()> {
                 _ => {}
             }
 
-            writeln!(file, "{},{},{},{}", a.outcome, a.price, a.size, date)?;
+            writeln!(csv, "{},{},{},{}", a.outcome, a.price, a.size, date)?;
         }
 
         let avg_up = if count_up > 0 {
@@ -272,19 +274,21 @@ async fn main() -> anyhow::Result<()> {
             0.0
         };
 
-        writeln!(file, "total up,,,{}", total_up)?;
-        writeln!(file, "total down,,,{}", total_down)?;
-        writeln!(file, "avg up price,{}", avg_up)?;
-        writeln!(file, "avg down price,{}", avg_down)?;
+        writeln!(csv, "total up,,,{}", total_up)?;
+        writeln!(csv, "total down,,,{}", total_down)?;
+        writeln!(csv, "avg up price,{}", avg_up)?;
+        writeln!(csv, "avg down price,{}", avg_down)?;
 
         if let Some((_, _, start_ts)) = parse_slug(ticker, activities) {
             if let Some(resolved) = outcomes.get(&start_ts) {
-                writeln!(file, "market outcome,{}", resolved)?;
+                writeln!(csv, "market outcome,{}", resolved)?;
             } else {
-                writeln!(file, "market outcome,unknown")?;
+                writeln!(csv, "market outcome,unknown")?;
             }
         }
 
+        encryption::write_file(&path, csv.as_bytes())?;
+
         info!(
             "Wrote {} rows to {} (up: {:.2}, down: {:.2})",
             activities.len(),
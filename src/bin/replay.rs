@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use clap::Parser;
+
+use white_shark::logging::init;
+use white_shark::replay::{self, ReplayOptions};
+
+/// Plays a recording back to the log.
+#[derive(Parser)]
+#[command(name = "replay")]
+struct Args {
+    /// Recording directory
+    dir: String,
+    /// Skip frames before this RFC3339 time
+    #[arg(long, value_parser = parse_time)]
+    from: Option<i64>,
+    /// Stop at this RFC3339 time
+    #[arg(long, value_parser = parse_time)]
+    to: Option<i64>,
+    #[arg(long, default_value_t = 1.0)]
+    speed: f64,
+    /// Jump over recording gaps longer than this many milliseconds
+    #[arg(long)]
+    skip_gaps: Option<u64>,
+    /// Only play frames of these symbols
+    #[arg(long, value_delimiter = ',')]
+    symbols: Vec<String>,
+    /// Serve the replay control API on this address
+    #[arg(long)]
+    admin: Option<String>,
+}
+
+fn parse_time(value: &str) -> Result<i64, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc).timestamp_micros())
+        .map_err(|e| format!("invalid RFC3339 time: {}", e))
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    init();
+
+    let options = ReplayOptions {
+        dir: args.dir,
+        from_micros: args.from,
+        to_micros: args.to,
+        speed: args.speed,
+        skip_gaps_ms: args.skip_gaps,
+        symbols: args.symbols,
+    };
+    replay::cli::run(options, args.admin)
+        .await
+        .expect("Replay failed");
+}
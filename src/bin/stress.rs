@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use clap::Parser;
+use tracing::warn;
+
+use white_shark::config::Config;
+use white_shark::exchanges::binance::api::BinanceApi;
+use white_shark::exchanges::kalshi::auth::KalshiAuth;
+use white_shark::exchanges::kalshi::{KalshiApi, KalshiMarket, OrderSide};
+use white_shark::instruments;
+use white_shark::logging::init;
+use white_shark::trader::exposure::market_terms;
+use white_shark::trader::positions::Holding;
+use white_shark::trader::stress::{Shock, StressLeg, StressLimits, StressTest};
+
+const DEFAULT_SPOT_SHOCK_PCT: f64 = 5.0;
+const DEFAULT_VOL_SHOCK_PCT: f64 = 20.0;
+
+/// Reprices the held Kalshi positions under spot and volatility shocks,
+/// printing one CSV row per scenario and exiting with 2 on a breach.
+#[derive(Parser)]
+#[command(name = "stress")]
+struct Args {
+    /// Spot move of the shocks, percent either way
+    #[arg(long, default_value_t = DEFAULT_SPOT_SHOCK_PCT)]
+    spot: f64,
+    /// Volatility change of the shocks, percent either way
+    #[arg(long, default_value_t = DEFAULT_VOL_SHOCK_PCT)]
+    vol: f64,
+    /// Annual volatility before the shocks, defaulting to the exposure config's
+    #[arg(long)]
+    volatility: Option<f64>,
+    /// Largest acceptable portfolio loss in dollars
+    #[arg(long)]
+    max_loss: Option<f64>,
+    /// Largest acceptable absolute delta per underlying, in base units
+    #[arg(long)]
+    max_delta: Option<f64>,
+}
+
+/// Mid of the held side in dollars, when both sides are quoted.
+fn side_mark(market: &KalshiMarket, side: OrderSide) -> Option<f64> {
+    let yes_mid = (market.yes_bid? + market.yes_ask?) / 200.0;
+    Some(match side {
+        OrderSide::Yes => yes_mid,
+        OrderSide::No => 1.0 - yes_mid,
+    })
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    init();
+
+    let limits = StressLimits {
+        max_loss: args.max_loss,
+        max_delta: args.max_delta,
+    };
+
+    let config = Config::from_env().expect("Failed to load config");
+    for (symbol, precision) in &config.precision_overrides {
+        instruments::registry().register(symbol, *precision);
+    }
+    for (binance_symbol, kalshi_series) in &config.symbol_map {
+        instruments::registry().link(binance_symbol, kalshi_series);
+    }
+    let volatility = args.volatility.unwrap_or(config.kalshi.exposure.volatility);
+
+    let auth = Arc::new(KalshiAuth::create_auth(&config.kalshi).expect("Failed to load Kalshi key"));
+    let api = KalshiApi::new(auth);
+    let positions = api.get_positions().await.expect("Failed to fetch positions");
+    if positions.is_empty() {
+        println!("No open positions");
+        return;
+    }
+
+    // Positions list tickers only; strikes and quotes come from the markets
+    // of each event.
+    let events: HashSet<&str> = positions
+        .iter()
+        .map(|p| p.ticker.rsplit_once('-').map(|(event, _)| event).unwrap_or(&p.ticker))
+        .collect();
+    let mut markets: HashMap<String, KalshiMarket> = HashMap::new();
+    for event in events {
+        match api.fetch_event_markets(event, None).await {
+            Ok(list) => markets.extend(list.into_iter().map(|m| (m.ticker.clone(), m))),
+            Err(e) => warn!("Failed to fetch markets for {}: {}", event, e),
+        }
+    }
+
+    let binance = BinanceApi::new();
+    let mut spots = HashMap::new();
+    let mut legs = Vec::new();
+    for position in positions {
+        let side = if position.position > 0 { OrderSide::Yes } else { OrderSide::No };
+        let series = position.ticker.split('-').next().unwrap_or(&position.ticker).to_string();
+        let underlying = instruments::registry().binance_symbol(&series);
+        if let Some(symbol) = &underlying {
+            if !spots.contains_key(symbol) {
+                match binance.fetch_book_ticker(symbol).await {
+                    Ok(update) => {
+                        if let (Some(bid), Some(ask)) = (update.bid, update.ask) {
+                            spots.insert(symbol.clone(), (bid + ask) / 2.0);
+                        }
+                    }
+                    Err(e) => warn!("Failed to price {}: {}", symbol, e),
+                }
+            }
+        }
+
+        let market = markets.get(&position.ticker);
+        let (strike, close_time) = market.map(market_terms).unwrap_or((None, None));
+        legs.push(StressLeg {
+            holding: Holding {
+                ticker: position.ticker.clone(),
+                side,
+                contracts: position.position.unsigned_abs(),
+                cost: position.market_exposure as f64 / 100.0,
+            },
+            underlying: underlying.unwrap_or(series),
+            strike,
+            close_time,
+            mark: market.and_then(|m| side_mark(m, side)),
+        });
+    }
+
+    let test = StressTest::new(legs, spots, volatility);
+    let cost: f64 = test.legs().iter().map(|l| l.holding.cost).sum();
+    println!(
+        "{} positions, cost ${:.2}, marked ${:.2}, volatility {:.0}%",
+        test.legs().len(),
+        cost,
+        test.mark_value(),
+        volatility * 100.0
+    );
+    for leg in test.unpriced() {
+        println!("  not repriced: {} (no strike, close time or spot)", leg.holding.ticker);
+    }
+
+    println!("spot_shock,vol_shock,underlying,pnl,delta,breaches");
+    let mut breached = false;
+    for shock in Shock::grid(args.spot / 100.0, args.vol / 100.0) {
+        let result = test.run(shock, &limits);
+        let label = |v: f64| format!("{:+.1}%", v * 100.0);
+        for u in &result.underlyings {
+            println!(
+                "{},{},{},{:.2},{:.4},",
+                label(shock.spot),
+                label(shock.volatility),
+                u.underlying,
+                u.pnl,
+                u.delta
+            );
+        }
+        println!(
+            "{},{},total,{:.2},,{}",
+            label(shock.spot),
+            label(shock.volatility),
+            result.pnl,
+            result.breaches.join("; ")
+        );
+        breached |= !result.breaches.is_empty();
+    }
+
+    if breached {
+        std::process::exit(2);
+    }
+}
@@ -0,0 +1,54 @@
+use clap::Parser;
+use tracing::info;
+
+use white_shark::config::RecorderConfig;
+use white_shark::logging::init;
+use white_shark::recorder::Recorder;
+use white_shark::synthetic::{SyntheticConfig, SyntheticFeed};
+
+/// Writes a synthetic recording of Binance and Kalshi frames for replay.
+#[derive(Parser)]
+#[command(name = "synthetic")]
+struct Args {
+    /// Directory receiving the recording
+    dir: String,
+    /// Simulated time to cover
+    #[arg(long, default_value_t = 15)]
+    minutes: u64,
+    /// Random seed, so a recording can be written again
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Standard deviation of log returns per second of simulated time
+    #[arg(long)]
+    volatility: Option<f64>,
+    /// Chance per tick that an imbalance episode starts
+    #[arg(long)]
+    imbalance_probability: Option<f64>,
+}
+
+fn main() {
+    let args = Args::parse();
+    init();
+
+    let defaults = SyntheticConfig::default();
+    let config = SyntheticConfig {
+        seed: args.seed.unwrap_or(defaults.seed),
+        volatility: args.volatility.unwrap_or(defaults.volatility),
+        imbalance_probability: args.imbalance_probability.unwrap_or(defaults.imbalance_probability),
+        ..defaults
+    };
+
+    let recorder_config = RecorderConfig {
+        dir: args.dir.clone(),
+        compression_level: 3,
+        chunk_bytes: 1 << 20,
+        chunk_interval_ms: 5000,
+        events: false,
+    };
+
+    let end_micros = config.start_micros + (args.minutes * 60_000_000) as i64;
+    let frames = SyntheticFeed::new(config).take_while(|f| f.timestamp_micros < end_micros);
+    let count = Recorder::write_all(&recorder_config, frames).expect("Failed to write recording");
+
+    info!("Done — wrote {} synthetic frames ({} minutes) to {}", count, args.minutes, args.dir);
+}
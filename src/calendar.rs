@@ -0,0 +1,238 @@
+//! High-impact economic events (FOMC, CPI, ...) from a calendar feed.
+//!
+//! `CALENDAR_SOURCE` names an ICS or JSON feed, by URL or file path, read
+//! again every `CALENDAR_REFRESH_SECS`. Around each event, from
+//! `CALENDAR_WINDOW_BEFORE_SECS` before it to `CALENDAR_WINDOW_AFTER_SECS`
+//! after, alert thresholds are scaled by `CALENDAR_ALERT_SENSITIVITY` and,
+//! with `CALENDAR_PAUSE_EXECUTION`, no orders are placed. Alerts fired in a
+//! window carry the event's name.
+//!
+//! A JSON feed is an array of `{"name", "time", "impact"}` with RFC 3339
+//! times; entries with an `impact` other than `high` are ignored. Every
+//! `VEVENT` of an ICS feed is taken as high impact.
+
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+
+static CALENDAR: OnceLock<Calendar> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub struct CalendarConfig {
+    /// `http(s)://` URL or file path
+    pub source: String,
+    pub refresh_secs: u64,
+    pub window_before_secs: i64,
+    pub window_after_secs: i64,
+    /// Alert thresholds are multiplied by this inside a window, 0 to 1
+    pub alert_sensitivity: f64,
+    pub pause_execution: bool,
+}
+
+impl CalendarConfig {
+    /// Reads `CALENDAR_SOURCE`, `CALENDAR_REFRESH_SECS`,
+    /// `CALENDAR_WINDOW_BEFORE_SECS`, `CALENDAR_WINDOW_AFTER_SECS`,
+    /// `CALENDAR_ALERT_SENSITIVITY` and `CALENDAR_PAUSE_EXECUTION`. `None`
+    /// without a source.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(source) = crate::config::var("CALENDAR_SOURCE") else {
+            return Ok(None);
+        };
+        let secs = |name: &str, default: u64| match crate::config::var(name) {
+            Ok(v) => v
+                .parse::<u64>()
+                .map_err(|_| Error::Config(format!("Invalid {} '{}'", name, v))),
+            Err(_) => Ok(default),
+        };
+        let alert_sensitivity = match crate::config::var("CALENDAR_ALERT_SENSITIVITY") {
+            Ok(v) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|f| *f > 0.0 && *f <= 1.0)
+                .ok_or_else(|| Error::Config(format!("Invalid CALENDAR_ALERT_SENSITIVITY '{}'", v)))?,
+            Err(_) => 0.8,
+        };
+        Ok(Some(Self {
+            source,
+            refresh_secs: secs("CALENDAR_REFRESH_SECS", 3600)?.max(60),
+            window_before_secs: secs("CALENDAR_WINDOW_BEFORE_SECS", 900)? as i64,
+            window_after_secs: secs("CALENDAR_WINDOW_AFTER_SECS", 900)? as i64,
+            alert_sensitivity,
+            pause_execution: crate::config::var("CALENDAR_PAUSE_EXECUTION")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub name: String,
+    pub at: DateTime<Utc>,
+}
+
+struct Calendar {
+    config: CalendarConfig,
+    events: RwLock<Vec<CalendarEvent>>,
+}
+
+impl Calendar {
+    fn active_at(&self, now: DateTime<Utc>) -> Option<CalendarEvent> {
+        let before = chrono::Duration::seconds(self.config.window_before_secs);
+        let after = chrono::Duration::seconds(self.config.window_after_secs);
+        let events = self.events.read().unwrap_or_else(|e| e.into_inner());
+        events
+            .iter()
+            .find(|e| now >= e.at - before && now <= e.at + after)
+            .cloned()
+    }
+}
+
+/// Loads the calendar and keeps it fresh. Later calls are ignored.
+pub fn spawn(config: CalendarConfig) {
+    let calendar = Calendar {
+        config,
+        events: RwLock::new(Vec::new()),
+    };
+    if CALENDAR.set(calendar).is_err() {
+        return;
+    }
+    tokio::spawn(async {
+        let Some(calendar) = CALENDAR.get() else {
+            return;
+        };
+        info!("📅 Reading event calendar from {}", calendar.config.source);
+        let mut ticker = tokio::time::interval(Duration::from_secs(calendar.config.refresh_secs));
+        loop {
+            ticker.tick().await;
+            match load(&calendar.config.source).await {
+                Ok(events) => {
+                    info!("📅 {} calendar events loaded", events.len());
+                    *calendar.events.write().unwrap_or_else(|e| e.into_inner()) = events;
+                }
+                Err(e) => warn!("Failed to load event calendar, keeping the last one: {}", e),
+            }
+        }
+    });
+}
+
+/// The event whose window we are in, if any.
+pub fn active() -> Option<CalendarEvent> {
+    CALENDAR.get()?.active_at(Utc::now())
+}
+
+/// Name of the event whose window we are in, for alert context.
+pub fn active_event() -> Option<String> {
+    active().map(|e| e.name)
+}
+
+/// `threshold` lowered by the configured sensitivity inside an event window.
+pub fn alert_threshold(threshold: f64) -> f64 {
+    match CALENDAR.get() {
+        Some(calendar) if calendar.active_at(Utc::now()).is_some() => threshold * calendar.config.alert_sensitivity,
+        _ => threshold,
+    }
+}
+
+/// The event execution is paused for, if any.
+pub fn pausing_execution() -> Option<CalendarEvent> {
+    let calendar = CALENDAR.get()?;
+    if !calendar.config.pause_execution {
+        return None;
+    }
+    calendar.active_at(Utc::now())
+}
+
+async fn load(source: &str) -> Result<Vec<CalendarEvent>> {
+    let body = if source.starts_with("http://") || source.starts_with("https://") {
+        reqwest::get(source).await?.error_for_status()?.text().await?
+    } else {
+        tokio::fs::read_to_string(source).await?
+    };
+    let mut events = if body.trim_start().starts_with("BEGIN:VCALENDAR") {
+        parse_ics(&body)
+    } else {
+        parse_json(&body)?
+    };
+    events.sort_by_key(|e| e.at);
+    Ok(events)
+}
+
+#[derive(Deserialize)]
+struct JsonEvent {
+    name: String,
+    time: DateTime<Utc>,
+    #[serde(default)]
+    impact: Option<String>,
+}
+
+pub fn parse_json(body: &str) -> Result<Vec<CalendarEvent>> {
+    let events: Vec<JsonEvent> = serde_json::from_str(body)?;
+    Ok(events
+        .into_iter()
+        .filter(|e| e.impact.as_deref().is_none_or(|i| i.eq_ignore_ascii_case("high")))
+        .map(|e| CalendarEvent { name: e.name, at: e.time })
+        .collect())
+}
+
+/// Events of an ICS feed with a `SUMMARY` and a `DTSTART` we can read.
+pub fn parse_ics(body: &str) -> Vec<CalendarEvent> {
+    // Lines starting with a space or tab continue the previous one
+    let mut lines: Vec<String> = Vec::new();
+    for line in body.lines() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut summary: Option<String> = None;
+    let mut start: Option<DateTime<Utc>> = None;
+    for line in &lines {
+        let Some((property, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = property.split(';');
+        let name = params.next().unwrap_or_default();
+        match (name, value) {
+            ("BEGIN", "VEVENT") => {
+                summary = None;
+                start = None;
+            }
+            ("SUMMARY", _) => summary = Some(value.replace("\\,", ",").replace("\\;", ";")),
+            ("DTSTART", _) => {
+                let tz = params.find_map(|p| p.strip_prefix("TZID=")).and_then(|t| t.parse::<Tz>().ok());
+                start = parse_ics_time(value, tz);
+            }
+            ("END", "VEVENT") => {
+                if let (Some(name), Some(at)) = (summary.take(), start.take()) {
+                    events.push(CalendarEvent { name, at });
+                }
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// An ICS date or date-time: UTC with a trailing `Z`, in `tz` when given,
+/// otherwise taken as UTC.
+fn parse_ics_time(value: &str, tz: Option<Tz>) -> Option<DateTime<Utc>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(|t| t.and_utc());
+    }
+    let local = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y%m%d").ok()?.and_hms_opt(0, 0, 0))?;
+    match tz {
+        Some(tz) => tz.from_local_datetime(&local).earliest().map(|t| t.with_timezone(&Utc)),
+        None => Some(local.and_utc()),
+    }
+}
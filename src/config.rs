@@ -1,10 +1,116 @@
+use std::collections::BTreeMap;
+use std::env::VarError;
+use std::sync::RwLock;
+use std::str::FromStr;
+
+use chrono_tz::Tz;
+
 use crate::error::{Error, Result};
+use crate::exchanges::binance::sbe::events::depth::{BlackoutAction, DepthWeighting, IMBALANCE_ALERT_RATIO};
+use crate::exchanges::depth::{self, DepthVenue};
+use crate::exchanges::schema::WireFormat;
+use crate::instruments::{self, Precision};
+use crate::exchanges::kalshi::constants::{DISCOVERY_INTERVAL_SECS, IMBALANCE_LEAN_RATIO};
+use crate::exchanges::kalshi::reference::ReferencePriceMethod;
+use crate::exchanges::kalshi::models::OrderSide;
+use crate::rules::Rule;
+use crate::server::auth::AdminAuth;
+use crate::signals::SignalKind;
+use crate::calendar::CalendarConfig;
+use crate::encryption::EncryptionKey;
+use crate::notify::webhook::JsonWebhookConfig;
+use crate::notify::WebhookConfig;
+use crate::self_test::SelfTestConfig;
+use crate::tags::Tags;
+use crate::trader::venue::BinanceMarket;
+use crate::utils::shard::shard_of;
+
+/// Settings layered around the process environment, which is only ever
+/// read: command-line overrides win over it, the config file loses to it.
+#[derive(Debug)]
+struct Layers {
+    /// Set by [`Config::override_var`]
+    overrides: BTreeMap<String, String>,
+    /// Set by [`Config::apply_file`]
+    file: BTreeMap<String, String>,
+}
+
+static LAYERS: RwLock<Layers> = RwLock::new(Layers {
+    overrides: BTreeMap::new(),
+    file: BTreeMap::new(),
+});
+
+/// Reads a setting like `std::env::var` does, but with a command-line
+/// override winning over the environment and the config file filling in
+/// what the environment lacks.
+pub fn var(name: impl AsRef<str>) -> std::result::Result<String, VarError> {
+    let name = name.as_ref();
+    let layers = LAYERS.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(value) = layers.overrides.get(name) {
+        return Ok(value.clone());
+    }
+    match std::env::var(name) {
+        Err(VarError::NotPresent) => layers.file.get(name).cloned().ok_or(VarError::NotPresent),
+        other => other,
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
+    /// TOML file the config was read from, read again on reload
+    pub file: Option<String>,
     pub kalshi: KalshiConfig,
-    // pub binance: BinanceConfig,
-    pub database: DatabaseConfig,
+    /// Binance SBE market data, streamed alongside Kalshi when `BINANCE_SBE` is set.
+    pub binance: Option<BinanceConfig>,
+    /// OKX public trades and books, streamed when `OKX_TRACKED_SYMBOLS` is set.
+    pub okx: Option<OkxConfig>,
+    /// Polymarket books compared with Kalshi, streamed when
+    /// `POLYMARKET_MARKETS` is set.
+    pub polymarket: Option<PolymarketConfig>,
+    /// Persistence of ticks and settlements; runs without a database when unset.
+    pub database: Option<DatabaseConfig>,
+    pub server: Option<ServerConfig>,
+    pub zmq: Option<ZmqConfig>,
+    pub flight: Option<FlightConfig>,
+    pub influx: Option<InfluxConfig>,
+    pub duckdb: Option<DuckDbConfig>,
+    pub recorder: Option<RecorderConfig>,
+    pub csv_export: Option<CsvExportConfig>,
+    pub parquet: Option<ParquetConfig>,
+    /// Upload of finished recordings and exports to object storage
+    pub archive: Option<ArchiveConfig>,
+    pub chaos: Option<ChaosConfig>,
+    pub leader: Option<LeaderConfig>,
+    pub shard: Option<ShardConfig>,
+    pub metrics_push: Option<MetricsPushConfig>,
+    pub sentry: Option<SentryConfig>,
+    /// Rolling log files next to stdout; stdout only when unset.
+    pub log_file: Option<LogFileConfig>,
+    pub heartbeat: Option<HeartbeatConfig>,
+    /// Session statistics line; off with `STATS_INTERVAL_MINS=0`.
+    pub stats: Option<StatsConfig>,
+    /// Alerts and operational events posted to a Slack webhook
+    pub slack: Option<WebhookConfig>,
+    /// The same posted to a Discord webhook as embeds
+    pub discord: Option<WebhookConfig>,
+    /// The same posted as signed JSON to arbitrary URLs
+    pub webhook: Option<JsonWebhookConfig>,
+    pub latency_race: Option<LatencyRaceConfig>,
+    pub rules: Option<RulesConfig>,
+    pub signals: Option<SignalsConfig>,
+    /// Per-symbol precision overrides for the instrument registry
+    pub precision_overrides: Vec<(String, Precision)>,
+    /// Binance symbol to Kalshi series pairs for cross-exchange correlation
+    pub symbol_map: Vec<(String, String)>,
+    pub timezone: TimezoneConfig,
+    /// Account and strategy stamped on persisted and reported data
+    pub tags: Tags,
+    /// Key sealing recordings and reports at rest; plaintext when unset.
+    pub encryption: Option<EncryptionKey>,
+    /// High-impact event feed tightening alerts around releases
+    pub calendar: Option<CalendarConfig>,
+    /// Checks run once before streaming when `SELF_TEST` is set
+    pub self_test: Option<SelfTestConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -15,29 +121,1266 @@ pub struct KalshiConfig {
     /// Path to PEM file (fallback for local development)
     pub private_key_path: Option<String>,
     pub tracked_symbols: Vec<String>,
+    /// Series tracked at event level: every market of the current event (the
+    /// full strike ladder) rather than only the nearest one
+    pub tracked_events: Vec<String>,
+    /// Adds matching series from the listed categories to `tracked_symbols`
+    pub discovery: Option<DiscoveryConfig>,
+    /// How a market's single YES price is derived for alerts and marking
+    pub reference_price: ReferencePriceConfig,
+    pub quote_skew: QuoteSkewConfig,
+    pub hedge: Option<HedgeConfig>,
+    pub exposure: ExposureConfig,
+    pub portfolio: PortfolioConfig,
+    /// Limit orders placed on alerts; `None` unless `TRADING_ENABLED` or
+    /// `PAPER_TRADING`
+    pub execution: Option<ExecutionConfig>,
+    pub announcements: Option<AnnouncementsConfig>,
+    /// YES bid against derived YES ask depth of each book, kept in
+    /// [`KalshiState::imbalances`](crate::state::KalshiState::imbalances)
+    pub imbalance: ImbalanceConfig,
+}
+
+/// Polling of Kalshi announcements and exchange status, see
+/// [`crate::exchanges::kalshi::announcements`].
+#[derive(Debug, Clone, Copy)]
+pub struct AnnouncementsConfig {
+    pub interval_secs: u64,
+}
+
+impl AnnouncementsConfig {
+    /// Reads `KALSHI_ANNOUNCEMENTS` and `KALSHI_ANNOUNCEMENTS_INTERVAL_SECS`.
+    /// `None` unless enabled.
+    pub fn from_env() -> Result<Option<Self>> {
+        let enabled = var("KALSHI_ANNOUNCEMENTS")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        let interval_secs = match var("KALSHI_ANNOUNCEMENTS_INTERVAL_SECS") {
+            Ok(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| Error::Config(format!("Invalid KALSHI_ANNOUNCEMENTS_INTERVAL_SECS '{}'", v)))?,
+            Err(_) => 60,
+        };
+        Ok(Some(Self { interval_secs }))
+    }
+}
+
+/// Portfolio exposure aggregation across positions and hedges.
+#[derive(Debug, Clone, Copy)]
+pub struct ExposureConfig {
+    /// Annualized volatility of the underlying used for binary deltas
+    pub volatility: f64,
+    pub interval_secs: u64,
+}
+
+impl Default for ExposureConfig {
+    fn default() -> Self {
+        Self {
+            volatility: 0.6,
+            interval_secs: 5,
+        }
+    }
+}
+
+impl ExposureConfig {
+    /// Reads `EXPOSURE_VOLATILITY` and `EXPOSURE_INTERVAL_SECS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            volatility: var("EXPOSURE_VOLATILITY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.volatility),
+            interval_secs: var("EXPOSURE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.interval_secs),
+        }
+    }
+}
+
+/// Position and P&L tracking of execution fills, see [`crate::portfolio`].
+#[derive(Debug, Clone, Copy)]
+pub struct PortfolioConfig {
+    /// How often positions are marked and exported
+    pub mark_interval_secs: u64,
+    /// How often marked positions are written to `position_snapshots`
+    pub snapshot_interval_secs: u64,
+}
+
+impl Default for PortfolioConfig {
+    fn default() -> Self {
+        Self {
+            mark_interval_secs: 5,
+            snapshot_interval_secs: 60,
+        }
+    }
+}
+
+impl PortfolioConfig {
+    /// Reads `PORTFOLIO_MARK_INTERVAL_SECS` and `PORTFOLIO_SNAPSHOT_INTERVAL_SECS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |name: &str, default: u64| {
+            var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default)
+        };
+        Self {
+            mark_interval_secs: secs("PORTFOLIO_MARK_INTERVAL_SECS", defaults.mark_interval_secs),
+            snapshot_interval_secs: secs("PORTFOLIO_SNAPSHOT_INTERVAL_SECS", defaults.snapshot_interval_secs),
+        }
+    }
+}
+
+/// Delta hedging of Kalshi positions on the linked Binance symbols.
+#[derive(Debug, Clone)]
+pub struct HedgeConfig {
+    /// Fraction of the computed delta to hedge, e.g. 0.5 hedges half
+    pub ratio: f64,
+    /// Base asset quantity the hedge must be off by before it is adjusted
+    pub rebalance_threshold: f64,
+    pub interval_secs: u64,
+    pub market: BinanceMarket,
+    /// Send orders; otherwise hedges are only logged
+    pub execute: bool,
+    pub api_key: Option<String>,
+    pub private_key_path: Option<String>,
+}
+
+/// Kalshi limit orders placed on alerts, see [`crate::execution`].
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionConfig {
+    /// Side bought on an alert pointing up; the other side is bought on one
+    /// pointing down
+    pub side: OrderSide,
+    /// Limit price, 1 to 99
+    pub price_cents: u64,
+    pub contracts: u64,
+    /// Resting orders are cancelled once this old
+    pub order_ttl_secs: u64,
+    /// Alerts on a market this soon after its last order are not traded,
+    /// so fills can't pile up a position faster than this
+    pub cooldown_secs: u64,
+    /// Fill orders against the live book instead of sending them
+    pub paper: bool,
+    pub risk: RiskConfig,
+}
+
+/// Pre-trade limits, see [`crate::execution::risk`]. Unset limits are off.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RiskConfig {
+    /// Contracts held on one market, counting resting orders
+    pub max_position: Option<u64>,
+    pub max_open_orders: Option<usize>,
+    /// Dollars lost since the start of the market day that engage the kill switch
+    pub max_daily_loss: Option<f64>,
+}
+
+impl RiskConfig {
+    /// Reads `RISK_MAX_POSITION`, `RISK_MAX_OPEN_ORDERS` and `RISK_MAX_DAILY_LOSS`.
+    pub fn from_env() -> Result<Self> {
+        fn limit<T: std::str::FromStr + PartialOrd + Default>(name: &str) -> Result<Option<T>> {
+            match var(name) {
+                Ok(v) => v
+                    .parse::<T>()
+                    .ok()
+                    .filter(|n| *n > T::default())
+                    .map(Some)
+                    .ok_or_else(|| Error::Config(format!("Invalid {} '{}'", name, v))),
+                Err(_) => Ok(None),
+            }
+        }
+        Ok(Self {
+            max_position: limit("RISK_MAX_POSITION")?,
+            max_open_orders: limit("RISK_MAX_OPEN_ORDERS")?,
+            max_daily_loss: limit("RISK_MAX_DAILY_LOSS")?,
+        })
+    }
+}
+
+impl ExecutionConfig {
+    /// Reads `EXECUTION_SIDE` (`yes` or `no`), `EXECUTION_PRICE_CENTS`,
+    /// `EXECUTION_CONTRACTS`, `EXECUTION_ORDER_TTL_SECS` and
+    /// `EXECUTION_COOLDOWN_SECS`. `None` unless
+    /// `TRADING_ENABLED` is set, so no order is ever sent by accident.
+    /// `PAPER_TRADING` simulates the orders instead, and wins over
+    /// `TRADING_ENABLED` when both are set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let flag = |name: &str| {
+            var(name)
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false)
+        };
+        let paper = flag("PAPER_TRADING");
+        if !paper && !flag("TRADING_ENABLED") {
+            return Ok(None);
+        }
+        let side = match var("EXECUTION_SIDE") {
+            Ok(v) => match v.trim().to_ascii_lowercase().as_str() {
+                "yes" => OrderSide::Yes,
+                "no" => OrderSide::No,
+                _ => return Err(Error::Config(format!("Invalid EXECUTION_SIDE '{}'", v))),
+            },
+            Err(_) => OrderSide::Yes,
+        };
+        let price_cents = match var("EXECUTION_PRICE_CENTS") {
+            Ok(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|c| (1..=99).contains(c))
+                .ok_or_else(|| Error::Config(format!("Invalid EXECUTION_PRICE_CENTS '{}'", v)))?,
+            Err(_) => {
+                return Err(Error::Config(
+                    "TRADING_ENABLED and PAPER_TRADING require EXECUTION_PRICE_CENTS".into(),
+                ))
+            }
+        };
+        let contracts = match var("EXECUTION_CONTRACTS") {
+            Ok(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| Error::Config(format!("Invalid EXECUTION_CONTRACTS '{}'", v)))?,
+            Err(_) => 1,
+        };
+        Ok(Some(Self {
+            side,
+            price_cents,
+            contracts,
+            order_ttl_secs: var("EXECUTION_ORDER_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(30),
+            cooldown_secs: var("EXECUTION_COOLDOWN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(300),
+            paper,
+            risk: RiskConfig::from_env()?,
+        }))
+    }
+}
+
+/// How the ladder leans away from inventory already held on a side.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuoteSkewConfig {
+    /// Cents every ladder price moves down per contract held on that side
+    pub cents_per_contract: f64,
+    /// Contracts on a side at which no further ladder is placed on it
+    pub max_inventory: Option<u64>,
+}
+
+impl QuoteSkewConfig {
+    /// Reads `TRADER_SKEW_CENTS_PER_CONTRACT` and `TRADER_MAX_INVENTORY`;
+    /// both unset leaves the ladder unskewed.
+    pub fn from_env() -> Result<Self> {
+        let cents_per_contract = match var("TRADER_SKEW_CENTS_PER_CONTRACT") {
+            Ok(v) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|c| c.is_finite() && *c >= 0.0)
+                .ok_or_else(|| Error::Config(format!("Invalid TRADER_SKEW_CENTS_PER_CONTRACT '{}'", v)))?,
+            Err(_) => 0.0,
+        };
+        let max_inventory = match var("TRADER_MAX_INVENTORY") {
+            Ok(v) => Some(
+                v.parse::<u64>()
+                    .map_err(|_| Error::Config(format!("Invalid TRADER_MAX_INVENTORY '{}'", v)))?,
+            ),
+            Err(_) => None,
+        };
+        Ok(Self { cents_per_contract, max_inventory })
+    }
+}
+
+/// Timezones timestamps are shown in and local schedules run in. Storage,
+/// recordings and the wire stay in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimezoneConfig {
+    /// Report timestamps, file names and daily summaries
+    pub display: Tz,
+    /// Exchange hours, such as the weekly Kalshi maintenance window
+    pub market: Tz,
+}
+
+impl Default for TimezoneConfig {
+    fn default() -> Self {
+        Self {
+            display: Tz::UTC,
+            market: Tz::America__New_York,
+        }
+    }
+}
+
+impl TimezoneConfig {
+    /// Reads IANA names such as `Europe/London` from `DISPLAY_TIMEZONE` and
+    /// `MARKET_TIMEZONE`; shared with the offline tools.
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let parse = |name: &str, default: Tz| match var(name) {
+            Ok(v) => v
+                .parse::<Tz>()
+                .map_err(|_| Error::Config(format!("Invalid {} '{}'", name, v))),
+            Err(_) => Ok(default),
+        };
+        Ok(Self {
+            display: parse("DISPLAY_TIMEZONE", defaults.display)?,
+            market: parse("MARKET_TIMEZONE", defaults.market)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReferencePriceConfig {
+    pub method: ReferencePriceMethod,
+    /// Age at which a last trade counts half towards the reference price
+    pub half_life_secs: u64,
+}
+
+impl Default for ReferencePriceConfig {
+    fn default() -> Self {
+        Self {
+            method: ReferencePriceMethod::default(),
+            half_life_secs: 30,
+        }
+    }
+}
+
+impl ReferencePriceConfig {
+    /// Reads `KALSHI_REFERENCE_PRICE` and `KALSHI_REFERENCE_HALF_LIFE_SECS`;
+    /// shared with the offline tools, which don't load the full config.
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        Ok(Self {
+            method: match var("KALSHI_REFERENCE_PRICE") {
+                Ok(method) => method.parse()?,
+                Err(_) => defaults.method,
+            },
+            half_life_secs: var("KALSHI_REFERENCE_HALF_LIFE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.half_life_secs),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    /// Kalshi series categories to list, e.g. `Crypto`
+    pub categories: Vec<String>,
+    /// Series ticker patterns (`*` wildcard) to add; empty adds every series
+    pub include: Vec<String>,
+    /// Series ticker patterns never added, checked after `include`
+    pub exclude: Vec<String>,
+    /// Only series with this listing frequency, e.g. `fifteen_min`
+    pub frequency: Option<String>,
+    pub interval_secs: u64,
+    /// Discovered series are sharded like configured ones
+    pub shard: Option<ShardConfig>,
 }
 
 #[derive(Debug, Clone)]
 pub struct BinanceConfig {
     pub api_key: Option<String>,
+    /// Spot SBE streams, or the USDⓈ-M perp JSON streams
+    pub market: BinanceMarket,
+    pub tracked_symbols: Vec<String>,
+    pub imbalance: ImbalanceConfig,
+    pub velocity: DepthVelocityConfig,
+    pub iceberg: IcebergConfig,
+    /// RSA key for signed REST calls, with `api_key`
+    pub private_key_path: Option<String>,
+    /// Candles fetched over REST for every symbol on startup
+    pub kline_backfill: Option<KlineBackfillConfig>,
+    /// Keep a full local book per symbol from depth diffs. On spot the
+    /// diff stream replaces the top-20 snapshots and imbalance is computed
+    /// from the book; depth velocity and iceberg detection, which need
+    /// snapshots, go quiet.
+    pub local_book: bool,
+    /// Pairs whose imbalance is detected on another venue's depth, from
+    /// `IMBALANCE_SOURCES`; the rest stay on `market`
+    pub depth_sources: Vec<(String, DepthVenue)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct KlineBackfillConfig {
+    /// Binance interval, e.g. `1m` or `1h`
+    pub interval: String,
+    pub limit: u16,
+}
+
+impl KlineBackfillConfig {
+    /// Reads `BINANCE_KLINE_INTERVAL`, which enables the backfill, and
+    /// `BINANCE_KLINE_LIMIT` (default 500, at most 1000).
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(interval) = var("BINANCE_KLINE_INTERVAL") else {
+            return Ok(None);
+        };
+        let limit = match var("BINANCE_KLINE_LIMIT") {
+            Ok(v) => v
+                .parse::<u16>()
+                .ok()
+                .filter(|n| (1..=1000).contains(n))
+                .ok_or_else(|| Error::Config(format!("Invalid BINANCE_KLINE_LIMIT '{}'", v)))?,
+            Err(_) => 500,
+        };
+        Ok(Some(Self { interval, limit }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OkxConfig {
+    /// Instrument ids, e.g. `BTC-USDT`
     pub tracked_symbols: Vec<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct PolymarketConfig {
+    /// Condition id and the Kalshi market ticker or series it is compared
+    /// with, from `POLYMARKET_MARKETS=0xabc...=KXBTCD-25OCT16-T110000,...`
+    pub markets: Vec<(String, String)>,
+}
+
+/// Add/cancel rate tracking on the top of the Binance book.
+#[derive(Debug, Clone, Copy)]
+pub struct DepthVelocityConfig {
+    /// Levels per side that are diffed between snapshots
+    pub levels: usize,
+    /// Rates are averaged over this trailing window
+    pub window_secs: u64,
+    /// Added quantity per second on a side that raises a stacking alert
+    pub add_rate_threshold: Option<f64>,
+    /// Cancelled quantity per second on a side that raises a pulling alert
+    pub cancel_rate_threshold: Option<f64>,
+}
+
+impl Default for DepthVelocityConfig {
+    fn default() -> Self {
+        Self {
+            levels: 10,
+            window_secs: 5,
+            add_rate_threshold: None,
+            cancel_rate_threshold: None,
+        }
+    }
+}
+
+impl DepthVelocityConfig {
+    /// Reads `DEPTH_VELOCITY_LEVELS`, `DEPTH_VELOCITY_WINDOW_SECS`,
+    /// `DEPTH_VELOCITY_ADD_THRESHOLD` and `DEPTH_VELOCITY_CANCEL_THRESHOLD`;
+    /// no alerts are raised without a threshold.
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let threshold = |name: &str| match var(name) {
+            Ok(v) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|t| t.is_finite() && *t > 0.0)
+                .map(Some)
+                .ok_or_else(|| Error::Config(format!("Invalid {} '{}'", name, v))),
+            Err(_) => Ok(None),
+        };
+        Ok(Self {
+            levels: var("DEPTH_VELOCITY_LEVELS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.levels),
+            window_secs: var("DEPTH_VELOCITY_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.window_secs),
+            add_rate_threshold: threshold("DEPTH_VELOCITY_ADD_THRESHOLD")?,
+            cancel_rate_threshold: threshold("DEPTH_VELOCITY_CANCEL_THRESHOLD")?,
+        })
+    }
+}
+
+/// Iceberg detection: price levels that keep refilling after trades
+/// consume them.
+#[derive(Debug, Clone, Copy)]
+pub struct IcebergConfig {
+    /// Refills within the window after which a level is flagged
+    pub min_refills: u32,
+    /// Refills older than this no longer count
+    pub window_secs: u64,
+    /// Share of a level's displayed size that must trade for it to count as
+    /// consumed, and that must show again for it to count as refilled
+    pub refill_ratio: f64,
+}
+
+impl Default for IcebergConfig {
+    fn default() -> Self {
+        Self {
+            min_refills: 3,
+            window_secs: 60,
+            refill_ratio: 0.8,
+        }
+    }
+}
+
+impl IcebergConfig {
+    /// Reads `ICEBERG_MIN_REFILLS`, `ICEBERG_WINDOW_SECS` and
+    /// `ICEBERG_REFILL_RATIO`.
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let refill_ratio = match var("ICEBERG_REFILL_RATIO") {
+            Ok(v) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|r| *r > 0.0 && *r <= 1.0)
+                .ok_or_else(|| Error::Config(format!("Invalid ICEBERG_REFILL_RATIO '{}'", v)))?,
+            Err(_) => defaults.refill_ratio,
+        };
+        Ok(Self {
+            min_refills: var("ICEBERG_MIN_REFILLS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.min_refills),
+            window_secs: var("ICEBERG_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.window_secs),
+            refill_ratio,
+        })
+    }
+}
+
+/// Depth imbalance detection on the reference book snapshots, see
+/// [`crate::exchanges::depth`].
+#[derive(Debug, Clone, Copy)]
+pub struct ImbalanceConfig {
+    /// Bid/ask ratio above which, or below its inverse, an alert is raised
+    pub alert_ratio: f64,
+    pub weighting: DepthWeighting,
+    /// Levels with less quantity than this are left out of the sums
+    pub min_level_qty: f64,
+    /// Seconds before the mapped Kalshi market closes during which alerts
+    /// are held back, as odds swing erratically into expiry; 0 disables
+    pub close_blackout_secs: u64,
+    pub close_blackout_action: BlackoutAction,
+}
+
+impl Default for ImbalanceConfig {
+    fn default() -> Self {
+        Self {
+            alert_ratio: IMBALANCE_ALERT_RATIO,
+            weighting: DepthWeighting::Quantity,
+            min_level_qty: 0.0,
+            close_blackout_secs: 0,
+            close_blackout_action: BlackoutAction::Suppress,
+        }
+    }
+}
+
+impl ImbalanceConfig {
+    /// Defaults for Kalshi contract books: the lower [`IMBALANCE_LEAN_RATIO`]
+    /// in place of the Binance alert ratio.
+    pub fn kalshi() -> Self {
+        Self {
+            alert_ratio: IMBALANCE_LEAN_RATIO,
+            ..Self::default()
+        }
+    }
+
+    /// Reads `IMBALANCE_ALERT_RATIO`, `IMBALANCE_WEIGHTING` (`quantity` or
+    /// `notional`), `IMBALANCE_MIN_LEVEL_QTY`, `IMBALANCE_CLOSE_BLACKOUT_SECS`
+    /// and `IMBALANCE_CLOSE_BLACKOUT_ACTION` (`suppress` or `downgrade`).
+    pub fn from_env() -> Result<Self> {
+        Self::from_env_with_prefix("IMBALANCE", Self::default())
+    }
+
+    /// Like [`from_env`](Self::from_env) for `<PREFIX>_ALERT_RATIO`,
+    /// `<PREFIX>_WEIGHTING`, `<PREFIX>_MIN_LEVEL_QTY` and the
+    /// `<PREFIX>_CLOSE_BLACKOUT_*` pair, falling back to `defaults`.
+    pub fn from_env_with_prefix(prefix: &str, defaults: Self) -> Result<Self> {
+        let var = |name: &str| {
+            let name = format!("{}_{}", prefix, name);
+            var(&name).map(|v| (name, v))
+        };
+        let alert_ratio = match var("ALERT_RATIO") {
+            Ok((name, v)) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|r| r.is_finite() && *r > 0.0)
+                .ok_or_else(|| Error::Config(format!("Invalid {} '{}'", name, v)))?,
+            Err(_) => defaults.alert_ratio,
+        };
+        let weighting = match var("WEIGHTING") {
+            Ok((_, v)) => v.parse()?,
+            Err(_) => defaults.weighting,
+        };
+        let min_level_qty = match var("MIN_LEVEL_QTY") {
+            Ok((name, v)) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|q| q.is_finite() && *q >= 0.0)
+                .ok_or_else(|| Error::Config(format!("Invalid {} '{}'", name, v)))?,
+            Err(_) => defaults.min_level_qty,
+        };
+        let close_blackout_secs = match var("CLOSE_BLACKOUT_SECS") {
+            Ok((name, v)) => v
+                .parse()
+                .map_err(|_| Error::Config(format!("Invalid {} '{}'", name, v)))?,
+            Err(_) => defaults.close_blackout_secs,
+        };
+        let close_blackout_action = match var("CLOSE_BLACKOUT_ACTION") {
+            Ok((_, v)) => v.parse()?,
+            Err(_) => defaults.close_blackout_action,
+        };
+        Ok(Self {
+            alert_ratio,
+            weighting,
+            min_level_qty,
+            close_blackout_secs,
+            close_blackout_action,
+        })
+    }
+
+    /// Whether a market closing in `closes_in` is inside the blackout,
+    /// including one already past its close.
+    pub fn in_close_blackout(&self, closes_in: chrono::Duration) -> bool {
+        self.close_blackout_secs > 0 && closes_in.num_seconds() < self.close_blackout_secs as i64
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DatabaseConfig {
     pub url: String,
+    /// Rows buffered before a `market_data` batch insert is forced
+    pub batch_size: usize,
+    pub flush_interval_ms: u64,
+    pub persist: PersistConfig,
+    /// Partition and rollup upkeep; off when unset.
+    pub maintenance: Option<MaintenanceConfig>,
+    /// How long a Kalshi market is followed after an alert; 0 turns
+    /// monitor sessions off
+    pub monitor_window_secs: u64,
+}
+
+/// Scheduled `market_data` upkeep: daily partitions created ahead of need
+/// on a partitioned Postgres table, ticks older than the retention rolled
+/// up into `market_data_1m` and deleted.
+#[derive(Debug, Clone, Copy)]
+pub struct MaintenanceConfig {
+    pub interval_secs: u64,
+    /// Raw ticks are kept this many days
+    pub raw_retention_days: u32,
+    pub partition_ahead_days: u32,
+    /// Report what would be done without changing anything
+    pub dry_run: bool,
+}
+
+impl MaintenanceConfig {
+    /// Reads `DATABASE_RAW_RETENTION_DAYS`, which enables maintenance,
+    /// `DATABASE_MAINTENANCE_INTERVAL_SECS`, `DATABASE_PARTITION_AHEAD_DAYS`
+    /// and `DATABASE_MAINTENANCE_DRY_RUN`.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(v) = var("DATABASE_RAW_RETENTION_DAYS") else {
+            return Ok(None);
+        };
+        let raw_retention_days = v
+            .parse::<u32>()
+            .ok()
+            .filter(|d| *d > 0)
+            .ok_or_else(|| Error::Config(format!("Invalid DATABASE_RAW_RETENTION_DAYS '{}'", v)))?;
+        Ok(Some(Self {
+            interval_secs: var("DATABASE_MAINTENANCE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(3600),
+            raw_retention_days,
+            partition_ahead_days: var("DATABASE_PARTITION_AHEAD_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            dry_run: var("DATABASE_MAINTENANCE_DRY_RUN")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        }))
+    }
+}
+
+/// Whether, and how often, rows of one table are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Persistence {
+    Off,
+    All,
+    /// At most one row per key (ticker, symbol) per this many milliseconds
+    SampleMs(u64),
+}
+
+impl FromStr for Persistence {
+    type Err = Error;
+
+    /// `off`, `all`, or a sampling interval such as `500ms`, `1s` or `1m`.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let invalid = || Error::Config(format!("Invalid persistence '{}', expected off, all or an interval like 1s", s));
+        match s.as_str() {
+            "off" | "false" | "0" => return Ok(Self::Off),
+            "all" | "true" | "1" => return Ok(Self::All),
+            _ => {}
+        }
+        let (number, unit_ms) = if let Some(n) = s.strip_suffix("ms") {
+            (n, 1)
+        } else if let Some(n) = s.strip_suffix('s') {
+            (n, 1_000)
+        } else if let Some(n) = s.strip_suffix('m') {
+            (n, 60_000)
+        } else {
+            return Err(invalid());
+        };
+        let number: u64 = number.trim().parse().map_err(|_| invalid())?;
+        match number * unit_ms {
+            0 => Ok(Self::All),
+            ms => Ok(Self::SampleMs(ms)),
+        }
+    }
+}
+
+/// Per-table persistence, from `DATABASE_PERSIST_<TABLE>`.
+#[derive(Debug, Clone, Copy)]
+pub struct PersistConfig {
+    pub market_data: Persistence,
+    pub imbalance_alerts: Persistence,
+    pub latency_races: Persistence,
+}
+
+impl Default for PersistConfig {
+    fn default() -> Self {
+        Self {
+            market_data: Persistence::All,
+            imbalance_alerts: Persistence::All,
+            latency_races: Persistence::All,
+        }
+    }
+}
+
+impl PersistConfig {
+    /// Reads `DATABASE_PERSIST_MARKET_DATA`, `DATABASE_PERSIST_IMBALANCE_ALERTS`
+    /// and `DATABASE_PERSIST_LATENCY_RACES`; every table is written in full
+    /// by default.
+    pub fn from_env() -> Result<Self> {
+        let read = |name: &str, default: Persistence| match var(name) {
+            Ok(v) => v.parse().map_err(|_| {
+                Error::Config(format!(
+                    "Invalid {} '{}', expected off, all or an interval like 1s",
+                    name, v
+                ))
+            }),
+            Err(_) => Ok(default),
+        };
+        let defaults = Self::default();
+        Ok(Self {
+            market_data: read("DATABASE_PERSIST_MARKET_DATA", defaults.market_data)?,
+            imbalance_alerts: read("DATABASE_PERSIST_IMBALANCE_ALERTS", defaults.imbalance_alerts)?,
+            latency_races: read("DATABASE_PERSIST_LATENCY_RACES", defaults.latency_races)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Address serving `/metrics` and `/dashboard.json`, e.g. `0.0.0.0:9100`
+    pub bind_addr: String,
+    /// Tokens and their roles; the API is open without any
+    pub auth: AdminAuth,
+}
+
+#[derive(Debug, Clone)]
+pub struct FlightConfig {
+    /// gRPC address of the Arrow Flight endpoint, e.g. `0.0.0.0:50051`
+    pub bind_addr: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct InfluxConfig {
+    pub url: String,
+    pub org: String,
+    pub bucket: String,
+    pub token: String,
+    pub batch_size: usize,
+    pub flush_interval_ms: u64,
+}
+
+/// CSV files of market data and alerts. Enabled by `CSV_EXPORT_DIR`.
+#[derive(Debug, Clone)]
+pub struct CsvExportConfig {
+    /// Directory receiving one subdirectory of files per date
+    pub dir: String,
+    pub flush_interval_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DuckDbConfig {
+    /// Local DuckDB file receiving captured ticks, alerts and market results
+    pub path: String,
+}
+
+/// Rotating Parquet datasets, only honoured in builds with the `parquet`
+/// feature. Enabled by `PARQUET_DIR`.
+#[derive(Debug, Clone)]
+pub struct ParquetConfig {
+    /// Directory receiving one subdirectory per dataset
+    pub dir: String,
+    /// Rows per row group, written once buffered
+    pub batch_rows: usize,
+    /// Age at which a file is closed and the next one started
+    pub rotate_secs: u64,
+    /// zstd level, 1 (fast) to 22 (small)
+    pub compression_level: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    /// Directory receiving hourly `.wsrec` files
+    pub dir: String,
+    /// zstd level, 1 (fast) to 22 (small)
+    pub compression_level: i32,
+    /// Uncompressed bytes per independently decodable chunk
+    pub chunk_bytes: usize,
+    /// Maximum time a chunk stays open before it is flushed
+    pub chunk_interval_ms: u64,
+    /// Also journal normalized market events alongside the raw frames
+    pub events: bool,
+}
+
+/// Upload of finished files to S3 or GCS. Enabled by `ARCHIVE_URL`.
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+    /// `s3://bucket/prefix` or `gs://bucket/prefix`; credentials come from
+    /// the usual `AWS_*` / `GOOGLE_*` variables
+    pub url: String,
+    /// Directories whose files are archived, under a prefix of their name
+    pub dirs: Vec<String>,
+    pub interval_secs: u64,
+    /// Files modified more recently than this are still being written
+    pub min_age_secs: u64,
+    /// Local copies are deleted this long after their last modification,
+    /// once uploaded; kept when unset
+    pub keep_local_hours: Option<u64>,
+    /// Archived objects are deleted after this many days; kept when unset
+    pub retention_days: Option<u64>,
+}
+
+impl ArchiveConfig {
+    /// Reads `ARCHIVE_URL`, `ARCHIVE_DIRS` (defaulting to `recorder_dir`),
+    /// `ARCHIVE_INTERVAL_SECS`, `ARCHIVE_MIN_AGE_SECS`,
+    /// `ARCHIVE_KEEP_LOCAL_HOURS` and `ARCHIVE_RETENTION_DAYS`.
+    pub fn from_env(recorder_dir: Option<&str>) -> Result<Option<Self>> {
+        let Ok(url) = var("ARCHIVE_URL") else {
+            return Ok(None);
+        };
+        if !(url.starts_with("s3://") || url.starts_with("gs://")) {
+            return Err(Error::Config(format!(
+                "Invalid ARCHIVE_URL '{}', expected s3://bucket/prefix or gs://bucket/prefix",
+                url
+            )));
+        }
+        let dirs = match var("ARCHIVE_DIRS") {
+            Ok(v) => split_list(&v, false),
+            Err(_) => recorder_dir.map(|d| vec![d.to_string()]).unwrap_or_default(),
+        };
+        if dirs.is_empty() {
+            return Err(Error::Config("ARCHIVE_URL requires ARCHIVE_DIRS or RECORDER_DIR".into()));
+        }
+        let optional = |name: &str| match var(name) {
+            Ok(v) => v
+                .parse::<u64>()
+                .map(Some)
+                .map_err(|_| Error::Config(format!("Invalid {} '{}'", name, v))),
+            Err(_) => Ok(None),
+        };
+        Ok(Some(Self {
+            url,
+            dirs,
+            interval_secs: var("ARCHIVE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(600),
+            // Recordings rotate hourly and their last chunk is flushed
+            // shortly after.
+            min_age_secs: var("ARCHIVE_MIN_AGE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3900),
+            keep_local_hours: optional("ARCHIVE_KEEP_LOCAL_HOURS")?,
+            retention_days: optional("ARCHIVE_RETENTION_DAYS")?,
+        }))
+    }
+}
+
+/// Fault injection settings, only honoured in builds with the `chaos` feature.
+#[derive(Debug, Clone)]
+pub struct ChaosConfig {
+    /// Drop each connection after this many inbound frames
+    pub drop_after_messages: Option<u64>,
+    /// Delay applied to a frame chosen with `delay_probability`
+    pub delay_ms: u64,
+    pub delay_probability: f64,
+    /// Chance that an SBE frame gets an unknown template id
+    pub corrupt_sbe_probability: f64,
+}
+
+/// Liveness reporting for external supervisors; at least one of `file` and
+/// `url` is set.
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    /// Rewritten with the unix time of the latest beat
+    pub file: Option<String>,
+    /// Dead-man's-switch URL hit with a GET, e.g. a healthchecks.io check
+    pub url: Option<String>,
+    pub interval_secs: u64,
+}
+
+/// How often the session statistics line is logged, see
+/// [`crate::state::SessionStats`].
+#[derive(Debug, Clone, Copy)]
+pub struct StatsConfig {
+    pub interval_mins: u64,
+}
+
+/// Timing of how fast other venues follow large Binance moves. Enabled by
+/// `LATENCY_RACE_MOVE_BPS`.
+#[derive(Debug, Clone, Copy)]
+pub struct LatencyRaceConfig {
+    /// Binance mid move, in basis points, that starts a race
+    pub move_bps: f64,
+    /// The move must happen within this many milliseconds
+    pub window_ms: u64,
+    /// Venues that haven't followed after this long count as missed
+    pub timeout_secs: u64,
+}
+
+/// Alert rules from `ALERT_RULES`, see [`crate::rules`].
+#[derive(Debug, Clone)]
+pub struct RulesConfig {
+    pub rules: Vec<Rule>,
+    /// Log the evaluated values behind every alert
+    pub explain: bool,
+    pub interval_ms: u64,
+}
+
+/// Signals over the merged event stream from `SIGNALS`, see
+/// [`crate::signals`].
+#[derive(Debug, Clone)]
+pub struct SignalsConfig {
+    pub signals: Vec<SignalKind>,
+    pub imbalance: ImbalanceConfig,
+    /// Mid move, in basis points, that raises a momentum alert
+    pub momentum_bps: f64,
+    /// The move must happen within this many milliseconds
+    pub momentum_window_ms: u64,
+    /// Spread, in basis points of the mid, above which a quote alerts
+    pub spread_bps: f64,
+    /// Distance, in basis points, between two venues' mids that alerts
+    pub divergence_bps: f64,
+    /// Repeats of a signal on the same instrument are held back this long
+    pub cooldown_secs: u64,
+}
+
+impl SignalsConfig {
+    /// Reads `SIGNALS` with `SIGNAL_MOMENTUM_BPS`,
+    /// `SIGNAL_MOMENTUM_WINDOW_MS`, `SIGNAL_SPREAD_BPS`,
+    /// `SIGNAL_DIVERGENCE_BPS` and `SIGNAL_COOLDOWN_SECS`; the imbalance
+    /// signal shares the `IMBALANCE_*` settings.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(spec) = var("SIGNALS") else {
+            return Ok(None);
+        };
+        let signals = spec
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<SignalKind>>>()?;
+        if signals.is_empty() {
+            return Ok(None);
+        }
+        let bps = |name: &str, default: f64| match var(name) {
+            Ok(v) => v
+                .parse::<f64>()
+                .ok()
+                .filter(|b| b.is_finite() && *b > 0.0)
+                .ok_or_else(|| Error::Config(format!("Invalid {} '{}'", name, v))),
+            Err(_) => Ok(default),
+        };
+        Ok(Some(Self {
+            signals,
+            imbalance: ImbalanceConfig::from_env()?,
+            momentum_bps: bps("SIGNAL_MOMENTUM_BPS", 50.0)?,
+            momentum_window_ms: var("SIGNAL_MOMENTUM_WINDOW_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(60_000),
+            spread_bps: bps("SIGNAL_SPREAD_BPS", 25.0)?,
+            divergence_bps: bps("SIGNAL_DIVERGENCE_BPS", 20.0)?,
+            cooldown_secs: var("SIGNAL_COOLDOWN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+        }))
+    }
+}
+
+/// Error reporting, only honoured in builds with the `sentry` feature.
+#[derive(Debug, Clone)]
+pub struct SentryConfig {
+    pub dsn: String,
+    /// e.g. `production`, `staging`
+    pub environment: Option<String>,
+}
+
+/// When a log file is closed and a new one started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    /// Once the file reaches this many bytes
+    Size(u64),
+}
+
+impl FromStr for LogRotation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hourly" => Ok(Self::Hourly),
+            "daily" => Ok(Self::Daily),
+            "size" => Ok(Self::Size(DEFAULT_LOG_FILE_MB * 1024 * 1024)),
+            other => Err(Error::Config(format!(
+                "Unknown log rotation '{}', expected hourly, daily or size",
+                other
+            ))),
+        }
+    }
+}
+
+const DEFAULT_LOG_FILE_MB: u64 = 100;
+
+/// Log files written under `dir` as `<prefix>.<date>.log` for time-based
+/// rotation, `<prefix>.log`, `<prefix>.log.1`, ... for size-based.
+#[derive(Debug, Clone)]
+pub struct LogFileConfig {
+    pub dir: String,
+    pub prefix: String,
+    pub rotation: LogRotation,
+    /// Rotated files kept besides the current one; older ones are deleted
+    pub max_files: usize,
+}
+
+impl LogFileConfig {
+    /// Reads `LOG_DIR`, `LOG_FILE_PREFIX`, `LOG_ROTATION` (`hourly`, `daily`
+    /// or `size`), `LOG_MAX_FILE_MB` for size rotation and `LOG_MAX_FILES`.
+    /// `None` without `LOG_DIR`.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(dir) = var("LOG_DIR") else {
+            return Ok(None);
+        };
+        let mut rotation = match var("LOG_ROTATION") {
+            Ok(v) => v.parse()?,
+            Err(_) => LogRotation::Daily,
+        };
+        if let (LogRotation::Size(_), Ok(v)) = (rotation, var("LOG_MAX_FILE_MB")) {
+            let mb = v
+                .parse::<u64>()
+                .ok()
+                .filter(|mb| *mb > 0)
+                .ok_or_else(|| Error::Config(format!("Invalid LOG_MAX_FILE_MB '{}'", v)))?;
+            rotation = LogRotation::Size(mb * 1024 * 1024);
+        }
+        Ok(Some(Self {
+            dir,
+            prefix: var("LOG_FILE_PREFIX").unwrap_or_else(|_| "white-shark".to_string()),
+            rotation,
+            max_files: var("LOG_MAX_FILES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(7),
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsPushMode {
+    Pushgateway,
+    RemoteWrite,
+}
+
+/// Pushes metrics for deployments Prometheus cannot scrape.
+#[derive(Debug, Clone)]
+pub struct MetricsPushConfig {
+    pub mode: MetricsPushMode,
+    /// Pushgateway base URL, or the full remote-write endpoint
+    pub url: String,
+    pub job: String,
+    pub instance: String,
+    pub interval_secs: u64,
+}
+
+/// Lease-based leader election through the database. Only the leader
+/// persists, alerts and trades; followers keep their feeds warm for failover.
+#[derive(Debug, Clone)]
+pub struct LeaderConfig {
+    /// Unique per instance, e.g. the hostname
+    pub instance_id: String,
+    /// Name of the lease row; instances sharing it compete for leadership
+    pub lease_name: String,
+    /// How long a lease stays valid without renewal
+    pub lease_secs: u64,
+    pub renew_interval_secs: u64,
+}
+
+/// Splits the tracked symbols across `count` instances; this one keeps the
+/// symbols whose hash lands on `index`.
+#[derive(Debug, Clone, Copy)]
+pub struct ShardConfig {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl ShardConfig {
+    pub fn owns(&self, symbol: &str) -> bool {
+        shard_of(symbol, self.count) == self.index
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ZmqConfig {
+    /// PUB socket endpoint, e.g. `tcp://127.0.0.1:5556` or `ipc:///tmp/white-shark.sock`
+    pub endpoint: String,
+    /// Payload encoding for non-tick events
+    pub format: WireFormat,
 }
 
 impl Config {
+    /// Loads a TOML file and then the environment, which wins over the file.
+    ///
+    /// Every key maps onto the env var of the same name under its section,
+    /// so `[database] batch_size = 500` stands for `DATABASE_BATCH_SIZE`.
+    /// Top-level keys map directly (`symbol_map`), arrays become comma
+    /// separated lists, and the tables under `[strategy]` drop that prefix:
+    ///
+    /// ```toml
+    /// [kalshi]
+    /// api_key_id = "..."
+    /// private_key_path = "private_key.pem"
+    /// tracked_symbols = ["BTC15M", "ETH15M"]
+    ///
+    /// [binance]
+    /// api_key = "..."
+    ///
+    /// [database]
+    /// url = "mysql://..."
+    ///
+    /// [strategy.trader]
+    /// skew_cents_per_contract = 0.1
+    ///
+    /// [strategy.hedge]
+    /// ratio = 0.5
+    /// ```
+    pub fn from_file(path: &str) -> Result<Self> {
+        Self::apply_file(path)?;
+        let mut config = Self::from_env()?;
+        config.file = Some(path.to_string());
+        Ok(config)
+    }
+
+    /// Reads the values of a [`Config::from_file`] TOML file in under the
+    /// env vars they stand for, seen by [`var`] where the environment lacks
+    /// them. Applying it again replaces every value of the earlier read, so
+    /// edits and removed keys are picked up.
+    pub fn apply_file(path: &str) -> Result<()> {
+        dotenv::dotenv().ok();
+        let file = Self::read_file(path)?;
+        LAYERS.write().unwrap_or_else(|e| e.into_inner()).file = file;
+        Ok(())
+    }
+
+    /// Reads the config again on SIGHUP, from `file` when there is one.
+    ///
+    /// Unlike [`Config::from_file`] this never touches the process
+    /// environment, which other threads read. The file is parsed before
+    /// anything changes, and its values are rolled back if they don't make
+    /// a valid config, so a bad edit leaves the running settings in place.
+    pub fn reload(file: Option<&str>) -> Result<Self> {
+        let Some(path) = file else {
+            return Self::load();
+        };
+        let values = Self::read_file(path)?;
+        let previous = std::mem::replace(&mut LAYERS.write().unwrap_or_else(|e| e.into_inner()).file, values);
+        match Self::load() {
+            Ok(mut config) => {
+                config.file = Some(path.to_string());
+                Ok(config)
+            }
+            Err(e) => {
+                LAYERS.write().unwrap_or_else(|e| e.into_inner()).file = previous;
+                Err(e)
+            }
+        }
+    }
+
+    /// The env var values of a [`Config::from_file`] TOML file.
+    fn read_file(path: &str) -> Result<BTreeMap<String, String>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("Failed to read config file {}: {}", path, e)))?;
+        let table: toml::Table = content
+            .parse()
+            .map_err(|e| Error::Config(format!("Invalid config file {}: {}", path, e)))?;
+
+        let mut vars = Vec::new();
+        for (key, value) in &table {
+            match (key.as_str(), value) {
+                ("strategy", toml::Value::Table(sections)) => {
+                    for (section, value) in sections {
+                        flatten_toml(section, value, &mut vars)?;
+                    }
+                }
+                _ => flatten_toml(key, value, &mut vars)?,
+            }
+        }
+        Ok(vars.into_iter().collect())
+    }
+
+    /// Sets a value for `name` that wins over both the environment and the
+    /// config file, even when the file is applied again.
+    pub fn override_var(name: &str, value: &str) {
+        LAYERS
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .overrides
+            .insert(name.to_string(), value.to_string());
+    }
+
     pub fn from_env() -> Result<Self> {
         dotenv::dotenv().ok();
+        Self::load()
+    }
 
-        let kalshi_api_key = std::env::var("KALSHI_API_KEY_ID")
+    /// Builds the config from [`var`], without reading `.env`.
+    fn load() -> Result<Self> {
+        let kalshi_api_key = var("KALSHI_API_KEY_ID")
             .map_err(|_| Error::Config("KALSHI_API_KEY_ID not set".into()))?;
 
         // Try KALSHI_PRIVATE_KEY (content) first, then fall back to KALSHI_PRIVATE_KEY_PATH (file)
-        let kalshi_private_key = std::env::var("KALSHI_PRIVATE_KEY").ok();
-        let kalshi_private_key_path = std::env::var("KALSHI_PRIVATE_KEY_PATH").ok();
+        let kalshi_private_key = var("KALSHI_PRIVATE_KEY").ok();
+        let kalshi_private_key_path = var("KALSHI_PRIVATE_KEY_PATH").ok();
 
         if kalshi_private_key.is_none() && kalshi_private_key_path.is_none() {
             return Err(Error::Config(
@@ -45,37 +1388,512 @@ impl Config {
             ));
         }
 
-        let kalshi_symbols = std::env::var("KALSHI_TRACKED_SYMBOLS")
+        let shard = match (var("SHARD_INDEX").ok(), var("SHARD_COUNT").ok()) {
+            (None, None) => None,
+            (Some(index), Some(count)) => {
+                let index: u32 = index
+                    .parse()
+                    .map_err(|_| Error::Config(format!("Invalid SHARD_INDEX: {}", index)))?;
+                let count: u32 = count
+                    .parse()
+                    .map_err(|_| Error::Config(format!("Invalid SHARD_COUNT: {}", count)))?;
+                if count == 0 || index >= count {
+                    return Err(Error::Config(format!(
+                        "SHARD_INDEX must be below SHARD_COUNT, got {}/{}",
+                        index, count
+                    )));
+                }
+                Some(ShardConfig { index, count })
+            }
+            _ => {
+                return Err(Error::Config(
+                    "SHARD_INDEX and SHARD_COUNT must be set together".into(),
+                ))
+            }
+        };
+
+        let kalshi_symbols: Vec<String> = var("KALSHI_TRACKED_SYMBOLS")
             .map_err(|_| Error::Config("KALSHI_TRACKED_SYMBOLS not set".into()))?
             .split(',')
             .map(|s| s.trim().to_uppercase())
             .collect();
+        let kalshi_symbols: Vec<String> = match shard {
+            Some(shard) => kalshi_symbols.into_iter().filter(|s| shard.owns(s)).collect(),
+            None => kalshi_symbols,
+        };
+
+        let kalshi_events = var("KALSHI_TRACKED_EVENTS")
+            .map(|v| split_list(&v, true))
+            .unwrap_or_default();
+        let kalshi_events: Vec<String> = match shard {
+            Some(shard) => kalshi_events.into_iter().filter(|s| shard.owns(s)).collect(),
+            None => kalshi_events,
+        };
+        if let Some(series) = kalshi_events.iter().find(|s| !kalshi_symbols.contains(s)) {
+            return Err(Error::Config(format!(
+                "KALSHI_TRACKED_EVENTS series {} is not in KALSHI_TRACKED_SYMBOLS",
+                series
+            )));
+        }
+
+        let discovery = var("KALSHI_DISCOVERY_CATEGORIES").ok().map(|categories| DiscoveryConfig {
+            categories: split_list(&categories, false),
+            include: var("KALSHI_DISCOVERY_INCLUDE")
+                .map(|v| split_list(&v, true))
+                .unwrap_or_default(),
+            exclude: var("KALSHI_DISCOVERY_EXCLUDE")
+                .map(|v| split_list(&v, true))
+                .unwrap_or_default(),
+            frequency: var("KALSHI_DISCOVERY_FREQUENCY").ok(),
+            interval_secs: var("KALSHI_DISCOVERY_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DISCOVERY_INTERVAL_SECS),
+            shard,
+        });
+
+        let persist = PersistConfig::from_env()?;
+        let maintenance = MaintenanceConfig::from_env()?;
+        let database = var("DATABASE_URL").ok().map(|url| DatabaseConfig {
+            url,
+            persist,
+            maintenance,
+            batch_size: var("DATABASE_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1000),
+            flush_interval_ms: var("DATABASE_FLUSH_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5000),
+            monitor_window_secs: var("DATABASE_MONITOR_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(300),
+        });
+
+        let server = match var("SERVER_BIND_ADDR") {
+            Ok(bind_addr) => Some(ServerConfig {
+                bind_addr,
+                auth: AdminAuth::from_env()?,
+            }),
+            Err(_) => None,
+        };
+
+        let zmq = match var("ZMQ_PUB_ENDPOINT").ok() {
+            Some(endpoint) => Some(ZmqConfig {
+                endpoint,
+                format: match var("ZMQ_WIRE_FORMAT") {
+                    Ok(format) => format.parse()?,
+                    Err(_) => WireFormat::default(),
+                },
+            }),
+            None => None,
+        };
+
+        let flight = var("FLIGHT_BIND_ADDR")
+            .ok()
+            .map(|bind_addr| FlightConfig { bind_addr });
+        if flight.is_some() && database.is_none() {
+            return Err(Error::Config("FLIGHT_BIND_ADDR requires DATABASE_URL".into()));
+        }
+
+        let influx = match var("INFLUX_URL").ok() {
+            Some(url) => Some(InfluxConfig {
+                url,
+                org: var("INFLUX_ORG")
+                    .map_err(|_| Error::Config("INFLUX_ORG not set".into()))?,
+                bucket: var("INFLUX_BUCKET")
+                    .map_err(|_| Error::Config("INFLUX_BUCKET not set".into()))?,
+                token: var("INFLUX_TOKEN")
+                    .map_err(|_| Error::Config("INFLUX_TOKEN not set".into()))?,
+                batch_size: var("INFLUX_BATCH_SIZE")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(5000),
+                flush_interval_ms: var("INFLUX_FLUSH_INTERVAL_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1000),
+            }),
+            None => None,
+        };
+
+        let duckdb = var("DUCKDB_PATH")
+            .ok()
+            .map(|path| DuckDbConfig { path });
+
+        let recorder = var("RECORDER_DIR").ok().map(|dir| RecorderConfig {
+            dir,
+            compression_level: var("RECORDER_ZSTD_LEVEL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            chunk_bytes: var("RECORDER_CHUNK_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1 << 20),
+            chunk_interval_ms: var("RECORDER_CHUNK_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5000),
+            events: var("RECORDER_EVENTS")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        });
+
+        let parquet = var("PARQUET_DIR").ok().map(|dir| ParquetConfig {
+            dir,
+            batch_rows: var("PARQUET_BATCH_ROWS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(50_000),
+            rotate_secs: var("PARQUET_ROTATE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(3600),
+            compression_level: var("PARQUET_ZSTD_LEVEL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+        });
+
+        let csv_export = var("CSV_EXPORT_DIR").ok().map(|dir| CsvExportConfig {
+            dir,
+            flush_interval_ms: var("CSV_EXPORT_FLUSH_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|ms| *ms > 0)
+                .unwrap_or(5000),
+        });
+
+        let archive = ArchiveConfig::from_env(recorder.as_ref().map(|r| r.dir.as_str()))?;
+
+        let chaos_vars = [
+            "CHAOS_DROP_AFTER_MESSAGES",
+            "CHAOS_DELAY_MS",
+            "CHAOS_CORRUPT_SBE_PROBABILITY",
+        ];
+        let chaos_enabled = chaos_vars.iter().any(|v| var(v).is_ok());
+        let chaos = if chaos_enabled && cfg!(feature = "chaos") {
+            Some(ChaosConfig {
+                drop_after_messages: var("CHAOS_DROP_AFTER_MESSAGES")
+                    .ok()
+                    .and_then(|v| v.parse().ok()),
+                delay_ms: var("CHAOS_DELAY_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0),
+                delay_probability: var("CHAOS_DELAY_PROBABILITY")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1.0_f64)
+                    .clamp(0.0, 1.0),
+                corrupt_sbe_probability: var("CHAOS_CORRUPT_SBE_PROBABILITY")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0.0_f64)
+                    .clamp(0.0, 1.0),
+            })
+        } else {
+            None
+        };
 
-        // let binance_api_key = std::env::var("BINANCE_API_KEY").ok();
+        let leader = var("LEADER_INSTANCE_ID").ok().map(|instance_id| LeaderConfig {
+            instance_id,
+            lease_name: var("LEADER_LEASE_NAME").unwrap_or_else(|_| "white-shark".to_string()),
+            lease_secs: var("LEADER_LEASE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(15),
+            renew_interval_secs: var("LEADER_RENEW_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+        });
+        if leader.is_some() && database.is_none() {
+            return Err(Error::Config("LEADER_INSTANCE_ID requires DATABASE_URL".into()));
+        }
+
+        let push_target = match (
+            var("METRICS_PUSHGATEWAY_URL").ok(),
+            var("METRICS_REMOTE_WRITE_URL").ok(),
+        ) {
+            (Some(_), Some(_)) => {
+                return Err(Error::Config(
+                    "Set only one of METRICS_PUSHGATEWAY_URL and METRICS_REMOTE_WRITE_URL".into(),
+                ))
+            }
+            (Some(url), None) => Some((MetricsPushMode::Pushgateway, url)),
+            (None, Some(url)) => Some((MetricsPushMode::RemoteWrite, url)),
+            (None, None) => None,
+        };
+        let metrics_push = push_target.map(|(mode, url)| MetricsPushConfig {
+            mode,
+            url,
+            job: var("METRICS_PUSH_JOB").unwrap_or_else(|_| "white-shark".to_string()),
+            instance: var("METRICS_PUSH_INSTANCE")
+                .or_else(|_| var("HOSTNAME"))
+                .unwrap_or_else(|_| "white-shark".to_string()),
+            interval_secs: var("METRICS_PUSH_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(15),
+        });
+
+        let log_file = LogFileConfig::from_env()?;
+
+        let sentry = var("SENTRY_DSN").ok().map(|dsn| SentryConfig {
+            dsn,
+            environment: var("SENTRY_ENVIRONMENT").ok(),
+        });
+
+        let latency_race = match var("LATENCY_RACE_MOVE_BPS") {
+            Ok(v) => Some(LatencyRaceConfig {
+                move_bps: v
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|b| *b > 0.0)
+                    .ok_or_else(|| Error::Config(format!("Invalid LATENCY_RACE_MOVE_BPS '{}'", v)))?,
+                window_ms: var("LATENCY_RACE_WINDOW_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1_000),
+                timeout_secs: var("LATENCY_RACE_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(30),
+            }),
+            Err(_) => None,
+        };
+
+        let rules = match var("ALERT_RULES") {
+            Ok(spec) => Some(RulesConfig {
+                rules: spec
+                    .split(';')
+                    .filter(|r| !r.trim().is_empty())
+                    .map(str::parse)
+                    .collect::<Result<_>>()?,
+                explain: var("ALERT_RULES_EXPLAIN")
+                    .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false),
+                interval_ms: var("ALERT_RULES_INTERVAL_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|n| *n > 0)
+                    .unwrap_or(1_000),
+            }),
+            Err(_) => None,
+        };
+
+        let heartbeat_file = var("HEARTBEAT_FILE").ok();
+        let heartbeat_url = var("HEARTBEAT_URL").ok();
+        let heartbeat = if heartbeat_file.is_some() || heartbeat_url.is_some() {
+            Some(HeartbeatConfig {
+                file: heartbeat_file,
+                url: heartbeat_url,
+                interval_secs: var("HEARTBEAT_INTERVAL_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(30),
+            })
+        } else {
+            None
+        };
+
+        let stats = match var("STATS_INTERVAL_MINS") {
+            Ok(v) => match v.parse::<u64>() {
+                Ok(0) => None,
+                Ok(interval_mins) => Some(StatsConfig { interval_mins }),
+                Err(_) => return Err(Error::Config(format!("Invalid STATS_INTERVAL_MINS '{}'", v))),
+            },
+            Err(_) => Some(StatsConfig { interval_mins: 5 }),
+        };
+
+        let hedge = match var("HEDGE_RATIO").ok() {
+            Some(ratio) => {
+                let execute = var("HEDGE_EXECUTE")
+                    .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false);
+                let api_key = var("BINANCE_API_KEY").ok();
+                let private_key_path = var("BINANCE_PRIVATE_KEY_PATH").ok();
+                if execute && (api_key.is_none() || private_key_path.is_none()) {
+                    return Err(Error::Config(
+                        "HEDGE_EXECUTE requires BINANCE_API_KEY and BINANCE_PRIVATE_KEY_PATH".into(),
+                    ));
+                }
+                Some(HedgeConfig {
+                    ratio: ratio
+                        .parse()
+                        .map_err(|_| Error::Config(format!("Invalid HEDGE_RATIO '{}'", ratio)))?,
+                    rebalance_threshold: var("HEDGE_REBALANCE_THRESHOLD")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(0.001),
+                    interval_secs: var("HEDGE_INTERVAL_SECS")
+                        .ok()
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(10),
+                    market: match var("HEDGE_MARKET") {
+                        Ok(market) => market.parse()?,
+                        Err(_) => BinanceMarket::Futures,
+                    },
+                    execute,
+                    api_key,
+                    private_key_path,
+                })
+            }
+            None => None,
+        };
 
-        // let binance_symbols = std::env::var("BINANCE_TRACKED_SYMBOLS")
-        //     .map_err(|_| Error::Config("BINANCE_TRACKED_SYMBOLS not set".into()))?
-        //     .split(',')
-        //     .map(|s| s.trim().to_uppercase())
-        //     .collect();
+        let precision_overrides = match var("INSTRUMENT_PRECISION") {
+            Ok(spec) => instruments::parse_overrides(&spec)?,
+            Err(_) => Vec::new(),
+        };
 
-        let database_url = std::env::var("DATABASE_URL")
-            .map_err(|_| Error::Config("DATABASE_URL not set".into()))?;
+        let symbol_map = match var("SYMBOL_MAP") {
+            Ok(spec) => instruments::parse_symbol_map(&spec)?,
+            Err(_) => Vec::new(),
+        };
+
+        let binance_sbe = var("BINANCE_SBE")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let binance_market = match var("BINANCE_MARKET") {
+            Ok(v) => Some(v.parse::<BinanceMarket>()?),
+            Err(_) => None,
+        };
+        // Futures streams need no key, so BINANCE_MARKET=futures alone
+        // enables them.
+        let binance = if binance_sbe || binance_market == Some(BinanceMarket::Futures) {
+            let market = binance_market.unwrap_or(BinanceMarket::Spot);
+            let api_key = match (var("BINANCE_API_KEY"), market) {
+                (Ok(key), _) => Some(key),
+                (Err(_), BinanceMarket::Futures) => None,
+                (Err(_), BinanceMarket::Spot) => {
+                    return Err(Error::Config("BINANCE_SBE requires BINANCE_API_KEY".into()))
+                }
+            };
+            // Defaults to the Binance side of every SYMBOL_MAP pair.
+            let tracked_symbols = match var("BINANCE_TRACKED_SYMBOLS") {
+                Ok(v) => split_list(&v, true),
+                Err(_) => symbol_map.iter().map(|(symbol, _)| symbol.clone()).collect(),
+            };
+            if tracked_symbols.is_empty() {
+                return Err(Error::Config(
+                    "BINANCE_SBE requires BINANCE_TRACKED_SYMBOLS or SYMBOL_MAP".into(),
+                ));
+            }
+            let depth_sources = match var("IMBALANCE_SOURCES") {
+                Ok(spec) => depth::parse_sources(&spec)?,
+                Err(_) => Vec::new(),
+            };
+            if api_key.is_none() && !depth::pairs_on(&depth_sources, DepthVenue::BinanceSpot).is_empty() {
+                return Err(Error::Config("IMBALANCE_SOURCES binance_spot requires BINANCE_API_KEY".into()));
+            }
+            Some(BinanceConfig {
+                api_key,
+                market,
+                tracked_symbols,
+                imbalance: ImbalanceConfig::from_env()?,
+                velocity: DepthVelocityConfig::from_env()?,
+                iceberg: IcebergConfig::from_env()?,
+                private_key_path: var("BINANCE_PRIVATE_KEY_PATH").ok(),
+                kline_backfill: KlineBackfillConfig::from_env()?,
+                local_book: var("BINANCE_LOCAL_BOOK")
+                    .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false),
+                depth_sources,
+            })
+        } else {
+            None
+        };
+
+        let okx = match var("OKX_TRACKED_SYMBOLS") {
+            Ok(v) => {
+                let tracked_symbols = split_list(&v, true);
+                if tracked_symbols.is_empty() {
+                    return Err(Error::Config(format!("Invalid OKX_TRACKED_SYMBOLS '{}'", v)));
+                }
+                Some(OkxConfig { tracked_symbols })
+            }
+            Err(_) => None,
+        };
+
+        let polymarket = match var("POLYMARKET_MARKETS") {
+            Ok(spec) => {
+                let mut markets = Vec::new();
+                for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                    let (condition_id, kalshi) = entry.split_once('=').ok_or_else(|| {
+                        Error::Config(format!(
+                            "Invalid POLYMARKET_MARKETS entry '{}', expected CONDITION_ID=KALSHI_TICKER",
+                            entry
+                        ))
+                    })?;
+                    markets.push((condition_id.trim().to_string(), kalshi.trim().to_uppercase()));
+                }
+                if markets.is_empty() {
+                    return Err(Error::Config(format!("Invalid POLYMARKET_MARKETS '{}'", spec)));
+                }
+                Some(PolymarketConfig { markets })
+            }
+            Err(_) => None,
+        };
 
         Ok(Config {
+            file: None,
             kalshi: KalshiConfig {
                 api_key_id: kalshi_api_key,
                 private_key: kalshi_private_key,
                 private_key_path: kalshi_private_key_path,
                 tracked_symbols: kalshi_symbols,
+                tracked_events: kalshi_events,
+                discovery,
+                reference_price: ReferencePriceConfig::from_env()?,
+                quote_skew: QuoteSkewConfig::from_env()?,
+                hedge,
+                exposure: ExposureConfig::from_env(),
+                portfolio: PortfolioConfig::from_env(),
+                execution: ExecutionConfig::from_env()?,
+                announcements: AnnouncementsConfig::from_env()?,
+                imbalance: ImbalanceConfig::from_env_with_prefix("KALSHI_IMBALANCE", ImbalanceConfig::kalshi())?,
             },
-            // binance: BinanceConfig {
-            //     api_key: binance_api_key,
-            //     tracked_symbols: binance_symbols
-            // },
-            database: DatabaseConfig {
-                url: database_url,
-            },
+            binance,
+            okx,
+            polymarket,
+            database,
+            server,
+            zmq,
+            flight,
+            influx,
+            duckdb,
+            recorder,
+            csv_export,
+            parquet,
+            archive,
+            chaos,
+            leader,
+            shard,
+            metrics_push,
+            sentry,
+            log_file,
+            heartbeat,
+            stats,
+            slack: WebhookConfig::from_env("SLACK")?,
+            discord: WebhookConfig::from_env("DISCORD")?,
+            webhook: JsonWebhookConfig::from_env()?,
+            latency_race,
+            rules,
+            signals: SignalsConfig::from_env()?,
+            precision_overrides,
+            symbol_map,
+            timezone: TimezoneConfig::from_env()?,
+            tags: Tags::from_env()?,
+            encryption: EncryptionKey::from_env()?,
+            calendar: CalendarConfig::from_env()?,
+            self_test: SelfTestConfig::from_env()?,
         })
     }
 }
@@ -87,6 +1905,16 @@ impl Default for KalshiConfig {
             private_key: None,
             private_key_path: Some("private_key.pem".to_string()),
             tracked_symbols: vec!["ETH15M".to_string(), "BTC15M".to_string()],
+            tracked_events: Vec::new(),
+            discovery: None,
+            reference_price: ReferencePriceConfig::default(),
+            quote_skew: QuoteSkewConfig::default(),
+            hedge: None,
+            exposure: ExposureConfig::default(),
+            portfolio: PortfolioConfig::default(),
+            execution: None,
+            announcements: None,
+            imbalance: ImbalanceConfig::kalshi(),
         }
     }
 }
@@ -95,8 +1923,51 @@ impl Default for BinanceConfig {
     fn default() -> Self {
         Self {
             api_key: None,
+            market: BinanceMarket::Spot,
             tracked_symbols: vec!["ETHUSDT".to_string(), "BTCUSDT".to_string()],
+            imbalance: ImbalanceConfig::default(),
+            velocity: DepthVelocityConfig::default(),
+            iceberg: IcebergConfig::default(),
+            private_key_path: None,
+            kline_backfill: None,
+            local_book: false,
+            depth_sources: Vec::new(),
         }
     }
 }
 
+/// Splits a comma separated env value, dropping empty entries.
+fn split_list(value: &str, uppercase: bool) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| if uppercase { s.to_uppercase() } else { s.to_string() })
+        .collect()
+}
+
+/// Env var name and value for every leaf under `prefix`.
+fn flatten_toml(prefix: &str, value: &toml::Value, out: &mut Vec<(String, String)>) -> Result<()> {
+    let name = prefix.to_uppercase();
+    let scalar = |value: &toml::Value| match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Float(f) => Ok(f.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        toml::Value::Datetime(d) => Ok(d.to_string()),
+        _ => Err(Error::Config(format!("Config file key {} must hold plain values", name))),
+    };
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                flatten_toml(&format!("{}_{}", prefix, key), value, out)?;
+            }
+        }
+        toml::Value::Array(items) => {
+            let items: Result<Vec<String>> = items.iter().map(scalar).collect();
+            out.push((name.clone(), items?.join(",")));
+        }
+        other => out.push((name.clone(), scalar(other)?)),
+    }
+    Ok(())
+}
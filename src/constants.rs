@@ -1,4 +1,9 @@
 pub const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
 pub const KALSHI_REST_URL: &str = "https://api.elections.kalshi.com";
 
-pub const BINANCE_SBE_WS_URL: &str = "wss://stream-sbe.binance.com:9443";
\ No newline at end of file
+pub const BINANCE_SBE_WS_URL: &str = "wss://stream-sbe.binance.com:9443";pub const BINANCE_REST_URL: &str = "https://api.binance.com";
+pub const OKX_WS_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+pub const POLYMARKET_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+pub const POLYMARKET_REST_URL: &str = "https://clob.polymarket.com";
+pub const BINANCE_FUTURES_WS_URL: &str = "wss://fstream.binance.com";
+pub const BINANCE_FUTURES_REST_URL: &str = "https://fapi.binance.com";
@@ -0,0 +1,49 @@
+use sea_orm::entity::prelude::*;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "imbalance_alerts")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i64,
+
+    pub event_id: String,
+
+    pub timestamp: DateTime<Utc>,
+
+    pub symbol: String,
+
+    /// Book depth the ratio was taken over: `N_5`, `N_10` or `All`.
+    pub depth: String,
+
+    pub ratio: Decimal,
+
+    pub bid_qty: Decimal,
+
+    pub ask_qty: Decimal,
+
+    #[sea_orm(nullable)]
+    pub kalshi_ticker: Option<String>,
+
+    #[sea_orm(nullable)]
+    pub kalshi_yes_bid: Option<Decimal>,
+
+    #[sea_orm(nullable)]
+    pub kalshi_yes_ask: Option<Decimal>,
+
+    #[sea_orm(nullable)]
+    pub outcome: Option<String>,
+
+    #[sea_orm(nullable)]
+    pub outcome_at: Option<DateTime<Utc>>,
+
+    pub account: String,
+
+    pub strategy: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
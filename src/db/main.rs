@@ -8,34 +8,37 @@ use sea_orm::{
     Statement, 
     DbBackend,
     ConnectionTrait,
+    Value,
+};
+use sea_query::{
+    Table, TableCreateStatement, ColumnDef, MysqlQueryBuilder, PostgresQueryBuilder, SqliteQueryBuilder,
+    Index, Alias,
 };
-use sea_query::{Table, ColumnDef, MysqlQueryBuilder, Index, Alias};
-use tracing::info;
-use chrono::Utc;
 use rust_decimal::Decimal;
-use std::str::FromStr;
-use std::path::Path;
-use std::io::Write;
-use std::time::Duration;
-
-use crate::error::{Error, Result};
-use crate::db::{market_data, market_info};
-
-#[derive(Debug, Clone, FromQueryResult)]
-pub struct MarketDataRow {
-    pub timestamp: chrono::DateTime<Utc>,
-    pub ticker: String,
-    pub asset: String,
-    pub yes_ask: f64,
-    pub yes_bid: f64,
-    pub no_ask: f64,
-    pub no_bid: f64,
-}
+use tokio::sync::mpsc;
+use tokio::time::interval;
+use tracing::{error, info};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, FromQueryResult)]
-pub struct TickerRow {
-    pub ticker: String,
-}
+use crate::config::DatabaseConfig;
+use crate::encryption;
+use crate::error::{ErrorContext, Result, ResultExt};
+use crate::db::sampler::Sampler;
+use crate::db::{imbalance_alerts, market_data, market_info};
+pub use crate::db::records::*;
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::resample::{self, OddsBar, OddsSample};
+use crate::shutdown;
+use crate::status;
+use crate::tags::{self, Tags, MAX_TAG_LEN};
+
+/// Rows the buffered writer can hold in its channel before senders see it
+/// as full.
+const WRITER_CHANNEL_BUFFER: usize = 50_000;
 
 pub struct Db {
     connection: DatabaseConnection,
@@ -52,9 +55,9 @@ impl Db {
 
         let connection = Database::connect(opts)
             .await
-            .map_err(|e| Error::Database(format!("Failed to connect to database: {}", e)))?;
+            .with_context(|| ErrorContext::new("connect to database"))?;
         
-        info!("✅ Connected to TiDB database");
+        info!("✅ Connected to {:?} database", connection.get_database_backend());
         Ok(Self { connection })
     }
 
@@ -62,25 +65,175 @@ impl Db {
         &self.connection
     }
 
-    pub async fn create_market_data_table(&self) -> Result<()> {
+    pub fn backend(&self) -> DbBackend {
+        self.connection.get_database_backend()
+    }
+
+    /// Statement for `sql` written with MySQL's `?` placeholders, renumbered
+    /// to `$1, $2, ...` on Postgres.
+    fn statement(&self, sql: &str, values: Vec<Value>) -> Statement {
+        let backend = self.backend();
+        if backend != DbBackend::Postgres {
+            return Statement::from_sql_and_values(backend, sql, values);
+        }
+        let mut numbered = String::with_capacity(sql.len() + 8);
+        let mut n = 0;
+        for c in sql.chars() {
+            if c == '?' {
+                n += 1;
+                numbered.push_str(&format!("${}", n));
+            } else {
+                numbered.push(c);
+            }
+        }
+        Statement::from_sql_and_values(backend, &numbered, values)
+    }
+
+    /// Timestamp column type: `DATETIME(6)` on MySQL when sub-second
+    /// precision matters, `TIMESTAMPTZ` on Postgres so rows decode as
+    /// `DateTime<Utc>`.
+    fn timestamp_column(&self, name: &str, micros: bool) -> ColumnDef {
+        let mut col = ColumnDef::new(Alias::new(name));
+        match self.backend() {
+            DbBackend::Postgres => col.custom(Alias::new("TIMESTAMPTZ")),
+            _ if micros => col.custom(Alias::new("DATETIME(6)")),
+            _ => col.date_time(),
+        };
+        col
+    }
+
+    /// Runs `stmt` for the connected backend. MySQL takes `indexes` inline;
+    /// elsewhere index names are schema-wide, so they are created separately
+    /// and prefixed with the table name.
+    async fn create_table(
+        &self,
+        table: &str,
+        stmt: TableCreateStatement,
+        indexes: &[(&str, &[&str])],
+    ) -> Result<()> {
+        self.create_table_with(table, stmt, indexes, "").await
+    }
+
+    /// [`Self::create_table`] with `suffix` appended to the generated
+    /// `CREATE TABLE`, e.g. a `PARTITION BY` clause.
+    async fn create_table_with(
+        &self,
+        table: &str,
+        mut stmt: TableCreateStatement,
+        indexes: &[(&str, &[&str])],
+        suffix: &str,
+    ) -> Result<()> {
+        let backend = self.backend();
+        if backend == DbBackend::MySql {
+            for (name, cols) in indexes {
+                let mut index = Index::create();
+                index.name(name);
+                for col in cols.iter() {
+                    index.col(Alias::new(col));
+                }
+                stmt.index(&mut index);
+            }
+        }
+
+        let mut sql = match backend {
+            DbBackend::MySql => stmt.to_string(MysqlQueryBuilder),
+            DbBackend::Postgres => stmt.to_string(PostgresQueryBuilder),
+            DbBackend::Sqlite => stmt.to_string(SqliteQueryBuilder),
+        };
+        sql.push_str(suffix);
+        self.connection.execute_unprepared(&sql)
+            .await
+            .with_context(|| ErrorContext::new(format!("create {} table", table)))?;
+
+        if backend != DbBackend::MySql {
+            for (name, cols) in indexes {
+                let sql = format!(
+                    "CREATE INDEX IF NOT EXISTS {}_{} ON {} ({})",
+                    table,
+                    name,
+                    table,
+                    cols.join(", ")
+                );
+                self.connection.execute_unprepared(&sql)
+                    .await
+                    .with_context(|| ErrorContext::new(format!("create index {} on {}", name, table)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A [`tags`] column; rows written before it existed read as the
+    /// default tag.
+    fn tag_column(name: &str) -> ColumnDef {
+        let mut col = ColumnDef::new(Alias::new(name));
+        col.string_len(MAX_TAG_LEN as u32)
+            .not_null()
+            .default(tags::DEFAULT_TAG);
+        col
+    }
+
+    /// Adds the `account` and `strategy` columns to `table` if it was
+    /// created before they existed. The probe is a plain select because
+    /// MySQL has no `ADD COLUMN IF NOT EXISTS`.
+    async fn add_tag_columns(&self, table: &str) -> Result<()> {
+        for name in ["account", "strategy"] {
+            let probe = format!("SELECT {} FROM {} WHERE 1 = 0", name, table);
+            if self.connection.execute_unprepared(&probe).await.is_ok() {
+                continue;
+            }
+            let stmt = Table::alter()
+                .table(Alias::new(table))
+                .add_column(Self::tag_column(name))
+                .to_owned();
+            let sql = match self.backend() {
+                DbBackend::MySql => stmt.to_string(MysqlQueryBuilder),
+                DbBackend::Postgres => stmt.to_string(PostgresQueryBuilder),
+                DbBackend::Sqlite => stmt.to_string(SqliteQueryBuilder),
+            };
+            self.connection.execute_unprepared(&sql)
+                .await
+                .with_context(|| ErrorContext::new(format!("add {} column to {}", name, table)))?;
+            info!("🏷️ Added {} column to {}", name, table);
+        }
+        Ok(())
+    }
+
+    /// Creates every table the client writes to, skipping existing ones.
+    /// Creates every table. A positive `partition_ahead_days` creates
+    /// `market_data` partitioned, see [`Self::create_market_data_table`].
+    pub async fn create_tables(&self, partition_ahead_days: u32) -> Result<()> {
+        self.create_market_data_table(partition_ahead_days).await?;
+        self.create_market_info_table().await?;
+        self.create_imbalance_alerts_table().await?;
+        self.create_leader_lease_table().await?;
+        self.create_latency_races_table().await?;
+        self.create_kalshi_trades_table().await?;
+        self.create_kalshi_markets_table().await?;
+        self.create_monitor_tables().await?;
+        self.create_market_data_1m_table().await?;
+        self.create_position_snapshots_table().await?;
+        Ok(())
+    }
+
+    /// On Postgres with a positive `partition_ahead_days`, a new table is
+    /// `PARTITION BY RANGE (timestamp)` with the daily partitions of today
+    /// and the next `partition_ahead_days` days; maintenance keeps creating
+    /// them from there. The key then has to include `timestamp`. An
+    /// existing table is left as it is.
+    pub async fn create_market_data_table(&self, partition_ahead_days: u32) -> Result<()> {
         info!("Creating market_data table...");
-        
-        use sea_orm::ConnectionTrait;
-        
-        let stmt = Table::create()
+
+        let partitioned = partition_ahead_days > 0 && self.backend() == DbBackend::Postgres;
+        let mut id = ColumnDef::new(Alias::new("id"));
+        id.big_integer().auto_increment();
+        if !partitioned {
+            id.primary_key();
+        }
+        let mut stmt = Table::create()
             .table(Alias::new("market_data"))
             .if_not_exists()
-            .col(
-                ColumnDef::new(Alias::new("id"))
-                    .big_integer()
-                    .auto_increment()
-                    .primary_key()
-            )
-            .col(
-                ColumnDef::new(Alias::new("timestamp"))
-                    .date_time()
-                    .not_null()
-            )
+            .col(id)
+            .col(self.timestamp_column("timestamp", false).not_null())
             .col(
                 ColumnDef::new(Alias::new("asset"))
                     .string_len(50)
@@ -107,33 +260,37 @@ impl Db {
                 ColumnDef::new(Alias::new("no_bid"))
                     .decimal_len(10, 4)
             )
-            .index(
-                Index::create()
-                    .name("idx_ticker")
-                    .col(Alias::new("ticker"))
-            )
-            .index(
-                Index::create()
-                    .name("idx_timestamp")
-                    .col(Alias::new("timestamp"))
-            )
+            .col(Self::tag_column("account"))
+            .col(Self::tag_column("strategy"))
             .to_owned();
-        
-        let sql = stmt.to_string(MysqlQueryBuilder);
-        
-        self.connection.execute_unprepared(&sql)
-            .await
-            .map_err(|e| Error::Database(format!("Failed to create table: {}", e)))?;
-        
+        if partitioned {
+            stmt.primary_key(Index::create().col(Alias::new("id")).col(Alias::new("timestamp")));
+        }
+
+        self.create_table_with(
+            "market_data",
+            stmt,
+            &[("idx_ticker", &["ticker"]), ("idx_timestamp", &["timestamp"])],
+            if partitioned { " PARTITION BY RANGE (\"timestamp\")" } else { "" },
+        )
+        .await?;
+        self.add_tag_columns("market_data").await?;
+
+        if partitioned && self.market_data_is_partitioned().await? {
+            let today = Utc::now().date_naive();
+            for day in today.iter_days().take(partition_ahead_days as usize + 1) {
+                self.create_market_data_partition(day).await?;
+            }
+            info!("✅ Created market_data table partitioned by day");
+            return Ok(());
+        }
         info!("✅ Created market_data table");
         Ok(())
     }
 
     pub async fn create_market_info_table(&self) -> Result<()> {
         info!("Creating market_info table...");
-        
-        use sea_orm::ConnectionTrait;
-        
+
         let stmt = Table::create()
             .table(Alias::new("market_info"))
             .if_not_exists()
@@ -143,11 +300,7 @@ impl Db {
                     .auto_increment()
                     .primary_key()
             )
-            .col(
-                ColumnDef::new(Alias::new("timestamp"))
-                    .date_time()
-                    .not_null()
-            )
+            .col(self.timestamp_column("timestamp", false).not_null())
             .col(
                 ColumnDef::new(Alias::new("ticker"))
                     .string_len(50)
@@ -162,25 +315,380 @@ impl Db {
                     .string_len(20)
                     .not_null()
             )
-            .index(
+            .col(Self::tag_column("account"))
+            .col(Self::tag_column("strategy"))
+            .to_owned();
+
+        self.create_table(
+            "market_info",
+            stmt,
+            &[("idx_ticker", &["ticker"]), ("idx_timestamp", &["timestamp"])],
+        )
+        .await?;
+        self.add_tag_columns("market_info").await?;
+
+        info!("✅ Created market_info table");
+        Ok(())
+    }
+
+    pub async fn create_imbalance_alerts_table(&self) -> Result<()> {
+        let stmt = Table::create()
+            .table(Alias::new("imbalance_alerts"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("id"))
+                    .big_integer()
+                    .auto_increment()
+                    .primary_key()
+            )
+            .col(ColumnDef::new(Alias::new("event_id")).string_len(64).not_null())
+            .col(self.timestamp_column("timestamp", true).not_null())
+            .col(ColumnDef::new(Alias::new("symbol")).string_len(20).not_null())
+            .col(ColumnDef::new(Alias::new("depth")).string_len(8).not_null())
+            .col(ColumnDef::new(Alias::new("ratio")).decimal_len(20, 4).not_null())
+            .col(ColumnDef::new(Alias::new("bid_qty")).decimal_len(24, 8).not_null())
+            .col(ColumnDef::new(Alias::new("ask_qty")).decimal_len(24, 8).not_null())
+            .col(ColumnDef::new(Alias::new("kalshi_ticker")).string_len(50))
+            .col(ColumnDef::new(Alias::new("kalshi_yes_bid")).decimal_len(20, 8))
+            .col(ColumnDef::new(Alias::new("kalshi_yes_ask")).decimal_len(20, 8))
+            .col(ColumnDef::new(Alias::new("outcome")).string_len(50))
+            .col(self.timestamp_column("outcome_at", true))
+            .col(Self::tag_column("account"))
+            .col(Self::tag_column("strategy"))
+            .to_owned();
+
+        self.create_table(
+            "imbalance_alerts",
+            stmt,
+            &[
+                ("idx_imbalance_event_id", &["event_id"]),
+                ("idx_imbalance_symbol_timestamp", &["symbol", "timestamp"]),
+            ],
+        )
+        .await?;
+        self.add_tag_columns("imbalance_alerts").await?;
+
+        info!("✅ Created imbalance_alerts table");
+        Ok(())
+    }
+
+    pub async fn create_leader_lease_table(&self) -> Result<()> {
+        let stmt = Table::create()
+            .table(Alias::new("leader_lease"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("name"))
+                    .string_len(100)
+                    .not_null()
+                    .primary_key()
+            )
+            .col(
+                ColumnDef::new(Alias::new("holder"))
+                    .string_len(100)
+                    .not_null()
+            )
+            .col(self.timestamp_column("expires_at", true).not_null())
+            .to_owned();
+
+        self.create_table("leader_lease", stmt, &[]).await
+    }
+
+    pub async fn create_latency_races_table(&self) -> Result<()> {
+        let stmt = Table::create()
+            .table(Alias::new("latency_races"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("id"))
+                    .big_integer()
+                    .auto_increment()
+                    .primary_key()
+            )
+            .col(ColumnDef::new(Alias::new("leader")).string_len(20).not_null())
+            .col(ColumnDef::new(Alias::new("follower")).string_len(20).not_null())
+            .col(ColumnDef::new(Alias::new("symbol")).string_len(20).not_null())
+            .col(ColumnDef::new(Alias::new("instrument")).string_len(50))
+            .col(self.timestamp_column("started_at", true).not_null())
+            .col(ColumnDef::new(Alias::new("move_bps")).decimal_len(12, 2).not_null())
+            .col(ColumnDef::new(Alias::new("lag_ms")).big_integer())
+            .col(Self::tag_column("account"))
+            .col(Self::tag_column("strategy"))
+            .to_owned();
+
+        self.create_table(
+            "latency_races",
+            stmt,
+            &[("idx_latency_races_pair_started", &["leader", "follower", "started_at"])],
+        )
+        .await?;
+        self.add_tag_columns("latency_races").await?;
+
+        info!("✅ Created latency_races table");
+        Ok(())
+    }
+
+    pub async fn create_position_snapshots_table(&self) -> Result<()> {
+        let stmt = Table::create()
+            .table(Alias::new("position_snapshots"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("id"))
+                    .big_integer()
+                    .auto_increment()
+                    .primary_key()
+            )
+            .col(self.timestamp_column("timestamp", true).not_null())
+            .col(ColumnDef::new(Alias::new("ticker")).string_len(100).not_null())
+            .col(ColumnDef::new(Alias::new("mode")).string_len(10).not_null())
+            .col(ColumnDef::new(Alias::new("position")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("avg_price")).decimal_len(10, 4).not_null())
+            .col(ColumnDef::new(Alias::new("realized_pnl")).decimal_len(16, 4).not_null())
+            .col(ColumnDef::new(Alias::new("unrealized_pnl")).decimal_len(16, 4).not_null())
+            .col(ColumnDef::new(Alias::new("mark")).decimal_len(10, 4))
+            .col(Self::tag_column("account"))
+            .col(Self::tag_column("strategy"))
+            .to_owned();
+
+        self.create_table(
+            "position_snapshots",
+            stmt,
+            &[("idx_position_snapshots_ticker_timestamp", &["ticker", "timestamp"])],
+        )
+        .await?;
+
+        info!("✅ Created position_snapshots table");
+        Ok(())
+    }
+
+    pub async fn create_kalshi_trades_table(&self) -> Result<()> {
+        let stmt = Table::create()
+            .table(Alias::new("kalshi_trades"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("trade_id"))
+                    .string_len(64)
+                    .not_null()
+                    .primary_key()
+            )
+            .col(ColumnDef::new(Alias::new("ticker")).string_len(100).not_null())
+            .col(ColumnDef::new(Alias::new("taker_side")).string_len(3))
+            .col(ColumnDef::new(Alias::new("yes_price")).decimal_len(10, 4).not_null())
+            .col(ColumnDef::new(Alias::new("count")).big_integer().not_null())
+            .col(self.timestamp_column("created_at", true).not_null())
+            .to_owned();
+
+        self.create_table(
+            "kalshi_trades",
+            stmt,
+            &[("idx_kalshi_trades_ticker_created", &["ticker", "created_at"])],
+        )
+        .await?;
+
+        info!("✅ Created kalshi_trades table");
+        Ok(())
+    }
+
+    pub async fn create_kalshi_markets_table(&self) -> Result<()> {
+        let stmt = Table::create()
+            .table(Alias::new("kalshi_markets"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("ticker"))
+                    .string_len(100)
+                    .not_null()
+                    .primary_key()
+            )
+            .col(ColumnDef::new(Alias::new("series_ticker")).string_len(50))
+            .col(ColumnDef::new(Alias::new("event_ticker")).string_len(100))
+            .col(ColumnDef::new(Alias::new("title")).string_len(255))
+            .col(ColumnDef::new(Alias::new("floor_strike")).decimal_len(20, 8))
+            .col(ColumnDef::new(Alias::new("cap_strike")).decimal_len(20, 8))
+            .col(self.timestamp_column("open_time", false))
+            .col(self.timestamp_column("close_time", false))
+            .col(self.timestamp_column("expiration_time", false))
+            .col(ColumnDef::new(Alias::new("status")).string_len(20).not_null())
+            .col(ColumnDef::new(Alias::new("result")).string_len(10))
+            .col(self.timestamp_column("updated_at", true).not_null())
+            .to_owned();
+
+        self.create_table(
+            "kalshi_markets",
+            stmt,
+            &[("idx_kalshi_markets_series_close", &["series_ticker", "close_time"])],
+        )
+        .await?;
+
+        info!("✅ Created kalshi_markets table");
+        Ok(())
+    }
+
+    /// One-minute rollups of `market_data`, kept after the raw ticks are
+    /// pruned, one bar per bucket for each account and strategy.
+    pub async fn create_market_data_1m_table(&self) -> Result<()> {
+        let stmt = Table::create()
+            .table(Alias::new("market_data_1m"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("ticker")).string_len(100).not_null())
+            .col(ColumnDef::new(Alias::new("asset")).string_len(50).not_null())
+            .col(self.timestamp_column("bucket_start", false).not_null())
+            .col(ColumnDef::new(Alias::new("yes_bid")).decimal_len(10, 4).not_null())
+            .col(ColumnDef::new(Alias::new("yes_ask")).decimal_len(10, 4).not_null())
+            .col(ColumnDef::new(Alias::new("no_bid")).decimal_len(10, 4).not_null())
+            .col(ColumnDef::new(Alias::new("no_ask")).decimal_len(10, 4).not_null())
+            .col(ColumnDef::new(Alias::new("samples")).integer().not_null())
+            .col(Self::tag_column("account"))
+            .col(Self::tag_column("strategy"))
+            .primary_key(
                 Index::create()
-                    .name("idx_ticker")
                     .col(Alias::new("ticker"))
+                    .col(Alias::new("bucket_start"))
+                    .col(Alias::new("account"))
+                    .col(Alias::new("strategy")),
             )
-            .index(
-                Index::create()
-                    .name("idx_timestamp")
-                    .col(Alias::new("timestamp"))
+            .to_owned();
+
+        self.create_table("market_data_1m", stmt, &[]).await?;
+        // Tables from before the tags keep their (ticker, bucket_start) key,
+        // so only one account's bars per bucket fit there
+        self.add_tag_columns("market_data_1m").await?;
+
+        info!("✅ Created market_data_1m table");
+        Ok(())
+    }
+
+    /// Creates `monitor_sessions`, `monitor_odds_changes` and
+    /// `monitor_responses`, which join on the session id.
+    pub async fn create_monitor_tables(&self) -> Result<()> {
+        let sessions = Table::create()
+            .table(Alias::new("monitor_sessions"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("id"))
+                    .string_len(64)
+                    .not_null()
+                    .primary_key()
             )
+            .col(ColumnDef::new(Alias::new("trigger_kind")).string_len(20).not_null())
+            .col(ColumnDef::new(Alias::new("symbol")).string_len(20).not_null())
+            .col(ColumnDef::new(Alias::new("kalshi_ticker")).string_len(100).not_null())
+            .col(self.timestamp_column("started_at", true).not_null())
+            .col(self.timestamp_column("ended_at", true))
+            .col(ColumnDef::new(Alias::new("outcome")).string_len(50))
+            .col(Self::tag_column("account"))
+            .col(Self::tag_column("strategy"))
             .to_owned();
-        
-        let sql = stmt.to_string(MysqlQueryBuilder);
-        
-        self.connection.execute_unprepared(&sql)
+        self.create_table(
+            "monitor_sessions",
+            sessions,
+            &[("idx_monitor_sessions_ticker_started", &["kalshi_ticker", "started_at"])],
+        )
+        .await?;
+        self.add_tag_columns("monitor_sessions").await?;
+
+        let changes = Table::create()
+            .table(Alias::new("monitor_odds_changes"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("id"))
+                    .big_integer()
+                    .auto_increment()
+                    .primary_key()
+            )
+            .col(ColumnDef::new(Alias::new("session_id")).string_len(64).not_null())
+            .col(self.timestamp_column("timestamp", true).not_null())
+            .col(ColumnDef::new(Alias::new("yes_bid")).decimal_len(10, 4))
+            .col(ColumnDef::new(Alias::new("yes_ask")).decimal_len(10, 4))
+            .to_owned();
+        self.create_table(
+            "monitor_odds_changes",
+            changes,
+            &[("idx_monitor_odds_changes_session_ts", &["session_id", "timestamp"])],
+        )
+        .await?;
+
+        let responses = Table::create()
+            .table(Alias::new("monitor_responses"))
+            .if_not_exists()
+            .col(
+                ColumnDef::new(Alias::new("session_id"))
+                    .string_len(64)
+                    .not_null()
+                    .primary_key()
+            )
+            .col(ColumnDef::new(Alias::new("changes")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("first_move_ms")).big_integer())
+            .col(ColumnDef::new(Alias::new("max_move")).decimal_len(10, 4).not_null())
+            .col(ColumnDef::new(Alias::new("final_move")).decimal_len(10, 4).not_null())
+            .to_owned();
+        self.create_table("monitor_responses", responses, &[]).await?;
+
+        info!("✅ Created monitor tables");
+        Ok(())
+    }
+
+    /// Takes or renews the lease `name` for `holder`. Succeeds when the lease is
+    /// free, expired, or already held by `holder`; returns whether `holder`
+    /// owns it afterwards.
+    pub async fn try_acquire_lease(&self, name: &str, holder: &str, lease_secs: u64) -> Result<bool> {
+        let lease_secs = lease_secs as i64;
+        let sql = match self.backend() {
+            // Postgres evaluates every SET against the old row, so both
+            // assignments repeat the takeover condition.
+            DbBackend::Postgres => r#"
+                INSERT INTO leader_lease (name, holder, expires_at)
+                VALUES (?, ?, CURRENT_TIMESTAMP(6) + ? * INTERVAL '1 second')
+                ON CONFLICT (name) DO UPDATE SET
+                  holder = CASE
+                    WHEN leader_lease.holder = EXCLUDED.holder OR leader_lease.expires_at < CURRENT_TIMESTAMP(6)
+                    THEN EXCLUDED.holder ELSE leader_lease.holder END,
+                  expires_at = CASE
+                    WHEN leader_lease.holder = EXCLUDED.holder OR leader_lease.expires_at < CURRENT_TIMESTAMP(6)
+                    THEN EXCLUDED.expires_at ELSE leader_lease.expires_at END
+            "#,
+            // MySQL applies assignments left to right, so the `expires_at` check
+            // sees the `holder` value chosen by the first IF.
+            _ => r#"
+                INSERT INTO leader_lease (name, holder, expires_at)
+                VALUES (?, ?, CURRENT_TIMESTAMP(6) + INTERVAL ? SECOND)
+                ON DUPLICATE KEY UPDATE
+                  holder = IF(holder = VALUES(holder) OR expires_at < CURRENT_TIMESTAMP(6), VALUES(holder), holder),
+                  expires_at = IF(holder = VALUES(holder), VALUES(expires_at), expires_at)
+            "#,
+        };
+        let upsert = self.statement(sql, vec![name.into(), holder.into(), lease_secs.into()]);
+        self.connection
+            .execute(upsert)
             .await
-            .map_err(|e| Error::Database(format!("Failed to create table: {}", e)))?;
-        
-        info!("✅ Created market_data table");
+            .with_context(|| ErrorContext::new(format!("acquire lease {}", name)))?;
+
+        let select = self.statement(
+            "SELECT holder FROM leader_lease WHERE name = ?",
+            vec![name.into()],
+        );
+        let row = self.connection
+            .query_one(select)
+            .await
+            .with_context(|| ErrorContext::new(format!("read lease {}", name)))?;
+
+        let current: Option<String> = match row {
+            Some(row) => row
+                .try_get("", "holder")
+                .with_context(|| ErrorContext::new(format!("read lease {}", name)))?,
+            None => None,
+        };
+        Ok(current.as_deref() == Some(holder))
+    }
+
+    /// Gives up the lease early so a follower can take over without waiting
+    /// for it to expire.
+    pub async fn release_lease(&self, name: &str, holder: &str) -> Result<()> {
+        let stmt = self.statement(
+            "UPDATE leader_lease SET expires_at = CURRENT_TIMESTAMP(6) WHERE name = ? AND holder = ?",
+            vec![name.into(), holder.into()],
+        );
+        self.connection
+            .execute(stmt)
+            .await
+            .with_context(|| ErrorContext::new(format!("release lease {}", name)))?;
         Ok(())
     }
 
@@ -207,30 +715,30 @@ impl Db {
         <market_data::Entity as EntityTrait>::insert(active_model)
             .exec(&self.connection)
             .await
-            .map_err(|e| Error::Database(format!("Failed to insert data: {}", e)))?;
+            .with_context(|| ErrorContext::new("insert market data").with_symbol(ticker))?;
 
         Ok(())
     }
 
-    pub async fn insert_market_data_batch(
-        &self,
-        records: Vec<(String, String, chrono::DateTime<Utc>, f64, f64, f64, f64)>,
-    ) -> Result<()> {
+    pub async fn insert_market_data_batch(&self, records: &[MarketDataRecord]) -> Result<()> {
         if records.is_empty() {
             return Ok(());
         }
 
+        let count = records.len();
         let active_models: Vec<market_data::ActiveModel> = records
-            .into_iter()
-            .map(|(ticker, asset, timestamp, yes_ask, yes_bid, no_ask, no_bid)| {
-                Self::create_market_data_active_model(&ticker, &asset, timestamp, yes_ask, yes_bid, no_ask, no_bid)
+            .iter()
+            .map(|r| {
+                Self::create_market_data_active_model(
+                    &r.ticker, &r.asset, r.timestamp, r.yes_ask, r.yes_bid, r.no_ask, r.no_bid,
+                )
             })
             .collect();
 
         <market_data::Entity as EntityTrait>::insert_many(active_models)
             .exec(&self.connection)
             .await
-            .map_err(|e| Error::Database(format!("Failed to batch insert data: {}", e)))?;
+            .with_context(|| ErrorContext::new(format!("batch insert {} market data rows", count)))?;
 
         Ok(())
     }
@@ -245,10 +753,9 @@ impl Db {
         no_ask: f64,
         no_bid: f64,
     ) -> market_data::ActiveModel {
-        let to_decimal = |v: f64| -> Option<Decimal> {
-            Decimal::from_str(&format!("{:.10}", v)).ok()
-        };
-        
+        let precision = instruments::registry().kalshi(ticker);
+        let to_decimal = |v: f64| precision.price_decimal(v);
+
         market_data::ActiveModel {
             id: ActiveValue::NotSet,
             ticker: ActiveValue::Set(ticker.to_string()),
@@ -258,6 +765,8 @@ impl Db {
             yes_bid: ActiveValue::Set(to_decimal(yes_bid)),
             no_ask: ActiveValue::Set(to_decimal(no_ask)),
             no_bid: ActiveValue::Set(to_decimal(no_bid)),
+            account: ActiveValue::Set(tags::account().to_string()),
+            strategy: ActiveValue::Set(tags::strategy().to_string()),
         }
     }
 
@@ -267,16 +776,16 @@ impl Db {
         strike_price: Option<f64>,
         result: &str,
     ) -> market_info::ActiveModel {
-        let to_decimal = |v: f64| -> Option<Decimal> {
-            Decimal::from_str(&format!("{:.10}", v)).ok()
-        };
-    
+        let precision = instruments::registry().kalshi(ticker);
+
         market_info::ActiveModel {
             id: ActiveValue::NotSet,
             ticker: ActiveValue::Set(ticker.to_string()),
             timestamp: ActiveValue::Set(timestamp),
-            strike_price: ActiveValue::Set(strike_price.and_then(to_decimal)),
+            strike_price: ActiveValue::Set(strike_price.and_then(|v| precision.price_decimal(v))),
             result: ActiveValue::Set(result.to_string().to_uppercase()),
+            account: ActiveValue::Set(tags::account().to_string()),
+            strategy: ActiveValue::Set(tags::strategy().to_string()),
         }
     }
 
@@ -297,11 +806,409 @@ impl Db {
         <market_info::Entity as EntityTrait>::insert(active_model)
             .exec(&self.connection)
             .await
-            .map_err(|e| Error::Database(format!("Failed to insert market info: {}", e)))?;
+            .with_context(|| ErrorContext::new("insert market info").with_symbol(ticker))?;
     
         Ok(())
     }
 
+    pub async fn insert_imbalance_alert(&self, alert: &ImbalanceAlertRecord) -> Result<()> {
+        let binance = instruments::registry().binance(&alert.symbol);
+        let kalshi = instruments::registry().kalshi(alert.kalshi_ticker.as_deref().unwrap_or_default());
+        let ratio = Decimal::from_f64_retain(alert.ratio).map(|d| d.round_dp(4)).unwrap_or_default();
+
+        let active_model = imbalance_alerts::ActiveModel {
+            id: ActiveValue::NotSet,
+            event_id: ActiveValue::Set(alert.event_id.clone()),
+            timestamp: ActiveValue::Set(alert.timestamp),
+            symbol: ActiveValue::Set(alert.symbol.clone()),
+            depth: ActiveValue::Set(alert.depth.clone()),
+            ratio: ActiveValue::Set(ratio),
+            bid_qty: ActiveValue::Set(binance.quantity_decimal(alert.bid_qty).unwrap_or_default()),
+            ask_qty: ActiveValue::Set(binance.quantity_decimal(alert.ask_qty).unwrap_or_default()),
+            kalshi_ticker: ActiveValue::Set(alert.kalshi_ticker.clone()),
+            kalshi_yes_bid: ActiveValue::Set(alert.kalshi_yes_bid.and_then(|v| kalshi.price_decimal(v))),
+            kalshi_yes_ask: ActiveValue::Set(alert.kalshi_yes_ask.and_then(|v| kalshi.price_decimal(v))),
+            outcome: ActiveValue::NotSet,
+            outcome_at: ActiveValue::NotSet,
+            account: ActiveValue::Set(tags::account().to_string()),
+            strategy: ActiveValue::Set(tags::strategy().to_string()),
+        };
+
+        <imbalance_alerts::Entity as EntityTrait>::insert(active_model)
+            .exec(&self.connection)
+            .await
+            .with_context(|| {
+                ErrorContext::new("insert imbalance alert")
+                    .with_exchange("binance")
+                    .with_symbol(alert.symbol.clone())
+            })?;
+
+        Ok(())
+    }
+
+    /// Records how the monitor following alert `event_id` ended, on every
+    /// depth row the alert raised.
+    pub async fn record_imbalance_alert_outcome(&self, event_id: &str, outcome: &str) -> Result<()> {
+        let stmt = self.statement(
+            "UPDATE imbalance_alerts SET outcome = ?, outcome_at = CURRENT_TIMESTAMP(6) WHERE event_id = ?",
+            vec![outcome.into(), event_id.into()],
+        );
+        self.connection
+            .execute(stmt)
+            .await
+            .with_context(|| ErrorContext::new("record imbalance alert outcome"))?;
+        Ok(())
+    }
+
+    pub async fn insert_latency_race(&self, race: &LatencyRaceRecord) -> Result<()> {
+        let move_bps = Decimal::from_f64_retain(race.move_bps).map(|d| d.round_dp(2)).unwrap_or_default();
+        let stmt = self.statement(
+            "INSERT INTO latency_races (leader, follower, symbol, instrument, started_at, move_bps, lag_ms, account, strategy) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            vec![
+                race.leader.clone().into(),
+                race.follower.clone().into(),
+                race.symbol.clone().into(),
+                race.instrument.clone().into(),
+                race.started_at.into(),
+                move_bps.into(),
+                race.lag_ms.into(),
+                tags::account().into(),
+                tags::strategy().into(),
+            ],
+        );
+        self.connection
+            .execute(stmt)
+            .await
+            .with_context(|| ErrorContext::new("insert latency race").with_symbol(race.symbol.clone()))?;
+        Ok(())
+    }
+
+    pub async fn insert_position_snapshots(&self, snapshots: &[PositionSnapshotRecord]) -> Result<()> {
+        if snapshots.is_empty() {
+            return Ok(());
+        }
+        let sql = format!(
+            "INSERT INTO position_snapshots \
+             (timestamp, ticker, mode, position, avg_price, realized_pnl, unrealized_pnl, mark, account, strategy) \
+             VALUES {}",
+            vec!["(?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"; snapshots.len()].join(", ")
+        );
+        let dollars = |v: f64| Decimal::from_f64_retain(v).map(|d| d.round_dp(4)).unwrap_or_default();
+        let mut values: Vec<Value> = Vec::with_capacity(snapshots.len() * 10);
+        for snapshot in snapshots {
+            values.push(snapshot.timestamp.into());
+            values.push(snapshot.ticker.clone().into());
+            values.push(snapshot.mode.clone().into());
+            values.push(snapshot.position.into());
+            values.push(dollars(snapshot.avg_price).into());
+            values.push(dollars(snapshot.realized_pnl).into());
+            values.push(dollars(snapshot.unrealized_pnl).into());
+            values.push(snapshot.mark.map(dollars).into());
+            values.push(tags::account().into());
+            values.push(tags::strategy().into());
+        }
+        self.connection
+            .execute(self.statement(&sql, values))
+            .await
+            .with_context(|| ErrorContext::new(format!("insert {} position snapshots", snapshots.len())))?;
+        Ok(())
+    }
+
+    pub async fn insert_monitor_session(&self, session: &MonitorSessionRecord) -> Result<()> {
+        let stmt = self.statement(
+            "INSERT INTO monitor_sessions (id, trigger_kind, symbol, kalshi_ticker, started_at, account, strategy) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            vec![
+                session.id.clone().into(),
+                session.trigger.clone().into(),
+                session.symbol.clone().into(),
+                session.kalshi_ticker.clone().into(),
+                session.started_at.into(),
+                tags::account().into(),
+                tags::strategy().into(),
+            ],
+        );
+        self.connection
+            .execute(stmt)
+            .await
+            .with_context(|| ErrorContext::new("insert monitor session").with_symbol(session.kalshi_ticker.clone()))?;
+        Ok(())
+    }
+
+    pub async fn insert_odds_changes(&self, changes: &[OddsChangeRecord]) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let sql = format!(
+            "INSERT INTO monitor_odds_changes (session_id, timestamp, yes_bid, yes_ask) VALUES {}",
+            vec!["(?, ?, ?, ?)"; changes.len()].join(", ")
+        );
+        let price = |v: Option<f64>| v.and_then(Decimal::from_f64_retain).map(|d| d.round_dp(4));
+        let mut values: Vec<Value> = Vec::with_capacity(changes.len() * 4);
+        for change in changes {
+            values.push(change.session_id.clone().into());
+            values.push(change.timestamp.into());
+            values.push(price(change.yes_bid).into());
+            values.push(price(change.yes_ask).into());
+        }
+        self.connection
+            .execute(self.statement(&sql, values))
+            .await
+            .with_context(|| ErrorContext::new(format!("insert {} odds changes", changes.len())))?;
+        Ok(())
+    }
+
+    /// Closes the session with its outcome and stores its response metrics.
+    pub async fn finish_monitor_session(
+        &self,
+        ended_at: chrono::DateTime<Utc>,
+        outcome: &str,
+        response: &MonitorResponseRecord,
+    ) -> Result<()> {
+        let context = || ErrorContext::new(format!("finish monitor session {}", response.session_id));
+        let update = self.statement(
+            "UPDATE monitor_sessions SET ended_at = ?, outcome = ? WHERE id = ?",
+            vec![ended_at.into(), outcome.into(), response.session_id.clone().into()],
+        );
+        self.connection.execute(update).await.with_context(context)?;
+
+        let amount = |v: f64| Decimal::from_f64_retain(v).map(|d| d.round_dp(4)).unwrap_or_default();
+        let insert = self.statement(
+            "INSERT INTO monitor_responses (session_id, changes, first_move_ms, max_move, final_move) VALUES (?, ?, ?, ?, ?)",
+            vec![
+                response.session_id.clone().into(),
+                response.changes.into(),
+                response.first_move_ms.into(),
+                amount(response.max_move).into(),
+                amount(response.final_move).into(),
+            ],
+        );
+        self.connection.execute(insert).await.with_context(context)?;
+        Ok(())
+    }
+
+    /// Inserts the market or replaces its stored definition.
+    pub async fn upsert_kalshi_market(&self, market: &KalshiMarketRecord) -> Result<()> {
+        const COLUMNS: &[&str] = &[
+            "series_ticker",
+            "event_ticker",
+            "title",
+            "floor_strike",
+            "cap_strike",
+            "open_time",
+            "close_time",
+            "expiration_time",
+            "status",
+            "result",
+            "updated_at",
+        ];
+        let updates = match self.backend() {
+            DbBackend::MySql => COLUMNS
+                .iter()
+                .map(|c| format!("{} = VALUES({})", c, c))
+                .collect::<Vec<_>>(),
+            _ => COLUMNS
+                .iter()
+                .map(|c| format!("{} = EXCLUDED.{}", c, c))
+                .collect::<Vec<_>>(),
+        };
+        let conflict = match self.backend() {
+            DbBackend::MySql => "ON DUPLICATE KEY UPDATE",
+            _ => "ON CONFLICT (ticker) DO UPDATE SET",
+        };
+        let sql = format!(
+            "INSERT INTO kalshi_markets (ticker, {}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) {} {}",
+            COLUMNS.join(", "),
+            conflict,
+            updates.join(", ")
+        );
+
+        let strike = |v: Option<f64>| v.and_then(Decimal::from_f64_retain).map(|d| d.round_dp(8));
+        let stmt = self.statement(
+            &sql,
+            vec![
+                market.ticker.clone().into(),
+                market.series_ticker.clone().into(),
+                market.event_ticker.clone().into(),
+                market.title.clone().into(),
+                strike(market.floor_strike).into(),
+                strike(market.cap_strike).into(),
+                market.open_time.into(),
+                market.close_time.into(),
+                market.expiration_time.into(),
+                market.status.clone().into(),
+                market.result.clone().into(),
+                market.updated_at.into(),
+            ],
+        );
+        self.connection
+            .execute(stmt)
+            .await
+            .with_context(|| ErrorContext::new("upsert kalshi market").with_symbol(market.ticker.clone()))?;
+        Ok(())
+    }
+
+    /// Inserts trades, skipping ones already stored so overlapping
+    /// backfills are harmless. Returns the number of new rows.
+    pub async fn insert_kalshi_trades(&self, trades: &[KalshiTradeRecord]) -> Result<u64> {
+        if trades.is_empty() {
+            return Ok(0);
+        }
+
+        let placeholders = vec!["(?, ?, ?, ?, ?, ?)"; trades.len()].join(", ");
+        let sql = match self.backend() {
+            DbBackend::MySql => format!(
+                "INSERT IGNORE INTO kalshi_trades (trade_id, ticker, taker_side, yes_price, count, created_at) VALUES {}",
+                placeholders
+            ),
+            _ => format!(
+                "INSERT INTO kalshi_trades (trade_id, ticker, taker_side, yes_price, count, created_at) VALUES {} \
+                 ON CONFLICT (trade_id) DO NOTHING",
+                placeholders
+            ),
+        };
+        let mut values: Vec<Value> = Vec::with_capacity(trades.len() * 6);
+        for trade in trades {
+            let yes_price = instruments::registry().kalshi(&trade.ticker).price_decimal(trade.yes_price);
+            values.push(trade.trade_id.clone().into());
+            values.push(trade.ticker.clone().into());
+            values.push(trade.taker_side.clone().into());
+            values.push(yes_price.unwrap_or_default().into());
+            values.push(trade.count.into());
+            values.push(trade.created_at.into());
+        }
+
+        let result = self.connection
+            .execute(self.statement(&sql, values))
+            .await
+            .with_context(|| ErrorContext::new(format!("insert {} kalshi trades", trades.len())))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Tickers with ticks of `tags` older than `before`.
+    pub async fn fetch_tickers_before(&self, before: chrono::DateTime<Utc>, tags: &Tags) -> Result<Vec<String>> {
+        let stmt = self.statement(
+            "SELECT DISTINCT ticker FROM market_data WHERE timestamp < ? AND account = ? AND strategy = ? \
+             ORDER BY ticker ASC",
+            vec![before.into(), tags.account.clone().into(), tags.strategy.clone().into()],
+        );
+        let rows = TickerRow::find_by_statement(stmt)
+            .all(&self.connection)
+            .await
+            .with_context(|| ErrorContext::new("fetch tickers before cutoff"))?;
+        Ok(rows.into_iter().map(|r| r.ticker).collect())
+    }
+
+    /// Stores one-minute bars of `tags`, leaving buckets already rolled up
+    /// alone. Returns the number of new rows.
+    pub async fn insert_market_data_1m(&self, bars: &[OddsBar], tags: &Tags) -> Result<u64> {
+        let mut inserted = 0;
+        // Chunked to stay under the bind parameter limits of every backend.
+        for chunk in bars.chunks(500) {
+            let placeholders = vec!["(?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"; chunk.len()].join(", ");
+            let columns = "ticker, asset, bucket_start, yes_bid, yes_ask, no_bid, no_ask, samples, account, strategy";
+            // No conflict target, so tables keyed with or without the tags
+            // both skip existing buckets
+            let sql = match self.backend() {
+                DbBackend::MySql => format!("INSERT IGNORE INTO market_data_1m ({}) VALUES {}", columns, placeholders),
+                _ => format!(
+                    "INSERT INTO market_data_1m ({}) VALUES {} ON CONFLICT DO NOTHING",
+                    columns, placeholders
+                ),
+            };
+            let mut values: Vec<Value> = Vec::with_capacity(chunk.len() * 10);
+            for bar in chunk {
+                let precision = instruments::registry().kalshi(&bar.ticker);
+                let price = |v: f64| precision.price_decimal(v).unwrap_or_default();
+                values.push(bar.ticker.clone().into());
+                values.push(bar.asset.clone().into());
+                values.push(bar.bucket_start.into());
+                values.push(price(bar.yes_bid).into());
+                values.push(price(bar.yes_ask).into());
+                values.push(price(bar.no_bid).into());
+                values.push(price(bar.no_ask).into());
+                values.push((bar.samples as i32).into());
+                values.push(tags.account.clone().into());
+                values.push(tags.strategy.clone().into());
+            }
+            let result = self.connection
+                .execute(self.statement(&sql, values))
+                .await
+                .with_context(|| ErrorContext::new(format!("insert {} market_data_1m rows", chunk.len())))?;
+            inserted += result.rows_affected();
+        }
+        Ok(inserted)
+    }
+
+    /// Deletes a ticker's ticks of `tags` older than `before`, returning
+    /// how many. Other accounts' and strategies' ticks are left alone.
+    pub async fn delete_market_data_before(
+        &self,
+        ticker: &str,
+        before: chrono::DateTime<Utc>,
+        tags: &Tags,
+    ) -> Result<u64> {
+        let stmt = self.statement(
+            "DELETE FROM market_data WHERE ticker = ? AND timestamp < ? AND account = ? AND strategy = ?",
+            vec![ticker.into(), before.into(), tags.account.clone().into(), tags.strategy.clone().into()],
+        );
+        let result = self.connection
+            .execute(stmt)
+            .await
+            .with_context(|| ErrorContext::new("prune market data").with_symbol(ticker))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Whether `market_data` was created as a Postgres partitioned table;
+    /// partitions are only managed then.
+    pub async fn market_data_is_partitioned(&self) -> Result<bool> {
+        if self.backend() != DbBackend::Postgres {
+            return Ok(false);
+        }
+        let stmt = self.statement(
+            "SELECT c.relname AS ticker FROM pg_partitioned_table p JOIN pg_class c ON c.oid = p.partrelid \
+             WHERE c.relname = 'market_data'",
+            vec![],
+        );
+        let row = TickerRow::find_by_statement(stmt)
+            .one(&self.connection)
+            .await
+            .with_context(|| ErrorContext::new("check market_data partitioning"))?;
+        Ok(row.is_some())
+    }
+
+    fn market_data_partition(day: chrono::NaiveDate) -> String {
+        format!("market_data_p{}", day.format("%Y%m%d"))
+    }
+
+    pub async fn market_data_partition_exists(&self, day: chrono::NaiveDate) -> Result<bool> {
+        let name = Self::market_data_partition(day);
+        let row = TickerRow::find_by_statement(self.statement(
+            "SELECT relname AS ticker FROM pg_class WHERE relname = ?",
+            vec![name.clone().into()],
+        ))
+        .one(&self.connection)
+        .await
+        .with_context(|| ErrorContext::new(format!("check partition {}", name)))?;
+        Ok(row.is_some())
+    }
+
+    /// Creates the daily `market_data` partition covering `day`.
+    pub async fn create_market_data_partition(&self, day: chrono::NaiveDate) -> Result<()> {
+        let name = Self::market_data_partition(day);
+        let next = day.succ_opt().unwrap_or(day);
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} PARTITION OF market_data FOR VALUES FROM ('{}') TO ('{}')",
+            name, day, next
+        );
+        self.connection
+            .execute_unprepared(&sql)
+            .await
+            .with_context(|| ErrorContext::new(format!("create partition {}", name)))?;
+        Ok(())
+    }
+
     pub async fn fetch_all_tickers(&self) -> Result<Vec<String>> {
         const BATCH_SIZE: i64 = 500;
 
@@ -316,16 +1223,12 @@ impl Db {
                 LIMIT ? OFFSET ?
             "#;
 
-            let stmt = Statement::from_sql_and_values(
-                DbBackend::MySql,
-                sql,
-                vec![BATCH_SIZE.into(), offset.into()]
-            );
+            let stmt = self.statement(sql, vec![BATCH_SIZE.into(), offset.into()]);
 
             let batch_rows = TickerRow::find_by_statement(stmt)
                 .all(&self.connection)
                 .await
-                .map_err(|e| Error::Database(format!("Failed to fetch all tickers: {}", e)))?;
+                .with_context(|| ErrorContext::new("fetch all tickers"))?;
 
             let batch_count = batch_rows.len();
             if batch_count == 0 {
@@ -343,37 +1246,70 @@ impl Db {
     }
 
     pub async fn fetch_ticker_market_data(&self, ticker: &str) -> Result<Vec<MarketDataRow>> {
+        self.fetch_market_data_rows(ticker, None).await
+    }
+
+    /// A ticker's ticks of `tags` older than `before`, oldest first.
+    pub async fn fetch_ticker_market_data_before(
+        &self,
+        ticker: &str,
+        before: chrono::DateTime<Utc>,
+        tags: &Tags,
+    ) -> Result<Vec<MarketDataRow>> {
+        self.fetch_market_data_rows(ticker, Some((before, tags))).await
+    }
+
+    async fn fetch_market_data_rows(
+        &self,
+        ticker: &str,
+        before: Option<(chrono::DateTime<Utc>, &Tags)>,
+    ) -> Result<Vec<MarketDataRow>> {
         const BATCH_SIZE: i64 = 500;
+        let double = match self.backend() {
+            DbBackend::Postgres => "DOUBLE PRECISION",
+            _ => "DOUBLE",
+        };
 
         let mut offset: i64 = 0;
         let mut rows: Vec<MarketDataRow> = Vec::new();
 
         loop {
-            let sql = r#"
+            let sql = format!(
+                r#"
                 SELECT
                   timestamp,
                   ticker,
                   asset,
-                  CAST(yes_ask AS DOUBLE) AS yes_ask,
-                  CAST(yes_bid AS DOUBLE) AS yes_bid,
-                  CAST(no_ask AS DOUBLE) AS no_ask,
-                  CAST(no_bid AS DOUBLE) AS no_bid
+                  CAST(yes_ask AS {double}) AS yes_ask,
+                  CAST(yes_bid AS {double}) AS yes_bid,
+                  CAST(no_ask AS {double}) AS no_ask,
+                  CAST(no_bid AS {double}) AS no_bid
                 FROM market_data
-                WHERE ticker = ?
+                WHERE ticker = ?{before_clause}
                 ORDER BY timestamp ASC
                 LIMIT ? OFFSET ?
-            "#;
-
-            let stmt = Statement::from_sql_and_values(
-                DbBackend::MySql,
-                sql,
-                vec![ticker.into(), BATCH_SIZE.into(), offset.into()]
+            "#,
+                double = double,
+                before_clause = if before.is_some() {
+                    " AND timestamp < ? AND account = ? AND strategy = ?"
+                } else {
+                    ""
+                },
             );
 
+            let mut values: Vec<Value> = vec![ticker.into()];
+            if let Some((before, tags)) = before {
+                values.push(before.into());
+                values.push(tags.account.clone().into());
+                values.push(tags.strategy.clone().into());
+            }
+            values.extend([BATCH_SIZE.into(), offset.into()]);
+            let stmt = self.statement(&sql, values);
+
             let batch_rows = MarketDataRow::find_by_statement(stmt)
                 .all(&self.connection)
                 .await
-                .map_err(|e| Error::Database(format!("Failed to fetch market data: {}", e)))?;
+                .with_context(|| ErrorContext::new("fetch market data").with_symbol(ticker))?;
 
             let batch_count = batch_rows.len();
             if batch_count == 0 {
@@ -390,39 +1326,132 @@ impl Db {
         }
     }
 
-    pub async fn export_ticker_to_csv(&self, ticker: &str, csv_path: &str) -> Result<usize> {
-        let path = Path::new(csv_path);
-        let file_exists = path.exists();
-
-        let mut file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)
-            .map_err(|e| Error::Database(format!("Failed to open CSV file: {}", e)))?;
-
-        if !file_exists {
-            writeln!(file, "timestamp,ticker,yes_ask,yes_bid,no_ask,no_bid")
-                .map_err(|e| Error::Database(format!("Failed to write CSV header: {}", e)))?;
-        }
-
+    /// A ticker's stored ticks resampled onto an `interval` grid with
+    /// forward fill, for analyses that line odds up with price bars.
+    pub async fn fetch_ticker_odds_bars(&self, ticker: &str, interval: Duration) -> Result<Vec<OddsBar>> {
+        let rows = self.fetch_ticker_market_data(ticker).await?;
+        Ok(resample::resample(rows.iter().map(OddsSample::from), interval))
+    }
 
+    pub async fn export_ticker_to_csv(&self, ticker: &str, csv_path: &str) -> Result<usize> {
         let rows = self.fetch_ticker_market_data(ticker).await?;
 
         let total_count = rows.len();
 
+        let mut csv = String::new();
         for row in rows {
-            writeln!(file, "{},{},{},{},{},{}",
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
                 row.timestamp.format("%Y-%m-%d %H:%M:%S"),
                 row.ticker,
-                row.yes_ask.to_string(),
-                row.yes_bid.to_string(),
-                row.no_ask.to_string(),
-                row.no_bid.to_string(),
-            ).map_err(|e| Error::Database(format!("Failed to write CSV row: {}", e)))?;
+                row.yes_ask,
+                row.yes_bid,
+                row.no_ask,
+                row.no_bid,
+            ));
         }
+        encryption::append_report(csv_path, "timestamp,ticker,yes_ask,yes_bid,no_ask,no_bid\n", &csv)
+            .with_context(|| ErrorContext::new(format!("write CSV file {}", csv_path)))?;
 
         info!("✅ Exported {} rows for ticker {} to {}", total_count, ticker, csv_path);
         Ok(total_count)
     }
 }
 
+/// Accumulates `market_data` rows and writes them with one `insert_many`
+/// per batch, flushing when `DATABASE_BATCH_SIZE` rows are buffered or every
+/// `DATABASE_FLUSH_INTERVAL_MS`, whichever comes first.
+pub struct BufferedWriter {
+    db: Arc<Db>,
+    batch_size: usize,
+    flush_interval: Duration,
+    sampler: Sampler,
+}
+
+impl BufferedWriter {
+    pub fn spawn(db: Arc<Db>, config: &DatabaseConfig) -> mpsc::Sender<MarketDataRecord> {
+        let (tx, rx) = mpsc::channel::<MarketDataRecord>(WRITER_CHANNEL_BUFFER);
+        status::board().register_queue("market_data", &tx);
+        let writer = Self {
+            db,
+            batch_size: config.batch_size.max(1),
+            flush_interval: Duration::from_millis(config.flush_interval_ms.max(1)),
+            sampler: Sampler::new("market_data", config.persist.market_data),
+        };
+        shutdown::coordinator().track("market_data writer", tokio::spawn(writer.run(rx)));
+        tx
+    }
+
+    async fn run(mut self, mut rx: mpsc::Receiver<MarketDataRecord>) {
+        let mut batch: Vec<MarketDataRecord> = Vec::with_capacity(self.batch_size);
+        let mut flush_interval = interval(self.flush_interval);
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+
+        loop {
+            tokio::select! {
+                maybe_record = rx.recv() => {
+                    match maybe_record {
+                        Some(record) if !self.sampler.admit(&record.ticker, record.timestamp) => {}
+                        Some(record) => {
+                            batch.push(record);
+                            if batch.len() >= self.batch_size {
+                                self.flush(&mut batch, rx.len()).await;
+                            }
+                        }
+                        None => {
+                            self.flush(&mut batch, 0).await;
+                            info!("Market data writer shutting down");
+                            break;
+                        }
+                    }
+                }
+                _ = flush_interval.tick() => {
+                    self.flush(&mut batch, rx.len()).await;
+                }
+                _ = shutdown::requested(&mut shutdown_rx) => {
+                    // Keep what is already queued, refuse the rest.
+                    rx.close();
+                    while let Some(record) = rx.recv().await {
+                        batch.push(record);
+                    }
+                    self.flush(&mut batch, 0).await;
+                    info!("Market data writer shutting down");
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn flush(&self, batch: &mut Vec<MarketDataRecord>, queued: usize) {
+        metrics::set(
+            names::DB_WRITER_QUEUE_DEPTH,
+            &[("table", "market_data")],
+            (queued + batch.len()) as f64,
+        );
+        if batch.is_empty() {
+            return;
+        }
+
+        let count = batch.len();
+        let started = Instant::now();
+        let result = self.db.insert_market_data_batch(batch).await;
+        metrics::observe(
+            names::DB_FLUSH_SECONDS,
+            &[("table", "market_data")],
+            started.elapsed().as_secs_f64(),
+        );
+        batch.clear();
+
+        if let Err(e) = result {
+            error!("Failed to batch insert market data: {}", e);
+            metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", "market_data")]);
+            notify::publish(Notification::DbFailure {
+                table: "market_data".to_string(),
+                error: e.to_string(),
+            });
+        } else {
+            info!("📝 Flushed {} market data records to DB", count);
+            metrics::add(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "market_data")], count as u64);
+        }
+    }
+}
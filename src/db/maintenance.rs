@@ -0,0 +1,164 @@
+//! Scheduled `market_data` upkeep.
+//!
+//! Each pass creates the daily partitions of the next days when the table
+//! is partitioned (Postgres only; `init-db` creates it partitioned when
+//! `DATABASE_PARTITION_AHEAD_DAYS` is above 0), then rolls ticks older than
+//! the retention up into one-minute bars in `market_data_1m` and deletes
+//! them. The cutoff sits on a minute boundary so every rolled-up bucket is
+//! complete. Only this instance's account and strategy are rolled up and
+//! pruned, so instances sharing a database each keep their own retention.
+//! A dry run reports the same counts without writing.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, DurationRound, Utc};
+use sea_orm::DbBackend;
+use tracing::{error, info, warn};
+
+use super::main::Db;
+use crate::config::MaintenanceConfig;
+use crate::error::Result;
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::resample::{self, OddsSample};
+use crate::tags;
+use crate::timezone;
+
+const ROLLUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What one pass did, or would have done on a dry run.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceReport {
+    pub dry_run: bool,
+    pub cutoff: Option<DateTime<Utc>>,
+    pub partitions_created: usize,
+    pub tickers: usize,
+    pub bars: u64,
+    pub raw_rows: u64,
+}
+
+impl MaintenanceReport {
+    pub fn log(&self) {
+        let prefix = if self.dry_run { "🧹 [dry run] Would have" } else { "🧹 Maintenance" };
+        info!(
+            "{} created {} partitions, rolled {} raw rows of {} tickers before {} into {} one-minute bars",
+            prefix,
+            self.partitions_created,
+            self.raw_rows,
+            self.tickers,
+            self.cutoff.map(timezone::format).unwrap_or_default(),
+            self.bars
+        );
+    }
+}
+
+pub struct Maintenance {
+    db: Arc<Db>,
+    config: MaintenanceConfig,
+}
+
+impl Maintenance {
+    pub fn new(db: Arc<Db>, config: MaintenanceConfig) -> Self {
+        Self { db, config }
+    }
+
+    /// Runs a pass every `interval_secs` while this instance leads.
+    pub fn spawn(self, leader: LeaderHandle) {
+        tokio::spawn(async move {
+            self.check_partitioning().await;
+            let mut interval = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+            loop {
+                interval.tick().await;
+                if !leader.is_leader() {
+                    continue;
+                }
+                match self.run_once(Utc::now()).await {
+                    Ok(report) => report.log(),
+                    Err(e) => error!("Database maintenance failed: {}", e),
+                }
+            }
+        });
+    }
+
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<MaintenanceReport> {
+        let mut report = MaintenanceReport {
+            dry_run: self.config.dry_run,
+            ..Default::default()
+        };
+        report.partitions_created = self.ensure_partitions(now).await?;
+
+        let retention = chrono::Duration::days(self.config.raw_retention_days as i64);
+        let cutoff = (now - retention)
+            .duration_trunc(chrono::Duration::minutes(1))
+            .unwrap_or(now - retention);
+        report.cutoff = Some(cutoff);
+
+        let tags = tags::current();
+        let tickers = self.db.fetch_tickers_before(cutoff, tags).await?;
+        report.tickers = tickers.len();
+        for ticker in &tickers {
+            let rows = self.db.fetch_ticker_market_data_before(ticker, cutoff, tags).await?;
+            // Forward-filled buckets carry no information the next bar lacks.
+            let bars: Vec<_> = resample::resample(rows.iter().map(OddsSample::from), ROLLUP_INTERVAL)
+                .into_iter()
+                .filter(|bar| bar.samples > 0)
+                .collect();
+
+            if self.config.dry_run {
+                report.bars += bars.len() as u64;
+                report.raw_rows += rows.len() as u64;
+                continue;
+            }
+
+            // Raw rows go only once their bars are stored.
+            let inserted = self.db.insert_market_data_1m(&bars, tags).await?;
+            metrics::add(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "market_data_1m")], inserted);
+            let deleted = self.db.delete_market_data_before(ticker, cutoff, tags).await?;
+            metrics::add(names::DB_ROWS_PRUNED_TOTAL, &[("table", "market_data")], deleted);
+            report.bars += inserted;
+            report.raw_rows += deleted;
+        }
+        Ok(report)
+    }
+
+    /// Warns when partitions are asked for but `market_data` on Postgres
+    /// was created unpartitioned, e.g. before partitioning was configured:
+    /// converting it is a manual migration, so until then no partitions are
+    /// created.
+    async fn check_partitioning(&self) {
+        if self.config.partition_ahead_days == 0 || self.db.backend() != DbBackend::Postgres {
+            return;
+        }
+        match self.db.market_data_is_partitioned().await {
+            Ok(true) => {}
+            Ok(false) => warn!(
+                "DATABASE_PARTITION_AHEAD_DAYS is {} but market_data is not partitioned; \
+                 recreate it with init-db or set DATABASE_PARTITION_AHEAD_DAYS=0",
+                self.config.partition_ahead_days
+            ),
+            Err(e) => error!("Failed to check market_data partitioning: {}", e.chain()),
+        }
+    }
+
+    /// Creates the partitions for today and the next `partition_ahead_days`
+    /// days, returning how many were (or would be) created.
+    async fn ensure_partitions(&self, now: DateTime<Utc>) -> Result<usize> {
+        if self.config.partition_ahead_days == 0 || !self.db.market_data_is_partitioned().await? {
+            return Ok(0);
+        }
+        let today = now.date_naive();
+        let mut created = 0;
+        for day in today.iter_days().take(self.config.partition_ahead_days as usize + 1) {
+            if self.db.market_data_partition_exists(day).await? {
+                continue;
+            }
+            if !self.config.dry_run {
+                self.db.create_market_data_partition(day).await?;
+                info!("🧱 Created market_data partition for {}", day);
+            }
+            created += 1;
+        }
+        Ok(created)
+    }
+}
@@ -25,6 +25,10 @@ pub struct Model {
     
     #[sea_orm(nullable)]
     pub no_bid: Option<Decimal>,
+    
+    pub account: String,
+    
+    pub strategy: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
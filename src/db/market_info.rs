@@ -16,6 +16,10 @@ pub struct Model {
     pub strike_price: Option<Decimal>,
     
     pub result: String,
+    
+    pub account: String,
+    
+    pub strategy: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,3 +1,15 @@
+#[cfg(feature = "db")]
+pub mod imbalance_alerts;
+#[cfg(feature = "db")]
 pub mod main;
+#[cfg(not(feature = "db"))]
+#[path = "disabled.rs"]
+pub mod main;
+#[cfg(feature = "db")]
+pub mod maintenance;
+#[cfg(feature = "db")]
 pub mod market_data;
-pub mod market_info;
\ No newline at end of file
+#[cfg(feature = "db")]
+pub mod market_info;
+pub mod records;
+pub mod sampler;
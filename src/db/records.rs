@@ -0,0 +1,156 @@
+//! Rows written to and read from the database, kept apart from [`Db`] so
+//! the code producing them builds without the `db` feature.
+//!
+//! [`Db`]: super::main::Db
+
+use chrono::Utc;
+#[cfg(feature = "db")]
+use sea_orm::FromQueryResult;
+
+/// One `market_data` row waiting in the buffered writer.
+#[derive(Debug, Clone)]
+pub struct MarketDataRecord {
+    pub ticker: String,
+    pub asset: String,
+    pub timestamp: chrono::DateTime<Utc>,
+    pub yes_ask: f64,
+    pub yes_bid: f64,
+    pub no_ask: f64,
+    pub no_bid: f64,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "db", derive(FromQueryResult))]
+pub struct MarketDataRow {
+    pub timestamp: chrono::DateTime<Utc>,
+    pub ticker: String,
+    pub asset: String,
+    pub yes_ask: f64,
+    pub yes_bid: f64,
+    pub no_ask: f64,
+    pub no_bid: f64,
+}
+
+/// A depth imbalance alert with the Kalshi market it was correlated to, as
+/// stored in `imbalance_alerts`.
+#[derive(Debug, Clone)]
+pub struct ImbalanceAlertRecord {
+    pub event_id: String,
+    pub timestamp: chrono::DateTime<Utc>,
+    pub symbol: String,
+    pub depth: String,
+    pub ratio: f64,
+    pub bid_qty: f64,
+    pub ask_qty: f64,
+    pub kalshi_ticker: Option<String>,
+    pub kalshi_yes_bid: Option<f64>,
+    pub kalshi_yes_ask: Option<f64>,
+}
+
+/// A marked Kalshi position, stored in `position_snapshots`.
+#[derive(Debug, Clone)]
+pub struct PositionSnapshotRecord {
+    pub timestamp: chrono::DateTime<Utc>,
+    pub ticker: String,
+    /// `kalshi` for real fills, `paper` for simulated ones
+    pub mode: String,
+    /// YES contracts held; negative is NO
+    pub position: i64,
+    /// YES price, dollars
+    pub avg_price: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub mark: Option<f64>,
+}
+
+/// One venue's response to a large Binance move, stored in `latency_races`.
+#[derive(Debug, Clone)]
+pub struct LatencyRaceRecord {
+    pub leader: String,
+    pub follower: String,
+    /// Binance symbol that moved
+    pub symbol: String,
+    /// Follower instrument that reflected the move; `None` when none did
+    pub instrument: Option<String>,
+    pub started_at: chrono::DateTime<Utc>,
+    pub move_bps: f64,
+    /// `None` when the follower missed the timeout
+    pub lag_ms: Option<i64>,
+}
+
+/// A window during which a Kalshi market is watched for its response to a
+/// trigger such as an imbalance alert, stored in `monitor_sessions`.
+#[derive(Debug, Clone)]
+pub struct MonitorSessionRecord {
+    /// Event id of the trigger, shared with the status board's monitor id
+    pub id: String,
+    /// What started the session, e.g. `imbalance`
+    pub trigger: String,
+    /// Binance symbol of the trigger
+    pub symbol: String,
+    pub kalshi_ticker: String,
+    pub started_at: chrono::DateTime<Utc>,
+}
+
+/// The watched market's top of book after a change during a session,
+/// stored in `monitor_odds_changes`.
+#[derive(Debug, Clone)]
+pub struct OddsChangeRecord {
+    pub session_id: String,
+    pub timestamp: chrono::DateTime<Utc>,
+    pub yes_bid: Option<f64>,
+    pub yes_ask: Option<f64>,
+}
+
+/// How the market responded over a finished session, computed from its
+/// odds changes and stored in `monitor_responses`.
+#[derive(Debug, Clone)]
+pub struct MonitorResponseRecord {
+    pub session_id: String,
+    /// Odds changes recorded in the session
+    pub changes: i64,
+    /// Time from the trigger to the first mid change, if any
+    pub first_move_ms: Option<i64>,
+    /// Largest mid move from the start, signed, in dollars
+    pub max_move: f64,
+    /// Mid move from the start to the end of the session, in dollars
+    pub final_move: f64,
+}
+
+/// A Kalshi market definition, upserted into `kalshi_markets` by ticker
+/// whenever the market is tracked or changes state.
+#[derive(Debug, Clone)]
+pub struct KalshiMarketRecord {
+    pub ticker: String,
+    pub series_ticker: Option<String>,
+    pub event_ticker: Option<String>,
+    pub title: Option<String>,
+    pub floor_strike: Option<f64>,
+    pub cap_strike: Option<f64>,
+    pub open_time: Option<chrono::DateTime<Utc>>,
+    pub close_time: Option<chrono::DateTime<Utc>>,
+    pub expiration_time: Option<chrono::DateTime<Utc>>,
+    pub status: String,
+    /// `yes` or `no` once determined
+    pub result: Option<String>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+/// A Kalshi public trade, stored in `kalshi_trades`.
+#[derive(Debug, Clone)]
+pub struct KalshiTradeRecord {
+    pub trade_id: String,
+    pub ticker: String,
+    /// `yes` or `no`, when Kalshi reports the taker
+    pub taker_side: Option<String>,
+    /// YES price in dollars
+    pub yes_price: f64,
+    pub count: i64,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "db", derive(FromQueryResult))]
+pub struct TickerRow {
+    pub ticker: String,
+}
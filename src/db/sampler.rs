@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::config::Persistence;
+use crate::metrics::{self, names};
+
+/// Applies a table's [`Persistence`] to the rows headed for it, keeping at
+/// most one row per key and interval when sampled.
+pub struct Sampler {
+    table: &'static str,
+    persistence: Persistence,
+    last: HashMap<String, DateTime<Utc>>,
+}
+
+impl Sampler {
+    pub fn new(table: &'static str, persistence: Persistence) -> Self {
+        Self {
+            table,
+            persistence,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Whether the row for `key` at `time` should be written.
+    pub fn admit(&mut self, key: &str, time: DateTime<Utc>) -> bool {
+        let admitted = match self.persistence {
+            Persistence::Off => false,
+            Persistence::All => true,
+            Persistence::SampleMs(ms) => match self.last.get(key) {
+                Some(last) if time - *last < chrono::Duration::milliseconds(ms as i64) => false,
+                _ => {
+                    self.last.insert(key.to_string(), time);
+                    true
+                }
+            },
+        };
+        if !admitted {
+            metrics::inc(names::DB_ROWS_SAMPLED_OUT_TOTAL, &[("table", self.table)]);
+        }
+        admitted
+    }
+}
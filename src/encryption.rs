@@ -0,0 +1,310 @@
+//! Optional at-rest encryption of recordings, the event journal and reports.
+//!
+//! With `ENCRYPTION_KEY` (a base64 32-byte key) or `ENCRYPTION_KEY_FILE`
+//! (a file holding one) set, new recordings seal each chunk and reports are
+//! written as sealed files, all with AES-256-GCM. Every sealed blob is a
+//! random 12-byte nonce followed by the ciphertext and tag. Reports that
+//! grow over the day are sealed in chunks, one per append, so extending
+//! one never rewrites what it already holds.
+//!
+//! Readers open sealed data with the same key and pass plaintext files
+//! through, so mixed directories stay readable. `white-shark decrypt` turns
+//! a sealed report back into plain text. Offline tools that never call
+//! [`init`] read the same env vars lazily.
+
+use std::fmt;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use rand::RngCore;
+use tracing::warn;
+
+use crate::error::{Error, Result};
+
+/// Leading bytes of a sealed report file, followed by one sealed blob.
+pub const FILE_MAGIC: &[u8; 8] = b"WSENC\x00\x00\x01";
+/// Leading bytes of an appendable sealed report, followed by chunks sealed
+/// on their own, each framed by its length before and after.
+pub const CHUNKED_FILE_MAGIC: &[u8; 8] = b"WSENC\x00\x00\x02";
+/// Bytes of a chunk length
+const CHUNK_LEN_SIZE: usize = 4;
+
+pub const KEY_LEN: usize = 32;
+pub const NONCE_LEN: usize = 12;
+/// GCM tag appended to every ciphertext
+pub const TAG_LEN: usize = 16;
+/// Bytes [`EncryptionKey::seal`] adds to its input
+pub const SEAL_OVERHEAD: usize = NONCE_LEN + TAG_LEN;
+
+static KEY: OnceLock<Option<EncryptionKey>> = OnceLock::new();
+
+/// An AES-256-GCM key; never printed.
+#[derive(Clone)]
+pub struct EncryptionKey {
+    cipher: Aes256Gcm,
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+impl EncryptionKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let cipher = Aes256Gcm::new_from_slice(bytes).map_err(|_| {
+            Error::Config(format!(
+                "Encryption key must be {} bytes, got {}",
+                KEY_LEN,
+                bytes.len()
+            ))
+        })?;
+        Ok(Self { cipher })
+    }
+
+    /// A key from standard base64, surrounding whitespace ignored.
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|_| Error::Config("Invalid ENCRYPTION_KEY, expected base64".to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Reads `ENCRYPTION_KEY`, or else the key in `ENCRYPTION_KEY_FILE`.
+    /// `None` without either.
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Ok(v) = crate::config::var("ENCRYPTION_KEY") {
+            return Self::from_base64(&v).map(Some);
+        }
+        let Ok(path) = crate::config::var("ENCRYPTION_KEY_FILE") else {
+            return Ok(None);
+        };
+        let encoded = fs::read_to_string(&path)
+            .map_err(|e| Error::Config(format!("Invalid ENCRYPTION_KEY_FILE '{}': {}", path, e)))?;
+        Self::from_base64(&encoded).map(Some)
+    }
+
+    /// `plaintext` sealed under a fresh nonce, bound to `aad`.
+    pub fn seal(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+            .map_err(|_| Error::Other("Encryption failed".to_string()))?;
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// The plaintext of a blob from [`seal`](Self::seal); fails on a wrong
+    /// key, different `aad` or any tampering.
+    pub fn open(&self, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < SEAL_OVERHEAD {
+            return Err(Error::Other("Sealed data is truncated".to_string()));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+            .map_err(|_| Error::Other("Decryption failed: wrong key or corrupted data".to_string()))
+    }
+}
+
+/// Sets the key for the life of the process; later calls are ignored.
+pub fn init(key: Option<EncryptionKey>) {
+    let _ = KEY.set(key);
+}
+
+/// The configured key, if encryption is on.
+pub fn key() -> Option<&'static EncryptionKey> {
+    KEY.get_or_init(|| {
+        EncryptionKey::from_env().unwrap_or_else(|e| {
+            warn!("Encryption disabled: {}", e);
+            None
+        })
+    })
+    .as_ref()
+}
+
+pub fn enabled() -> bool {
+    key().is_some()
+}
+
+/// The configured key, or an error naming what needed it.
+pub fn require_key(what: &str) -> Result<&'static EncryptionKey> {
+    key().ok_or_else(|| Error::Config(format!("{} is encrypted but no ENCRYPTION_KEY is set", what)))
+}
+
+/// Whether `bytes` are a sealed report file, whole or chunked.
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.starts_with(FILE_MAGIC) || bytes.starts_with(CHUNKED_FILE_MAGIC)
+}
+
+/// The magic `path` starts with, if it is long enough to have one.
+fn magic_of(path: &Path) -> Result<Option<[u8; 8]>> {
+    let mut magic = [0u8; FILE_MAGIC.len()];
+    let mut file = fs::File::open(path)?;
+    Ok(file.read_exact(&mut magic).is_ok().then_some(magic))
+}
+
+/// Writes `contents` to `path`, sealed when a key is configured. The file
+/// is replaced in one step, so a crash leaves the old one or the new one.
+pub fn write_file(path: impl AsRef<Path>, contents: &[u8]) -> Result<()> {
+    let path = path.as_ref();
+    match key() {
+        Some(key) => {
+            let mut out = FILE_MAGIC.to_vec();
+            out.extend(key.seal(contents, FILE_MAGIC)?);
+            replace_file(path, &out)
+        }
+        None => replace_file(path, contents),
+    }
+}
+
+/// Writes `contents` next to `path` and renames it over `path`.
+fn replace_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Reads `path`, opening it if sealed and passing plain files through.
+pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<u8>> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)?;
+    if !is_sealed(&bytes) {
+        return Ok(bytes);
+    }
+    let key = require_key(&path.display().to_string())?;
+    if !bytes.starts_with(CHUNKED_FILE_MAGIC) {
+        return key.open(&bytes[FILE_MAGIC.len()..], FILE_MAGIC);
+    }
+
+    let mut plain = Vec::new();
+    let mut rest = &bytes[CHUNKED_FILE_MAGIC.len()..];
+    while !rest.is_empty() {
+        let Some(chunk) = next_chunk(rest) else {
+            warn!("Ignoring a torn chunk at the end of {}", path.display());
+            break;
+        };
+        plain.extend(key.open(chunk, CHUNKED_FILE_MAGIC)?);
+        rest = &rest[chunk.len() + 2 * CHUNK_LEN_SIZE..];
+    }
+    Ok(plain)
+}
+
+/// The sealed blob of the chunk `bytes` start with, `None` if it is cut
+/// short.
+fn next_chunk(bytes: &[u8]) -> Option<&[u8]> {
+    let len_bytes = bytes.get(..CHUNK_LEN_SIZE)?;
+    let len = u32::from_le_bytes(len_bytes.try_into().ok()?) as usize;
+    let chunk = bytes.get(CHUNK_LEN_SIZE..CHUNK_LEN_SIZE + len)?;
+    let trailer = bytes.get(CHUNK_LEN_SIZE + len..2 * CHUNK_LEN_SIZE + len)?;
+    (trailer == len_bytes).then_some(chunk)
+}
+
+/// `plaintext` sealed as one chunk of a chunked report.
+fn sealed_chunk(key: &EncryptionKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let blob = key.seal(plaintext, CHUNKED_FILE_MAGIC)?;
+    let len = (blob.len() as u32).to_le_bytes();
+    let mut out = Vec::with_capacity(blob.len() + 2 * CHUNK_LEN_SIZE);
+    out.extend_from_slice(&len);
+    out.extend(blob);
+    out.extend_from_slice(&len);
+    Ok(out)
+}
+
+/// Where the last whole chunk of a chunked report ends. Normally the end
+/// of the file, checked from the last chunk's lengths; after a crash tore
+/// the last chunk, the chunks are walked from the front.
+fn intact_end(file: &mut fs::File) -> Result<u64> {
+    let file_len = file.metadata()?.len();
+    let start = CHUNKED_FILE_MAGIC.len() as u64;
+    let frame = 2 * CHUNK_LEN_SIZE as u64;
+    if file_len == start {
+        return Ok(file_len);
+    }
+    if file_len >= start + frame {
+        let len = read_len_at(file, file_len - CHUNK_LEN_SIZE as u64)?;
+        let chunk_start = file_len.checked_sub(frame + len as u64).filter(|s| *s >= start);
+        if let Some(chunk_start) = chunk_start {
+            if read_len_at(file, chunk_start)? == len {
+                return Ok(file_len);
+            }
+        }
+    }
+
+    let mut end = start;
+    while end + frame <= file_len {
+        let len = read_len_at(file, end)?;
+        let next = end + frame + len as u64;
+        if next > file_len || read_len_at(file, next - CHUNK_LEN_SIZE as u64)? != len {
+            break;
+        }
+        end = next;
+    }
+    Ok(end)
+}
+
+fn read_len_at(file: &mut fs::File, offset: u64) -> Result<u32> {
+    let mut len = [0u8; CHUNK_LEN_SIZE];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut len)?;
+    Ok(u32::from_le_bytes(len))
+}
+
+/// Appends `rows` to the report at `path`, starting it with `header` if new.
+///
+/// Plain reports are appended in place. With a key, `rows` are sealed as a
+/// new chunk at the end of a chunked report, after cutting off a chunk a
+/// crash tore. A new report, or an existing plain or whole sealed one, is
+/// first written as a chunked report holding what it had.
+pub fn append_report(path: impl AsRef<Path>, header: &str, rows: &str) -> Result<()> {
+    let path = path.as_ref();
+    let exists = path.exists();
+    let magic = if exists { magic_of(path)? } else { None };
+    let Some(key) = key() else {
+        if magic.is_some_and(|m| is_sealed(&m)) {
+            require_key(&path.display().to_string())?;
+        }
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        let mut out = String::new();
+        if !exists {
+            out.push_str(header);
+        }
+        out.push_str(rows);
+        file.write_all(out.as_bytes())?;
+        return Ok(());
+    };
+
+    if magic.as_ref() == Some(CHUNKED_FILE_MAGIC) {
+        let mut file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+        let end = intact_end(&mut file)?;
+        if end < file.metadata()?.len() {
+            warn!("Cutting a torn chunk off the end of {}", path.display());
+            file.set_len(end)?;
+        }
+        file.seek(SeekFrom::Start(end))?;
+        file.write_all(&sealed_chunk(key, rows.as_bytes())?)?;
+        return Ok(());
+    }
+
+    let mut contents = if exists {
+        read_file(path)?
+    } else {
+        header.as_bytes().to_vec()
+    };
+    contents.extend_from_slice(rows.as_bytes());
+    let mut out = CHUNKED_FILE_MAGIC.to_vec();
+    out.extend(sealed_chunk(key, &contents)?);
+    replace_file(path, &out)
+}
@@ -1,5 +1,9 @@
+use std::fmt;
+
 use thiserror::Error;
 
+use crate::utils::websocket::CloseReason;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Error, Debug)]
@@ -13,6 +17,15 @@ pub enum Error {
     #[error("WebSocket error: {0}")]
     WebSocket(String),
 
+    #[error("WebSocket error: {0}")]
+    Tungstenite(#[source] Box<tokio_tungstenite::tungstenite::Error>),
+
+    #[error("{exchange} closed the connection: {reason}")]
+    Closed {
+        exchange: &'static str,
+        reason: CloseReason,
+    },
+
     #[error("Connection error: {0}")]
     Connection(String),
 
@@ -22,8 +35,14 @@ pub enum Error {
     #[error("HTTP error: {0}")]
     Http(String),
 
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("HTTP {status}: {body}")]
+    HttpStatus { status: u16, body: String },
+
     #[error("TLS error: {0}")]
-    Tls(String),
+    Tls(#[from] native_tls::Error),
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -43,19 +62,164 @@ pub enum Error {
     #[error("Database error: {0}")]
     Database(String),
 
+    #[cfg(feature = "db")]
+    #[error("Database error: {0}")]
+    Orm(#[source] Box<sea_orm::DbErr>),
+
+    #[cfg(feature = "duckdb")]
+    #[error("DuckDB error: {0}")]
+    DuckDb(#[source] Box<duckdb::Error>),
+
+    #[error("Sink error: {0}")]
+    Sink(String),
+
+    #[error("Recording error: {0}")]
+    Recording(String),
+
+    #[cfg(feature = "archive")]
+    #[error("Object store error: {0}")]
+    ObjectStore(#[source] Box<object_store::Error>),
+
+    #[error("Schema error: {0}")]
+    Schema(String),
+
+    #[error("{context}")]
+    Context {
+        context: ErrorContext,
+        #[source]
+        source: Box<Error>,
+    },
+
     #[error("{0}")]
     Other(String),
 }
 
+// Boxed so every `Result` stays small; these sources run past 100 bytes.
 impl From<tokio_tungstenite::tungstenite::Error> for Error {
     fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
-        Error::WebSocket(e.to_string())
+        Error::Tungstenite(Box::new(e))
+    }
+}
+
+#[cfg(feature = "db")]
+impl From<sea_orm::DbErr> for Error {
+    fn from(e: sea_orm::DbErr) -> Self {
+        Error::Orm(Box::new(e))
     }
 }
 
-impl From<native_tls::Error> for Error {
-    fn from(e: native_tls::Error) -> Self {
-        Error::Tls(e.to_string())
+#[cfg(feature = "duckdb")]
+impl From<duckdb::Error> for Error {
+    fn from(e: duckdb::Error) -> Self {
+        Error::DuckDb(Box::new(e))
     }
 }
 
+#[cfg(feature = "archive")]
+impl From<object_store::Error> for Error {
+    fn from(e: object_store::Error) -> Self {
+        Error::ObjectStore(Box::new(e))
+    }
+}
+
+impl Error {
+    /// The innermost error, skipping any context layers.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    /// Displays each context layer down to the root error, `outer: inner`.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain(self)
+    }
+}
+
+/// See [`Error::chain`].
+pub struct Chain<'a>(&'a Error);
+
+impl fmt::Display for Chain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut error = self.0;
+        while let Error::Context { context, source } = error {
+            write!(f, "{}: ", context)?;
+            error = source;
+        }
+        write!(f, "{}", error)
+    }
+}
+
+/// What was being attempted when an error occurred, e.g.
+/// `fetch markets (exchange=kalshi, endpoint=GET /trade-api/v2/markets, symbol=KXBTC15M)`.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorContext {
+    pub operation: String,
+    pub exchange: Option<&'static str>,
+    pub endpoint: Option<String>,
+    pub symbol: Option<String>,
+}
+
+impl ErrorContext {
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_exchange(mut self, exchange: &'static str) -> Self {
+        self.exchange = Some(exchange);
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = Some(symbol.into());
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operation)?;
+        let mut fields = Vec::new();
+        if let Some(exchange) = self.exchange {
+            fields.push(format!("exchange={}", exchange));
+        }
+        if let Some(endpoint) = &self.endpoint {
+            fields.push(format!("endpoint={}", endpoint));
+        }
+        if let Some(symbol) = &self.symbol {
+            fields.push(format!("symbol={}", symbol));
+        }
+        if !fields.is_empty() {
+            write!(f, " ({})", fields.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Attaches an [`ErrorContext`] to any error convertible into [`Error`].
+pub trait ResultExt<T> {
+    fn with_context<F>(self, context: F) -> Result<T>
+    where
+        F: FnOnce() -> ErrorContext;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn with_context<F>(self, context: F) -> Result<T>
+    where
+        F: FnOnce() -> ErrorContext,
+    {
+        self.map_err(|e| Error::Context {
+            context: context(),
+            source: Box::new(e.into()),
+        })
+    }
+}
@@ -0,0 +1,293 @@
+//! Tracks subscribe/unsubscribe requests until the exchange acknowledges
+//! them.
+//!
+//! Both venues echo the request id in their reply, so every request sent is
+//! held by id until the reply arrives. One left unanswered for
+//! [`ACK_TIMEOUT`] is resent under a new id, and after [`MAX_ATTEMPTS`] the
+//! caller escalates by dropping the connection, whose reconnect subscribes
+//! everything afresh.
+//!
+//! Topics are `channel:ticker` on Kalshi and stream names on Binance. The
+//! tracker keeps what we asked for next to what the exchange confirmed and
+//! publishes the difference to the [status board](crate::status).
+
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+
+use crate::metrics::{self, names};
+use crate::status;
+
+/// How long a request may go unacknowledged before it is resent.
+pub const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Sends of one request, the first included, before escalating.
+pub const MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    Subscribe,
+    Unsubscribe,
+}
+
+impl RequestKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestKind::Subscribe => "subscribe",
+            RequestKind::Unsubscribe => "unsubscribe",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingRequest {
+    pub id: u64,
+    pub kind: RequestKind,
+    pub topics: Vec<String>,
+    /// The request as sent, resent with a fresh `id` on retry
+    pub payload: serde_json::Value,
+    pub attempts: u32,
+    sent_at: Instant,
+}
+
+impl PendingRequest {
+    /// The payload to resend under `id`.
+    pub fn retry_payload(&self, id: u64) -> serde_json::Value {
+        let mut payload = self.payload.clone();
+        payload["id"] = id.into();
+        payload
+    }
+}
+
+/// What to do with a request that timed out.
+#[derive(Debug)]
+pub enum Expired {
+    Retry(PendingRequest),
+    /// Out of attempts; the connection can no longer be trusted
+    Escalate(PendingRequest),
+}
+
+pub struct SubscriptionTracker {
+    exchange: &'static str,
+    pending: HashMap<u64, PendingRequest>,
+    intended: BTreeSet<String>,
+    confirmed: BTreeSet<String>,
+}
+
+impl SubscriptionTracker {
+    pub fn new(exchange: &'static str) -> Self {
+        Self {
+            exchange,
+            pending: HashMap::new(),
+            intended: BTreeSet::new(),
+            confirmed: BTreeSet::new(),
+        }
+    }
+
+    /// Records a request just sent for the first time.
+    pub fn sent(&mut self, id: u64, kind: RequestKind, topics: Vec<String>, payload: serde_json::Value) {
+        match kind {
+            RequestKind::Subscribe => self.intended.extend(topics.iter().cloned()),
+            RequestKind::Unsubscribe => {
+                for topic in &topics {
+                    self.intended.remove(topic);
+                }
+            }
+        }
+        self.insert(id, kind, topics, payload, 1);
+    }
+
+    /// Records `request` resent under `id`.
+    pub fn resent(&mut self, id: u64, request: PendingRequest) {
+        let payload = request.retry_payload(id);
+        self.insert(id, request.kind, request.topics, payload, request.attempts + 1);
+    }
+
+    fn insert(&mut self, id: u64, kind: RequestKind, topics: Vec<String>, payload: serde_json::Value, attempts: u32) {
+        self.pending.insert(
+            id,
+            PendingRequest {
+                id,
+                kind,
+                topics,
+                payload,
+                attempts,
+                sent_at: Instant::now(),
+            },
+        );
+        self.publish();
+    }
+
+    /// Marks topics subscribed without a request, e.g. streams named in the
+    /// connection URL.
+    pub fn confirm(&mut self, topics: &[String]) {
+        self.intended.extend(topics.iter().cloned());
+        self.confirmed.extend(topics.iter().cloned());
+        self.publish();
+    }
+
+    /// Resolves request `id` as accepted. `None` if it is not pending, e.g.
+    /// a second reply to the same request.
+    pub fn acknowledged(&mut self, id: u64) -> Option<PendingRequest> {
+        let request = self.pending.remove(&id)?;
+        match request.kind {
+            RequestKind::Subscribe => self.confirmed.extend(request.topics.iter().cloned()),
+            RequestKind::Unsubscribe => {
+                for topic in &request.topics {
+                    self.confirmed.remove(topic);
+                }
+            }
+        }
+        self.count("acked");
+        self.publish();
+        Some(request)
+    }
+
+    /// Resolves request `id` as refused. Its topics stay intended, so a
+    /// refused subscribe shows up as missing.
+    pub fn rejected(&mut self, id: u64, reason: &str) -> Option<PendingRequest> {
+        let request = self.pending.remove(&id)?;
+        warn!(
+            "{} rejected {} {} for {:?}: {}",
+            self.exchange,
+            request.kind.as_str(),
+            id,
+            request.topics,
+            reason
+        );
+        self.count("rejected");
+        self.publish();
+        Some(request)
+    }
+
+    /// Removes and returns requests unanswered for [`ACK_TIMEOUT`].
+    pub fn expired(&mut self) -> Vec<Expired> {
+        let ids: Vec<u64> = self
+            .pending
+            .values()
+            .filter(|r| r.sent_at.elapsed() >= ACK_TIMEOUT)
+            .map(|r| r.id)
+            .collect();
+        let mut expired = Vec::with_capacity(ids.len());
+        for id in ids {
+            let Some(request) = self.pending.remove(&id) else {
+                continue;
+            };
+            // Unsubscribed since; resending would bring it back.
+            if request.kind == RequestKind::Subscribe
+                && !request.topics.iter().any(|t| self.intended.contains(t))
+            {
+                continue;
+            }
+            if request.attempts < MAX_ATTEMPTS {
+                warn!(
+                    "⏱️ {} {} {} unacknowledged after {}s, retrying",
+                    self.exchange,
+                    request.kind.as_str(),
+                    id,
+                    ACK_TIMEOUT.as_secs()
+                );
+                self.count("retried");
+                expired.push(Expired::Retry(request));
+            } else {
+                error!(
+                    exchange = self.exchange,
+                    "🚨 {} {} for {:?} unacknowledged after {} attempts",
+                    self.exchange,
+                    request.kind.as_str(),
+                    request.topics,
+                    request.attempts
+                );
+                self.count("escalated");
+                expired.push(Expired::Escalate(request));
+            }
+        }
+        if !expired.is_empty() {
+            self.publish();
+        }
+        expired
+    }
+
+    /// Replaces the confirmed topics with the list the exchange reports.
+    pub fn set_confirmed(&mut self, topics: impl IntoIterator<Item = String>) {
+        self.confirmed = topics.into_iter().collect();
+        self.publish();
+    }
+
+    /// Whether a request for `topic` is awaiting its acknowledgment.
+    pub fn is_pending(&self, topic: &str) -> bool {
+        self.pending.values().any(|r| r.topics.iter().any(|t| t == topic))
+    }
+
+    /// Topics subscribed or being subscribed to.
+    pub fn intended(&self) -> impl Iterator<Item = &str> {
+        self.intended.iter().map(String::as_str)
+    }
+
+    /// Forgets everything; subscriptions do not survive a reconnect.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.intended.clear();
+        self.confirmed.clear();
+        self.publish();
+    }
+
+    fn count(&self, outcome: &str) {
+        metrics::inc(
+            names::SUBSCRIPTION_REQUESTS_TOTAL,
+            &[("exchange", self.exchange), ("outcome", outcome)],
+        );
+    }
+
+    pub fn view(&self) -> SubscriptionView {
+        let mut pending: Vec<PendingView> = self
+            .pending
+            .values()
+            .map(|r| PendingView {
+                id: r.id,
+                kind: r.kind,
+                topics: r.topics.clone(),
+                attempts: r.attempts,
+                age_secs: r.sent_at.elapsed().as_secs_f64(),
+            })
+            .collect();
+        pending.sort_by_key(|p| p.id);
+        SubscriptionView {
+            exchange: self.exchange.to_string(),
+            intended: self.intended.len(),
+            confirmed: self.confirmed.len(),
+            missing: self.intended.difference(&self.confirmed).cloned().collect(),
+            unexpected: self.confirmed.difference(&self.intended).cloned().collect(),
+            pending,
+        }
+    }
+
+    fn publish(&self) {
+        status::board().set_subscriptions(self.view());
+    }
+}
+
+/// Intended versus confirmed subscriptions of one exchange.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionView {
+    pub exchange: String,
+    pub intended: usize,
+    pub confirmed: usize,
+    /// Intended but not confirmed, including those still pending
+    pub missing: Vec<String>,
+    /// Confirmed but no longer intended, i.e. an unsubscribe in flight or lost
+    pub unexpected: Vec<String>,
+    pub pending: Vec<PendingView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingView {
+    pub id: u64,
+    pub kind: RequestKind,
+    pub topics: Vec<String>,
+    pub attempts: u32,
+    /// Since the latest send
+    pub age_secs: f64,
+}
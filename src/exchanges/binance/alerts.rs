@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use super::sbe::events::depth::ImbalanceAlert;
+use crate::config::Persistence;
+use crate::db::main::{Db, ImbalanceAlertRecord};
+use crate::db::sampler::Sampler;
+use crate::instruments;
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::shutdown;
+use crate::state::KalshiState;
+use crate::status;
+
+const ALERT_CHANNEL_BUFFER: usize = 1024;
+
+/// Stores imbalance alerts in `imbalance_alerts`, together with the top of
+/// book of the Kalshi market mapped to the alerting symbol at the time.
+/// Like other persistence, leader only.
+pub struct ImbalanceAlertWriter;
+
+impl ImbalanceAlertWriter {
+    pub fn spawn(
+        db: Arc<Db>,
+        kalshi: Option<Arc<KalshiState>>,
+        persistence: Persistence,
+        leader: LeaderHandle,
+    ) -> mpsc::Sender<ImbalanceAlert> {
+        let (tx, rx) = mpsc::channel::<ImbalanceAlert>(ALERT_CHANNEL_BUFFER);
+        status::board().register_queue("imbalance_alerts", &tx);
+        let sampler = Sampler::new("imbalance_alerts", persistence);
+        shutdown::coordinator().track(
+            "imbalance alert writer",
+            tokio::spawn(Self::run(db, kalshi, sampler, leader, rx)),
+        );
+        tx
+    }
+
+    async fn run(
+        db: Arc<Db>,
+        kalshi: Option<Arc<KalshiState>>,
+        mut sampler: Sampler,
+        leader: LeaderHandle,
+        mut rx: mpsc::Receiver<ImbalanceAlert>,
+    ) {
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+        loop {
+            let alert = tokio::select! {
+                alert = rx.recv() => alert,
+                _ = shutdown::requested(&mut shutdown_rx) => {
+                    rx.close();
+                    if leader.is_leader() {
+                        while let Some(alert) = rx.recv().await {
+                            Self::store(&db, kalshi.as_deref(), &alert).await;
+                        }
+                    }
+                    None
+                }
+            };
+            match alert {
+                Some(_) if !leader.is_leader() => {}
+                Some(alert) if !sampler.admit(&alert.symbol, alert.timestamp) => {}
+                Some(alert) => Self::store(&db, kalshi.as_deref(), &alert).await,
+                None => break,
+            }
+        }
+        info!("Imbalance alert writer shutting down");
+    }
+
+    async fn store(db: &Db, kalshi: Option<&KalshiState>, alert: &ImbalanceAlert) {
+        let record = Self::record(alert, kalshi);
+        match db.insert_imbalance_alert(&record).await {
+            Ok(()) => metrics::inc(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "imbalance_alerts")]),
+            Err(e) => {
+                error!("Failed to store imbalance alert {}: {}", alert.event_id, e);
+                metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", "imbalance_alerts")]);
+                notify::publish(Notification::DbFailure {
+                    table: "imbalance_alerts".to_string(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    fn record(alert: &ImbalanceAlert, kalshi: Option<&KalshiState>) -> ImbalanceAlertRecord {
+        let kalshi_ticker = kalshi.and_then(|state| {
+            let series = instruments::registry().kalshi_series(&alert.symbol)?;
+            state.current_market_for_series(&series)
+        });
+        let (kalshi_yes_bid, kalshi_yes_ask) = match (kalshi, &kalshi_ticker) {
+            (Some(state), Some(ticker)) => (state.get_top_bid(ticker), state.get_top_ask(ticker)),
+            _ => (None, None),
+        };
+
+        ImbalanceAlertRecord {
+            event_id: alert.event_id.to_string(),
+            timestamp: alert.timestamp,
+            symbol: alert.symbol.clone(),
+            depth: alert.depth.to_string(),
+            ratio: alert.ratio,
+            bid_qty: alert.bid_qty,
+            ask_qty: alert.ask_qty,
+            kalshi_ticker,
+            kalshi_yes_bid,
+            kalshi_yes_ask,
+        }
+    }
+}
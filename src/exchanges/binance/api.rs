@@ -0,0 +1,80 @@
+use chrono::Utc;
+use reqwest::Client as HttpClient;
+use serde::Deserialize;
+
+use crate::constants::{BINANCE_FUTURES_REST_URL, BINANCE_REST_URL};
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::PriceUpdate;
+use crate::trader::venue::BinanceMarket;
+use crate::utils::http::ensure_success;
+use crate::utils::numeric;
+
+/// `GET /api/v3/ticker/bookTicker`. Prices and quantities are decimal strings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTicker {
+    pub symbol: String,
+    pub bid_price: String,
+    pub bid_qty: String,
+    pub ask_price: String,
+    pub ask_qty: String,
+}
+
+pub struct BinanceApi {
+    http: HttpClient,
+    market: BinanceMarket,
+}
+
+impl BinanceApi {
+    pub fn new() -> Self {
+        Self::for_market(BinanceMarket::Spot)
+    }
+
+    pub fn for_market(market: BinanceMarket) -> Self {
+        Self {
+            http: HttpClient::new(),
+            market,
+        }
+    }
+
+    fn context(operation: &str, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_exchange("binance")
+            .with_endpoint(format!("GET {}", path))
+    }
+
+    pub async fn fetch_book_ticker(&self, symbol: &str) -> Result<PriceUpdate> {
+        let (base_url, url_path) = match self.market {
+            BinanceMarket::Spot => (BINANCE_REST_URL, "/api/v3/ticker/bookTicker"),
+            BinanceMarket::Futures => (BINANCE_FUTURES_REST_URL, "/fapi/v1/ticker/bookTicker"),
+        };
+        let url = format!("{}{}?symbol={}", base_url, url_path, symbol.to_ascii_uppercase());
+        let context = || Self::context("fetch book ticker", url_path).with_symbol(symbol);
+
+        let resp = self.http.get(&url).send().await.with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+        let ticker: BookTicker = resp.json().await.with_context(context)?;
+
+        let parse = |field: &str, value: &str| {
+            numeric::parse_decimal(value)
+                .ok_or_else(|| Error::Http(format!("Invalid {} '{}'", field, value)))
+                .with_context(context)
+        };
+
+        Ok(PriceUpdate {
+            exchange: "binance".to_string(),
+            symbol: ticker.symbol.clone(),
+            timestamp: Utc::now(),
+            bid: Some(parse("bidPrice", &ticker.bid_price)?),
+            ask: Some(parse("askPrice", &ticker.ask_price)?),
+            last_price: None,
+            volume_24h: None,
+        })
+    }
+}
+
+impl Default for BinanceApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
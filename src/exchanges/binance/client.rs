@@ -1,67 +1,249 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
 use futures_util::{SinkExt, StreamExt};
 use tokio::net::TcpStream;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_native_tls::TlsConnector;
 use tokio_tungstenite::tungstenite::handshake::client::generate_key;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{client_async, WebSocketStream};
 use tracing::{debug, error, info, warn};
 
-use super::sbe::{decoder::SbeDecoder, messages::SbeMessage, url::build_sbe_combined_url};
-use crate::config::BinanceConfig;
-use crate::error::{Error, Result};
+use super::api::BinanceApi;
+use super::futures::{self, FuturesFrame};
+use super::orderbook::{DepthDiff, DiffOutcome, LocalOrderBook, SNAPSHOT_DEPTH};
+use super::rest::BinanceRest;
+use super::sbe::{
+    decoder::{DecoderStats, SbeDecoder},
+    messages::SbeMessage,
+    url::build_sbe_combined_url,
+};
+use super::iceberg::IcebergDetector;
+use super::velocity::DepthVelocity;
+use crate::config::{BinanceConfig, ChaosConfig};
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::acks::{Expired, RequestKind, SubscriptionTracker, SubscriptionView, ACK_TIMEOUT};
+use crate::exchanges::dedup::SequenceFilter;
+use crate::exchanges::event::MarketEvent;
 use crate::exchanges::PriceUpdate;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::recorder::{FrameSource, RecorderHandle};
+use crate::reload::next_update;
+use crate::shutdown;
+use crate::state::{self, BinanceState};
+use crate::trader::venue::BinanceMarket;
+use crate::utils::chaos::{Fault, FaultInjector};
+use crate::utils::websocket::{CloseReason, ReconnectPolicy, ReconnectStrategy};
 use http::Request;
 
 type WsStream = WebSocketStream<tokio_native_tls::TlsStream<tokio::net::TcpStream>>;
 
+/// How often the live stream list is fetched and reconciled.
+const SUBSCRIPTION_AUDIT_INTERVAL: Duration = Duration::from_secs(60);
+
 pub struct BinanceClient {
     config: BinanceConfig,
     stream: Option<WsStream>,
     sbe_decoder: SbeDecoder,
     recv_buf: Vec<u8>,
+    recorder: Option<RecorderHandle>,
+    chaos_config: Option<ChaosConfig>,
+    chaos: Option<FaultInjector>,
+    trade_filter: SequenceFilter<String>,
+    best_bid_ask_filter: SequenceFilter<String>,
+    depth_filter: SequenceFilter<String>,
+    depth_diff_filter: SequenceFilter<String>,
+    api: BinanceApi,
+    rest: BinanceRest,
+    state: Arc<BinanceState>,
+    /// Latest config, for the imbalance detectors reading its books
+    config_tx: watch::Sender<BinanceConfig>,
+    velocity: DepthVelocity,
+    icebergs: IcebergDetector,
+    /// Symbols streamed on the current connection, kept across reconnects
+    subscribed: Vec<String>,
+    reload_rx: Option<mpsc::Receiver<BinanceConfig>>,
+    request_id: u64,
+    acks: SubscriptionTracker,
+    /// Id of the LIST_SUBSCRIPTIONS request awaiting its reply
+    audit_request: Option<u64>,
+}
+
+/// Reply to a SUBSCRIBE/UNSUBSCRIBE request: `{"result":null,"id":1}`, or
+/// `{"error":{"code":2,"msg":"..."},"id":1}` when refused.
+#[derive(Debug, serde::Deserialize)]
+struct StreamReply {
+    id: Option<u64>,
+    error: Option<serde_json::Value>,
+    /// Stream names, on a LIST_SUBSCRIPTIONS reply
+    result: Option<serde_json::Value>,
 }
 
 impl BinanceClient {
     pub fn new(config: BinanceConfig) -> Self {
+        let velocity = DepthVelocity::new(config.velocity);
+        let icebergs = IcebergDetector::new(config.iceberg);
+        let api = BinanceApi::for_market(config.market);
+        let mut rest = BinanceRest::new(config.market).with_api_key(config.api_key.clone());
+        if let Some(path) = &config.private_key_path {
+            rest = match rest.with_private_key(path) {
+                Ok(rest) => rest,
+                Err(e) => {
+                    warn!("Binance REST requests will be unsigned: {}", e);
+                    BinanceRest::new(config.market).with_api_key(config.api_key.clone())
+                }
+            };
+        }
+        let config_tx = watch::channel(config.clone()).0;
         Self {
             config,
             stream: None,
             sbe_decoder: SbeDecoder::new(),
             recv_buf: Vec::new(),
+            recorder: None,
+            chaos_config: None,
+            chaos: None,
+            trade_filter: SequenceFilter::new("binance", "trade"),
+            best_bid_ask_filter: SequenceFilter::new("binance", "best_bid_ask"),
+            depth_filter: SequenceFilter::new("binance", "depth_snapshot"),
+            depth_diff_filter: SequenceFilter::new("binance", "depth_diff"),
+            api,
+            rest,
+            state: Arc::new(BinanceState::new()),
+            config_tx,
+            velocity,
+            icebergs,
+            subscribed: Vec::new(),
+            reload_rx: None,
+            request_id: 0,
+            acks: SubscriptionTracker::new("binance"),
+            audit_request: None,
         }
     }
 
-    fn ws_url(&self, symbols: &[String]) -> String {
-        let mut streams = Vec::with_capacity(symbols.len() * 3);
+    pub fn with_recorder(mut self, recorder: Option<RecorderHandle>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    pub fn with_chaos(mut self, chaos: Option<ChaosConfig>) -> Self {
+        self.chaos_config = chaos;
+        self
+    }
+
+    /// The config in effect, updated on reloads, for an
+    /// [`ImbalanceDetector`](crate::exchanges::depth::ImbalanceDetector)
+    /// fed this client's books.
+    pub fn config_watch(&self) -> watch::Receiver<BinanceConfig> {
+        self.config_tx.subscribe()
+    }
+
+    /// Channel for config reloads, applied between frames of the running
+    /// connection.
+    pub fn reload_handle(&mut self) -> mpsc::Sender<BinanceConfig> {
+        let (tx, rx) = mpsc::channel(1);
+        self.reload_rx = Some(rx);
+        tx
+    }
+
+    pub fn state(&self) -> &BinanceState {
+        &self.state
+    }
+
+    pub fn shared_state(&self) -> Arc<BinanceState> {
+        self.state.clone()
+    }
+
+    pub fn subscriptions(&self) -> SubscriptionView {
+        self.acks.view()
+    }
+
+    /// Schema version seen on the wire and how many frames carried fields
+    /// newer than this decoder knows.
+    pub fn decoder_stats(&self) -> DecoderStats {
+        self.sbe_decoder.stats()
+    }
+
+    /// Seeds the best bid/ask for every symbol over REST so consumers have a
+    /// price before the first stream message arrives, and backfills candles
+    /// when `BINANCE_KLINE_INTERVAL` is set.
+    async fn warm_start(&self, symbols: &[String]) {
         for symbol in symbols {
-            let symbol_lower = symbol.to_ascii_lowercase();
-            streams.push(format!("{}@trade", symbol_lower));
-            streams.push(format!("{}@bestBidAsk", symbol_lower));
-            streams.push(format!("{}@depth{}", symbol_lower, 20));
+            match self.api.fetch_book_ticker(symbol).await {
+                Ok(price) => {
+                    info!("🌡️ Warm start {}: bid={:?}, ask={:?}", price.symbol, price.bid, price.ask);
+                    self.state.best_bid_ask.insert(price.symbol.clone(), price);
+                }
+                Err(e) => warn!("Warm start failed for {}: {}", symbol, e),
+            }
+
+            let Some(backfill) = &self.config.kline_backfill else {
+                continue;
+            };
+            match self.rest.klines(symbol, &backfill.interval, None, None, backfill.limit).await {
+                Ok(klines) => {
+                    info!("🕯️ Backfilled {} {} klines for {}", klines.len(), backfill.interval, symbol);
+                    self.state.klines.insert(symbol.to_ascii_uppercase(), klines);
+                }
+                Err(e) => warn!("Kline backfill failed for {}: {}", symbol, e),
+            }
         }
+    }
 
-        build_sbe_combined_url(&streams)
+    fn streams_for(config: &BinanceConfig, symbol: &str) -> Vec<String> {
+        if config.market == BinanceMarket::Futures {
+            let mut streams = futures::streams_for(symbol).to_vec();
+            if config.local_book {
+                streams.push(futures::diff_stream_for(symbol));
+            }
+            return streams;
+        }
+        let symbol_lower = symbol.to_ascii_lowercase();
+        // A local book is kept from diffs instead of top-20 snapshots.
+        let depth = if config.local_book {
+            format!("{}@depth", symbol_lower)
+        } else {
+            format!("{}@depth{}", symbol_lower, 20)
+        };
+        vec![
+            format!("{}@trade", symbol_lower),
+            format!("{}@bestBidAsk", symbol_lower),
+            depth,
+        ]
+    }
+
+    fn ws_url(&self, symbols: &[String]) -> String {
+        let market = self.config.market;
+        let streams: Vec<String> = symbols.iter().flat_map(|s| Self::streams_for(&self.config, s)).collect();
+        match market {
+            BinanceMarket::Spot => build_sbe_combined_url(&streams),
+            BinanceMarket::Futures => futures::combined_url(&streams),
+        }
     }
 
     pub async fn connect(&mut self, symbols: &[String]) -> Result<()> {
         let url_str = self.ws_url(symbols);
         info!("Connecting to Binance WebSocket: {}", url_str);
 
-        let url = url::Url::parse(&url_str)
-            .map_err(|e| Error::WebSocket(format!("Invalid URL: {}", e)))?;
-
-        let api_key = self
-            .config
-            .api_key
-            .as_ref()
-            .ok_or_else(|| {
-                Error::WebSocket(
-                    "BINANCE_API_KEY is required for SBE connections. \
-                    Please set it in your environment variables."
-                        .into(),
-                )
-            })?;
+        let context = |operation: &str| {
+            ErrorContext::new(operation)
+                .with_exchange("binance")
+                .with_endpoint(url_str.clone())
+        };
+
+        let url = url::Url::parse(&url_str).with_context(|| context("parse stream URL"))?;
+
+        let api_key = self.config.api_key.as_ref();
+        if api_key.is_none() && self.config.market == BinanceMarket::Spot {
+            return Err(Error::WebSocket(
+                "BINANCE_API_KEY is required for SBE connections. \
+                Please set it in your environment variables."
+                    .into(),
+            ));
+        }
 
         let host = url
             .host_str()
@@ -70,29 +252,30 @@ impl BinanceClient {
         let port = url.port_or_known_default().unwrap_or(443);
 
         let ws_key = generate_key();
-        let request = Request::builder()
+        let mut request = Request::builder()
             .uri(&url_str)
             .header("Host", host)
             .header("Upgrade", "websocket")
             .header("Connection", "Upgrade")
             .header("Sec-WebSocket-Key", &ws_key)
-            .header("Sec-WebSocket-Version", "13")
-            .header("X-MBX-APIKEY", api_key)
+            .header("Sec-WebSocket-Version", "13");
+        if let Some(api_key) = api_key {
+            request = request.header("X-MBX-APIKEY", api_key);
+        }
+        let request = request
             .body(())
             .map_err(|e| Error::WebSocket(format!("Failed to build request: {}", e)))?;
 
         let tcp_stream = TcpStream::connect(format!("{}:{}", host, port))
             .await
-            .map_err(|e| Error::WebSocket(format!("TCP connection failed: {}", e)))?;
+            .with_context(|| context("open TCP connection"))?;
 
-        let tls_connector = native_tls::TlsConnector::builder()
-            .build()
-            .map_err(|e| Error::WebSocket(format!("TLS connector failed: {}", e)))?;
+        let tls_connector = native_tls::TlsConnector::builder().build()?;
         let tls_connector = TlsConnector::from(tls_connector);
         let tls_stream = tls_connector
             .connect(host, tcp_stream)
             .await
-            .map_err(|e| Error::WebSocket(format!("TLS connection failed: {}", e)))?;
+            .with_context(|| context("TLS handshake"))?;
 
         let (stream, _) = client_async(request, tls_stream).await.map_err(|e| {
             let error_msg = match &e {
@@ -129,6 +312,20 @@ impl BinanceClient {
         })?;
 
         self.stream = Some(stream);
+        // Streams named in the URL are live once the handshake succeeds.
+        self.acks.reset();
+        self.audit_request = None;
+        self.acks.confirm(
+            &symbols
+                .iter()
+                .flat_map(|s| Self::streams_for(&self.config, s))
+                .collect::<Vec<_>>(),
+        );
+        self.chaos = self
+            .chaos_config
+            .clone()
+            .map(|c| FaultInjector::new(c, "binance"));
+        metrics::set(names::WS_CONNECTED, &[("exchange", "binance")], 1.0);
 
         info!("Connected to Binance WebSocket");
         Ok(())
@@ -137,11 +334,9 @@ impl BinanceClient {
     pub async fn disconnect(&mut self) -> Result<()> {
         if let Some(stream) = self.stream.take() {
             let mut stream = stream;
-            stream
-                .close(None)
-                .await
-                .map_err(|e| Error::WebSocket(e.to_string()))?;
+            stream.close(None).await?;
         }
+        metrics::set(names::WS_CONNECTED, &[("exchange", "binance")], 0.0);
         info!("Disconnected from Binance WebSocket");
         Ok(())
     }
@@ -154,19 +349,31 @@ impl BinanceClient {
         match &mut self.stream {
             Some(s) => match s.next().await {
                 Some(Ok(msg)) => Ok(Some(msg)),
-                Some(Err(e)) => Err(Error::WebSocket(e.to_string())),
+                Some(Err(e)) => Err(e.into()),
                 None => Ok(None),
             },
             None => Err(Error::WebSocket("Not connected".into())),
         }
     }
 
-    pub async fn recv_sbe<'a>(&'a mut self) -> Result<Option<SbeMessage<'a>>> {
+    /// Reads the next frame, leaving SBE payloads in `recv_buf`; true when
+    /// one arrived. Decoding is left to the caller so the message only
+    /// borrows the buffer, not the whole client.
+    pub async fn recv_frame(&mut self) -> Result<bool> {
         match self.recv_raw().await? {
-            Some(Message::Binary(data)) => {
+            Some(Message::Binary(mut data)) => {
+                if let Some(recorder) = &self.recorder {
+                    recorder.record(FrameSource::BinanceSbe, &data);
+                }
+                if let Some(chaos) = &mut self.chaos {
+                    if chaos.on_frame().await == Fault::Disconnect {
+                        self.stream = None;
+                        return Err(Error::WebSocket("Connection dropped by chaos layer".into()));
+                    }
+                    chaos.corrupt_sbe(&mut data);
+                }
                 self.recv_buf = data;
-                let msg = self.sbe_decoder.decode(&self.recv_buf)?;
-                Ok(Some(msg))
+                Ok(true)
             }
             Some(Message::Ping(data)) => {
                 debug!("Received ping, sending pong");
@@ -181,11 +388,11 @@ impl BinanceClient {
                         return Err(Error::WebSocket("Stream is None when trying to send pong".into()));
                     }
                 }
-                Ok(None)
+                Ok(false)
             }
             Some(Message::Pong(_)) => {
                 warn!("Received unsolicited pong");
-                Ok(None)
+                Ok(false)
             }
             Some(Message::Close(frame)) => {
                 if let Some(close_frame) = &frame {
@@ -194,15 +401,62 @@ impl BinanceClient {
                     info!("WebSocket closed by server");
                 }
                 self.stream = None;
-                Err(Error::WebSocket("WebSocket connection closed".into()))
+                let reason = CloseReason::from_frame(frame.as_ref());
+                metrics::inc(
+                    names::WS_CLOSES_TOTAL,
+                    &[("exchange", "binance"), ("reason", reason.as_str())],
+                );
+                Err(Error::Closed { exchange: "binance", reason })
+            }
+            Some(Message::Text(text))
+                if self.config.market == BinanceMarket::Futures && text.contains("\"data\"") =>
+            {
+                match futures::decode(&text)? {
+                    FuturesFrame::Sbe(data) => {
+                        if let Some(recorder) = &self.recorder {
+                            recorder.record(FrameSource::BinanceSbe, &data);
+                        }
+                        self.recv_buf = data;
+                        Ok(true)
+                    }
+                    FuturesFrame::Diff { symbol, diff } => {
+                        self.apply_depth_diff(&symbol, diff).await;
+                        Ok(false)
+                    }
+                }
             }
             Some(Message::Text(text)) => {
-                warn!("Received unexpected text message in SBE mode: {}", text);
-                Ok(None)
+                // Replies to SUBSCRIBE/UNSUBSCRIBE requests are the only other
+                // JSON expected.
+                match serde_json::from_str::<StreamReply>(&text) {
+                    Ok(StreamReply { id: Some(id), result: Some(result), error: None })
+                        if self.audit_request == Some(id) =>
+                    {
+                        self.audit_request = None;
+                        self.reconcile_streams(&result).await?;
+                    }
+                    Ok(StreamReply { id: Some(id), error: Some(error), .. }) => {
+                        if self.audit_request == Some(id) {
+                            self.audit_request = None;
+                            warn!("Binance refused LIST_SUBSCRIPTIONS: {}", error);
+                        } else {
+                            self.acks.rejected(id, &error.to_string());
+                        }
+                    }
+                    Ok(StreamReply { id: Some(id), error: None, .. }) => {
+                        debug!("Binance stream request reply: {}", text);
+                        self.acks.acknowledged(id);
+                    }
+                    Ok(StreamReply { id: None, error: Some(error), .. }) => {
+                        warn!("Binance rejected a stream request: {}", error);
+                    }
+                    _ => warn!("Unexpected Binance text frame: {}", text),
+                }
+                Ok(false)
             }
             Some(Message::Frame(_)) => {
                 debug!("Received raw frame (unexpected)");
-                Ok(None)
+                Ok(false)
             }
             None => {
                 warn!("WebSocket stream ended (received None)");
@@ -212,33 +466,396 @@ impl BinanceClient {
         }
     }
 
-    pub async fn run(&mut self, price_tx: mpsc::Sender<PriceUpdate>) -> Result<()> {
+    /// Applies a diff to the symbol's local book, syncing it from a REST
+    /// snapshot when it has none yet or fell out of sequence.
+    async fn apply_depth_diff(&self, symbol: &str, diff: DepthDiff) {
+        let outcome = self
+            .state
+            .books
+            .entry(symbol.to_string())
+            .or_insert_with(|| LocalOrderBook::new(symbol))
+            .apply_diff(diff);
+        if outcome == DiffOutcome::NeedsSnapshot {
+            self.sync_book(symbol).await;
+        }
+    }
+
+    /// Fetches a REST snapshot and syncs the symbol's local book from it.
+    async fn sync_book(&self, symbol: &str) {
+        let snapshot = self.rest.depth(symbol, SNAPSHOT_DEPTH).await;
+        let Some(mut book) = self.state.books.get_mut(symbol) else {
+            return;
+        };
+        match snapshot {
+            Ok(snapshot) => {
+                if !book.sync(&snapshot) {
+                    metrics::inc(
+                        names::BINANCE_BOOK_RESYNCS_TOTAL,
+                        &[("symbol", symbol), ("reason", "stale_snapshot")],
+                    );
+                }
+            }
+            Err(e) => {
+                warn!("Depth snapshot for {} failed: {}", symbol, e);
+                book.snapshot_failed();
+            }
+        }
+    }
+
+    pub async fn run(&mut self, event_tx: mpsc::Sender<MarketEvent>) -> Result<()> {
         info!("Starting Binance message loop");
+        let mut reload_rx = self.reload_rx.take();
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+        let mut ack_check = tokio::time::interval(ACK_TIMEOUT / 2);
+        let mut audit = tokio::time::interval(SUBSCRIPTION_AUDIT_INTERVAL);
+        // The first tick fires at once; streams named in the URL need no audit.
+        audit.tick().await;
 
-        let _ = price_tx;
-        loop {
-            match self.recv_sbe().await {
-                Ok(Some(msg)) => {
-                    msg.print_update();
+        let result = loop {
+            tokio::select! {
+                received = self.recv_frame() => match received {
+                    Ok(true) => match self.process_frame(&event_tx) {
+                        Ok(Some(symbol)) => self.sync_book(&symbol).await,
+                        Ok(None) => {}
+                        Err(e) => {
+                            error!("Error receiving SBE message: {}", e);
+                            break Err(e);
+                        }
+                    },
+                    Ok(false) => continue,
+                    Err(e) => {
+                        error!("Error receiving SBE message: {}", e);
+                        break Err(e);
+                    }
+                },
+                Some(config) = next_update(&mut reload_rx) => {
+                    self.apply_config(config).await;
                 }
-                Ok(None) => {
-                    continue;
+                _ = ack_check.tick() => {
+                    if let Err(e) = self.retry_expired().await {
+                        error!("{}, reconnecting", e);
+                        break Err(e);
+                    }
                 }
-                Err(e) => {
-                    error!("Error receiving SBE message: {}", e);
-                    return Err(e);
+                _ = audit.tick() => {
+                    if let Err(e) = self.request_stream_list().await {
+                        warn!("Failed to request Binance stream list: {}", e);
+                    }
+                }
+                _ = shutdown::requested(&mut shutdown_rx) => break Ok(()),
+            }
+        };
+        self.reload_rx = reload_rx;
+        result
+    }
+
+    /// Decodes the frame in `recv_buf` and hands it to state and the event
+    /// sinks, imbalance detection among them. Returns the symbol whose local book needs a REST
+    /// snapshot, which is fetched once the frame is no longer borrowed.
+    fn process_frame(&mut self, event_tx: &mpsc::Sender<MarketEvent>) -> Result<Option<String>> {
+        let msg = self
+            .sbe_decoder
+            .decode(&self.recv_buf)
+            .inspect_err(|_| state::session().record_decode_error("binance"))?;
+        if let Some(id) = msg.sequence_id() {
+            // Ids survive reconnects, so overlap replayed after a
+            // resubscribe is dropped here.
+            let filter = match msg {
+                SbeMessage::Trade(_) => &mut self.trade_filter,
+                SbeMessage::BestBidAsk(_) => &mut self.best_bid_ask_filter,
+                SbeMessage::DepthSnapshot(_) => &mut self.depth_filter,
+                SbeMessage::DepthDiff(_) => &mut self.depth_diff_filter,
+            };
+            if filter.is_duplicate(msg.symbol(), id) {
+                return Ok(None);
+            }
+        }
+
+        let now = chrono::Utc::now();
+        metrics::inc(
+            names::WS_MESSAGES_TOTAL,
+            &[("exchange", "binance"), ("type", msg.kind())],
+        );
+        state::session().record_message("binance", msg.kind());
+        metrics::set(
+            names::WS_LAST_MESSAGE_TIMESTAMP,
+            &[("exchange", "binance")],
+            now.timestamp() as f64,
+        );
+        let latency = (now - msg.timestamp()).num_microseconds().unwrap_or(0);
+        metrics::observe(
+            names::FEED_LATENCY_SECONDS,
+            &[("exchange", "binance")],
+            latency.max(0) as f64 / 1_000_000.0,
+        );
+        let icebergs = self.icebergs.observe(&msg);
+        msg.print_update();
+        if let SbeMessage::DepthSnapshot(e) = &msg {
+            self.velocity.observe(e);
+            self.state.icebergs.insert(e.symbol.to_string(), icebergs);
+            if let Ok(Some(imbalance)) = e.imbalance(&self.config.imbalance) {
+                self.state.imbalances.insert(e.symbol.to_string(), imbalance);
+            }
+        }
+        let mut needs_snapshot = None;
+        if let SbeMessage::DepthDiff(e) = &msg {
+            let mut book = self
+                .state
+                .books
+                .entry(e.symbol.to_string())
+                .or_insert_with(|| LocalOrderBook::new(e.symbol));
+            match book.apply_sbe_diff(e) {
+                DiffOutcome::Applied => {
+                    if let Some(imbalance) = book.imbalance(&self.config.imbalance) {
+                        self.state.imbalances.insert(e.symbol.to_string(), imbalance);
+                    }
                 }
+                DiffOutcome::Buffered => {}
+                DiffOutcome::NeedsSnapshot => needs_snapshot = Some(e.symbol.to_string()),
             }
         }
+        if let Some(event) = msg.to_event() {
+            if let Err(e) = event_tx.try_send(event) {
+                error!("Failed to queue Binance event: {}", e);
+            }
+        }
+        if let SbeMessage::BestBidAsk(e) = msg {
+            let price = PriceUpdate {
+                exchange: "binance".to_string(),
+                symbol: e.symbol.to_string(),
+                timestamp: e.event_time,
+                bid: Some(e.bid_price),
+                ask: Some(e.ask_price),
+                last_price: None,
+                volume_24h: None,
+            };
+            self.state.best_bid_ask.insert(price.symbol.clone(), price);
+        }
+        Ok(needs_snapshot)
     }
 
-    pub async fn start(&mut self, symbols: &[String], price_tx: mpsc::Sender<PriceUpdate>) -> Result<()> {
-        if !self.is_connected() {
-            self.connect(symbols).await?;
+    /// Applies a reloaded config: streams of added and removed symbols are
+    /// (un)subscribed on the open connection, thresholds take effect on the
+    /// next frame.
+    async fn apply_config(&mut self, config: BinanceConfig) {
+        let added: Vec<String> = config
+            .tracked_symbols
+            .iter()
+            .filter(|s| !self.subscribed.contains(s))
+            .cloned()
+            .collect();
+        let removed: Vec<String> = self
+            .subscribed
+            .iter()
+            .filter(|s| !config.tracked_symbols.contains(s))
+            .cloned()
+            .collect();
+
+        for (method, symbols) in [("UNSUBSCRIBE", &removed), ("SUBSCRIBE", &added)] {
+            if symbols.is_empty() {
+                continue;
+            }
+            if let Err(e) = self.send_stream_request(method, symbols).await {
+                error!("Failed to {} {:?}: {}", method.to_ascii_lowercase(), symbols, e);
+            }
+        }
+        for symbol in &removed {
+            self.state.best_bid_ask.remove(symbol);
+            self.state.icebergs.remove(symbol);
+            self.state.imbalances.remove(symbol);
+            self.state.klines.remove(symbol);
+            self.state.books.remove(symbol);
         }
-        
-        self.run(price_tx).await?;
+        self.warm_start(&added).await;
+
+        self.subscribed = config.tracked_symbols.clone();
+        self.velocity.set_config(config.velocity);
+        self.icebergs.set_config(config.iceberg);
+        self.config_tx.send_replace(config.clone());
+        self.config = config;
+        info!(
+            "🔁 Binance config reloaded: +{:?} -{:?}, streaming {:?}",
+            added, removed, self.subscribed
+        );
+    }
+
+    async fn send_stream_request(&mut self, method: &str, symbols: &[String]) -> Result<()> {
+        let params: Vec<String> = symbols.iter().flat_map(|s| Self::streams_for(&self.config, s)).collect();
+        self.send_streams(method, params).await?;
+        info!("📡 Sent {} for {:?}", method, symbols);
+        Ok(())
+    }
+
+    async fn send_streams(&mut self, method: &str, params: Vec<String>) -> Result<()> {
+        self.request_id += 1;
+        let request = serde_json::json!({
+            "method": method,
+            "params": params,
+            "id": self.request_id,
+        });
+        self.send_json(&request).await?;
+        let kind = if method == "UNSUBSCRIBE" {
+            RequestKind::Unsubscribe
+        } else {
+            RequestKind::Subscribe
+        };
+        self.acks.sent(self.request_id, kind, params, request);
+        Ok(())
+    }
 
+    /// Asks for the streams live on this connection; the reply is handled
+    /// by [`reconcile_streams`](Self::reconcile_streams). Skipped while a
+    /// previous request is unanswered.
+    async fn request_stream_list(&mut self) -> Result<()> {
+        if self.audit_request.is_some() {
+            return Ok(());
+        }
+        self.request_id += 1;
+        let request = serde_json::json!({ "method": "LIST_SUBSCRIPTIONS", "id": self.request_id });
+        self.send_json(&request).await?;
+        self.audit_request = Some(self.request_id);
         Ok(())
     }
+
+    /// Compares the live streams with those the tracked symbols need,
+    /// resubscribing missing ones and dropping strays. Streams with a
+    /// request in flight are left alone.
+    async fn reconcile_streams(&mut self, result: &serde_json::Value) -> Result<()> {
+        let live: BTreeSet<String> = result
+            .as_array()
+            .map(|streams| streams.iter().filter_map(|s| s.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        let intended: BTreeSet<String> = self
+            .subscribed
+            .iter()
+            .flat_map(|s| Self::streams_for(&self.config, s))
+            .collect();
+        self.acks.set_confirmed(live.iter().cloned());
+
+        let missing: Vec<String> = intended
+            .difference(&live)
+            .filter(|s| !self.acks.is_pending(s))
+            .cloned()
+            .collect();
+        let strays: Vec<String> = live
+            .difference(&intended)
+            .filter(|s| !self.acks.is_pending(s))
+            .cloned()
+            .collect();
+        for (kind, streams) in [("missing", &missing), ("stray", &strays)] {
+            if !streams.is_empty() {
+                metrics::add(
+                    names::SUBSCRIPTION_DRIFT_TOTAL,
+                    &[("exchange", "binance"), ("kind", kind)],
+                    streams.len() as u64,
+                );
+            }
+        }
+        if !missing.is_empty() {
+            warn!("🧾 Binance streams missing from the connection, resubscribing: {:?}", missing);
+            self.send_streams("SUBSCRIBE", missing).await?;
+        }
+        if !strays.is_empty() {
+            warn!("🧾 Stray Binance streams on the connection, unsubscribing: {:?}", strays);
+            self.send_streams("UNSUBSCRIBE", strays).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_json(&mut self, request: &serde_json::Value) -> Result<()> {
+        match &mut self.stream {
+            Some(s) => s.send(Message::Text(request.to_string())).await?,
+            None => return Err(Error::WebSocket("Not connected".into())),
+        }
+        Ok(())
+    }
+
+    /// Resends stream requests left unacknowledged too long. Fails once one
+    /// is out of attempts, so the connection is rebuilt from the URL.
+    async fn retry_expired(&mut self) -> Result<()> {
+        for expired in self.acks.expired() {
+            match expired {
+                Expired::Retry(request) => {
+                    self.request_id += 1;
+                    self.send_json(&request.retry_payload(self.request_id)).await?;
+                    self.acks.resent(self.request_id, request);
+                }
+                Expired::Escalate(request) => {
+                    return Err(Error::WebSocket(format!(
+                        "Binance {} for {:?} never acknowledged",
+                        request.kind.as_str(),
+                        request.topics
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn start(&mut self, symbols: &[String], event_tx: mpsc::Sender<MarketEvent>) -> Result<()> {
+        self.warm_start(symbols).await;
+        self.subscribed = symbols.to_vec();
+
+        let strategy = ReconnectStrategy::default();
+        let mut attempt = 0;
+
+        loop {
+            if shutdown::coordinator().is_triggered() {
+                return Ok(());
+            }
+            let result = if self.is_connected() {
+                Ok(())
+            } else {
+                let symbols = self.subscribed.clone();
+                self.connect(&symbols).await
+            };
+            let result = match result {
+                Ok(()) => {
+                    attempt = 0;
+                    self.run(event_tx.clone()).await
+                }
+                Err(e) => Err(e),
+            };
+            let e = match result {
+                Ok(()) => {
+                    if let Err(e) = self.disconnect().await {
+                        warn!("Error disconnecting Binance client: {}", e);
+                    }
+                    return Ok(());
+                }
+                Err(e) => e,
+            };
+            let _ = self.disconnect().await;
+
+            match ReconnectPolicy::for_error(&e) {
+                ReconnectPolicy::Abort => {
+                    error!(exchange = "binance", "🚨 Binance WebSocket error: {}. Not reconnecting, manual intervention required", e);
+                    return Err(e);
+                }
+                ReconnectPolicy::Immediate => {
+                    warn!("🔁 Binance WebSocket error: {}. Reconnecting immediately...", e);
+                }
+                ReconnectPolicy::Delayed(delay) => {
+                    warn!("⏳ Binance WebSocket error: {}. Reconnecting in {}s...", e, delay.as_secs());
+                    tokio::time::sleep(delay).await;
+                }
+                ReconnectPolicy::Backoff => {
+                    if attempt >= strategy.max_retries {
+                        error!(exchange = "binance", "🔴 Binance WebSocket error: {}. Giving up after {} attempts", e, attempt);
+                        return Err(e);
+                    }
+                    let delay = strategy.delay_for_attempt(attempt);
+                    attempt += 1;
+                    error!(exchange = "binance", "🔴 Binance WebSocket error: {}. Reconnecting in {}s...", e, delay.as_secs());
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            metrics::inc(names::WS_RECONNECTS_TOTAL, &[("exchange", "binance")]);
+            state::session().record_reconnect("binance");
+            notify::publish(Notification::Reconnect {
+                exchange: "binance".to_string(),
+                error: e.to_string(),
+            });
+        }
+    }
 }
@@ -0,0 +1,194 @@
+//! USDⓈ-M futures market data.
+//!
+//! `fstream` has no SBE streams, so its JSON payloads are re-encoded into
+//! the spot SBE layouts as they arrive. The rest of the client (dedup,
+//! imbalance and iceberg detection, recording and replay) then handles
+//! perp books exactly like spot ones. Depth diffs for the local book have
+//! no spot SBE counterpart and are handed over as [`DepthDiff`]s instead.
+
+use serde::Deserialize;
+
+use super::orderbook::DepthDiff;
+use super::sbe::encoder::{self, EncodedTrade, Scale};
+use crate::constants::BINANCE_FUTURES_WS_URL;
+use crate::error::{Error, Result};
+use crate::utils::numeric;
+
+/// Fixed-point scaling of re-encoded futures payloads: eight decimals
+/// covers every USDⓈ-M tick and step size.
+const SCALE: Scale = Scale {
+    price_exponent: -8,
+    qty_exponent: -8,
+};
+
+/// Futures counterparts of the spot `trade`, `bestBidAsk` and `depth20`
+/// streams. Perps only publish aggregated trades.
+pub fn streams_for(symbol: &str) -> [String; 3] {
+    let symbol_lower = symbol.to_ascii_lowercase();
+    [
+        format!("{}@aggTrade", symbol_lower),
+        format!("{}@bookTicker", symbol_lower),
+        format!("{}@depth20@100ms", symbol_lower),
+    ]
+}
+
+/// Diff stream feeding the local book.
+pub fn diff_stream_for(symbol: &str) -> String {
+    format!("{}@depth@100ms", symbol.to_ascii_lowercase())
+}
+
+pub fn combined_url(streams: &[String]) -> String {
+    format!("{}/stream?streams={}", BINANCE_FUTURES_WS_URL, streams.join("/"))
+}
+
+/// Combined stream envelope.
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    /// e.g. `btcusdt@depth20@100ms`; partial depth and diffs share the
+    /// `depthUpdate` event type and only differ here
+    stream: String,
+    data: FuturesPayload,
+}
+
+/// A decoded combined stream text frame.
+pub enum FuturesFrame {
+    /// Re-encoded as the SBE message of the matching spot stream
+    Sbe(Vec<u8>),
+    Diff { symbol: String, diff: DepthDiff },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+pub enum FuturesPayload {
+    #[serde(rename = "aggTrade")]
+    AggTrade(AggTrade),
+    #[serde(rename = "bookTicker")]
+    BookTicker(BookTicker),
+    #[serde(rename = "depthUpdate")]
+    Depth(PartialDepth),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AggTrade {
+    #[serde(rename = "E")]
+    pub event_time: i64,
+    /// Matching engine time, which `E` trails
+    #[serde(rename = "T")]
+    pub trade_time: i64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "a")]
+    pub agg_id: i64,
+    #[serde(rename = "p")]
+    pub price: String,
+    #[serde(rename = "q")]
+    pub qty: String,
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BookTicker {
+    #[serde(rename = "E")]
+    pub event_time: i64,
+    #[serde(rename = "T")]
+    pub transaction_time: i64,
+    #[serde(rename = "u")]
+    pub update_id: i64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "b")]
+    pub bid_price: String,
+    #[serde(rename = "B")]
+    pub bid_qty: String,
+    #[serde(rename = "a")]
+    pub ask_price: String,
+    #[serde(rename = "A")]
+    pub ask_qty: String,
+}
+
+/// `depth20` push or `depth` diff. Unlike spot, perps send the top levels
+/// as a `depthUpdate` carrying update ids too.
+#[derive(Debug, Deserialize)]
+pub struct PartialDepth {
+    #[serde(rename = "E")]
+    pub event_time: i64,
+    #[serde(rename = "T")]
+    pub transaction_time: i64,
+    #[serde(rename = "U")]
+    pub first_update_id: i64,
+    #[serde(rename = "u")]
+    pub final_update_id: i64,
+    /// Final update id of the previous push, for gap checks
+    #[serde(rename = "pu")]
+    pub prev_final_update_id: i64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "b")]
+    pub bids: Vec<(String, String)>,
+    #[serde(rename = "a")]
+    pub asks: Vec<(String, String)>,
+}
+
+fn parse(field: &str, value: &str) -> Result<f64> {
+    numeric::parse_decimal(value).ok_or_else(|| Error::SbeDecode(format!("Invalid futures {} '{}'", field, value)))
+}
+
+fn levels(levels: &[(String, String)]) -> Result<Vec<(f64, f64)>> {
+    levels
+        .iter()
+        .map(|(price, qty)| Ok((parse("price", price)?, parse("qty", qty)?)))
+        .collect()
+}
+
+pub fn decode(text: &str) -> Result<FuturesFrame> {
+    let message: StreamMessage = serde_json::from_str(text)?;
+    match message.data {
+        FuturesPayload::Depth(d) if !message.stream.contains("@depth20") => Ok(FuturesFrame::Diff {
+            diff: DepthDiff {
+                first_update_id: d.first_update_id,
+                final_update_id: d.final_update_id,
+                prev_final_update_id: Some(d.prev_final_update_id),
+                bids: levels(&d.bids)?,
+                asks: levels(&d.asks)?,
+            },
+            symbol: d.symbol,
+        }),
+        payload => to_sbe(payload).map(FuturesFrame::Sbe),
+    }
+}
+
+/// Re-encodes a payload as the SBE message of the matching spot stream.
+fn to_sbe(payload: FuturesPayload) -> Result<Vec<u8>> {
+    Ok(match payload {
+        FuturesPayload::AggTrade(t) => encoder::encode_trades(
+            &t.symbol,
+            t.event_time * 1000,
+            SCALE,
+            &[EncodedTrade {
+                id: t.agg_id,
+                price: parse("price", &t.price)?,
+                qty: parse("qty", &t.qty)?,
+                is_buyer_maker: t.is_buyer_maker,
+            }],
+        ),
+        FuturesPayload::BookTicker(b) => encoder::encode_best_bid_ask(
+            &b.symbol,
+            b.event_time * 1000,
+            b.update_id,
+            SCALE,
+            parse("bid price", &b.bid_price)?,
+            parse("bid qty", &b.bid_qty)?,
+            parse("ask price", &b.ask_price)?,
+            parse("ask qty", &b.ask_qty)?,
+        ),
+        FuturesPayload::Depth(d) => encoder::encode_depth_snapshot(
+            &d.symbol,
+            d.event_time * 1000,
+            d.final_update_id,
+            SCALE,
+            &levels(&d.bids)?,
+            &levels(&d.asks)?,
+        ),
+    })
+}
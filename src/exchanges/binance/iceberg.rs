@@ -0,0 +1,250 @@
+//! Probable iceberg orders on the Binance book.
+//!
+//! Trades are charged to the resting level they hit: a buyer-maker trade
+//! consumed a bid, any other an ask. When the next depth snapshot shows a
+//! level back near its displayed size although most of that size traded,
+//! hidden quantity refilled it. Levels refilling repeatedly within the
+//! window are flagged.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use super::sbe::events::depth::DepthSnapshotStreamEvent;
+use super::sbe::events::trade::TradeStreamEvent;
+use super::sbe::messages::SbeMessage;
+use super::sbe::types::DepthSide;
+use crate::calendar;
+use crate::config::IcebergConfig;
+use crate::exchanges::PriceLevel;
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::status::{self, AlertDirection, AlertRecord};
+use crate::tags;
+use crate::utils::event_id::{EventId, EventKind};
+
+/// A level flagged as a probable iceberg.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Iceberg {
+    pub side: DepthSide,
+    pub price: f64,
+    /// Refills seen within the window
+    pub refills: u32,
+    /// Quantity traded at the level over those refills, a lower bound on
+    /// the hidden size
+    pub executed: f64,
+    /// Size the level currently shows
+    pub displayed: f64,
+    pub last_refill: DateTime<Utc>,
+}
+
+impl Iceberg {
+    pub fn side_label(&self) -> &'static str {
+        match self.side {
+            DepthSide::Bid => "bid",
+            _ => "ask",
+        }
+    }
+}
+
+#[derive(Default)]
+struct LevelTrack {
+    /// Size shown in the last snapshot
+    displayed: f64,
+    /// Quantity traded at the level since the last snapshot
+    traded: f64,
+    /// `(time, traded)` per refill inside the window
+    refills: VecDeque<(DateTime<Utc>, f64)>,
+    flagged: bool,
+}
+
+#[derive(Default)]
+struct SymbolBook {
+    bids: HashMap<u64, LevelTrack>,
+    asks: HashMap<u64, LevelTrack>,
+}
+
+/// Per-symbol refill tracking over depth snapshots and trades.
+pub struct IcebergDetector {
+    config: IcebergConfig,
+    symbols: HashMap<String, SymbolBook>,
+}
+
+impl IcebergDetector {
+    pub fn new(config: IcebergConfig) -> Self {
+        Self {
+            config,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn set_config(&mut self, config: IcebergConfig) {
+        self.config = config;
+    }
+
+    /// Feeds any SBE message; depth snapshots return the symbol's flagged
+    /// levels, everything else nothing.
+    pub fn observe(&mut self, msg: &SbeMessage<'_>) -> Vec<Iceberg> {
+        match msg {
+            SbeMessage::Trade(e) => {
+                self.observe_trade(e);
+                Vec::new()
+            }
+            SbeMessage::DepthSnapshot(e) => self.observe_depth(e),
+            SbeMessage::BestBidAsk(_) | SbeMessage::DepthDiff(_) => Vec::new(),
+        }
+    }
+
+    pub fn observe_trade(&mut self, event: &TradeStreamEvent<'_>) {
+        let book = self.symbols.entry(event.symbol.to_string()).or_default();
+        for trade in &event.trades {
+            let levels = if trade.is_buyer_maker { &mut book.bids } else { &mut book.asks };
+            // Only levels seen in a snapshot can refill; trades through
+            // deeper levels are ignored.
+            if let Some(level) = levels.get_mut(&trade.price.to_bits()) {
+                level.traded += trade.qty;
+            }
+        }
+    }
+
+    /// Scores the traded levels against the new snapshot and returns the
+    /// symbol's flagged levels.
+    pub fn observe_depth(&mut self, event: &DepthSnapshotStreamEvent<'_>) -> Vec<Iceberg> {
+        let (bids, asks) = match (event.bids.levels(), event.asks.levels()) {
+            (Ok(bids), Ok(asks)) => (bids, asks),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("Failed to decode depth levels for iceberg detection: {}", e);
+                return self.icebergs(event.symbol);
+            }
+        };
+
+        let config = self.config;
+        let book = self.symbols.entry(event.symbol.to_string()).or_default();
+        for (side, tracks, levels) in [(DepthSide::Bid, &mut book.bids, bids), (DepthSide::Ask, &mut book.asks, asks)] {
+            Self::update_side(&config, event, side, tracks, &levels);
+        }
+
+        let icebergs = self.icebergs(event.symbol);
+        for (side, label) in [(DepthSide::Bid, "bid"), (DepthSide::Ask, "ask")] {
+            let count = icebergs.iter().filter(|i| i.side == side).count();
+            metrics::set(
+                names::BINANCE_ICEBERG_LEVELS,
+                &[("symbol", event.symbol), ("side", label)],
+                count as f64,
+            );
+        }
+        icebergs
+    }
+
+    fn update_side(
+        config: &IcebergConfig,
+        event: &DepthSnapshotStreamEvent<'_>,
+        side: DepthSide,
+        tracks: &mut HashMap<u64, LevelTrack>,
+        levels: &[PriceLevel],
+    ) {
+        let time = event.event_time;
+        let cutoff = time - chrono::Duration::seconds(config.window_secs as i64);
+
+        // Levels that vanished were consumed or cancelled, not refilled.
+        tracks.retain(|price, _| levels.iter().any(|l| l.price.to_bits() == *price));
+
+        for level in levels {
+            let track = tracks.entry(level.price.to_bits()).or_default();
+            let consumed = track.displayed > 0.0 && track.traded >= track.displayed * config.refill_ratio;
+            if consumed {
+                if level.quantity >= track.displayed * config.refill_ratio {
+                    track.refills.push_back((time, track.traded));
+                } else {
+                    track.refills.clear();
+                }
+            }
+            while track.refills.front().is_some_and(|(t, _)| *t < cutoff) {
+                track.refills.pop_front();
+            }
+            track.displayed = level.quantity;
+            track.traded = 0.0;
+
+            let flagged = track.refills.len() as u32 >= config.min_refills;
+            if flagged && !track.flagged {
+                let iceberg = Self::iceberg(side, level.price, track);
+                Self::alert(event, &iceberg);
+            }
+            track.flagged = flagged;
+        }
+    }
+
+    fn iceberg(side: DepthSide, price: f64, track: &LevelTrack) -> Iceberg {
+        Iceberg {
+            side,
+            price,
+            refills: track.refills.len() as u32,
+            executed: track.refills.iter().map(|(_, traded)| traded).sum(),
+            displayed: track.displayed,
+            last_refill: track.refills.back().map(|(t, _)| *t).unwrap_or_default(),
+        }
+    }
+
+    /// Levels of `symbol` currently flagged, best price first per side.
+    pub fn icebergs(&self, symbol: &str) -> Vec<Iceberg> {
+        let Some(book) = self.symbols.get(symbol) else {
+            return Vec::new();
+        };
+        let collect = |side: DepthSide, tracks: &HashMap<u64, LevelTrack>| {
+            let mut levels: Vec<Iceberg> = tracks
+                .iter()
+                .filter(|(_, t)| t.flagged)
+                .map(|(price, t)| Self::iceberg(side, f64::from_bits(*price), t))
+                .collect();
+            levels.sort_by(|a, b| match side {
+                DepthSide::Bid => b.price.total_cmp(&a.price),
+                _ => a.price.total_cmp(&b.price),
+            });
+            levels
+        };
+        let mut icebergs = collect(DepthSide::Bid, &book.bids);
+        icebergs.extend(collect(DepthSide::Ask, &book.asks));
+        icebergs
+    }
+
+    fn alert(event: &DepthSnapshotStreamEvent<'_>, iceberg: &Iceberg) {
+        let event_id = EventId::new(EventKind::Alert);
+        let kalshi_series = instruments::registry().kalshi_series(event.symbol);
+        let precision = instruments::registry().binance(event.symbol);
+        let message = format!(
+            "probable {} iceberg at {}: {} refills, {} executed, {} shown",
+            iceberg.side_label(),
+            precision.price(iceberg.price),
+            iceberg.refills,
+            precision.quantity(iceberg.executed),
+            precision.quantity(iceberg.displayed)
+        );
+        info!(
+            "ALERT [{}]: {} on {} (kalshi series: {})",
+            event_id,
+            message,
+            event.symbol,
+            kalshi_series.as_deref().unwrap_or("unmapped")
+        );
+        metrics::inc(
+            names::ICEBERG_ALERTS_TOTAL,
+            &[("symbol", event.symbol), ("side", iceberg.side_label())],
+        );
+        status::board().record_alert(AlertRecord {
+            event_id: event_id.to_string(),
+            timestamp: event.event_time,
+            symbol: event.symbol.to_string(),
+            kalshi_series,
+            message,
+            imbalance_ratio: None,
+            tags: tags::current().clone(),
+            calendar_event: calendar::active_event(),
+            // A hidden buyer holds the price up, a hidden seller down
+            direction: Some(match iceberg.side {
+                DepthSide::Bid => AlertDirection::Up,
+                _ => AlertDirection::Down,
+            }),
+        });
+    }
+}
@@ -1,3 +1,13 @@
+pub mod alerts;
+pub mod api;
+pub mod auth;
+#[cfg(feature = "sbe")]
 pub mod client;
+#[cfg(feature = "sbe")]
+pub mod futures;
+pub mod iceberg;
 pub mod models;
-pub mod sbe;
\ No newline at end of file
+pub mod orderbook;
+pub mod rest;
+pub mod sbe;
+pub mod velocity;
@@ -0,0 +1,279 @@
+//! Local order books maintained from depth diffs, as the Binance docs
+//! describe it:
+//!
+//! 1. Buffer diffs from the `@depth` stream.
+//! 2. Fetch a REST depth snapshot.
+//! 3. Drop buffered diffs that end before the snapshot's `lastUpdateId`;
+//!    the first one kept must start at or before `lastUpdateId + 1`.
+//! 4. Apply diffs in order, each starting right after the previous one
+//!    (`U == prev u + 1`, or `pu == prev u` on futures). Quantities are
+//!    absolute and 0 removes a level.
+//!
+//! Any gap drops the book and starts over from step 1.
+//!
+//! Spot diffs arrive as SBE frames and are applied straight from the frame
+//! by [`LocalOrderBook::apply_sbe_diff`]; only diffs that have to be
+//! buffered are copied out.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+use super::rest::DepthSnapshot;
+use super::sbe::events::depth::{DepthDiffStreamEvent, DepthImbalance};
+use crate::config::ImbalanceConfig;
+use crate::exchanges::PriceLevel;
+use crate::metrics::{self, names};
+
+/// Levels requested for the REST snapshot; the futures maximum.
+pub const SNAPSHOT_DEPTH: u16 = 1000;
+/// Diffs held while waiting for a snapshot before the oldest are dropped.
+const MAX_BUFFERED_DIFFS: usize = 1000;
+/// Wait after a failed snapshot before fetching again.
+const SNAPSHOT_RETRY: Duration = Duration::from_secs(5);
+/// Levels a side the imbalance covers, as on the `@depth20` stream the
+/// book replaces, so alert thresholds carry over.
+const IMBALANCE_LEVELS: usize = 20;
+/// Fixed-point scale of book keys, matching the re-encoded futures prices.
+const PRICE_SCALE: f64 = 1e8;
+
+/// One `depthUpdate`: every level changed between `first_update_id` and
+/// `final_update_id`, with its new quantity.
+#[derive(Debug, Clone)]
+pub struct DepthDiff {
+    pub first_update_id: i64,
+    pub final_update_id: i64,
+    /// `pu` on futures streams, which chain diffs by it instead of `U`
+    pub prev_final_update_id: Option<i64>,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// What the caller should do after handing a diff to the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOutcome {
+    Applied,
+    /// Held until the book is synced from a snapshot
+    Buffered,
+    /// Held, and a snapshot should be fetched and passed to
+    /// [`LocalOrderBook::sync`]
+    NeedsSnapshot,
+}
+
+/// Book levels keyed by price in fixed point so they sort and compare
+/// exactly.
+type Side = BTreeMap<i64, f64>;
+
+fn key(price: f64) -> i64 {
+    (price * PRICE_SCALE).round() as i64
+}
+
+fn price(key: i64) -> f64 {
+    key as f64 / PRICE_SCALE
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalOrderBook {
+    pub symbol: String,
+    bids: Side,
+    asks: Side,
+    /// Update id the book reflects; `None` until synced
+    last_update_id: Option<i64>,
+    buffer: Vec<DepthDiff>,
+    /// No snapshot is requested before this, after one failed
+    retry_at: Option<Instant>,
+}
+
+impl LocalOrderBook {
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            bids: Side::new(),
+            asks: Side::new(),
+            last_update_id: None,
+            buffer: Vec::new(),
+            retry_at: None,
+        }
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.last_update_id.is_some()
+    }
+
+    pub fn last_update_id(&self) -> Option<i64> {
+        self.last_update_id
+    }
+
+    pub fn apply_diff(&mut self, diff: DepthDiff) -> DiffOutcome {
+        let Some(last) = self.last_update_id else {
+            return self.buffer_diff(diff);
+        };
+        if diff.final_update_id <= last {
+            return DiffOutcome::Applied;
+        }
+        let continuous = match diff.prev_final_update_id {
+            Some(pu) => pu == last,
+            None => diff.first_update_id == last + 1,
+        };
+        if !continuous {
+            warn!(
+                "📉 {} depth gap: book at {}, diff {}..={}; resyncing",
+                self.symbol, last, diff.first_update_id, diff.final_update_id
+            );
+            metrics::inc(
+                names::BINANCE_BOOK_RESYNCS_TOTAL,
+                &[("symbol", self.symbol.as_str()), ("reason", "gap")],
+            );
+            self.reset();
+            return self.buffer_diff(diff);
+        }
+        self.apply_levels(&diff);
+        DiffOutcome::Applied
+    }
+
+    /// [`apply_diff`](Self::apply_diff) for an SBE diff. The common case, a
+    /// synced book and the next diff in sequence, reads levels from the
+    /// frame without allocating.
+    pub fn apply_sbe_diff(&mut self, event: &DepthDiffStreamEvent<'_>) -> DiffOutcome {
+        match self.last_update_id {
+            Some(last) if event.last_book_update_id <= last => DiffOutcome::Applied,
+            Some(last) if event.first_book_update_id == last + 1 => {
+                for (side, levels) in [(&mut self.bids, &event.bids), (&mut self.asks, &event.asks)] {
+                    for (p, q) in levels.iter() {
+                        update_level(side, p, q);
+                    }
+                }
+                self.last_update_id = Some(event.last_book_update_id);
+                DiffOutcome::Applied
+            }
+            _ => self.apply_diff(event.to_diff()),
+        }
+    }
+
+    fn buffer_diff(&mut self, diff: DepthDiff) -> DiffOutcome {
+        if self.buffer.len() >= MAX_BUFFERED_DIFFS {
+            self.buffer.remove(0);
+        }
+        self.buffer.push(diff);
+        match self.retry_at {
+            Some(at) if Instant::now() < at => DiffOutcome::Buffered,
+            _ => DiffOutcome::NeedsSnapshot,
+        }
+    }
+
+    /// Seeds the book from `snapshot` and replays the buffered diffs on top.
+    /// Returns false, leaving the book unsynced, when the snapshot is older
+    /// than every buffered diff or a buffered diff is missing.
+    pub fn sync(&mut self, snapshot: &DepthSnapshot) -> bool {
+        let last = snapshot.last_update_id;
+        let buffered: Vec<DepthDiff> = std::mem::take(&mut self.buffer)
+            .into_iter()
+            .filter(|d| d.final_update_id > last)
+            .collect();
+        if let Some(first) = buffered.first() {
+            if first.first_update_id > last + 1 {
+                warn!(
+                    "{} depth snapshot at {} predates the buffered diffs from {}",
+                    self.symbol, last, first.first_update_id
+                );
+                self.buffer = buffered;
+                return false;
+            }
+        }
+
+        self.bids = snapshot.bids.iter().map(|&(p, q)| (key(p), q)).collect();
+        self.asks = snapshot.asks.iter().map(|&(p, q)| (key(p), q)).collect();
+        self.last_update_id = Some(last);
+        self.retry_at = None;
+
+        let count = buffered.len();
+        for (idx, diff) in buffered.into_iter().enumerate() {
+            // The first diff straddles the snapshot, so only later ones are
+            // chained.
+            if idx == 0 {
+                self.apply_levels(&diff);
+            } else if self.apply_diff(diff) != DiffOutcome::Applied {
+                return false;
+            }
+        }
+        info!(
+            "📚 {} local book synced at {} ({} bids, {} asks, {} buffered diffs)",
+            self.symbol,
+            self.last_update_id.unwrap_or(last),
+            self.bids.len(),
+            self.asks.len(),
+            count
+        );
+        true
+    }
+
+    /// Backs off snapshot requests after a failed fetch.
+    pub fn snapshot_failed(&mut self) {
+        self.retry_at = Some(Instant::now() + SNAPSHOT_RETRY);
+    }
+
+    fn apply_levels(&mut self, diff: &DepthDiff) {
+        for (side, levels) in [(&mut self.bids, &diff.bids), (&mut self.asks, &diff.asks)] {
+            for &(p, q) in levels {
+                update_level(side, p, q);
+            }
+        }
+        self.last_update_id = Some(diff.final_update_id);
+    }
+
+    fn reset(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.last_update_id = None;
+    }
+
+    /// Up to `depth` bids, best (highest) first.
+    pub fn bids(&self, depth: usize) -> Vec<PriceLevel> {
+        self.bids
+            .iter()
+            .rev()
+            .take(depth)
+            .map(|(&k, &quantity)| PriceLevel { price: price(k), quantity })
+            .collect()
+    }
+
+    /// Up to `depth` asks, best (lowest) first.
+    pub fn asks(&self, depth: usize) -> Vec<PriceLevel> {
+        self.asks
+            .iter()
+            .take(depth)
+            .map(|(&k, &quantity)| PriceLevel { price: price(k), quantity })
+            .collect()
+    }
+
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids(1).pop()
+    }
+
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks(1).pop()
+    }
+
+    /// The same bid/ask ratios a depth snapshot yields, over the top
+    /// [`IMBALANCE_LEVELS`]; `None` until synced or while the top asks are
+    /// empty.
+    pub fn imbalance(&self, config: &ImbalanceConfig) -> Option<DepthImbalance> {
+        if !self.is_synced() {
+            return None;
+        }
+        DepthImbalance::from_levels(
+            self.bids.iter().rev().take(IMBALANCE_LEVELS).map(|(&k, &q)| (price(k), q)),
+            self.asks.iter().take(IMBALANCE_LEVELS).map(|(&k, &q)| (price(k), q)),
+            config,
+        )
+    }
+}
+
+fn update_level(side: &mut Side, price: f64, qty: f64) {
+    if qty == 0.0 {
+        side.remove(&key(price));
+    } else {
+        side.insert(key(price), qty);
+    }
+}
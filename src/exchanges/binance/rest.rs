@@ -0,0 +1,244 @@
+//! REST market data: depth snapshots to seed a local book, klines to
+//! backfill candles and 24h ticker statistics.
+//!
+//! Market data endpoints need no key, but one sent in `X-MBX-APIKEY` gets
+//! its own rate limit. [`BinanceRest::get_signed`] adds the RSA signature
+//! account endpoints require, the same way hedge orders are signed.
+
+use chrono::{DateTime, Utc};
+use reqwest::Client as HttpClient;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::constants::{BINANCE_FUTURES_REST_URL, BINANCE_REST_URL};
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::binance::auth::BinanceSigner;
+use crate::trader::venue::BinanceMarket;
+use crate::utils::http::ensure_success;
+use crate::utils::numeric;
+
+/// Most klines returned by one request.
+pub const MAX_KLINES: u16 = 1000;
+
+/// `GET /api/v3/depth`. Levels are `(price, qty)`, best first.
+#[derive(Debug, Clone)]
+pub struct DepthSnapshot {
+    pub symbol: String,
+    pub last_update_id: i64,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawDepth {
+    last_update_id: i64,
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Kline {
+    pub open_time: DateTime<Utc>,
+    pub close_time: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Base asset volume
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trades: u64,
+}
+
+/// Klines come as positional arrays:
+/// `[open_time, open, high, low, close, volume, close_time, quote_volume,
+/// trades, taker_base, taker_quote, ignore]`.
+#[derive(Debug, Deserialize)]
+struct RawKline(
+    i64,
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    String,
+    u64,
+    serde::de::IgnoredAny,
+    serde::de::IgnoredAny,
+    serde::de::IgnoredAny,
+);
+
+/// `GET /api/v3/ticker/24hr` for one symbol.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker24h {
+    pub symbol: String,
+    pub price_change_percent: String,
+    pub last_price: String,
+    pub high_price: String,
+    pub low_price: String,
+    pub volume: String,
+    pub quote_volume: String,
+    pub count: u64,
+}
+
+pub struct BinanceRest {
+    http: HttpClient,
+    market: BinanceMarket,
+    api_key: Option<String>,
+    signer: Option<BinanceSigner>,
+}
+
+impl BinanceRest {
+    pub fn new(market: BinanceMarket) -> Self {
+        Self {
+            http: HttpClient::new(),
+            market,
+            api_key: None,
+            signer: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    /// Enables [`get_signed`](Self::get_signed); needs an API key too.
+    pub fn with_private_key(mut self, private_key_path: &str) -> Result<Self> {
+        if self.api_key.is_none() {
+            return Err(Error::Config("Signed Binance requests need BINANCE_API_KEY".into()));
+        }
+        self.signer = Some(BinanceSigner::from_file(private_key_path)?);
+        Ok(self)
+    }
+
+    fn base_url(&self) -> &'static str {
+        match self.market {
+            BinanceMarket::Spot => BINANCE_REST_URL,
+            BinanceMarket::Futures => BINANCE_FUTURES_REST_URL,
+        }
+    }
+
+    /// The market's path for a spot `/api/v3` endpoint.
+    fn path(&self, endpoint: &str) -> String {
+        match self.market {
+            BinanceMarket::Spot => format!("/api/v3/{}", endpoint),
+            BinanceMarket::Futures => format!("/fapi/v1/{}", endpoint),
+        }
+    }
+
+    fn context(operation: &str, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_exchange("binance")
+            .with_endpoint(format!("GET {}", path))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, operation: &str, path: &str, query: &str) -> Result<T> {
+        let url = format!("{}{}?{}", self.base_url(), path, query);
+        let context = || Self::context(operation, path);
+
+        let mut request = self.http.get(&url);
+        if let Some(key) = &self.api_key {
+            request = request.header("X-MBX-APIKEY", key);
+        }
+        let resp = request.send().await.with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+        resp.json().await.with_context(context)
+    }
+
+    /// GET on a `USER_DATA` endpoint, e.g. `/api/v3/account`, with a
+    /// timestamp and signature appended to `query`.
+    pub async fn get_signed<T: DeserializeOwned>(&self, path: &str, query: &str) -> Result<T> {
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            Error::Config("Signed Binance requests need BINANCE_PRIVATE_KEY_PATH".into())
+        })?;
+        let timestamp = format!("timestamp={}", Utc::now().timestamp_millis());
+        let payload = if query.is_empty() {
+            timestamp
+        } else {
+            format!("{}&{}", query, timestamp)
+        };
+        let signature = signer.sign(&payload);
+        self.get("signed request", path, &format!("{}&signature={}", payload, signature))
+            .await
+    }
+
+    /// Order book snapshot with `limit` levels a side (5 to 5000).
+    pub async fn depth(&self, symbol: &str, limit: u16) -> Result<DepthSnapshot> {
+        let symbol = symbol.to_ascii_uppercase();
+        let path = self.path("depth");
+        let raw: RawDepth = self
+            .get("fetch depth", &path, &format!("symbol={}&limit={}", symbol, limit))
+            .await?;
+        let context = || Self::context("fetch depth", &path).with_symbol(symbol.clone());
+        Ok(DepthSnapshot {
+            last_update_id: raw.last_update_id,
+            bids: levels(&raw.bids).with_context(context)?,
+            asks: levels(&raw.asks).with_context(context)?,
+            symbol,
+        })
+    }
+
+    /// Up to `limit` (at most [`MAX_KLINES`]) candles of `interval`, e.g.
+    /// `1m` or `1h`, oldest first. Without `start` the most recent ones.
+    pub async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        limit: u16,
+    ) -> Result<Vec<Kline>> {
+        let symbol = symbol.to_ascii_uppercase();
+        let path = self.path("klines");
+        let mut query = format!("symbol={}&interval={}&limit={}", symbol, interval, limit.min(MAX_KLINES));
+        if let Some(start) = start {
+            query.push_str(&format!("&startTime={}", start.timestamp_millis()));
+        }
+        if let Some(end) = end {
+            query.push_str(&format!("&endTime={}", end.timestamp_millis()));
+        }
+        let raw: Vec<RawKline> = self.get("fetch klines", &path, &query).await?;
+        let context = || Self::context("fetch klines", &path).with_symbol(symbol.clone());
+        raw.into_iter()
+            .map(|k| {
+                Ok(Kline {
+                    open_time: millis(k.0)?,
+                    close_time: millis(k.6)?,
+                    open: parse("open", &k.1)?,
+                    high: parse("high", &k.2)?,
+                    low: parse("low", &k.3)?,
+                    close: parse("close", &k.4)?,
+                    volume: parse("volume", &k.5)?,
+                    quote_volume: parse("quote volume", &k.7)?,
+                    trades: k.8,
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .with_context(context)
+    }
+
+    pub async fn ticker_24hr(&self, symbol: &str) -> Result<Ticker24h> {
+        let symbol = symbol.to_ascii_uppercase();
+        self.get("fetch 24h ticker", &self.path("ticker/24hr"), &format!("symbol={}", symbol))
+            .await
+    }
+}
+
+fn parse(field: &str, value: &str) -> Result<f64> {
+    numeric::parse_decimal(value).ok_or_else(|| Error::Http(format!("Invalid {} '{}'", field, value)))
+}
+
+fn levels(levels: &[(String, String)]) -> Result<Vec<(f64, f64)>> {
+    levels
+        .iter()
+        .map(|(price, qty)| Ok((parse("price", price)?, parse("qty", qty)?)))
+        .collect()
+}
+
+fn millis(ms: i64) -> Result<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(ms).ok_or_else(|| Error::Http(format!("Invalid timestamp {}", ms)))
+}
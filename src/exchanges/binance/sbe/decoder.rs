@@ -1,3 +1,5 @@
+use std::sync::Mutex;
+
 use tracing::warn;
 use tracing::error;
 
@@ -5,57 +7,99 @@ use crate::error::{Error, Result};
 
 use super::events::{
     bid_ask::BestBidAskStreamEvent, 
-    depth::DepthSnapshotStreamEvent, 
+    depth::{DepthDiffStreamEvent, DepthSnapshotStreamEvent},
     trade::TradeStreamEvent
 };
 use super::messages::*;
 use super::types::*;
 
+/// What the decoder has seen so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecoderStats {
+    /// Schema id and version of the latest frame, whatever was expected
+    pub schema_id: u16,
+    pub version: u16,
+    pub decoded: u64,
+    pub failed: u64,
+    pub unknown_templates: u64,
+    /// Frames whose root block was longer than the fields known here, i.e.
+    /// sent under a newer schema version
+    pub extended_blocks: u64,
+    /// Bytes of unknown root block fields skipped over
+    pub skipped_bytes: u64,
+}
+
+/// Decodes SBE frames by their header: the root block length and each
+/// group's block length are honoured, so fields a newer schema version
+/// appends are skipped rather than misread.
 pub struct SbeDecoder {
     pub expected_schema_id: u16,
     pub expected_version: u16,
+    stats: Mutex<DecoderStats>,
 }
 
 impl SbeDecoder {
     pub fn new() -> Self {
-        Self {
-            expected_schema_id: SCHEMA_ID,
-            expected_version: SCHEMA_VERSION,
-        }
+        Self::with_schema(SCHEMA_ID, SCHEMA_VERSION)
     }
 
     pub fn with_schema(schema_id: u16, version: u16) -> Self {
         Self {
             expected_schema_id: schema_id,
             expected_version: version,
+            stats: Mutex::new(DecoderStats::default()),
         }
     }
 
+    pub fn stats(&self) -> DecoderStats {
+        *self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     pub fn decode<'a>(&self, data: &'a [u8]) -> Result<SbeMessage<'a>> {
         let header = MessageHeader::decode(data)?;
+        let result = self.decode_body(&header, &data[MessageHeader::SIZE..]);
 
-        if header.schema_id != self.expected_schema_id {
-            warn!(
-                "Schema ID from server: {} (expected {}), Version: {}",
-                header.schema_id,
-                self.expected_schema_id,
-                header.version
-            );
+        // One lock per frame for the header and the outcome
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        self.observe_header(&mut stats, &header);
+        match (&result, header.message_type()) {
+            (Ok(_), _) => stats.decoded += 1,
+            (Err(_), SbeMessageType::Unknown(_)) => stats.unknown_templates += 1,
+            (Err(_), _) => stats.failed += 1,
         }
+        result
+    }
 
-        if header.version != self.expected_version {
+    /// Records the header's schema, warning once per change rather than on
+    /// every frame, and counts root blocks longer than known.
+    fn observe_header(&self, stats: &mut DecoderStats, header: &MessageHeader) {
+        let changed = stats.decoded + stats.failed + stats.unknown_templates == 0
+            || (stats.schema_id, stats.version) != (header.schema_id, header.version);
+        stats.schema_id = header.schema_id;
+        stats.version = header.version;
+        if changed
+            && (header.schema_id != self.expected_schema_id || header.version != self.expected_version)
+        {
             warn!(
-                "Version from server: {} (expected {}), Schema ID: {}",
+                "SBE schema from server: id {} version {} (expected id {} version {}), skipping unknown fields",
+                header.schema_id,
                 header.version,
-                self.expected_version,
-                header.schema_id
+                self.expected_schema_id,
+                self.expected_version
             );
         }
+        if let Some(known) = header.message_type().known_block_length() {
+            if header.block_length > known {
+                stats.extended_blocks += 1;
+                stats.skipped_bytes += (header.block_length - known) as u64;
+            }
+        }
+    }
 
-        let body = &data[MessageHeader::SIZE..];
-
+    fn decode_body<'a>(&self, header: &MessageHeader, body: &'a [u8]) -> Result<SbeMessage<'a>> {
+        let block_length = header.block_length;
         match header.message_type() {
-            SbeMessageType::Trade => TradeStreamEvent::decode(body)
+            SbeMessageType::Trade => TradeStreamEvent::decode(body, block_length)
                 .map(SbeMessage::Trade)
                 .map_err(|e| {
                     error!(
@@ -65,7 +109,7 @@ impl SbeDecoder {
                     );
                     e
                 }),
-            SbeMessageType::BestBidAsk => BestBidAskStreamEvent::decode(body)
+            SbeMessageType::BestBidAsk => BestBidAskStreamEvent::decode(body, block_length)
                 .map(SbeMessage::BestBidAsk)
                 .map_err(|e| {
                     error!(
@@ -75,10 +119,17 @@ impl SbeDecoder {
                     );
                     e
                 }),
-            SbeMessageType::DepthDiff => {
-                Err(Error::SbeDecode("DepthDiff message not supported".into()))
-            }
-            SbeMessageType::DepthSnapshot => DepthSnapshotStreamEvent::decode(body)
+            SbeMessageType::DepthDiff => DepthDiffStreamEvent::decode(body, block_length)
+                .map(SbeMessage::DepthDiff)
+                .map_err(|e| {
+                    error!(
+                        "Failed to decode DepthDiff message (body_len={}): {}",
+                        body.len(),
+                        e
+                    );
+                    e
+                }),
+            SbeMessageType::DepthSnapshot => DepthSnapshotStreamEvent::decode(body, block_length)
                 .map(SbeMessage::DepthSnapshot)
                 .map_err(|e| {
                     tracing::error!(
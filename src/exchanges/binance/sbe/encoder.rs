@@ -0,0 +1,142 @@
+//! Encoders for the stream messages understood by [`super::decoder::SbeDecoder`].
+//! Used to build synthetic feeds; layouts mirror the event decoders.
+
+use super::types::{
+    SCHEMA_ID, SCHEMA_VERSION, TEMPLATE_BEST_BID_ASK_STREAM, TEMPLATE_DEPTH_SNAPSHOT_STREAM,
+    TEMPLATE_TRADES_STREAM,
+};
+
+const TRADE_BLOCK_LENGTH: u16 = 18;
+const TRADE_ENTRY_LENGTH: u16 = 25;
+const BEST_BID_ASK_BLOCK_LENGTH: u16 = 50;
+const DEPTH_BLOCK_LENGTH: u16 = 18;
+const DEPTH_LEVEL_LENGTH: u16 = 16;
+
+/// Fixed-point scaling shared by every encoded price and quantity.
+#[derive(Debug, Clone, Copy)]
+pub struct Scale {
+    pub price_exponent: i8,
+    pub qty_exponent: i8,
+}
+
+impl Scale {
+    fn price(&self, value: f64) -> i64 {
+        (value / 10f64.powi(self.price_exponent as i32)).round() as i64
+    }
+
+    fn qty(&self, value: f64) -> i64 {
+        (value / 10f64.powi(self.qty_exponent as i32)).round() as i64
+    }
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Self {
+            price_exponent: -2,
+            qty_exponent: -5,
+        }
+    }
+}
+
+fn header(buf: &mut Vec<u8>, block_length: u16, template_id: u16) {
+    buf.extend_from_slice(&block_length.to_le_bytes());
+    buf.extend_from_slice(&template_id.to_le_bytes());
+    buf.extend_from_slice(&SCHEMA_ID.to_le_bytes());
+    buf.extend_from_slice(&SCHEMA_VERSION.to_le_bytes());
+}
+
+fn symbol(buf: &mut Vec<u8>, symbol: &str) {
+    let bytes = &symbol.as_bytes()[..symbol.len().min(u8::MAX as usize)];
+    buf.push(bytes.len() as u8);
+    buf.extend_from_slice(bytes);
+}
+
+pub struct EncodedTrade {
+    pub id: i64,
+    pub price: f64,
+    pub qty: f64,
+    pub is_buyer_maker: bool,
+}
+
+pub fn encode_trades(
+    symbol_name: &str,
+    event_time_micros: i64,
+    scale: Scale,
+    trades: &[EncodedTrade],
+) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(32 + trades.len() * TRADE_ENTRY_LENGTH as usize);
+    header(&mut buf, TRADE_BLOCK_LENGTH, TEMPLATE_TRADES_STREAM);
+    buf.extend_from_slice(&event_time_micros.to_le_bytes());
+    buf.extend_from_slice(&event_time_micros.to_le_bytes());
+    buf.push(scale.price_exponent as u8);
+    buf.push(scale.qty_exponent as u8);
+
+    buf.extend_from_slice(&TRADE_ENTRY_LENGTH.to_le_bytes());
+    buf.extend_from_slice(&(trades.len() as u32).to_le_bytes());
+    for trade in trades {
+        buf.extend_from_slice(&trade.id.to_le_bytes());
+        buf.extend_from_slice(&scale.price(trade.price).to_le_bytes());
+        buf.extend_from_slice(&scale.qty(trade.qty).to_le_bytes());
+        buf.push(trade.is_buyer_maker as u8);
+    }
+    symbol(&mut buf, symbol_name);
+    buf
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn encode_best_bid_ask(
+    symbol_name: &str,
+    event_time_micros: i64,
+    book_update_id: i64,
+    scale: Scale,
+    bid_price: f64,
+    bid_qty: f64,
+    ask_price: f64,
+    ask_qty: f64,
+) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(64);
+    header(&mut buf, BEST_BID_ASK_BLOCK_LENGTH, TEMPLATE_BEST_BID_ASK_STREAM);
+    buf.extend_from_slice(&event_time_micros.to_le_bytes());
+    buf.extend_from_slice(&book_update_id.to_le_bytes());
+    buf.push(scale.price_exponent as u8);
+    buf.push(scale.qty_exponent as u8);
+    for mantissa in [
+        scale.price(bid_price),
+        scale.qty(bid_qty),
+        scale.price(ask_price),
+        scale.qty(ask_qty),
+    ] {
+        buf.extend_from_slice(&mantissa.to_le_bytes());
+    }
+    symbol(&mut buf, symbol_name);
+    buf
+}
+
+/// `bids` and `asks` are `(price, qty)` levels, best first.
+pub fn encode_depth_snapshot(
+    symbol_name: &str,
+    event_time_micros: i64,
+    book_update_id: i64,
+    scale: Scale,
+    bids: &[(f64, f64)],
+    asks: &[(f64, f64)],
+) -> Vec<u8> {
+    let levels = bids.len() + asks.len();
+    let mut buf = Vec::with_capacity(48 + levels * DEPTH_LEVEL_LENGTH as usize);
+    header(&mut buf, DEPTH_BLOCK_LENGTH, TEMPLATE_DEPTH_SNAPSHOT_STREAM);
+    buf.extend_from_slice(&event_time_micros.to_le_bytes());
+    buf.extend_from_slice(&book_update_id.to_le_bytes());
+    buf.push(scale.price_exponent as u8);
+    buf.push(scale.qty_exponent as u8);
+
+    for side in [bids, asks] {
+        buf.extend_from_slice(&DEPTH_LEVEL_LENGTH.to_le_bytes());
+        buf.extend_from_slice(&(side.len().min(u16::MAX as usize) as u16).to_le_bytes());
+        for (price, qty) in side.iter().take(u16::MAX as usize) {
+            buf.extend_from_slice(&scale.price(*price).to_le_bytes());
+            buf.extend_from_slice(&scale.qty(*qty).to_le_bytes());
+        }
+    }
+    symbol(&mut buf, symbol_name);
+    buf
+}
@@ -1,7 +1,8 @@
 use chrono::{DateTime, Utc};
-use tracing::info;
+use tracing::debug;
 use crate::{
     error::Result,
+    instruments,
     exchanges::binance::sbe::{
         types::micros_to_datetime,
         utils::SbeCursor,
@@ -20,7 +21,9 @@ pub struct BestBidAskStreamEvent<'a> {
 }
 
 impl<'a> BestBidAskStreamEvent<'a> {
-    pub fn decode(data: &'a [u8]) -> Result<Self> {
+    /// Decodes a message body whose root block is `root_block_length`
+    /// bytes, as given by the message header.
+    pub fn decode(data: &'a [u8], root_block_length: u16) -> Result<Self> {
         let mut cursor = SbeCursor::new(data);
 
         let event_time_micros = cursor.read_i64_le()?;
@@ -41,6 +44,7 @@ impl<'a> BestBidAskStreamEvent<'a> {
 
         let ask_qty_mantissa = cursor.read_i64_le()?;
         let ask_qty = ask_qty_mantissa as f64 * qty_scale;
+        cursor.skip_block(0, root_block_length as usize)?;
 
         let symbol = cursor.read_var_string8()?;
 
@@ -57,6 +61,11 @@ impl<'a> BestBidAskStreamEvent<'a> {
 
     pub fn print_update(&self) {
         let last_price = (self.bid_price * self.ask_qty + self.ask_price * self.bid_qty) / (self.bid_qty + self.ask_qty);
-        info!("⚖️ bid = {}, ask = {}, last_price = {:.3}\n at event time: {}, now time: {}", self.bid_price, self.ask_price, last_price, self.event_time, Utc::now());
+        let precision = instruments::registry().binance(self.symbol);
+        debug!(
+            "⚖️ bid = {}, ask = {}, last_price = {}\n at event time: {}, now time: {}",
+            precision.price(self.bid_price), precision.price(self.ask_price), precision.price(last_price),
+            self.event_time, Utc::now()
+        );
     }
 }
\ No newline at end of file
@@ -1,19 +1,121 @@
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
-use tracing::{info, warn};
+use tracing::{debug, info};
 use crate::{
     Error,
+    calendar,
+    config::ImbalanceConfig,
     error::Result,
+    exchanges::PriceLevel,
+    exchanges::binance::iceberg::Iceberg,
+    exchanges::binance::orderbook::DepthDiff,
+    instruments,
+    metrics::{self, names},
+    state,
     exchanges::binance::sbe::{
         types::micros_to_datetime,
         utils::{read_group_size16, read_i64_le_from, SbeCursor},
     },
+    status::{self, AlertDirection, AlertRecord},
+    tags,
+    utils::event_id::{EventId, EventKind},
 };
 
+/// Default bid/ask ratio above which, or below its inverse, a depth
+/// imbalance alert is raised.
+pub const IMBALANCE_ALERT_RATIO: f64 = 100.0;
+
+/// What each level contributes to the depth sums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthWeighting {
+    /// Base asset quantity
+    Quantity,
+    /// Quantity times price, so deep levels far from the touch weigh what
+    /// they would cost to fill
+    Notional,
+}
+
+impl FromStr for DepthWeighting {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "quantity" | "qty" => Ok(DepthWeighting::Quantity),
+            "notional" => Ok(DepthWeighting::Notional),
+            other => Err(Error::Config(format!(
+                "Unknown depth weighting '{}', expected quantity or notional",
+                other
+            ))),
+        }
+    }
+}
+
+/// What becomes of an imbalance alert raised inside the blackout before
+/// the mapped Kalshi market closes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlackoutAction {
+    /// Dropped, only counted in the session stats
+    Suppress,
+    /// Listed among the status page's recent alerts marked as near close,
+    /// but never broadcast, so not notified, persisted or traded on
+    Downgrade,
+}
+
+impl FromStr for BlackoutAction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "suppress" => Ok(BlackoutAction::Suppress),
+            "downgrade" => Ok(BlackoutAction::Downgrade),
+            other => Err(Error::Config(format!(
+                "Unknown close blackout action '{}', expected suppress or downgrade",
+                other
+            ))),
+        }
+    }
+}
+
+/// Session stats reason for alerts held back near a Kalshi market's close.
+pub const SUPPRESSED_NEAR_CLOSE: &str = "close_blackout";
+
+/// A depth imbalance alert as raised, before any Kalshi context is attached.
+#[derive(Debug, Clone)]
+pub struct ImbalanceAlert {
+    pub event_id: EventId,
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    /// `N_5`, `N_10` or `All`.
+    pub depth: &'static str,
+    pub ratio: f64,
+    pub direction: AlertDirection,
+    /// As posted to the status board.
+    pub message: String,
+    /// Bid side sum, in quote currency under notional weighting.
+    pub bid_qty: f64,
+    pub ask_qty: f64,
+    /// Probable icebergs on the symbol when the alert was raised
+    pub icebergs: Vec<Iceberg>,
+}
+
+/// Bid/ask ratios of one depth snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DepthImbalance {
+    pub top_5: f64,
+    pub top_10: f64,
+    pub all: f64,
+    /// Bid sums over the top 5, top 10 and all levels
+    pub bid_sums: (f64, f64, f64),
+    pub ask_sums: (f64, f64, f64),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct DepthLevels<'a> {
     data: &'a [u8],
     count: u16,
     block_length: u16,
+    price_scale: f64,
     qty_scale: f64,
 }
 
@@ -22,6 +124,7 @@ impl<'a> DepthLevels<'a> {
         data: &'a [u8],
         count: u16,
         block_length: u16,
+        price_scale: f64,
         qty_scale: f64,
     ) -> Result<Self> {
         if block_length < 16 {
@@ -30,44 +133,153 @@ impl<'a> DepthLevels<'a> {
                 block_length
             )));
         }
+        let needed = count as usize * block_length as usize;
+        if data.len() < needed {
+            return Err(Error::SbeDecode(format!(
+                "Not enough data for depth levels: need {} bytes, have {} bytes",
+                needed,
+                data.len()
+            )));
+        }
         Ok(Self {
             data,
             count,
             block_length,
+            price_scale,
             qty_scale,
         })
     }
 
-    pub fn sum_qtys_top5_top10_all(&self) -> Result<(f64, f64, f64)> {
-        let mut top_5_sum = 0.0_f64;
-        let mut top_10_sum = 0.0_f64;
-        let mut all_sum = 0.0_f64;
-        let block_length = self.block_length as usize;
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
 
-        let mut offset = 0usize;
+    /// `(price, qty)` of each level, best first, read straight from the
+    /// frame. Lengths are checked on construction, so this cannot fail.
+    pub fn iter(&self) -> impl Iterator<Item = (f64, f64)> + 'a {
+        let (price_scale, qty_scale) = (self.price_scale, self.qty_scale);
+        self.data
+            .chunks_exact(self.block_length as usize)
+            .take(self.count as usize)
+            .map(move |level| {
+                (
+                    mantissa(&level[..8]) as f64 * price_scale,
+                    mantissa(&level[8..16]) as f64 * qty_scale,
+                )
+            })
+    }
+
+    /// Decoded levels, best first.
+    pub fn levels(&self) -> Result<Vec<PriceLevel>> {
+        let block_length = self.block_length as usize;
+        let mut levels = Vec::with_capacity(self.count as usize);
         for idx in 0..self.count as usize {
-            let qty_offset = offset + 8;
-            if qty_offset + 8 > self.data.len() {
+            let offset = idx * block_length;
+            if offset + 16 > self.data.len() {
                 return Err(Error::SbeDecode(format!(
-                    "Not enough data for depth level qty: need {} bytes, have {} bytes",
-                    qty_offset + 8,
+                    "Not enough data for depth level: need {} bytes, have {} bytes",
+                    offset + 16,
                     self.data.len()
                 )));
             }
+            levels.push(PriceLevel {
+                price: read_i64_le_from(&self.data[offset..])? as f64 * self.price_scale,
+                quantity: read_i64_le_from(&self.data[offset + 8..])? as f64 * self.qty_scale,
+            });
+        }
+        Ok(levels)
+    }
 
-            let qty_mantissa = read_i64_le_from(&self.data[qty_offset..])?;
-            let qty = qty_mantissa as f64 * self.qty_scale;
-            if idx < 5 {
-                top_5_sum += qty;
-            }
-            if idx < 10 {
-                top_10_sum += qty;
-            }
-            all_sum += qty;
-            offset += block_length;
+    pub fn sum_qtys_top5_top10_all(&self) -> Result<(f64, f64, f64)> {
+        self.sums_top5_top10_all(DepthWeighting::Quantity, 0.0)
+    }
+
+    /// Weighted sums over the first 5, first 10 and all levels. Levels below
+    /// `min_qty` still count as a position in the book but add nothing.
+    pub fn sums_top5_top10_all(&self, weighting: DepthWeighting, min_qty: f64) -> Result<(f64, f64, f64)> {
+        Ok(weighted_sums(self.iter(), weighting, min_qty))
+    }
+}
+
+fn mantissa(bytes: &[u8]) -> i64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    i64::from_le_bytes(buf)
+}
+
+/// Weighted sums of `(price, qty)` levels, best first, over the first 5,
+/// first 10 and all of them. Shared by depth snapshots and local books so
+/// both alert on the same numbers.
+pub fn weighted_sums(
+    levels: impl IntoIterator<Item = (f64, f64)>,
+    weighting: DepthWeighting,
+    min_qty: f64,
+) -> (f64, f64, f64) {
+    let mut top_5_sum = 0.0_f64;
+    let mut top_10_sum = 0.0_f64;
+    let mut all_sum = 0.0_f64;
+    for (idx, (price, qty)) in levels.into_iter().enumerate() {
+        if qty < min_qty {
+            continue;
+        }
+        let weight = match weighting {
+            DepthWeighting::Quantity => qty,
+            DepthWeighting::Notional => price * qty,
+        };
+        if idx < 5 {
+            top_5_sum += weight;
         }
+        if idx < 10 {
+            top_10_sum += weight;
+        }
+        all_sum += weight;
+    }
+    (top_5_sum, top_10_sum, all_sum)
+}
 
-        Ok((top_5_sum, top_10_sum, all_sum))
+/// The side of a book outweighing the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImbalanceLean {
+    Bid,
+    Ask,
+}
+
+impl DepthImbalance {
+    /// The side the top five levels lean to by more than `ratio` either
+    /// way, if any.
+    pub fn lean(&self, ratio: f64) -> Option<ImbalanceLean> {
+        if self.top_5 > ratio {
+            Some(ImbalanceLean::Bid)
+        } else if self.top_5 * ratio < 1.0 {
+            Some(ImbalanceLean::Ask)
+        } else {
+            None
+        }
+    }
+
+    /// Bid/ask ratios of `bids` against `asks` under `config`; `None` while
+    /// the top five ask levels are empty.
+    pub fn from_levels(
+        bids: impl IntoIterator<Item = (f64, f64)>,
+        asks: impl IntoIterator<Item = (f64, f64)>,
+        config: &ImbalanceConfig,
+    ) -> Option<Self> {
+        let bid_sums = weighted_sums(bids, config.weighting, config.min_level_qty);
+        let ask_sums = weighted_sums(asks, config.weighting, config.min_level_qty);
+        if ask_sums.0 <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            top_5: bid_sums.0 / ask_sums.0,
+            top_10: bid_sums.1 / ask_sums.1,
+            all: bid_sums.2 / ask_sums.2,
+            bid_sums,
+            ask_sums,
+        })
     }
 }
 
@@ -81,24 +293,28 @@ pub struct DepthSnapshotStreamEvent<'a> {
 }
 
 impl<'a> DepthSnapshotStreamEvent<'a> {
-    pub fn decode(data: &'a [u8]) -> Result<Self> {
+    /// Decodes a message body whose root block is `root_block_length`
+    /// bytes, as given by the message header.
+    pub fn decode(data: &'a [u8], root_block_length: u16) -> Result<Self> {
         let mut cursor = SbeCursor::new(data);
 
         let event_time_micros = cursor.read_i64_le()?;
         let book_update_id = cursor.read_i64_le()?;
-        let _price_exponent = cursor.read_i8()?;
+        let price_exponent = cursor.read_i8()?;
         let qty_exponent = cursor.read_i8()?;
+        let price_scale = 10f64.powi(price_exponent as i32);
         let qty_scale = 10f64.powi(qty_exponent as i32);
+        cursor.skip_block(0, root_block_length as usize)?;
 
         let (bids_block_length, num_bids) = read_group_size16(&mut cursor)?;
         let bids_bytes = bids_block_length as usize * num_bids as usize;
         let bids_data = cursor.read_bytes(bids_bytes)?;
-        let bids = DepthLevels::new(bids_data, num_bids, bids_block_length, qty_scale)?;
+        let bids = DepthLevels::new(bids_data, num_bids, bids_block_length, price_scale, qty_scale)?;
 
         let (asks_block_length, num_asks) = read_group_size16(&mut cursor)?;
         let asks_bytes = asks_block_length as usize * num_asks as usize;
         let asks_data = cursor.read_bytes(asks_bytes)?;
-        let asks = DepthLevels::new(asks_data, num_asks, asks_block_length, qty_scale)?;
+        let asks = DepthLevels::new(asks_data, num_asks, asks_block_length, price_scale, qty_scale)?;
 
         let symbol = cursor.read_var_string8()?;
 
@@ -111,52 +327,220 @@ impl<'a> DepthSnapshotStreamEvent<'a> {
         })
     }
 
+    /// Bid/ask ratios of the depth sums under `config`; `None` while the
+    /// top five ask levels are empty.
+    pub fn imbalance(&self, config: &ImbalanceConfig) -> Result<Option<DepthImbalance>> {
+        Ok(DepthImbalance::from_levels(self.bids.iter(), self.asks.iter(), config))
+    }
+
     pub fn print_update(&self) {
-        let (top_5_bids_total_qty, top_10_bids_total_qty, all_bids_total_qty) =
-            match self.bids.sum_qtys_top5_top10_all() {
-                Ok(values) => values,
-                Err(e) => {
-                    warn!("Failed to compute bid quantities: {}", e);
-                    return;
-                }
-            };
-        let (top_5_asks_total_qty, top_10_asks_total_qty, all_asks_total_qty) =
-            match self.asks.sum_qtys_top5_top10_all() {
-                Ok(values) => values,
-                Err(e) => {
-                    warn!("Failed to compute ask quantities: {}", e);
-                    return;
-                }
-            };
-
-        if top_5_asks_total_qty <= 0.0 {
-            return;
-        }
+        debug!(
+            "📚 {} depth: {} bids, {} asks, update {} at event time: {}",
+            self.symbol, self.bids.len(), self.asks.len(), self.book_update_id, self.event_time
+        );
+    }
+}
 
-        let imbalance_top_5 = top_5_bids_total_qty / top_5_asks_total_qty;
-        let imbalance_top_10 = top_10_bids_total_qty / top_10_asks_total_qty;
-        let imbalance_all = all_bids_total_qty / all_asks_total_qty;
+/// Logs `imbalance` of `symbol`'s book and raises an alert for each depth
+/// past the configured ratio either way, up for bids and down for asks,
+/// returning the alerts for persistence.
+/// `closes_in` is the time left on the mapped Kalshi market, if known;
+/// inside the configured close blackout alerts are suppressed or downgraded.
+pub fn report_imbalance(
+    symbol: &str,
+    event_time: DateTime<Utc>,
+    imbalance: &DepthImbalance,
+    config: &ImbalanceConfig,
+    icebergs: &[Iceberg],
+    closes_in: Option<chrono::Duration>,
+) -> Vec<ImbalanceAlert> {
+    let DepthImbalance {
+        top_5: imbalance_top_5,
+        top_10: imbalance_top_10,
+        all: imbalance_all,
+        bid_sums: (top_5_bids_total_qty, top_10_bids_total_qty, all_bids_total_qty),
+        ask_sums: (top_5_asks_total_qty, top_10_asks_total_qty, all_asks_total_qty),
+    } = *imbalance;
 
-        info!(
-            "📕 N_5: bids = {:.2}, asks = {:.2}, ratio = {:.3} at event time: {}, now time: {}",
-            top_5_bids_total_qty, top_5_asks_total_qty, imbalance_top_5, self.event_time, Utc::now()
-        );
-        info!(
-            "📘 N_10: bids = {:.2}, asks = {:.2}, ratio = {:.3} at event time: {}, now time: {}",
-            top_10_bids_total_qty, top_10_asks_total_qty, imbalance_top_10, self.event_time, Utc::now()
+    for (depth, ratio) in [("5", imbalance_top_5), ("10", imbalance_top_10), ("all", imbalance_all)] {
+        metrics::set(
+            names::BINANCE_DEPTH_IMBALANCE,
+            &[("symbol", symbol), ("depth", depth)],
+            ratio,
         );
-        info!(
-            "📙 All: bids = {:.2}, asks = {:.2}, ratio = {:.3} at event time: {}, now time: {}",
-            all_bids_total_qty, all_asks_total_qty, imbalance_all, self.event_time, Utc::now()
-        );
-        if imbalance_top_5 > 100.0 {
-            info!("ALERT: N_5: imbalance\n");
+    }
+
+    let precision = instruments::registry().binance(symbol);
+    let amount = |v: f64| match config.weighting {
+        DepthWeighting::Quantity => precision.quantity(v),
+        DepthWeighting::Notional => format!("${:.2}", v),
+    };
+    debug!(
+        "📕 N_5: bids = {}, asks = {}, ratio = {:.3} at event time: {}, now time: {}",
+        amount(top_5_bids_total_qty), amount(top_5_asks_total_qty),
+        imbalance_top_5, event_time, Utc::now()
+    );
+    debug!(
+        "📘 N_10: bids = {}, asks = {}, ratio = {:.3} at event time: {}, now time: {}",
+        amount(top_10_bids_total_qty), amount(top_10_asks_total_qty),
+        imbalance_top_10, event_time, Utc::now()
+    );
+    debug!(
+        "📙 All: bids = {}, asks = {}, ratio = {:.3} at event time: {}, now time: {}",
+        amount(all_bids_total_qty), amount(all_asks_total_qty),
+        imbalance_all, event_time, Utc::now()
+    );
+    let kalshi_series = instruments::registry().kalshi_series(symbol);
+    let iceberg_context: String = icebergs
+        .iter()
+        .map(|i| {
+            format!(
+                "; {} iceberg at {} ({} refills)",
+                i.side_label(),
+                precision.price(i.price),
+                i.refills
+            )
+        })
+        .collect();
+    let alert_ratio = calendar::alert_threshold(config.alert_ratio);
+    let mut alerts = Vec::new();
+    for (depth, ratio, bid_qty, ask_qty) in [
+        ("N_5", imbalance_top_5, top_5_bids_total_qty, top_5_asks_total_qty),
+        ("N_10", imbalance_top_10, top_10_bids_total_qty, top_10_asks_total_qty),
+        ("All", imbalance_all, all_bids_total_qty, all_asks_total_qty),
+    ] {
+        let direction = if ratio > alert_ratio {
+            AlertDirection::Up
+        } else if ratio * alert_ratio < 1.0 {
+            AlertDirection::Down
+        } else {
+            continue;
+        };
+        // Ask-heavy ratios read better inverted
+        let shown = match direction {
+            AlertDirection::Up => format!("{:.1}", ratio),
+            AlertDirection::Down => format!("1/{:.1}", 1.0 / ratio),
+        };
+        let near_close = closes_in.filter(|left| config.in_close_blackout(*left));
+        if near_close.is_some() {
+            state::session().record_suppression(SUPPRESSED_NEAR_CLOSE);
+            metrics::inc(
+                names::ALERTS_SUPPRESSED_TOTAL,
+                &[("symbol", symbol), ("reason", SUPPRESSED_NEAR_CLOSE)],
+            );
+        }
+        if let (Some(left), BlackoutAction::Suppress) = (near_close, config.close_blackout_action) {
+            debug!(
+                "Suppressed {} imbalance {} on {}: Kalshi market closes in {}s",
+                depth, shown, symbol, left.num_seconds()
+            );
+            continue;
         }
-        if imbalance_top_10 > 100.0 {
-            info!("ALERT: N_10: imbalance\n");
+        let event_id = EventId::new(EventKind::Alert);
+        let record = AlertRecord {
+            event_id: event_id.to_string(),
+            timestamp: event_time,
+            symbol: symbol.to_string(),
+            kalshi_series: kalshi_series.clone(),
+            message: format!("{} depth imbalance {}{}", depth, shown, iceberg_context),
+            imbalance_ratio: Some(ratio),
+            tags: tags::current().clone(),
+            calendar_event: calendar::active_event(),
+            direction: Some(direction),
+        };
+        if let Some(left) = near_close {
+            info!(
+                "Downgraded {} imbalance alert [{}] on {}: Kalshi market closes in {}s",
+                depth, event_id, symbol, left.num_seconds()
+            );
+            status::board().record_downgraded_alert(AlertRecord {
+                message: format!(
+                    "{} (downgraded, Kalshi market closes in {}s)",
+                    record.message,
+                    left.num_seconds().max(0)
+                ),
+                ..record
+            });
+            continue;
         }
-        if imbalance_all > 100.0 {
-            info!("ALERT: All: imbalance\n");
+        info!(
+            "ALERT [{}]: {}: imbalance on {} (kalshi series: {})\n",
+            event_id, depth, symbol, kalshi_series.as_deref().unwrap_or("unmapped")
+        );
+        metrics::inc(names::IMBALANCE_ALERTS_TOTAL, &[("symbol", symbol)]);
+        let message = record.message.clone();
+        status::board().record_alert(record);
+        alerts.push(ImbalanceAlert {
+            event_id,
+            timestamp: event_time,
+            symbol: symbol.to_string(),
+            depth,
+            ratio,
+            direction,
+            message,
+            bid_qty,
+            ask_qty,
+            icebergs: icebergs.to_vec(),
+        });
+    }
+    alerts
+}
+
+/// One `@depth` diff: every level changed between the two update ids with
+/// its new quantity, 0 for a removed level.
+#[derive(Debug, Clone)]
+pub struct DepthDiffStreamEvent<'a> {
+    pub event_time: DateTime<Utc>,
+    pub first_book_update_id: i64,
+    pub last_book_update_id: i64,
+    pub bids: DepthLevels<'a>,
+    pub asks: DepthLevels<'a>,
+    pub symbol: &'a str,
+}
+
+impl<'a> DepthDiffStreamEvent<'a> {
+    /// Decodes a message body whose root block is `root_block_length`
+    /// bytes, as given by the message header.
+    pub fn decode(data: &'a [u8], root_block_length: u16) -> Result<Self> {
+        let mut cursor = SbeCursor::new(data);
+
+        let event_time_micros = cursor.read_i64_le()?;
+        let first_book_update_id = cursor.read_i64_le()?;
+        let last_book_update_id = cursor.read_i64_le()?;
+        let price_exponent = cursor.read_i8()?;
+        let qty_exponent = cursor.read_i8()?;
+        let price_scale = 10f64.powi(price_exponent as i32);
+        let qty_scale = 10f64.powi(qty_exponent as i32);
+        cursor.skip_block(0, root_block_length as usize)?;
+
+        let (bids_block_length, num_bids) = read_group_size16(&mut cursor)?;
+        let bids_data = cursor.read_bytes(bids_block_length as usize * num_bids as usize)?;
+        let bids = DepthLevels::new(bids_data, num_bids, bids_block_length, price_scale, qty_scale)?;
+
+        let (asks_block_length, num_asks) = read_group_size16(&mut cursor)?;
+        let asks_data = cursor.read_bytes(asks_block_length as usize * num_asks as usize)?;
+        let asks = DepthLevels::new(asks_data, num_asks, asks_block_length, price_scale, qty_scale)?;
+
+        let symbol = cursor.read_var_string8()?;
+
+        Ok(Self {
+            event_time: micros_to_datetime(event_time_micros as u64),
+            first_book_update_id,
+            last_book_update_id,
+            bids,
+            asks,
+            symbol,
+        })
+    }
+
+    /// An owned copy, for diffs a book has to buffer.
+    pub fn to_diff(&self) -> DepthDiff {
+        DepthDiff {
+            first_update_id: self.first_book_update_id,
+            final_update_id: self.last_book_update_id,
+            prev_final_update_id: None,
+            bids: self.bids.iter().collect(),
+            asks: self.asks.iter().collect(),
         }
     }
 }
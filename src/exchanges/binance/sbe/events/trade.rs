@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use tracing::info;
+use tracing::debug;
 use crate::{
     Error,
     error::Result,
@@ -22,11 +22,15 @@ pub struct TradeStreamEvent<'a> {
     pub event_time: DateTime<Utc>,
     pub transact_time: DateTime<Utc>,
     pub last_trade: Option<Trade>,
+    /// Every trade of the event, oldest first
+    pub trades: Vec<Trade>,
     pub symbol: &'a str,
 }
 
 impl<'a> TradeStreamEvent<'a> {
-    pub fn decode(data: &'a [u8]) -> Result<Self> {
+    /// Decodes a message body whose root block is `root_block_length`
+    /// bytes, as given by the message header.
+    pub fn decode(data: &'a [u8], root_block_length: u16) -> Result<Self> {
         let mut cursor = SbeCursor::new(data);
 
         let event_time_micros = cursor.read_i64_le()?;
@@ -35,32 +39,29 @@ impl<'a> TradeStreamEvent<'a> {
         let qty_exponent = cursor.read_i8()?;
         let price_scale = 10f64.powi(price_exponent as i32);
         let qty_scale = 10f64.powi(qty_exponent as i32);
+        cursor.skip_block(0, root_block_length as usize)?;
 
         let (block_length, num_trades) = read_group_size(&mut cursor)?;
         let block_length = block_length as usize;
 
-        let last_trade = if num_trades > 0 {
-            if num_trades > 1 {
-                let skip_bytes = (num_trades - 1) as usize * block_length;
-                cursor.skip(skip_bytes)?;
-            }
+        if num_trades > 0 && block_length < 25 {
+            return Err(Error::SbeDecode(format!(
+                "Trade block too short: need at least 25 bytes, have {} bytes",
+                block_length
+            )));
+        }
 
+        let mut trades = Vec::with_capacity(num_trades as usize);
+        for _ in 0..num_trades {
             let position_before = cursor.position();
             if cursor.remaining() < block_length {
                 return Err(Error::SbeDecode(format!(
-                    "Not enough data for last trade: need {} bytes, have {} bytes",
+                    "Not enough data for trade: need {} bytes, have {} bytes",
                     block_length,
                     cursor.remaining()
                 )));
             }
 
-            if block_length < 25 {
-                return Err(Error::SbeDecode(format!(
-                    "Trade block too short: need at least 25 bytes, have {} bytes",
-                    block_length
-                )));
-            }
-
             let id = cursor.read_i64_le()?;
             let price_mantissa = cursor.read_i64_le()?;
             let price = price_mantissa as f64 * price_scale;
@@ -73,15 +74,14 @@ impl<'a> TradeStreamEvent<'a> {
                 cursor.skip(block_length - bytes_read)?;
             }
 
-            Some(Trade {
+            trades.push(Trade {
                 id,
                 price,
                 qty,
                 is_buyer_maker,
-            })
-        } else {
-            None
-        };
+            });
+        }
+        let last_trade = trades.last().cloned();
 
         let symbol = cursor.read_var_string8()?;
 
@@ -89,12 +89,13 @@ impl<'a> TradeStreamEvent<'a> {
             event_time: micros_to_datetime(event_time_micros as u64),
             transact_time: micros_to_datetime(transact_time_micros as u64),
             last_trade,
+            trades,
             symbol,
         })
     }
 
     pub fn print_update(&self) {
         let last_price = self.last_trade.as_ref().map(|t| t.price).unwrap_or(0.0);
-        info!("⚡ price = {}\n at event time: {}, now time: {}", last_price, self.event_time, Utc::now());
+        debug!("⚡ price = {}\n at event time: {}, now time: {}", last_price, self.event_time, Utc::now());
     }
 }
@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
+use tracing::warn;
 
+use crate::exchanges::event::{BookEvent, EventMeta, Exchange, MarketEvent, QuoteEvent, TradeEvent};
+use crate::exchanges::TradeSide;
 use crate::exchanges::binance::sbe::events::{
     bid_ask::BestBidAskStreamEvent,
-    depth::DepthSnapshotStreamEvent,
+    depth::{DepthDiffStreamEvent, DepthSnapshotStreamEvent},
     trade::TradeStreamEvent,
 };
 
@@ -12,14 +15,27 @@ pub enum SbeMessage<'a> {
     Trade(TradeStreamEvent<'a>),
     BestBidAsk(BestBidAskStreamEvent<'a>),
     DepthSnapshot(DepthSnapshotStreamEvent<'a>),
+    DepthDiff(DepthDiffStreamEvent<'a>),
 }
 
 impl<'a> SbeMessage<'a> {
+    /// Logs the message. Imbalance alerts are raised from the normalized
+    /// event, see [`crate::exchanges::depth::ImbalanceDetector`].
     pub fn print_update(&self) {
         match self {
             SbeMessage::Trade(e) => e.print_update(),
             SbeMessage::BestBidAsk(e) => e.print_update(),
             SbeMessage::DepthSnapshot(e) => e.print_update(),
+            SbeMessage::DepthDiff(_) => {}
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SbeMessage::Trade(_) => "trade",
+            SbeMessage::BestBidAsk(_) => "best_bid_ask",
+            SbeMessage::DepthSnapshot(_) => "depth_snapshot",
+            SbeMessage::DepthDiff(_) => "depth_diff",
         }
     }
 
@@ -28,6 +44,7 @@ impl<'a> SbeMessage<'a> {
             SbeMessage::Trade(e) => &e.symbol,
             SbeMessage::BestBidAsk(e) => &e.symbol,
             SbeMessage::DepthSnapshot(e) => &e.symbol,
+            SbeMessage::DepthDiff(e) => &e.symbol,
         }
     }
 
@@ -36,6 +53,58 @@ impl<'a> SbeMessage<'a> {
             SbeMessage::Trade(e) => e.event_time,
             SbeMessage::BestBidAsk(e) => e.event_time,
             SbeMessage::DepthSnapshot(e) => e.event_time,
+            SbeMessage::DepthDiff(e) => e.event_time,
+        }
+    }
+
+    /// The exchange-neutral form of this message. The partial depth stream
+    /// carries the whole top of book, so it maps to a snapshot.
+    pub fn to_event(&self) -> Option<MarketEvent> {
+        let meta = EventMeta::new(Exchange::Binance, self.symbol(), self.timestamp());
+        match self {
+            SbeMessage::Trade(e) => {
+                let trade = e.last_trade.as_ref()?;
+                Some(MarketEvent::Trade(TradeEvent {
+                    meta,
+                    trade_id: trade.id,
+                    price: trade.price,
+                    quantity: trade.qty,
+                    // A resting buyer means the seller took liquidity.
+                    side: if trade.is_buyer_maker { TradeSide::Sell } else { TradeSide::Buy },
+                }))
+            }
+            SbeMessage::BestBidAsk(e) => Some(MarketEvent::Quote(QuoteEvent {
+                meta,
+                bid: e.bid_price,
+                bid_qty: e.bid_qty,
+                ask: e.ask_price,
+                ask_qty: e.ask_qty,
+                binary: None,
+            })),
+            SbeMessage::DepthSnapshot(e) => match (e.bids.levels(), e.asks.levels()) {
+                (Ok(bids), Ok(asks)) => Some(MarketEvent::BookSnapshot(BookEvent { meta, bids, asks })),
+                (Err(err), _) | (_, Err(err)) => {
+                    warn!("Failed to decode depth levels for {}: {}", e.symbol, err);
+                    None
+                }
+            },
+            SbeMessage::DepthDiff(e) => match (e.bids.levels(), e.asks.levels()) {
+                (Ok(bids), Ok(asks)) => Some(MarketEvent::BookDelta(BookEvent { meta, bids, asks })),
+                (Err(err), _) | (_, Err(err)) => {
+                    warn!("Failed to decode depth levels for {}: {}", e.symbol, err);
+                    None
+                }
+            },
+        }
+    }
+
+    /// Monotonic per-stream id: the trade id for trades, the book update id otherwise.
+    pub fn sequence_id(&self) -> Option<i64> {
+        match self {
+            SbeMessage::Trade(e) => e.last_trade.as_ref().map(|t| t.id),
+            SbeMessage::BestBidAsk(e) => Some(e.book_update_id),
+            SbeMessage::DepthSnapshot(e) => Some(e.book_update_id),
+            SbeMessage::DepthDiff(e) => Some(e.last_book_update_id),
         }
     }
 }
@@ -1,4 +1,5 @@
 pub mod decoder;
+pub mod encoder;
 pub mod events;
 pub mod messages;
 pub mod types;
@@ -58,6 +58,18 @@ pub enum SbeMessageType {
 }
 
 impl SbeMessageType {
+    /// Root block length of the template in the schema version decoded
+    /// here; a longer block carries fields appended by a newer version.
+    pub fn known_block_length(&self) -> Option<u16> {
+        match self {
+            SbeMessageType::Trade => Some(18),
+            SbeMessageType::BestBidAsk => Some(50),
+            SbeMessageType::DepthSnapshot => Some(18),
+            SbeMessageType::DepthDiff => Some(26),
+            SbeMessageType::Unknown(_) => None,
+        }
+    }
+
     pub fn from_template_id(id: u16) -> Self {
         match id {
             TEMPLATE_TRADES_STREAM => SbeMessageType::Trade,
@@ -24,6 +24,21 @@ impl<'a> SbeCursor<'a> {
         self.advance(len)
     }
 
+    /// Moves to the end of a `block_length` byte block that started at
+    /// `start`, past any fields a newer schema appended after those already
+    /// read. Fails if the block is shorter than what was read.
+    pub fn skip_block(&mut self, start: usize, block_length: usize) -> Result<()> {
+        let end = start + block_length;
+        if self.pos > end {
+            return Err(Error::SbeDecode(format!(
+                "Block of {} bytes is shorter than the {} bytes of known fields",
+                block_length,
+                self.pos - start
+            )));
+        }
+        self.advance(end - self.pos)
+    }
+
     pub fn read_u8(&mut self) -> Result<u8> {
         if self.remaining() < 1 {
             return Err(Error::SbeDecode("Not enough data to read u8".into()));
@@ -0,0 +1,196 @@
+//! Rate at which quantity is added to and pulled from the top of the
+//! Binance book.
+//!
+//! Consecutive depth snapshots are diffed level by level: growth at a price
+//! counts as added, shrinkage or a vanished level as cancelled. Levels that
+//! drop out of the top N because the book moved are ignored. Trades show up
+//! as cancels, since the snapshot can't tell them apart.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use super::sbe::events::depth::DepthSnapshotStreamEvent;
+use crate::calendar;
+use crate::config::DepthVelocityConfig;
+use crate::exchanges::PriceLevel;
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::status::{self, AlertDirection, AlertRecord};
+use crate::tags;
+use crate::utils::event_id::{EventId, EventKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BookSide {
+    Bid,
+    Ask,
+}
+
+impl BookSide {
+    fn label(&self) -> &'static str {
+        match self {
+            BookSide::Bid => "bid",
+            BookSide::Ask => "ask",
+        }
+    }
+}
+
+#[derive(Default)]
+struct SideFlow {
+    levels: Vec<PriceLevel>,
+    /// `(time, added, cancelled)` per snapshot inside the window
+    window: VecDeque<(DateTime<Utc>, f64, f64)>,
+    add_alerting: bool,
+    cancel_alerting: bool,
+}
+
+impl SideFlow {
+    /// Diffs `levels` against the previous snapshot and returns the add and
+    /// cancel rates per second over the window.
+    fn update(&mut self, side: BookSide, levels: Vec<PriceLevel>, time: DateTime<Utc>, window_secs: u64) -> (f64, f64) {
+        let mut added = 0.0;
+        let mut cancelled = 0.0;
+
+        if !self.levels.is_empty() {
+            let quantity_at = |levels: &[PriceLevel], price: f64| {
+                levels.iter().find(|l| l.price == price).map(|l| l.quantity)
+            };
+            for level in &levels {
+                let change = level.quantity - quantity_at(&self.levels, level.price).unwrap_or(0.0);
+                if change > 0.0 {
+                    added += change;
+                } else {
+                    cancelled -= change;
+                }
+            }
+            if let Some(worst) = levels.last().map(|l| l.price) {
+                for old in &self.levels {
+                    let in_view = match side {
+                        BookSide::Bid => old.price >= worst,
+                        BookSide::Ask => old.price <= worst,
+                    };
+                    if in_view && quantity_at(&levels, old.price).is_none() {
+                        cancelled += old.quantity;
+                    }
+                }
+            }
+        }
+        self.levels = levels;
+
+        self.window.push_back((time, added, cancelled));
+        let cutoff = time - chrono::Duration::seconds(window_secs as i64);
+        while self.window.front().is_some_and(|(t, _, _)| *t < cutoff) {
+            self.window.pop_front();
+        }
+        let secs = window_secs.max(1) as f64;
+        let (added, cancelled) = self
+            .window
+            .iter()
+            .fold((0.0, 0.0), |(a, c), (_, added, cancelled)| (a + added, c + cancelled));
+        (added / secs, cancelled / secs)
+    }
+}
+
+/// Per-symbol depth flow, alerting when the add or cancel rate of a side
+/// crosses its threshold.
+pub struct DepthVelocity {
+    config: DepthVelocityConfig,
+    symbols: HashMap<String, (SideFlow, SideFlow)>,
+}
+
+impl DepthVelocity {
+    pub fn new(config: DepthVelocityConfig) -> Self {
+        Self {
+            config,
+            symbols: HashMap::new(),
+        }
+    }
+
+    /// Swaps thresholds and window in place; the flow seen so far is kept.
+    pub fn set_config(&mut self, config: DepthVelocityConfig) {
+        self.config = config;
+    }
+
+    pub fn observe(&mut self, event: &DepthSnapshotStreamEvent<'_>) {
+        let (bids, asks) = match (event.bids.levels(), event.asks.levels()) {
+            (Ok(bids), Ok(asks)) => (bids, asks),
+            (Err(e), _) | (_, Err(e)) => {
+                warn!("Failed to decode depth levels for velocity: {}", e);
+                return;
+            }
+        };
+        let levels = self.config.levels;
+        let top = |mut l: Vec<PriceLevel>| {
+            l.truncate(levels);
+            l
+        };
+
+        let config = self.config;
+        let (bid_flow, ask_flow) = self.symbols.entry(event.symbol.to_string()).or_default();
+        for (side, flow, levels) in [
+            (BookSide::Bid, bid_flow, top(bids)),
+            (BookSide::Ask, ask_flow, top(asks)),
+        ] {
+            let (add_rate, cancel_rate) = flow.update(side, levels, event.event_time, config.window_secs);
+            metrics::set(
+                names::BINANCE_DEPTH_VELOCITY,
+                &[("symbol", event.symbol), ("side", side.label()), ("kind", "add")],
+                add_rate,
+            );
+            metrics::set(
+                names::BINANCE_DEPTH_VELOCITY,
+                &[("symbol", event.symbol), ("side", side.label()), ("kind", "cancel")],
+                cancel_rate,
+            );
+
+            if let Some(threshold) = config.add_rate_threshold {
+                let above = add_rate > calendar::alert_threshold(threshold);
+                if above && !flow.add_alerting {
+                    Self::alert(event, side, "stacking", add_rate);
+                }
+                flow.add_alerting = above;
+            }
+            if let Some(threshold) = config.cancel_rate_threshold {
+                let above = cancel_rate > calendar::alert_threshold(threshold);
+                if above && !flow.cancel_alerting {
+                    Self::alert(event, side, "pulling", cancel_rate);
+                }
+                flow.cancel_alerting = above;
+            }
+        }
+    }
+
+    fn alert(event: &DepthSnapshotStreamEvent<'_>, side: BookSide, kind: &str, rate: f64) {
+        let event_id = EventId::new(EventKind::Alert);
+        let kalshi_series = instruments::registry().kalshi_series(event.symbol);
+        let precision = instruments::registry().binance(event.symbol);
+        info!(
+            "ALERT [{}]: {} {} {}/s on {} (kalshi series: {})",
+            event_id,
+            side.label(),
+            kind,
+            precision.quantity(rate),
+            event.symbol,
+            kalshi_series.as_deref().unwrap_or("unmapped")
+        );
+        metrics::inc(
+            names::DEPTH_VELOCITY_ALERTS_TOTAL,
+            &[("symbol", event.symbol), ("side", side.label()), ("kind", kind)],
+        );
+        status::board().record_alert(AlertRecord {
+            event_id: event_id.to_string(),
+            timestamp: event.event_time,
+            symbol: event.symbol.to_string(),
+            kalshi_series,
+            message: format!("{} {} at {}/s", side.label(), kind, precision.quantity(rate)),
+            imbalance_ratio: None,
+            tags: tags::current().clone(),
+            calendar_event: calendar::active_event(),
+            direction: Some(match (side, kind) {
+                (BookSide::Bid, "stacking") | (BookSide::Ask, "pulling") => AlertDirection::Up,
+                _ => AlertDirection::Down,
+            }),
+        });
+    }
+}
@@ -0,0 +1,69 @@
+//! Drops messages an exchange resends after a reconnect-and-resubscribe.
+//!
+//! Each stream carries a monotonically increasing id (Binance trade id or
+//! book update id, Kalshi per-subscription `seq`), so remembering the highest
+//! id seen per stream is enough to recognise replays.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use tracing::debug;
+
+use crate::metrics::{self, names};
+
+pub struct SequenceFilter<K> {
+    exchange: &'static str,
+    stream: &'static str,
+    last_seen: HashMap<K, i64>,
+}
+
+impl<K: Hash + Eq> SequenceFilter<K> {
+    pub fn new(exchange: &'static str, stream: &'static str) -> Self {
+        Self {
+            exchange,
+            stream,
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Records `id` for `key` and reports whether it was already seen.
+    pub fn is_duplicate<Q>(&mut self, key: &Q, id: i64) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = K> + ?Sized,
+    {
+        match self.last_seen.get_mut(key) {
+            Some(last) if id <= *last => {
+                debug!("Dropping duplicate {} {} message: id {} <= {}", self.exchange, self.stream, id, last);
+                metrics::inc(
+                    names::DUPLICATES_DROPPED_TOTAL,
+                    &[("exchange", self.exchange), ("stream", self.stream)],
+                );
+                true
+            }
+            Some(last) => {
+                *last = id;
+                false
+            }
+            None => {
+                self.last_seen.insert(key.to_owned(), id);
+                false
+            }
+        }
+    }
+
+    /// Forgets one stream, e.g. a subscription that was cancelled.
+    pub fn forget<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.last_seen.remove(key);
+    }
+
+    /// Forgets all ids, for streams whose numbering restarts on a new connection.
+    pub fn reset(&mut self) {
+        self.last_seen.clear();
+    }
+}
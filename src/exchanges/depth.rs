@@ -0,0 +1,351 @@
+//! Reference depth feeds for the imbalance detector.
+//!
+//! Each tracked pair's imbalance is detected on one venue's book, chosen
+//! with `IMBALANCE_SOURCES=BTCUSDT=okx,ETHUSDT=binance_futures`; pairs not
+//! listed stay on the configured Binance market. A [`DepthSource`] picks
+//! full books out of a venue's normalized event stream and an
+//! [`ImbalanceDetector`] fed that stream like any other sink raises the
+//! alerts, so live SBE, futures JSON, OKX and replayed events all alert
+//! identically and the alert writer and monitors downstream see no
+//! difference.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{mpsc, watch};
+use tracing::{error, info};
+
+use crate::config::BinanceConfig;
+use crate::error::{Error, Result};
+use crate::exchanges::binance::iceberg::Iceberg;
+use crate::exchanges::binance::sbe::events::depth::{report_imbalance, DepthImbalance, ImbalanceAlert};
+use crate::exchanges::event::{AlertEvent, BookEvent, EventMeta, Exchange, MarketEvent};
+use crate::exchanges::PriceLevel;
+use crate::instruments;
+use crate::sinks::SINK_CHANNEL_BUFFER;
+use crate::state::{BinanceState, KalshiState};
+use crate::status;
+use crate::trader::venue::BinanceMarket;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepthVenue {
+    BinanceSpot,
+    BinanceFutures,
+    Okx,
+}
+
+impl DepthVenue {
+    pub fn binance(market: BinanceMarket) -> Self {
+        match market {
+            BinanceMarket::Spot => DepthVenue::BinanceSpot,
+            BinanceMarket::Futures => DepthVenue::BinanceFutures,
+        }
+    }
+
+    pub fn exchange(&self) -> Exchange {
+        match self {
+            DepthVenue::BinanceSpot | DepthVenue::BinanceFutures => Exchange::Binance,
+            DepthVenue::Okx => Exchange::Okx,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DepthVenue::BinanceSpot => "binance_spot",
+            DepthVenue::BinanceFutures => "binance_futures",
+            DepthVenue::Okx => "okx",
+        }
+    }
+
+    /// Queue name of the venue's detector on the status board.
+    fn queue(&self) -> &'static str {
+        match self {
+            DepthVenue::BinanceSpot => "imbalance_binance_spot",
+            DepthVenue::BinanceFutures => "imbalance_binance_futures",
+            DepthVenue::Okx => "imbalance_okx",
+        }
+    }
+}
+
+impl fmt::Display for DepthVenue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DepthVenue {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "binance_spot" | "spot" => Ok(DepthVenue::BinanceSpot),
+            "binance_futures" | "futures" | "usdm" => Ok(DepthVenue::BinanceFutures),
+            "okx" => Ok(DepthVenue::Okx),
+            other => Err(Error::Config(format!(
+                "Unknown depth source '{}', expected binance_spot, binance_futures or okx",
+                other
+            ))),
+        }
+    }
+}
+
+/// Parses `IMBALANCE_SOURCES`: comma separated `PAIR=VENUE` entries.
+pub fn parse_sources(spec: &str) -> Result<Vec<(String, DepthVenue)>> {
+    let mut sources: Vec<(String, DepthVenue)> = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let (pair, venue) = entry.split_once('=').ok_or_else(|| {
+            Error::Config(format!("Invalid IMBALANCE_SOURCES entry '{}', expected PAIR=VENUE", entry))
+        })?;
+        let pair = pair.trim().to_uppercase();
+        if sources.iter().any(|(p, _)| *p == pair) {
+            return Err(Error::Config(format!("IMBALANCE_SOURCES lists {} more than once", pair)));
+        }
+        sources.push((pair, venue.parse()?));
+    }
+    Ok(sources)
+}
+
+/// The venue `pair`'s imbalance is detected on: its entry in `sources`,
+/// else `default`.
+pub fn venue_for(sources: &[(String, DepthVenue)], pair: &str, default: DepthVenue) -> DepthVenue {
+    sources
+        .iter()
+        .find(|(p, _)| p == pair)
+        .map_or(default, |(_, venue)| *venue)
+}
+
+/// Pairs `sources` moves to `venue`.
+pub fn pairs_on(sources: &[(String, DepthVenue)], venue: DepthVenue) -> Vec<String> {
+    sources
+        .iter()
+        .filter(|(_, v)| *v == venue)
+        .map(|(pair, _)| pair.clone())
+        .collect()
+}
+
+/// A pair's full book as of `timestamp`.
+#[derive(Debug, Clone)]
+pub struct DepthBook {
+    /// In Binance symbol form, e.g. `BTCUSDT`
+    pub pair: String,
+    pub timestamp: DateTime<Utc>,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+}
+
+impl DepthBook {
+    fn from_event(pair: String, book: &BookEvent) -> Self {
+        Self {
+            pair,
+            timestamp: book.meta.timestamp,
+            bids: book.bids.clone(),
+            asks: book.asks.clone(),
+        }
+    }
+}
+
+/// A venue's depth feed as the imbalance detector reads it.
+pub trait DepthSource: Send + Sync {
+    fn venue(&self) -> DepthVenue;
+
+    /// The full book `event` carries or completes; `None` for other
+    /// venues' events and anything that isn't depth.
+    fn book(&self, event: &MarketEvent) -> Option<DepthBook>;
+
+    /// Levels flagged as probable icebergs on `pair`, attached to its alerts.
+    fn icebergs(&self, _pair: &str) -> Vec<Iceberg> {
+        Vec::new()
+    }
+}
+
+/// Books of a Binance client streaming `market`: its top-20 snapshots,
+/// and with `BINANCE_LOCAL_BOOK` the local book each diff is applied to.
+/// The local book and icebergs are read from the client's `state`.
+pub struct BinanceDepth {
+    market: BinanceMarket,
+    state: Option<Arc<BinanceState>>,
+}
+
+impl BinanceDepth {
+    pub fn new(market: BinanceMarket, state: Option<Arc<BinanceState>>) -> Self {
+        Self { market, state }
+    }
+}
+
+impl DepthSource for BinanceDepth {
+    fn venue(&self) -> DepthVenue {
+        DepthVenue::binance(self.market)
+    }
+
+    fn book(&self, event: &MarketEvent) -> Option<DepthBook> {
+        match event {
+            MarketEvent::BookSnapshot(book) if book.meta.exchange == Exchange::Binance => {
+                Some(DepthBook::from_event(book.meta.instrument.clone(), book))
+            }
+            MarketEvent::BookDelta(delta) if delta.meta.exchange == Exchange::Binance => {
+                let local = self.state.as_ref()?.books.get(&delta.meta.instrument)?;
+                local.is_synced().then(|| DepthBook {
+                    pair: delta.meta.instrument.clone(),
+                    timestamp: delta.meta.timestamp,
+                    bids: local.bids(usize::MAX),
+                    asks: local.asks(usize::MAX),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn icebergs(&self, pair: &str) -> Vec<Iceberg> {
+        self.state
+            .as_ref()
+            .and_then(|state| state.icebergs.get(pair).map(|i| i.clone()))
+            .unwrap_or_default()
+    }
+}
+
+/// OKX `books5` snapshots; `BTC-USDT` and `BTC-USDT-SWAP` both read as
+/// `BTCUSDT`.
+pub struct OkxDepth;
+
+impl DepthSource for OkxDepth {
+    fn venue(&self) -> DepthVenue {
+        DepthVenue::Okx
+    }
+
+    fn book(&self, event: &MarketEvent) -> Option<DepthBook> {
+        match event {
+            MarketEvent::BookSnapshot(book) if book.meta.exchange == Exchange::Okx => {
+                let pair: String = book.meta.instrument.split('-').take(2).collect();
+                Some(DepthBook::from_event(pair.to_uppercase(), book))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Depth imbalance alerting on one [`DepthSource`], for the pairs assigned
+/// to its venue. Thresholds and assignments follow the Binance config as
+/// reloaded.
+pub struct ImbalanceDetector<S> {
+    source: S,
+    config: watch::Receiver<BinanceConfig>,
+    alert_tx: Option<mpsc::Sender<ImbalanceAlert>>,
+    events_tx: Option<mpsc::Sender<MarketEvent>>,
+    /// Close times of the mapped Kalshi markets, for the close blackout
+    kalshi: Option<Arc<KalshiState>>,
+}
+
+impl<S: DepthSource + 'static> ImbalanceDetector<S> {
+    pub fn new(source: S, config: watch::Receiver<BinanceConfig>) -> Self {
+        Self {
+            source,
+            config,
+            alert_tx: None,
+            events_tx: None,
+            kalshi: None,
+        }
+    }
+
+    /// Hands raised alerts to `alert_tx`, e.g. an
+    /// [`ImbalanceAlertWriter`](crate::exchanges::binance::alerts::ImbalanceAlertWriter).
+    pub fn with_alerts(mut self, alert_tx: Option<mpsc::Sender<ImbalanceAlert>>) -> Self {
+        self.alert_tx = alert_tx;
+        self
+    }
+
+    /// Publishes raised alerts as [`MarketEvent::Alert`] to `events_tx`,
+    /// normally the sinks.
+    pub fn with_events(mut self, events_tx: Option<mpsc::Sender<MarketEvent>>) -> Self {
+        self.events_tx = events_tx;
+        self
+    }
+
+    /// Looks up each pair's current Kalshi market in `kalshi`, so alerts
+    /// close to its expiry fall under `IMBALANCE_CLOSE_BLACKOUT_SECS`.
+    pub fn with_kalshi(mut self, kalshi: Option<Arc<KalshiState>>) -> Self {
+        self.kalshi = kalshi;
+        self
+    }
+
+    /// Starts the detector; feed it the source venue's events like a sink.
+    pub fn spawn(self) -> mpsc::Sender<MarketEvent> {
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue(self.source.venue().queue(), &tx);
+        tokio::spawn(self.run(rx));
+        tx
+    }
+
+    async fn run(self, mut rx: mpsc::Receiver<MarketEvent>) {
+        let venue = self.source.venue();
+        info!("⚖️ Detecting depth imbalance on {}", venue);
+        while let Some(event) = rx.recv().await {
+            for alert in self.process(&event) {
+                if let Some(events_tx) = &self.events_tx {
+                    if let Err(e) = events_tx.try_send(self.alert_event(&alert)) {
+                        error!("Failed to publish imbalance alert: {}", e);
+                    }
+                }
+                if let Some(alert_tx) = &self.alert_tx {
+                    if let Err(e) = alert_tx.try_send(alert) {
+                        error!("Failed to queue imbalance alert: {}", e);
+                    }
+                }
+            }
+        }
+        info!("{} imbalance detector shutting down", venue);
+    }
+
+    /// Raises alerts for `event` if it carries a full book of a pair
+    /// assigned to this venue.
+    pub fn process(&self, event: &MarketEvent) -> Vec<ImbalanceAlert> {
+        let Some(book) = self.source.book(event) else {
+            return Vec::new();
+        };
+        let imbalance_config = {
+            let config = self.config.borrow();
+            let default = DepthVenue::binance(config.market);
+            if venue_for(&config.depth_sources, &book.pair, default) != self.source.venue() {
+                return Vec::new();
+            }
+            config.imbalance
+        };
+        let Some(imbalance) = DepthImbalance::from_levels(
+            book.bids.iter().map(|l| (l.price, l.quantity)),
+            book.asks.iter().map(|l| (l.price, l.quantity)),
+            &imbalance_config,
+        ) else {
+            return Vec::new();
+        };
+        let icebergs = self.source.icebergs(&book.pair);
+        let closes_in = self.kalshi_closes_in(&book.pair, book.timestamp);
+        report_imbalance(&book.pair, book.timestamp, &imbalance, &imbalance_config, &icebergs, closes_in)
+    }
+
+    fn alert_event(&self, alert: &ImbalanceAlert) -> MarketEvent {
+        let series = instruments::registry().kalshi_series(&alert.symbol);
+        let kalshi_ticker = series
+            .as_ref()
+            .zip(self.kalshi.as_ref())
+            .and_then(|(series, kalshi)| kalshi.current_market_for_series(series));
+        let mut meta = EventMeta::new(self.source.venue().exchange(), alert.symbol.clone(), alert.timestamp);
+        meta.series = series;
+        MarketEvent::Alert(AlertEvent {
+            meta,
+            event_id: alert.event_id.to_string(),
+            message: alert.message.clone(),
+            direction: Some(alert.direction),
+            kalshi_ticker,
+        })
+    }
+
+    /// Time from `now` until the current Kalshi market of `pair`'s series
+    /// closes.
+    fn kalshi_closes_in(&self, pair: &str, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        let kalshi = self.kalshi.as_ref()?;
+        let series = instruments::registry().kalshi_series(pair)?;
+        let ticker = kalshi.current_market_for_series(&series)?;
+        Some(kalshi.close_time(&ticker)? - now)
+    }
+}
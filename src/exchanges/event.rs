@@ -0,0 +1,219 @@
+//! Exchange-neutral market events.
+//!
+//! Connectors translate their wire messages into [`MarketEvent`]s at the edge,
+//! so the sinks and anything else downstream handle one type instead of a
+//! Kalshi tick here and a Binance SBE message there.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{PriceLevel, TradeSide};
+use crate::status::AlertDirection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Exchange {
+    Kalshi,
+    Binance,
+    Okx,
+    Polymarket,
+}
+
+impl Exchange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Exchange::Kalshi => "kalshi",
+            Exchange::Binance => "binance",
+            Exchange::Okx => "okx",
+            Exchange::Polymarket => "polymarket",
+        }
+    }
+}
+
+impl fmt::Display for Exchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventMeta {
+    pub exchange: Exchange,
+    /// Exchange symbol: the Binance pair, the OKX instrument id, the
+    /// Polymarket condition id or the Kalshi market ticker.
+    pub instrument: String,
+    /// Kalshi series the market belongs to; `None` for spot pairs.
+    pub series: Option<String>,
+    /// When the contract stops trading, for expiring instruments.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Exchange event time, or receive time where the feed carries none.
+    pub timestamp: DateTime<Utc>,
+    pub received_at: DateTime<Utc>,
+}
+
+impl EventMeta {
+    pub fn new(exchange: Exchange, instrument: impl Into<String>, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            exchange,
+            instrument: instrument.into(),
+            series: None,
+            expires_at: None,
+            timestamp,
+            received_at: Utc::now(),
+        }
+    }
+
+    pub fn with_series(mut self, series: impl Into<String>) -> Self {
+        self.series = Some(series.into());
+        self
+    }
+
+    pub fn with_expiry(mut self, expires_at: Option<DateTime<Utc>>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MarketEvent {
+    Trade(TradeEvent),
+    Quote(QuoteEvent),
+    /// The full book, replacing whatever was held before.
+    BookSnapshot(BookEvent),
+    /// Changed levels only.
+    BookDelta(BookEvent),
+    Lifecycle(LifecycleEvent),
+    Alert(AlertEvent),
+    /// A Kalshi contract's best YES bid or ask moved, in price or size.
+    TopOfBookChanged(KalshiTopOfBookChanged),
+}
+
+impl MarketEvent {
+    pub fn meta(&self) -> &EventMeta {
+        match self {
+            MarketEvent::Trade(e) => &e.meta,
+            MarketEvent::Quote(e) => &e.meta,
+            MarketEvent::BookSnapshot(e) | MarketEvent::BookDelta(e) => &e.meta,
+            MarketEvent::Lifecycle(e) => &e.meta,
+            MarketEvent::Alert(e) => &e.meta,
+            MarketEvent::TopOfBookChanged(e) => &e.meta,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MarketEvent::Trade(_) => "trade",
+            MarketEvent::Quote(_) => "quote",
+            MarketEvent::BookSnapshot(_) => "book_snapshot",
+            MarketEvent::BookDelta(_) => "book_delta",
+            MarketEvent::Lifecycle(_) => "lifecycle",
+            MarketEvent::Alert(_) => "alert",
+            MarketEvent::TopOfBookChanged(_) => "top_of_book",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeEvent {
+    pub meta: EventMeta,
+    pub trade_id: i64,
+    pub price: f64,
+    pub quantity: f64,
+    /// Aggressor side.
+    pub side: TradeSide,
+}
+
+/// Top of book. For Kalshi contracts `bid`/`ask` are the YES side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteEvent {
+    pub meta: EventMeta,
+    pub bid: f64,
+    pub bid_qty: f64,
+    pub ask: f64,
+    pub ask_qty: f64,
+    /// Set for binary-outcome contracts.
+    pub binary: Option<BinaryQuote>,
+}
+
+/// The NO side of a binary contract's top of book, plus the depth the
+/// trader sizes entries against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryQuote {
+    pub no_bid: f64,
+    pub no_ask: f64,
+    /// YES contracts offered at or above the fill-or-kill price.
+    pub yes_ask_depth: i64,
+    /// NO contracts offered at or above the fill-or-kill price.
+    pub no_ask_depth: i64,
+}
+
+/// Book levels, best first. In a delta a quantity of 0 removes the level.
+/// Kalshi books are expressed from the YES side: NO bids appear as YES asks
+/// at `1 - price`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookEvent {
+    pub meta: EventMeta,
+    pub bids: Vec<PriceLevel>,
+    pub asks: Vec<PriceLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    pub meta: EventMeta,
+    /// Exchange status after the event, e.g. `open`, `closed`, `settled`.
+    pub status: String,
+    /// Settlement result, once determined.
+    pub result: Option<String>,
+}
+
+/// An imbalance or signal alert, published back onto the stream so sinks
+/// and the recorder see it next to the events that raised it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub meta: EventMeta,
+    pub event_id: String,
+    pub message: String,
+    /// Which way the alert points the underlying, where its source can tell
+    #[serde(default)]
+    pub direction: Option<AlertDirection>,
+    /// Kalshi market of `meta.series` trading when the alert fired
+    #[serde(default)]
+    pub kalshi_ticker: Option<String>,
+}
+
+/// Best YES bid and ask with their sizes; an empty side reads 0.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TopOfBook {
+    pub bid: f64,
+    pub bid_qty: f64,
+    pub ask: f64,
+    pub ask_qty: f64,
+}
+
+/// A Kalshi contract's top of book before and after the snapshot or delta
+/// that changed it, so consumers need not diff full books.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KalshiTopOfBookChanged {
+    pub meta: EventMeta,
+    pub previous: TopOfBook,
+    pub current: TopOfBook,
+}
+
+impl KalshiTopOfBookChanged {
+    /// How far the best bid moved, in dollars.
+    pub fn bid_move(&self) -> f64 {
+        self.current.bid - self.previous.bid
+    }
+
+    /// How far the best ask moved, in dollars.
+    pub fn ask_move(&self) -> f64 {
+        self.current.ask - self.previous.ask
+    }
+
+    /// Whether only sizes changed, with both prices where they were.
+    pub fn is_size_only(&self) -> bool {
+        self.bid_move() == 0.0 && self.ask_move() == 0.0
+    }
+}
@@ -0,0 +1,163 @@
+//! Kalshi exchange announcements and trading status, polled over REST.
+//!
+//! An announcement concerns us when it names a tracked series, or is a
+//! warning or error for the whole exchange. Each one is alerted once, as a
+//! [`EventKind::Notice`] so execution never trades on it, and the active ones
+//! are listed on `/status` next to whether Kalshi reports trading open.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{info, warn};
+
+use super::api::KalshiApi;
+use super::models::KalshiAnnouncement;
+use crate::calendar;
+use crate::config::AnnouncementsConfig;
+use crate::status::{self, AlertRecord, NoticeView};
+use crate::tags;
+use crate::utils::event_id::{EventId, EventKind};
+
+const HALT_WORDS: &[&str] = &["halt", "paused", "pause", "suspend", "closed early"];
+const RULE_WORDS: &[&str] = &["rule", "terms", "settlement source", "contract change", "amend"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoticeKind {
+    Halt,
+    RuleChange,
+    /// Exchange-wide warning or error that names no tracked series
+    Exchange,
+}
+
+impl NoticeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoticeKind::Halt => "halt",
+            NoticeKind::RuleChange => "rule_change",
+            NoticeKind::Exchange => "exchange",
+        }
+    }
+}
+
+/// What `announcement` means for `tracked` series: its kind and the series
+/// it names, or `None` when it does not concern us.
+pub fn classify(announcement: &KalshiAnnouncement, tracked: &[String]) -> Option<(NoticeKind, Option<String>)> {
+    let message = announcement.message.to_lowercase();
+    let series = tracked
+        .iter()
+        .find(|s| message.contains(&s.to_lowercase()))
+        .cloned();
+    let severe = matches!(announcement.kind.to_lowercase().as_str(), "warning" | "error");
+    let kind = if HALT_WORDS.iter().any(|w| message.contains(w)) {
+        NoticeKind::Halt
+    } else if RULE_WORDS.iter().any(|w| message.contains(w)) {
+        NoticeKind::RuleChange
+    } else {
+        NoticeKind::Exchange
+    };
+    if series.is_none() && !severe {
+        return None;
+    }
+    Some((kind, series))
+}
+
+pub struct AnnouncementWatcher {
+    api: Arc<KalshiApi>,
+    config: AnnouncementsConfig,
+    /// Announcements already alerted, by delivery time and message
+    seen: HashSet<(Option<String>, String)>,
+    trading_active: Option<bool>,
+}
+
+impl AnnouncementWatcher {
+    pub fn spawn(api: Arc<KalshiApi>, config: AnnouncementsConfig) {
+        info!("📣 Polling Kalshi announcements every {}s", config.interval_secs);
+        let watcher = Self {
+            api,
+            config,
+            seen: HashSet::new(),
+            trading_active: None,
+        };
+        tokio::spawn(watcher.run());
+    }
+
+    async fn run(mut self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+        loop {
+            ticker.tick().await;
+            self.poll_status().await;
+            self.poll_announcements().await;
+        }
+    }
+
+    async fn poll_status(&mut self) {
+        let status = match self.api.fetch_exchange_status().await {
+            Ok(status) => status,
+            Err(e) => {
+                warn!("Failed to fetch Kalshi exchange status: {}", e);
+                return;
+            }
+        };
+        let active = status.exchange_active && status.trading_active;
+        status::board().set_kalshi_trading_active(active);
+        match self.trading_active {
+            Some(was) if was != active => {
+                alert(None, format!("Kalshi trading {}", if active { "resumed" } else { "paused" }));
+            }
+            None if !active => alert(None, "Kalshi trading is paused".to_string()),
+            _ => {}
+        }
+        self.trading_active = Some(active);
+    }
+
+    async fn poll_announcements(&mut self) {
+        let announcements = match self.api.fetch_announcements().await {
+            Ok(announcements) => announcements,
+            Err(e) => {
+                warn!("Failed to fetch Kalshi announcements: {}", e);
+                return;
+            }
+        };
+        let tracked = status::board().tracked_series();
+        let mut notices = Vec::new();
+        for announcement in announcements.iter().filter(|a| a.is_active()) {
+            let Some((kind, series)) = classify(announcement, &tracked) else {
+                continue;
+            };
+            let key = (announcement.delivery_time.clone(), announcement.message.clone());
+            if self.seen.insert(key) {
+                alert(
+                    series.as_deref(),
+                    format!("Kalshi {} notice: {}", kind.as_str(), announcement.message),
+                );
+            }
+            notices.push(NoticeView {
+                kind: kind.as_str().to_string(),
+                series,
+                message: announcement.message.clone(),
+                delivered_at: announcement.delivered_at(),
+            });
+        }
+        notices.sort_by_key(|n| Reverse(n.delivered_at));
+        status::board().set_notices(notices);
+    }
+}
+
+fn alert(series: Option<&str>, message: String) {
+    let event_id = EventId::new(EventKind::Notice);
+    info!("ALERT [{}]: {}", event_id, message);
+    status::board().record_alert(AlertRecord {
+        event_id: event_id.to_string(),
+        timestamp: Utc::now(),
+        symbol: series.unwrap_or("kalshi").to_string(),
+        kalshi_series: series.map(str::to_string),
+        message,
+        imbalance_ratio: None,
+        tags: tags::current().clone(),
+        calendar_event: calendar::active_event(),
+        direction: None,
+    });
+}
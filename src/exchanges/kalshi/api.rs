@@ -5,12 +5,18 @@ use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 
 use super::auth::KalshiAuth;
 use super::models::{
-    CreateOrderRequest, CreateOrderResponse, GetOrdersResponse, KalshiMarket,
-    KalshiOrder, MarketsResponse, OrderAction, OrderSide,
+    AnnouncementsResponse, CreateOrderRequest, CreateOrderResponse, GetOrderResponse, GetOrdersResponse,
+    KalshiAnnouncement, KalshiExchangeStatus, KalshiMarket, KalshiMarketPosition, KalshiOrder, KalshiOrderbookSnapshot, KalshiSeries, KalshiTrade,
+    MarketsResponse, OrderAction, OrderSide, OrderbookResponse, PositionsResponse, SeriesResponse,
+    TradesResponse,
 };
-use crate::error::{Error, Result};
+use crate::error::{Error, ErrorContext, Result, ResultExt};
 use crate::constants::KALSHI_REST_URL;
 use crate::exchanges::kalshi::{BatchCancelOrdersRequest, KalshiBatchCancelOrdersResponse, KalshiCancelOrder, OrderType};
+use crate::utils::http::ensure_success;
+
+/// Largest page `GET /markets/trades` serves.
+pub const TRADES_PAGE_LIMIT: u32 = 1000;
 
 pub struct KalshiApi {
     http: HttpClient,
@@ -25,6 +31,12 @@ impl KalshiApi {
         }
     }
 
+    fn context(operation: &str, method: &str, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_exchange("kalshi")
+            .with_endpoint(format!("{} {}", method, path))
+    }
+
     fn auth_headers(&self, method: &str, path: &str) -> Result<HeaderMap> {
         let headers = self.auth.generate_headers(method, path)?;
         let mut map = HeaderMap::new();
@@ -47,6 +59,7 @@ impl KalshiApi {
         &self,
         status: Option<&str>,
         series_ticker: Option<&str>,
+        event_ticker: Option<&str>,
         cursor: Option<&str>,
         limit: Option<u32>,
     ) -> Result<MarketsResponse> {
@@ -58,6 +71,9 @@ impl KalshiApi {
         if let Some(ticker) = series_ticker {
             params.push(format!("series_ticker={}", ticker));
         }
+        if let Some(ticker) = event_ticker {
+            params.push(format!("event_ticker={}", ticker));
+        }
         if let Some(c) = cursor {
             params.push(format!("cursor={}", c));
         }
@@ -73,6 +89,13 @@ impl KalshiApi {
         }
         
         let auth_headers = self.auth_headers("GET", url_path)?;
+        let context = || {
+            let ctx = Self::context("fetch markets", "GET", url_path);
+            match series_ticker.or(event_ticker) {
+                Some(ticker) => ctx.with_symbol(ticker),
+                None => ctx,
+            }
+        };
 
         let resp = self
             .http
@@ -80,18 +103,13 @@ impl KalshiApi {
             .headers(auth_headers)
             .send()
             .await
-            .map_err(|e| Error::Http(e.to_string()))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(Error::Http(format!("HTTP {}: {}", status, body)));
-        }
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
 
         let data: MarketsResponse = resp
             .json()
             .await
-            .map_err(|e| Error::Http(e.to_string()))?;
+            .with_context(context)?;
 
         Ok(data)
     }
@@ -106,7 +124,7 @@ impl KalshiApi {
 
         loop {
             let resp = self
-                .fetch_markets(status, Some(series_ticker), cursor.as_deref(), None)
+                .fetch_markets(status, Some(series_ticker), None, cursor.as_deref(), None)
                 .await?;
 
             all_markets.extend(resp.markets);
@@ -125,6 +143,210 @@ impl KalshiApi {
         Ok(all_markets)
     }
 
+    /// Every market under `event_ticker`, i.e. all strikes of one event.
+    pub async fn fetch_event_markets(
+        &self,
+        event_ticker: &str,
+        status: Option<&str>,
+    ) -> Result<Vec<KalshiMarket>> {
+        let mut all_markets = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let resp = self
+                .fetch_markets(status, None, Some(event_ticker), cursor.as_deref(), None)
+                .await?;
+
+            all_markets.extend(resp.markets);
+
+            match resp.cursor {
+                Some(c) if !c.is_empty() => cursor = Some(c),
+                _ => break,
+            }
+        }
+
+        Ok(all_markets)
+    }
+
+    /// Lists the series in `category`, e.g. `Crypto`.
+    /// One page of public trades, newest first. `min_ts` and `max_ts` are
+    /// Unix seconds.
+    pub async fn fetch_trades(
+        &self,
+        ticker: Option<&str>,
+        min_ts: Option<i64>,
+        max_ts: Option<i64>,
+        cursor: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<TradesResponse> {
+        let mut params = vec![];
+        if let Some(t) = ticker {
+            params.push(format!("ticker={}", t));
+        }
+        if let Some(ts) = min_ts {
+            params.push(format!("min_ts={}", ts));
+        }
+        if let Some(ts) = max_ts {
+            params.push(format!("max_ts={}", ts));
+        }
+        if let Some(c) = cursor {
+            params.push(format!("cursor={}", c));
+        }
+        if let Some(l) = limit {
+            params.push(format!("limit={}", l));
+        }
+
+        let url_path = "/trade-api/v2/markets/trades";
+        let mut url = format!("{}{}", KALSHI_REST_URL, url_path);
+        if !params.is_empty() {
+            url = format!("{}?{}", url, params.join("&"));
+        }
+
+        let auth_headers = self.auth_headers("GET", url_path)?;
+        let context = || {
+            let ctx = Self::context("fetch trades", "GET", url_path);
+            match ticker {
+                Some(t) => ctx.with_symbol(t),
+                None => ctx,
+            }
+        };
+
+        let resp = self
+            .http
+            .get(&url)
+            .headers(auth_headers)
+            .send()
+            .await
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+
+        let data: TradesResponse = resp
+            .json()
+            .await
+            .with_context(context)?;
+
+        Ok(data)
+    }
+
+    /// Every public trade matching the filters, following the cursor to the
+    /// last page.
+    pub async fn get_trades(
+        &self,
+        ticker: Option<&str>,
+        min_ts: Option<i64>,
+        max_ts: Option<i64>,
+    ) -> Result<Vec<KalshiTrade>> {
+        let mut all_trades = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let resp = self
+                .fetch_trades(ticker, min_ts, max_ts, cursor.as_deref(), Some(TRADES_PAGE_LIMIT))
+                .await?;
+            all_trades.extend(resp.trades);
+
+            match resp.cursor {
+                Some(c) if !c.is_empty() => cursor = Some(c),
+                _ => break,
+            }
+        }
+
+        Ok(all_trades)
+    }
+
+    pub async fn fetch_series(&self, category: &str) -> Result<Vec<KalshiSeries>> {
+        let url_path = "/trade-api/v2/series";
+        let url = format!("{}{}?category={}", KALSHI_REST_URL, url_path, category);
+
+        let auth_headers = self.auth_headers("GET", url_path)?;
+        let context = || Self::context("fetch series", "GET", url_path);
+
+        let resp = self
+            .http
+            .get(&url)
+            .headers(auth_headers)
+            .send()
+            .await
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+
+        let data: SeriesResponse = resp
+            .json()
+            .await
+            .with_context(context)?;
+
+        Ok(data.series.unwrap_or_default())
+    }
+
+    pub async fn fetch_announcements(&self) -> Result<Vec<KalshiAnnouncement>> {
+        let url_path = "/trade-api/v2/exchange/announcements";
+        let url = format!("{}{}", KALSHI_REST_URL, url_path);
+
+        let auth_headers = self.auth_headers("GET", url_path)?;
+        let context = || Self::context("fetch announcements", "GET", url_path);
+
+        let resp = self
+            .http
+            .get(&url)
+            .headers(auth_headers)
+            .send()
+            .await
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+
+        let data: AnnouncementsResponse = resp
+            .json()
+            .await
+            .with_context(context)?;
+
+        Ok(data.announcements)
+    }
+
+    pub async fn fetch_exchange_status(&self) -> Result<KalshiExchangeStatus> {
+        let url_path = "/trade-api/v2/exchange/status";
+        let url = format!("{}{}", KALSHI_REST_URL, url_path);
+
+        let auth_headers = self.auth_headers("GET", url_path)?;
+        let context = || Self::context("fetch exchange status", "GET", url_path);
+
+        let resp = self
+            .http
+            .get(&url)
+            .headers(auth_headers)
+            .send()
+            .await
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+
+        resp.json().await.with_context(context)
+    }
+
+    pub async fn fetch_orderbook(&self, market_ticker: &str) -> Result<KalshiOrderbookSnapshot> {
+        let url_path = format!("/trade-api/v2/markets/{}/orderbook", market_ticker);
+        let url = format!("{}{}", KALSHI_REST_URL, url_path);
+
+        let auth_headers = self.auth_headers("GET", &url_path)?;
+        let context = || {
+            Self::context("fetch orderbook", "GET", &url_path).with_symbol(market_ticker)
+        };
+
+        let resp = self
+            .http
+            .get(&url)
+            .headers(auth_headers)
+            .send()
+            .await
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+
+        let data: OrderbookResponse = resp
+            .json()
+            .await
+            .with_context(context)?;
+
+        Ok(data.orderbook.into_snapshot(market_ticker.to_string()))
+    }
+
     pub async fn get_markets_for_tickers(&self, tickers: &[&str]) -> Result<Vec<KalshiMarket>> {
         let mut all_markets = Vec::new();
         for ticker in tickers {
@@ -148,6 +370,9 @@ impl KalshiApi {
         let url = format!("{}{}", KALSHI_REST_URL, url_path);
   
         let auth_headers = self.auth_headers("POST", url_path)?;
+        let context = || {
+            Self::context("create order", "POST", url_path).with_symbol(request.ticker.clone())
+        };
 
         let resp = self
             .http
@@ -156,24 +381,20 @@ impl KalshiApi {
             .json(&request)
             .send()
             .await
-            .map_err(|e| Error::Http(e.to_string()))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(Error::Http(format!("HTTP {}: {}", status, body)));
-        }
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
 
         let data: CreateOrderResponse = resp
             .json()
             .await
-            .map_err(|e| Error::Http(e.to_string()))?;
+            .with_context(context)?;
 
         info!("Order created: {:?}", data);
 
         Ok(data)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_order(
         &self,
         ticker: &str,
@@ -182,6 +403,7 @@ impl KalshiApi {
         count: u64,
         price: u64,
         order_type: OrderType,
+        client_order_id: Option<String>,
     ) -> Result<CreateOrderResponse> {
         let request: CreateOrderRequest;
 
@@ -203,6 +425,7 @@ impl KalshiApi {
             );
         }
 
+        let request = request.with_client_order_id(client_order_id);
         info!("Creating order: {:?}", request);
 
         Ok(self._base_create_order(request).await?)
@@ -236,6 +459,13 @@ impl KalshiApi {
             }
 
             let auth_headers = self.auth_headers("GET", url_path)?;
+            let context = || {
+                let ctx = Self::context("fetch orders", "GET", url_path);
+                match ticker {
+                    Some(t) => ctx.with_symbol(t),
+                    None => ctx,
+                }
+            };
 
             let resp = self
                 .http
@@ -243,18 +473,13 @@ impl KalshiApi {
                 .headers(auth_headers)
                 .send()
                 .await
-                .map_err(|e| Error::Http(e.to_string()))?;
-
-            if !resp.status().is_success() {
-                let status = resp.status();
-                let body = resp.text().await.unwrap_or_default();
-                return Err(Error::Http(format!("HTTP {}: {}", status, body)));
-            }
+                .with_context(context)?;
+            let resp = ensure_success(resp).await.with_context(context)?;
 
             let data: GetOrdersResponse = resp
                 .json()
                 .await
-                .map_err(|e| Error::Http(e.to_string()))?;
+                .with_context(context)?;
 
             all_orders.extend(data.orders);
 
@@ -267,6 +492,71 @@ impl KalshiApi {
         Ok(all_orders)
     }
 
+    pub async fn get_order(&self, order_id: &str) -> Result<KalshiOrder> {
+        let url_path = format!("/trade-api/v2/portfolio/orders/{}", order_id);
+        let url = format!("{}{}", KALSHI_REST_URL, url_path);
+
+        let auth_headers = self.auth_headers("GET", &url_path)?;
+        let context = || Self::context("fetch order", "GET", &url_path);
+
+        let resp = self
+            .http
+            .get(&url)
+            .headers(auth_headers)
+            .send()
+            .await
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+
+        let data: GetOrderResponse = resp
+            .json()
+            .await
+            .with_context(context)?;
+
+        Ok(data.order)
+    }
+
+    /// Open market positions on the account.
+    pub async fn get_positions(&self) -> Result<Vec<KalshiMarketPosition>> {
+        let url_path = "/trade-api/v2/portfolio/positions";
+
+        let mut all_positions = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut url = format!("{}{}?count_filter=position", KALSHI_REST_URL, url_path);
+            if let Some(c) = &cursor {
+                url = format!("{}&cursor={}", url, c);
+            }
+
+            let auth_headers = self.auth_headers("GET", url_path)?;
+            let context = || Self::context("fetch positions", "GET", url_path);
+
+            let resp = self
+                .http
+                .get(&url)
+                .headers(auth_headers)
+                .send()
+                .await
+                .with_context(context)?;
+            let resp = ensure_success(resp).await.with_context(context)?;
+
+            let data: PositionsResponse = resp
+                .json()
+                .await
+                .with_context(context)?;
+
+            all_positions.extend(data.market_positions.into_iter().filter(|p| p.position != 0));
+
+            if data.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(data.cursor);
+        }
+
+        Ok(all_positions)
+    }
+
     pub async fn batch_cancel_orders(
         &self,
         order_ids: &[&str],
@@ -275,6 +565,7 @@ impl KalshiApi {
         let url = format!("{}{}", KALSHI_REST_URL, url_path);
   
         let auth_headers = self.auth_headers("DELETE", url_path)?;
+        let context = || Self::context("batch cancel orders", "DELETE", url_path);
 
         let request = BatchCancelOrdersRequest {
             orders: order_ids.iter().map(|id| KalshiCancelOrder { order_id: id.to_string() }).collect(),
@@ -289,15 +580,10 @@ impl KalshiApi {
             .json(&request)
             .send()
             .await
-            .map_err(|e| Error::Http(e.to_string()))?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(Error::Http(format!("HTTP {}: {}", status, body)));
-        }
+            .with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
 
-        let data: KalshiBatchCancelOrdersResponse = resp.json().await.map_err(|e| Error::Http(e.to_string()))?;
+        let data: KalshiBatchCancelOrdersResponse = resp.json().await.with_context(context)?;
 
         Ok(data)
     }
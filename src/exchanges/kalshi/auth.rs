@@ -30,27 +30,8 @@ impl KalshiAuth {
     }
 
     fn parse_pem(api_key_id: &str, key_data: &[u8]) -> Result<Self> {
-        let pem_data = parse(key_data)
-            .map_err(|e| Error::Auth(format!("Failed to parse PEM: {}", e)))?;
-
-        let private_key = match pem_data.tag().to_string().as_str() {
-            "PRIVATE KEY" => {
-                RsaPrivateKey::from_pkcs8_der(pem_data.contents())
-                    .map_err(|e| Error::Auth(format!("Failed to parse PKCS#8 key: {}", e)))?
-            }
-            "RSA PRIVATE KEY" => {
-                RsaPrivateKey::from_pkcs1_der(pem_data.contents())
-                    .map_err(|e| Error::Auth(format!("Failed to parse PKCS#1 key: {}", e)))?
-            }
-            _ => {
-                RsaPrivateKey::from_pkcs8_der(pem_data.contents())
-                    .or_else(|_| RsaPrivateKey::from_pkcs1_der(pem_data.contents()))
-                    .map_err(|e| Error::Auth(format!("Failed to parse key: {}", e)))?
-            }
-        };
-
         Ok(Self {
-            private_key,
+            private_key: parse_private_key(key_data)?,
             api_key_id: api_key_id.to_string(),
         })
     }
@@ -59,6 +40,10 @@ impl KalshiAuth {
         &self.api_key_id
     }
 
+    pub fn private_key(&self) -> &RsaPrivateKey {
+        &self.private_key
+    }
+
     pub fn sign(&self, message: &str) -> Result<String> {
         let mut rng = OsRng;
         let signing_key = BlindedSigningKey::<Sha256>::new(self.private_key.clone());
@@ -94,6 +79,30 @@ impl KalshiAuth {
     }
 }
 
+/// RSA private key from a PKCS#8 or PKCS#1 PEM; Binance API keys use the
+/// same files.
+pub fn parse_private_key(key_data: &[u8]) -> Result<RsaPrivateKey> {
+    let pem_data = parse(key_data)
+        .map_err(|e| Error::Auth(format!("Failed to parse PEM: {}", e)))?;
+
+    let private_key = match pem_data.tag().to_string().as_str() {
+        "PRIVATE KEY" => {
+            RsaPrivateKey::from_pkcs8_der(pem_data.contents())
+                .map_err(|e| Error::Auth(format!("Failed to parse PKCS#8 key: {}", e)))?
+        }
+        "RSA PRIVATE KEY" => {
+            RsaPrivateKey::from_pkcs1_der(pem_data.contents())
+                .map_err(|e| Error::Auth(format!("Failed to parse PKCS#1 key: {}", e)))?
+        }
+        _ => {
+            RsaPrivateKey::from_pkcs8_der(pem_data.contents())
+                .or_else(|_| RsaPrivateKey::from_pkcs1_der(pem_data.contents()))
+                .map_err(|e| Error::Auth(format!("Failed to parse key: {}", e)))?
+        }
+    };
+    Ok(private_key)
+}
+
 #[derive(Debug, Clone)]
 pub struct AuthHeaders {
     pub api_key: String,
@@ -0,0 +1,146 @@
+//! Backfill of `kalshi_trades` from the REST trade history, for periods
+//! before live capture started.
+//!
+//! Pages are stored as they arrive, so an interrupted backfill keeps what it
+//! fetched, and trades already stored are skipped, so ranges may overlap.
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use super::api::{KalshiApi, TRADES_PAGE_LIMIT};
+use super::models::{KalshiMarket, KalshiTrade};
+use crate::db::main::{Db, KalshiTradeRecord};
+use crate::error::Result;
+use crate::metrics::{self, names};
+
+/// Counts from one backfill run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BackfillSummary {
+    pub markets: usize,
+    pub fetched: usize,
+    /// Trades not stored before this run
+    pub inserted: u64,
+    /// Trades missing an id, price or time
+    pub skipped: usize,
+}
+
+pub struct TradeBackfill<'a> {
+    api: &'a KalshiApi,
+    db: &'a Db,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+impl<'a> TradeBackfill<'a> {
+    pub fn new(api: &'a KalshiApi, db: &'a Db) -> Self {
+        Self {
+            api,
+            db,
+            from: None,
+            to: None,
+        }
+    }
+
+    pub fn with_range(mut self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    /// Backfills each entry of `symbols`: a market ticker (`KXBTCD-25OCT16-T...`)
+    /// directly, a series ticker (`KXBTCD`) through every market of the series
+    /// open during the range.
+    pub async fn run(&self, symbols: &[String]) -> Result<BackfillSummary> {
+        let mut summary = BackfillSummary::default();
+        for symbol in symbols {
+            let tickers = if symbol.contains('-') {
+                vec![symbol.clone()]
+            } else {
+                let markets = self.api.fetch_market_by_ticker(symbol, None).await?;
+                let tickers: Vec<String> = markets
+                    .into_iter()
+                    .filter(|m| self.overlaps(m))
+                    .map(|m| m.ticker)
+                    .collect();
+                info!("📜 {} markets of {} in range", tickers.len(), symbol);
+                tickers
+            };
+            for ticker in tickers {
+                self.backfill_market(&ticker, &mut summary).await?;
+                summary.markets += 1;
+            }
+        }
+        info!(
+            "📜 Trade backfill done: {} markets, {} trades fetched, {} new, {} skipped",
+            summary.markets, summary.fetched, summary.inserted, summary.skipped
+        );
+        Ok(summary)
+    }
+
+    async fn backfill_market(&self, ticker: &str, summary: &mut BackfillSummary) -> Result<()> {
+        let min_ts = self.from.map(|t| t.timestamp());
+        let max_ts = self.to.map(|t| t.timestamp());
+        let mut cursor: Option<String> = None;
+        let mut inserted = 0;
+
+        loop {
+            let page = self
+                .api
+                .fetch_trades(Some(ticker), min_ts, max_ts, cursor.as_deref(), Some(TRADES_PAGE_LIMIT))
+                .await?;
+            summary.fetched += page.trades.len();
+
+            let records: Vec<KalshiTradeRecord> = page.trades.iter().filter_map(to_record).collect();
+            summary.skipped += page.trades.len() - records.len();
+            let rows = self.db.insert_kalshi_trades(&records).await?;
+            metrics::add(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "kalshi_trades")], rows);
+            inserted += rows;
+
+            match page.cursor {
+                Some(c) if !c.is_empty() => cursor = Some(c),
+                _ => break,
+            }
+        }
+
+        if inserted > 0 {
+            info!("📜 {}: {} new trades", ticker, inserted);
+        }
+        summary.inserted += inserted;
+        Ok(())
+    }
+
+    /// Whether the market traded during the range, keeping markets whose
+    /// times are missing or unparseable.
+    fn overlaps(&self, market: &KalshiMarket) -> bool {
+        let parse = |t: &Option<String>| {
+            t.as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.to_utc())
+        };
+        let opened_before_end = match (self.to, parse(&market.open_time)) {
+            (Some(to), Some(open)) => open <= to,
+            _ => true,
+        };
+        let closed_after_start = match (self.from, parse(&market.close_time)) {
+            (Some(from), Some(close)) => close >= from,
+            _ => true,
+        };
+        opened_before_end && closed_after_start
+    }
+}
+
+fn to_record(trade: &KalshiTrade) -> Option<KalshiTradeRecord> {
+    let record = KalshiTradeRecord {
+        trade_id: trade.trade_id.clone()?,
+        ticker: trade.market_ticker.clone(),
+        taker_side: trade.side.clone(),
+        yes_price: trade.yes_price_f64()?,
+        count: trade.count.unwrap_or(0),
+        created_at: trade.timestamp()?,
+    };
+    if record.trade_id.is_empty() {
+        warn!("Skipping {} trade without an id", record.ticker);
+        return None;
+    }
+    Some(record)
+}
@@ -1,16 +1,18 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, watch, Mutex};
 use tokio::time::{sleep_until, Instant};
 use tracing::{error, info, warn};
 
+use super::announcements::AnnouncementWatcher;
 use super::api::KalshiApi;
 use super::auth::KalshiAuth;
 use super::context::ClientContext;
+use super::discovery::SeriesDiscovery;
 use super::handler::MessageHandler;
-use super::market_data::MarketDataWriter;
-use super::models::KalshiWsMessage;
+use super::models::{KalshiOrderbook, KalshiWsMessage};
+use super::reference::ReferencePricer;
 use super::subscriptions::SubscriptionManager;
 use super::utils::{
     maintenance_sleep_duration, 
@@ -18,45 +20,181 @@ use super::utils::{
     next_maintenance_start
 };
 use super::websocket::KalshiWebSocket;
-use crate::config::KalshiConfig;
+use crate::config::{ChaosConfig, ExecutionConfig, KalshiConfig, PortfolioConfig, QuoteSkewConfig};
 use crate::constants::KALSHI_WS_URL;
-use crate::db::main::Db;
+use crate::db::main::{Db, MarketDataRecord};
 use crate::error::{Error, Result};
+use crate::exchanges::acks::ACK_TIMEOUT;
+use crate::exchanges::event::MarketEvent;
 use crate::exchanges::kalshi::constants::*;
-use crate::state::KalshiState;
+use crate::execution::backend::{KalshiBackend, OrderBackend};
+use crate::execution::paper::PaperBackend;
+use crate::execution::AlertExecutor;
+use crate::heartbeat::HeartbeatHandle;
+use crate::portfolio::{Portfolio, PortfolioTracker};
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::recorder::RecorderHandle;
+use crate::reload::next_update;
+use crate::state::{self, KalshiState, PortfolioState};
+use crate::trader::exposure::ExposureTracker;
+use crate::trader::hedge::Hedger;
 use crate::trader::main::Trader;
+use crate::trader::positions::PositionManager;
+use crate::utils::websocket::ReconnectPolicy;
 
 pub struct KalshiClient {
     auth: Arc<KalshiAuth>,
     api: Arc<KalshiApi>,
     ws: Option<Arc<Mutex<KalshiWebSocket>>>,
     ctx: ClientContext,
+    recorder: Option<RecorderHandle>,
+    chaos: Option<ChaosConfig>,
+    heartbeat: HeartbeatHandle,
+    discovery: Option<SeriesDiscovery>,
+    portfolio: Arc<PortfolioState>,
+    positions: PositionManager,
+    skew_tx: watch::Sender<QuoteSkewConfig>,
+    /// Series from the config, as opposed to discovered ones
+    configured_series: Vec<String>,
+    reload_rx: Option<mpsc::Receiver<KalshiConfig>>,
+    /// Trading on alerts, started with the client once its leader is known
+    execution: Option<ExecutionConfig>,
+    portfolio_config: PortfolioConfig,
 }
 
 impl KalshiClient {
-    pub fn new(config: KalshiConfig, db: Arc<Db>) -> Result<Self> {
+    pub fn new(
+        config: KalshiConfig,
+        db: Option<Arc<Db>>,
+        sink_txs: Vec<mpsc::Sender<MarketEvent>>,
+    ) -> Result<Self> {
         let auth = Arc::new(KalshiAuth::create_auth(&config)?);
         let api = Arc::new(KalshiApi::new(auth.clone()));
 
-        if config.tracked_symbols.is_empty() {
+        if config.tracked_symbols.is_empty() && config.discovery.is_none() {
             return Err(Error::Config("No tracked symbols configured".into()));
         }
 
-        let market_data_tx = MarketDataWriter::spawn(db.clone());
-        let trading_tx = Trader::spawn(api.clone());
-        let ctx = ClientContext::new(config.tracked_symbols, db, market_data_tx, trading_tx);
+        let discovery = config.discovery.map(SeriesDiscovery::new);
+        let reference = ReferencePricer::new(config.reference_price);
+        let positions = PositionManager::new(Arc::new(Portfolio::new("kalshi")));
+        let (skew_tx, skew_rx) = watch::channel(config.quote_skew);
+        let risk = config.execution.as_ref().map(|e| e.risk).unwrap_or_default();
+        let trading_tx = Trader::spawn(api.clone(), positions.clone(), risk, skew_rx);
+        let configured_series = config.tracked_symbols.clone();
+        let mut ctx = ClientContext::new(
+            config.tracked_symbols,
+            config.tracked_events,
+            db,
+            trading_tx,
+            sink_txs,
+        );
+        ctx.reference = reference;
+        ctx.imbalance = config.imbalance;
+        let portfolio = Arc::new(PortfolioState::new());
+        ExposureTracker::spawn(config.exposure, positions.clone(), ctx.state.clone(), portfolio.clone());
+        if let Some(hedge_config) = &config.hedge {
+            Hedger::spawn(hedge_config, portfolio.clone())?;
+        }
+        if let Some(announcements) = config.announcements {
+            AnnouncementWatcher::spawn(api.clone(), announcements);
+        }
+
+        Ok(Self {
+            auth,
+            api,
+            ws: None,
+            ctx,
+            recorder: None,
+            chaos: None,
+            heartbeat: HeartbeatHandle::disabled(),
+            discovery,
+            portfolio,
+            positions,
+            skew_tx,
+            configured_series,
+            reload_rx: None,
+            execution: config.execution,
+            portfolio_config: config.portfolio,
+        })
+    }
+
+    /// Channel for config reloads, applied between messages of the running
+    /// connection.
+    pub fn reload_handle(&mut self) -> mpsc::Sender<KalshiConfig> {
+        let (tx, rx) = mpsc::channel(1);
+        self.reload_rx = Some(rx);
+        tx
+    }
+
+    pub fn with_recorder(mut self, recorder: Option<RecorderHandle>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    pub fn with_chaos(mut self, chaos: Option<ChaosConfig>) -> Self {
+        self.chaos = chaos;
+        self
+    }
 
-        Ok(Self { auth, api, ws: None, ctx })
+    /// Queue of the database's buffered writer; ticks are only persisted
+    /// when one is set.
+    pub fn with_market_data_writer(mut self, tx: Option<mpsc::Sender<MarketDataRecord>>) -> Self {
+        self.ctx.market_data_tx = tx;
+        self
+    }
+
+    /// Adds a sink started after the client, such as one reading its state.
+    pub fn with_sink(mut self, sink_tx: mpsc::Sender<MarketEvent>) -> Self {
+        self.ctx.sink_txs.push(sink_tx);
+        self
+    }
+
+    pub fn with_leader(mut self, leader: LeaderHandle) -> Self {
+        self.ctx.leader = leader;
+        self
+    }
+
+    pub fn with_heartbeat(mut self, heartbeat: HeartbeatHandle) -> Self {
+        self.heartbeat = heartbeat;
+        self
     }
 
     pub fn state(&self) -> &KalshiState {
         &self.ctx.state
     }
 
+    /// Shared handle for readers outside the client, such as the dashboard.
+    pub fn shared_state(&self) -> Arc<KalshiState> {
+        self.ctx.state.clone()
+    }
+
+    /// Exposure of the positions traded from this client, and their hedges.
+    pub fn portfolio(&self) -> Arc<PortfolioState> {
+        self.portfolio.clone()
+    }
+
+    /// Contracts held per market, as filled by the trader.
+    pub fn positions(&self) -> PositionManager {
+        self.positions.clone()
+    }
+
+    /// Connects the WebSocket, reusing the existing one (and its message id
+    /// counter) on reconnects.
     pub async fn connect(&mut self) -> Result<()> {
-        let mut ws = KalshiWebSocket::new(KALSHI_WS_URL, self.auth.clone());
-        ws.connect().await?;
-        self.ws = Some(Arc::new(Mutex::new(ws)));
+        match &self.ws {
+            Some(ws) => ws.lock().await.reconnect().await?,
+            None => {
+                let mut ws = KalshiWebSocket::new(KALSHI_WS_URL, self.auth.clone())
+                    .with_recorder(self.recorder.clone())
+                    .with_chaos(self.chaos.clone());
+                ws.connect().await?;
+                self.ws = Some(Arc::new(Mutex::new(ws)));
+            }
+        }
+        metrics::set(names::WS_CONNECTED, &[("exchange", "kalshi")], 1.0);
         Ok(())
     }
 
@@ -64,7 +202,7 @@ impl KalshiClient {
         if let Some(ws) = &self.ws {
             ws.lock().await.disconnect().await?;
         }
-        self.ws = None;
+        metrics::set(names::WS_CONNECTED, &[("exchange", "kalshi")], 0.0);
         Ok(())
     }
 
@@ -75,14 +213,100 @@ impl KalshiClient {
         }
     }
 
+    /// Seeds current markets and their books over REST before the WebSocket
+    /// connects, so the first signals of a session see a full book.
+    async fn warm_start(&mut self) {
+        if let Err(e) = SubscriptionManager::fetch_and_set_all(&mut self.ctx, &self.api).await {
+            warn!("Warm start failed to fetch markets: {}", e);
+            return;
+        }
+        self.refresh_orderbooks("Warm start").await;
+    }
+
+    /// Brings state back in line after a reconnect: deltas missed during the
+    /// gap make the held books wrong, so they are replaced with fresh REST
+    /// snapshots until the resubscription's own snapshot arrives.
+    async fn resync(&mut self) {
+        info!("🔄 Resyncing Kalshi state after reconnect");
+        self.refresh_orderbooks("Resync").await;
+    }
+
+    async fn refresh_orderbooks(&mut self, reason: &str) {
+        for ticker in self.ctx.subscribed_tickers() {
+            match self.api.fetch_orderbook(&ticker).await {
+                Ok(snapshot) => {
+                    info!("🌡️ {} orderbook for {}", reason, ticker);
+                    let mut book = KalshiOrderbook::new_empty(ticker.clone());
+                    book.apply_snapshot(snapshot);
+                    self.ctx.state.orderbooks.insert(ticker, book);
+                }
+                Err(e) => {
+                    warn!("{} failed for {}: {}", reason, ticker, e);
+                    // Better no book than one missing the gap's deltas.
+                    self.ctx.state.orderbooks.remove(&ticker);
+                }
+            }
+        }
+    }
+
+    /// Adds newly discovered series to tracking. Returns whether any were added.
+    async fn discover_series(&mut self) -> bool {
+        let Some(discovery) = &self.discovery else {
+            return false;
+        };
+        let found = discovery.discover(&self.api, &self.ctx.series_tickers).await;
+        if found.is_empty() {
+            return false;
+        }
+        info!("🔭 Adding {} discovered series: {:?}", found.len(), found);
+        self.ctx.series_tickers.extend(found);
+        true
+    }
+
     pub async fn start(&mut self) -> Result<()> {
         let mut backoff_secs = INITIAL_BACKOFF_SECS;
 
+        self.discover_series().await;
+
+        if maintenance_sleep_duration().is_none() {
+            self.warm_start().await;
+        }
+
+        if let Some(execution) = self.execution.take() {
+            let leader = self.ctx.leader.clone();
+            let backend: Box<dyn OrderBackend> = if execution.paper {
+                Box::new(PaperBackend::new(self.ctx.state.clone()))
+            } else {
+                Box::new(KalshiBackend::new(self.api.clone()))
+            };
+            let portfolio = if execution.paper {
+                Arc::new(Portfolio::new(backend.name()))
+            } else {
+                // Shared with the trader, and limits have to count what we
+                // held before a restart
+                let portfolio = self.positions.portfolio();
+                portfolio.seed(&self.api.get_positions().await?);
+                portfolio.mark(&self.ctx.state);
+                portfolio
+            };
+            PortfolioTracker::spawn(
+                portfolio.clone(),
+                self.ctx.state.clone(),
+                self.ctx.db.clone(),
+                self.portfolio_config,
+            );
+            let fills_tx = AlertExecutor::spawn(backend, portfolio, execution, leader);
+            // Paper fills come from the simulator, not the fill channel
+            if !execution.paper {
+                self.ctx.fills_tx = Some(fills_tx);
+            }
+        }
+
         loop {
             if let Some(sleep_dur) = maintenance_sleep_duration() {
                 info!("🛑 Maintenance window active, sleeping for {}s...", sleep_dur.as_secs());
                 let _ = self.disconnect().await;
-                tokio::time::sleep(sleep_dur).await;
+                self.heartbeat.sleep(sleep_dur).await;
                 info!("✅ Maintenance window ended, resuming...");
                 backoff_secs = INITIAL_BACKOFF_SECS;
             }
@@ -98,10 +322,33 @@ impl KalshiClient {
                     if was_stable {
                         backoff_secs = INITIAL_BACKOFF_SECS;
                     }
-                    error!("🔴 WebSocket error: {}. Reconnecting in {}s...", e, backoff_secs);
                     let _ = self.disconnect().await;
-                    tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
-                    backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
+
+                    match ReconnectPolicy::for_error(&e) {
+                        ReconnectPolicy::Abort => {
+                            error!(exchange = "kalshi", "🚨 WebSocket error: {}. Not reconnecting, manual intervention required", e.chain());
+                            return Err(e);
+                        }
+                        ReconnectPolicy::Immediate => {
+                            warn!("🔁 WebSocket error: {}. Reconnecting immediately...", e.chain());
+                            backoff_secs = INITIAL_BACKOFF_SECS;
+                        }
+                        ReconnectPolicy::Delayed(delay) => {
+                            warn!("⏳ WebSocket error: {}. Reconnecting in {}s...", e.chain(), delay.as_secs());
+                            tokio::time::sleep(delay).await;
+                        }
+                        ReconnectPolicy::Backoff => {
+                            error!(exchange = "kalshi", "🔴 WebSocket error: {}. Reconnecting in {}s...", e.chain(), backoff_secs);
+                            tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
+                            backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
+                        }
+                    }
+                    metrics::inc(names::WS_RECONNECTS_TOTAL, &[("exchange", "kalshi")]);
+                    state::session().record_reconnect("kalshi");
+                    notify::publish(Notification::Reconnect {
+                        exchange: "kalshi".to_string(),
+                        error: e.to_string(),
+                    });
                 }
             }
         }
@@ -110,10 +357,12 @@ impl KalshiClient {
     }
 
     async fn run_connection_loop(&mut self) -> (Result<()>, bool) {
+        let reconnecting = self.ws.is_some();
         if let Err(e) = self.connect().await {
             return (Err(e), false);
         }
         info!("🔗 WebSocket connected");
+        self.ctx.on_connected();
 
         let ws = match &self.ws {
             Some(ws) => ws.clone(),
@@ -123,6 +372,9 @@ impl KalshiClient {
         if let Err(e) = SubscriptionManager::fetch_and_set_all(&mut self.ctx, &self.api).await {
             return (Err(e), false);
         }
+        if reconnecting {
+            self.resync().await;
+        }
         if let Err(e) = SubscriptionManager::subscribe_all(&mut self.ctx, &ws).await {
             return (Err(e), false);
         }
@@ -152,10 +404,11 @@ impl KalshiClient {
                     }
                     Err(e) => {
                         error!("WebSocket receive error: {}", e);
-                        break;
+                        return Some(e);
                     }
                 }
             }
+            None
         });
 
         info!("🧠 Starting message processing loop");
@@ -165,6 +418,15 @@ impl KalshiClient {
         let maintenance_deadline = next_maintenance_start();
         let mut last_message_at = Instant::now();
         let mut idle_deadline = last_message_at + Duration::from_secs(WS_IDLE_RECONNECT_SECS);
+        let mut eviction = tokio::time::interval(Duration::from_secs(EVICTION_INTERVAL_SECS));
+        let mut ack_check = tokio::time::interval(ACK_TIMEOUT / 2);
+        let discovery_period = self
+            .discovery
+            .as_ref()
+            .map(|d| d.interval())
+            .unwrap_or(Duration::from_secs(DISCOVERY_INTERVAL_SECS));
+        let mut discovery_deadline = Instant::now() + discovery_period;
+        let mut reload_rx = self.reload_rx.take();
 
         loop {
             tokio::select! {
@@ -172,6 +434,7 @@ impl KalshiClient {
                     match maybe_msg {
                         Some(msg) => {
                             received_messages = true;
+                            self.heartbeat.beat();
                             last_message_at = Instant::now();
                             idle_deadline = last_message_at + Duration::from_secs(WS_IDLE_RECONNECT_SECS);
                             if let Err(e) = MessageHandler::handle(&mut self.ctx, msg).await {
@@ -190,6 +453,33 @@ impl KalshiClient {
                     }
                     fetch_deadline = next_15min_interval();
                 }
+                _ = eviction.tick() => {
+                    self.ctx.evict_retired_markets();
+                }
+                _ = ack_check.tick() => {
+                    // The reader holds the socket while it waits for a frame;
+                    // on a quiet feed the check is skipped rather than stall
+                    // the loop.
+                    if let Ok(mut guard) = tokio::time::timeout(ACK_TIMEOUT / 2, ws.lock()).await {
+                        if let Err(e) = guard.retry_expired().await {
+                            error!("{}, reconnecting", e);
+                            break;
+                        }
+                    }
+                }
+                _ = sleep_until(discovery_deadline), if self.discovery.is_some() => {
+                    if self.discover_series().await {
+                        if let Err(e) = SubscriptionManager::fetch_and_set_all(&mut self.ctx, &self.api).await {
+                            error!("Failed to fetch markets for discovered series: {}", e);
+                        } else if let Err(e) = SubscriptionManager::subscribe_all(&mut self.ctx, &ws).await {
+                            error!("Failed to subscribe discovered series: {}", e);
+                        }
+                    }
+                    discovery_deadline = Instant::now() + discovery_period;
+                }
+                Some(config) = next_update(&mut reload_rx) => {
+                    self.apply_config(config, &ws).await;
+                }
                 _ = sleep_until(maintenance_deadline) => {
                     info!("🛑 Approaching maintenance window, disconnecting...");
                     break;
@@ -204,8 +494,70 @@ impl KalshiClient {
             }
         }
 
+        self.reload_rx = reload_rx;
         ws_handle.abort();
-        let _ = ws_handle.await;
-        (Err(Error::WebSocket("Connection lost".into())), received_messages)
+        let error = ws_handle
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| Error::WebSocket("Connection lost".into()));
+        (Err(error), received_messages)
+    }
+
+    /// Applies a reloaded config: series and events are diffed against the
+    /// running ones and resubscribed, pricing and skew are swapped in place.
+    async fn apply_config(&mut self, config: KalshiConfig, ws: &Arc<Mutex<KalshiWebSocket>>) {
+        let removed: Vec<String> = self
+            .configured_series
+            .iter()
+            .filter(|s| !config.tracked_symbols.contains(s))
+            .cloned()
+            .collect();
+        let added: Vec<String> = config
+            .tracked_symbols
+            .iter()
+            .filter(|s| !self.configured_series.contains(s))
+            .cloned()
+            .collect();
+        for series in &removed {
+            self.ctx.untrack_series(series);
+        }
+        for series in &added {
+            if !self.ctx.series_tickers.contains(series) {
+                self.ctx.series_tickers.push(series.clone());
+            }
+        }
+
+        // Series leaving event level keep their current market only.
+        let dropped_events: Vec<String> = self
+            .ctx
+            .event_series
+            .iter()
+            .filter(|s| !config.tracked_events.contains(s))
+            .cloned()
+            .collect();
+        for series in dropped_events {
+            self.ctx.event_series.remove(&series);
+            for market in self.ctx.event_markets.remove(&series).into_iter().flatten() {
+                self.ctx.state.retire_market(&market.ticker);
+                self.ctx.market_to_series.remove(&market.ticker);
+            }
+        }
+        self.ctx.event_series.extend(config.tracked_events.iter().cloned());
+
+        self.ctx.reference = ReferencePricer::new(config.reference_price);
+        self.ctx.imbalance = config.imbalance;
+        self.skew_tx.send_replace(config.quote_skew);
+        self.configured_series = config.tracked_symbols;
+        info!(
+            "🔁 Kalshi config reloaded: +{:?} -{:?}, tracking {:?}",
+            added, removed, self.ctx.series_tickers
+        );
+
+        if let Err(e) = SubscriptionManager::fetch_and_set_all(&mut self.ctx, &self.api).await {
+            error!("Failed to fetch markets after reload: {}", e);
+        } else if let Err(e) = SubscriptionManager::subscribe_all(&mut self.ctx, ws).await {
+            error!("Failed to resubscribe after reload: {}", e);
+        }
     }
 }
@@ -1,6 +1,3 @@
-pub const BATCH_SIZE: usize = 1000;
-pub const FLUSH_INTERVAL_MS: u64 = 5000;
-pub const CHANNEL_BUFFER_SIZE: usize = 50000;
 pub const FETCH_AFTER_CLOSE_SECS: i64 = 3;
 
 pub const INITIAL_BACKOFF_SECS: u64 = 1;
@@ -8,5 +5,15 @@ pub const MAX_BACKOFF_SECS: u64 = 60;
 
 pub const WS_IDLE_RECONNECT_SECS: u64 = 60;
 
+/// How long a closed market's book and metadata stay in memory.
+pub const RETIRED_MARKET_TTL_SECS: u64 = 300;
+pub const EVICTION_INTERVAL_SECS: u64 = 60;
+
 pub const MAX_MARKET_FETCH_ATTEMPTS: u64 = 20;
 pub const MARKET_FETCH_INTERVAL_SECS: u64 = 10;
+
+pub const DISCOVERY_INTERVAL_SECS: u64 = 3600;
+
+/// Default YES bid/ask depth ratio past which a Kalshi book counts as
+/// leaning; contract books are far thinner than Binance's.
+pub const IMBALANCE_LEAN_RATIO: f64 = 3.0;
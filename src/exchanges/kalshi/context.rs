@@ -1,45 +1,96 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
-use chrono::DateTime;
+use chrono::{DateTime, Utc};
 use tokio::sync::mpsc;
 use tracing::{error, info};
 
-use super::models::{KalshiMarket, KalshiOrderbook};
-use crate::db::main::Db;
+use super::models::{KalshiFill, KalshiMarket, KalshiOrderbook};
+use super::reference::ReferencePricer;
+use crate::config::ImbalanceConfig;
+use crate::db::main::{Db, KalshiMarketRecord, MarketDataRecord};
+use crate::exchanges::dedup::SequenceFilter;
+use crate::exchanges::event::{EventMeta, Exchange, MarketEvent};
+use crate::exchanges::kalshi::constants::RETIRED_MARKET_TTL_SECS;
 use crate::exchanges::kalshi::TickUpdate;
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
 use crate::state::KalshiState;
+use crate::status;
 
 pub(crate) struct ClientContext {
-    pub state: KalshiState,
+    pub state: Arc<KalshiState>,
     pub current_markets: HashMap<String, KalshiMarket>,
     pub market_to_series: HashMap<String, String>,
     pub series_tickers: Vec<String>,
+    /// Series whose whole current event is tracked, not just `current_markets`.
+    pub event_series: HashSet<String>,
+    /// The other markets of each event-level series' current event, keyed by series.
+    pub event_markets: HashMap<String, Vec<KalshiMarket>>,
     pub subscription_ids: HashMap<String, u64>,
-    pub db: Arc<Db>,
-    pub market_data_tx: mpsc::Sender<TickUpdate>,
+    /// Highest `seq` seen per subscription id on the current connection.
+    pub sequence_filter: SequenceFilter<u64>,
+    /// `None` when no database is configured; nothing is persisted then.
+    pub db: Option<Arc<Db>>,
+    pub market_data_tx: Option<mpsc::Sender<MarketDataRecord>>,
     pub trading_tx: mpsc::Sender<TickUpdate>,
+    /// Execution's queue of our own fills; the `fill` channel is only
+    /// subscribed when set.
+    pub fills_tx: Option<mpsc::Sender<KalshiFill>>,
+    pub sink_txs: Vec<mpsc::Sender<MarketEvent>>,
+    /// Followers keep their books current but leave persistence and
+    /// trading to the leader.
+    pub leader: LeaderHandle,
+    pub reference: ReferencePricer,
+    pub imbalance: ImbalanceConfig,
 }
 
 impl ClientContext {
     pub fn new(
         series_tickers: Vec<String>,
-        db: Arc<Db>,
-        market_data_tx: mpsc::Sender<TickUpdate>,
+        event_series: Vec<String>,
+        db: Option<Arc<Db>>,
         trading_tx: mpsc::Sender<TickUpdate>,
+        sink_txs: Vec<mpsc::Sender<MarketEvent>>,
     ) -> Self {
         Self {
-            state: KalshiState::new(),
+            state: Arc::new(KalshiState::new()),
             current_markets: HashMap::new(),
             market_to_series: HashMap::new(),
             series_tickers,
+            event_series: event_series.into_iter().collect(),
+            event_markets: HashMap::new(),
             subscription_ids: HashMap::new(),
+            sequence_filter: SequenceFilter::new("kalshi", "sid"),
             db,
-            market_data_tx,
+            market_data_tx: None,
             trading_tx,
+            fills_tx: None,
+            sink_txs,
+            leader: LeaderHandle::always(),
+            reference: ReferencePricer::default(),
+            imbalance: ImbalanceConfig::kalshi(),
         }
     }
 
+    /// Subscription ids and their seq numbers start over on every
+    /// connection.
+    pub fn on_connected(&mut self) {
+        self.subscription_ids.clear();
+        self.sequence_filter.reset();
+    }
+
+    /// Makes `market` the one `series` trades, replacing its event markets.
+    pub fn set_current_market(&mut self, series: &str, market: &KalshiMarket) {
+        self.current_markets.insert(series.to_string(), market.clone());
+        self.event_markets.remove(series);
+        self.market_to_series.insert(market.ticker.clone(), series.to_string());
+        self.track_market(market);
+        status::board().track_market(&market.ticker, series);
+    }
+
     pub fn resolve_series_ticker(&self, market_ticker: &str) -> Option<String> {
         self.market_to_series
             .get(market_ticker)
@@ -52,6 +103,69 @@ impl ClientContext {
             })
     }
 
+    /// Every market the orderbook subscription should cover: the current
+    /// market of each series plus the rest of any event-level series' event.
+    pub fn subscribed_tickers(&self) -> Vec<String> {
+        let mut tickers: Vec<String> = self.current_markets.values().map(|m| m.ticker.clone()).collect();
+        for market in self.event_markets.values().flatten() {
+            if !tickers.contains(&market.ticker) {
+                tickers.push(market.ticker.clone());
+            }
+        }
+        tickers
+    }
+
+    /// Whether `market_ticker` is the market its series trades and persists,
+    /// as opposed to another strike of an event-level series.
+    pub fn is_current_market(&self, market_ticker: &str) -> bool {
+        self.current_markets.values().any(|m| m.ticker == market_ticker)
+    }
+
+    /// The market's YES reference price from `ob` and its last trade.
+    pub fn reference_price(&self, ob: &KalshiOrderbook) -> Option<f64> {
+        let last_trade = self.state.last_trades.get(&ob.market_ticker).map(|t| *t);
+        self.reference.price(ob.yes_quote(), last_trade, Utc::now())
+    }
+
+    fn close_time(&self, market_ticker: &str) -> Option<DateTime<Utc>> {
+        self.state.close_time(market_ticker)
+    }
+
+    /// Metadata for an event on `market_ticker` received now.
+    pub fn event_meta(&self, market_ticker: &str) -> EventMeta {
+        let meta = EventMeta::new(Exchange::Kalshi, market_ticker, Utc::now())
+            .with_expiry(self.close_time(market_ticker));
+        match self.resolve_series_ticker(market_ticker) {
+            Some(series) => meta.with_series(series),
+            None => meta,
+        }
+    }
+
+    /// Hands `event` to every sink. Like persistence, leader only.
+    pub fn publish(&self, event: MarketEvent) {
+        if !self.leader.is_leader() {
+            return;
+        }
+        for sink_tx in &self.sink_txs {
+            if let Err(e) = sink_tx.try_send(event.clone()) {
+                error!("Failed to queue sink update: {}", e);
+            }
+        }
+    }
+
+    /// Keeps the book's depth imbalance in the shared state, dropping it
+    /// while the book has no asks.
+    pub fn update_imbalance(&self, ob: &KalshiOrderbook) {
+        match ob.imbalance(&self.imbalance) {
+            Some(imbalance) => {
+                self.state.imbalances.insert(ob.market_ticker.clone(), imbalance);
+            }
+            None => {
+                self.state.imbalances.remove(&ob.market_ticker);
+            }
+        }
+    }
+
     pub fn queue_market_data_update(&self, ob: &KalshiOrderbook) {
         let asset = match self.resolve_series_ticker(&ob.market_ticker) {
             Some(s) => s,
@@ -61,26 +175,157 @@ impl ClientContext {
             }
         };
 
-        let close_time = self
-            .state
-            .tracked_markets
-            .get(&ob.market_ticker)
-            .and_then(|m| m.close_time.as_ref().and_then(|ct| DateTime::parse_from_rfc3339(ct).ok()))
-            .map(|dt| dt.to_utc());
+        let close_time = self.close_time(&ob.market_ticker);
+        if !self.is_current_market(&ob.market_ticker) {
+            // Other strikes of the event only feed the sinks; the trader,
+            // tick history and per-series gauges follow the current market.
+            let meta = EventMeta::new(Exchange::Kalshi, &ob.market_ticker, Utc::now())
+                .with_series(asset)
+                .with_expiry(close_time);
+            self.publish(ob.quote_event(meta));
+            return;
+        }
 
-        let update = TickUpdate::from_orderbook(ob, asset, close_time);
-        if let Err(e) = self.market_data_tx.try_send(update.clone()) {
-            error!("Failed to queue market data update: {}", e);
+        let mut update = TickUpdate::from_orderbook(ob, asset, close_time);
+        update.reference_price = self.reference_price(ob);
+        if let Some(reference) = update.reference_price {
+            metrics::set(
+                names::KALSHI_TOP_OF_BOOK,
+                &[("market", &update.asset), ("side", "reference")],
+                reference,
+            );
+        }
+        for (side, price) in [
+            ("yes_bid", update.yes_bid),
+            ("yes_ask", update.yes_ask),
+            ("no_bid", update.no_bid),
+            ("no_ask", update.no_ask),
+        ] {
+            metrics::set(
+                names::KALSHI_TOP_OF_BOOK,
+                &[("market", &update.asset), ("side", side)],
+                price,
+            );
         }
+
+        if !self.leader.is_leader() {
+            return;
+        }
+
+        if let Some(market_data_tx) = &self.market_data_tx {
+            let record = MarketDataRecord {
+                ticker: update.ticker.clone(),
+                asset: update.asset.clone(),
+                timestamp: update.timestamp,
+                yes_ask: update.yes_ask,
+                yes_bid: update.yes_bid,
+                no_ask: update.no_ask,
+                no_bid: update.no_bid,
+            };
+            if let Err(e) = market_data_tx.try_send(record) {
+                error!("Failed to queue market data update: {}", e);
+            }
+        }
+        let meta = EventMeta::new(Exchange::Kalshi, &update.ticker, update.timestamp)
+            .with_series(&update.asset)
+            .with_expiry(close_time);
+        self.publish(ob.quote_event(meta));
         if let Err(e) = self.trading_tx.try_send(update) {
             error!("Failed to queue trading update: {}", e);
         }
     }
 
+    /// Evicts markets retired longer than [`RETIRED_MARKET_TTL_SECS`] from
+    /// the shared state and the status board.
+    pub fn evict_retired_markets(&self) {
+        let evicted = self
+            .state
+            .evict_retired(Duration::from_secs(RETIRED_MARKET_TTL_SECS));
+        if evicted.is_empty() {
+            return;
+        }
+        for ticker in &evicted {
+            status::board().untrack_market(ticker);
+        }
+        info!("🧹 Evicted {} retired markets: {:?}", evicted.len(), evicted);
+    }
+
+    /// Stops tracking `series`: its current and event markets are retired
+    /// and leave the next subscription.
+    pub fn untrack_series(&mut self, series: &str) {
+        self.series_tickers.retain(|s| s != series);
+        self.event_series.remove(series);
+        let markets = self
+            .current_markets
+            .remove(series)
+            .into_iter()
+            .chain(self.event_markets.remove(series).into_iter().flatten());
+        for market in markets {
+            self.state.retire_market(&market.ticker);
+        }
+        self.market_to_series.retain(|_, s| s != series);
+        info!("🗑️ Stopped tracking series {}", series);
+    }
+
     pub fn track_market(&self, market: &KalshiMarket) {
         info!("🪄 Tracking market: {} ({:?})", market.ticker, market.status);
+        self.state.reinstate_market(&market.ticker);
         self.state
             .tracked_markets
             .insert(market.ticker.clone(), market.clone());
+        self.persist_market(market);
+    }
+
+    /// Upserts the market's definition into `kalshi_markets`. Like other
+    /// persistence, leader only.
+    pub fn persist_market(&self, market: &KalshiMarket) {
+        let Some(db) = self.db.clone().filter(|_| self.leader.is_leader()) else {
+            return;
+        };
+        let series = market
+            .series_ticker
+            .clone()
+            .or_else(|| self.market_to_series.get(&market.ticker).cloned());
+        let record = market_record(market, series);
+        tokio::spawn(async move {
+            match db.upsert_kalshi_market(&record).await {
+                Ok(()) => metrics::inc(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "kalshi_markets")]),
+                Err(e) => {
+                    error!("Failed to store market {}: {}", record.ticker, e);
+                    metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", "kalshi_markets")]);
+                    notify::publish(Notification::DbFailure {
+                        table: "kalshi_markets".to_string(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        });
+    }
+}
+
+fn market_record(market: &KalshiMarket, series_ticker: Option<String>) -> KalshiMarketRecord {
+    let time = |t: &Option<String>| {
+        t.as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.to_utc())
+    };
+    KalshiMarketRecord {
+        ticker: market.ticker.clone(),
+        series_ticker,
+        event_ticker: market.event_ticker.clone(),
+        title: market.title.clone(),
+        floor_strike: market.extra.get("floor_strike").and_then(|v| v.as_f64()),
+        cap_strike: market.extra.get("cap_strike").and_then(|v| v.as_f64()),
+        open_time: time(&market.open_time),
+        close_time: time(&market.close_time),
+        expiration_time: time(&market.expiration_time),
+        status: market.status.as_str().to_string(),
+        result: market
+            .extra
+            .get("result")
+            .and_then(|v| v.as_str())
+            .filter(|r| !r.is_empty())
+            .map(str::to_string),
+        updated_at: Utc::now(),
     }
 }
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use super::api::KalshiApi;
+use super::models::KalshiSeries;
+use crate::config::DiscoveryConfig;
+
+/// Finds series to track by listing the configured categories, so newly
+/// listed series (say a fresh 15-minute crypto contract) are picked up
+/// without a config change.
+pub struct SeriesDiscovery {
+    config: DiscoveryConfig,
+}
+
+impl SeriesDiscovery {
+    pub fn new(config: DiscoveryConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.config.interval_secs)
+    }
+
+    pub fn matches(&self, series: &KalshiSeries) -> bool {
+        let ticker = series.ticker.to_uppercase();
+        let included = self.config.include.is_empty()
+            || self.config.include.iter().any(|p| glob_match(p, &ticker));
+        let excluded = self.config.exclude.iter().any(|p| glob_match(p, &ticker));
+        let frequency_ok = match &self.config.frequency {
+            Some(wanted) => series.frequency.as_deref() == Some(wanted.as_str()),
+            None => true,
+        };
+        let owned = self.config.shard.map(|s| s.owns(&ticker)).unwrap_or(true);
+        included && !excluded && frequency_ok && owned
+    }
+
+    /// Series that match the filters and are not in `known`, uppercased like
+    /// configured tickers. Categories that fail to list are skipped.
+    pub async fn discover(&self, api: &KalshiApi, known: &[String]) -> Vec<String> {
+        let mut found = Vec::new();
+        for category in &self.config.categories {
+            let series = match api.fetch_series(category).await {
+                Ok(series) => series,
+                Err(e) => {
+                    warn!("Series discovery failed for category {}: {}", category, e);
+                    continue;
+                }
+            };
+            for s in series.iter().filter(|s| self.matches(s)) {
+                let ticker = s.ticker.to_uppercase();
+                if !known.contains(&ticker) && !found.contains(&ticker) {
+                    info!(
+                        "🔭 Discovered series {} ({}) in {}",
+                        ticker,
+                        s.title.as_deref().unwrap_or("untitled"),
+                        category
+                    );
+                    found.push(ticker);
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` stands for any run of
+/// characters. Comparison is exact otherwise.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || !text[first.len()..].ends_with(last) {
+        return false;
+    }
+
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
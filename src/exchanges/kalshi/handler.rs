@@ -1,17 +1,37 @@
-use chrono::Utc;
-use tracing::{error, info, warn};
+use chrono::{DateTime, Utc};
+use tracing::{debug, error, info, warn};
 
 use super::context::ClientContext;
 use super::models::{
-    KalshiMarketLifecycleMsg, KalshiMarketStatus, KalshiOrderbook, KalshiOrderbookDelta,
-    KalshiOrderbookSnapshot, KalshiWsMessage,
+    KalshiFill, KalshiMarketLifecycleMsg, KalshiMarketStatus, KalshiOrderbook, KalshiOrderbookDelta,
+    KalshiOrderbookSnapshot, KalshiTrade, KalshiWsMessage,
 };
+use super::reference::LastTrade;
 use crate::error::Result;
+use crate::exchanges::event::{LifecycleEvent, MarketEvent};
+use crate::metrics::{self, names};
+use crate::state;
+use crate::status;
 
 pub(crate) struct MessageHandler;
 
 impl MessageHandler {
     pub async fn handle(ctx: &mut ClientContext, msg: KalshiWsMessage) -> Result<()> {
+        let msg_type = msg.msg_type.as_deref().unwrap_or("unknown");
+        metrics::inc(names::WS_MESSAGES_TOTAL, &[("exchange", "kalshi"), ("type", msg_type)]);
+        state::session().record_message("kalshi", msg_type);
+        metrics::set(
+            names::WS_LAST_MESSAGE_TIMESTAMP,
+            &[("exchange", "kalshi")],
+            Utc::now().timestamp() as f64,
+        );
+
+        if let (Some(sid), Some(seq)) = (msg.sid, msg.seq) {
+            if ctx.sequence_filter.is_duplicate(&sid, seq as i64) {
+                return Ok(());
+            }
+        }
+
         if msg.is_subscribed() {
             return Self::on_subscription_confirm(ctx, &msg);
         }
@@ -37,6 +57,8 @@ impl MessageHandler {
             Some("orderbook_snapshot") => Self::on_orderbook_snapshot(ctx, payload).await,
             Some("orderbook_delta") => Self::on_orderbook_delta(ctx, payload).await,
             Some("market_lifecycle_v2") => Self::on_market_lifecycle(ctx, payload).await,
+            Some("trade") => Self::on_trade(ctx, payload),
+            Some("fill") => Self::on_fill(ctx, payload),
             _ => Ok(()),
         }
     }
@@ -68,13 +90,14 @@ impl MessageHandler {
             Ok(s) => s,
             Err(e) => {
                 warn!("Failed to parse orderbook snapshot: {}, payload: {:?}", e, payload);
+                state::session().record_decode_error("kalshi");
                 return Ok(());
             }
         };
 
         let ticker = snapshot.market_ticker.clone();
         if ctx.resolve_series_ticker(&ticker).is_none() {
-            info!("Skipping orderbook snapshot data for unknown/expired market: {}", ticker);
+            debug!("Skipping orderbook snapshot data for unknown/expired market: {}", ticker);
             return Ok(());
         }
 
@@ -84,8 +107,14 @@ impl MessageHandler {
             .entry(ticker.clone())
             .or_insert_with(|| KalshiOrderbook::new_empty(ticker));
 
+        let previous_top = entry.top_of_book();
         entry.apply_snapshot(snapshot);
         entry.log_summary();
+        ctx.update_imbalance(&entry);
+        ctx.publish(entry.snapshot_event(ctx.event_meta(&entry.market_ticker)));
+        if let Some(event) = entry.top_change_event(previous_top, ctx.event_meta(&entry.market_ticker)) {
+            ctx.publish(event);
+        }
         ctx.queue_market_data_update(&entry);
 
         Ok(())
@@ -96,13 +125,14 @@ impl MessageHandler {
             Ok(d) => d,
             Err(e) => {
                 warn!("Failed to parse orderbook delta: {}, payload: {:?}", e, payload);
+                state::session().record_decode_error("kalshi");
                 return Ok(());
             }
         };
 
         let ticker = delta.market_ticker.clone();
         if ctx.resolve_series_ticker(&ticker).is_none() {
-            info!("Skipping orderbook delta data for unknown/expired market: {}", ticker);
+            debug!("Skipping orderbook delta data for unknown/expired market: {}", ticker);
             return Ok(());
         }
 
@@ -112,22 +142,74 @@ impl MessageHandler {
             .entry(ticker.clone())
             .or_insert_with(|| KalshiOrderbook::new_empty(ticker));
 
+        let previous_top = entry.top_of_book();
         if let Err(e) = entry.apply_delta(&delta) {
             warn!("{}", e);
             return Ok(());
         }
 
         entry.log_summary();
+        ctx.update_imbalance(&entry);
+        if let Some(event) = entry.delta_event(&delta, ctx.event_meta(&entry.market_ticker)) {
+            ctx.publish(event);
+        }
+        if let Some(event) = entry.top_change_event(previous_top, ctx.event_meta(&entry.market_ticker)) {
+            ctx.publish(event);
+        }
         ctx.queue_market_data_update(&entry);
 
         Ok(())
     }
 
+    fn on_trade(ctx: &ClientContext, payload: serde_json::Value) -> Result<()> {
+        let trade: KalshiTrade = match serde_json::from_value(payload.clone()) {
+            Ok(t) => t,
+            Err(e) => {
+                warn!("Failed to parse trade: {}, payload: {:?}", e, payload);
+                state::session().record_decode_error("kalshi");
+                return Ok(());
+            }
+        };
+
+        if ctx.resolve_series_ticker(&trade.market_ticker).is_none() {
+            return Ok(());
+        }
+        if let Some(yes_price) = trade.yes_price_f64() {
+            ctx.state.last_trades.insert(
+                trade.market_ticker.clone(),
+                LastTrade {
+                    yes_price,
+                    at: trade.timestamp().unwrap_or_else(Utc::now),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn on_fill(ctx: &ClientContext, payload: serde_json::Value) -> Result<()> {
+        let Some(fills_tx) = &ctx.fills_tx else {
+            return Ok(());
+        };
+        let fill: KalshiFill = match serde_json::from_value(payload.clone()) {
+            Ok(f) => f,
+            Err(e) => {
+                warn!("Failed to parse fill: {}, payload: {:?}", e, payload);
+                state::session().record_decode_error("kalshi");
+                return Ok(());
+            }
+        };
+        if let Err(e) = fills_tx.try_send(fill) {
+            error!("Failed to forward fill to execution: {}", e);
+        }
+        Ok(())
+    }
+
     async fn on_market_lifecycle(ctx: &mut ClientContext, payload: serde_json::Value) -> Result<()> {
         let msg: KalshiMarketLifecycleMsg = match serde_json::from_value(payload.clone()) {
             Ok(m) => m,
             Err(e) => {
                 warn!("Failed to parse market lifecycle: {}, payload: {:?}", e, payload);
+                state::session().record_decode_error("kalshi");
                 return Ok(());
             }
         };
@@ -154,6 +236,12 @@ impl MessageHandler {
             "🔄 {} -> {:?} (event: {:?})",
             msg.market_ticker, new_status, msg.event_type
         );
+        ctx.publish(MarketEvent::Lifecycle(LifecycleEvent {
+            meta: ctx.event_meta(&msg.market_ticker),
+            status: new_status.as_str().to_string(),
+            result: msg.result.clone(),
+        }));
+        Self::update_tracked_market(ctx, &msg, new_status);
 
         if new_status == KalshiMarketStatus::Closed || new_status == KalshiMarketStatus::Settled {
             Self::on_market_close(ctx, &msg, &series_ticker).await;
@@ -162,12 +250,36 @@ impl MessageHandler {
         Ok(())
     }
 
+    /// Applies the lifecycle change to the tracked market and stores the
+    /// updated definition.
+    fn update_tracked_market(ctx: &ClientContext, msg: &KalshiMarketLifecycleMsg, status: KalshiMarketStatus) {
+        let market = {
+            let Some(mut market) = ctx.state.tracked_markets.get_mut(&msg.market_ticker) else {
+                return;
+            };
+            let rfc3339 = |ts: i64| DateTime::from_timestamp(ts, 0).map(|t| t.to_rfc3339());
+            market.status = status;
+            if let Some(open_time) = msg.open_ts.and_then(rfc3339) {
+                market.open_time = Some(open_time);
+            }
+            if let Some(close_time) = msg.close_ts.and_then(rfc3339) {
+                market.close_time = Some(close_time);
+            }
+            if let (Some(result), Some(extra)) = (&msg.result, market.extra.as_object_mut()) {
+                extra.insert("result".to_string(), serde_json::Value::String(result.clone()));
+            }
+            market.clone()
+        };
+        ctx.persist_market(&market);
+    }
+
     async fn on_market_close(
         ctx: &mut ClientContext,
         msg: &KalshiMarketLifecycleMsg,
         series_ticker: &str,
     ) {
-        if let Some(result) = &msg.result {
+        let result = msg.result.as_ref().filter(|_| ctx.leader.is_leader());
+        if let (Some(result), Some(db)) = (result, ctx.db.clone()) {
             let strike_price = ctx
                 .current_markets
                 .get(series_ticker)
@@ -180,7 +292,6 @@ impl MessageHandler {
 
             let ticker = msg.market_ticker.clone();
             let result = result.clone();
-            let db = ctx.db.clone();
             let now = Utc::now();
             tokio::spawn(async move {
                 if let Err(e) = db.insert_market_info(&ticker, now, strike_price, &result).await {
@@ -195,6 +306,8 @@ impl MessageHandler {
         );
 
         ctx.market_to_series.remove(&msg.market_ticker);
+        ctx.state.retire_market(&msg.market_ticker);
+        status::board().untrack_market(&msg.market_ticker);
 
         let is_still_current = ctx
             .current_markets
@@ -204,5 +317,8 @@ impl MessageHandler {
         if is_still_current {
             ctx.current_markets.remove(series_ticker);
         }
+        if let Some(siblings) = ctx.event_markets.get_mut(series_ticker) {
+            siblings.retain(|m| m.ticker != msg.market_ticker);
+        }
     }
 }
@@ -1,12 +1,17 @@
+pub mod announcements;
 pub mod api;
 pub mod auth;
+#[cfg(feature = "db")]
+pub mod backfill;
 pub mod client;
 mod context;
 pub mod constants;
+pub mod discovery;
 mod handler;
-pub mod market_data;
 pub mod models;
 pub mod orderbook;
+pub mod reference;
+pub mod replay;
 mod subscriptions;
 pub mod utils;
 pub mod websocket;
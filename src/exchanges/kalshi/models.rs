@@ -3,6 +3,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use std::str::FromStr;
 
 use crate::trader::constants::FILL_OR_KILL_ORDER_PRICE;
+use crate::utils::numeric;
 
 #[derive(Debug, Serialize)]
 pub struct SubscribeMessage {
@@ -60,13 +61,31 @@ impl UnsubscribeMessage {
             },
         }
     }
+
+    /// Unsubscribes `market_tickers` from `channels`, for subscriptions whose
+    /// sid never arrived.
+    pub fn by_channel(id: u64, channels: Vec<String>, market_tickers: Vec<String>) -> Self {
+        Self {
+            id,
+            cmd: "unsubscribe".to_string(),
+            params: UnsubscribeParams {
+                sids: None,
+                channels: Some(channels),
+                market_tickers: Some(market_tickers),
+            },
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct KalshiWsMessage {
+    /// Echo of the command id on replies to subscribe/unsubscribe
+    pub id: Option<u64>,
     #[serde(rename = "type")]
     pub msg_type: Option<String>,
     pub sid: Option<u64>,
+    /// Per-subscription sequence number; restarts with every new subscription.
+    pub seq: Option<u64>,
     pub msg: Option<serde_json::Value>,
     pub data: Option<serde_json::Value>,
     pub status: Option<String>,
@@ -140,8 +159,8 @@ impl KalshiTicker {
     pub fn yes_bid_f64(&self) -> Option<f64> {
         // Try dollars string first
         self.yes_bid_dollars
-            .as_ref()
-            .and_then(|d| d.parse::<f64>().ok())
+            .as_deref()
+            .and_then(|d| numeric::checked("kalshi", "yes_bid_dollars", d))
             .or_else(|| {
                 // Fall back to cents converted to decimal (cents / 100)
                 self.yes_bid.map(|cents| cents as f64 / 100.0)
@@ -151,8 +170,8 @@ impl KalshiTicker {
     /// Get NO bid price as f64 (from dollars string)
     pub fn no_bid_f64(&self) -> Option<f64> {
         self.no_bid_dollars
-            .as_ref()
-            .and_then(|s| s.parse::<f64>().ok())
+            .as_deref()
+            .and_then(|d| numeric::checked("kalshi", "no_bid_dollars", d))
     }
 
     /// Get NO ask price as f64 (inferred from YES bid: 1 - yes_bid)
@@ -163,8 +182,8 @@ impl KalshiTicker {
     /// Get last price as f64 (from dollars string or cents converted to decimal)
     pub fn price_f64(&self) -> Option<f64> {
         self.price_dollars
-            .as_ref()
-            .and_then(|d| d.parse::<f64>().ok())
+            .as_deref()
+            .and_then(|d| numeric::checked("kalshi", "price_dollars", d))
             .or_else(|| {
                 self.price.map(|cents| cents as f64 / 100.0)
             })
@@ -200,17 +219,83 @@ pub struct KalshiMarket {
     pub open_interest: Option<i64>,
     pub category: Option<String>,
     pub series_ticker: Option<String>,
+    pub event_ticker: Option<String>,
     #[serde(flatten)]
     pub extra: serde_json::Value,
 }
 
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KalshiSeries {
+    pub ticker: String,
+    pub title: Option<String>,
+    pub category: Option<String>,
+    /// Listing cadence, e.g. `fifteen_min`, `hourly`, `daily`.
+    pub frequency: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+/// An exchange notice from `GET /exchange/announcements`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiAnnouncement {
+    /// `info`, `warning` or `error`
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    pub message: String,
+    #[serde(default)]
+    pub delivery_time: Option<String>,
+    /// `active` or `inactive`
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+impl KalshiAnnouncement {
+    pub fn is_active(&self) -> bool {
+        self.status.as_deref().is_none_or(|s| s.eq_ignore_ascii_case("active"))
+    }
+
+    pub fn delivered_at(&self) -> Option<DateTime<Utc>> {
+        self.delivery_time
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|dt| dt.to_utc())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementsResponse {
+    #[serde(default)]
+    pub announcements: Vec<KalshiAnnouncement>,
+}
+
+/// `GET /exchange/status`
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct KalshiExchangeStatus {
+    pub exchange_active: bool,
+    pub trading_active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesResponse {
+    #[serde(default)]
+    pub series: Option<Vec<KalshiSeries>>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct MarketsResponse {
     pub markets: Vec<KalshiMarket>,
     pub cursor: Option<String>,
 }
 
+/// A page of `GET /markets/trades`, newest trade first.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TradesResponse {
+    pub trades: Vec<KalshiTrade>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct KalshiOrderbook {
     pub market_ticker: String,
@@ -239,6 +324,30 @@ pub struct KalshiOrderbookSnapshot {
     pub no_dollars: Vec<(String, i64)>,
 }
 
+/// `GET /markets/{ticker}/orderbook`. Empty sides come back as `null`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderbookResponse {
+    pub orderbook: RestOrderbook,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestOrderbook {
+    #[serde(default)]
+    pub yes_dollars: Option<Vec<(String, i64)>>,
+    #[serde(default)]
+    pub no_dollars: Option<Vec<(String, i64)>>,
+}
+
+impl RestOrderbook {
+    pub fn into_snapshot(self, market_ticker: String) -> KalshiOrderbookSnapshot {
+        KalshiOrderbookSnapshot {
+            market_ticker,
+            yes_dollars: self.yes_dollars.unwrap_or_default(),
+            no_dollars: self.no_dollars.unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct KalshiOrderbookDelta {
     pub market_ticker: String,
@@ -325,13 +434,59 @@ pub struct KalshiMarketLifecycleMsg {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct KalshiTrade {
+    #[serde(alias = "ticker")]
     pub market_ticker: String,
     pub trade_id: Option<String>,
+    #[serde(alias = "taker_side")]
     pub side: Option<String>,
-    pub yes_price: Option<f64>,
+    pub yes_price: Option<f64>, // Cents
     pub no_price: Option<f64>,
+    #[serde(default)]
+    pub yes_price_dollars: Option<String>,
     pub count: Option<i64>,
     pub created_time: Option<String>,
+    #[serde(default)]
+    pub ts: Option<i64>, // Unix timestamp in seconds
+}
+
+impl KalshiTrade {
+    /// YES price in dollars, preferring the dollars string over cents.
+    pub fn yes_price_f64(&self) -> Option<f64> {
+        self.yes_price_dollars
+            .as_deref()
+            .and_then(|d| numeric::checked("kalshi", "yes_price_dollars", d))
+            .or_else(|| self.yes_price.map(|cents| cents / 100.0))
+    }
+
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.ts
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .or_else(|| {
+                self.created_time
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|dt| dt.to_utc())
+            })
+    }
+}
+
+/// One of our own orders filling, from the authenticated `fill` channel.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KalshiFill {
+    pub trade_id: String,
+    pub order_id: String,
+    pub market_ticker: String,
+    #[serde(default)]
+    pub is_taker: bool,
+    pub side: OrderSide,
+    /// Cents
+    #[serde(default)]
+    pub yes_price: Option<i64>,
+    pub count: i64,
+    #[serde(default)]
+    pub action: Option<String>,
+    #[serde(default)]
+    pub ts: Option<i64>, // Unix timestamp in seconds
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -340,6 +495,7 @@ pub enum KalshiChannel {
     OrderbookDelta,
     Trade,
     MarketLifecycle,
+    Fill,
 }
 
 impl KalshiChannel {
@@ -349,6 +505,7 @@ impl KalshiChannel {
             KalshiChannel::OrderbookDelta => "orderbook_delta",
             KalshiChannel::Trade => "trade",
             KalshiChannel::MarketLifecycle => "market_lifecycle_v2",
+            KalshiChannel::Fill => "fill",
         }
     }
 }
@@ -377,7 +534,7 @@ impl KalshiMarketStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OrderSide {
     Yes,
@@ -419,6 +576,8 @@ pub struct CreateOrderRequest {
     pub no_price: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_order_id: Option<String>,
 }
 
 impl CreateOrderRequest {
@@ -432,6 +591,7 @@ impl CreateOrderRequest {
             yes_price: None,
             no_price: None,
             post_only: None,
+            client_order_id: None,
         };
 
         match side {
@@ -452,6 +612,11 @@ impl CreateOrderRequest {
         base.post_only = Some(true);
         base
     }
+
+    pub fn with_client_order_id(mut self, client_order_id: Option<String>) -> Self {
+        self.client_order_id = client_order_id;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -513,6 +678,33 @@ pub struct GetOrdersResponse {
     pub cursor: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetOrderResponse {
+    pub order: KalshiOrder,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiMarketPosition {
+    pub ticker: String,
+    /// Contracts held; positive for YES, negative for NO.
+    pub position: i64,
+    /// Cost of the open position in cents.
+    #[serde(default)]
+    pub market_exposure: i64,
+    #[serde(default)]
+    pub realized_pnl: i64,
+    #[serde(default)]
+    pub fees_paid: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PositionsResponse {
+    #[serde(default)]
+    pub market_positions: Vec<KalshiMarketPosition>,
+    #[serde(default)]
+    pub cursor: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct BatchCancelOrdersRequest {
     pub orders: Vec<KalshiCancelOrder>,
@@ -547,6 +739,9 @@ pub struct TickUpdate {
     pub yes_ask_qty: i64,
     pub no_ask_qty: i64,
     pub close_time: Option<DateTime<Utc>>,
+    /// YES price per the configured reference method; `None` if the book
+    /// is one-sided with no trade to fall back on.
+    pub reference_price: Option<f64>,
 }
 
 impl TickUpdate {
@@ -566,6 +761,7 @@ impl TickUpdate {
             yes_ask_qty: ob.yes_ask_qty_at_or_above(FILL_OR_KILL_ORDER_PRICE),
             no_ask_qty: ob.no_ask_qty_at_or_above(FILL_OR_KILL_ORDER_PRICE),
             close_time,
+            reference_price: None,
         }
     }
 
@@ -1,8 +1,29 @@
 use chrono::Utc;
-use tracing::info;
+use tracing::debug;
 
+use crate::exchanges::event::{
+    BinaryQuote, BookEvent, EventMeta, KalshiTopOfBookChanged, MarketEvent, QuoteEvent, TopOfBook,
+};
+use crate::config::ImbalanceConfig;
+use crate::exchanges::binance::sbe::events::depth::DepthImbalance;
+use crate::exchanges::PriceLevel;
+use crate::instruments;
+use crate::trader::constants::FILL_OR_KILL_ORDER_PRICE;
+use crate::utils::numeric;
+
+use super::reference::YesQuote;
 use super::models::{KalshiOrderbook, KalshiOrderbookDelta, KalshiOrderbookSnapshot, OrderbookLevel};
 
+fn price_levels(levels: &[OrderbookLevel]) -> Vec<PriceLevel> {
+    levels
+        .iter()
+        .map(|l| PriceLevel {
+            price: l.price,
+            quantity: l.quantity as f64,
+        })
+        .collect()
+}
+
 impl KalshiOrderbook {
     pub fn new_empty(market_ticker: String) -> Self {
         Self {
@@ -18,7 +39,7 @@ impl KalshiOrderbook {
         self.yes_bids = Self::parse_dollar_levels(snapshot.yes_dollars);
         self.no_bids = Self::parse_dollar_levels(snapshot.no_dollars);
 
-        info!(
+        debug!(
             "📸 Orderbook snapshot for {} ({} YES levels, {} NO levels)",
             self.market_ticker,
             self.yes_bids.len(),
@@ -30,10 +51,10 @@ impl KalshiOrderbook {
     }
 
     pub fn apply_delta(&mut self, delta: &KalshiOrderbookDelta) -> std::result::Result<(), String> {
-        let price = delta
-            .price_dollars
-            .parse::<f64>()
-            .map_err(|e| format!("Failed to parse delta price '{}': {}", delta.price_dollars, e))?;
+        let price = numeric::parse_decimal(&delta.price_dollars).ok_or_else(|| {
+            numeric::report("kalshi", "price_dollars", &delta.price_dollars);
+            format!("Failed to parse delta price '{}'", delta.price_dollars)
+        })?;
 
         let levels = if delta.side.eq_ignore_ascii_case("yes") {
             &mut self.yes_bids
@@ -62,6 +83,61 @@ impl KalshiOrderbook {
         Ok(())
     }
 
+    pub fn quote_event(&self, meta: EventMeta) -> MarketEvent {
+        let top_qty = |levels: &[OrderbookLevel]| levels.first().map_or(0.0, |l| l.quantity as f64);
+        MarketEvent::Quote(QuoteEvent {
+            meta,
+            bid: self.top_yes_bid(),
+            bid_qty: top_qty(&self.yes_bids),
+            ask: self.top_yes_ask(),
+            ask_qty: top_qty(&self.yes_asks),
+            binary: Some(BinaryQuote {
+                no_bid: self.top_no_bid(),
+                no_ask: self.top_no_ask(),
+                yes_ask_depth: self.yes_ask_qty_at_or_above(FILL_OR_KILL_ORDER_PRICE),
+                no_ask_depth: self.no_ask_qty_at_or_above(FILL_OR_KILL_ORDER_PRICE),
+            }),
+        })
+    }
+
+    pub fn snapshot_event(&self, meta: EventMeta) -> MarketEvent {
+        MarketEvent::BookSnapshot(BookEvent {
+            meta,
+            bids: price_levels(&self.yes_bids),
+            asks: price_levels(&self.yes_asks),
+        })
+    }
+
+    /// The level `delta` touched, with its size after the delta was applied.
+    pub fn delta_event(&self, delta: &KalshiOrderbookDelta, meta: EventMeta) -> Option<MarketEvent> {
+        let price = numeric::parse_decimal(&delta.price_dollars)?;
+        let quantity_at = |levels: &[OrderbookLevel]| {
+            levels
+                .iter()
+                .find(|l| (l.price - price).abs() < 1e-12)
+                .map_or(0.0, |l| l.quantity as f64)
+        };
+
+        let (bids, asks) = if delta.side.eq_ignore_ascii_case("yes") {
+            (vec![PriceLevel { price, quantity: quantity_at(&self.yes_bids) }], Vec::new())
+        } else {
+            (Vec::new(), vec![PriceLevel { price: 1.0 - price, quantity: quantity_at(&self.no_bids) }])
+        };
+        Some(MarketEvent::BookDelta(BookEvent { meta, bids, asks }))
+    }
+
+    /// A [`MarketEvent::TopOfBookChanged`] if the top no longer matches
+    /// `previous`, taken with [`top_of_book`](Self::top_of_book) before the
+    /// update was applied.
+    pub fn top_change_event(&self, previous: TopOfBook, meta: EventMeta) -> Option<MarketEvent> {
+        let current = self.top_of_book();
+        (current != previous).then_some(MarketEvent::TopOfBookChanged(KalshiTopOfBookChanged {
+            meta,
+            previous,
+            current,
+        }))
+    }
+
     pub fn derive_asks_from_bids(&mut self) {
         self.yes_asks = self
             .no_bids
@@ -101,12 +177,13 @@ impl KalshiOrderbook {
     }
 
     pub fn log_summary(&self) {
+        let precision = instruments::registry().kalshi(&self.market_ticker);
         let fmt_level = |l: Option<&OrderbookLevel>| {
-            l.map(|l| format!("${:.4} @ {}", l.price, l.quantity))
+            l.map(|l| format!("${} @ {}", precision.price(l.price), l.quantity))
                 .unwrap_or_else(|| "N/A".to_string())
         };
 
-        info!(
+        debug!(
             "📚 Kalshi {} | YES bid: {} | YES ask: {} | NO bid: {} | NO ask: {} at {}",
             self.market_ticker,
             fmt_level(self.yes_bids.first()),
@@ -133,6 +210,39 @@ impl KalshiOrderbook {
         self.no_asks.first().map(|l| l.price).unwrap_or(0.0)
     }
 
+    pub fn top_of_book(&self) -> TopOfBook {
+        let top_qty = |levels: &[OrderbookLevel]| levels.first().map_or(0.0, |l| l.quantity as f64);
+        TopOfBook {
+            bid: self.top_yes_bid(),
+            bid_qty: top_qty(&self.yes_bids),
+            ask: self.top_yes_ask(),
+            ask_qty: top_qty(&self.yes_asks),
+        }
+    }
+
+    /// The Binance depth ratios over this book from the YES side: YES bids
+    /// against the YES asks derived from NO bids, in contracts or, under
+    /// notional weighting, dollars. `None` while there are no asks.
+    pub fn imbalance(&self, config: &ImbalanceConfig) -> Option<DepthImbalance> {
+        let levels = |levels: &[OrderbookLevel]| {
+            levels
+                .iter()
+                .map(|l| (l.price, l.quantity as f64))
+                .collect::<Vec<_>>()
+        };
+        DepthImbalance::from_levels(levels(&self.yes_bids), levels(&self.yes_asks), config)
+    }
+
+    /// Top of book from the YES side, for reference pricing.
+    pub fn yes_quote(&self) -> YesQuote {
+        YesQuote {
+            bid: self.top_yes_bid(),
+            bid_qty: self.yes_bids.first().map(|l| l.quantity as f64).unwrap_or(0.0),
+            ask: self.top_yes_ask(),
+            ask_qty: self.yes_asks.first().map(|l| l.quantity as f64).unwrap_or(0.0),
+        }
+    }
+
     pub fn yes_ask_qty_at_or_above(&self, min_price: f64) -> i64 {
         self.yes_asks
             .iter()
@@ -153,9 +263,7 @@ impl KalshiOrderbook {
         dollars
             .into_iter()
             .filter_map(|(p, q)| {
-                p.parse::<f64>()
-                    .ok()
-                    .map(|price| OrderbookLevel { price, quantity: q })
+                numeric::checked("kalshi", "level_price", &p).map(|price| OrderbookLevel { price, quantity: q })
             })
             .collect()
     }
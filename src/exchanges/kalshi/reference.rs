@@ -0,0 +1,123 @@
+//! The single YES price a Kalshi market is valued at.
+//!
+//! Alerts, calibration and P&L marking all need "the" price of a contract;
+//! picking `yes_ask` in one place and `yes_bid` in another makes them
+//! disagree by the spread. Everything goes through [`ReferencePricer`]
+//! instead, configured by `KALSHI_REFERENCE_PRICE`.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::config::ReferencePriceConfig;
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferencePriceMethod {
+    /// Halfway between the best YES bid and ask.
+    #[default]
+    Mid,
+    /// Mid weighted towards the side with less resting size, which is the
+    /// side more likely to trade through next.
+    Microprice,
+    /// Last traded YES price, blended back towards the mid as it ages.
+    LastTrade,
+}
+
+impl ReferencePriceMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferencePriceMethod::Mid => "mid",
+            ReferencePriceMethod::Microprice => "microprice",
+            ReferencePriceMethod::LastTrade => "last_trade",
+        }
+    }
+}
+
+impl FromStr for ReferencePriceMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mid" => Ok(ReferencePriceMethod::Mid),
+            "microprice" => Ok(ReferencePriceMethod::Microprice),
+            "last_trade" | "last" => Ok(ReferencePriceMethod::LastTrade),
+            other => Err(Error::Config(format!(
+                "Unknown reference price '{}', expected mid, microprice or last_trade",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LastTrade {
+    pub yes_price: f64,
+    pub at: DateTime<Utc>,
+}
+
+/// Top of book as seen from the YES side. Quantities are contracts.
+#[derive(Debug, Clone, Copy)]
+pub struct YesQuote {
+    pub bid: f64,
+    pub bid_qty: f64,
+    pub ask: f64,
+    pub ask_qty: f64,
+}
+
+impl YesQuote {
+    fn is_two_sided(&self) -> bool {
+        self.bid > 0.0 && self.ask > 0.0 && self.ask >= self.bid
+    }
+
+    fn mid(&self) -> Option<f64> {
+        self.is_two_sided().then(|| (self.bid + self.ask) / 2.0)
+    }
+
+    fn microprice(&self) -> Option<f64> {
+        if !self.is_two_sided() {
+            return None;
+        }
+        let total = self.bid_qty + self.ask_qty;
+        if total <= 0.0 {
+            return self.mid();
+        }
+        Some((self.bid * self.ask_qty + self.ask * self.bid_qty) / total)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReferencePricer {
+    config: ReferencePriceConfig,
+}
+
+impl ReferencePricer {
+    pub fn new(config: ReferencePriceConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn method(&self) -> ReferencePriceMethod {
+        self.config.method
+    }
+
+    /// The YES reference price, or `None` when the book is one-sided and
+    /// there is no trade to fall back on.
+    pub fn price(&self, quote: YesQuote, last_trade: Option<LastTrade>, now: DateTime<Utc>) -> Option<f64> {
+        match self.config.method {
+            ReferencePriceMethod::Mid => quote.mid(),
+            ReferencePriceMethod::Microprice => quote.microprice(),
+            ReferencePriceMethod::LastTrade => {
+                let Some(trade) = last_trade else {
+                    return quote.mid();
+                };
+                let Some(mid) = quote.mid() else {
+                    return Some(trade.yes_price);
+                };
+                let age_secs = (now - trade.at).num_milliseconds().max(0) as f64 / 1000.0;
+                let half_life = self.config.half_life_secs.max(1) as f64;
+                let weight = 0.5f64.powf(age_secs / half_life);
+                Some(weight * trade.yes_price + (1.0 - weight) * mid)
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Recorded Kalshi frames through the client's message handling.
+//!
+//! [`KalshiFeed`] applies `.wsrec` frames, as played back by
+//! [`Replayer`](crate::replay::Replayer), to the same context and handler
+//! the live client uses, so books, lifecycle changes and rollovers end up in
+//! its [`KalshiState`] the way they would live.
+//!
+//! Recordings only hold what the exchange sent, so the feed infers what the
+//! client did in between: a subscription confirmed with an id that is
+//! already in use means a new connection, where ids start over, and an
+//! `unsubscribed` reply drops its id like the client's unsubscribe does.
+
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use super::context::ClientContext;
+use super::handler::MessageHandler;
+use super::models::{KalshiMarket, KalshiWsMessage};
+use crate::error::Result;
+use crate::exchanges::kalshi::TickUpdate;
+use crate::recorder::{FrameSource, RawFrame};
+use crate::state::KalshiState;
+use crate::trader::constants::TRADING_CHANNEL_BUFFER;
+
+pub struct KalshiFeed {
+    ctx: ClientContext,
+    trading_rx: mpsc::Receiver<TickUpdate>,
+}
+
+impl KalshiFeed {
+    pub fn new() -> Self {
+        let (trading_tx, trading_rx) = mpsc::channel(TRADING_CHANNEL_BUFFER);
+        Self {
+            ctx: ClientContext::new(Vec::new(), Vec::new(), None, trading_tx, Vec::new()),
+            trading_rx,
+        }
+    }
+
+    /// Makes `market` the one `series` trades, as discovery does live.
+    pub fn set_market(&mut self, series: &str, market: &KalshiMarket) {
+        if !self.ctx.series_tickers.iter().any(|s| s == series) {
+            self.ctx.series_tickers.push(series.to_string());
+        }
+        self.ctx.set_current_market(series, market);
+    }
+
+    pub fn state(&self) -> Arc<KalshiState> {
+        self.ctx.state.clone()
+    }
+
+    /// Applies one recorded frame; frames of other sources are ignored.
+    pub async fn apply(&mut self, frame: &RawFrame) -> Result<()> {
+        if frame.source != FrameSource::KalshiJson {
+            return Ok(());
+        }
+        let msg: KalshiWsMessage = serde_json::from_slice(&frame.data)?;
+
+        if msg.is_subscribed() {
+            let sid = msg.payload().and_then(|p| p.get("sid")).and_then(|s| s.as_u64());
+            if sid.is_some_and(|sid| self.ctx.subscription_ids.values().any(|s| *s == sid)) {
+                self.ctx.on_connected();
+            }
+        } else if msg.msg_type.as_deref() == Some("unsubscribed") {
+            if let Some(sid) = msg.sid {
+                self.ctx.subscription_ids.retain(|_, s| *s != sid);
+                self.ctx.sequence_filter.forget(&sid);
+            }
+        }
+
+        MessageHandler::handle(&mut self.ctx, msg).await?;
+        // Nothing trades on a replay
+        while self.trading_rx.try_recv().is_ok() {}
+        Ok(())
+    }
+}
+
+impl Default for KalshiFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
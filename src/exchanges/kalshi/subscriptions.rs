@@ -6,10 +6,13 @@ use tracing::{error, info, warn};
 
 use super::api::KalshiApi;
 use super::context::ClientContext;
-use super::models::KalshiMarketStatus;
+use super::models::{KalshiChannel, KalshiMarketStatus};
+use super::reference::ReferencePriceMethod;
 use super::websocket::KalshiWebSocket;
 use crate::error::{Error, Result};
 use crate::exchanges::kalshi::constants::*;
+use crate::notify::{self, Notification};
+use crate::status;
 
 pub(crate) struct SubscriptionManager;
 
@@ -22,27 +25,91 @@ impl SubscriptionManager {
             return Err(Error::Other("No current markets set".into()));
         }
 
-        let tickers: Vec<String> = ctx.current_markets.values().map(|m| m.ticker.clone()).collect();
+        let tickers = ctx.subscribed_tickers();
         let mut ws_guard = ws.lock().await;
 
-        if let Some(sid) = ctx.subscription_ids.remove("orderbook_delta") {
-            info!("⛓️‍💥 Unsubscribing from orderbook with sid: {:?}", sid);
-            ws_guard.unsubscribe(vec![sid]).await?;
+        Self::unsubscribe_channel(ctx, &mut ws_guard, KalshiChannel::OrderbookDelta).await?;
+
+        if ctx.reference.method() == ReferencePriceMethod::LastTrade {
+            Self::unsubscribe_channel(ctx, &mut ws_guard, KalshiChannel::Trade).await?;
+            info!("💱 Subscribing to trades for {} markets", tickers.len());
+            ws_guard.subscribe_trades(tickers.clone()).await?;
         }
 
         info!("📡 Subscribing to {} markets: {:?}", tickers.len(), tickers);
         ws_guard.subscribe_orderbook(tickers).await?;
 
-        if !ctx.subscription_ids.contains_key("market_lifecycle_v2") {
+        if !ctx.subscription_ids.contains_key("market_lifecycle_v2")
+            && !ws_guard.has_channel(KalshiChannel::MarketLifecycle)
+        {
             info!("🤝 Subscribing to market lifecycle");
             ws_guard.subscribe_market_lifecycle().await?;
         }
 
+        if ctx.fills_tx.is_some()
+            && !ctx.subscription_ids.contains_key(KalshiChannel::Fill.as_str())
+            && !ws_guard.has_channel(KalshiChannel::Fill)
+        {
+            info!("🧾 Subscribing to fills");
+            ws_guard.subscribe_fills().await?;
+        }
+
         Ok(())
     }
 
+    /// Drops the subscription on `channel` by its sid, or, when the sid
+    /// never arrived, by channel and ticker.
+    async fn unsubscribe_channel(
+        ctx: &mut ClientContext,
+        ws: &mut KalshiWebSocket,
+        channel: KalshiChannel,
+    ) -> Result<()> {
+        if let Some(sid) = ctx.subscription_ids.remove(channel.as_str()) {
+            info!("⛓️‍💥 Unsubscribing from {} with sid: {:?}", channel.as_str(), sid);
+            ws.unsubscribe(vec![sid]).await?;
+            ctx.sequence_filter.forget(&sid);
+            return Ok(());
+        }
+        let dropped = ws.unsubscribe_channel(channel).await?;
+        if dropped > 0 {
+            warn!(
+                "⛓️‍💥 No sid for the {} subscription, unsubscribed {} tickers by name",
+                channel.as_str(),
+                dropped
+            );
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the market-data subscriptions at rollover: every data
+    /// channel is dropped whatever the reference method, so nothing from the
+    /// previous markets or a previous config lingers, then the current
+    /// markets are subscribed again.
+    pub async fn resync_subscriptions(
+        ctx: &mut ClientContext,
+        ws: &Arc<Mutex<KalshiWebSocket>>,
+    ) -> Result<()> {
+        {
+            let mut ws_guard = ws.lock().await;
+            let view = ws_guard.subscriptions();
+            if !view.missing.is_empty() || !view.unexpected.is_empty() {
+                warn!(
+                    "🧾 Resyncing Kalshi subscriptions: {} unconfirmed, {} not intended, {} requests pending",
+                    view.missing.len(),
+                    view.unexpected.len(),
+                    view.pending.len()
+                );
+            }
+            for channel in [KalshiChannel::OrderbookDelta, KalshiChannel::Trade] {
+                Self::unsubscribe_channel(ctx, &mut ws_guard, channel).await?;
+            }
+        }
+        Self::subscribe_all(ctx, ws).await
+    }
+
     pub async fn fetch_and_set_all(ctx: &mut ClientContext, api: &KalshiApi) -> Result<()> {
-        for series_ticker in &ctx.series_tickers {
+        let series_tickers = ctx.series_tickers.clone();
+        for series_ticker in &series_tickers {
             if let Some(existing) = ctx.current_markets.get(series_ticker) {
                 if matches!(existing.status, KalshiMarketStatus::Open | KalshiMarketStatus::Active) {
                     continue;
@@ -75,32 +142,83 @@ impl SubscriptionManager {
                     "🔄 Replacing market {} with {} for series {}",
                     old_market.ticker, next_market.ticker, series_ticker
                 );
+                notify::publish(Notification::MarketSwitch {
+                    series: series_ticker.clone(),
+                    from: old_market.ticker.clone(),
+                    to: next_market.ticker.clone(),
+                });
             } else {
                 info!("📡 Setting initial market for {}: {}", series_ticker, next_market.ticker);
             }
 
-            ctx.current_markets.insert(series_ticker.clone(), next_market.clone());
-            ctx.market_to_series.insert(next_market.ticker.clone(), series_ticker.clone());
-            ctx.track_market(next_market);
+            ctx.set_current_market(series_ticker, next_market);
 
             if let Some(floor_strike) = next_market.extra.get("floor_strike") {
                 info!("💰 Floor strike for {}: {}", next_market.ticker, floor_strike);
             }
         }
 
+        Self::fetch_event_markets(ctx, api).await;
+
         Ok(())
     }
 
+    /// Fills `event_markets` with the sibling markets of each event-level
+    /// series' current market. A failed fetch leaves that series tracking its
+    /// current market only until the next rotation.
+    async fn fetch_event_markets(ctx: &mut ClientContext, api: &KalshiApi) {
+        let series: Vec<String> = ctx.event_series.iter().cloned().collect();
+        for series_ticker in series {
+            if ctx.event_markets.contains_key(&series_ticker) {
+                continue;
+            }
+            let Some(current) = ctx.current_markets.get(&series_ticker) else {
+                continue;
+            };
+            let Some(event_ticker) = current.event_ticker.clone() else {
+                warn!("Market {} has no event ticker, tracking it alone", current.ticker);
+                continue;
+            };
+            let current_ticker = current.ticker.clone();
+
+            let markets = match api.fetch_event_markets(&event_ticker, Some("open")).await {
+                Ok(markets) => markets,
+                Err(e) => {
+                    warn!("Failed to fetch markets for event {}: {}", event_ticker, e);
+                    continue;
+                }
+            };
+
+            let siblings: Vec<_> = markets
+                .into_iter()
+                .filter(|m| m.ticker != current_ticker)
+                .filter(|m| matches!(m.status, KalshiMarketStatus::Open | KalshiMarketStatus::Active))
+                .collect();
+            info!(
+                "🪜 Tracking {} more markets of event {} for series {}",
+                siblings.len(), event_ticker, series_ticker
+            );
+            for market in &siblings {
+                ctx.market_to_series.insert(market.ticker.clone(), series_ticker.clone());
+                ctx.track_market(market);
+                status::board().track_market(&market.ticker, &series_ticker);
+            }
+            ctx.event_markets.insert(series_ticker, siblings);
+        }
+    }
+
     pub async fn handle_due_markets(
         ctx: &mut ClientContext,
         api: &KalshiApi,
         ws: &Arc<Mutex<KalshiWebSocket>>,
     ) -> Result<()> {
         info!("⏰ 15-minute interval reached, rotating all markets...");
+        for market in ctx.current_markets.values().chain(ctx.event_markets.values().flatten()) {
+            ctx.state.retire_market(&market.ticker);
+        }
         ctx.current_markets.clear();
+        ctx.event_markets.clear();
         ctx.market_to_series.clear();
-        ctx.state.orderbooks.clear();
-        ctx.state.tracked_markets.clear();
 
         let mut attempt = 0;
         loop {
@@ -140,7 +258,7 @@ impl SubscriptionManager {
             tokio::time::sleep(Duration::from_secs(MARKET_FETCH_INTERVAL_SECS)).await;
         }
 
-        Self::subscribe_all(ctx, ws).await?;
+        Self::resync_subscriptions(ctx, ws).await?;
         Ok(())
     }
 }
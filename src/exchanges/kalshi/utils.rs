@@ -1,16 +1,22 @@
 use std::time::Duration;
 
 use chrono::{Datelike, Timelike, TimeZone, Utc, Weekday};
-use chrono_tz::US::Eastern;
 use tokio::time::Instant as TokioInstant;
 
 use crate::exchanges::kalshi::constants::FETCH_AFTER_CLOSE_SECS;
+use crate::timezone;
 
+/// Kalshi's weekly maintenance runs Thursdays 3-5am in the market timezone.
 pub fn maintenance_sleep_duration() -> Option<Duration> {
-    let now_et = Utc::now().with_timezone(&Eastern);
-    if now_et.weekday() == Weekday::Thu && now_et.hour() >= 3 && now_et.hour() < 5 {
-        let end = now_et.date_naive().and_hms_opt(5, 0, 0).unwrap();
-        let end_utc = Eastern.from_local_datetime(&end).unwrap().with_timezone(&Utc);
+    let market = timezone::market();
+    let now_local = Utc::now().with_timezone(&market);
+    if now_local.weekday() == Weekday::Thu && now_local.hour() >= 3 && now_local.hour() < 5 {
+        let end = now_local.date_naive().and_hms_opt(5, 0, 0).unwrap();
+        let end_utc = market
+            .from_local_datetime(&end)
+            .earliest()
+            .unwrap_or_else(|| market.from_utc_datetime(&end))
+            .with_timezone(&Utc);
         let remaining = (end_utc - Utc::now()).to_std().unwrap_or(Duration::from_secs(60));
         Some(remaining)
     } else {
@@ -19,15 +25,16 @@ pub fn maintenance_sleep_duration() -> Option<Duration> {
 }
 
 pub fn next_maintenance_start() -> TokioInstant {
+    let market = timezone::market();
     let now = Utc::now();
-    let now_et = now.with_timezone(&Eastern);
+    let now_local = now.with_timezone(&market);
 
-    let mut target = now_et.date_naive();
+    let mut target = now_local.date_naive();
     loop {
         if target.weekday() == Weekday::Thu {
             let start = target.and_hms_opt(3, 0, 0).unwrap();
-            if let Some(start_et) = Eastern.from_local_datetime(&start).single() {
-                let start_utc = start_et.with_timezone(&Utc);
+            if let Some(start_local) = market.from_local_datetime(&start).single() {
+                let start_utc = start_local.with_timezone(&Utc);
                 if start_utc > now {
                     let secs = (start_utc - now).num_seconds().max(0) as u64;
                     return TokioInstant::now() + Duration::from_secs(secs);
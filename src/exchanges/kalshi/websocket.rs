@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
 
 use futures_util::{SinkExt, StreamExt};
@@ -10,7 +11,14 @@ use tracing::{info, warn};
 
 use super::auth::KalshiAuth;
 use super::models::{KalshiChannel, KalshiWsMessage, SubscribeMessage, UnsubscribeMessage};
-use crate::error::{Error, Result};
+use crate::config::ChaosConfig;
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::acks::{Expired, RequestKind, SubscriptionTracker, SubscriptionView};
+use crate::metrics::{self, names};
+use crate::recorder::{FrameSource, RecorderHandle};
+use crate::state;
+use crate::utils::chaos::{Fault, FaultInjector};
+use crate::utils::websocket::CloseReason;
 
 type WsStream = WebSocketStream<tokio_native_tls::TlsStream<TcpStream>>;
 
@@ -19,6 +27,11 @@ pub struct KalshiWebSocket {
     auth: Arc<KalshiAuth>,
     stream: Option<WsStream>,
     message_id: AtomicU64,
+    recorder: Option<RecorderHandle>,
+    chaos: Option<FaultInjector>,
+    acks: SubscriptionTracker,
+    /// Topics behind each confirmed subscription id, for unsubscribes
+    sid_topics: HashMap<u64, Vec<String>>,
 }
 
 impl KalshiWebSocket {
@@ -28,9 +41,23 @@ impl KalshiWebSocket {
             auth,
             stream: None,
             message_id: AtomicU64::new(1),
+            recorder: None,
+            chaos: None,
+            acks: SubscriptionTracker::new("kalshi"),
+            sid_topics: HashMap::new(),
         }
     }
 
+    pub fn with_recorder(mut self, recorder: Option<RecorderHandle>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    pub fn with_chaos(mut self, chaos: Option<ChaosConfig>) -> Self {
+        self.chaos = chaos.map(|c| FaultInjector::new(c, "kalshi"));
+        self
+    }
+
     fn next_id(&self) -> u64 {
         self.message_id.fetch_add(1, Ordering::SeqCst)
     }
@@ -59,35 +86,48 @@ impl KalshiWebSocket {
             .body(())
             .map_err(|e| Error::Connection(e.to_string()))?;
 
+        let context = |operation: &str| {
+            ErrorContext::new(operation)
+                .with_exchange("kalshi")
+                .with_endpoint(self.url.clone())
+        };
+
         let tcp_stream = TcpStream::connect(format!("{}:{}", host, port))
             .await
-            .map_err(|e| Error::Connection(e.to_string()))?;
+            .with_context(|| context("open TCP connection"))?;
 
-        let tls_connector = native_tls::TlsConnector::builder()
-            .build()
-            .map_err(|e| Error::Tls(e.to_string()))?;
+        let tls_connector = native_tls::TlsConnector::builder().build()?;
         let tls_connector = TlsConnector::from(tls_connector);
         let tls_stream = tls_connector
             .connect(host, tcp_stream)
             .await
-            .map_err(|e| Error::Tls(e.to_string()))?;
+            .with_context(|| context("TLS handshake"))?;
 
         let (ws_stream, _) = tokio_tungstenite::client_async(request, tls_stream)
             .await
-            .map_err(|e| Error::WebSocket(e.to_string()))?;
+            .with_context(|| context("WebSocket handshake"))?;
 
         self.stream = Some(ws_stream);
+        self.acks.reset();
+        self.sid_topics.clear();
         info!("🔋 Connected to Kalshi WebSocket");
 
         Ok(())
     }
 
+    /// Drops the current stream, if any, and connects again with freshly
+    /// signed auth headers. Subscriptions do not survive; callers resubscribe.
+    pub async fn reconnect(&mut self) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.close(None).await;
+        }
+        info!("🔁 Reconnecting to Kalshi WebSocket");
+        self.connect().await
+    }
+
     pub async fn disconnect(&mut self) -> Result<()> {
         if let Some(mut stream) = self.stream.take() {
-            stream
-                .close(None)
-                .await
-                .map_err(|e| Error::WebSocket(e.to_string()))?;
+            stream.close(None).await?;
         }
         info!("Disconnected from Kalshi WebSocket");
         Ok(())
@@ -105,35 +145,137 @@ impl KalshiWebSocket {
 
         let json = serde_json::to_string(msg)?;
 
-        stream
-            .send(Message::Text(json))
-            .await
-            .map_err(|e| Error::WebSocket(e.to_string()))?;
+        stream.send(Message::Text(json)).await?;
 
         Ok(())
     }
 
+    /// Sends one subscribe per channel, so each acknowledgment maps to a
+    /// single subscription id.
     pub async fn subscribe(
         &mut self,
         channels: &[KalshiChannel],
         tickers: Option<Vec<String>>,
     ) -> Result<()> {
-        let channel_strs: Vec<String> = channels.iter().map(|c| c.as_str().to_string()).collect();
-        let msg = SubscribeMessage::new(self.next_id(), channel_strs, tickers);
-        self.send_message(&msg).await?;
+        for channel in channels {
+            let topics = match &tickers {
+                Some(tickers) => tickers.iter().map(|t| format!("{}:{}", channel.as_str(), t)).collect(),
+                None => vec![channel.as_str().to_string()],
+            };
+            let msg = SubscribeMessage::new(self.next_id(), vec![channel.as_str().to_string()], tickers.clone());
+            self.send_message(&msg).await?;
+            self.acks.sent(msg.id, RequestKind::Subscribe, topics, serde_json::to_value(&msg)?);
+        }
         Ok(())
     }
 
     pub async fn unsubscribe(&mut self, sids: Vec<u64>) -> Result<()> {
+        let topics = sids
+            .iter()
+            .filter_map(|sid| self.sid_topics.remove(sid))
+            .flatten()
+            .collect();
         let msg = UnsubscribeMessage::new(self.next_id(), sids);
         self.send_message(&msg).await?;
+        self.acks.sent(msg.id, RequestKind::Unsubscribe, topics, serde_json::to_value(&msg)?);
         Ok(())
     }
 
+    /// Unsubscribes every ticker subscribed or being subscribed on `channel`
+    /// by name, for when its sid is unknown, e.g. the ack was lost. Returns
+    /// how many tickers were dropped.
+    pub async fn unsubscribe_channel(&mut self, channel: KalshiChannel) -> Result<usize> {
+        let prefix = format!("{}:", channel.as_str());
+        let topics: Vec<String> = self
+            .acks
+            .intended()
+            .filter(|t| t.starts_with(&prefix))
+            .map(str::to_string)
+            .collect();
+        if topics.is_empty() {
+            return Ok(0);
+        }
+        let tickers: Vec<String> = topics.iter().map(|t| t[prefix.len()..].to_string()).collect();
+        self.sid_topics
+            .retain(|_, sid_topics| !sid_topics.iter().any(|t| t.starts_with(&prefix)));
+
+        let msg = UnsubscribeMessage::by_channel(self.next_id(), vec![channel.as_str().to_string()], tickers);
+        self.send_message(&msg).await?;
+        self.acks.sent(msg.id, RequestKind::Unsubscribe, topics, serde_json::to_value(&msg)?);
+        Ok(msg.params.market_tickers.as_ref().map_or(0, Vec::len))
+    }
+
+    /// Resends requests left unacknowledged too long. Fails once one is out
+    /// of attempts, so the caller reconnects and resubscribes from scratch.
+    pub async fn retry_expired(&mut self) -> Result<()> {
+        for expired in self.acks.expired() {
+            match expired {
+                Expired::Retry(request) => {
+                    let id = self.next_id();
+                    self.send_message(&request.retry_payload(id)).await?;
+                    self.acks.resent(id, request);
+                }
+                Expired::Escalate(request) => {
+                    return Err(Error::WebSocket(format!(
+                        "Kalshi {} for {:?} never acknowledged",
+                        request.kind.as_str(),
+                        request.topics
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether anything on `channel` is subscribed or being subscribed.
+    pub fn has_channel(&self, channel: KalshiChannel) -> bool {
+        let name = channel.as_str();
+        self.acks
+            .intended()
+            .any(|t| t == name || t.strip_prefix(name).is_some_and(|rest| rest.starts_with(':')))
+    }
+
+    pub fn subscriptions(&self) -> SubscriptionView {
+        self.acks.view()
+    }
+
+    /// Resolves the pending request a command reply refers to.
+    fn track_reply(&mut self, msg: &KalshiWsMessage) {
+        let Some(id) = msg.id else {
+            return;
+        };
+        match msg.msg_type.as_deref() {
+            Some("subscribed") => {
+                let sid = msg.payload().and_then(|p| p.get("sid")).and_then(|s| s.as_u64());
+                if let (Some(request), Some(sid)) = (self.acks.acknowledged(id), sid) {
+                    self.sid_topics.insert(sid, request.topics);
+                }
+            }
+            Some("unsubscribed") | Some("ok") => {
+                self.acks.acknowledged(id);
+            }
+            Some("error") => {
+                let reason = msg
+                    .payload()
+                    .and_then(|p| p.get("msg"))
+                    .and_then(|m| m.as_str())
+                    .or(msg.error.as_deref())
+                    .unwrap_or("unknown error");
+                self.acks.rejected(id, reason);
+            }
+            _ => {}
+        }
+    }
+
     pub async fn subscribe_market_lifecycle(&mut self) -> Result<()> {
         self.subscribe(&[KalshiChannel::MarketLifecycle], None).await
     }
 
+    /// Fills of this account's orders on every market.
+    pub async fn subscribe_fills(&mut self) -> Result<()> {
+        self.subscribe(&[KalshiChannel::Fill], None).await
+    }
+
     pub async fn subscribe_tickers(&mut self, tickers: Vec<String>) -> Result<()> {
         self.subscribe(&[KalshiChannel::Ticker], Some(tickers)).await
     }
@@ -142,6 +284,10 @@ impl KalshiWebSocket {
         self.subscribe(&[KalshiChannel::Ticker], None).await
     }
 
+    pub async fn subscribe_trades(&mut self, tickers: Vec<String>) -> Result<()> {
+        self.subscribe(&[KalshiChannel::Trade], Some(tickers)).await
+    }
+
     pub async fn subscribe_orderbook(&mut self, tickers: Vec<String>) -> Result<()> {
         self.subscribe(&[KalshiChannel::OrderbookDelta], Some(tickers))
             .await
@@ -157,7 +303,7 @@ impl KalshiWebSocket {
             Some(Ok(msg)) => Ok(Some(msg)),
             Some(Err(e)) => {
                 warn!("WebSocket stream error: {}", e);
-                Err(Error::WebSocket(e.to_string()))
+                Err(e.into())
             }
             None => {
                 warn!("WebSocket stream returned None (connection closed)");
@@ -169,7 +315,18 @@ impl KalshiWebSocket {
     pub async fn recv(&mut self) -> Result<Option<KalshiWsMessage>> {
         match self.recv_raw().await? {
             Some(Message::Text(text)) => {
-                let msg: KalshiWsMessage = serde_json::from_str(&text)?;
+                if let Some(chaos) = &mut self.chaos {
+                    if chaos.on_frame().await == Fault::Disconnect {
+                        self.stream = None;
+                        return Ok(None);
+                    }
+                }
+                if let Some(recorder) = &self.recorder {
+                    recorder.record(FrameSource::KalshiJson, text.as_bytes());
+                }
+                let msg: KalshiWsMessage = serde_json::from_str(&text)
+                    .inspect_err(|_| state::session().record_decode_error("kalshi"))?;
+                self.track_reply(&msg);
                 Ok(Some(msg))
             }
             Some(Message::Ping(data)) => {
@@ -186,7 +343,12 @@ impl KalshiWebSocket {
                     warn!("WebSocket closed by server");
                 }
                 self.stream = None;
-                Ok(None)
+                let reason = CloseReason::from_frame(frame.as_ref());
+                metrics::inc(
+                    names::WS_CLOSES_TOTAL,
+                    &[("exchange", "kalshi"), ("reason", reason.as_str())],
+                );
+                Err(Error::Closed { exchange: "kalshi", reason })
             }
             Some(Message::Binary(_)) => {
                 warn!("Received unexpected binary message, ignoring");
@@ -1,5 +1,13 @@
+pub mod acks;
 pub mod binance;
+pub mod dedup;
+pub mod depth;
+pub mod event;
 pub mod kalshi;
+pub mod okx;
+pub mod polymarket;
+pub mod schema;
 pub mod traits;
 
+pub use event::{Exchange, EventMeta, MarketEvent};
 pub use traits::{OrderbookUpdate, PriceLevel, PriceUpdate, TradeSide};
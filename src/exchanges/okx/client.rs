@@ -0,0 +1,294 @@
+//! OKX public market data: the `trades` and `books5` channels of every
+//! tracked instrument, translated into [`MarketEvent`]s.
+//!
+//! OKX closes connections that stay silent for 30 seconds and has no
+//! WebSocket-level pings, so the client sends the text frame `ping` once
+//! the feed has been idle for a while and expects `pong` back. A missing
+//! pong is treated as a dead connection and reconnected.
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error, info, warn};
+
+use super::models::{OkxBook, OkxMessage, OkxRequest, OkxTrade};
+use crate::config::OkxConfig;
+use crate::constants::OKX_WS_URL;
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::dedup::SequenceFilter;
+use crate::exchanges::event::MarketEvent;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::shutdown;
+use crate::state;
+use crate::utils::websocket::{CloseReason, ReconnectPolicy, ReconnectStrategy};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Idle time after which a `ping` is sent, inside OKX's 30s cutoff.
+const PING_AFTER: Duration = Duration::from_secs(25);
+/// Time allowed for the `pong` reply.
+const PONG_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct OkxClient {
+    config: OkxConfig,
+    stream: Option<WsStream>,
+    trade_filter: SequenceFilter<String>,
+    book_filter: SequenceFilter<String>,
+}
+
+impl OkxClient {
+    pub fn new(config: OkxConfig) -> Self {
+        Self {
+            config,
+            stream: None,
+            trade_filter: SequenceFilter::new("okx", "trades"),
+            book_filter: SequenceFilter::new("okx", "books5"),
+        }
+    }
+
+    pub async fn connect(&mut self) -> Result<()> {
+        info!("Connecting to OKX WebSocket: {}", OKX_WS_URL);
+        let (stream, _) = connect_async(OKX_WS_URL).await.with_context(|| {
+            ErrorContext::new("connect")
+                .with_exchange("okx")
+                .with_endpoint(OKX_WS_URL)
+        })?;
+        self.stream = Some(stream);
+        metrics::set(names::WS_CONNECTED, &[("exchange", "okx")], 1.0);
+        info!("Connected to OKX WebSocket");
+
+        let request = OkxRequest::new("subscribe", &self.config.tracked_symbols);
+        self.send_text(&serde_json::to_string(&request)?).await?;
+        info!("📡 Subscribed to OKX {:?}", self.config.tracked_symbols);
+        Ok(())
+    }
+
+    pub async fn disconnect(&mut self) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            stream.close(None).await?;
+        }
+        metrics::set(names::WS_CONNECTED, &[("exchange", "okx")], 0.0);
+        info!("Disconnected from OKX WebSocket");
+        Ok(())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    async fn send_text(&mut self, text: &str) -> Result<()> {
+        match &mut self.stream {
+            Some(s) => Ok(s.send(Message::Text(text.to_string())).await?),
+            None => Err(Error::WebSocket("Not connected".into())),
+        }
+    }
+
+    /// Reads the next frame, or `None` once `wait` passes without one.
+    async fn recv_within(&mut self, wait: Duration) -> Result<Option<Message>> {
+        let stream = self
+            .stream
+            .as_mut()
+            .ok_or_else(|| Error::WebSocket("Not connected".into()))?;
+        match tokio::time::timeout(wait, stream.next()).await {
+            Ok(Some(Ok(msg))) => Ok(Some(msg)),
+            Ok(Some(Err(e))) => Err(e.into()),
+            Ok(None) => {
+                warn!("OKX WebSocket stream ended");
+                self.stream = None;
+                Err(Error::WebSocket("WebSocket stream ended".into()))
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub async fn run(&mut self, event_tx: mpsc::Sender<MarketEvent>) -> Result<()> {
+        info!("Starting OKX message loop");
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+        let mut awaiting_pong = false;
+
+        loop {
+            let wait = if awaiting_pong { PONG_TIMEOUT } else { PING_AFTER };
+            tokio::select! {
+                received = self.recv_within(wait) => match received? {
+                    Some(msg) => {
+                        awaiting_pong = false;
+                        self.handle_message(msg, &event_tx).await?;
+                    }
+                    None if awaiting_pong => {
+                        return Err(Error::WebSocket(format!(
+                            "No pong within {}s",
+                            PONG_TIMEOUT.as_secs()
+                        )));
+                    }
+                    None => {
+                        debug!("OKX feed idle, sending ping");
+                        self.send_text("ping").await?;
+                        awaiting_pong = true;
+                    }
+                },
+                _ = shutdown::requested(&mut shutdown_rx) => return Ok(()),
+            }
+        }
+    }
+
+    async fn handle_message(&mut self, msg: Message, event_tx: &mpsc::Sender<MarketEvent>) -> Result<()> {
+        match msg {
+            Message::Text(text) if text == "pong" => debug!("Received OKX pong"),
+            Message::Text(text) => {
+                for event in self.parse_text(&text)? {
+                    if let Err(e) = event_tx.try_send(event) {
+                        error!("Failed to queue OKX event: {}", e);
+                    }
+                }
+            }
+            Message::Ping(data) => {
+                if let Some(s) = &mut self.stream {
+                    s.send(Message::Pong(data)).await?;
+                }
+            }
+            Message::Close(frame) => {
+                info!("OKX WebSocket closed by server: {:?}", frame);
+                self.stream = None;
+                let reason = CloseReason::from_frame(frame.as_ref());
+                metrics::inc(
+                    names::WS_CLOSES_TOTAL,
+                    &[("exchange", "okx"), ("reason", reason.as_str())],
+                );
+                return Err(Error::Closed { exchange: "okx", reason });
+            }
+            _ => debug!("Ignoring unexpected OKX frame"),
+        }
+        Ok(())
+    }
+
+    /// Events carried by a text frame; request replies carry none.
+    fn parse_text(&mut self, text: &str) -> Result<Vec<MarketEvent>> {
+        let msg: OkxMessage =
+            serde_json::from_str(text).inspect_err(|_| state::session().record_decode_error("okx"))?;
+        if let Some(event) = &msg.event {
+            match event.as_str() {
+                "error" => warn!(
+                    "OKX rejected a request: code={} msg={}",
+                    msg.code.unwrap_or_default(),
+                    msg.msg.unwrap_or_default()
+                ),
+                _ => debug!("OKX {} reply: {:?}", event, msg.arg),
+            }
+            return Ok(Vec::new());
+        }
+        let (Some(arg), Some(data)) = (msg.arg, msg.data) else {
+            debug!("Ignoring OKX message: {}", text);
+            return Ok(Vec::new());
+        };
+
+        let mut events = Vec::new();
+        match arg.channel.as_str() {
+            "trades" => {
+                for trade in serde_json::from_value::<Vec<OkxTrade>>(data)? {
+                    let event = trade.to_event()?;
+                    if let MarketEvent::Trade(t) = &event {
+                        if self.trade_filter.is_duplicate(arg.inst_id.as_str(), t.trade_id) {
+                            continue;
+                        }
+                    }
+                    events.push(event);
+                }
+            }
+            "books5" => {
+                for book in serde_json::from_value::<Vec<OkxBook>>(data)? {
+                    if let Some(seq_id) = book.seq_id {
+                        if self.book_filter.is_duplicate(arg.inst_id.as_str(), seq_id) {
+                            continue;
+                        }
+                    }
+                    events.extend(book.to_events(&arg.inst_id)?);
+                }
+            }
+            channel => debug!("Ignoring OKX {} data", channel),
+        }
+
+        let now = chrono::Utc::now();
+        metrics::inc(
+            names::WS_MESSAGES_TOTAL,
+            &[("exchange", "okx"), ("type", arg.channel.as_str())],
+        );
+        state::session().record_message("okx", arg.channel.as_str());
+        metrics::set(
+            names::WS_LAST_MESSAGE_TIMESTAMP,
+            &[("exchange", "okx")],
+            now.timestamp() as f64,
+        );
+        if let Some(event) = events.first() {
+            let latency = (now - event.meta().timestamp).num_microseconds().unwrap_or(0);
+            metrics::observe(
+                names::FEED_LATENCY_SECONDS,
+                &[("exchange", "okx")],
+                latency.max(0) as f64 / 1_000_000.0,
+            );
+        }
+        Ok(events)
+    }
+
+    pub async fn start(&mut self, event_tx: mpsc::Sender<MarketEvent>) -> Result<()> {
+        let strategy = ReconnectStrategy::default();
+        let mut attempt = 0;
+
+        loop {
+            if shutdown::coordinator().is_triggered() {
+                return Ok(());
+            }
+            let result = match self.connect().await {
+                Ok(()) => {
+                    attempt = 0;
+                    self.run(event_tx.clone()).await
+                }
+                Err(e) => Err(e),
+            };
+            let e = match result {
+                Ok(()) => {
+                    if let Err(e) = self.disconnect().await {
+                        warn!("Error disconnecting OKX client: {}", e);
+                    }
+                    return Ok(());
+                }
+                Err(e) => e,
+            };
+            let _ = self.disconnect().await;
+
+            match ReconnectPolicy::for_error(&e) {
+                ReconnectPolicy::Abort => {
+                    error!(exchange = "okx", "🚨 OKX WebSocket error: {}. Not reconnecting, manual intervention required", e.chain());
+                    return Err(e);
+                }
+                ReconnectPolicy::Immediate => {
+                    warn!("🔁 OKX WebSocket error: {}. Reconnecting immediately...", e.chain());
+                }
+                ReconnectPolicy::Delayed(delay) => {
+                    warn!("⏳ OKX WebSocket error: {}. Reconnecting in {}s...", e.chain(), delay.as_secs());
+                    tokio::time::sleep(delay).await;
+                }
+                ReconnectPolicy::Backoff => {
+                    if attempt >= strategy.max_retries {
+                        error!(exchange = "okx", "🔴 OKX WebSocket error: {}. Giving up after {} attempts", e.chain(), attempt);
+                        return Err(e);
+                    }
+                    let delay = strategy.delay_for_attempt(attempt);
+                    attempt += 1;
+                    error!(exchange = "okx", "🔴 OKX WebSocket error: {}. Reconnecting in {}s...", e.chain(), delay.as_secs());
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            metrics::inc(names::WS_RECONNECTS_TOTAL, &[("exchange", "okx")]);
+            state::session().record_reconnect("okx");
+            notify::publish(Notification::Reconnect {
+                exchange: "okx".to_string(),
+                error: e.to_string(),
+            });
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::exchanges::event::{BookEvent, EventMeta, Exchange, MarketEvent, QuoteEvent, TradeEvent};
+use crate::exchanges::{PriceLevel, TradeSide};
+use crate::utils::numeric;
+
+/// Public channels streamed for every instrument.
+pub const CHANNELS: [&str; 2] = ["trades", "books5"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelArg {
+    pub channel: String,
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OkxRequest {
+    pub op: String,
+    pub args: Vec<ChannelArg>,
+}
+
+impl OkxRequest {
+    /// `op` is `subscribe` or `unsubscribe`; every channel of [`CHANNELS`]
+    /// for each instrument.
+    pub fn new(op: &str, instruments: &[String]) -> Self {
+        let args = instruments
+            .iter()
+            .flat_map(|inst_id| {
+                CHANNELS.iter().map(move |channel| ChannelArg {
+                    channel: channel.to_string(),
+                    inst_id: inst_id.clone(),
+                })
+            })
+            .collect();
+        Self { op: op.to_string(), args }
+    }
+}
+
+/// Any JSON frame from the public endpoint: a reply to a request when
+/// `event` is set, channel data otherwise.
+#[derive(Debug, Deserialize)]
+pub struct OkxMessage {
+    pub event: Option<String>,
+    pub code: Option<String>,
+    pub msg: Option<String>,
+    pub arg: Option<ChannelArg>,
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OkxTrade {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "tradeId")]
+    pub trade_id: String,
+    pub px: String,
+    pub sz: String,
+    /// Taker side, `buy` or `sell`
+    pub side: String,
+    /// Milliseconds since the epoch
+    pub ts: String,
+}
+
+/// A `books5` push: the five best levels per side, always in full.
+/// Levels are `[price, size, deprecated, order count]`.
+#[derive(Debug, Deserialize)]
+pub struct OkxBook {
+    pub asks: Vec<Vec<String>>,
+    pub bids: Vec<Vec<String>>,
+    pub ts: String,
+    #[serde(rename = "seqId")]
+    pub seq_id: Option<i64>,
+}
+
+fn parse_f64(field: &str, value: &str) -> Result<f64> {
+    numeric::parse_decimal(value).ok_or_else(|| Error::Other(format!("Invalid OKX {} '{}'", field, value)))
+}
+
+fn parse_ts(value: &str) -> Result<DateTime<Utc>> {
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .ok_or_else(|| Error::Other(format!("Invalid OKX ts '{}'", value)))
+}
+
+fn parse_levels(levels: &[Vec<String>]) -> Result<Vec<PriceLevel>> {
+    levels
+        .iter()
+        .map(|level| match level.as_slice() {
+            [price, quantity, ..] => Ok(PriceLevel {
+                price: parse_f64("price", price)?,
+                quantity: parse_f64("size", quantity)?,
+            }),
+            _ => Err(Error::Other(format!("Invalid OKX book level {:?}", level))),
+        })
+        .collect()
+}
+
+impl OkxTrade {
+    pub fn to_event(&self) -> Result<MarketEvent> {
+        Ok(MarketEvent::Trade(TradeEvent {
+            meta: EventMeta::new(Exchange::Okx, &self.inst_id, parse_ts(&self.ts)?),
+            trade_id: self
+                .trade_id
+                .parse()
+                .map_err(|_| Error::Other(format!("Invalid OKX tradeId '{}'", self.trade_id)))?,
+            price: parse_f64("px", &self.px)?,
+            quantity: parse_f64("sz", &self.sz)?,
+            side: match self.side.as_str() {
+                "buy" => TradeSide::Buy,
+                "sell" => TradeSide::Sell,
+                _ => TradeSide::Unknown,
+            },
+        }))
+    }
+}
+
+impl OkxBook {
+    /// The snapshot, followed by the top of book when both sides have a level.
+    pub fn to_events(&self, inst_id: &str) -> Result<Vec<MarketEvent>> {
+        let meta = EventMeta::new(Exchange::Okx, inst_id, parse_ts(&self.ts)?);
+        let bids = parse_levels(&self.bids)?;
+        let asks = parse_levels(&self.asks)?;
+
+        let quote = match (bids.first(), asks.first()) {
+            (Some(bid), Some(ask)) => Some(MarketEvent::Quote(QuoteEvent {
+                meta: meta.clone(),
+                bid: bid.price,
+                bid_qty: bid.quantity,
+                ask: ask.price,
+                ask_qty: ask.quantity,
+                binary: None,
+            })),
+            _ => None,
+        };
+        let mut events = vec![MarketEvent::BookSnapshot(BookEvent { meta, bids, asks })];
+        events.extend(quote);
+        Ok(events)
+    }
+}
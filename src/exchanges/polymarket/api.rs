@@ -0,0 +1,51 @@
+use reqwest::Client as HttpClient;
+
+use super::models::{PolymarketBookMessage, PolymarketMarket};
+use crate::constants::POLYMARKET_REST_URL;
+use crate::error::{ErrorContext, Result, ResultExt};
+use crate::utils::http::ensure_success;
+
+/// Public endpoints of the Polymarket CLOB; nothing here needs a key.
+pub struct PolymarketApi {
+    http: HttpClient,
+}
+
+impl PolymarketApi {
+    pub fn new() -> Self {
+        Self {
+            http: HttpClient::new(),
+        }
+    }
+
+    fn context(operation: &str, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_exchange("polymarket")
+            .with_endpoint(format!("GET {}", path))
+    }
+
+    pub async fn fetch_market(&self, condition_id: &str) -> Result<PolymarketMarket> {
+        let url_path = format!("/markets/{}", condition_id);
+        let url = format!("{}{}", POLYMARKET_REST_URL, url_path);
+        let context = || Self::context("fetch market", &url_path).with_symbol(condition_id);
+
+        let resp = self.http.get(&url).send().await.with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+        resp.json().await.with_context(context)
+    }
+
+    pub async fn fetch_book(&self, token_id: &str) -> Result<PolymarketBookMessage> {
+        let url_path = "/book";
+        let url = format!("{}{}?token_id={}", POLYMARKET_REST_URL, url_path, token_id);
+        let context = || Self::context("fetch book", url_path).with_symbol(token_id);
+
+        let resp = self.http.get(&url).send().await.with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+        resp.json().await.with_context(context)
+    }
+}
+
+impl Default for PolymarketApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
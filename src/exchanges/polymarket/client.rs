@@ -0,0 +1,346 @@
+//! Polymarket CLOB market channel for the configured markets.
+//!
+//! Each market is resolved over REST to its YES token, whose book is seeded
+//! from `/book` and then kept from the `book` and `price_change` messages.
+//! Events carry the condition id as instrument and, like Kalshi's, express
+//! the book from the YES side, so the two venues' probabilities line up.
+//! The server expects a text `PING` every few seconds and answers `PONG`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, error, info, warn};
+
+use super::api::PolymarketApi;
+use super::models::{MarketSubscription, PolymarketMessage};
+use super::orderbook::PolymarketBook;
+use crate::config::PolymarketConfig;
+use crate::constants::POLYMARKET_WS_URL;
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::event::{BookEvent, EventMeta, Exchange, MarketEvent};
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::shutdown;
+use crate::state::{self, PolymarketState};
+use crate::utils::websocket::{CloseReason, ReconnectPolicy, ReconnectStrategy};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+/// Silence after which the connection is considered dead.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct PolymarketClient {
+    config: PolymarketConfig,
+    api: PolymarketApi,
+    stream: Option<WsStream>,
+    state: Arc<PolymarketState>,
+    /// Condition id by YES token id
+    tokens: HashMap<String, String>,
+}
+
+impl PolymarketClient {
+    pub fn new(config: PolymarketConfig) -> Self {
+        Self {
+            config,
+            api: PolymarketApi::new(),
+            stream: None,
+            state: Arc::new(PolymarketState::new()),
+            tokens: HashMap::new(),
+        }
+    }
+
+    pub fn shared_state(&self) -> Arc<PolymarketState> {
+        self.state.clone()
+    }
+
+    /// Looks up the YES token of every configured market, skipping those
+    /// that cannot be found or are closed.
+    async fn resolve_markets(&mut self) -> Result<()> {
+        for (condition_id, kalshi) in &self.config.markets {
+            let market = match self.api.fetch_market(condition_id).await {
+                Ok(market) => market,
+                Err(e) => {
+                    warn!("Failed to fetch Polymarket market {}: {}", condition_id, e);
+                    continue;
+                }
+            };
+            if market.closed {
+                warn!("Polymarket market {} is closed, skipping", condition_id);
+                continue;
+            }
+            let Some(token) = market.yes_token() else {
+                warn!("Polymarket market {} has no YES token, skipping", condition_id);
+                continue;
+            };
+            info!("🔗 Polymarket \"{}\" -> {}", market.question, kalshi);
+            self.tokens.insert(token.token_id.clone(), condition_id.clone());
+            self.state.links.insert(condition_id.clone(), kalshi.clone());
+            self.state.books.insert(
+                condition_id.clone(),
+                PolymarketBook::new_empty(condition_id.clone(), token.token_id.clone()),
+            );
+        }
+        if self.tokens.is_empty() {
+            return Err(Error::MarketNotFound("No Polymarket market could be resolved".into()));
+        }
+        Ok(())
+    }
+
+    /// Seeds every book over REST so consumers have a price before the
+    /// first stream message arrives.
+    async fn warm_start(&self, event_tx: &mpsc::Sender<MarketEvent>) {
+        for (token_id, condition_id) in &self.tokens {
+            match self.api.fetch_book(token_id).await {
+                Ok(book) => {
+                    if let Some(mut entry) = self.state.books.get_mut(condition_id) {
+                        entry.apply_snapshot(&book);
+                        let meta = event_meta(condition_id, book.timestamp.as_deref());
+                        publish(event_tx, entry.snapshot_event(meta.clone()));
+                        publish(event_tx, entry.quote_event(meta));
+                    }
+                }
+                Err(e) => warn!("Warm start failed for Polymarket {}: {}", condition_id, e),
+            }
+        }
+    }
+
+    pub async fn connect(&mut self) -> Result<()> {
+        info!("Connecting to Polymarket WebSocket: {}", POLYMARKET_WS_URL);
+        let (stream, _) = connect_async(POLYMARKET_WS_URL).await.with_context(|| {
+            ErrorContext::new("connect")
+                .with_exchange("polymarket")
+                .with_endpoint(POLYMARKET_WS_URL)
+        })?;
+        self.stream = Some(stream);
+        metrics::set(names::WS_CONNECTED, &[("exchange", "polymarket")], 1.0);
+        info!("Connected to Polymarket WebSocket");
+
+        let subscription = MarketSubscription::new(self.tokens.keys().cloned().collect());
+        self.send_text(&serde_json::to_string(&subscription)?).await?;
+        info!("📡 Subscribed to {} Polymarket markets", self.tokens.len());
+        Ok(())
+    }
+
+    pub async fn disconnect(&mut self) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            stream.close(None).await?;
+        }
+        metrics::set(names::WS_CONNECTED, &[("exchange", "polymarket")], 0.0);
+        info!("Disconnected from Polymarket WebSocket");
+        Ok(())
+    }
+
+    async fn send_text(&mut self, text: &str) -> Result<()> {
+        match &mut self.stream {
+            Some(s) => Ok(s.send(Message::Text(text.to_string())).await?),
+            None => Err(Error::WebSocket("Not connected".into())),
+        }
+    }
+
+    async fn recv(&mut self) -> Result<Message> {
+        let stream = self
+            .stream
+            .as_mut()
+            .ok_or_else(|| Error::WebSocket("Not connected".into()))?;
+        match tokio::time::timeout(READ_TIMEOUT, stream.next()).await {
+            Ok(Some(Ok(msg))) => Ok(msg),
+            Ok(Some(Err(e))) => Err(e.into()),
+            Ok(None) => {
+                self.stream = None;
+                Err(Error::WebSocket("WebSocket stream ended".into()))
+            }
+            Err(_) => Err(Error::WebSocket("Read timeout".into())),
+        }
+    }
+
+    pub async fn run(&mut self, event_tx: mpsc::Sender<MarketEvent>) -> Result<()> {
+        info!("Starting Polymarket message loop");
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+
+        loop {
+            tokio::select! {
+                received = self.recv() => self.handle_message(received?, &event_tx).await?,
+                _ = ping.tick() => self.send_text("PING").await?,
+                _ = shutdown::requested(&mut shutdown_rx) => return Ok(()),
+            }
+        }
+    }
+
+    async fn handle_message(&mut self, msg: Message, event_tx: &mpsc::Sender<MarketEvent>) -> Result<()> {
+        match msg {
+            Message::Text(text) if text == "PONG" => debug!("Received Polymarket pong"),
+            Message::Text(text) => {
+                // Initial books arrive as an array, updates one at a time.
+                let value: serde_json::Value = serde_json::from_str(&text)
+                    .inspect_err(|_| state::session().record_decode_error("polymarket"))?;
+                let messages = match value {
+                    serde_json::Value::Array(items) => items,
+                    item => vec![item],
+                };
+                for message in messages {
+                    match serde_json::from_value::<PolymarketMessage>(message) {
+                        Ok(message) => self.apply(message, event_tx),
+                        Err(e) => {
+                            warn!("Failed to parse Polymarket message: {}", e);
+                            state::session().record_decode_error("polymarket");
+                        }
+                    }
+                }
+                metrics::set(
+                    names::WS_LAST_MESSAGE_TIMESTAMP,
+                    &[("exchange", "polymarket")],
+                    Utc::now().timestamp() as f64,
+                );
+            }
+            Message::Ping(data) => {
+                if let Some(s) = &mut self.stream {
+                    s.send(Message::Pong(data)).await?;
+                }
+            }
+            Message::Close(frame) => {
+                info!("Polymarket WebSocket closed by server: {:?}", frame);
+                self.stream = None;
+                let reason = CloseReason::from_frame(frame.as_ref());
+                metrics::inc(
+                    names::WS_CLOSES_TOTAL,
+                    &[("exchange", "polymarket"), ("reason", reason.as_str())],
+                );
+                return Err(Error::Closed { exchange: "polymarket", reason });
+            }
+            _ => debug!("Ignoring unexpected Polymarket frame"),
+        }
+        Ok(())
+    }
+
+    fn apply(&self, message: PolymarketMessage, event_tx: &mpsc::Sender<MarketEvent>) {
+        match message {
+            PolymarketMessage::Book(book) => {
+                metrics::inc(
+                    names::WS_MESSAGES_TOTAL,
+                    &[("exchange", "polymarket"), ("type", "book")],
+                );
+                state::session().record_message("polymarket", "book");
+                let Some(condition_id) = self.tokens.get(&book.asset_id) else {
+                    return;
+                };
+                if let Some(mut entry) = self.state.books.get_mut(condition_id) {
+                    entry.apply_snapshot(&book);
+                    let meta = event_meta(condition_id, book.timestamp.as_deref());
+                    publish(event_tx, entry.snapshot_event(meta.clone()));
+                    publish(event_tx, entry.quote_event(meta));
+                }
+            }
+            PolymarketMessage::PriceChange { price_changes, timestamp, .. } => {
+                metrics::inc(
+                    names::WS_MESSAGES_TOTAL,
+                    &[("exchange", "polymarket"), ("type", "price_change")],
+                );
+                state::session().record_message("polymarket", "price_change");
+                for change in &price_changes {
+                    // Changes to the NO token mirror those of the YES token.
+                    let Some(condition_id) = self.tokens.get(&change.asset_id) else {
+                        continue;
+                    };
+                    let Some(mut entry) = self.state.books.get_mut(condition_id) else {
+                        continue;
+                    };
+                    let Some((is_bid, level)) = entry.apply_change(change) else {
+                        warn!("Invalid Polymarket price change for {}: {:?}", condition_id, change);
+                        continue;
+                    };
+                    let meta = event_meta(condition_id, timestamp.as_deref());
+                    let (bids, asks) = if is_bid { (vec![level], Vec::new()) } else { (Vec::new(), vec![level]) };
+                    publish(event_tx, MarketEvent::BookDelta(BookEvent { meta: meta.clone(), bids, asks }));
+                    publish(event_tx, entry.quote_event(meta));
+                }
+            }
+            PolymarketMessage::Other => {}
+        }
+    }
+
+    pub async fn start(&mut self, event_tx: mpsc::Sender<MarketEvent>) -> Result<()> {
+        self.resolve_markets().await?;
+        self.warm_start(&event_tx).await;
+
+        let strategy = ReconnectStrategy::default();
+        let mut attempt = 0;
+
+        loop {
+            if shutdown::coordinator().is_triggered() {
+                return Ok(());
+            }
+            let result = match self.connect().await {
+                Ok(()) => {
+                    attempt = 0;
+                    self.run(event_tx.clone()).await
+                }
+                Err(e) => Err(e),
+            };
+            let e = match result {
+                Ok(()) => {
+                    if let Err(e) = self.disconnect().await {
+                        warn!("Error disconnecting Polymarket client: {}", e);
+                    }
+                    return Ok(());
+                }
+                Err(e) => e,
+            };
+            let _ = self.disconnect().await;
+
+            match ReconnectPolicy::for_error(&e) {
+                ReconnectPolicy::Abort => {
+                    error!(exchange = "polymarket", "🚨 Polymarket WebSocket error: {}. Not reconnecting, manual intervention required", e);
+                    return Err(e);
+                }
+                ReconnectPolicy::Immediate => {
+                    warn!("🔁 Polymarket WebSocket error: {}. Reconnecting immediately...", e);
+                }
+                ReconnectPolicy::Delayed(delay) => {
+                    warn!("⏳ Polymarket WebSocket error: {}. Reconnecting in {}s...", e, delay.as_secs());
+                    tokio::time::sleep(delay).await;
+                }
+                ReconnectPolicy::Backoff => {
+                    if attempt >= strategy.max_retries {
+                        error!(exchange = "polymarket", "🔴 Polymarket WebSocket error: {}. Giving up after {} attempts", e, attempt);
+                        return Err(e);
+                    }
+                    let delay = strategy.delay_for_attempt(attempt);
+                    attempt += 1;
+                    error!(exchange = "polymarket", "🔴 Polymarket WebSocket error: {}. Reconnecting in {}s...", e, delay.as_secs());
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            metrics::inc(names::WS_RECONNECTS_TOTAL, &[("exchange", "polymarket")]);
+            state::session().record_reconnect("polymarket");
+            notify::publish(Notification::Reconnect {
+                exchange: "polymarket".to_string(),
+                error: e.to_string(),
+            });
+        }
+    }
+}
+
+/// Metadata for an event on `condition_id`, timed by the message's
+/// millisecond timestamp when it has one.
+fn event_meta(condition_id: &str, timestamp: Option<&str>) -> EventMeta {
+    let timestamp = timestamp
+        .and_then(|t| t.parse::<i64>().ok())
+        .and_then(DateTime::from_timestamp_millis)
+        .unwrap_or_else(Utc::now);
+    EventMeta::new(Exchange::Polymarket, condition_id, timestamp)
+}
+
+fn publish(event_tx: &mpsc::Sender<MarketEvent>, event: MarketEvent) {
+    if let Err(e) = event_tx.try_send(event) {
+        error!("Failed to queue Polymarket event: {}", e);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod api;
+pub mod client;
+pub mod models;
+pub mod orderbook;
+
+pub use api::PolymarketApi;
+pub use client::PolymarketClient;
+pub use orderbook::PolymarketBook;
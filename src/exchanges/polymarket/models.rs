@@ -0,0 +1,87 @@
+use serde::Deserialize;
+
+/// `GET /markets/{condition_id}`: a binary market and its two outcome tokens.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolymarketMarket {
+    pub condition_id: String,
+    #[serde(default)]
+    pub question: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub tokens: Vec<PolymarketToken>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolymarketToken {
+    pub token_id: String,
+    /// `Yes` / `No` on binary markets
+    pub outcome: String,
+}
+
+impl PolymarketMarket {
+    /// The token paying out on YES, whose book is the market's book; the
+    /// NO token's book mirrors it.
+    pub fn yes_token(&self) -> Option<&PolymarketToken> {
+        self.tokens.iter().find(|t| t.outcome.eq_ignore_ascii_case("yes"))
+    }
+}
+
+/// A level of the CLOB; prices are probabilities in dollars, as strings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolymarketLevel {
+    pub price: String,
+    pub size: String,
+}
+
+/// `GET /book?token_id=...`, and the `book` message of the market channel.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolymarketBookMessage {
+    pub asset_id: String,
+    pub market: String,
+    #[serde(default)]
+    pub bids: Vec<PolymarketLevel>,
+    #[serde(default)]
+    pub asks: Vec<PolymarketLevel>,
+    /// Milliseconds since the epoch
+    pub timestamp: Option<String>,
+}
+
+/// One level of a `price_change` message, with its new total size.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PolymarketPriceChange {
+    pub asset_id: String,
+    pub price: String,
+    pub size: String,
+    /// `BUY` for a bid level, `SELL` for an ask level
+    pub side: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+pub enum PolymarketMessage {
+    Book(PolymarketBookMessage),
+    PriceChange {
+        market: String,
+        price_changes: Vec<PolymarketPriceChange>,
+        timestamp: Option<String>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct MarketSubscription {
+    pub assets_ids: Vec<String>,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl MarketSubscription {
+    pub fn new(assets_ids: Vec<String>) -> Self {
+        Self {
+            assets_ids,
+            kind: "market".to_string(),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use serde::Serialize;
+
+use super::models::{PolymarketBookMessage, PolymarketLevel, PolymarketPriceChange};
+use crate::exchanges::event::{BinaryQuote, BookEvent, EventMeta, MarketEvent, QuoteEvent};
+use crate::exchanges::PriceLevel;
+use crate::trader::constants::FILL_OR_KILL_ORDER_PRICE;
+use crate::utils::numeric;
+
+/// Book of a market's YES token. The NO side is its mirror: a YES bid at
+/// `p` is a NO ask at `1 - p`, so events read like Kalshi's YES-side books.
+#[derive(Debug, Clone, Serialize)]
+pub struct PolymarketBook {
+    pub condition_id: String,
+    pub token_id: String,
+    /// Best first
+    pub bids: Vec<PriceLevel>,
+    /// Best first
+    pub asks: Vec<PriceLevel>,
+}
+
+fn parse_levels(levels: &[PolymarketLevel]) -> Vec<PriceLevel> {
+    levels
+        .iter()
+        .filter_map(|l| {
+            Some(PriceLevel {
+                price: numeric::checked("polymarket", "price", &l.price)?,
+                quantity: numeric::checked("polymarket", "size", &l.size)?,
+            })
+        })
+        .filter(|l| l.quantity > 0.0)
+        .collect()
+}
+
+fn qty_at_or_above(levels: &[PriceLevel], min_price: f64) -> i64 {
+    levels
+        .iter()
+        .filter(|l| l.price >= min_price - 1e-12)
+        .map(|l| l.quantity)
+        .sum::<f64>() as i64
+}
+
+impl PolymarketBook {
+    pub fn new_empty(condition_id: String, token_id: String) -> Self {
+        Self {
+            condition_id,
+            token_id,
+            bids: Vec::new(),
+            asks: Vec::new(),
+        }
+    }
+
+    pub fn apply_snapshot(&mut self, book: &PolymarketBookMessage) {
+        self.bids = parse_levels(&book.bids);
+        self.asks = parse_levels(&book.asks);
+        self.sort();
+    }
+
+    /// Sets the level of `change` to its new size; 0 removes it. Returns the
+    /// level as a delta event payload, `None` when it cannot be parsed.
+    pub fn apply_change(&mut self, change: &PolymarketPriceChange) -> Option<(bool, PriceLevel)> {
+        let price = numeric::checked("polymarket", "price", &change.price)?;
+        let quantity = numeric::checked("polymarket", "size", &change.size)?;
+        let is_bid = change.side.eq_ignore_ascii_case("buy");
+        let levels = if is_bid { &mut self.bids } else { &mut self.asks };
+
+        match levels.iter().position(|l| (l.price - price).abs() < 1e-12) {
+            Some(idx) if quantity <= 0.0 => {
+                levels.remove(idx);
+            }
+            Some(idx) => levels[idx].quantity = quantity,
+            None if quantity > 0.0 => levels.push(PriceLevel { price, quantity }),
+            None => {}
+        }
+        self.sort();
+        Some((is_bid, PriceLevel { price, quantity }))
+    }
+
+    fn sort(&mut self) {
+        self.bids
+            .sort_by(|a, b| b.price.partial_cmp(&a.price).unwrap_or(std::cmp::Ordering::Equal));
+        self.asks
+            .sort_by(|a, b| a.price.partial_cmp(&b.price).unwrap_or(std::cmp::Ordering::Equal));
+    }
+
+    pub fn top_yes_bid(&self) -> f64 {
+        self.bids.first().map(|l| l.price).unwrap_or(0.0)
+    }
+
+    pub fn top_yes_ask(&self) -> f64 {
+        self.asks.first().map(|l| l.price).unwrap_or(0.0)
+    }
+
+    /// Mid of the YES top of book, `None` while a side is empty.
+    pub fn implied_probability(&self) -> Option<f64> {
+        match (self.bids.first(), self.asks.first()) {
+            (Some(bid), Some(ask)) => Some((bid.price + ask.price) / 2.0),
+            _ => None,
+        }
+    }
+
+    pub fn quote_event(&self, meta: EventMeta) -> MarketEvent {
+        let top_qty = |levels: &[PriceLevel]| levels.first().map_or(0.0, |l| l.quantity);
+        let no_asks: Vec<PriceLevel> = self
+            .bids
+            .iter()
+            .map(|l| PriceLevel { price: 1.0 - l.price, quantity: l.quantity })
+            .collect();
+        MarketEvent::Quote(QuoteEvent {
+            meta,
+            bid: self.top_yes_bid(),
+            bid_qty: top_qty(&self.bids),
+            ask: self.top_yes_ask(),
+            ask_qty: top_qty(&self.asks),
+            binary: Some(BinaryQuote {
+                no_bid: self.asks.first().map_or(0.0, |l| 1.0 - l.price),
+                no_ask: self.bids.first().map_or(0.0, |l| 1.0 - l.price),
+                yes_ask_depth: qty_at_or_above(&self.asks, FILL_OR_KILL_ORDER_PRICE),
+                no_ask_depth: qty_at_or_above(&no_asks, FILL_OR_KILL_ORDER_PRICE),
+            }),
+        })
+    }
+
+    pub fn snapshot_event(&self, meta: EventMeta) -> MarketEvent {
+        MarketEvent::BookSnapshot(BookEvent {
+            meta,
+            bids: self.bids.clone(),
+            asks: self.asks.clone(),
+        })
+    }
+}
@@ -0,0 +1,184 @@
+//! Versioned serialized form of normalized events.
+//!
+//! Anything that leaves the process or outlives it (ZMQ subscribers, event
+//! journals in recordings) goes through [`encode`], which wraps the value as
+//! `{"v": <version>, "data": ...}`. [`decode`] upgrades older payloads one
+//! version at a time through [`Versioned::migrate`] before deserializing, so
+//! history written by earlier builds stays readable.
+//!
+//! The envelope also carries the process's [`tags`](crate::tags) as
+//! `account` and `strategy`, so journals and subscribers fed by several
+//! instances can be split per stream. They sit outside `data`, so adding
+//! them did not change any versioned type and [`decode`] ignores them.
+//!
+//! A change to a versioned type that alters its serde shape (renamed or
+//! removed fields, new required fields) bumps its `VERSION` and adds a step
+//! to `migrate` rewriting the previous shape. Adding an `Option` field with
+//! `#[serde(default)]` does not need a bump.
+//!
+//! High-rate consumers can take [`WireFormat::Bincode`] instead: a 4-byte
+//! little-endian version followed by the bincode encoded value, laid out as
+//! described in `schema/`. Bincode carries no field names, so it has no
+//! migration path; readers must match the writer's version exactly. The
+//! layout files are checked in so out-of-tree decoders can track changes,
+//! and any version bump must update them.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{MarketEvent, PriceUpdate};
+use crate::error::{Error, Result};
+use crate::tags;
+
+pub trait Versioned: Serialize + DeserializeOwned {
+    const NAME: &'static str;
+    const VERSION: u32;
+
+    /// Rewrites `data`, stored as version `from`, into the `from + 1` shape.
+    fn migrate(from: u32, data: Value) -> Result<Value> {
+        let _ = data;
+        Err(Error::Schema(format!("No migration for {} v{}", Self::NAME, from)))
+    }
+}
+
+impl Versioned for MarketEvent {
+    const NAME: &'static str = "market_event";
+    /// v2 added `TopOfBookChanged`, the OKX and Polymarket exchanges and the
+    /// alert's direction and Kalshi ticker, which only moved the bincode
+    /// layout.
+    const VERSION: u32 = 2;
+
+    fn migrate(from: u32, data: Value) -> Result<Value> {
+        match from {
+            // Everything v2 added is a new variant or a defaulted field
+            1 => Ok(data),
+            _ => Err(Error::Schema(format!("No migration for {} v{}", Self::NAME, from))),
+        }
+    }
+}
+
+impl Versioned for PriceUpdate {
+    const NAME: &'static str = "price_update";
+    const VERSION: u32 = 1;
+}
+
+#[derive(Serialize)]
+struct EnvelopeRef<'a, T> {
+    v: u32,
+    data: &'a T,
+    account: &'a str,
+    strategy: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    v: u32,
+    data: Value,
+}
+
+pub fn encode<T: Versioned>(value: &T) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&EnvelopeRef {
+        v: T::VERSION,
+        data: value,
+        account: tags::account(),
+        strategy: tags::strategy(),
+    })?)
+}
+
+/// Payload encoding for external sinks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    /// Self-describing, migratable; see [`encode`].
+    #[default]
+    Json,
+    /// Compact and fixed-layout; see [`encode_compact`].
+    Bincode,
+}
+
+impl WireFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WireFormat::Json => "json",
+            WireFormat::Bincode => "bincode",
+        }
+    }
+
+    pub fn encode<T: Versioned>(&self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            WireFormat::Json => encode(value),
+            WireFormat::Bincode => encode_compact(value),
+        }
+    }
+
+    pub fn decode<T: Versioned>(&self, bytes: &[u8]) -> Result<T> {
+        match self {
+            WireFormat::Json => decode(bytes),
+            WireFormat::Bincode => decode_compact(bytes),
+        }
+    }
+}
+
+impl fmt::Display for WireFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WireFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(WireFormat::Json),
+            "bincode" => Ok(WireFormat::Bincode),
+            other => Err(Error::Config(format!(
+                "Unknown wire format '{}', expected json or bincode",
+                other
+            ))),
+        }
+    }
+}
+
+pub fn encode_compact<T: Versioned>(value: &T) -> Result<Vec<u8>> {
+    let mut out = T::VERSION.to_le_bytes().to_vec();
+    bincode::serialize_into(&mut out, value)
+        .map_err(|e| Error::Schema(format!("Failed to encode {}: {}", T::NAME, e)))?;
+    Ok(out)
+}
+
+pub fn decode_compact<T: Versioned>(bytes: &[u8]) -> Result<T> {
+    if bytes.len() < 4 {
+        return Err(Error::Schema(format!("{} frame shorter than its version prefix", T::NAME)));
+    }
+    let (version, body) = bytes.split_at(4);
+    let v = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+    if v != T::VERSION {
+        return Err(Error::Schema(format!(
+            "{} bincode v{} cannot be read by this build (expects v{})",
+            T::NAME,
+            v,
+            T::VERSION
+        )));
+    }
+    bincode::deserialize(body).map_err(|e| Error::Schema(format!("Failed to decode {}: {}", T::NAME, e)))
+}
+
+pub fn decode<T: Versioned>(bytes: &[u8]) -> Result<T> {
+    let Envelope { v, mut data } = serde_json::from_slice(bytes)?;
+    if v > T::VERSION {
+        return Err(Error::Schema(format!(
+            "{} v{} was written by a newer build (this one reads up to v{})",
+            T::NAME,
+            v,
+            T::VERSION
+        )));
+    }
+    for from in v..T::VERSION {
+        data = T::migrate(from, data)?;
+    }
+    Ok(serde_json::from_value(data)?)
+}
@@ -0,0 +1,119 @@
+//! Where alert orders go: Kalshi itself, or the paper simulator.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::error::Result;
+use crate::exchanges::kalshi::api::KalshiApi;
+use crate::exchanges::kalshi::models::{KalshiFill, OrderAction, OrderSide, OrderType};
+use crate::metrics::{self, names};
+
+/// A buy limit order to place.
+#[derive(Debug, Clone)]
+pub struct OrderRequest {
+    pub market_ticker: String,
+    pub side: OrderSide,
+    pub price_cents: u64,
+    pub contracts: u64,
+    pub client_order_id: String,
+}
+
+/// An order as accepted, with what filled on arrival.
+#[derive(Debug, Clone)]
+pub struct PlacedOrder {
+    pub order_id: String,
+    pub filled: u64,
+    pub remaining: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelledOrder {
+    pub order_id: String,
+    pub reduced_by: u64,
+}
+
+#[async_trait]
+pub trait OrderBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Hands over the queue later fills of our orders must be sent to. The
+    /// live backend needs none, its fills come from the `fill` channel.
+    fn attach_fills(&self, _fills: mpsc::Sender<KalshiFill>) {}
+
+    async fn place(&self, order: &OrderRequest) -> Result<PlacedOrder>;
+
+    /// Cancels `order_ids`; orders missing from the result may have filled
+    /// or been cancelled already, or may still rest.
+    async fn cancel(&self, order_ids: &[&str]) -> Result<Vec<CancelledOrder>>;
+
+    /// Contracts still resting on `order_id`, `None` once it no longer rests.
+    async fn remaining(&self, order_id: &str) -> Result<Option<u64>>;
+}
+
+/// Real orders through the Kalshi REST API.
+pub struct KalshiBackend {
+    api: Arc<KalshiApi>,
+}
+
+impl KalshiBackend {
+    pub fn new(api: Arc<KalshiApi>) -> Self {
+        Self { api }
+    }
+}
+
+#[async_trait]
+impl OrderBackend for KalshiBackend {
+    fn name(&self) -> &'static str {
+        "kalshi"
+    }
+
+    async fn place(&self, order: &OrderRequest) -> Result<PlacedOrder> {
+        let side_label = format!("{:?}", order.side).to_lowercase();
+        let labels = [("side", side_label.as_str()), ("order_type", "limit")];
+
+        let started = Instant::now();
+        let result = self
+            .api
+            .create_order(
+                &order.market_ticker,
+                OrderAction::Buy,
+                order.side,
+                order.contracts,
+                order.price_cents,
+                OrderType::Limit,
+                Some(order.client_order_id.clone()),
+            )
+            .await;
+        metrics::observe(names::ORDER_LATENCY_SECONDS, &[], started.elapsed().as_secs_f64());
+        metrics::inc(names::ORDERS_TOTAL, &labels);
+        let placed = result
+            .inspect_err(|_| metrics::inc(names::ORDER_ERRORS_TOTAL, &[]))?
+            .order;
+        Ok(PlacedOrder {
+            order_id: placed.order_id,
+            filled: placed.fill_count.max(0) as u64,
+            remaining: placed.remaining_count.max(0) as u64,
+        })
+    }
+
+    async fn cancel(&self, order_ids: &[&str]) -> Result<Vec<CancelledOrder>> {
+        let resp = self.api.batch_cancel_orders(order_ids).await?;
+        metrics::add(names::CANCELLED_ORDERS_TOTAL, &[], resp.orders.len() as u64);
+        Ok(resp
+            .orders
+            .into_iter()
+            .map(|o| CancelledOrder {
+                order_id: o.order_id,
+                reduced_by: o.reduced_by,
+            })
+            .collect())
+    }
+
+    async fn remaining(&self, order_id: &str) -> Result<Option<u64>> {
+        let order = self.api.get_order(order_id).await?;
+        Ok((order.status == "resting").then_some(order.remaining_count.max(0) as u64))
+    }
+}
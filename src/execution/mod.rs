@@ -0,0 +1,402 @@
+//! Kalshi limit orders placed on alerts.
+//!
+//! Every alert posted to the status board that names a Kalshi series and
+//! points a direction, from the Binance client, the signal engine or the
+//! rules, becomes one limit order on that series' tracked market at the
+//! price and size of [`ExecutionConfig`]. An alert pointing up buys its
+//! `side`, one pointing down the other side; alerts without a direction are
+//! not traded. Orders are followed through the `fill` channel and
+//! cancelled once they have rested for `order_ttl_secs` without filling.
+//! Fills move the [`Portfolio`].
+//!
+//! Nothing is sent unless `TRADING_ENABLED` is set, and only the leader
+//! trades: losing leadership, like shutting down, cancels whatever still
+//! rests. A market with an order still resting gets no second one, nor
+//! does one ordered on less than `cooldown_secs` ago, and exchange
+//! notices, which are alerts too, are never traded. With
+//! `PAPER_TRADING` the orders go to [`paper::PaperBackend`] instead, which
+//! fills them against the live book. With `CALENDAR_PAUSE_EXECUTION`,
+//! alerts around high-impact events are not traded either. Every order is
+//! checked against the [`risk`] limits first, and engaging the kill switch
+//! cancels whatever is still resting.
+
+pub mod backend;
+pub mod paper;
+pub mod risk;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::{error, info, warn};
+
+use crate::calendar;
+use crate::config::ExecutionConfig;
+use crate::error::Result;
+use crate::exchanges::kalshi::models::{KalshiFill, OrderSide};
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::portfolio::Portfolio;
+use crate::shutdown;
+use crate::status::{self, AlertDirection, AlertRecord};
+use crate::trader::constants::MAX_CANCEL_CHUNK_SIZE;
+use crate::utils::event_id::EventKind;
+use backend::{OrderBackend, OrderRequest};
+use risk::RiskManager;
+
+pub const FILLS_CHANNEL_BUFFER: usize = 256;
+
+/// How often resting orders are checked against their TTL.
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An order placed on an alert and not yet filled or cancelled.
+#[derive(Debug, Clone)]
+struct RestingOrder {
+    market_ticker: String,
+    /// The alert it was placed for
+    event_id: String,
+    /// Filled on arrival and already off `remaining`, but not yet seen as
+    /// taker fills on the fill channel
+    unreported: u64,
+    remaining: u64,
+    placed_at: Instant,
+}
+
+pub struct AlertExecutor {
+    backend: Box<dyn OrderBackend>,
+    /// Positions our fills move
+    portfolio: Arc<Portfolio>,
+    config: ExecutionConfig,
+    leader: LeaderHandle,
+    risk: RiskManager,
+    /// Order id -> order
+    resting: HashMap<String, RestingOrder>,
+    /// Market ticker -> when we last ordered on it
+    last_order: HashMap<String, Instant>,
+    /// Whether we led at the last check, to notice losing it
+    leading: bool,
+}
+
+impl AlertExecutor {
+    pub fn new(
+        backend: Box<dyn OrderBackend>,
+        portfolio: Arc<Portfolio>,
+        config: ExecutionConfig,
+        leader: LeaderHandle,
+    ) -> Self {
+        Self {
+            backend,
+            risk: RiskManager::new(config.risk, &portfolio),
+            portfolio,
+            config,
+            leading: leader.is_leader(),
+            leader,
+            resting: HashMap::new(),
+            last_order: HashMap::new(),
+        }
+    }
+
+    /// Starts trading on alerts; our fills must be sent to the returned queue.
+    pub fn spawn(
+        backend: Box<dyn OrderBackend>,
+        portfolio: Arc<Portfolio>,
+        config: ExecutionConfig,
+        leader: LeaderHandle,
+    ) -> mpsc::Sender<KalshiFill> {
+        let (tx, rx) = mpsc::channel::<KalshiFill>(FILLS_CHANNEL_BUFFER);
+        status::board().register_queue("execution_fills", &tx);
+        backend.attach_fills(tx.clone());
+        info!(
+            "💸 Trading on alerts via {}: {} {:?} on up alerts @ {}c, cancelled after {}s, {}s apart per market",
+            backend.name(),
+            config.contracts,
+            config.side,
+            config.price_cents,
+            config.order_ttl_secs,
+            config.cooldown_secs
+        );
+        let executor = Self::new(backend, portfolio, config, leader);
+        let kill = risk::kill_switch().subscribe();
+        let handle = tokio::spawn(executor.run(status::board().subscribe_alerts(), rx, kill));
+        shutdown::coordinator().track("alert executor", handle);
+        tx
+    }
+
+    async fn run(
+        mut self,
+        mut alerts: broadcast::Receiver<AlertRecord>,
+        mut fills: mpsc::Receiver<KalshiFill>,
+        mut kill: watch::Receiver<Option<String>>,
+    ) {
+        let mut expiry = tokio::time::interval(EXPIRY_CHECK_INTERVAL);
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+        loop {
+            tokio::select! {
+                alert = alerts.recv() => match alert {
+                    Ok(alert) => self.on_alert(&alert).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Execution fell behind, skipped {} alerts", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                Some(fill) = fills.recv() => self.on_fill(&fill),
+                Ok(()) = kill.changed() => {
+                    let engaged = kill.borrow_and_update().is_some();
+                    if engaged {
+                        self.cancel_all().await;
+                    }
+                }
+                _ = expiry.tick() => {
+                    // Engaging the switch here cancels through the branch above
+                    self.risk.check_daily_loss(&self.portfolio);
+                    self.check_leadership().await;
+                    self.cancel_expired().await;
+                }
+                _ = shutdown::requested(&mut shutdown_rx) => {
+                    self.cancel_all().await;
+                    break;
+                }
+            }
+        }
+        info!("Execution shutting down");
+    }
+
+    /// Cancels what still rests once another instance has taken over.
+    async fn check_leadership(&mut self) {
+        let leading = self.leader.is_leader();
+        if self.leading && !leading && !self.resting.is_empty() {
+            warn!("No longer leading, cancelling {} resting orders", self.resting.len());
+            self.cancel_all().await;
+        }
+        self.leading = leading;
+    }
+
+    /// Orders resting on `market_ticker`, as contracts still to fill.
+    pub fn resting_on(&self, market_ticker: &str) -> u64 {
+        self.resting
+            .values()
+            .filter(|o| o.market_ticker == market_ticker)
+            .map(|o| o.remaining)
+            .sum()
+    }
+
+    pub async fn on_alert(&mut self, alert: &AlertRecord) {
+        let Some(series) = &alert.kalshi_series else {
+            return;
+        };
+        if !alert.event_id.starts_with(EventKind::Alert.prefix()) {
+            return;
+        }
+        if !self.leader.is_leader() {
+            return;
+        }
+        if let Some(event) = calendar::pausing_execution() {
+            info!("Execution paused around {}, not trading alert {}", event.name, alert.event_id);
+            return;
+        }
+        let side = match alert.direction {
+            Some(AlertDirection::Up) => self.config.side,
+            Some(AlertDirection::Down) => opposite(self.config.side),
+            None => {
+                info!("Alert {} points no direction, not trading", alert.event_id);
+                return;
+            }
+        };
+        let markets = status::board().markets_of_series(series);
+        let [market_ticker] = markets.as_slice() else {
+            info!(
+                "No single tracked {} market for alert {} ({} tracked), not trading",
+                series,
+                alert.event_id,
+                markets.len()
+            );
+            return;
+        };
+        if self.resting.values().any(|o| &o.market_ticker == market_ticker) {
+            info!("Order already resting on {}, not trading alert {}", market_ticker, alert.event_id);
+            return;
+        }
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        if let Some(last) = self.last_order.get(market_ticker.as_str()) {
+            if last.elapsed() < cooldown {
+                info!(
+                    "Ordered on {} {}s ago, not trading alert {}",
+                    market_ticker,
+                    last.elapsed().as_secs(),
+                    alert.event_id
+                );
+                return;
+            }
+        }
+        let delta = signed(side, self.config.contracts);
+        let open_orders = self.resting.len();
+        // Nothing of ours rests on the market, see above
+        if let Err(breach) = self
+            .risk
+            .check(market_ticker, delta, 0, open_orders, &self.portfolio)
+        {
+            warn!("🛑 Not trading alert {} on {}: {}", alert.event_id, market_ticker, breach);
+            metrics::inc(names::RISK_REJECTIONS_TOTAL, &[("limit", breach.limit())]);
+            return;
+        }
+        if let Err(e) = self.place(market_ticker, side, &alert.event_id).await {
+            error!("Order on {} for alert {} failed: {}", market_ticker, alert.event_id, e);
+        }
+    }
+
+    async fn place(&mut self, market_ticker: &str, side: OrderSide, event_id: &str) -> Result<()> {
+        let side_label = format!("{:?}", side).to_lowercase();
+        let request = OrderRequest {
+            market_ticker: market_ticker.to_string(),
+            side,
+            price_cents: self.config.price_cents,
+            contracts: self.config.contracts,
+            client_order_id: event_id.to_string(),
+        };
+        let order = self.backend.place(&request).await?;
+        self.last_order.insert(market_ticker.to_string(), Instant::now());
+
+        info!(
+            "💸 {} order {} for alert {}: {} {} {}x @ {}c, filled={}, remaining={}",
+            self.backend.name(),
+            order.order_id,
+            event_id,
+            market_ticker,
+            side_label,
+            self.config.contracts,
+            self.config.price_cents,
+            order.filled,
+            order.remaining
+        );
+        if order.remaining > 0 {
+            self.resting.insert(
+                order.order_id,
+                RestingOrder {
+                    market_ticker: market_ticker.to_string(),
+                    event_id: event_id.to_string(),
+                    unreported: order.filled,
+                    remaining: order.remaining,
+                    placed_at: Instant::now(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    pub fn on_fill(&mut self, fill: &KalshiFill) {
+        metrics::inc(names::EXECUTION_FILLS_TOTAL, &[("market", &fill.market_ticker)]);
+        self.portfolio.record_fill(fill);
+        let Some(order) = self.resting.get_mut(&fill.order_id) else {
+            return;
+        };
+        let count = fill.count.max(0) as u64;
+        // Our taker fills are what filled on arrival, counted when placed
+        let counted = if fill.is_taker { count.min(order.unreported) } else { 0 };
+        order.unreported -= counted;
+        order.remaining = order.remaining.saturating_sub(count - counted);
+        info!(
+            "✅ Filled {} of order {} on {} for alert {}, {} remaining",
+            fill.count, fill.order_id, order.market_ticker, order.event_id, order.remaining
+        );
+        if order.remaining == 0 {
+            self.resting.remove(&fill.order_id);
+        }
+    }
+
+    /// Cancels orders resting past the TTL. Ones that fail to cancel are
+    /// kept and retried on the next check.
+    pub async fn cancel_expired(&mut self) {
+        let ttl = Duration::from_secs(self.config.order_ttl_secs);
+        let expired: Vec<String> = self
+            .resting
+            .iter()
+            .filter(|(_, o)| o.placed_at.elapsed() >= ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+        self.cancel(expired, "expired").await;
+    }
+
+    /// Cancels every resting order, for the kill switch, shutdown and
+    /// losing leadership.
+    pub async fn cancel_all(&mut self) {
+        let all: Vec<String> = self.resting.keys().cloned().collect();
+        self.cancel(all, "halted").await;
+    }
+
+    async fn cancel(&mut self, order_ids: Vec<String>, why: &str) {
+        if order_ids.is_empty() {
+            return;
+        }
+
+        for chunk in order_ids.chunks(MAX_CANCEL_CHUNK_SIZE) {
+            let ids: Vec<&str> = chunk.iter().map(String::as_str).collect();
+            match self.backend.cancel(&ids).await {
+                Ok(cancelled_orders) => {
+                    for cancelled in &cancelled_orders {
+                        if let Some(order) = self.resting.remove(&cancelled.order_id) {
+                            info!(
+                                "⌛ Cancelled {} order {} on {} for alert {} after {}s, reduced by {}",
+                                why,
+                                cancelled.order_id,
+                                order.market_ticker,
+                                order.event_id,
+                                order.placed_at.elapsed().as_secs(),
+                                cancelled.reduced_by
+                            );
+                        }
+                    }
+                    // Orders missing from the response may have filled or
+                    // been cancelled elsewhere, or may still rest
+                    for id in chunk {
+                        if self.resting.contains_key(id) {
+                            self.refresh(id).await;
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to cancel {} {} orders: {}", ids.len(), why, e),
+            }
+        }
+    }
+
+    /// Stops tracking `order_id` once the backend no longer has it resting;
+    /// one still resting, or that can't be looked up, is kept for the next
+    /// check.
+    async fn refresh(&mut self, order_id: &str) {
+        match self.backend.remaining(order_id).await {
+            Ok(None) => {
+                if let Some(order) = self.resting.remove(order_id) {
+                    info!(
+                        "Order {} on {} for alert {} no longer rests",
+                        order_id, order.market_ticker, order.event_id
+                    );
+                }
+            }
+            Ok(Some(remaining)) => {
+                if let Some(order) = self.resting.get_mut(order_id) {
+                    warn!(
+                        "Order {} on {} still rests after cancel, {} remaining",
+                        order_id, order.market_ticker, remaining
+                    );
+                    order.remaining = remaining;
+                }
+            }
+            Err(e) => warn!("Failed to look up order {} after cancel: {}", order_id, e),
+        }
+    }
+}
+
+/// `contracts` of `side` in YES terms.
+fn signed(side: OrderSide, contracts: u64) -> i64 {
+    match side {
+        OrderSide::Yes => contracts as i64,
+        OrderSide::No => -(contracts as i64),
+    }
+}
+
+fn opposite(side: OrderSide) -> OrderSide {
+    match side {
+        OrderSide::Yes => OrderSide::No,
+        OrderSide::No => OrderSide::Yes,
+    }
+}
@@ -0,0 +1,297 @@
+//! Paper trading: alert orders filled against the live Kalshi book.
+//!
+//! An order first takes whatever asks sit at or below its limit, at their
+//! prices. The rest rests behind the contracts already bid at its price and
+//! takes only size added to those asks later, as the book still shows what
+//! we took. The queue ahead only shrinks, to the size the book shows at the
+//! price, and once it is gone a trade at our price fills the order; a trade
+//! below it fills it regardless of the queue. Trade sizes are not in the
+//! state, so such a trade fills all that remains.
+//!
+//! Nothing reaches Kalshi. Fills go to the executor like real ones, with
+//! the next match, which books them into a `paper`
+//! [`Portfolio`](crate::portfolio::Portfolio) for P&L.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use super::backend::{CancelledOrder, OrderBackend, OrderRequest, PlacedOrder};
+use crate::error::Result;
+use crate::exchanges::kalshi::models::{KalshiFill, KalshiOrderbook, OrderSide, OrderbookLevel};
+use crate::metrics::{self, names};
+use crate::state::KalshiState;
+
+/// How often resting paper orders are matched against the book.
+const MATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+const PRICE_EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone)]
+struct PaperOrder {
+    market_ticker: String,
+    side: OrderSide,
+    /// Dollars
+    price: f64,
+    remaining: u64,
+    /// Contracts bid at our price ahead of us
+    queue_ahead: i64,
+    /// Ask price level -> contracts of it we took, on arrival or since
+    taken: HashMap<i64, i64>,
+    /// Trades up to here have been matched already
+    since: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct PaperBook {
+    next_id: u64,
+    /// Order id -> resting order
+    orders: HashMap<String, PaperOrder>,
+    /// Fills not yet sent to the executor
+    pending: Vec<KalshiFill>,
+}
+
+pub struct PaperBackend {
+    state: Arc<KalshiState>,
+    book: Arc<Mutex<PaperBook>>,
+    fills: OnceLock<mpsc::Sender<KalshiFill>>,
+}
+
+impl PaperBackend {
+    pub fn new(state: Arc<KalshiState>) -> Self {
+        Self {
+            state,
+            book: Arc::new(Mutex::new(PaperBook::default())),
+            fills: OnceLock::new(),
+        }
+    }
+
+    async fn run(state: Arc<KalshiState>, book: Arc<Mutex<PaperBook>>, fills: mpsc::Sender<KalshiFill>) {
+        let mut ticker = tokio::time::interval(MATCH_INTERVAL);
+        while !fills.is_closed() {
+            ticker.tick().await;
+            let matched = {
+                let mut book = book.lock().unwrap();
+                book.match_resting(&state);
+                std::mem::take(&mut book.pending)
+            };
+            // Waits out a full queue rather than losing a position
+            for fill in matched {
+                if fills.send(fill).await.is_err() {
+                    warn!("Paper fills queue closed");
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl OrderBackend for PaperBackend {
+    fn name(&self) -> &'static str {
+        "paper"
+    }
+
+    fn attach_fills(&self, fills: mpsc::Sender<KalshiFill>) {
+        if self.fills.set(fills.clone()).is_ok() {
+            tokio::spawn(Self::run(self.state.clone(), self.book.clone(), fills));
+        }
+    }
+
+    async fn place(&self, order: &OrderRequest) -> Result<PlacedOrder> {
+        let ob = self.state.orderbooks.get(&order.market_ticker).map(|b| b.clone());
+        // The executor placing this also drains the fills queue, so arrival
+        // fills wait for the match loop instead of a send that could block it
+        Ok(self.book.lock().unwrap().place(order, ob.as_ref()))
+    }
+
+    async fn cancel(&self, order_ids: &[&str]) -> Result<Vec<CancelledOrder>> {
+        let mut book = self.book.lock().unwrap();
+        Ok(order_ids
+            .iter()
+            .filter_map(|id| {
+                book.orders.remove(*id).map(|o| CancelledOrder {
+                    order_id: id.to_string(),
+                    reduced_by: o.remaining,
+                })
+            })
+            .collect())
+    }
+
+    async fn remaining(&self, order_id: &str) -> Result<Option<u64>> {
+        Ok(self.book.lock().unwrap().orders.get(order_id).map(|o| o.remaining))
+    }
+}
+
+impl PaperBook {
+    fn place(&mut self, request: &OrderRequest, ob: Option<&KalshiOrderbook>) -> PlacedOrder {
+        self.next_id += 1;
+        let order_id = format!("paper-{}", self.next_id);
+        let price = request.price_cents as f64 / 100.0;
+
+        let mut remaining = request.contracts;
+        let mut taken = HashMap::new();
+        if let Some(ob) = ob {
+            for level in asks(ob, request.side) {
+                if remaining == 0 || level.price > price + PRICE_EPSILON {
+                    break;
+                }
+                let count = remaining.min(level.quantity.max(0) as u64);
+                if count > 0 {
+                    remaining -= count;
+                    taken.insert(level_key(level.price), count as i64);
+                    let fill = self.fill(&order_id, &request.market_ticker, request.side, level.price, count, true);
+                    self.pending.push(fill);
+                }
+            }
+        }
+
+        if remaining > 0 {
+            let queue_ahead = ob.map(|ob| qty_at(bids(ob, request.side), price)).unwrap_or(0);
+            self.orders.insert(
+                order_id.clone(),
+                PaperOrder {
+                    market_ticker: request.market_ticker.clone(),
+                    side: request.side,
+                    price,
+                    remaining,
+                    queue_ahead,
+                    taken,
+                    since: Utc::now(),
+                },
+            );
+        }
+        PlacedOrder {
+            order_id,
+            filled: request.contracts - remaining,
+            remaining,
+        }
+    }
+
+    /// Fills resting orders the book or its trades have reached, queueing
+    /// the fills.
+    fn match_resting(&mut self, state: &KalshiState) {
+        let mut matched: Vec<(String, u64)> = Vec::new();
+        for (id, order) in self.orders.iter_mut() {
+            let Some(ob) = state.orderbooks.get(&order.market_ticker) else {
+                continue;
+            };
+            let levels: Vec<(i64, i64)> = asks(&ob, order.side)
+                .iter()
+                .filter(|l| l.price <= order.price + PRICE_EPSILON)
+                .map(|l| (level_key(l.price), l.quantity.max(0)))
+                .collect();
+            order.queue_ahead = order.queue_ahead.min(qty_at(bids(&ob, order.side), order.price));
+            drop(ob);
+
+            // What we took still shows in a level's size; one that shrank or
+            // went away lost our part first
+            order.taken.retain(|key, _| levels.iter().any(|(k, _)| k == key));
+            let mut count = 0;
+            for (key, quantity) in levels {
+                let taken = order.taken.entry(key).or_insert(0);
+                *taken = (*taken).min(quantity);
+                let take = (order.remaining - count).min((quantity - *taken) as u64);
+                *taken += take as i64;
+                count += take;
+            }
+            if let Some(trade) = state.last_trades.get(&order.market_ticker) {
+                if trade.at > order.since {
+                    order.since = trade.at;
+                    // What the seller got for our side
+                    let price = match order.side {
+                        OrderSide::Yes => trade.yes_price,
+                        OrderSide::No => 1.0 - trade.yes_price,
+                    };
+                    let through = price < order.price - PRICE_EPSILON;
+                    let at_front = price <= order.price + PRICE_EPSILON && order.queue_ahead <= 0;
+                    if through || at_front {
+                        count = order.remaining;
+                    }
+                }
+            }
+            if count > 0 {
+                matched.push((id.clone(), count));
+            }
+        }
+
+        for (id, count) in matched {
+            let Some(order) = self.orders.get_mut(&id) else {
+                continue;
+            };
+            order.remaining -= count;
+            let order = order.clone();
+            if order.remaining == 0 {
+                self.orders.remove(&id);
+            }
+            let fill = self.fill(&id, &order.market_ticker, order.side, order.price, count, false);
+            self.pending.push(fill);
+        }
+    }
+
+    fn fill(&self, order_id: &str, market_ticker: &str, side: OrderSide, price: f64, count: u64, is_taker: bool) -> KalshiFill {
+        let side_label = format!("{:?}", side).to_lowercase();
+        metrics::add(
+            names::PAPER_FILLS_TOTAL,
+            &[("market", market_ticker), ("side", &side_label)],
+            count,
+        );
+        info!(
+            "📝 Paper {} {} {} {}x @ {:.2} on order {}",
+            if is_taker { "took" } else { "filled" },
+            market_ticker,
+            side_label,
+            count,
+            price,
+            order_id
+        );
+
+        let yes_price = match side {
+            OrderSide::Yes => price,
+            OrderSide::No => 1.0 - price,
+        };
+        KalshiFill {
+            trade_id: format!("{}-{}", order_id, Utc::now().timestamp_micros()),
+            order_id: order_id.to_string(),
+            market_ticker: market_ticker.to_string(),
+            is_taker,
+            side,
+            yes_price: Some((yes_price * 100.0).round() as i64),
+            count: count as i64,
+            action: Some("buy".to_string()),
+            ts: Some(Utc::now().timestamp()),
+        }
+    }
+}
+
+fn asks(ob: &KalshiOrderbook, side: OrderSide) -> &[OrderbookLevel] {
+    match side {
+        OrderSide::Yes => &ob.yes_asks,
+        OrderSide::No => &ob.no_asks,
+    }
+}
+
+fn bids(ob: &KalshiOrderbook, side: OrderSide) -> &[OrderbookLevel] {
+    match side {
+        OrderSide::Yes => &ob.yes_bids,
+        OrderSide::No => &ob.no_bids,
+    }
+}
+
+fn qty_at(levels: &[OrderbookLevel], price: f64) -> i64 {
+    levels
+        .iter()
+        .find(|l| (l.price - price).abs() < PRICE_EPSILON)
+        .map(|l| l.quantity.max(0))
+        .unwrap_or(0)
+}
+
+/// An ask price as a map key, in hundredths of a cent.
+fn level_key(price: f64) -> i64 {
+    (price * 10_000.0).round() as i64
+}
@@ -0,0 +1,236 @@
+//! Pre-trade risk limits and the kill switch.
+//!
+//! Before every order, placed on an alert or by the trader, it is checked
+//! against [`RiskConfig`]: the contracts it would leave us holding on its
+//! market, counting what already rests there, the number of open orders,
+//! and the P&L lost since the start of the market-timezone day, or since
+//! startup on the first day. The loss limit is also checked every second;
+//! crossing it engages the kill switch, which stops new orders and cancels
+//! the working ones, the trader's ladder included, until an operator
+//! releases it with `DELETE /kill-switch`, and again while the loss stays
+//! over the limit.
+//! `POST /kill-switch` engages it by hand.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+#[cfg(feature = "http")]
+use axum::routing::get;
+#[cfg(feature = "http")]
+use axum::{Json, Router};
+use chrono::{NaiveDate, Utc};
+#[cfg(feature = "http")]
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::config::RiskConfig;
+use crate::metrics::{self, names};
+use crate::portfolio::Portfolio;
+use crate::timezone;
+
+static KILL_SWITCH: OnceLock<KillSwitch> = OnceLock::new();
+
+/// Process-wide halt of execution; holds the reason while engaged.
+pub struct KillSwitch {
+    tx: watch::Sender<Option<String>>,
+}
+
+pub fn kill_switch() -> &'static KillSwitch {
+    KILL_SWITCH.get_or_init(|| KillSwitch {
+        tx: watch::channel(None).0,
+    })
+}
+
+impl KillSwitch {
+    /// Engages the switch; a second engagement keeps the first reason.
+    pub fn engage(&self, reason: &str) {
+        let engaged = self.tx.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason.to_string());
+            true
+        });
+        if engaged {
+            warn!("🛑 Kill switch engaged: {}", reason);
+            metrics::set(names::KILL_SWITCH_ENGAGED, &[], 1.0);
+        }
+    }
+
+    pub fn release(&self) {
+        if self.tx.send_replace(None).is_some() {
+            info!("Kill switch released");
+            metrics::set(names::KILL_SWITCH_ENGAGED, &[], 0.0);
+        }
+    }
+
+    pub fn reason(&self) -> Option<String> {
+        self.tx.borrow().clone()
+    }
+
+    /// Changes from now on.
+    pub fn subscribe(&self) -> watch::Receiver<Option<String>> {
+        self.tx.subscribe()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Breach {
+    Halted(String),
+    Position { market: String, would_hold: i64, max: u64 },
+    OpenOrders { open: usize, max: usize },
+    DailyLoss { loss: f64, max: f64 },
+}
+
+impl Breach {
+    pub fn limit(&self) -> &'static str {
+        match self {
+            Breach::Halted(_) => "kill_switch",
+            Breach::Position { .. } => "max_position",
+            Breach::OpenOrders { .. } => "max_open_orders",
+            Breach::DailyLoss { .. } => "max_daily_loss",
+        }
+    }
+}
+
+impl fmt::Display for Breach {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breach::Halted(reason) => write!(f, "kill switch engaged ({})", reason),
+            Breach::Position { market, would_hold, max } => {
+                write!(f, "would hold {} contracts on {}, max {}", would_hold.unsigned_abs(), market, max)
+            }
+            Breach::OpenOrders { open, max } => write!(f, "{} orders open, max {}", open, max),
+            Breach::DailyLoss { loss, max } => write!(f, "lost ${:.2} today, max ${:.2}", loss, max),
+        }
+    }
+}
+
+pub struct RiskManager {
+    config: RiskConfig,
+    /// Market day the loss is counted over
+    day: NaiveDate,
+    /// Portfolio P&L when the day started
+    day_start_pnl: f64,
+}
+
+impl RiskManager {
+    /// Counts the day's loss from where `portfolio` stands now.
+    pub fn new(config: RiskConfig, portfolio: &Portfolio) -> Self {
+        Self {
+            config,
+            day: market_today(),
+            day_start_pnl: portfolio.total_pnl(),
+        }
+    }
+
+    /// The first limit an order moving `market` by `delta` YES contracts
+    /// would break. `resting` is what our working orders there would add.
+    pub fn check(
+        &mut self,
+        market: &str,
+        delta: i64,
+        resting: i64,
+        open_orders: usize,
+        portfolio: &Portfolio,
+    ) -> Result<(), Breach> {
+        if let Some(reason) = kill_switch().reason() {
+            return Err(Breach::Halted(reason));
+        }
+        if let Some(breach) = self.check_daily_loss(portfolio) {
+            return Err(breach);
+        }
+        if let Some(max) = self.config.max_open_orders {
+            if open_orders >= max {
+                return Err(Breach::OpenOrders { open: open_orders, max });
+            }
+        }
+        if let Some(max) = self.config.max_position {
+            let would_hold = portfolio.position(market) + resting + delta;
+            if would_hold.unsigned_abs() > max {
+                return Err(Breach::Position {
+                    market: market.to_string(),
+                    would_hold,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Engages the kill switch once the day's loss reaches the limit.
+    pub fn check_daily_loss(&mut self, portfolio: &Portfolio) -> Option<Breach> {
+        self.check_daily_loss_on(market_today(), portfolio)
+    }
+
+    /// [`Self::check_daily_loss`] as of market day `today`; a new day
+    /// starts counting from the P&L it opens with.
+    pub fn check_daily_loss_on(&mut self, today: NaiveDate, portfolio: &Portfolio) -> Option<Breach> {
+        let max = self.config.max_daily_loss?;
+        let pnl = portfolio.total_pnl();
+        if today != self.day {
+            self.day = today;
+            self.day_start_pnl = pnl;
+        }
+        let loss = self.day_start_pnl - pnl;
+        if loss < max {
+            return None;
+        }
+        let breach = Breach::DailyLoss { loss, max };
+        kill_switch().engage(&breach.to_string());
+        Some(breach)
+    }
+}
+
+fn market_today() -> NaiveDate {
+    Utc::now().with_timezone(&timezone::market()).date_naive()
+}
+
+#[cfg(feature = "http")]
+#[derive(Debug, Serialize)]
+struct KillSwitchStatus {
+    engaged: bool,
+    reason: Option<String>,
+}
+
+#[cfg(feature = "http")]
+#[derive(Debug, Default, Deserialize)]
+struct EngageRequest {
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// `GET /kill-switch` reports it, `POST` engages it with an optional
+/// `{"reason": ...}` and `DELETE` releases it.
+#[cfg(feature = "http")]
+pub fn router() -> Router {
+    Router::new().route("/kill-switch", get(status).post(engage).delete(release))
+}
+
+#[cfg(feature = "http")]
+fn current() -> Json<KillSwitchStatus> {
+    let reason = kill_switch().reason();
+    Json(KillSwitchStatus {
+        engaged: reason.is_some(),
+        reason,
+    })
+}
+
+#[cfg(feature = "http")]
+async fn status() -> Json<KillSwitchStatus> {
+    current()
+}
+
+#[cfg(feature = "http")]
+async fn engage(body: Option<Json<EngageRequest>>) -> Json<KillSwitchStatus> {
+    let request = body.map(|Json(r)| r).unwrap_or_default();
+    kill_switch().engage(request.reason.as_deref().unwrap_or("engaged by an operator"));
+    current()
+}
+
+#[cfg(feature = "http")]
+async fn release() -> Json<KillSwitchStatus> {
+    kill_switch().release();
+    current()
+}
@@ -0,0 +1,232 @@
+//! Golden-file corpus of raw exchange frames and their expected decoded fields.
+//!
+//! ```text
+//! <corpus>/binance/<name>.sbe            raw SBE frame
+//! <corpus>/kalshi/<name>.json            raw Kalshi WebSocket text frame
+//! <corpus>/<exchange>/<name>.expected.json
+//! ```
+//!
+//! The expected file is a flat JSON object. Only the keys it lists are
+//! compared, so new decoded fields do not invalidate existing fixtures.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::{json, Map, Value};
+
+use crate::error::{Error, Result};
+use crate::exchanges::binance::sbe::decoder::SbeDecoder;
+use crate::exchanges::binance::sbe::messages::SbeMessage;
+use crate::exchanges::schema;
+use crate::exchanges::MarketEvent;
+use crate::exchanges::kalshi::models::{
+    KalshiMarketLifecycleMsg, KalshiOrderbook, KalshiOrderbookDelta, KalshiOrderbookSnapshot,
+    KalshiWsMessage,
+};
+use crate::recorder::{FrameSource, RawFrame};
+
+const EXPECTED_SUFFIX: &str = ".expected.json";
+const FLOAT_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub name: String,
+    pub source: FrameSource,
+    pub data: Vec<u8>,
+    pub expected: Value,
+}
+
+pub fn load_corpus<P: AsRef<Path>>(dir: P) -> Result<Vec<Fixture>> {
+    let mut fixtures = Vec::new();
+    for (subdir, extension, source) in [
+        ("binance", "sbe", FrameSource::BinanceSbe),
+        ("kalshi", "json", FrameSource::KalshiJson),
+        ("events", "json", FrameSource::Event),
+    ] {
+        let dir = dir.as_ref().join(subdir);
+        if !dir.exists() {
+            continue;
+        }
+        let mut paths: Vec<_> = fs::read_dir(&dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                let name = p.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                p.extension().and_then(|e| e.to_str()) == Some(extension)
+                    && !name.ends_with(EXPECTED_SUFFIX)
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            let expected_path = dir.join(format!("{}{}", stem, EXPECTED_SUFFIX));
+            let expected = serde_json::from_slice(&fs::read(&expected_path).map_err(|e| {
+                Error::Other(format!("Missing {}: {}", expected_path.display(), e))
+            })?)?;
+            fixtures.push(Fixture {
+                name: format!("{}/{}", subdir, stem),
+                source,
+                data: fs::read(&path)?,
+                expected,
+            });
+        }
+    }
+    Ok(fixtures)
+}
+
+/// Writes `frame` into the corpus with its currently decoded fields as the
+/// expectation. Review the generated file before committing it.
+pub fn save_fixture<P: AsRef<Path>>(dir: P, name: &str, frame: &RawFrame) -> Result<()> {
+    let (subdir, extension) = match frame.source {
+        FrameSource::BinanceSbe => ("binance", "sbe"),
+        FrameSource::KalshiJson => ("kalshi", "json"),
+        FrameSource::Event => ("events", "json"),
+    };
+    let dir = dir.as_ref().join(subdir);
+    fs::create_dir_all(&dir)?;
+
+    let expected = decoded_fields(frame.source, &frame.data)?;
+    fs::write(dir.join(format!("{}.{}", name, extension)), &frame.data)?;
+    fs::write(
+        dir.join(format!("{}{}", name, EXPECTED_SUFFIX)),
+        serde_json::to_vec_pretty(&expected)?,
+    )?;
+    Ok(())
+}
+
+pub fn decoded_fields(source: FrameSource, data: &[u8]) -> Result<Value> {
+    match source {
+        FrameSource::BinanceSbe => sbe_fields(&SbeDecoder::new().decode(data)?),
+        FrameSource::KalshiJson => kalshi_fields(data),
+        FrameSource::Event => event_fields(data),
+    }
+}
+
+fn event_fields(data: &[u8]) -> Result<Value> {
+    let event: MarketEvent = schema::decode(data)?;
+    let meta = event.meta();
+    Ok(json!({
+        "kind": event.kind(),
+        "exchange": meta.exchange.as_str(),
+        "instrument": meta.instrument,
+        "event_time_micros": meta.timestamp.timestamp_micros(),
+    }))
+}
+
+fn sbe_fields(msg: &SbeMessage<'_>) -> Result<Value> {
+    let mut fields = json!({
+        "kind": msg.kind(),
+        "symbol": msg.symbol(),
+        "event_time_micros": msg.timestamp().timestamp_micros(),
+    });
+    let extra = match msg {
+        SbeMessage::Trade(e) => {
+            let mut extra = json!({ "transact_time_micros": e.transact_time.timestamp_micros() });
+            if let Some(t) = &e.last_trade {
+                extra["trade_id"] = json!(t.id);
+                extra["price"] = json!(t.price);
+                extra["qty"] = json!(t.qty);
+                extra["is_buyer_maker"] = json!(t.is_buyer_maker);
+            }
+            extra
+        }
+        SbeMessage::BestBidAsk(e) => json!({
+            "book_update_id": e.book_update_id,
+            "bid_price": e.bid_price,
+            "bid_qty": e.bid_qty,
+            "ask_price": e.ask_price,
+            "ask_qty": e.ask_qty,
+        }),
+        SbeMessage::DepthSnapshot(e) => {
+            let (bid_top5, bid_top10, bid_all) = e.bids.sum_qtys_top5_top10_all()?;
+            let (ask_top5, ask_top10, ask_all) = e.asks.sum_qtys_top5_top10_all()?;
+            json!({
+                "book_update_id": e.book_update_id,
+                "bid_levels": e.bids.len(),
+                "ask_levels": e.asks.len(),
+                "bid_qty_top5": bid_top5,
+                "bid_qty_top10": bid_top10,
+                "bid_qty_all": bid_all,
+                "ask_qty_top5": ask_top5,
+                "ask_qty_top10": ask_top10,
+                "ask_qty_all": ask_all,
+            })
+        }
+        SbeMessage::DepthDiff(e) => json!({
+            "first_book_update_id": e.first_book_update_id,
+            "last_book_update_id": e.last_book_update_id,
+            "bid_levels": e.bids.len(),
+            "ask_levels": e.asks.len(),
+        }),
+    };
+    merge(&mut fields, extra);
+    Ok(fields)
+}
+
+fn kalshi_fields(data: &[u8]) -> Result<Value> {
+    let msg: KalshiWsMessage = serde_json::from_slice(data)?;
+    let mut fields = json!({ "type": msg.msg_type.clone(), "sid": msg.sid });
+    let payload = match msg.payload() {
+        Some(p) => p.clone(),
+        None => return Ok(fields),
+    };
+
+    let extra = match msg.msg_type.as_deref() {
+        Some("orderbook_snapshot") => {
+            let snapshot: KalshiOrderbookSnapshot = serde_json::from_value(payload)?;
+            let mut book = KalshiOrderbook::new_empty(snapshot.market_ticker.clone());
+            let (yes_levels, no_levels) = (snapshot.yes_dollars.len(), snapshot.no_dollars.len());
+            book.apply_snapshot(snapshot);
+            json!({
+                "market_ticker": book.market_ticker,
+                "yes_levels": yes_levels,
+                "no_levels": no_levels,
+                "top_yes_bid": book.top_yes_bid(),
+                "top_yes_ask": book.top_yes_ask(),
+                "top_no_bid": book.top_no_bid(),
+                "top_no_ask": book.top_no_ask(),
+            })
+        }
+        Some("orderbook_delta") => {
+            let delta: KalshiOrderbookDelta = serde_json::from_value(payload)?;
+            json!({
+                "market_ticker": delta.market_ticker,
+                "price": delta.price_dollars.parse::<f64>().ok(),
+                "delta": delta.delta,
+                "side": delta.side,
+            })
+        }
+        Some("market_lifecycle_v2") => {
+            serde_json::to_value(serde_json::from_value::<KalshiMarketLifecycleMsg>(payload)?)?
+        }
+        _ => payload,
+    };
+    merge(&mut fields, extra);
+    Ok(fields)
+}
+
+fn merge(into: &mut Value, extra: Value) {
+    if let (Some(into), Value::Object(extra)) = (into.as_object_mut(), extra) {
+        into.extend(extra);
+    }
+}
+
+/// Compares every key of `expected` against `actual`, with a small tolerance
+/// for floats. Returns a description of the first mismatch.
+pub fn check_fields(expected: &Value, actual: &Value) -> std::result::Result<(), String> {
+    let empty = Map::new();
+    let expected = expected.as_object().ok_or("Expected fields must be a JSON object")?;
+    let actual = actual.as_object().unwrap_or(&empty);
+
+    for (key, want) in expected {
+        let got = actual.get(key).unwrap_or(&Value::Null);
+        let matches = match (want.as_f64(), got.as_f64()) {
+            (Some(w), Some(g)) => (w - g).abs() <= FLOAT_TOLERANCE * w.abs().max(1.0),
+            _ => want == got,
+        };
+        if !matches {
+            return Err(format!("field `{}`: expected {}, decoded {}", key, want, got));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! Liveness signal for external supervisors.
+//!
+//! The processing loops call [`HeartbeatHandle::beat`] whenever they make
+//! progress. Every `interval_secs` a background task checks whether any beat
+//! happened since its last report and, if so, writes the current unix time to
+//! the heartbeat file and/or pings the dead-man's-switch URL. A hung loop stops
+//! beating, the file goes stale and the URL stops being hit, which is what
+//! systemd, healthchecks.io and friends alert or restart on.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+use crate::config::HeartbeatConfig;
+
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Cheap cloneable handle the loops beat through.
+#[derive(Clone, Default)]
+pub struct HeartbeatHandle {
+    /// Unix millis of the latest beat, 0 before the first one.
+    last_beat: Arc<AtomicU64>,
+}
+
+impl HeartbeatHandle {
+    /// Handle that records beats but reports them nowhere.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn beat(&self) {
+        self.last_beat.store(now_millis(), Ordering::Relaxed);
+    }
+
+    /// Time since the latest beat, `None` before the first one.
+    pub fn last_beat_age(&self) -> Option<Duration> {
+        match self.last_beat.load(Ordering::Relaxed) {
+            0 => None,
+            last => Some(Duration::from_millis(now_millis().saturating_sub(last))),
+        }
+    }
+
+    /// Sleeps while still beating, for deliberate idle periods such as the
+    /// maintenance window that would otherwise look like a hang.
+    pub async fn sleep(&self, duration: Duration) {
+        let deadline = tokio::time::Instant::now() + duration;
+        let step = Duration::from_secs(1);
+        loop {
+            self.beat();
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                break;
+            }
+            tokio::time::sleep((deadline - now).min(step)).await;
+        }
+    }
+}
+
+pub struct Heartbeat {
+    config: HeartbeatConfig,
+    client: reqwest::Client,
+    last_beat: Arc<AtomicU64>,
+}
+
+impl Heartbeat {
+    pub fn spawn(config: &HeartbeatConfig) -> HeartbeatHandle {
+        let handle = HeartbeatHandle::default();
+        let heartbeat = Self {
+            config: config.clone(),
+            client: reqwest::Client::new(),
+            last_beat: handle.last_beat.clone(),
+        };
+
+        if let Some(file) = &config.file {
+            info!("💓 Writing heartbeat to {} every {}s", file, config.interval_secs);
+        }
+        if let Some(url) = &config.url {
+            info!("💓 Pinging heartbeat URL {} every {}s", url, config.interval_secs);
+        }
+        tokio::spawn(heartbeat.run());
+        handle
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+        let mut last_reported = 0;
+
+        loop {
+            ticker.tick().await;
+
+            let last_beat = self.last_beat.load(Ordering::Relaxed);
+            if last_beat <= last_reported {
+                continue;
+            }
+            last_reported = last_beat;
+
+            if let Some(file) = &self.config.file {
+                if let Err(e) = write_heartbeat(Path::new(file), last_beat / 1000).await {
+                    warn!("Failed to write heartbeat file {}: {}", file, e);
+                }
+            }
+            if let Some(url) = &self.config.url {
+                match self.client.get(url).timeout(PING_TIMEOUT).send().await {
+                    Ok(resp) if !resp.status().is_success() => {
+                        warn!("Heartbeat URL returned {}", resp.status());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to ping heartbeat URL: {}", e),
+                }
+            }
+        }
+    }
+}
+
+/// Writes through a temp file and renames, so readers never see a partial write.
+async fn write_heartbeat(path: &Path, unix_secs: u64) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, format!("{}\n", unix_secs)).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -0,0 +1,192 @@
+//! Per-instrument decimal precision, used wherever prices and quantities are
+//! rendered or stored so logs, reports and DB rows agree on rounding.
+//!
+//! Lookups try the exact symbol, then the Kalshi series a market ticker
+//! belongs to (`KXBTC15M-25OCT161530-30` -> `KXBTC15M`), then the exchange
+//! default. Overrides come from `INSTRUMENT_PRECISION`.
+//!
+//! The registry also holds the Binance pair each Kalshi series settles
+//! against (`SYMBOL_MAP`), so an alert on `BTCUSDT` is correlated with
+//! `KXBTC15M` and not whichever Kalshi market happens to be tracked.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{OnceLock, RwLock};
+
+use rust_decimal::Decimal;
+
+use crate::error::{Error, Result};
+
+/// Kalshi quotes contract prices in dollars with subpenny ticks on some
+/// markets, and trades whole contracts.
+const KALSHI_DEFAULT: Precision = Precision::new(4, 0);
+/// Covers the USDT majors; thinner pairs should be overridden.
+const BINANCE_DEFAULT: Precision = Precision::new(2, 5);
+const FALLBACK: Precision = Precision::new(4, 4);
+
+const BINANCE_SYMBOLS: &[(&str, Precision)] = &[
+    ("BTCUSDT", Precision::new(2, 5)),
+    ("ETHUSDT", Precision::new(2, 4)),
+    ("SOLUSDT", Precision::new(2, 3)),
+    ("XRPUSDT", Precision::new(4, 1)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Precision {
+    pub price_decimals: u32,
+    pub quantity_decimals: u32,
+}
+
+impl Precision {
+    pub const fn new(price_decimals: u32, quantity_decimals: u32) -> Self {
+        Self {
+            price_decimals,
+            quantity_decimals,
+        }
+    }
+
+    pub fn price(&self, value: f64) -> String {
+        format!("{:.*}", self.price_decimals as usize, value)
+    }
+
+    pub fn quantity(&self, value: f64) -> String {
+        format!("{:.*}", self.quantity_decimals as usize, value)
+    }
+
+    /// `value` rounded to the price precision, for DECIMAL columns.
+    pub fn price_decimal(&self, value: f64) -> Option<Decimal> {
+        Decimal::from_str(&self.price(value)).ok()
+    }
+
+    /// `value` rounded to the quantity precision, for DECIMAL columns.
+    pub fn quantity_decimal(&self, value: f64) -> Option<Decimal> {
+        Decimal::from_str(&self.quantity(value)).ok()
+    }
+}
+
+impl FromStr for Precision {
+    type Err = Error;
+
+    /// Parses `PRICE:QUANTITY` decimals, e.g. `2:5`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Config(format!("Invalid precision '{}', expected PRICE:QUANTITY", s));
+        let (price, quantity) = s.split_once(':').ok_or_else(invalid)?;
+        Ok(Self::new(
+            price.trim().parse().map_err(|_| invalid())?,
+            quantity.trim().parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+pub struct InstrumentRegistry {
+    instruments: RwLock<HashMap<String, Precision>>,
+    /// Binance symbol -> Kalshi series.
+    links: RwLock<HashMap<String, String>>,
+}
+
+impl InstrumentRegistry {
+    fn new() -> Self {
+        let instruments = BINANCE_SYMBOLS
+            .iter()
+            .map(|(symbol, precision)| (symbol.to_string(), *precision))
+            .collect();
+        Self {
+            instruments: RwLock::new(instruments),
+            links: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn register(&self, symbol: &str, precision: Precision) {
+        self.instruments
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(symbol.to_uppercase(), precision);
+    }
+
+    /// Precision for `symbol` on `exchange` (`"kalshi"` or `"binance"`).
+    pub fn precision(&self, exchange: &str, symbol: &str) -> Precision {
+        let instruments = self.instruments.read().unwrap_or_else(|e| e.into_inner());
+        let symbol = symbol.to_uppercase();
+        let series = symbol.split('-').next().unwrap_or(&symbol);
+
+        instruments
+            .get(&symbol)
+            .or_else(|| instruments.get(series))
+            .copied()
+            .unwrap_or(match exchange {
+                "kalshi" => KALSHI_DEFAULT,
+                "binance" => BINANCE_DEFAULT,
+                _ => FALLBACK,
+            })
+    }
+
+    pub fn kalshi(&self, market_ticker: &str) -> Precision {
+        self.precision("kalshi", market_ticker)
+    }
+
+    pub fn binance(&self, symbol: &str) -> Precision {
+        self.precision("binance", symbol)
+    }
+
+    pub fn link(&self, binance_symbol: &str, kalshi_series: &str) {
+        self.links
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(binance_symbol.to_uppercase(), kalshi_series.to_uppercase());
+    }
+
+    /// The Kalshi series mapped to `binance_symbol`, if any.
+    pub fn kalshi_series(&self, binance_symbol: &str) -> Option<String> {
+        let links = self.links.read().unwrap_or_else(|e| e.into_inner());
+        links.get(&binance_symbol.to_uppercase()).cloned()
+    }
+
+    /// The Binance symbol mapped to `kalshi_series`, if any.
+    pub fn binance_symbol(&self, kalshi_series: &str) -> Option<String> {
+        let links = self.links.read().unwrap_or_else(|e| e.into_inner());
+        let series = kalshi_series.to_uppercase();
+        links
+            .iter()
+            .find(|(_, s)| **s == series)
+            .map(|(symbol, _)| symbol.clone())
+    }
+}
+
+/// Parses `SYMBOL=PRICE:QUANTITY` pairs separated by commas, e.g.
+/// `BTCUSDT=2:5,KXETH15M=2:0`.
+pub fn parse_overrides(spec: &str) -> Result<Vec<(String, Precision)>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (symbol, precision) = entry.split_once('=').ok_or_else(|| {
+                Error::Config(format!("Invalid INSTRUMENT_PRECISION entry '{}', expected SYMBOL=PRICE:QUANTITY", entry))
+            })?;
+            Ok((symbol.trim().to_uppercase(), precision.parse()?))
+        })
+        .collect()
+}
+
+/// Parses `BINANCE_SYMBOL=KALSHI_SERIES` pairs separated by commas, e.g.
+/// `BTCUSDT=KXBTC15M,ETHUSDT=KXETH15M`. A Binance symbol may map to one
+/// series only.
+pub fn parse_symbol_map(spec: &str) -> Result<Vec<(String, String)>> {
+    let mut map: Vec<(String, String)> = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let (symbol, series) = entry.split_once('=').ok_or_else(|| {
+            Error::Config(format!("Invalid SYMBOL_MAP entry '{}', expected BINANCE_SYMBOL=KALSHI_SERIES", entry))
+        })?;
+        let (symbol, series) = (symbol.trim().to_uppercase(), series.trim().to_uppercase());
+        if map.iter().any(|(s, _)| *s == symbol) {
+            return Err(Error::Config(format!("SYMBOL_MAP maps {} more than once", symbol)));
+        }
+        map.push((symbol, series));
+    }
+    Ok(map)
+}
+
+static REGISTRY: OnceLock<InstrumentRegistry> = OnceLock::new();
+
+pub fn registry() -> &'static InstrumentRegistry {
+    REGISTRY.get_or_init(InstrumentRegistry::new)
+}
@@ -0,0 +1,283 @@
+//! Cross-venue latency races.
+//!
+//! A Binance mid moving at least `move_bps` within `window_ms` starts a
+//! race on that symbol. Every other venue quoting the same underlying is
+//! then timed until its quote reflects the move: spot books of the pair
+//! must move the same way, Kalshi markets of the linked series just have to
+//! reprice, since strikes either side of spot move in opposite directions.
+//! Lags go to a histogram per venue pair and, with a database, to
+//! `latency_races`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use crate::config::{LatencyRaceConfig, Persistence};
+use crate::db::main::{Db, LatencyRaceRecord};
+use crate::db::sampler::Sampler;
+use crate::exchanges::event::{Exchange, MarketEvent, QuoteEvent};
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::sinks::SINK_CHANNEL_BUFFER;
+use crate::status;
+
+const LEADER: Exchange = Exchange::Binance;
+const RECORD_CHANNEL_BUFFER: usize = 1024;
+
+struct Race {
+    started_at: DateTime<Utc>,
+    up: bool,
+    move_bps: f64,
+    /// Follower mids when the race started, per venue and instrument
+    baselines: HashMap<Exchange, HashMap<String, f64>>,
+}
+
+pub struct LatencyRace {
+    config: LatencyRaceConfig,
+    /// Recent leader mids per symbol, inside the move window
+    history: HashMap<String, VecDeque<(DateTime<Utc>, f64)>>,
+    /// Latest follower mids per underlying symbol, venue and instrument
+    followers: HashMap<String, HashMap<Exchange, HashMap<String, f64>>>,
+    races: HashMap<String, Race>,
+    record_tx: Option<mpsc::Sender<LatencyRaceRecord>>,
+}
+
+impl LatencyRace {
+    /// Starts the race tracker; feed it every venue's events like a sink.
+    /// Races are stored when `db` is set, subject to `persistence`.
+    pub fn spawn(
+        config: LatencyRaceConfig,
+        db: Option<Arc<Db>>,
+        persistence: Persistence,
+    ) -> mpsc::Sender<MarketEvent> {
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue("latency_race", &tx);
+
+        let record_tx = db.filter(|_| persistence != Persistence::Off).map(|db| {
+            let (record_tx, mut record_rx) = mpsc::channel::<LatencyRaceRecord>(RECORD_CHANNEL_BUFFER);
+            let mut sampler = Sampler::new("latency_races", persistence);
+            tokio::spawn(async move {
+                while let Some(record) = record_rx.recv().await {
+                    let key = format!("{}:{}", record.symbol, record.follower);
+                    if !sampler.admit(&key, record.started_at) {
+                        continue;
+                    }
+                    match db.insert_latency_race(&record).await {
+                        Ok(()) => metrics::inc(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "latency_races")]),
+                        Err(e) => {
+                            error!("Failed to store latency race on {}: {}", record.symbol, e);
+                            metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", "latency_races")]);
+                            notify::publish(Notification::DbFailure {
+                                table: "latency_races".to_string(),
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                }
+            });
+            record_tx
+        });
+
+        let race = Self {
+            config,
+            history: HashMap::new(),
+            followers: HashMap::new(),
+            races: HashMap::new(),
+            record_tx,
+        };
+        tokio::spawn(race.run(rx));
+        tx
+    }
+
+    async fn run(mut self, mut rx: mpsc::Receiver<MarketEvent>) {
+        info!(
+            "🏁 Latency race started: {} bps within {}ms",
+            self.config.move_bps, self.config.window_ms
+        );
+        let mut expiry = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Some(MarketEvent::Quote(quote)) => self.on_quote(&quote),
+                    Some(_) => {}
+                    None => break,
+                },
+                _ = expiry.tick() => self.expire(Utc::now()),
+            }
+        }
+    }
+
+    fn on_quote(&mut self, quote: &QuoteEvent) {
+        if quote.bid <= 0.0 || quote.ask <= 0.0 {
+            return;
+        }
+        let mid = (quote.bid + quote.ask) / 2.0;
+        let meta = &quote.meta;
+        if meta.exchange == LEADER {
+            self.on_leader(&meta.instrument, meta.timestamp, mid);
+            return;
+        }
+
+        let symbol = match &meta.series {
+            Some(series) => match instruments::registry().binance_symbol(series) {
+                Some(symbol) => symbol,
+                None => return,
+            },
+            None => normalize(&meta.instrument),
+        };
+        self.followers
+            .entry(symbol.clone())
+            .or_default()
+            .entry(meta.exchange)
+            .or_default()
+            .insert(meta.instrument.clone(), mid);
+
+        let Some(race) = self.races.get_mut(&symbol) else {
+            return;
+        };
+        let Some(baseline) = race.baselines.get(&meta.exchange).and_then(|b| b.get(&meta.instrument)) else {
+            return;
+        };
+        let reflected = if meta.series.is_some() {
+            mid != *baseline
+        } else if race.up {
+            mid > *baseline
+        } else {
+            mid < *baseline
+        };
+        if !reflected {
+            return;
+        }
+
+        let lag = meta.timestamp - race.started_at;
+        race.baselines.remove(&meta.exchange);
+        let record = LatencyRaceRecord {
+            leader: LEADER.to_string(),
+            follower: meta.exchange.to_string(),
+            symbol: symbol.clone(),
+            instrument: Some(meta.instrument.clone()),
+            started_at: race.started_at,
+            move_bps: race.move_bps,
+            lag_ms: Some(lag.num_milliseconds()),
+        };
+        if race.baselines.is_empty() {
+            self.races.remove(&symbol);
+        }
+        self.finish(record);
+    }
+
+    fn on_leader(&mut self, symbol: &str, time: DateTime<Utc>, mid: f64) {
+        let history = self.history.entry(symbol.to_string()).or_default();
+        history.push_back((time, mid));
+        let cutoff = time - chrono::Duration::milliseconds(self.config.window_ms as i64);
+        while history.front().is_some_and(|(t, _)| *t < cutoff) {
+            history.pop_front();
+        }
+        if self.races.contains_key(symbol) {
+            return;
+        }
+
+        let Some(&(_, from)) = history.front() else {
+            return;
+        };
+        let move_bps = (mid - from) / from * 10_000.0;
+        if move_bps.abs() < self.config.move_bps {
+            return;
+        }
+        history.clear();
+
+        let baselines = self.followers.get(symbol).cloned().unwrap_or_default();
+        if baselines.is_empty() {
+            return;
+        }
+        info!(
+            "🏁 {} moved {:+.1} bps, timing {} venues",
+            symbol,
+            move_bps,
+            baselines.len()
+        );
+        self.races.insert(
+            symbol.to_string(),
+            Race {
+                started_at: time,
+                up: move_bps > 0.0,
+                move_bps,
+                baselines,
+            },
+        );
+    }
+
+    /// Closes races past the timeout, counting the venues still pending as
+    /// missed.
+    fn expire(&mut self, now: DateTime<Utc>) {
+        let timeout = chrono::Duration::seconds(self.config.timeout_secs as i64);
+        let expired: Vec<String> = self
+            .races
+            .iter()
+            .filter(|(_, race)| now - race.started_at > timeout)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        for symbol in expired {
+            let Some(race) = self.races.remove(&symbol) else {
+                continue;
+            };
+            for follower in race.baselines.keys() {
+                self.finish(LatencyRaceRecord {
+                    leader: LEADER.to_string(),
+                    follower: follower.to_string(),
+                    symbol: symbol.clone(),
+                    instrument: None,
+                    started_at: race.started_at,
+                    move_bps: race.move_bps,
+                    lag_ms: None,
+                });
+            }
+        }
+    }
+
+    fn finish(&self, record: LatencyRaceRecord) {
+        let outcome = match record.lag_ms {
+            Some(lag_ms) => {
+                metrics::observe(
+                    names::CROSS_VENUE_LAG_SECONDS,
+                    &[("leader", record.leader.as_str()), ("follower", record.follower.as_str())],
+                    lag_ms.max(0) as f64 / 1000.0,
+                );
+                info!(
+                    "🏁 {} followed {} on {} after {}ms",
+                    record.follower, record.leader, record.symbol, lag_ms
+                );
+                "reflected"
+            }
+            None => "missed",
+        };
+        metrics::inc(
+            names::LATENCY_RACES_TOTAL,
+            &[
+                ("leader", record.leader.as_str()),
+                ("follower", record.follower.as_str()),
+                ("outcome", outcome),
+            ],
+        );
+        if let Some(tx) = &self.record_tx {
+            if let Err(e) = tx.try_send(record) {
+                error!("Failed to queue latency race record: {}", e);
+            }
+        }
+    }
+}
+
+/// Pair name without separators, e.g. `BTC-USDT` -> `BTCUSDT`, to match
+/// other venues' spot books to the Binance symbol.
+pub(crate) fn normalize(instrument: &str) -> String {
+    instrument
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
@@ -0,0 +1,124 @@
+//! Optional lease-based leader election for running two instances side by side.
+//!
+//! Every instance keeps its feeds connected, but only the current leader
+//! persists, alerts and trades. Leadership is a row in `leader_lease` that the
+//! leader renews every `renew_interval_secs`; if it stops renewing for
+//! `lease_secs`, any follower can take it. On shutdown the leader releases
+//! the lease so a follower takes over on its next renewal instead of
+//! waiting out `lease_secs`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tracing::{error, info, warn};
+
+use crate::config::LeaderConfig;
+use crate::db::main::Db;
+use crate::metrics::{self, names};
+use crate::shutdown;
+
+/// Cheap cloneable view of whether this instance currently leads.
+#[derive(Clone)]
+pub struct LeaderHandle {
+    is_leader: Arc<AtomicBool>,
+}
+
+impl LeaderHandle {
+    /// For single-instance deployments: always the leader.
+    pub fn always() -> Self {
+        Self {
+            is_leader: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.is_leader.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for LeaderHandle {
+    fn default() -> Self {
+        Self::always()
+    }
+}
+
+pub struct LeaderElector {
+    config: LeaderConfig,
+    db: Arc<Db>,
+    is_leader: Arc<AtomicBool>,
+}
+
+impl LeaderElector {
+    /// Creates the lease table if needed and starts campaigning. The handle
+    /// starts as a follower until the first successful acquisition.
+    pub async fn spawn(config: &LeaderConfig, db: Arc<Db>) -> crate::error::Result<LeaderHandle> {
+        db.create_leader_lease_table().await?;
+
+        let is_leader = Arc::new(AtomicBool::new(false));
+        let elector = Self {
+            config: config.clone(),
+            db,
+            is_leader: is_leader.clone(),
+        };
+        info!(
+            "🗳️ Leader election enabled: instance {} on lease {}",
+            config.instance_id, config.lease_name
+        );
+        metrics::set(names::LEADER, &[], 0.0);
+        shutdown::coordinator().track("leader elector", tokio::spawn(elector.run()));
+        Ok(LeaderHandle { is_leader })
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.renew_interval_secs));
+        let lease = Duration::from_secs(self.config.lease_secs);
+        let mut last_renewed: Option<Instant> = None;
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown::requested(&mut shutdown_rx) => break,
+            }
+
+            let leading = match self
+                .db
+                .try_acquire_lease(&self.config.lease_name, &self.config.instance_id, self.config.lease_secs)
+                .await
+            {
+                Ok(true) => {
+                    last_renewed = Some(Instant::now());
+                    true
+                }
+                Ok(false) => false,
+                Err(e) => {
+                    error!("Leader lease renewal failed: {}", e);
+                    // Keep leading only while the last renewal is still valid,
+                    // so two leaders never overlap.
+                    self.is_leader.load(Ordering::Relaxed)
+                        && last_renewed.is_some_and(|at| at.elapsed() < lease)
+                }
+            };
+
+            let was_leading = self.is_leader.swap(leading, Ordering::Relaxed);
+            if leading != was_leading {
+                if leading {
+                    info!("👑 Instance {} became leader", self.config.instance_id);
+                } else {
+                    warn!("🪑 Instance {} is now a follower", self.config.instance_id);
+                }
+                metrics::set(names::LEADER, &[], if leading { 1.0 } else { 0.0 });
+            }
+        }
+
+        // Writers still draining keep the handle as it is; the follower only
+        // campaigns again on its next renewal.
+        if self.is_leader.load(Ordering::Relaxed) {
+            match self.db.release_lease(&self.config.lease_name, &self.config.instance_id).await {
+                Ok(()) => info!("🏳️ Instance {} released the leader lease", self.config.instance_id),
+                Err(e) => warn!("Failed to release the leader lease: {}", e.chain()),
+            }
+        }
+    }
+}
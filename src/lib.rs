@@ -1,12 +1,41 @@
 pub mod app;
+#[cfg(feature = "archive")]
+pub mod archive;
 pub mod backtest;
+pub mod calendar;
 pub mod config;
 pub mod constants;
 pub mod db;
+pub mod encryption;
 pub mod error;
 pub mod exchanges;
+pub mod execution;
+pub mod fixtures;
+pub mod heartbeat;
+pub mod instruments;
+pub mod latency_race;
+pub mod leader;
 pub mod logging;
+pub mod metrics;
+pub mod monitor;
+pub mod notify;
+pub mod portfolio;
+pub mod recorder;
+pub mod reload;
+pub mod replay;
+pub mod resample;
+pub mod rules;
+pub mod self_test;
+pub mod server;
+pub mod shutdown;
+pub mod signals;
+pub mod sinks;
 pub mod state;
+pub mod status;
+pub mod synthetic;
+pub mod systemd;
+pub mod tags;
+pub mod timezone;
 pub mod trader;
 pub mod utils;
 
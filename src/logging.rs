@@ -1,16 +1,359 @@
-use tracing::Level;
-use tracing_subscriber::FmtSubscriber;
+use std::fmt;
+use std::str::FromStr;
 
-pub fn init() {
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .with_target(false)
+use tracing::{Level, Subscriber};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::config::Config;
+
+/// Keeps the Sentry client and the log file writer alive; events still queued
+/// are flushed when it drops, so hold it for the life of the process.
+pub struct LogGuard {
+    #[cfg(feature = "sentry")]
+    _sentry: Option<sentry::ClientInitGuard>,
+    _file: Option<WorkerGuard>,
+}
+
+/// How log lines are written, from `LOG_FORMAT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event, for log shippers such as Loki or
+    /// Elasticsearch
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" | "pretty" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!("unknown log format {:?}, expected text or json", other)),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        })
+    }
+}
+
+/// Most verbose level logged, from `LOG_LEVEL` (`error` to `trace`);
+/// `info` when unset or unrecognised.
+pub fn max_level() -> Level {
+    crate::config::var("LOG_LEVEL")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(Level::INFO)
+}
+
+/// Output format from `LOG_FORMAT`; text when unset or unrecognised.
+pub fn format() -> LogFormat {
+    crate::config::var("LOG_FORMAT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Per-module filter from `RUST_LOG` (e.g. `info,white_shark::exchanges=debug`),
+/// falling back to [`max_level`] for everything not listed.
+pub fn filter() -> EnvFilter {
+    EnvFilter::builder()
+        .with_default_directive(LevelFilter::from_level(max_level()).into())
+        .from_env_lossy()
+}
+
+/// A layer writing to `writer` in the configured [`format`].
+fn fmt_layer<S, W>(writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi)
         .with_thread_ids(false)
         .with_file(false)
-        .with_line_number(false)
-        .finish();
-    
+        .with_line_number(false);
+    match format() {
+        LogFormat::Text => layer.with_target(false).boxed(),
+        // Targets stay in JSON so shipped logs can be filtered by module.
+        LogFormat::Json => layer.json().flatten_event(true).with_current_span(false).boxed(),
+    }
+}
+
+pub fn init() {
+    let subscriber = tracing_subscriber::registry()
+        .with(filter())
+        .with(fmt_layer(std::io::stdout, true));
+
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("Failed to set tracing subscriber");
+}
+
+/// Like [`init`], additionally writing rolling log files when `log_file` is
+/// set, and reporting error-level events and panics to Sentry when `sentry`
+/// is set and the crate is built with the `sentry` feature.
+pub fn init_with_config(config: &Config) -> LogGuard {
+    let (file, file_guard, file_error) = match config.log_file.as_ref().map(rolling::writer) {
+        Some(Ok((writer, guard))) => (Some(writer), Some(guard), None),
+        Some(Err(e)) => (None, None, Some(e)),
+        None => (None, None, None),
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(filter())
+        .with(fmt_layer(std::io::stdout, true))
+        .with(file.map(|writer: NonBlocking| fmt_layer(writer, false)));
+    #[cfg(feature = "sentry")]
+    let sentry = config.sentry.as_ref().map(sentry_reporting::init);
+    #[cfg(feature = "sentry")]
+    let subscriber = subscriber.with(sentry.is_some().then(sentry_reporting::layer));
     tracing::subscriber::set_global_default(subscriber)
         .expect("Failed to set tracing subscriber");
+
+    if let Some(log_file) = &config.log_file {
+        match file_error {
+            Some(e) => tracing::warn!("Failed to open log files in {}, logging to stdout only: {}", log_file.dir, e),
+            None => tracing::info!("📝 Writing log files to {}", log_file.dir),
+        }
+    }
+    #[cfg(feature = "sentry")]
+    if sentry.is_some() {
+        tracing::info!("🛰️ Reporting errors to Sentry");
+    }
+    #[cfg(not(feature = "sentry"))]
+    if config.sentry.is_some() {
+        tracing::warn!("SENTRY_DSN is set but this build has no `sentry` feature, not reporting errors");
+    }
+
+    LogGuard {
+        #[cfg(feature = "sentry")]
+        _sentry: sentry,
+        _file: file_guard,
+    }
+}
+
+/// Rolling log files: time-based through `tracing-appender`, size-based
+/// through [`SizeRollingFile`](rolling::SizeRollingFile).
+mod rolling {
+    use std::fs::{self, File, OpenOptions};
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+
+    use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+    use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+    use crate::config::{LogFileConfig, LogRotation};
+
+    /// A non-blocking writer to the configured files; lines still buffered
+    /// are written when the guard drops.
+    pub fn writer(config: &LogFileConfig) -> io::Result<(NonBlocking, WorkerGuard)> {
+        fs::create_dir_all(&config.dir)?;
+        let rotation = match config.rotation {
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Size(max_bytes) => {
+                let file = SizeRollingFile::open(config, max_bytes)?;
+                return Ok(tracing_appender::non_blocking(file));
+            }
+        };
+        let appender = RollingFileAppender::builder()
+            .rotation(rotation)
+            .filename_prefix(&config.prefix)
+            .filename_suffix("log")
+            // The limit counts the file being written too.
+            .max_log_files(config.max_files + 1)
+            .build(&config.dir)
+            .map_err(io::Error::other)?;
+        Ok(tracing_appender::non_blocking(appender))
+    }
+
+    /// `<prefix>.log`, moved to `<prefix>.log.1` once it would grow past
+    /// `max_bytes`, shifting older files up to `<prefix>.log.<max_files>`.
+    pub struct SizeRollingFile {
+        path: PathBuf,
+        max_bytes: u64,
+        max_files: usize,
+        file: File,
+        written: u64,
+    }
+
+    impl SizeRollingFile {
+        fn open(config: &LogFileConfig, max_bytes: u64) -> io::Result<Self> {
+            let path = Path::new(&config.dir).join(format!("{}.log", config.prefix));
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let written = file.metadata()?.len();
+            Ok(Self {
+                path,
+                max_bytes,
+                max_files: config.max_files,
+                file,
+                written,
+            })
+        }
+
+        fn rotated(&self, n: usize) -> PathBuf {
+            let mut path = self.path.clone().into_os_string();
+            path.push(format!(".{}", n));
+            path.into()
+        }
+
+        fn roll(&mut self) -> io::Result<()> {
+            self.file.flush()?;
+            if self.max_files == 0 {
+                self.file = OpenOptions::new().write(true).truncate(true).open(&self.path)?;
+            } else {
+                let _ = fs::remove_file(self.rotated(self.max_files));
+                for n in (1..self.max_files).rev() {
+                    let from = self.rotated(n);
+                    if from.exists() {
+                        fs::rename(&from, self.rotated(n + 1))?;
+                    }
+                }
+                fs::rename(&self.path, self.rotated(1))?;
+                self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+            }
+            self.written = 0;
+            Ok(())
+        }
+    }
+
+    impl Write for SizeRollingFile {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+                self.roll()?;
+            }
+            let n = self.file.write(buf)?;
+            self.written += n as u64;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.file.flush()
+        }
+    }
 }
 
+#[cfg(feature = "sentry")]
+mod sentry_reporting {
+    use std::collections::BTreeMap;
+    use std::fmt;
+
+    use sentry_tracing::{EventFilter, EventMapping, SentryLayer};
+    use tracing::field::{Field, Visit};
+    use tracing::{Level, Subscriber};
+    use tracing_subscriber::fmt::format::DefaultFields;
+    use tracing_subscriber::fmt::FormattedFields;
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::registry::LookupSpan;
+
+    use crate::config::SentryConfig;
+
+    /// Event fields promoted to Sentry tags so issues can be filtered by them.
+    const TAG_FIELDS: &[&str] = &["exchange", "symbol", "event_id"];
+
+    pub fn init(config: &SentryConfig) -> sentry::ClientInitGuard {
+        sentry::init((
+            config.dsn.clone(),
+            sentry::ClientOptions {
+                release: sentry::release_name!(),
+                environment: config.environment.clone().map(Into::into),
+                attach_stacktrace: true,
+                ..Default::default()
+            },
+        ))
+    }
+
+    pub fn layer<S>() -> SentryLayer<S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        sentry_tracing::layer()
+            .event_filter(|metadata| match *metadata.level() {
+                Level::ERROR => EventFilter::Event,
+                Level::WARN | Level::INFO => EventFilter::Breadcrumb,
+                _ => EventFilter::Ignore,
+            })
+            .event_mapper(map_event)
+    }
+
+    fn map_event<S>(event: &tracing::Event<'_>, ctx: Context<'_, S>) -> EventMapping
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        if *event.metadata().level() != Level::ERROR {
+            return EventMapping::Breadcrumb(sentry_tracing::breadcrumb_from_event(event));
+        }
+
+        let mut visitor = TagVisitor::default();
+        event.record(&mut visitor);
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope {
+                if let Some(fields) = span.extensions().get::<FormattedFields<DefaultFields>>() {
+                    visitor.record_message(fields);
+                }
+            }
+        }
+
+        let mut sentry_event = sentry_tracing::event_from_event(event, ctx);
+        // Errors wrapped in an `ErrorContext` carry `exchange=..`/`symbol=..`
+        // in their message even when the log call has no structured fields.
+        if let Some(message) = &sentry_event.message {
+            visitor.record_message(message);
+        }
+        sentry_event.tags.extend(visitor.tags);
+        let tags = crate::tags::current();
+        sentry_event.tags.insert("account".into(), tags.account.clone());
+        sentry_event.tags.insert("strategy".into(), tags.strategy.clone());
+        EventMapping::Event(sentry_event)
+    }
+
+    #[derive(Default)]
+    struct TagVisitor {
+        tags: BTreeMap<String, String>,
+    }
+
+    impl TagVisitor {
+        fn insert(&mut self, name: &str, value: String) {
+            if TAG_FIELDS.contains(&name) {
+                self.tags.entry(name.to_string()).or_insert(value);
+            }
+        }
+
+        /// Picks `key=value` pairs out of free text, stopping at `,`, `)` or whitespace.
+        fn record_message(&mut self, message: &str) {
+            for &name in TAG_FIELDS {
+                let needle = format!("{}=", name);
+                if let Some(start) = message.find(&needle) {
+                    let rest = &message[start + needle.len()..];
+                    let end = rest.find([',', ')', ' ']).unwrap_or(rest.len());
+                    self.insert(name, rest[..end].trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+
+    impl Visit for TagVisitor {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.insert(field.name(), format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
@@ -1,13 +1,378 @@
+use std::path::Path;
+#[cfg(feature = "db")]
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use tracing::{error, info, warn, Level};
+
 use white_shark::app::run;
-use white_shark::config::Config;
-use white_shark::error::Result;
-use white_shark::logging::init;
+use white_shark::config::{self, Config};
+#[cfg(feature = "db")]
+use white_shark::db::main::Db;
+#[cfg(feature = "db")]
+use white_shark::db::maintenance::Maintenance;
+use white_shark::encryption;
+use white_shark::error::{Error, Result};
+#[cfg(feature = "db")]
+use white_shark::exchanges::kalshi::auth::KalshiAuth;
+#[cfg(feature = "db")]
+use white_shark::exchanges::kalshi::backfill::TradeBackfill;
+#[cfg(feature = "db")]
+use white_shark::exchanges::kalshi::KalshiApi;
+use white_shark::logging::{init, init_with_config, LogFormat};
+use white_shark::recorder::verify;
+use white_shark::replay::{self, ReplayOptions};
+use white_shark::self_test::{self, SelfTestConfig};
+use white_shark::status::StatusReport;
+
+const DEFAULT_ADMIN_ADDR: &str = "127.0.0.1:9100";
+
+/// Kalshi and Binance market data and trading client.
+///
+/// Everything is configured through env vars (see `Config::from_env`), an
+/// optional TOML file, and the flags below, which win over both.
+#[derive(Parser)]
+#[command(name = "white-shark", version)]
+struct Cli {
+    /// TOML config file, falling back to `CONFIG_FILE`
+    #[arg(long, global = true)]
+    config: Option<String>,
+
+    /// Kalshi series to track, overriding `KALSHI_TRACKED_SYMBOLS`; filters
+    /// the frames played back by `replay`
+    #[arg(long, global = true, value_delimiter = ',')]
+    symbols: Vec<String>,
+
+    /// Stream Binance SBE market data alongside Kalshi (`BINANCE_SBE`)
+    #[arg(long, global = true)]
+    sbe: bool,
+
+    /// Log the evaluated values behind every alert rule that fires
+    /// (`ALERT_RULES_EXPLAIN`)
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// error, warn, info, debug or trace (`LOG_LEVEL`)
+    #[arg(long, global = true)]
+    log_level: Option<Level>,
+
+    /// text or json (`LOG_FORMAT`); per-module filters go in `RUST_LOG`
+    #[arg(long, global = true)]
+    log_format: Option<LogFormat>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Stream market data and trade (the default)
+    Run,
+    /// Like `run`, also recording raw frames to `DIR`
+    Record {
+        /// Directory receiving hourly `.wsrec` files (`RECORDER_DIR`)
+        dir: String,
+        /// Also journal normalized market events
+        #[arg(long)]
+        events: bool,
+    },
+    /// Play a recording back to the log
+    Replay {
+        dir: String,
+        #[arg(long, value_parser = parse_time)]
+        from: Option<i64>,
+        #[arg(long, value_parser = parse_time)]
+        to: Option<i64>,
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+        /// Jump over recording gaps longer than this many milliseconds
+        #[arg(long)]
+        skip_gaps: Option<u64>,
+        /// Serve the replay control API on this address
+        #[arg(long)]
+        admin: Option<String>,
+    },
+    /// Check recordings for corrupt chunks, out-of-order timestamps and
+    /// stale indexes
+    Verify {
+        /// Recording files or directories
+        #[arg(required = true)]
+        paths: Vec<String>,
+    },
+    /// Print a report sealed with `ENCRYPTION_KEY`, or write it to `--out`
+    Decrypt {
+        path: String,
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Create the database tables at `DATABASE_URL`
+    #[cfg(feature = "db")]
+    InitDb,
+    /// Roll up and prune old ticks once, as the scheduled maintenance does
+    /// with `DATABASE_RAW_RETENTION_DAYS`
+    #[cfg(feature = "db")]
+    DbMaintenance {
+        /// Report what would be done without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Store Kalshi trade history in `kalshi_trades`
+    #[cfg(feature = "db")]
+    BackfillTrades {
+        /// Market or series tickers, defaulting to `--symbols`, then
+        /// `KALSHI_TRACKED_SYMBOLS`
+        tickers: Vec<String>,
+        #[arg(long, value_parser = parse_time)]
+        from: Option<i64>,
+        #[arg(long, value_parser = parse_time)]
+        to: Option<i64>,
+    },
+    /// Run the startup self-test and exit
+    SelfTest,
+    /// Print a running instance's status
+    Status {
+        /// Admin address, defaulting to `SERVER_BIND_ADDR`, then 127.0.0.1:9100
+        addr: Option<String>,
+    },
+}
+
+fn parse_time(value: &str) -> std::result::Result<i64, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc).timestamp_micros())
+        .map_err(|e| format!("invalid RFC3339 time: {}", e))
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    init();
+    let cli = Cli::parse();
 
-    let config = Config::from_env()?;
+    // Flags are applied as overrides of the env vars they stand for, so
+    // they win over both the environment and the config file.
+    dotenv::dotenv().ok();
+    let config_file = cli.config.clone().or_else(|| config::var("CONFIG_FILE").ok());
+    if let Some(path) = &config_file {
+        Config::apply_file(path)?;
+    }
+    if !cli.symbols.is_empty() {
+        Config::override_var("KALSHI_TRACKED_SYMBOLS", &cli.symbols.join(","));
+    }
+    if cli.sbe {
+        Config::override_var("BINANCE_SBE", "true");
+    }
+    if cli.explain {
+        Config::override_var("ALERT_RULES_EXPLAIN", "true");
+    }
+    if let Some(level) = cli.log_level {
+        Config::override_var("LOG_LEVEL", &level.to_string());
+    }
+    if let Some(format) = cli.log_format {
+        Config::override_var("LOG_FORMAT", &format.to_string());
+    }
+
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => start(config_file).await,
+        Command::Record { dir, events } => {
+            Config::override_var("RECORDER_DIR", &dir);
+            if events {
+                Config::override_var("RECORDER_EVENTS", "true");
+            }
+            start(config_file).await
+        }
+        Command::Replay {
+            dir,
+            from,
+            to,
+            speed,
+            skip_gaps,
+            admin,
+        } => {
+            init();
+            let options = ReplayOptions {
+                dir,
+                from_micros: from,
+                to_micros: to,
+                speed,
+                skip_gaps_ms: skip_gaps,
+                symbols: cli.symbols,
+            };
+            replay::cli::run(options, admin).await
+        }
+        Command::Verify { paths } => verify_recordings(paths),
+        Command::Decrypt { path, out } => decrypt(&path, out.as_deref()),
+        #[cfg(feature = "db")]
+        Command::InitDb => init_db().await,
+        #[cfg(feature = "db")]
+        Command::DbMaintenance { dry_run } => {
+            if dry_run {
+                Config::override_var("DATABASE_MAINTENANCE_DRY_RUN", "true");
+            }
+            db_maintenance(config_file).await
+        }
+        #[cfg(feature = "db")]
+        Command::BackfillTrades { tickers, from, to } => {
+            backfill_trades(config_file, tickers, from, to).await
+        }
+        Command::SelfTest => self_test(config_file).await,
+        Command::Status { addr } => status(addr).await,
+    }
+}
+
+async fn start(config_file: Option<String>) -> Result<()> {
+    let config = match config_file {
+        Some(path) => Config::from_file(&path)?,
+        None => Config::from_env()?,
+    };
+    let _logging = init_with_config(&config);
 
     run(config).await
 }
+
+async fn self_test(config_file: Option<String>) -> Result<()> {
+    let config = match config_file {
+        Some(path) => Config::from_file(&path)?,
+        None => Config::from_env()?,
+    };
+    let _logging = init_with_config(&config);
+
+    let self_test_config = SelfTestConfig {
+        allow_failures: false,
+        ..config.self_test.unwrap_or_default()
+    };
+    self_test::run(&config, self_test_config).await?;
+    Ok(())
+}
+
+fn verify_recordings(paths: Vec<String>) -> Result<()> {
+    init();
+    let mut failed = 0;
+    let mut checked = 0;
+    for path in &paths {
+        for file in verify::targets(Path::new(path))? {
+            let report = verify::verify(&file);
+            checked += 1;
+            if report.is_ok() {
+                info!(
+                    "✅ {}: {} chunks, {} records",
+                    file.display(),
+                    report.chunks,
+                    report.records
+                );
+                if report.unchecked_chunks > 0 {
+                    warn!(
+                        "{} predates chunk checksums; {} chunks were only decoded",
+                        file.display(),
+                        report.unchecked_chunks
+                    );
+                }
+            } else {
+                failed += 1;
+                for problem in &report.problems {
+                    error!("❌ {}: {}", file.display(), problem);
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Recording(format!(
+            "{} of {} recordings failed verification",
+            failed, checked
+        )));
+    }
+    info!("All {} recordings verified", checked);
+    Ok(())
+}
+
+fn decrypt(path: &str, out: Option<&str>) -> Result<()> {
+    init();
+    let plain = encryption::read_file(path)?;
+    match out {
+        Some(out) => {
+            std::fs::write(out, &plain)?;
+            info!("🔓 Decrypted {} to {}", path, out);
+        }
+        None => std::io::Write::write_all(&mut std::io::stdout(), &plain)?,
+    }
+    Ok(())
+}
+
+#[cfg(feature = "db")]
+async fn init_db() -> Result<()> {
+    init();
+    let url = config::var("DATABASE_URL").map_err(|_| Error::Config("DATABASE_URL not set".into()))?;
+    let partition_ahead_days = config::MaintenanceConfig::from_env()?.map_or(0, |m| m.partition_ahead_days);
+    let db = Db::new(&url).await?;
+    db.create_tables(partition_ahead_days).await?;
+    info!("🗄️ Database tables ready");
+    Ok(())
+}
+
+#[cfg(feature = "db")]
+async fn db_maintenance(config_file: Option<String>) -> Result<()> {
+    init();
+    let config = match config_file {
+        Some(path) => Config::from_file(&path)?,
+        None => Config::from_env()?,
+    };
+    let database = config
+        .database
+        .as_ref()
+        .ok_or_else(|| Error::Config("DATABASE_URL not set".into()))?;
+    let maintenance = database
+        .maintenance
+        .ok_or_else(|| Error::Config("DATABASE_RAW_RETENTION_DAYS not set".into()))?;
+
+    let db = Arc::new(Db::new(&database.url).await?);
+    db.create_market_data_1m_table().await?;
+    let report = Maintenance::new(db, maintenance).run_once(Utc::now()).await?;
+    report.log();
+    Ok(())
+}
+
+#[cfg(feature = "db")]
+async fn backfill_trades(
+    config_file: Option<String>,
+    tickers: Vec<String>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<()> {
+    init();
+    let config = match config_file {
+        Some(path) => Config::from_file(&path)?,
+        None => Config::from_env()?,
+    };
+    let database = config
+        .database
+        .as_ref()
+        .ok_or_else(|| Error::Config("DATABASE_URL not set".into()))?;
+    let tickers = if tickers.is_empty() {
+        config.kalshi.tracked_symbols.clone()
+    } else {
+        tickers
+    };
+    if tickers.is_empty() {
+        return Err(Error::Config("No tickers to backfill".into()));
+    }
+
+    let db = Db::new(&database.url).await?;
+    db.create_kalshi_trades_table().await?;
+    let auth = Arc::new(KalshiAuth::create_auth(&config.kalshi)?);
+    let api = KalshiApi::new(auth);
+    TradeBackfill::new(&api, &db)
+        .with_range(from.and_then(DateTime::from_timestamp_micros), to.and_then(DateTime::from_timestamp_micros))
+        .run(&tickers)
+        .await?;
+    Ok(())
+}
+
+/// `white-shark status [ADDR]`: prints a running instance's status.
+async fn status(addr: Option<String>) -> Result<()> {
+    let addr = addr
+        .or_else(|| config::var("SERVER_BIND_ADDR").ok())
+        .unwrap_or_else(|| DEFAULT_ADMIN_ADDR.to_string());
+
+    let report = StatusReport::fetch(&addr).await?;
+    print!("{}", report.render());
+    Ok(())
+}
@@ -0,0 +1,25 @@
+pub mod names;
+pub mod push;
+pub mod registry;
+
+pub use push::MetricsPusher;
+pub use registry::{registry, Registry, Sample};
+
+/// Grafana dashboard provisioned against the names in [`names`].
+pub const DASHBOARD_JSON: &str = include_str!("dashboard.json");
+
+pub fn inc(name: &'static str, labels: &[(&'static str, &str)]) {
+    registry().add_counter(name, labels, 1);
+}
+
+pub fn add(name: &'static str, labels: &[(&'static str, &str)], value: u64) {
+    registry().add_counter(name, labels, value);
+}
+
+pub fn set(name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+    registry().set_gauge(name, labels, value);
+}
+
+pub fn observe(name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+    registry().observe(name, labels, value);
+}
@@ -0,0 +1,398 @@
+//! Stable metric namespace. Every exported series is prefixed with `white_shark_`
+//! and grouped by area so dashboards can be built against fixed names.
+
+pub struct MetricDesc {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+// Connectivity
+pub const WS_CONNECTED: &str = "white_shark_ws_connected";
+pub const WS_RECONNECTS_TOTAL: &str = "white_shark_ws_reconnects_total";
+pub const WS_CLOSES_TOTAL: &str = "white_shark_ws_closes_total";
+pub const WS_MESSAGES_TOTAL: &str = "white_shark_ws_messages_total";
+pub const WS_LAST_MESSAGE_TIMESTAMP: &str = "white_shark_ws_last_message_timestamp_seconds";
+pub const DUPLICATES_DROPPED_TOTAL: &str = "white_shark_duplicates_dropped_total";
+pub const DATA_QUALITY_ERRORS_TOTAL: &str = "white_shark_data_quality_errors_total";
+pub const SUBSCRIPTION_REQUESTS_TOTAL: &str = "white_shark_subscription_requests_total";
+pub const SUBSCRIPTION_DRIFT_TOTAL: &str = "white_shark_subscription_drift_total";
+
+// Latency
+pub const FEED_LATENCY_SECONDS: &str = "white_shark_feed_latency_seconds";
+pub const ORDER_LATENCY_SECONDS: &str = "white_shark_order_latency_seconds";
+pub const CROSS_VENUE_LAG_SECONDS: &str = "white_shark_cross_venue_lag_seconds";
+pub const LATENCY_RACES_TOTAL: &str = "white_shark_latency_races_total";
+
+// Signal
+pub const BINANCE_DEPTH_IMBALANCE: &str = "white_shark_binance_depth_imbalance";
+pub const KALSHI_TOP_OF_BOOK: &str = "white_shark_kalshi_top_of_book";
+pub const IMBALANCE_ALERTS_TOTAL: &str = "white_shark_imbalance_alerts_total";
+pub const ALERTS_SUPPRESSED_TOTAL: &str = "white_shark_alerts_suppressed_total";
+pub const BINANCE_DEPTH_VELOCITY: &str = "white_shark_binance_depth_velocity";
+pub const DEPTH_VELOCITY_ALERTS_TOTAL: &str = "white_shark_depth_velocity_alerts_total";
+pub const BINANCE_BOOK_RESYNCS_TOTAL: &str = "white_shark_binance_book_resyncs_total";
+pub const BINANCE_ICEBERG_LEVELS: &str = "white_shark_binance_iceberg_levels";
+pub const ICEBERG_ALERTS_TOTAL: &str = "white_shark_iceberg_alerts_total";
+pub const RULE_ALERTS_TOTAL: &str = "white_shark_rule_alerts_total";
+pub const SIGNAL_ALERTS_TOTAL: &str = "white_shark_signal_alerts_total";
+
+// Execution
+pub const ORDERS_TOTAL: &str = "white_shark_orders_total";
+pub const ORDER_ERRORS_TOTAL: &str = "white_shark_order_errors_total";
+pub const CANCELLED_ORDERS_TOTAL: &str = "white_shark_cancelled_orders_total";
+pub const EXECUTION_FILLS_TOTAL: &str = "white_shark_execution_fills_total";
+pub const PAPER_FILLS_TOTAL: &str = "white_shark_paper_fills_total";
+pub const RISK_REJECTIONS_TOTAL: &str = "white_shark_risk_rejections_total";
+pub const KILL_SWITCH_ENGAGED: &str = "white_shark_kill_switch_engaged";
+pub const POSITION_CONTRACTS: &str = "white_shark_position_contracts";
+pub const POSITION_REALIZED_PNL: &str = "white_shark_position_realized_pnl_dollars";
+pub const POSITION_UNREALIZED_PNL: &str = "white_shark_position_unrealized_pnl_dollars";
+pub const HEDGE_ORDERS_TOTAL: &str = "white_shark_hedge_orders_total";
+pub const HEDGE_POSITION: &str = "white_shark_hedge_position";
+pub const PORTFOLIO_NET_DELTA: &str = "white_shark_portfolio_net_delta";
+pub const PORTFOLIO_WORST_CASE_LOSS: &str = "white_shark_portfolio_worst_case_loss_dollars";
+pub const INVENTORY_CONTRACTS: &str = "white_shark_inventory_contracts";
+pub const QUOTE_FILL_PROBABILITY: &str = "white_shark_quote_fill_probability";
+pub const QUOTE_TIME_TO_FILL_SECONDS: &str = "white_shark_quote_time_to_fill_seconds";
+
+// Persistence
+pub const DB_ROWS_WRITTEN_TOTAL: &str = "white_shark_db_rows_written_total";
+pub const DB_WRITE_ERRORS_TOTAL: &str = "white_shark_db_write_errors_total";
+pub const DB_FLUSH_SECONDS: &str = "white_shark_db_flush_seconds";
+pub const DB_WRITER_QUEUE_DEPTH: &str = "white_shark_db_writer_queue_depth";
+pub const DB_ROWS_SAMPLED_OUT_TOTAL: &str = "white_shark_db_rows_sampled_out_total";
+pub const DB_ROWS_PRUNED_TOTAL: &str = "white_shark_db_rows_pruned_total";
+pub const CSV_ROWS_WRITTEN_TOTAL: &str = "white_shark_csv_rows_written_total";
+pub const CSV_WRITE_ERRORS_TOTAL: &str = "white_shark_csv_write_errors_total";
+pub const RECORDER_BYTES_WRITTEN_TOTAL: &str = "white_shark_recorder_bytes_written_total";
+pub const RECORDER_DROPPED_FRAMES_TOTAL: &str = "white_shark_recorder_dropped_frames_total";
+pub const ARCHIVE_UPLOADED_BYTES_TOTAL: &str = "white_shark_archive_uploaded_bytes_total";
+pub const ARCHIVE_FILES_TOTAL: &str = "white_shark_archive_files_total";
+
+// State
+pub const STATE_ENTRIES: &str = "white_shark_state_entries";
+pub const STATE_EVICTIONS_TOTAL: &str = "white_shark_state_evictions_total";
+
+// Cluster
+pub const LEADER: &str = "white_shark_leader";
+
+// Notifications
+pub const NOTIFICATIONS_TOTAL: &str = "white_shark_notifications_total";
+
+// Testing
+pub const CHAOS_FAULTS_TOTAL: &str = "white_shark_chaos_faults_total";
+
+pub const ALL: &[MetricDesc] = &[
+    MetricDesc {
+        name: WS_CONNECTED,
+        help: "1 while the exchange WebSocket is connected, 0 otherwise",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: WS_RECONNECTS_TOTAL,
+        help: "WebSocket reconnect attempts",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: WS_CLOSES_TOTAL,
+        help: "Server-initiated WebSocket closes, by close reason",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: WS_MESSAGES_TOTAL,
+        help: "WebSocket messages received, by message type",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: WS_LAST_MESSAGE_TIMESTAMP,
+        help: "Unix time of the last WebSocket message received",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: DUPLICATES_DROPPED_TOTAL,
+        help: "Messages dropped as replays of already seen ids, by stream",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: DATA_QUALITY_ERRORS_TOTAL,
+        help: "Payload fields rejected as malformed numbers, by exchange and field",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: SUBSCRIPTION_REQUESTS_TOTAL,
+        help: "Subscribe/unsubscribe requests resolved, by exchange and outcome (acked, rejected, retried, escalated)",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: SUBSCRIPTION_DRIFT_TOTAL,
+        help: "Streams found missing or stray when auditing live subscriptions, by exchange and kind",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: FEED_LATENCY_SECONDS,
+        help: "Delay between exchange event time and local receive time",
+        kind: MetricKind::Histogram,
+    },
+    MetricDesc {
+        name: ORDER_LATENCY_SECONDS,
+        help: "Round trip time of order REST calls",
+        kind: MetricKind::Histogram,
+    },
+    MetricDesc {
+        name: CROSS_VENUE_LAG_SECONDS,
+        help: "Time a follower venue took to reflect a large leader move, by venue pair",
+        kind: MetricKind::Histogram,
+    },
+    MetricDesc {
+        name: LATENCY_RACES_TOTAL,
+        help: "Latency races by venue pair and whether the follower reflected the move in time",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: BINANCE_DEPTH_IMBALANCE,
+        help: "Bid/ask quantity ratio of the Binance depth snapshot",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: KALSHI_TOP_OF_BOOK,
+        help: "Best Kalshi price in dollars, by side",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: IMBALANCE_ALERTS_TOTAL,
+        help: "Depth imbalance alerts raised",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: ALERTS_SUPPRESSED_TOTAL,
+        help: "Alerts suppressed or downgraded, by reason",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: BINANCE_DEPTH_VELOCITY,
+        help: "Quantity added or cancelled per second in the top Binance levels, by side",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: DEPTH_VELOCITY_ALERTS_TOTAL,
+        help: "Depth stacking and pulling alerts raised",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: BINANCE_BOOK_RESYNCS_TOTAL,
+        help: "Local Binance books rebuilt from a REST snapshot, by symbol and reason",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: BINANCE_ICEBERG_LEVELS,
+        help: "Binance price levels currently flagged as probable icebergs, by side",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: ICEBERG_ALERTS_TOTAL,
+        help: "Probable iceberg orders flagged",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: RULE_ALERTS_TOTAL,
+        help: "Alerts raised by user-defined alert rules, by rule",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: SIGNAL_ALERTS_TOTAL,
+        help: "Alerts raised by signals over the merged event stream, by signal",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: ORDERS_TOTAL,
+        help: "Orders submitted to Kalshi",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: ORDER_ERRORS_TOTAL,
+        help: "Orders rejected or failed",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: CANCELLED_ORDERS_TOTAL,
+        help: "Orders cancelled",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: EXECUTION_FILLS_TOTAL,
+        help: "Fills of our own Kalshi orders from the fill channel, by market",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: PAPER_FILLS_TOTAL,
+        help: "Contracts filled by paper trading, by market and side",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: RISK_REJECTIONS_TOTAL,
+        help: "Alert and trader orders refused by a risk limit, by limit",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: KILL_SWITCH_ENGAGED,
+        help: "1 while the execution kill switch is engaged",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: POSITION_CONTRACTS,
+        help: "YES contracts held from execution fills, negative for NO, by market and mode",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: POSITION_REALIZED_PNL,
+        help: "P&L closed out of execution positions, by market and mode",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: POSITION_UNREALIZED_PNL,
+        help: "P&L of open execution positions at the mark, by market and mode",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: HEDGE_ORDERS_TOTAL,
+        help: "Hedge orders sent, by symbol, side and venue",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: HEDGE_POSITION,
+        help: "Base asset quantity held as hedge, by symbol; negative is short",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: PORTFOLIO_NET_DELTA,
+        help: "Base asset units of Kalshi positions plus hedges, by underlying",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: PORTFOLIO_WORST_CASE_LOSS,
+        help: "Largest settlement loss of Kalshi positions, by underlying",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: INVENTORY_CONTRACTS,
+        help: "Contracts held, by market and side",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: QUOTE_FILL_PROBABILITY,
+        help: "Estimated fill probability of a resting quote, by side and distance from mid",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: QUOTE_TIME_TO_FILL_SECONDS,
+        help: "Time a virtual resting quote took to fill, by side",
+        kind: MetricKind::Histogram,
+    },
+    MetricDesc {
+        name: DB_ROWS_WRITTEN_TOTAL,
+        help: "Rows written to the database, by table",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: DB_WRITE_ERRORS_TOTAL,
+        help: "Failed database writes, by table",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: DB_FLUSH_SECONDS,
+        help: "Time taken by one batched database insert, by table",
+        kind: MetricKind::Histogram,
+    },
+    MetricDesc {
+        name: DB_WRITER_QUEUE_DEPTH,
+        help: "Rows buffered or queued for the batched database writer, by table",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: DB_ROWS_SAMPLED_OUT_TOTAL,
+        help: "Rows skipped by a table's persistence sampling, by table",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: DB_ROWS_PRUNED_TOTAL,
+        help: "Rows deleted by database maintenance after rollup, by table",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: CSV_ROWS_WRITTEN_TOTAL,
+        help: "Rows appended to CSV export files",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: CSV_WRITE_ERRORS_TOTAL,
+        help: "Failed appends to CSV export files",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: RECORDER_BYTES_WRITTEN_TOTAL,
+        help: "Compressed bytes written to recording files",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: RECORDER_DROPPED_FRAMES_TOTAL,
+        help: "Raw frames dropped because the recorder fell behind",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: ARCHIVE_UPLOADED_BYTES_TOTAL,
+        help: "Bytes uploaded to the archive object store",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: ARCHIVE_FILES_TOTAL,
+        help: "Files handled by the archiver, by action (uploaded, failed, removed_local, expired)",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: STATE_ENTRIES,
+        help: "Entries held in in-memory market state, by map",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: STATE_EVICTIONS_TOTAL,
+        help: "Entries evicted from in-memory state, by map",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: LEADER,
+        help: "1 while this instance holds the leader lease, 0 otherwise",
+        kind: MetricKind::Gauge,
+    },
+    MetricDesc {
+        name: NOTIFICATIONS_TOTAL,
+        help: "Chat notifications by target, event kind and outcome (sent, failed, throttled)",
+        kind: MetricKind::Counter,
+    },
+    MetricDesc {
+        name: CHAOS_FAULTS_TOTAL,
+        help: "Faults injected by the chaos layer, by exchange and fault",
+        kind: MetricKind::Counter,
+    },
+];
+
+pub fn describe(name: &str) -> Option<&'static MetricDesc> {
+    ALL.iter().find(|d| d.name == name)
+}
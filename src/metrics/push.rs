@@ -0,0 +1,155 @@
+//! Push exporters for environments where Prometheus cannot scrape `/metrics`:
+//! a Pushgateway (text format) or a remote-write endpoint (snappy protobuf).
+
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::Client as HttpClient;
+use tracing::{error, info};
+
+use super::registry::{registry, Sample};
+use crate::config::{MetricsPushConfig, MetricsPushMode};
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::utils::http::ensure_success;
+
+pub struct MetricsPusher {
+    config: MetricsPushConfig,
+    http: HttpClient,
+}
+
+impl MetricsPusher {
+    pub fn spawn(config: &MetricsPushConfig) {
+        let pusher = Self {
+            config: config.clone(),
+            http: HttpClient::new(),
+        };
+        info!(
+            "📤 Pushing metrics to {} ({:?}) every {}s",
+            config.url, config.mode, config.interval_secs
+        );
+        tokio::spawn(pusher.run());
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.push().await {
+                error!("Failed to push metrics: {}", e.chain());
+            }
+        }
+    }
+
+    async fn push(&self) -> Result<()> {
+        match self.config.mode {
+            MetricsPushMode::Pushgateway => self.push_gateway().await,
+            MetricsPushMode::RemoteWrite => self.remote_write().await,
+        }
+    }
+
+    /// Replaces this instance's group on the Pushgateway.
+    async fn push_gateway(&self) -> Result<()> {
+        let url = format!(
+            "{}/metrics/job/{}/instance/{}",
+            self.config.url.trim_end_matches('/'),
+            self.config.job,
+            self.config.instance
+        );
+        let context = || ErrorContext::new("push metrics").with_endpoint(url.clone());
+
+        let resp = self
+            .http
+            .put(&url)
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(registry().render())
+            .send()
+            .await
+            .with_context(context)?;
+        ensure_success(resp).await.with_context(context)?;
+        Ok(())
+    }
+
+    async fn remote_write(&self) -> Result<()> {
+        let extra = [
+            ("job".to_string(), self.config.job.clone()),
+            ("instance".to_string(), self.config.instance.clone()),
+        ];
+        let body = encode_write_request(&registry().samples(), &extra, Utc::now().timestamp_millis());
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&body)
+            .map_err(|e| Error::Other(format!("Snappy compression failed: {}", e)))?;
+
+        let context = || ErrorContext::new("remote-write metrics").with_endpoint(self.config.url.clone());
+        let resp = self
+            .http
+            .post(&self.config.url)
+            .header("Content-Type", "application/x-protobuf")
+            .header("Content-Encoding", "snappy")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+            .body(body)
+            .send()
+            .await
+            .with_context(context)?;
+        ensure_success(resp).await.with_context(context)?;
+        Ok(())
+    }
+}
+
+// Minimal protobuf encoding of the remote-write `WriteRequest`:
+//
+//   WriteRequest { repeated TimeSeries timeseries = 1; }
+//   TimeSeries   { repeated Label labels = 1; repeated Sample samples = 2; }
+//   Label        { string name = 1; string value = 2; }
+//   Sample       { double value = 1; int64 timestamp = 2; }
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+
+fn encode_write_request(samples: &[Sample], extra: &[(String, String)], timestamp_ms: i64) -> Vec<u8> {
+    let mut out = Vec::new();
+    for sample in samples {
+        let mut labels: Vec<(&str, &str)> = Vec::with_capacity(sample.labels.len() + extra.len() + 1);
+        labels.push(("__name__", &sample.name));
+        labels.extend(sample.labels.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        labels.extend(extra.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        // Remote-write requires labels sorted by name.
+        labels.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut series = Vec::new();
+        for (name, value) in labels {
+            let mut label = Vec::new();
+            write_bytes(&mut label, 1, name.as_bytes());
+            write_bytes(&mut label, 2, value.as_bytes());
+            write_bytes(&mut series, 1, &label);
+        }
+
+        let mut point = Vec::new();
+        write_key(&mut point, 1, WIRE_FIXED64);
+        point.extend_from_slice(&sample.value.to_le_bytes());
+        write_key(&mut point, 2, WIRE_VARINT);
+        write_varint(&mut point, timestamp_ms as u64);
+        write_bytes(&mut series, 2, &point);
+
+        write_bytes(&mut out, 1, &series);
+    }
+    out
+}
+
+fn write_key(out: &mut Vec<u8>, field: u32, wire_type: u8) {
+    write_varint(out, ((field << 3) | wire_type as u32) as u64);
+}
+
+fn write_bytes(out: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    write_key(out, field, WIRE_LEN);
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
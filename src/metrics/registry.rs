@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use dashmap::DashMap;
+
+use super::names::{self, MetricKind};
+
+const DEFAULT_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct SeriesKey {
+    name: &'static str,
+    labels: Vec<(&'static str, String)>,
+}
+
+impl SeriesKey {
+    fn new(name: &'static str, labels: &[(&'static str, &str)]) -> Self {
+        Self {
+            name,
+            labels: labels.iter().map(|(k, v)| (*k, v.to_string())).collect(),
+        }
+    }
+
+    fn format_labels(&self, extra: Option<(&str, String)>) -> String {
+        let mut parts: Vec<String> = self
+            .labels
+            .iter()
+            .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+            .collect();
+        if let Some((k, v)) = extra {
+            parts.push(format!("{}=\"{}\"", k, v));
+        }
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", parts.join(","))
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+struct Histogram {
+    buckets: &'static [f64],
+    counts: Vec<AtomicU64>,
+    sum_bits: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    fn new(buckets: &'static [f64]) -> Self {
+        Self {
+            buckets,
+            counts: buckets.iter().map(|_| AtomicU64::new(0)).collect(),
+            sum_bits: AtomicU64::new(0f64.to_bits()),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, value: f64) {
+        for (idx, bound) in self.buckets.iter().enumerate() {
+            if value <= *bound {
+                self.counts[idx].fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum_bits
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) + value).to_bits())
+            });
+    }
+}
+
+/// Process-wide store of counters, gauges and histograms, rendered in the
+/// Prometheus text exposition format.
+#[derive(Default)]
+pub struct Registry {
+    counters: DashMap<SeriesKey, Arc<AtomicU64>>,
+    gauges: DashMap<SeriesKey, Arc<AtomicU64>>,
+    histograms: DashMap<SeriesKey, Arc<Histogram>>,
+}
+
+impl Registry {
+    pub fn add_counter(&self, name: &'static str, labels: &[(&'static str, &str)], value: u64) {
+        self.counters
+            .entry(SeriesKey::new(name, labels))
+            .or_insert_with(|| Arc::new(AtomicU64::new(0)))
+            .fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn set_gauge(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        self.gauges
+            .entry(SeriesKey::new(name, labels))
+            .or_insert_with(|| Arc::new(AtomicU64::new(0f64.to_bits())))
+            .store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn observe(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        self.histograms
+            .entry(SeriesKey::new(name, labels))
+            .or_insert_with(|| Arc::new(Histogram::new(DEFAULT_BUCKETS)))
+            .observe(value);
+    }
+
+    pub fn counter_value(&self, name: &'static str, labels: &[(&'static str, &str)]) -> u64 {
+        self.counters
+            .get(&SeriesKey::new(name, labels))
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    pub fn gauge_value(&self, name: &'static str, labels: &[(&'static str, &str)]) -> Option<f64> {
+        self.gauges
+            .get(&SeriesKey::new(name, labels))
+            .map(|g| f64::from_bits(g.load(Ordering::Relaxed)))
+    }
+
+    /// Every series as flat samples, histograms expanded into their
+    /// `_bucket`, `_sum` and `_count` series. Used by the push exporters.
+    pub fn samples(&self) -> Vec<Sample> {
+        let mut samples = Vec::new();
+        for e in self.counters.iter() {
+            samples.push(Sample::new(e.key().name.to_string(), &e.key().labels, None, e.value().load(Ordering::Relaxed) as f64));
+        }
+        for e in self.gauges.iter() {
+            samples.push(Sample::new(
+                e.key().name.to_string(),
+                &e.key().labels,
+                None,
+                f64::from_bits(e.value().load(Ordering::Relaxed)),
+            ));
+        }
+        for e in self.histograms.iter() {
+            let (key, hist) = (e.key(), e.value());
+            for (idx, bound) in hist.buckets.iter().enumerate() {
+                samples.push(Sample::new(
+                    format!("{}_bucket", key.name),
+                    &key.labels,
+                    Some(("le", bound.to_string())),
+                    hist.counts[idx].load(Ordering::Relaxed) as f64,
+                ));
+            }
+            let count = hist.count.load(Ordering::Relaxed) as f64;
+            samples.push(Sample::new(format!("{}_bucket", key.name), &key.labels, Some(("le", "+Inf".to_string())), count));
+            samples.push(Sample::new(
+                format!("{}_sum", key.name),
+                &key.labels,
+                None,
+                f64::from_bits(hist.sum_bits.load(Ordering::Relaxed)),
+            ));
+            samples.push(Sample::new(format!("{}_count", key.name), &key.labels, None, count));
+        }
+        samples
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+
+        let mut counters: Vec<_> = self
+            .counters
+            .iter()
+            .map(|e| (e.key().clone(), e.value().load(Ordering::Relaxed)))
+            .collect();
+        counters.sort();
+        for (key, value) in counters {
+            lines
+                .entry(key.name)
+                .or_default()
+                .push(format!("{}{} {}", key.name, key.format_labels(None), value));
+        }
+
+        let mut gauges: Vec<_> = self
+            .gauges
+            .iter()
+            .map(|e| (e.key().clone(), f64::from_bits(e.value().load(Ordering::Relaxed))))
+            .collect();
+        gauges.sort_by(|a, b| a.0.cmp(&b.0));
+        for (key, value) in gauges {
+            lines
+                .entry(key.name)
+                .or_default()
+                .push(format!("{}{} {}", key.name, key.format_labels(None), value));
+        }
+
+        let mut histograms: Vec<_> = self
+            .histograms
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect();
+        histograms.sort_by(|a, b| a.0.cmp(&b.0));
+        for (key, hist) in histograms {
+            let series = lines.entry(key.name).or_default();
+            for (idx, bound) in hist.buckets.iter().enumerate() {
+                series.push(format!(
+                    "{}_bucket{} {}",
+                    key.name,
+                    key.format_labels(Some(("le", bound.to_string()))),
+                    hist.counts[idx].load(Ordering::Relaxed)
+                ));
+            }
+            let count = hist.count.load(Ordering::Relaxed);
+            series.push(format!(
+                "{}_bucket{} {}",
+                key.name,
+                key.format_labels(Some(("le", "+Inf".to_string()))),
+                count
+            ));
+            series.push(format!(
+                "{}_sum{} {}",
+                key.name,
+                key.format_labels(None),
+                f64::from_bits(hist.sum_bits.load(Ordering::Relaxed))
+            ));
+            series.push(format!("{}_count{} {}", key.name, key.format_labels(None), count));
+        }
+
+        let mut out = String::new();
+        for (name, series) in lines {
+            if let Some(desc) = names::describe(name) {
+                let _ = writeln!(out, "# HELP {} {}", name, desc.help);
+                let _ = writeln!(out, "# TYPE {} {}", name, desc.kind.as_str());
+            } else {
+                let _ = writeln!(out, "# TYPE {} {}", name, MetricKind::Gauge.as_str());
+            }
+            for line in series {
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+        out
+    }
+}
+
+/// One point of one series, as sent to Prometheus remote-write.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
+impl Sample {
+    fn new(
+        name: String,
+        labels: &[(&'static str, String)],
+        extra: Option<(&str, String)>,
+        value: f64,
+    ) -> Self {
+        let mut labels: Vec<(String, String)> = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        if let Some((k, v)) = extra {
+            labels.push((k.to_string(), v));
+        }
+        Self { name, labels, value }
+    }
+}
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+pub fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::default)
+}
@@ -0,0 +1,227 @@
+//! Monitor sessions: how a Kalshi market responds to an alert.
+//!
+//! Every alert mapped to a Kalshi market opens a session on that market,
+//! listed on the status board and stored in `monitor_sessions`. The
+//! market's top of book changes are collected for `window_secs`, then
+//! written to `monitor_odds_changes` and summed up in `monitor_responses`,
+//! and the session ends with an outcome: `followed` or `faded` when the
+//! YES mid moved with or against the alert's direction, `moved` when the
+//! alert had none, `unchanged` otherwise. Sessions still open at shutdown
+//! end as `interrupted`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use crate::db::main::{Db, MonitorResponseRecord, MonitorSessionRecord, OddsChangeRecord};
+use crate::exchanges::event::{AlertEvent, KalshiTopOfBookChanged, MarketEvent};
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::shutdown;
+use crate::sinks::SINK_CHANNEL_BUFFER;
+use crate::state::KalshiState;
+use crate::status::{self, AlertDirection};
+
+/// Outcome of sessions cut short by a shutdown.
+const SHUTDOWN_OUTCOME: &str = "interrupted";
+
+struct Session {
+    record: MonitorSessionRecord,
+    direction: Option<AlertDirection>,
+    /// YES mid when the alert fired
+    start_mid: Option<f64>,
+    changes: Vec<OddsChangeRecord>,
+}
+
+impl Session {
+    fn response(&self) -> MonitorResponseRecord {
+        let moves: Vec<(DateTime<Utc>, f64)> = match self.start_mid {
+            Some(start) => self
+                .changes
+                .iter()
+                .filter_map(|c| Some((c.timestamp, mid(c.yes_bid, c.yes_ask)? - start)))
+                .collect(),
+            None => Vec::new(),
+        };
+        let first_move_ms = moves
+            .iter()
+            .find(|(_, m)| m.abs() > f64::EPSILON)
+            .map(|(at, _)| (*at - self.record.started_at).num_milliseconds().max(0));
+        let max_move = moves
+            .iter()
+            .map(|(_, m)| *m)
+            .fold(0.0, |max: f64, m| if m.abs() > max.abs() { m } else { max });
+        MonitorResponseRecord {
+            session_id: self.record.id.clone(),
+            changes: self.changes.len() as i64,
+            first_move_ms,
+            max_move,
+            final_move: moves.last().map_or(0.0, |(_, m)| *m),
+        }
+    }
+}
+
+/// YES mid of a top of book; `None` unless both sides are quoted.
+fn mid(yes_bid: Option<f64>, yes_ask: Option<f64>) -> Option<f64> {
+    match (yes_bid, yes_ask) {
+        (Some(bid), Some(ask)) if bid > 0.0 && ask > 0.0 => Some((bid + ask) / 2.0),
+        _ => None,
+    }
+}
+
+/// `followed`, `faded`, `moved` or `unchanged`, see the module docs.
+pub fn response_outcome(direction: Option<AlertDirection>, final_move: f64) -> &'static str {
+    if final_move.abs() <= f64::EPSILON {
+        return "unchanged";
+    }
+    match direction {
+        Some(AlertDirection::Up) if final_move > 0.0 => "followed",
+        Some(AlertDirection::Down) if final_move < 0.0 => "followed",
+        Some(_) => "faded",
+        None => "moved",
+    }
+}
+
+pub struct MonitorWriter {
+    db: Arc<Db>,
+    kalshi: Arc<KalshiState>,
+    leader: LeaderHandle,
+    window: chrono::Duration,
+    sessions: HashMap<String, Session>,
+}
+
+impl MonitorWriter {
+    /// Starts the writer; feed it the sinks' events, alerts included.
+    pub fn spawn(
+        db: Arc<Db>,
+        kalshi: Arc<KalshiState>,
+        window_secs: u64,
+        leader: LeaderHandle,
+    ) -> mpsc::Sender<MarketEvent> {
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue("monitors", &tx);
+        let writer = Self {
+            db,
+            kalshi,
+            leader,
+            window: chrono::Duration::seconds(window_secs as i64),
+            sessions: HashMap::new(),
+        };
+        shutdown::coordinator().track("monitor writer", tokio::spawn(writer.run(rx)));
+        tx
+    }
+
+    async fn run(mut self, mut rx: mpsc::Receiver<MarketEvent>) {
+        info!("🔭 Monitoring Kalshi responses to alerts for {}s", self.window.num_seconds());
+        let mut expiry = tokio::time::interval(Duration::from_secs(1));
+        let mut shutdown_rx = shutdown::coordinator().subscribe();
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Some(MarketEvent::Alert(alert)) => self.start(&alert).await,
+                    Some(MarketEvent::TopOfBookChanged(change)) => self.on_change(&change),
+                    Some(_) => {}
+                    None => break,
+                },
+                _ = expiry.tick() => self.expire(Utc::now()).await,
+                _ = shutdown::requested(&mut shutdown_rx) => break,
+            }
+        }
+        let open: Vec<String> = self.sessions.keys().cloned().collect();
+        for id in open {
+            self.finish(&id, Utc::now(), Some(SHUTDOWN_OUTCOME)).await;
+        }
+        info!("Monitor writer shutting down");
+    }
+
+    async fn start(&mut self, alert: &AlertEvent) {
+        let Some(ticker) = &alert.kalshi_ticker else {
+            return;
+        };
+        if !self.leader.is_leader() {
+            return;
+        }
+        let record = MonitorSessionRecord {
+            id: alert.event_id.clone(),
+            trigger: "alert".to_string(),
+            symbol: alert.meta.instrument.clone(),
+            kalshi_ticker: ticker.clone(),
+            started_at: alert.meta.timestamp,
+        };
+        if let Err(e) = self.db.insert_monitor_session(&record).await {
+            error!("Failed to store monitor session {}: {}", record.id, e.chain());
+            metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", "monitor_sessions")]);
+            return;
+        }
+        metrics::inc(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "monitor_sessions")]);
+        status::board().monitor_started(&record.id, &format!("{} on {}", alert.message, ticker));
+        let start_mid = mid(self.kalshi.get_top_bid(ticker), self.kalshi.get_top_ask(ticker));
+        self.sessions.insert(
+            record.id.clone(),
+            Session {
+                record,
+                direction: alert.direction,
+                start_mid,
+                changes: Vec::new(),
+            },
+        );
+    }
+
+    fn on_change(&mut self, change: &KalshiTopOfBookChanged) {
+        let quoted = |price: f64| (price > 0.0).then_some(price);
+        for session in self.sessions.values_mut() {
+            if session.record.kalshi_ticker != change.meta.instrument {
+                continue;
+            }
+            session.changes.push(OddsChangeRecord {
+                session_id: session.record.id.clone(),
+                timestamp: change.meta.timestamp,
+                yes_bid: quoted(change.current.bid),
+                yes_ask: quoted(change.current.ask),
+            });
+        }
+    }
+
+    async fn expire(&mut self, now: DateTime<Utc>) {
+        let due: Vec<String> = self
+            .sessions
+            .values()
+            .filter(|s| now - s.record.started_at >= self.window)
+            .map(|s| s.record.id.clone())
+            .collect();
+        for id in due {
+            self.finish(&id, now, None).await;
+        }
+    }
+
+    /// Stores the session's changes and response and closes it, with
+    /// `outcome` or the one its response implies.
+    async fn finish(&mut self, id: &str, now: DateTime<Utc>, outcome: Option<&str>) {
+        let Some(session) = self.sessions.remove(id) else {
+            return;
+        };
+        let response = session.response();
+        let outcome = outcome.unwrap_or_else(|| response_outcome(session.direction, response.final_move));
+        status::board().monitor_finished(id, outcome);
+        if !self.leader.is_leader() {
+            return;
+        }
+
+        let result = async {
+            self.db.insert_odds_changes(&session.changes).await?;
+            self.db.finish_monitor_session(now, outcome, &response).await?;
+            self.db.record_imbalance_alert_outcome(id, outcome).await
+        };
+        match result.await {
+            Ok(()) => metrics::inc(names::DB_ROWS_WRITTEN_TOTAL, &[("table", "monitor_responses")]),
+            Err(e) => {
+                error!("Failed to store monitor session {} response: {}", id, e.chain());
+                metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", "monitor_responses")]);
+            }
+        }
+    }
+}
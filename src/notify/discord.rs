@@ -0,0 +1,104 @@
+//! Posts notifications to a Discord webhook as embeds, configured with the
+//! `DISCORD_` variables of
+//! [`WebhookConfig::from_env`](super::WebhookConfig::from_env).
+//!
+//! The rendered template is the embed title. Alerts add fields for the
+//! imbalance ratio and, when the symbol maps to a Kalshi series, the current
+//! market's YES bid and ask at the time the notification goes out.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use super::{Notification, Notifier};
+use crate::error::Result;
+use crate::state::KalshiState;
+use crate::utils::http::ensure_success;
+
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Discord rejects embed titles longer than this
+const MAX_TITLE_CHARS: usize = 256;
+
+const COLOR_ALERT: u32 = 0xE0_3C_3C;
+const COLOR_WARNING: u32 = 0xF0_A0_20;
+const COLOR_INFO: u32 = 0x3C_8C_E0;
+
+pub struct DiscordNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+    kalshi: Option<Arc<KalshiState>>,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: &str, kalshi: Option<Arc<KalshiState>>) -> Self {
+        Self {
+            webhook_url: webhook_url.to_string(),
+            client: reqwest::Client::new(),
+            kalshi,
+        }
+    }
+
+    fn embed(&self, notification: &Notification, text: &str) -> Value {
+        let (color, timestamp) = match notification {
+            Notification::Alert(alert) => (COLOR_ALERT, alert.timestamp),
+            Notification::Reconnect { .. } | Notification::DbFailure { .. } => (COLOR_WARNING, Utc::now()),
+            Notification::MarketSwitch { .. } => (COLOR_INFO, Utc::now()),
+        };
+        json!({
+            "title": text.chars().take(MAX_TITLE_CHARS).collect::<String>(),
+            "color": color,
+            "timestamp": timestamp.to_rfc3339(),
+            "fields": self.fields(notification),
+        })
+    }
+
+    fn fields(&self, notification: &Notification) -> Vec<Value> {
+        let field = |name: &str, value: String| json!({ "name": name, "value": value, "inline": true });
+        let Notification::Alert(alert) = notification else {
+            return Vec::new();
+        };
+
+        let mut fields = vec![field("Symbol", alert.symbol.clone())];
+        if let Some(ratio) = alert.imbalance_ratio {
+            fields.push(field("Imbalance", format!("{:.2}", ratio)));
+        }
+        let market = match (&self.kalshi, &alert.kalshi_series) {
+            (Some(state), Some(series)) => state.current_market_for_series(series),
+            _ => None,
+        };
+        if let (Some(state), Some(ticker)) = (&self.kalshi, market) {
+            let cents = |price: Option<f64>| price.map_or_else(|| "-".to_string(), |p| format!("{:.0}¢", p * 100.0));
+            fields.push(field("Kalshi", ticker.clone()));
+            fields.push(field("YES bid", cents(state.get_top_bid(&ticker))));
+            fields.push(field("YES ask", cents(state.get_top_ask(&ticker))));
+        } else if let Some(series) = &alert.kalshi_series {
+            fields.push(field("Kalshi", series.clone()));
+        }
+        if let Some(event) = &alert.calendar_event {
+            fields.push(field("Calendar", event.clone()));
+        }
+        fields
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn send(&self, notification: &Notification, text: &str) -> Result<()> {
+        let resp = self
+            .client
+            .post(&self.webhook_url)
+            .timeout(POST_TIMEOUT)
+            .json(&json!({ "embeds": [self.embed(notification, text)] }))
+            .send()
+            .await?;
+        ensure_success(resp).await?;
+        Ok(())
+    }
+}
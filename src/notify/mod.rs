@@ -0,0 +1,291 @@
+//! Chat notifications for alerts and operational events.
+//!
+//! Alerts reach notifiers through the status board's alert broadcast. The
+//! operational events (reconnects, Kalshi market switches, failed database
+//! writes) are published here by the component they happen in; publishing
+//! is a no-op when no notifier is running.
+//!
+//! Each target ([`slack`], [`discord`], [`webhook`]) implements [`Notifier`] and is fed by
+//! [`spawn`], which applies the target's enabled kinds, templates and
+//! cooldown. Reconnects and database failures repeat for as long as an
+//! outage lasts, so after one is sent for an exchange or table the next is
+//! held back until the cooldown has passed. Like alerting itself, delivery
+//! is leader only, so an HA pair posts everything once.
+
+pub mod discord;
+pub mod slack;
+pub mod webhook;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::status::{self, AlertRecord};
+
+/// Events a slow notifier may fall behind by before it skips ahead.
+const EVENT_BROADCAST_CAPACITY: usize = 256;
+
+/// Event kinds, as used in config names and metric labels.
+pub const KINDS: &[&str] = &["alert", "reconnect", "market_switch", "db_failure"];
+
+#[derive(Debug, Clone)]
+pub enum Notification {
+    Alert(AlertRecord),
+    Reconnect { exchange: String, error: String },
+    /// Kalshi series moved on from its current market to the next one
+    MarketSwitch { series: String, from: String, to: String },
+    DbFailure { table: String, error: String },
+}
+
+impl Notification {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Notification::Alert(_) => "alert",
+            Notification::Reconnect { .. } => "reconnect",
+            Notification::MarketSwitch { .. } => "market_switch",
+            Notification::DbFailure { .. } => "db_failure",
+        }
+    }
+
+    /// What repeats of this event are throttled by; `None` for events that
+    /// are always sent.
+    pub fn throttle_key(&self) -> Option<&str> {
+        match self {
+            Notification::Reconnect { exchange, .. } => Some(exchange),
+            Notification::DbFailure { table, .. } => Some(table),
+            Notification::Alert(_) | Notification::MarketSwitch { .. } => None,
+        }
+    }
+
+    /// Values for the `{name}` placeholders of a [`Template`].
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Notification::Alert(alert) => vec![
+                ("event_id", alert.event_id.clone()),
+                ("time", alert.timestamp.to_rfc3339()),
+                ("symbol", alert.symbol.clone()),
+                ("series", alert.kalshi_series.clone().unwrap_or_else(|| "-".to_string())),
+                ("message", alert.message.clone()),
+                ("ratio", alert.imbalance_ratio.map_or_else(|| "-".to_string(), |r| format!("{:.2}", r))),
+                ("calendar_event", alert.calendar_event.clone().unwrap_or_else(|| "-".to_string())),
+                ("account", alert.tags.account.clone()),
+                ("strategy", alert.tags.strategy.clone()),
+            ],
+            Notification::Reconnect { exchange, error } => {
+                vec![("exchange", exchange.clone()), ("error", error.clone())]
+            }
+            Notification::MarketSwitch { series, from, to } => vec![
+                ("series", series.clone()),
+                ("from", from.clone()),
+                ("to", to.clone()),
+            ],
+            Notification::DbFailure { table, error } => {
+                vec![("table", table.clone()), ("error", error.clone())]
+            }
+        }
+    }
+}
+
+/// Message text with `{name}` placeholders filled from
+/// [`Notification::fields`]. Unknown placeholders are left as written.
+#[derive(Debug, Clone)]
+pub struct Template(String);
+
+impl Template {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self(text.into())
+    }
+
+    /// The template used for `kind` unless one is configured.
+    pub fn default_for(kind: &str) -> Self {
+        Self::new(match kind {
+            "alert" => "🚨 *{symbol}* ({series}) {message}",
+            "reconnect" => "🔁 {exchange} reconnecting: {error}",
+            "market_switch" => "🔄 {series}: {from} → {to}",
+            "db_failure" => "🗄️ Write to {table} failed: {error}",
+            _ => "{kind}",
+        })
+    }
+
+    pub fn render(&self, notification: &Notification) -> String {
+        let mut out = self.0.replace("{kind}", notification.kind());
+        for (name, value) in notification.fields() {
+            out = out.replace(&format!("{{{}}}", name), &value);
+        }
+        out
+    }
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync + 'static {
+    /// Target name for logs and the `target` metric label.
+    fn name(&self) -> &'static str;
+
+    /// Delivers one notification; `text` is its rendered template.
+    async fn send(&self, notification: &Notification, text: &str) -> Result<()>;
+}
+
+/// A webhook target: where to post, which kinds and how they read.
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub webhook_url: String,
+    /// Template per enabled event kind; kinds missing here are not sent
+    pub templates: BTreeMap<&'static str, Template>,
+    /// Minimum gap between repeated reconnect or database failure messages
+    /// for the same exchange or table
+    pub cooldown_secs: u64,
+}
+
+impl WebhookConfig {
+    /// Reads `<PREFIX>_WEBHOOK_URL`, `<PREFIX>_COOLDOWN_SECS` and, for each
+    /// event kind, `<PREFIX>_NOTIFY_<KIND>` (on by default) and
+    /// `<PREFIX>_TEMPLATE_<KIND>`. `None` without a webhook URL.
+    pub fn from_env(prefix: &str) -> Result<Option<Self>> {
+        let Ok(webhook_url) = crate::config::var(format!("{}_WEBHOOK_URL", prefix)) else {
+            return Ok(None);
+        };
+        let mut templates = BTreeMap::new();
+        for kind in KINDS {
+            let upper = kind.to_uppercase();
+            let enabled = crate::config::var(format!("{}_NOTIFY_{}", prefix, upper))
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(true);
+            if !enabled {
+                continue;
+            }
+            let template = match crate::config::var(format!("{}_TEMPLATE_{}", prefix, upper)) {
+                Ok(text) => Template::new(text),
+                Err(_) => Template::default_for(kind),
+            };
+            templates.insert(*kind, template);
+        }
+        let cooldown_name = format!("{}_COOLDOWN_SECS", prefix);
+        let cooldown_secs = match crate::config::var(&cooldown_name) {
+            Ok(v) => v
+                .parse()
+                .map_err(|_| Error::Config(format!("Invalid {} '{}'", cooldown_name, v)))?,
+            Err(_) => 60,
+        };
+        Ok(Some(Self {
+            webhook_url,
+            templates,
+            cooldown_secs,
+        }))
+    }
+}
+
+/// Feeds alerts and published events to `notifier` until shutdown, while
+/// this instance leads.
+pub fn spawn(notifier: impl Notifier, config: &WebhookConfig, leader: LeaderHandle) {
+    let kinds: Vec<_> = config.templates.keys().collect();
+    info!("💬 Posting {:?} notifications to {}", kinds, notifier.name());
+    let dispatcher = Dispatcher {
+        notifier,
+        config: config.clone(),
+        leader,
+        last_sent: HashMap::new(),
+    };
+    tokio::spawn(dispatcher.run(status::board().subscribe_alerts(), subscribe()));
+}
+
+struct Dispatcher<N> {
+    notifier: N,
+    config: WebhookConfig,
+    leader: LeaderHandle,
+    /// When the latest message per kind and throttle key was sent
+    last_sent: HashMap<(&'static str, String), Instant>,
+}
+
+impl<N: Notifier> Dispatcher<N> {
+    async fn run(
+        mut self,
+        mut alerts: broadcast::Receiver<AlertRecord>,
+        mut events: broadcast::Receiver<Notification>,
+    ) {
+        let name = self.notifier.name();
+        loop {
+            let notification = tokio::select! {
+                alert = alerts.recv() => match alert {
+                    Ok(alert) => Notification::Alert(alert),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("{} notifier fell behind, skipped {} alerts", name, skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                event = events.recv() => match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("{} notifier fell behind, skipped {} events", name, skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            if !self.leader.is_leader() {
+                continue;
+            }
+            self.dispatch(&notification).await;
+        }
+        info!("{} notifier shutting down", name);
+    }
+
+    async fn dispatch(&mut self, notification: &Notification) {
+        let (name, kind) = (self.notifier.name(), notification.kind());
+        if !self.config.templates.contains_key(kind) {
+            return;
+        }
+        if self.throttled(notification) {
+            metrics::inc(names::NOTIFICATIONS_TOTAL, &[("target", name), ("kind", kind), ("outcome", "throttled")]);
+            return;
+        }
+
+        let text = self.config.templates[kind].render(notification);
+        let outcome = match self.notifier.send(notification, &text).await {
+            Ok(()) => "sent",
+            Err(e) => {
+                warn!("Failed to post {} notification to {}: {}", kind, name, e);
+                "failed"
+            }
+        };
+        metrics::inc(names::NOTIFICATIONS_TOTAL, &[("target", name), ("kind", kind), ("outcome", outcome)]);
+    }
+
+    /// Whether a message with the same kind and key went out within the
+    /// cooldown; records this one as sent otherwise.
+    fn throttled(&mut self, notification: &Notification) -> bool {
+        let Some(key) = notification.throttle_key() else {
+            return false;
+        };
+        let key = (notification.kind(), key.to_string());
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        if self.last_sent.get(&key).is_some_and(|last| last.elapsed() < cooldown) {
+            return true;
+        }
+        self.last_sent.insert(key, Instant::now());
+        false
+    }
+}
+
+static EVENTS: OnceLock<broadcast::Sender<Notification>> = OnceLock::new();
+
+fn events() -> &'static broadcast::Sender<Notification> {
+    EVENTS.get_or_init(|| broadcast::channel(EVENT_BROADCAST_CAPACITY).0)
+}
+
+/// Hands an operational event to the running notifiers.
+pub fn publish(notification: Notification) {
+    // Fails only when nobody is subscribed
+    let _ = events().send(notification);
+}
+
+pub fn subscribe() -> broadcast::Receiver<Notification> {
+    events().subscribe()
+}
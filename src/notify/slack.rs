@@ -0,0 +1,47 @@
+//! Posts notifications to a Slack incoming webhook as plain `text`
+//! messages, configured with the `SLACK_` variables of
+//! [`WebhookConfig::from_env`](super::WebhookConfig::from_env).
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{Notification, Notifier};
+use crate::error::Result;
+use crate::utils::http::ensure_success;
+
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct SlackNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        Self {
+            webhook_url: webhook_url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn send(&self, _notification: &Notification, text: &str) -> Result<()> {
+        let resp = self
+            .client
+            .post(&self.webhook_url)
+            .timeout(POST_TIMEOUT)
+            .json(&json!({ "text": text }))
+            .send()
+            .await?;
+        ensure_success(resp).await?;
+        Ok(())
+    }
+}
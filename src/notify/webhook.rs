@@ -0,0 +1,231 @@
+//! Posts notifications as JSON to arbitrary HTTP endpoints, so alerts can
+//! be routed into the user's own infrastructure.
+//!
+//! Each notification is one `POST` of
+//! `{"kind", "text", "sent_at", "data"}`, where `data` is the full
+//! [`AlertRecord`](crate::status::AlertRecord) for alerts and the
+//! template fields for operational events. Network errors, `429` and `5xx`
+//! responses are retried with exponential backoff; other statuses are not.
+//!
+//! With `ALERT_WEBHOOK_SECRET` set every request carries
+//! `X-White-Shark-Timestamp` (unix seconds) and
+//! `X-White-Shark-Signature: sha256=<hex>`, the HMAC-SHA256 of
+//! `<timestamp>.<body>` under the secret. Receivers should recompute it and
+//! reject stale timestamps.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Map, Value};
+use sha2::Sha256;
+use tracing::warn;
+
+use super::{Notification, Notifier, WebhookConfig};
+use crate::error::{Error, Result};
+use crate::utils::http::ensure_success;
+
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest wait between two attempts, however many retries are configured
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+pub const TIMESTAMP_HEADER: &str = "X-White-Shark-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-White-Shark-Signature";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The generic webhook target: the `ALERT_` variables of
+/// [`WebhookConfig::from_env`], where `ALERT_WEBHOOK_URL` may list several
+/// comma separated URLs, plus the delivery settings below.
+#[derive(Debug, Clone)]
+pub struct JsonWebhookConfig {
+    pub target: WebhookConfig,
+    pub urls: Vec<String>,
+    /// HMAC-SHA256 key the requests are signed with; unsigned when unset
+    pub secret: Option<String>,
+    /// Attempts after the first before a delivery is given up
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each one after
+    pub initial_backoff_ms: u64,
+}
+
+impl JsonWebhookConfig {
+    /// Reads `ALERT_WEBHOOK_URL` and the other `ALERT_` target variables,
+    /// `ALERT_WEBHOOK_SECRET`, `ALERT_WEBHOOK_MAX_RETRIES` (default 3) and
+    /// `ALERT_WEBHOOK_BACKOFF_MS` (default 500). `None` without a URL.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(target) = WebhookConfig::from_env("ALERT")? else {
+            return Ok(None);
+        };
+        let urls: Vec<String> = target
+            .webhook_url
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+            .collect();
+        if urls.is_empty() {
+            return Err(Error::Config("ALERT_WEBHOOK_URL lists no URLs".to_string()));
+        }
+        let secret = crate::config::var("ALERT_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty());
+        let max_retries = match crate::config::var("ALERT_WEBHOOK_MAX_RETRIES") {
+            Ok(v) => v
+                .parse()
+                .map_err(|_| Error::Config(format!("Invalid ALERT_WEBHOOK_MAX_RETRIES '{}'", v)))?,
+            Err(_) => 3,
+        };
+        let initial_backoff_ms = match crate::config::var("ALERT_WEBHOOK_BACKOFF_MS") {
+            Ok(v) => v
+                .parse()
+                .map_err(|_| Error::Config(format!("Invalid ALERT_WEBHOOK_BACKOFF_MS '{}'", v)))?,
+            Err(_) => 500,
+        };
+        Ok(Some(Self {
+            target,
+            urls,
+            secret,
+            max_retries,
+            initial_backoff_ms,
+        }))
+    }
+}
+
+pub struct JsonWebhookNotifier {
+    urls: Vec<String>,
+    secret: Option<String>,
+    max_retries: u32,
+    initial_backoff: Duration,
+    client: reqwest::Client,
+}
+
+impl JsonWebhookNotifier {
+    pub fn new(config: &JsonWebhookConfig) -> Self {
+        Self {
+            urls: config.urls.clone(),
+            secret: config.secret.clone(),
+            max_retries: config.max_retries,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Posts `body` to `url`, retrying transient failures.
+    async fn deliver(&self, url: &str, body: &[u8]) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.post(url, body).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < self.max_retries && retryable(&e) => {
+                    let delay = self.backoff(attempt);
+                    warn!(
+                        "Webhook post to {} failed ({}), retrying in {:?} ({}/{})",
+                        url,
+                        e,
+                        delay,
+                        attempt + 1,
+                        self.max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn post(&self, url: &str, body: &[u8]) -> Result<()> {
+        let mut request = self
+            .client
+            .post(url)
+            .timeout(POST_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(secret) = &self.secret {
+            // Signed per attempt so retries carry a fresh timestamp
+            let timestamp = Utc::now().timestamp().to_string();
+            request = request
+                .header(TIMESTAMP_HEADER, &timestamp)
+                .header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, &timestamp, body)));
+        }
+        let resp = request.body(body.to_vec()).send().await?;
+        ensure_success(resp).await?;
+        Ok(())
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF)
+    }
+}
+
+#[async_trait]
+impl Notifier for JsonWebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, notification: &Notification, text: &str) -> Result<()> {
+        let body = serde_json::to_vec(&payload(notification, text))?;
+        let results = join_all(self.urls.iter().map(|url| self.deliver(url, &body))).await;
+
+        let mut failed = 0;
+        let mut last_error = None;
+        for (url, result) in self.urls.iter().zip(results) {
+            if let Err(e) = result {
+                warn!("Giving up on webhook post to {}: {}", url, e);
+                failed += 1;
+                last_error = Some(e);
+            }
+        }
+        match last_error {
+            None => Ok(()),
+            Some(e) if self.urls.len() == 1 => Err(e),
+            Some(e) => Err(Error::Http(format!(
+                "{} of {} webhook URLs failed, last: {}",
+                failed,
+                self.urls.len(),
+                e
+            ))),
+        }
+    }
+}
+
+fn payload(notification: &Notification, text: &str) -> Value {
+    let data = match notification {
+        Notification::Alert(alert) => serde_json::to_value(alert).unwrap_or(Value::Null),
+        _ => Value::Object(
+            notification
+                .fields()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), Value::String(value)))
+                .collect::<Map<_, _>>(),
+        ),
+    };
+    json!({
+        "kind": notification.kind(),
+        "text": text,
+        "sent_at": Utc::now().to_rfc3339(),
+        "data": data,
+    })
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>` under `secret`.
+pub fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Network errors, rate limiting and server errors; a `4xx` means the
+/// receiver rejected the payload and would again.
+fn retryable(error: &Error) -> bool {
+    match error {
+        Error::Request(_) => true,
+        Error::HttpStatus { status, .. } => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
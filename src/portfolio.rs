@@ -0,0 +1,270 @@
+//! Live positions and P&L per Kalshi market, built from execution fills.
+//!
+//! Every fill the executor or the trader sees, real or from paper trading,
+//! moves one position. Trading on Kalshi, the positions it reports are loaded at
+//! startup so holdings from before a restart count too. Positions are kept in YES terms: buying NO at `p` is selling
+//! YES at `1 - p`, so a market nets to one signed contract count with an
+//! average YES price. Reducing a position realizes P&L against that
+//! average; what is left is marked at the YES mid, or the last trade when
+//! the book is one-sided. Fees are not in the fill and are ignored.
+//!
+//! Marked positions are exported as metrics and logged every
+//! `PORTFOLIO_MARK_INTERVAL_SECS`, and written to `position_snapshots`
+//! every `PORTFOLIO_SNAPSHOT_INTERVAL_SECS` when a database is configured.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{error, info};
+
+use crate::config::PortfolioConfig;
+use crate::db::main::Db;
+use crate::db::records::PositionSnapshotRecord;
+use crate::exchanges::kalshi::models::{KalshiFill, KalshiMarketPosition, OrderSide};
+use crate::metrics::{self, names};
+use crate::state::KalshiState;
+
+#[derive(Debug, Clone)]
+pub struct MarketPosition {
+    pub ticker: String,
+    /// YES contracts held; negative is NO
+    pub position: i64,
+    /// Average YES price of the open position, dollars
+    pub avg_price: f64,
+    pub realized_pnl: f64,
+    /// Last YES price the position was marked at
+    pub mark: Option<f64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MarketPosition {
+    fn new(ticker: &str) -> Self {
+        Self {
+            ticker: ticker.to_string(),
+            position: 0,
+            avg_price: 0.0,
+            realized_pnl: 0.0,
+            mark: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Moves the position by `delta` YES contracts at `price`.
+    fn apply(&mut self, delta: i64, price: f64) {
+        if delta == 0 {
+            return;
+        }
+        if self.position == 0 || self.position.signum() == delta.signum() {
+            let held = self.position.unsigned_abs() as f64;
+            let added = delta.unsigned_abs() as f64;
+            self.avg_price = (self.avg_price * held + price * added) / (held + added);
+            self.position += delta;
+        } else {
+            let closed = delta.unsigned_abs().min(self.position.unsigned_abs()) as f64;
+            self.realized_pnl += closed * (price - self.avg_price) * self.position.signum() as f64;
+            let before = self.position;
+            self.position += delta;
+            if self.position == 0 {
+                self.avg_price = 0.0;
+            } else if self.position.signum() != before.signum() {
+                // Flipped through flat; the rest opened at this price
+                self.avg_price = price;
+            }
+        }
+        self.updated_at = Utc::now();
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        match self.mark {
+            Some(mark) => self.position as f64 * (mark - self.avg_price),
+            None => 0.0,
+        }
+    }
+}
+
+/// Positions of one execution backend, by market ticker.
+pub struct Portfolio {
+    /// `kalshi` or `paper`, after the execution backend
+    mode: &'static str,
+    positions: Mutex<HashMap<String, MarketPosition>>,
+}
+
+impl Portfolio {
+    pub fn new(mode: &'static str) -> Self {
+        Self {
+            mode,
+            positions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn mode(&self) -> &'static str {
+        self.mode
+    }
+
+    /// Starts from the positions Kalshi reports held. Each is taken at its
+    /// cost and marked there until the next mark; P&L realized before the
+    /// restart is not carried over.
+    pub fn seed(&self, held: &[KalshiMarketPosition]) {
+        let mut positions = self.positions.lock().unwrap_or_else(|e| e.into_inner());
+        for held in held.iter().filter(|h| h.position != 0) {
+            // Exposure is what the side held cost, in cents
+            let side_price = held.market_exposure as f64 / 100.0 / held.position.unsigned_abs() as f64;
+            let yes_price = if held.position > 0 { side_price } else { 1.0 - side_price };
+            let mut position = MarketPosition::new(&held.ticker);
+            position.apply(held.position, yes_price);
+            position.mark = Some(yes_price);
+            info!(
+                "📒 {} {} starts at {} YES @ {:.4}",
+                self.mode, position.ticker, position.position, position.avg_price
+            );
+            positions.insert(held.ticker.clone(), position);
+        }
+    }
+
+    pub fn record_fill(&self, fill: &KalshiFill) {
+        let Some(yes_cents) = fill.yes_price else {
+            return;
+        };
+        let sell = fill.action.as_deref().is_some_and(|a| a.eq_ignore_ascii_case("sell"));
+        self.record(&fill.market_ticker, fill.side, sell, fill.count, yes_cents as f64 / 100.0);
+    }
+
+    /// Moves `ticker` by `count` contracts of `side` bought, or sold, at
+    /// `yes_price` dollars.
+    pub fn record(&self, ticker: &str, side: OrderSide, sell: bool, count: i64, yes_price: f64) {
+        // Buying YES or selling NO adds YES contracts
+        let sign = match (side, sell) {
+            (OrderSide::Yes, false) | (OrderSide::No, true) => 1,
+            (OrderSide::Yes, true) | (OrderSide::No, false) => -1,
+        };
+        let mut positions = self.positions.lock().unwrap_or_else(|e| e.into_inner());
+        let position = positions
+            .entry(ticker.to_string())
+            .or_insert_with(|| MarketPosition::new(ticker));
+        position.apply(sign * count, yes_price);
+        info!(
+            "📒 {} {} now {} YES @ {:.4}, realized ${:.2}",
+            self.mode, position.ticker, position.position, position.avg_price, position.realized_pnl
+        );
+    }
+
+    /// YES contracts held on `ticker`; negative is NO.
+    pub fn position(&self, ticker: &str) -> i64 {
+        let positions = self.positions.lock().unwrap_or_else(|e| e.into_inner());
+        positions.get(ticker).map_or(0, |p| p.position)
+    }
+
+    /// Realized plus unrealized P&L over every market, at the last marks.
+    pub fn total_pnl(&self) -> f64 {
+        let positions = self.positions.lock().unwrap_or_else(|e| e.into_inner());
+        positions.values().map(|p| p.realized_pnl + p.unrealized_pnl()).sum()
+    }
+
+    pub fn positions(&self) -> Vec<MarketPosition> {
+        let positions = self.positions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out: Vec<MarketPosition> = positions.values().cloned().collect();
+        out.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+        out
+    }
+
+    /// Marks every position at its market's YES mid, or its last trade.
+    /// Markets no longer in `state` keep their last mark.
+    pub fn mark(&self, state: &KalshiState) {
+        let mut positions = self.positions.lock().unwrap_or_else(|e| e.into_inner());
+        for position in positions.values_mut() {
+            if let Some(mark) = mark_price(state, &position.ticker) {
+                position.mark = Some(mark);
+            }
+        }
+    }
+}
+
+fn mark_price(state: &KalshiState, ticker: &str) -> Option<f64> {
+    if let Some(book) = state.orderbooks.get(ticker) {
+        let quote = book.yes_quote();
+        if quote.bid > 0.0 && quote.ask > 0.0 {
+            return Some((quote.bid + quote.ask) / 2.0);
+        }
+    }
+    state.last_trades.get(ticker).map(|t| t.yes_price)
+}
+
+pub struct PortfolioTracker {
+    portfolio: Arc<Portfolio>,
+    state: Arc<KalshiState>,
+    db: Option<Arc<Db>>,
+    config: PortfolioConfig,
+}
+
+impl PortfolioTracker {
+    pub fn spawn(portfolio: Arc<Portfolio>, state: Arc<KalshiState>, db: Option<Arc<Db>>, config: PortfolioConfig) {
+        let tracker = Self {
+            portfolio,
+            state,
+            db,
+            config,
+        };
+        tokio::spawn(tracker.run());
+    }
+
+    async fn run(self) {
+        let mut mark = tokio::time::interval(Duration::from_secs(self.config.mark_interval_secs));
+        let mut snapshot = tokio::time::interval(Duration::from_secs(self.config.snapshot_interval_secs));
+        loop {
+            tokio::select! {
+                _ = mark.tick() => self.mark(),
+                _ = snapshot.tick() => self.snapshot().await,
+            }
+        }
+    }
+
+    fn mark(&self) {
+        self.portfolio.mark(&self.state);
+        let mode = self.portfolio.mode();
+        let positions = self.portfolio.positions();
+        for position in &positions {
+            let labels = [("market", position.ticker.as_str()), ("mode", mode)];
+            metrics::set(names::POSITION_CONTRACTS, &labels, position.position as f64);
+            metrics::set(names::POSITION_REALIZED_PNL, &labels, position.realized_pnl);
+            metrics::set(names::POSITION_UNREALIZED_PNL, &labels, position.unrealized_pnl());
+        }
+        if positions.iter().any(|p| p.position != 0) {
+            let realized: f64 = positions.iter().map(|p| p.realized_pnl).sum();
+            let unrealized: f64 = positions.iter().map(|p| p.unrealized_pnl()).sum();
+            info!(
+                "📒 {} P&L: realized ${:.2}, unrealized ${:.2} over {} markets",
+                mode,
+                realized,
+                unrealized,
+                positions.len()
+            );
+        }
+    }
+
+    async fn snapshot(&self) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let now = Utc::now();
+        let records: Vec<PositionSnapshotRecord> = self
+            .portfolio
+            .positions()
+            .into_iter()
+            .map(|p| PositionSnapshotRecord {
+                timestamp: now,
+                mode: self.portfolio.mode().to_string(),
+                position: p.position,
+                avg_price: p.avg_price,
+                realized_pnl: p.realized_pnl,
+                unrealized_pnl: p.unrealized_pnl(),
+                mark: p.mark,
+                ticker: p.ticker,
+            })
+            .collect();
+        if let Err(e) = db.insert_position_snapshots(&records).await {
+            error!("Failed to write {} position snapshots: {}", records.len(), e);
+        }
+    }
+}
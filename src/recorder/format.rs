@@ -0,0 +1,175 @@
+//! On-disk layout of recording files.
+//!
+//! ```text
+//! file   := FILE_MAGIC chunk*
+//! chunk  := chunk_header checksum zstd_frame
+//! header := magic u32 | compressed_len u32 | uncompressed_len u32 | record_count u32
+//!           | first_ts_micros i64 | last_ts_micros i64
+//! checksum := crc32 u32 of chunk_header and zstd_frame
+//! record := ts_micros i64 | source u8 | len u32 | bytes
+//! ```
+//!
+//! All integers are little-endian. Every chunk is an independent zstd frame,
+//! so a reader can start decoding at any chunk boundary. Files written before
+//! checksums were added start with [`FILE_MAGIC_V1`] and have none.
+//!
+//! Files written with an encryption key start with [`FILE_MAGIC_ENCRYPTED`]
+//! and replace each zstd frame with the frame sealed by
+//! [`crate::encryption`], bound to its encoded chunk header.
+//! `compressed_len` and the checksum then cover the sealed bytes, so the
+//! chunk headers and index stay readable without the key.
+
+use std::io::Write;
+
+use crate::error::{Error, Result};
+
+pub const FILE_MAGIC: &[u8; 8] = b"WSREC\x00\x00\x02";
+pub const FILE_MAGIC_V1: &[u8; 8] = b"WSREC\x00\x00\x01";
+pub const FILE_MAGIC_ENCRYPTED: &[u8; 8] = b"WSREC\x00\x00\x03";
+pub const CHUNK_MAGIC: u32 = u32::from_le_bytes(*b"WSCK");
+pub const CHUNK_HEADER_SIZE: usize = 32;
+pub const CHECKSUM_SIZE: usize = 4;
+pub const RECORD_HEADER_SIZE: usize = 13;
+pub const RECORDING_EXTENSION: &str = "wsrec";
+pub const INDEX_EXTENSION: &str = "wsidx";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FrameSource {
+    KalshiJson = 0,
+    BinanceSbe = 1,
+    /// A normalized [`MarketEvent`](crate::exchanges::MarketEvent) in its
+    /// versioned [`schema`](crate::exchanges::schema) encoding.
+    Event = 2,
+}
+
+impl FrameSource {
+    pub fn from_u8(v: u8) -> Result<Self> {
+        match v {
+            0 => Ok(FrameSource::KalshiJson),
+            1 => Ok(FrameSource::BinanceSbe),
+            2 => Ok(FrameSource::Event),
+            _ => Err(Error::Recording(format!("Unknown frame source: {}", v))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrameSource::KalshiJson => "kalshi",
+            FrameSource::BinanceSbe => "binance",
+            FrameSource::Event => "event",
+        }
+    }
+}
+
+/// A raw WebSocket payload as received from an exchange.
+#[derive(Debug, Clone)]
+pub struct RawFrame {
+    pub timestamp_micros: i64,
+    pub source: FrameSource,
+    pub data: Vec<u8>,
+}
+
+impl RawFrame {
+    pub fn encoded_len(&self) -> usize {
+        RECORD_HEADER_SIZE + self.data.len()
+    }
+
+    pub fn encode_into<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        out.write_all(&self.timestamp_micros.to_le_bytes())?;
+        out.write_all(&[self.source as u8])?;
+        out.write_all(&(self.data.len() as u32).to_le_bytes())?;
+        out.write_all(&self.data)
+    }
+
+    pub fn decode_all(mut buf: &[u8]) -> Result<Vec<RawFrame>> {
+        let mut frames = Vec::new();
+        while !buf.is_empty() {
+            if buf.len() < RECORD_HEADER_SIZE {
+                return Err(Error::Recording(format!(
+                    "Truncated record header: {} bytes left",
+                    buf.len()
+                )));
+            }
+            let timestamp_micros = i64::from_le_bytes(buf[0..8].try_into().unwrap());
+            let source = FrameSource::from_u8(buf[8])?;
+            let len = u32::from_le_bytes(buf[9..13].try_into().unwrap()) as usize;
+            let end = RECORD_HEADER_SIZE + len;
+            if buf.len() < end {
+                return Err(Error::Recording(format!(
+                    "Truncated record body: need {} bytes, have {} bytes",
+                    len,
+                    buf.len() - RECORD_HEADER_SIZE
+                )));
+            }
+            frames.push(RawFrame {
+                timestamp_micros,
+                source,
+                data: buf[RECORD_HEADER_SIZE..end].to_vec(),
+            });
+            buf = &buf[end..];
+        }
+        Ok(frames)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkHeader {
+    pub compressed_len: u32,
+    pub uncompressed_len: u32,
+    pub record_count: u32,
+    pub first_ts_micros: i64,
+    pub last_ts_micros: i64,
+}
+
+impl ChunkHeader {
+    pub fn encode(&self) -> [u8; CHUNK_HEADER_SIZE] {
+        let mut out = [0u8; CHUNK_HEADER_SIZE];
+        out[0..4].copy_from_slice(&CHUNK_MAGIC.to_le_bytes());
+        out[4..8].copy_from_slice(&self.compressed_len.to_le_bytes());
+        out[8..12].copy_from_slice(&self.uncompressed_len.to_le_bytes());
+        out[12..16].copy_from_slice(&self.record_count.to_le_bytes());
+        out[16..24].copy_from_slice(&self.first_ts_micros.to_le_bytes());
+        out[24..32].copy_from_slice(&self.last_ts_micros.to_le_bytes());
+        out
+    }
+
+    pub fn decode(buf: &[u8; CHUNK_HEADER_SIZE]) -> Result<Self> {
+        let magic = u32::from_le_bytes(buf[0..4].try_into().unwrap());
+        if magic != CHUNK_MAGIC {
+            return Err(Error::Recording(format!("Bad chunk magic: {:#010x}", magic)));
+        }
+        Ok(Self {
+            compressed_len: u32::from_le_bytes(buf[4..8].try_into().unwrap()),
+            uncompressed_len: u32::from_le_bytes(buf[8..12].try_into().unwrap()),
+            record_count: u32::from_le_bytes(buf[12..16].try_into().unwrap()),
+            first_ts_micros: i64::from_le_bytes(buf[16..24].try_into().unwrap()),
+            last_ts_micros: i64::from_le_bytes(buf[24..32].try_into().unwrap()),
+        })
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 (IEEE) of an encoded chunk header and its compressed payload.
+pub fn chunk_checksum(header: &[u8], payload: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in header.iter().chain(payload) {
+        crc = CRC32_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -0,0 +1,131 @@
+//! Sidecar `.wsidx` files mapping chunk time ranges to byte offsets in the
+//! matching `.wsrec` file.
+//!
+//! ```text
+//! index := INDEX_MAGIC entry*
+//! entry := first_ts_micros i64 | last_ts_micros i64 | offset u64
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::format::INDEX_EXTENSION;
+use super::reader::RecordingReader;
+use crate::error::{Error, Result};
+
+pub const INDEX_MAGIC: &[u8; 8] = b"WSIDX\x00\x00\x01";
+pub const INDEX_ENTRY_SIZE: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub first_ts_micros: i64,
+    pub last_ts_micros: i64,
+    pub offset: u64,
+}
+
+impl IndexEntry {
+    pub fn encode(&self) -> [u8; INDEX_ENTRY_SIZE] {
+        let mut out = [0u8; INDEX_ENTRY_SIZE];
+        out[0..8].copy_from_slice(&self.first_ts_micros.to_le_bytes());
+        out[8..16].copy_from_slice(&self.last_ts_micros.to_le_bytes());
+        out[16..24].copy_from_slice(&self.offset.to_le_bytes());
+        out
+    }
+
+    pub fn decode(buf: &[u8]) -> Self {
+        Self {
+            first_ts_micros: i64::from_le_bytes(buf[0..8].try_into().unwrap()),
+            last_ts_micros: i64::from_le_bytes(buf[8..16].try_into().unwrap()),
+            offset: u64::from_le_bytes(buf[16..24].try_into().unwrap()),
+        }
+    }
+}
+
+pub fn index_path_for(recording: &Path) -> PathBuf {
+    recording.with_extension(INDEX_EXTENSION)
+}
+
+/// Appends entries to the index of the recording currently being written.
+pub struct IndexWriter {
+    file: BufWriter<File>,
+}
+
+impl IndexWriter {
+    pub fn open(recording: &Path) -> Result<Self> {
+        let path = index_path_for(recording);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(INDEX_MAGIC)?;
+        }
+        Ok(Self {
+            file: BufWriter::new(file),
+        })
+    }
+
+    pub fn append(&mut self, entry: &IndexEntry) -> Result<()> {
+        self.file.write_all(&entry.encode())?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RecordingIndex {
+    pub entries: Vec<IndexEntry>,
+}
+
+impl RecordingIndex {
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut buf = Vec::new();
+        File::open(path)?.read_to_end(&mut buf)?;
+        if buf.len() < INDEX_MAGIC.len() || &buf[..INDEX_MAGIC.len()] != INDEX_MAGIC {
+            return Err(Error::Recording(format!("{} is not an index file", path.display())));
+        }
+
+        // A partially written trailing entry is ignored.
+        let entries = buf[INDEX_MAGIC.len()..]
+            .chunks_exact(INDEX_ENTRY_SIZE)
+            .map(IndexEntry::decode)
+            .collect();
+        Ok(Self { entries })
+    }
+
+    /// Rebuilds an index by scanning chunk headers of a recording.
+    pub fn build(recording: &Path) -> Result<Self> {
+        let mut reader = RecordingReader::open(recording)?;
+        let mut entries = Vec::new();
+        loop {
+            let offset = reader.offset();
+            match reader.next_raw_chunk()? {
+                Some((header, _)) => entries.push(IndexEntry {
+                    first_ts_micros: header.first_ts_micros,
+                    last_ts_micros: header.last_ts_micros,
+                    offset,
+                }),
+                None => break,
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Loads the sidecar index of `recording`, scanning the recording if the
+    /// index is missing or unreadable.
+    pub fn load_or_build(recording: &Path) -> Result<Self> {
+        Self::load(&index_path_for(recording)).or_else(|_| Self::build(recording))
+    }
+
+    /// Offset of the first chunk that may contain frames at or after `ts_micros`.
+    pub fn offset_for(&self, ts_micros: i64) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|e| e.last_ts_micros >= ts_micros)
+            .map(|e| e.offset)
+    }
+
+    pub fn time_range(&self) -> Option<(i64, i64)> {
+        let first = self.entries.first()?;
+        let last = self.entries.last()?;
+        Some((first.first_ts_micros, last.last_ts_micros))
+    }
+}
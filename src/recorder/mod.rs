@@ -0,0 +1,12 @@
+pub mod format;
+pub mod index;
+pub mod reader;
+pub mod verify;
+pub mod writer;
+
+pub use format::{FrameSource, RawFrame};
+pub use index::{IndexEntry, RecordingIndex};
+pub use reader::RecordingReader;
+pub use writer::{Recorder, RecorderHandle};
+
+pub const RECORDER_CHANNEL_BUFFER: usize = 100_000;
@@ -0,0 +1,169 @@
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use super::format::{
+    chunk_checksum, ChunkHeader, RawFrame, CHECKSUM_SIZE, CHUNK_HEADER_SIZE, FILE_MAGIC,
+    FILE_MAGIC_ENCRYPTED, FILE_MAGIC_V1, RECORDING_EXTENSION,
+};
+use super::index::RecordingIndex;
+use crate::encryption;
+use crate::error::{Error, Result};
+
+/// Sequential reader over a recording file, one chunk at a time.
+pub struct RecordingReader {
+    reader: BufReader<File>,
+    offset: u64,
+    /// Whether chunks carry a checksum, i.e. the file is not version 1
+    checksums: bool,
+    /// Whether chunk payloads are sealed; headers and offsets are readable
+    /// either way, only decoding needs the key
+    encrypted: bool,
+    path: PathBuf,
+}
+
+impl RecordingReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path.as_ref())?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        let (checksums, encrypted) = match &magic {
+            m if m == FILE_MAGIC => (true, false),
+            m if m == FILE_MAGIC_V1 => (false, false),
+            m if m == FILE_MAGIC_ENCRYPTED => (true, true),
+            _ => {
+                return Err(Error::Recording(format!(
+                    "{} is not a recording file",
+                    path.as_ref().display()
+                )))
+            }
+        };
+        Ok(Self {
+            reader,
+            offset: FILE_MAGIC.len() as u64,
+            checksums,
+            encrypted,
+            path: path.as_ref().to_path_buf(),
+        })
+    }
+
+    pub fn has_checksums(&self) -> bool {
+        self.checksums
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Byte offset of the next chunk header.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn seek(&mut self, offset: u64) -> Result<()> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.offset = offset;
+        Ok(())
+    }
+
+    /// Positions the reader on the first chunk that may hold frames at or
+    /// after `ts_micros`. Returns `false` if the recording ends before it.
+    pub fn seek_to_time(&mut self, index: &RecordingIndex, ts_micros: i64) -> Result<bool> {
+        match index.offset_for(ts_micros) {
+            Some(offset) => {
+                self.seek(offset)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Reads the next chunk header and its compressed payload, still sealed
+    /// in an encrypted file, or `None` at EOF. Fails on a checksum mismatch.
+    pub fn next_raw_chunk(&mut self) -> Result<Option<(ChunkHeader, Vec<u8>)>> {
+        let mut header_buf = [0u8; CHUNK_HEADER_SIZE];
+        match self.reader.read_exact(&mut header_buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let header = ChunkHeader::decode(&header_buf)?;
+
+        let mut checksum_buf = [0u8; CHECKSUM_SIZE];
+        if self.checksums {
+            self.reader.read_exact(&mut checksum_buf)?;
+        }
+        let mut compressed = vec![0u8; header.compressed_len as usize];
+        self.reader.read_exact(&mut compressed)?;
+
+        if self.checksums {
+            let expected = u32::from_le_bytes(checksum_buf);
+            let actual = chunk_checksum(&header_buf, &compressed);
+            if actual != expected {
+                return Err(Error::Recording(format!(
+                    "Checksum mismatch in chunk at offset {}: stored {:#010x}, computed {:#010x}",
+                    self.offset, expected, actual
+                )));
+            }
+            self.offset += CHECKSUM_SIZE as u64;
+        }
+        self.offset += (CHUNK_HEADER_SIZE + compressed.len()) as u64;
+
+        Ok(Some((header, compressed)))
+    }
+
+    /// Reads and decompresses the next chunk.
+    pub fn next_chunk(&mut self) -> Result<Option<(ChunkHeader, Vec<RawFrame>)>> {
+        match self.next_raw_chunk()? {
+            Some((header, compressed)) => {
+                let frames = self.decode(&header, &compressed)?;
+                Ok(Some((header, frames)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Frames of a payload from [`next_raw_chunk`](Self::next_raw_chunk),
+    /// opened with the configured key first if the file is encrypted.
+    pub fn decode(&self, header: &ChunkHeader, payload: &[u8]) -> Result<Vec<RawFrame>> {
+        if !self.encrypted {
+            return decode_chunk(header, payload);
+        }
+        let key = encryption::require_key(&self.path.display().to_string())?;
+        let compressed = key
+            .open(payload, &header.encode())
+            .map_err(|e| Error::Recording(e.to_string()))?;
+        decode_chunk(header, &compressed)
+    }
+}
+
+pub fn decode_chunk(header: &ChunkHeader, compressed: &[u8]) -> Result<Vec<RawFrame>> {
+    let raw = zstd::stream::decode_all(compressed)?;
+    if raw.len() != header.uncompressed_len as usize {
+        return Err(Error::Recording(format!(
+            "Chunk length mismatch: header says {} bytes, decoded {} bytes",
+            header.uncompressed_len,
+            raw.len()
+        )));
+    }
+    let frames = RawFrame::decode_all(&raw)?;
+    if frames.len() != header.record_count as usize {
+        return Err(Error::Recording(format!(
+            "Chunk record count mismatch: header says {}, decoded {}",
+            header.record_count,
+            frames.len()
+        )));
+    }
+    Ok(frames)
+}
+
+/// Recording files in `dir`, oldest first. File names are `YYYYMMDD-HH` so
+/// lexical order is chronological.
+pub fn recordings_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some(RECORDING_EXTENSION))
+        .collect();
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,117 @@
+//! Integrity checks for recordings before they are used for backtests.
+//!
+//! Every chunk is read back and decompressed, its checksum is verified
+//! (version 1 files have none and are only decoded), timestamps are checked
+//! to never run backwards, and the sidecar index, if any, must point at
+//! exactly the chunks found.
+
+use std::path::{Path, PathBuf};
+
+use super::index::{index_path_for, IndexEntry, RecordingIndex};
+use super::reader::{recordings_in, RecordingReader};
+use crate::error::Result;
+
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    pub path: PathBuf,
+    pub chunks: usize,
+    pub records: u64,
+    /// Chunks from a version 1 file, which could only be decoded
+    pub unchecked_chunks: usize,
+    pub problems: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Recordings under `path`, which is either a recording directory or a
+/// single file.
+pub fn targets(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_dir() {
+        recordings_in(path)
+    } else {
+        Ok(vec![path.to_path_buf()])
+    }
+}
+
+/// Checks one recording file. Reading stops at the first corrupt chunk,
+/// since chunk boundaries after it cannot be trusted.
+pub fn verify(path: &Path) -> VerifyReport {
+    let mut report = VerifyReport {
+        path: path.to_path_buf(),
+        chunks: 0,
+        records: 0,
+        unchecked_chunks: 0,
+        problems: Vec::new(),
+    };
+
+    let mut reader = match RecordingReader::open(path) {
+        Ok(r) => r,
+        Err(e) => {
+            report.problems.push(e.to_string());
+            return report;
+        }
+    };
+
+    let mut entries = Vec::new();
+    let mut last_ts = i64::MIN;
+    loop {
+        let offset = reader.offset();
+        let (header, compressed) = match reader.next_raw_chunk() {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                report.problems.push(format!("Chunk at offset {}: {}", offset, e));
+                break;
+            }
+        };
+        if let Err(e) = reader.decode(&header, &compressed) {
+            report.problems.push(format!("Chunk at offset {}: {}", offset, e));
+            break;
+        }
+
+        if header.first_ts_micros > header.last_ts_micros {
+            report.problems.push(format!(
+                "Chunk at offset {} ends before it starts ({} > {})",
+                offset, header.first_ts_micros, header.last_ts_micros
+            ));
+        }
+        if header.first_ts_micros < last_ts {
+            report.problems.push(format!(
+                "Chunk at offset {} starts at {}, before the previous chunk ended at {}",
+                offset, header.first_ts_micros, last_ts
+            ));
+        }
+        last_ts = last_ts.max(header.last_ts_micros);
+
+        report.chunks += 1;
+        report.records += header.record_count as u64;
+        if !reader.has_checksums() {
+            report.unchecked_chunks += 1;
+        }
+        entries.push(IndexEntry {
+            first_ts_micros: header.first_ts_micros,
+            last_ts_micros: header.last_ts_micros,
+            offset,
+        });
+    }
+
+    let index_path = index_path_for(path);
+    if index_path.exists() {
+        match RecordingIndex::load(&index_path) {
+            Ok(index) if index.entries != entries => report.problems.push(format!(
+                "Index {} does not match the recording ({} entries, {} chunks)",
+                index_path.display(),
+                index.entries.len(),
+                entries.len()
+            )),
+            Ok(_) => {}
+            Err(e) => report.problems.push(format!("Index {}: {}", index_path.display(), e)),
+        }
+    }
+
+    report
+}
@@ -0,0 +1,280 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tracing::{error, info, warn};
+
+use super::format::{
+    chunk_checksum, ChunkHeader, FrameSource, RawFrame, CHECKSUM_SIZE, FILE_MAGIC,
+    FILE_MAGIC_ENCRYPTED, FILE_MAGIC_V1, RECORDING_EXTENSION,
+};
+use super::index::{IndexEntry, IndexWriter};
+use super::RECORDER_CHANNEL_BUFFER;
+use crate::config::RecorderConfig;
+use crate::encryption::{self, EncryptionKey};
+use crate::error::{Error, Result};
+use crate::exchanges::schema;
+use crate::exchanges::MarketEvent;
+use crate::metrics::{self, names};
+
+/// Cheap cloneable handle the WebSocket readers use to hand frames to the
+/// recorder thread. Frames are dropped (and counted) if the recorder falls
+/// behind rather than applying backpressure to the feed.
+#[derive(Clone)]
+pub struct RecorderHandle {
+    tx: SyncSender<RawFrame>,
+}
+
+impl RecorderHandle {
+    pub fn record(&self, source: FrameSource, data: &[u8]) {
+        let frame = RawFrame {
+            timestamp_micros: Utc::now().timestamp_micros(),
+            source,
+            data: data.to_vec(),
+        };
+        if self.tx.try_send(frame).is_err() {
+            metrics::inc(names::RECORDER_DROPPED_FRAMES_TOTAL, &[("source", source.as_str())]);
+        }
+    }
+
+    pub fn record_event(&self, event: &MarketEvent) {
+        match schema::encode(event) {
+            Ok(data) => self.record(FrameSource::Event, &data),
+            Err(e) => warn!("Failed to encode {} event for recording: {}", event.kind(), e),
+        }
+    }
+
+    /// A sink channel journaling every event it receives into the recording
+    /// next to the raw frames.
+    pub fn event_sink(&self) -> tokio::sync::mpsc::Sender<MarketEvent> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<MarketEvent>(crate::sinks::SINK_CHANNEL_BUFFER);
+        crate::status::board().register_queue("event_journal", &tx);
+        let handle = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                handle.record_event(&event);
+            }
+        });
+        tx
+    }
+}
+
+struct OpenChunk {
+    encoder: zstd::stream::write::Encoder<'static, Vec<u8>>,
+    header: ChunkHeader,
+    opened_at: Instant,
+}
+
+/// Writes raw frames into hourly rotated recording files made of
+/// independently compressed zstd chunks.
+pub struct Recorder {
+    config: RecorderConfig,
+    file: Option<BufWriter<File>>,
+    file_offset: u64,
+    /// Whether the open file carries chunk checksums; appending to a file
+    /// written before they existed keeps its version 1 layout
+    checksums: bool,
+    /// Key sealing the open file's chunks; appending to a plaintext file
+    /// keeps it plaintext
+    key: Option<&'static EncryptionKey>,
+    index: Option<IndexWriter>,
+    file_hour: Option<String>,
+    chunk: Option<OpenChunk>,
+}
+
+impl Recorder {
+    fn new(config: &RecorderConfig) -> Self {
+        Self {
+            config: config.clone(),
+            file: None,
+            file_offset: 0,
+            checksums: true,
+            key: None,
+            index: None,
+            file_hour: None,
+            chunk: None,
+        }
+    }
+
+    pub fn spawn(config: &RecorderConfig) -> Result<RecorderHandle> {
+        std::fs::create_dir_all(&config.dir)?;
+        let (tx, rx) = sync_channel::<RawFrame>(RECORDER_CHANNEL_BUFFER);
+        let recorder = Self::new(config);
+
+        info!(
+            "🎙️ Recording raw frames to {} (zstd level {}, {} byte chunks)",
+            config.dir, config.compression_level, config.chunk_bytes
+        );
+
+        std::thread::Builder::new()
+            .name("recorder".into())
+            .spawn(move || recorder.run(rx))
+            .map_err(|e| Error::Recording(format!("Failed to spawn recorder thread: {}", e)))?;
+
+        Ok(RecorderHandle { tx })
+    }
+
+    /// Writes frames on the calling thread, keeping their own timestamps.
+    /// For offline producers such as the synthetic generator.
+    pub fn write_all<I: IntoIterator<Item = RawFrame>>(
+        config: &RecorderConfig,
+        frames: I,
+    ) -> Result<u64> {
+        std::fs::create_dir_all(&config.dir)?;
+        let mut recorder = Self::new(config);
+        let mut count = 0;
+        for frame in frames {
+            recorder.write_frame(&frame)?;
+            count += 1;
+        }
+        recorder.finish_chunk()?;
+        Ok(count)
+    }
+
+    fn run(mut self, rx: Receiver<RawFrame>) {
+        let chunk_timeout = Duration::from_millis(self.config.chunk_interval_ms);
+        loop {
+            match rx.recv_timeout(chunk_timeout) {
+                Ok(frame) => {
+                    if let Err(e) = self.write_frame(&frame) {
+                        error!("Failed to record frame: {}", e);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let chunk_expired = self
+                .chunk
+                .as_ref()
+                .map(|c| c.opened_at.elapsed() >= chunk_timeout)
+                .unwrap_or(false);
+            if chunk_expired {
+                if let Err(e) = self.finish_chunk() {
+                    error!("Failed to finish recording chunk: {}", e);
+                }
+            }
+        }
+
+        if let Err(e) = self.finish_chunk() {
+            error!("Failed to finish recording chunk: {}", e);
+        }
+        info!("Recorder shutting down");
+    }
+
+    fn write_frame(&mut self, frame: &RawFrame) -> Result<()> {
+        let hour = DateTime::from_timestamp_micros(frame.timestamp_micros)
+            .unwrap_or_else(Utc::now)
+            .format("%Y%m%d-%H")
+            .to_string();
+        if self.file_hour.as_deref() != Some(hour.as_str()) {
+            self.rotate(hour)?;
+        }
+
+        if self.chunk.is_none() {
+            self.chunk = Some(OpenChunk {
+                encoder: zstd::stream::write::Encoder::new(Vec::new(), self.config.compression_level)?,
+                header: ChunkHeader {
+                    compressed_len: 0,
+                    uncompressed_len: 0,
+                    record_count: 0,
+                    first_ts_micros: frame.timestamp_micros,
+                    last_ts_micros: frame.timestamp_micros,
+                },
+                opened_at: Instant::now(),
+            });
+        }
+
+        let chunk = self.chunk.as_mut().expect("chunk opened above");
+        frame.encode_into(&mut chunk.encoder)?;
+        chunk.header.uncompressed_len += frame.encoded_len() as u32;
+        chunk.header.record_count += 1;
+        chunk.header.last_ts_micros = frame.timestamp_micros;
+
+        if chunk.header.uncompressed_len as usize >= self.config.chunk_bytes {
+            self.finish_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn finish_chunk(&mut self) -> Result<()> {
+        let chunk = match self.chunk.take() {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+        let file = match self.file.as_mut() {
+            Some(f) => f,
+            None => {
+                warn!("Dropping recording chunk with no open file");
+                return Ok(());
+            }
+        };
+
+        let mut compressed = chunk.encoder.finish()?;
+        let mut header = chunk.header;
+        header.compressed_len = (compressed.len() + self.key.map_or(0, |_| encryption::SEAL_OVERHEAD)) as u32;
+        let encoded_header = header.encode();
+        if let Some(key) = self.key {
+            compressed = key.seal(&compressed, &encoded_header)?;
+        }
+
+        let chunk_offset = self.file_offset;
+        file.write_all(&encoded_header)?;
+        let mut written = (encoded_header.len() + compressed.len()) as u64;
+        if self.checksums {
+            file.write_all(&chunk_checksum(&encoded_header, &compressed).to_le_bytes())?;
+            written += CHECKSUM_SIZE as u64;
+        }
+        file.write_all(&compressed)?;
+        file.flush()?;
+
+        self.file_offset += written;
+        metrics::add(names::RECORDER_BYTES_WRITTEN_TOTAL, &[], written);
+
+        if let Some(index) = self.index.as_mut() {
+            index.append(&IndexEntry {
+                first_ts_micros: header.first_ts_micros,
+                last_ts_micros: header.last_ts_micros,
+                offset: chunk_offset,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self, hour: String) -> Result<()> {
+        self.finish_chunk()?;
+        if let Some(mut old) = self.file.take() {
+            old.flush()?;
+        }
+
+        let path = Path::new(&self.config.dir).join(format!("{}.{}", hour, RECORDING_EXTENSION));
+        let mut file = OpenOptions::new().read(true).create(true).append(true).open(&path)?;
+        if file.metadata()?.len() == 0 {
+            self.key = encryption::key();
+            file.write_all(if self.key.is_some() { FILE_MAGIC_ENCRYPTED } else { FILE_MAGIC })?;
+            self.checksums = true;
+        } else {
+            let mut magic = [0u8; 8];
+            file.read_exact(&mut magic)?;
+            self.checksums = &magic != FILE_MAGIC_V1;
+            self.key = if &magic == FILE_MAGIC_ENCRYPTED {
+                Some(encryption::require_key(&path.display().to_string())?)
+            } else {
+                if encryption::enabled() {
+                    warn!("Appending to unencrypted {}, left unencrypted", path.display());
+                }
+                None
+            };
+        }
+
+        info!("🎙️ Recording to {}", path.display());
+        self.file_offset = file.metadata()?.len();
+        self.index = Some(IndexWriter::open(&path)?);
+        self.file = Some(BufWriter::new(file));
+        self.file_hour = Some(hour);
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+//! Live config reload on SIGHUP.
+//!
+//! The config is read again (from its file when it came from one), diffed
+//! against the running one, and the Kalshi and Binance sections are handed
+//! to their clients, which resubscribe and swap thresholds in place. Other
+//! sections only take effect on restart.
+
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::config::{BinanceConfig, Config, KalshiConfig};
+use crate::error::Result;
+use crate::instruments;
+
+/// Next value from an optional channel; never resolves without one, so it
+/// can sit in a `select!` unconditionally.
+pub async fn next_update<T>(rx: &mut Option<mpsc::Receiver<T>>) -> Option<T> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+pub struct ConfigReloader {
+    current: Config,
+    kalshi_tx: mpsc::Sender<KalshiConfig>,
+    binance_tx: Option<mpsc::Sender<BinanceConfig>>,
+}
+
+impl ConfigReloader {
+    pub fn spawn(
+        current: Config,
+        kalshi_tx: mpsc::Sender<KalshiConfig>,
+        binance_tx: Option<mpsc::Sender<BinanceConfig>>,
+    ) {
+        let reloader = Self {
+            current,
+            kalshi_tx,
+            binance_tx,
+        };
+        tokio::spawn(reloader.run());
+    }
+
+    #[cfg(unix)]
+    async fn run(mut self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                warn!("Failed to install SIGHUP handler, config reload disabled: {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            info!("🔁 SIGHUP received, reloading config");
+            if let Err(e) = self.reload().await {
+                error!("Config reload failed, keeping the running config: {}", e);
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn run(self) {
+        warn!("Config reload needs SIGHUP, which this platform lacks");
+    }
+
+    async fn reload(&mut self) -> Result<()> {
+        let config = Config::reload(self.current.file.as_deref())?;
+
+        for (binance_symbol, kalshi_series) in &config.symbol_map {
+            instruments::registry().link(binance_symbol, kalshi_series);
+        }
+
+        if config.database.is_some() != self.current.database.is_some()
+            || config.server.is_some() != self.current.server.is_some()
+            || config.binance.is_some() != self.current.binance.is_some()
+        {
+            warn!("Database, server and Binance streaming changes take effect on restart");
+        }
+
+        if let Err(e) = self.kalshi_tx.send(config.kalshi.clone()).await {
+            error!("Kalshi client gone, reload not applied: {}", e);
+        }
+        if let (Some(tx), Some(binance)) = (&self.binance_tx, &config.binance) {
+            if let Err(e) = tx.send(binance.clone()).await {
+                error!("Binance client gone, reload not applied: {}", e);
+            }
+        }
+
+        self.current = config;
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use tracing::info;
+
+use super::control::{ReplayControl, ReplayStatus, ReplayUpdate};
+
+/// Admin routes for steering an active replay:
+/// `GET /replay` returns the status, `POST /replay` applies a [`ReplayUpdate`].
+pub fn router(control: Arc<ReplayControl>) -> Router {
+    Router::new()
+        .route("/replay", get(status).post(update))
+        .with_state(control)
+}
+
+async fn status(State(control): State<Arc<ReplayControl>>) -> Json<ReplayStatus> {
+    Json(control.status())
+}
+
+async fn update(
+    State(control): State<Arc<ReplayControl>>,
+    Json(update): Json<ReplayUpdate>,
+) -> Json<ReplayStatus> {
+    info!("⏯️ Replay update: {:?}", update);
+    control.apply(update);
+    Json(control.status())
+}
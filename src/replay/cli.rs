@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+#[cfg(feature = "http")]
+use super::api;
+use super::{frame_symbol, ReplayOptions, Replayer};
+#[cfg(feature = "http")]
+use crate::config::ServerConfig;
+use crate::config::{BinanceConfig, IcebergConfig, ImbalanceConfig};
+use crate::error::Result;
+use crate::exchanges::binance::iceberg::IcebergDetector;
+use crate::exchanges::binance::sbe::decoder::SbeDecoder;
+use crate::exchanges::binance::sbe::messages::SbeMessage;
+use crate::exchanges::depth::{self, BinanceDepth, ImbalanceDetector, OkxDepth};
+use crate::exchanges::schema;
+use crate::exchanges::{Exchange, MarketEvent};
+use crate::recorder::FrameSource;
+#[cfg(feature = "http")]
+use crate::server::auth::AdminAuth;
+#[cfg(feature = "http")]
+use crate::server::HttpServer;
+use crate::state::BinanceState;
+
+/// Plays a recording back to the log, optionally serving the replay control
+/// API on `admin_addr`. Shared by `white-shark replay` and the `replay` bin.
+pub async fn run(options: ReplayOptions, admin_addr: Option<String>) -> Result<()> {
+    let replayer = Replayer::new(options);
+    let control = replayer.control();
+
+    #[cfg(feature = "http")]
+    if let Some(bind_addr) = admin_addr {
+        let router = HttpServer::router().merge(api::router(control.clone()));
+        let server = ServerConfig {
+            bind_addr,
+            auth: AdminAuth::from_env()?,
+        };
+        HttpServer::serve(&server, router).await?;
+    }
+    #[cfg(not(feature = "http"))]
+    if admin_addr.is_some() {
+        warn!("Admin address set but built without the http feature, replay control API disabled");
+    }
+
+    let binance = BinanceConfig {
+        imbalance: ImbalanceConfig::from_env()?,
+        depth_sources: match crate::config::var("IMBALANCE_SOURCES") {
+            Ok(spec) => depth::parse_sources(&spec)?,
+            Err(_) => Vec::new(),
+        },
+        ..BinanceConfig::default()
+    };
+    // Alerts are raised from the normalized events, as in a live run
+    let (_config_tx, config_watch) = watch::channel(binance.clone());
+    let state = Arc::new(BinanceState::new());
+    let binance_depth = BinanceDepth::new(binance.market, Some(state.clone()));
+    let binance_detector = ImbalanceDetector::new(binance_depth, config_watch.clone());
+    let okx_detector = ImbalanceDetector::new(OkxDepth, config_watch);
+    let mut icebergs = IcebergDetector::new(IcebergConfig::from_env()?);
+    let mut frames = replayer.spawn()?;
+    let decoder = SbeDecoder::new();
+    while let Some(frame) = frames.recv().await {
+        match frame.source {
+            FrameSource::KalshiJson => info!(
+                "{} kalshi {} ({} bytes)",
+                frame.timestamp_micros,
+                frame_symbol(&decoder, &frame).unwrap_or_default(),
+                frame.data.len()
+            ),
+            FrameSource::BinanceSbe => {
+                if let Ok(msg) = decoder.decode(&frame.data) {
+                    let flagged = icebergs.observe(&msg);
+                    msg.print_update();
+                    if let SbeMessage::DepthSnapshot(e) = &msg {
+                        state.icebergs.insert(e.symbol.to_string(), flagged);
+                    }
+                    if let Some(event) = msg.to_event() {
+                        binance_detector.process(&event);
+                    }
+                }
+            }
+            FrameSource::Event => match schema::decode::<MarketEvent>(&frame.data) {
+                Ok(event) => {
+                    info!(
+                        "{} {} {} {}",
+                        frame.timestamp_micros,
+                        event.meta().exchange,
+                        event.kind(),
+                        event.meta().instrument
+                    );
+                    // Journaled Binance events repeat the raw frames above
+                    if event.meta().exchange != Exchange::Binance {
+                        okx_detector.process(&event);
+                    }
+                }
+                Err(e) => warn!("Undecodable event frame at {}: {}", frame.timestamp_micros, e),
+            },
+        }
+    }
+
+    info!("Done — {:?}", control.status());
+    Ok(())
+}
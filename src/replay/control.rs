@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+/// Replay settings that can be changed while a replay is running.
+///
+/// Shared between the replay thread and the admin API; every read happens per
+/// frame so updates take effect immediately.
+pub struct ReplayControl {
+    speed_bits: AtomicU64,
+    skip_gaps_ms: AtomicU64,
+    paused: AtomicBool,
+    symbols: RwLock<HashSet<String>>,
+    position_micros: AtomicI64,
+    frames_emitted: AtomicU64,
+    frames_filtered: AtomicU64,
+    finished: AtomicBool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayStatus {
+    pub speed: f64,
+    pub skip_gaps_ms: Option<u64>,
+    pub paused: bool,
+    pub symbols: Vec<String>,
+    pub position_micros: i64,
+    pub frames_emitted: u64,
+    pub frames_filtered: u64,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReplayUpdate {
+    pub speed: Option<f64>,
+    /// `0` disables gap skipping.
+    pub skip_gaps_ms: Option<u64>,
+    pub paused: Option<bool>,
+    /// An empty list replays every symbol.
+    pub symbols: Option<Vec<String>>,
+}
+
+impl ReplayControl {
+    pub fn new(speed: f64, skip_gaps_ms: Option<u64>, symbols: Vec<String>) -> Self {
+        Self {
+            speed_bits: AtomicU64::new(speed.to_bits()),
+            skip_gaps_ms: AtomicU64::new(skip_gaps_ms.unwrap_or(0)),
+            paused: AtomicBool::new(false),
+            symbols: RwLock::new(symbols.into_iter().map(|s| s.to_uppercase()).collect()),
+            position_micros: AtomicI64::new(0),
+            frames_emitted: AtomicU64::new(0),
+            frames_filtered: AtomicU64::new(0),
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    /// Playback speed multiplier; `0` replays as fast as possible.
+    pub fn speed(&self) -> f64 {
+        f64::from_bits(self.speed_bits.load(Ordering::Relaxed))
+    }
+
+    pub fn set_speed(&self, speed: f64) {
+        self.speed_bits.store(speed.max(0.0).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Gaps between frames longer than this are skipped instead of waited out.
+    pub fn skip_gaps_ms(&self) -> Option<u64> {
+        match self.skip_gaps_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(ms),
+        }
+    }
+
+    pub fn set_skip_gaps_ms(&self, ms: Option<u64>) {
+        self.skip_gaps_ms.store(ms.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn set_symbols(&self, symbols: Vec<String>) {
+        *self.symbols.write().unwrap() = symbols.into_iter().map(|s| s.to_uppercase()).collect();
+    }
+
+    /// Whether a frame for `symbol` passes the filter. Symbols match by
+    /// prefix, so a Kalshi series ticker selects all of its markets.
+    pub fn accepts(&self, symbol: Option<&str>) -> bool {
+        let symbols = self.symbols.read().unwrap();
+        match symbol {
+            _ if symbols.is_empty() => true,
+            Some(symbol) => {
+                let symbol = symbol.to_uppercase();
+                symbols.iter().any(|s| symbol.starts_with(s.as_str()))
+            }
+            None => true,
+        }
+    }
+
+    pub fn apply(&self, update: ReplayUpdate) {
+        if let Some(speed) = update.speed {
+            self.set_speed(speed);
+        }
+        if let Some(ms) = update.skip_gaps_ms {
+            self.set_skip_gaps_ms(Some(ms).filter(|ms| *ms > 0));
+        }
+        if let Some(paused) = update.paused {
+            self.set_paused(paused);
+        }
+        if let Some(symbols) = update.symbols {
+            self.set_symbols(symbols);
+        }
+    }
+
+    pub(crate) fn record_emitted(&self, ts_micros: i64) {
+        self.position_micros.store(ts_micros, Ordering::Relaxed);
+        self.frames_emitted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_filtered(&self) {
+        self.frames_filtered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn mark_finished(&self) {
+        self.finished.store(true, Ordering::Relaxed);
+    }
+
+    pub fn status(&self) -> ReplayStatus {
+        let mut symbols: Vec<String> = self.symbols.read().unwrap().iter().cloned().collect();
+        symbols.sort();
+        ReplayStatus {
+            speed: self.speed(),
+            skip_gaps_ms: self.skip_gaps_ms(),
+            paused: self.is_paused(),
+            symbols,
+            position_micros: self.position_micros.load(Ordering::Relaxed),
+            frames_emitted: self.frames_emitted.load(Ordering::Relaxed),
+            frames_filtered: self.frames_filtered.load(Ordering::Relaxed),
+            finished: self.finished.load(Ordering::Relaxed),
+        }
+    }
+}
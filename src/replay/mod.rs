@@ -0,0 +1,10 @@
+#[cfg(feature = "http")]
+pub mod api;
+pub mod cli;
+pub mod control;
+pub mod player;
+
+pub use control::{ReplayControl, ReplayStatus, ReplayUpdate};
+pub use player::{frame_symbol, ReplayOptions, Replayer};
+
+pub const REPLAY_CHANNEL_BUFFER: usize = 10_000;
@@ -0,0 +1,172 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use super::control::ReplayControl;
+use super::REPLAY_CHANNEL_BUFFER;
+use crate::error::{Error, Result};
+use crate::exchanges::binance::sbe::decoder::SbeDecoder;
+use crate::exchanges::schema;
+use crate::exchanges::MarketEvent;
+use crate::recorder::reader::recordings_in;
+use crate::recorder::{FrameSource, RawFrame, RecordingIndex, RecordingReader};
+
+const PAUSE_POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    pub dir: String,
+    pub from_micros: Option<i64>,
+    pub to_micros: Option<i64>,
+    pub speed: f64,
+    pub skip_gaps_ms: Option<u64>,
+    pub symbols: Vec<String>,
+}
+
+/// Plays recordings back in timestamp order, pacing frames by their recorded
+/// spacing divided by the current speed.
+pub struct Replayer {
+    options: ReplayOptions,
+    control: Arc<ReplayControl>,
+    decoder: SbeDecoder,
+}
+
+impl Replayer {
+    pub fn new(options: ReplayOptions) -> Self {
+        let control = Arc::new(ReplayControl::new(
+            options.speed,
+            options.skip_gaps_ms,
+            options.symbols.clone(),
+        ));
+        Self {
+            options,
+            control,
+            decoder: SbeDecoder::new(),
+        }
+    }
+
+    pub fn control(&self) -> Arc<ReplayControl> {
+        self.control.clone()
+    }
+
+    pub fn spawn(self) -> Result<mpsc::Receiver<RawFrame>> {
+        let (tx, rx) = mpsc::channel(REPLAY_CHANNEL_BUFFER);
+        std::thread::Builder::new()
+            .name("replay".into())
+            .spawn(move || {
+                if let Err(e) = self.run(tx) {
+                    error!("Replay failed: {}", e);
+                }
+            })
+            .map_err(|e| Error::Recording(format!("Failed to spawn replay thread: {}", e)))?;
+        Ok(rx)
+    }
+
+    pub fn run(&self, tx: mpsc::Sender<RawFrame>) -> Result<()> {
+        let files = recordings_in(Path::new(&self.options.dir))?;
+        info!("⏪ Replaying {} recording(s) from {}", files.len(), self.options.dir);
+
+        let mut clock: Option<(i64, Instant)> = None;
+        for path in files {
+            let index = RecordingIndex::load_or_build(&path)?;
+            if let Some((first, last)) = index.time_range() {
+                let before_start = self.options.from_micros.is_some_and(|from| last < from);
+                let after_end = self.options.to_micros.is_some_and(|to| first > to);
+                if before_start || after_end {
+                    continue;
+                }
+            }
+
+            let mut reader = RecordingReader::open(&path)?;
+            if let Some(from) = self.options.from_micros {
+                if !reader.seek_to_time(&index, from)? {
+                    continue;
+                }
+            }
+
+            while let Some((_, frames)) = reader.next_chunk()? {
+                for frame in frames {
+                    if self.options.from_micros.is_some_and(|from| frame.timestamp_micros < from) {
+                        continue;
+                    }
+                    if self.options.to_micros.is_some_and(|to| frame.timestamp_micros > to) {
+                        self.control.mark_finished();
+                        return Ok(());
+                    }
+                    if !self.control.accepts(frame_symbol(&self.decoder, &frame).as_deref()) {
+                        self.control.record_filtered();
+                        continue;
+                    }
+
+                    self.wait_for(&mut clock, frame.timestamp_micros);
+                    self.control.record_emitted(frame.timestamp_micros);
+                    if tx.blocking_send(frame).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        self.control.mark_finished();
+        info!("⏪ Replay finished: {:?}", self.control.status());
+        Ok(())
+    }
+
+    /// Sleeps until `ts_micros` is due relative to the replay clock. The clock
+    /// is re-anchored after pauses, speed changes and skipped gaps so none of
+    /// them cause a burst of catch-up frames.
+    fn wait_for(&self, clock: &mut Option<(i64, Instant)>, ts_micros: i64) {
+        let mut was_paused = false;
+        while self.control.is_paused() {
+            was_paused = true;
+            std::thread::sleep(PAUSE_POLL);
+        }
+
+        let speed = self.control.speed();
+        let (anchor_ts, anchor_at) = match *clock {
+            Some(anchor) if !was_paused && speed > 0.0 => anchor,
+            _ => {
+                *clock = Some((ts_micros, Instant::now()));
+                return;
+            }
+        };
+
+        let gap_micros = (ts_micros - anchor_ts).max(0) as u64;
+        if let Some(skip_ms) = self.control.skip_gaps_ms() {
+            if gap_micros > skip_ms * 1000 {
+                *clock = Some((ts_micros, Instant::now()));
+                return;
+            }
+        }
+
+        let due = anchor_at + Duration::from_micros((gap_micros as f64 / speed) as u64);
+        let now = Instant::now();
+        if due > now {
+            std::thread::sleep(due - now);
+        }
+        *clock = Some((ts_micros, due.max(now)));
+    }
+}
+
+/// Market ticker of a Kalshi frame or symbol of a Binance frame, if present.
+pub fn frame_symbol(decoder: &SbeDecoder, frame: &RawFrame) -> Option<String> {
+    match frame.source {
+        FrameSource::KalshiJson => serde_json::from_slice::<serde_json::Value>(&frame.data)
+            .ok()?
+            .get("msg")?
+            .get("market_ticker")?
+            .as_str()
+            .map(str::to_string),
+        FrameSource::BinanceSbe => decoder
+            .decode(&frame.data)
+            .ok()
+            .map(|m| m.symbol().to_string()),
+        FrameSource::Event => schema::decode::<MarketEvent>(&frame.data)
+            .ok()
+            .map(|e| e.meta().instrument.clone()),
+    }
+}
+
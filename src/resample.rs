@@ -0,0 +1,148 @@
+//! Fixed-interval resampling of Kalshi top of book.
+//!
+//! Ticks arrive whenever the book changes, so two markets (or a market and a
+//! Binance kline) never share timestamps. Resampling puts every ticker on the
+//! same epoch-aligned grid: each bar holds the last quote seen in its
+//! interval, and intervals without a quote repeat the previous bar
+//! (`samples == 0`) so joins against price bars have no holes.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::db::main::MarketDataRow;
+
+#[derive(Debug, Clone)]
+pub struct OddsSample {
+    pub ticker: String,
+    pub asset: String,
+    pub timestamp: DateTime<Utc>,
+    pub yes_bid: f64,
+    pub yes_ask: f64,
+    pub no_bid: f64,
+    pub no_ask: f64,
+}
+
+impl From<&MarketDataRow> for OddsSample {
+    fn from(row: &MarketDataRow) -> Self {
+        Self {
+            ticker: row.ticker.clone(),
+            asset: row.asset.clone(),
+            timestamp: row.timestamp,
+            yes_bid: row.yes_bid,
+            yes_ask: row.yes_ask,
+            no_bid: row.no_bid,
+            no_ask: row.no_ask,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OddsBar {
+    pub ticker: String,
+    pub asset: String,
+    /// Start of the interval, a multiple of the interval since the epoch.
+    pub bucket_start: DateTime<Utc>,
+    pub yes_bid: f64,
+    pub yes_ask: f64,
+    pub no_bid: f64,
+    pub no_ask: f64,
+    /// Quotes that fell in the interval; 0 for a forward-filled bar.
+    pub samples: u32,
+}
+
+impl OddsBar {
+    pub fn yes_mid(&self) -> f64 {
+        (self.yes_bid + self.yes_ask) / 2.0
+    }
+}
+
+struct OpenBar {
+    bucket: i64,
+    last: OddsSample,
+    samples: u32,
+}
+
+/// Streaming resampler. Feed samples in time order per ticker; samples
+/// older than a ticker's open bar are dropped.
+pub struct OddsResampler {
+    interval_micros: i64,
+    open: HashMap<String, OpenBar>,
+}
+
+impl OddsResampler {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval_micros: (interval.as_micros() as i64).max(1),
+            open: HashMap::new(),
+        }
+    }
+
+    fn bucket_of(&self, ts: DateTime<Utc>) -> i64 {
+        ts.timestamp_micros().div_euclid(self.interval_micros)
+    }
+
+    fn bar(&self, open: &OpenBar, bucket: i64, samples: u32) -> OddsBar {
+        let s = &open.last;
+        OddsBar {
+            ticker: s.ticker.clone(),
+            asset: s.asset.clone(),
+            bucket_start: DateTime::from_timestamp_micros(bucket * self.interval_micros)
+                .unwrap_or(s.timestamp),
+            yes_bid: s.yes_bid,
+            yes_ask: s.yes_ask,
+            no_bid: s.no_bid,
+            no_ask: s.no_ask,
+            samples,
+        }
+    }
+
+    /// Adds a sample, returning the bars it completes: the ticker's open bar
+    /// plus forward-filled bars for any empty intervals since.
+    pub fn push(&mut self, sample: OddsSample) -> Vec<OddsBar> {
+        let bucket = self.bucket_of(sample.timestamp);
+        let Some(open) = self.open.remove(&sample.ticker) else {
+            self.open.insert(sample.ticker.clone(), OpenBar { bucket, last: sample, samples: 1 });
+            return Vec::new();
+        };
+
+        if bucket < open.bucket {
+            self.open.insert(sample.ticker.clone(), open);
+            return Vec::new();
+        }
+        if bucket == open.bucket {
+            let samples = open.samples + 1;
+            self.open.insert(sample.ticker.clone(), OpenBar { bucket, last: sample, samples });
+            return Vec::new();
+        }
+
+        let mut bars = vec![self.bar(&open, open.bucket, open.samples)];
+        bars.extend((open.bucket + 1..bucket).map(|b| self.bar(&open, b, 0)));
+        self.open.insert(sample.ticker.clone(), OpenBar { bucket, last: sample, samples: 1 });
+        bars
+    }
+
+    /// Closes every open bar. The result is ordered by time, then ticker.
+    pub fn finish(self) -> Vec<OddsBar> {
+        let mut bars: Vec<OddsBar> = self
+            .open
+            .values()
+            .map(|open| self.bar(open, open.bucket, open.samples))
+            .collect();
+        bars.sort_by(|a, b| a.bucket_start.cmp(&b.bucket_start).then_with(|| a.ticker.cmp(&b.ticker)));
+        bars
+    }
+}
+
+/// Resamples a complete series in one go.
+pub fn resample<I>(samples: I, interval: Duration) -> Vec<OddsBar>
+where
+    I: IntoIterator<Item = OddsSample>,
+{
+    let mut resampler = OddsResampler::new(interval);
+    let mut bars: Vec<OddsBar> = samples.into_iter().flat_map(|s| resampler.push(s)).collect();
+    bars.extend(resampler.finish());
+    bars.sort_by(|a, b| a.bucket_start.cmp(&b.bucket_start).then_with(|| a.ticker.cmp(&b.ticker)));
+    bars
+}
@@ -0,0 +1,408 @@
+//! User-defined alert rules over a typed per-market context.
+//!
+//! Rules come from `ALERT_RULES` as `name: expression` pairs separated by
+//! `;`, e.g.
+//!
+//! ```text
+//! late_pressure: imbalance_5 > 50 and time_to_close < 300; wide: yes_spread >= 0.1
+//! ```
+//!
+//! An expression is comparisons of a context field against a number,
+//! joined by `and`, then `or` (`and` binds tighter). Comparisons are `<`,
+//! `<=`, `>`, `>=`, `==` and `!=`; a field without a value makes its
+//! comparison false. The fields are listed in [`FIELDS`].
+//!
+//! Every tracked Kalshi market is evaluated once per interval and a rule
+//! alerts when it starts matching. With `--explain` each alert also logs
+//! the evaluated value of every field the rule reads.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::info;
+
+use crate::calendar;
+use crate::config::RulesConfig;
+use crate::error::{Error, Result};
+use crate::exchanges::kalshi::models::OrderSide;
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::state::{BinanceState, KalshiState};
+use crate::status::{self, AlertRecord};
+use crate::tags;
+use crate::trader::exposure::market_terms;
+use crate::trader::positions::PositionManager;
+use crate::utils::event_id::{EventId, EventKind};
+
+/// Every field a rule can read, with what it holds.
+pub const FIELDS: &[(&str, &str)] = &[
+    ("yes_bid", "Best Kalshi YES bid, as priced in the book"),
+    ("yes_ask", "Best Kalshi YES ask"),
+    ("yes_mid", "Midpoint of the YES bid and ask"),
+    ("yes_spread", "YES ask minus YES bid"),
+    ("spot", "Binance mid of the linked symbol"),
+    ("spot_spread_bps", "Binance ask minus bid, in basis points of the mid"),
+    ("imbalance_5", "Binance bid/ask depth ratio over the top 5 levels"),
+    ("imbalance_10", "Binance bid/ask depth ratio over the top 10 levels"),
+    ("imbalance_all", "Binance bid/ask depth ratio over the whole snapshot"),
+    ("time_to_close", "Seconds until the market closes"),
+    ("volatility", "Annualized volatility assumed for the underlying"),
+    ("position", "Contracts held, positive YES and negative NO"),
+];
+
+/// What a rule sees of one Kalshi market and its linked Binance symbol.
+/// Fields are `None` where the feed has no value yet.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AlertContext {
+    pub market: String,
+    pub series: Option<String>,
+    /// Linked Binance symbol
+    pub symbol: Option<String>,
+    pub yes_bid: Option<f64>,
+    pub yes_ask: Option<f64>,
+    pub yes_mid: Option<f64>,
+    pub yes_spread: Option<f64>,
+    pub spot: Option<f64>,
+    pub spot_spread_bps: Option<f64>,
+    pub imbalance_5: Option<f64>,
+    pub imbalance_10: Option<f64>,
+    pub imbalance_all: Option<f64>,
+    pub time_to_close: Option<f64>,
+    pub volatility: Option<f64>,
+    pub position: Option<f64>,
+}
+
+impl AlertContext {
+    /// Value of the named field; `None` for unknown names as well.
+    pub fn get(&self, field: &str) -> Option<f64> {
+        match field {
+            "yes_bid" => self.yes_bid,
+            "yes_ask" => self.yes_ask,
+            "yes_mid" => self.yes_mid,
+            "yes_spread" => self.yes_spread,
+            "spot" => self.spot,
+            "spot_spread_bps" => self.spot_spread_bps,
+            "imbalance_5" => self.imbalance_5,
+            "imbalance_10" => self.imbalance_10,
+            "imbalance_all" => self.imbalance_all,
+            "time_to_close" => self.time_to_close,
+            "volatility" => self.volatility,
+            "position" => self.position,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    fn apply(&self, left: f64, right: f64) -> bool {
+        match self {
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Eq => "==",
+            Op::Ne => "!=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub field: String,
+    pub op: Op,
+    pub value: f64,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.field, self.op.as_str(), self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    /// Alternatives joined by `or`, each a list of comparisons joined by `and`
+    pub any: Vec<Vec<Comparison>>,
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    /// Parses `name: expression`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, expr) = s
+            .split_once(':')
+            .ok_or_else(|| Error::Config(format!("Alert rule '{}' is missing 'name:'", s.trim())))?;
+        let name = name.trim().to_string();
+        let invalid = |reason: String| Error::Config(format!("Alert rule '{}': {}", name, reason));
+
+        let mut any = Vec::new();
+        for alternative in split_keyword(expr, "or") {
+            let mut all = Vec::new();
+            for comparison in split_keyword(alternative, "and") {
+                all.push(parse_comparison(comparison).map_err(invalid)?);
+            }
+            any.push(all);
+        }
+        Ok(Rule { name, any })
+    }
+}
+
+/// Splits on a whole-word keyword.
+fn split_keyword<'a>(s: &'a str, keyword: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while let Some(pos) = s[i..].find(keyword) {
+        let at = i + pos;
+        let end = at + keyword.len();
+        let bounded = |idx: Option<&u8>| idx.is_none_or(|b| b.is_ascii_whitespace());
+        if bounded(at.checked_sub(1).and_then(|p| bytes.get(p))) && bounded(bytes.get(end)) {
+            parts.push(&s[start..at]);
+            start = end;
+        }
+        i = end;
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn parse_comparison(s: &str) -> std::result::Result<Comparison, String> {
+    let s = s.trim();
+    let ops = [("<=", Op::Le), (">=", Op::Ge), ("==", Op::Eq), ("!=", Op::Ne), ("<", Op::Lt), (">", Op::Gt)];
+    let (at, token, op) = ops
+        .iter()
+        .filter_map(|(token, op)| s.find(token).map(|at| (at, *token, *op)))
+        .min_by_key(|(at, token, _)| (*at, std::cmp::Reverse(token.len())))
+        .ok_or_else(|| format!("no comparison in '{}'", s))?;
+
+    let field = s[..at].trim();
+    if !FIELDS.iter().any(|(name, _)| *name == field) {
+        return Err(format!("unknown field '{}'", field));
+    }
+    let value = s[at + token.len()..].trim();
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    Ok(Comparison {
+        field: field.to_string(),
+        op,
+        value,
+    })
+}
+
+/// Outcome of one rule against one context.
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub fired: bool,
+    /// The matching alternative when fired, else every comparison, each
+    /// with the value it saw
+    pub values: Vec<(Comparison, Option<f64>)>,
+}
+
+impl Evaluation {
+    /// `field = value (op threshold)` per comparison, for `--explain`.
+    pub fn explain(&self) -> String {
+        self.values
+            .iter()
+            .map(|(c, value)| {
+                let value = value.map_or("n/a".to_string(), |v| format!("{}", v));
+                format!("{} = {} ({} {})", c.field, value, c.op.as_str(), c.value)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Rule {
+    pub fn evaluate(&self, ctx: &AlertContext) -> Evaluation {
+        let seen = |all: &[Comparison]| -> Vec<(Comparison, Option<f64>)> {
+            all.iter().map(|c| (c.clone(), ctx.get(&c.field))).collect()
+        };
+        for all in &self.any {
+            let matched = all
+                .iter()
+                .all(|c| ctx.get(&c.field).is_some_and(|v| c.op.apply(v, c.value)));
+            if matched {
+                return Evaluation {
+                    fired: true,
+                    values: seen(all),
+                };
+            }
+        }
+        Evaluation {
+            fired: false,
+            values: self.any.iter().flat_map(|all| seen(all)).collect(),
+        }
+    }
+}
+
+/// Evaluates the configured rules against every tracked Kalshi market.
+pub struct RuleEngine {
+    config: RulesConfig,
+    kalshi: Arc<KalshiState>,
+    binance: Option<Arc<BinanceState>>,
+    positions: PositionManager,
+    volatility: f64,
+    /// `(rule, market)` pairs currently matching
+    firing: HashSet<(String, String)>,
+}
+
+impl RuleEngine {
+    pub fn spawn(
+        config: RulesConfig,
+        kalshi: Arc<KalshiState>,
+        binance: Option<Arc<BinanceState>>,
+        positions: PositionManager,
+        volatility: f64,
+    ) {
+        let engine = Self {
+            config,
+            kalshi,
+            binance,
+            positions,
+            volatility,
+            firing: HashSet::new(),
+        };
+        tokio::spawn(engine.run());
+    }
+
+    async fn run(mut self) {
+        info!("📐 Evaluating {} alert rules", self.config.rules.len());
+        let mut interval = tokio::time::interval(Duration::from_millis(self.config.interval_ms));
+        loop {
+            interval.tick().await;
+            self.evaluate_all(Utc::now());
+        }
+    }
+
+    fn evaluate_all(&mut self, now: DateTime<Utc>) {
+        let markets: Vec<String> = self
+            .kalshi
+            .tracked_markets
+            .iter()
+            .map(|m| m.key().clone())
+            .filter(|ticker| !self.kalshi.is_retired(ticker))
+            .collect();
+        self.firing.retain(|(_, market)| markets.contains(market));
+
+        for market in markets {
+            let ctx = self.context(&market, now);
+            for rule in &self.config.rules {
+                let evaluation = rule.evaluate(&ctx);
+                let key = (rule.name.clone(), market.clone());
+                if !evaluation.fired {
+                    self.firing.remove(&key);
+                    continue;
+                }
+                if self.firing.insert(key) {
+                    self.alert(rule, &ctx, &evaluation, now);
+                }
+            }
+        }
+    }
+
+    /// Builds the context of `market` from the live state.
+    pub fn context(&self, market: &str, now: DateTime<Utc>) -> AlertContext {
+        let series = market.split('-').next().map(str::to_string);
+        let symbol = series.as_deref().and_then(|s| instruments::registry().binance_symbol(s));
+        let yes_bid = self.kalshi.get_top_bid(market);
+        let yes_ask = self.kalshi.get_top_ask(market);
+        let close_time = self.kalshi.tracked_markets.get(market).and_then(|m| market_terms(&m).1);
+
+        let spot_quote = symbol
+            .as_deref()
+            .zip(self.binance.as_ref())
+            .and_then(|(symbol, binance)| binance.get_price(symbol));
+        let (spot, spot_spread_bps) = match spot_quote.and_then(|q| q.bid.zip(q.ask)) {
+            Some((bid, ask)) => {
+                let mid = (bid + ask) / 2.0;
+                (Some(mid), (mid > 0.0).then(|| (ask - bid) / mid * 10_000.0))
+            }
+            None => (None, None),
+        };
+        let imbalance = symbol
+            .as_deref()
+            .zip(self.binance.as_ref())
+            .and_then(|(symbol, binance)| binance.get_imbalance(symbol));
+
+        let position = match self.positions.inventory(market) {
+            Some((OrderSide::Yes, contracts)) => contracts as f64,
+            Some((OrderSide::No, contracts)) => -(contracts as f64),
+            None => 0.0,
+        };
+
+        AlertContext {
+            market: market.to_string(),
+            series,
+            symbol,
+            yes_bid,
+            yes_ask,
+            yes_mid: yes_bid.zip(yes_ask).map(|(b, a)| (b + a) / 2.0),
+            yes_spread: yes_bid.zip(yes_ask).map(|(b, a)| a - b),
+            spot,
+            spot_spread_bps,
+            imbalance_5: imbalance.map(|i| i.top_5),
+            imbalance_10: imbalance.map(|i| i.top_10),
+            imbalance_all: imbalance.map(|i| i.all),
+            time_to_close: close_time.map(|t| (t - now).num_milliseconds() as f64 / 1000.0),
+            volatility: Some(self.volatility),
+            position: Some(position),
+        }
+    }
+
+    fn alert(&self, rule: &Rule, ctx: &AlertContext, evaluation: &Evaluation, now: DateTime<Utc>) {
+        let event_id = EventId::new(EventKind::Alert);
+        if self.config.explain {
+            info!(
+                "ALERT [{}]: rule {} fired on {}: {}",
+                event_id,
+                rule.name,
+                ctx.market,
+                evaluation.explain()
+            );
+        } else {
+            info!("ALERT [{}]: rule {} fired on {}", event_id, rule.name, ctx.market);
+        }
+        metrics::inc(names::RULE_ALERTS_TOTAL, &[("rule", rule.name.as_str())]);
+        status::board().record_alert(AlertRecord {
+            event_id: event_id.to_string(),
+            timestamp: now,
+            symbol: ctx.symbol.clone().unwrap_or_else(|| ctx.market.clone()),
+            kalshi_series: ctx.series.clone(),
+            message: format!("rule {} on {}: {}", rule.name, ctx.market, evaluation.explain()),
+            imbalance_ratio: None,
+            tags: tags::current().clone(),
+            calendar_event: calendar::active_event(),
+            direction: None,
+        });
+    }
+}
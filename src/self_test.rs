@@ -0,0 +1,308 @@
+//! Startup self-test: one pass over everything the process depends on.
+//!
+//! With `SELF_TEST` set, [`app::run`](crate::app::run) authenticates to
+//! Kalshi and Binance, fetches one tracked market, subscribes to it
+//! briefly, writes and reads back a database row and posts a test
+//! notification, then logs a pass/fail matrix before streaming. A failed
+//! check stops startup unless `SELF_TEST_ALLOW_FAILURES` is set. Checks
+//! for parts that are not configured are skipped. `white-shark self-test`
+//! runs the same checks and exits.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+#[cfg(feature = "sbe")]
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{error, info, warn};
+
+use crate::config::{BinanceConfig, Config, KalshiConfig};
+use crate::constants::KALSHI_WS_URL;
+use crate::db::main::Db;
+use crate::error::{Error, Result};
+#[cfg(feature = "sbe")]
+use crate::exchanges::binance::client::BinanceClient;
+use crate::exchanges::binance::rest::BinanceRest;
+use crate::exchanges::kalshi::api::KalshiApi;
+use crate::exchanges::kalshi::auth::KalshiAuth;
+use crate::exchanges::kalshi::websocket::KalshiWebSocket;
+use crate::status::{self, AlertRecord};
+use crate::tags;
+use crate::trader::venue::BinanceMarket;
+use crate::utils::event_id::{EventId, EventKind};
+
+/// Lease name the database check writes; never one the elector uses.
+const DB_CHECK_LEASE: &str = "self_test";
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfTestConfig {
+    /// Time allowed for each check
+    pub timeout_secs: u64,
+    /// Start anyway when a check fails
+    pub allow_failures: bool,
+}
+
+impl SelfTestConfig {
+    /// Reads `SELF_TEST`, `SELF_TEST_TIMEOUT_SECS` and
+    /// `SELF_TEST_ALLOW_FAILURES`. `None` unless enabled.
+    pub fn from_env() -> Result<Option<Self>> {
+        let flag = |name: &str| {
+            crate::config::var(name)
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false)
+        };
+        if !flag("SELF_TEST") {
+            return Ok(None);
+        }
+        let timeout_secs = match crate::config::var("SELF_TEST_TIMEOUT_SECS") {
+            Ok(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or_else(|| Error::Config(format!("Invalid SELF_TEST_TIMEOUT_SECS '{}'", v)))?,
+            Err(_) => 10,
+        };
+        Ok(Some(Self {
+            timeout_secs,
+            allow_failures: flag("SELF_TEST_ALLOW_FAILURES"),
+        }))
+    }
+}
+
+impl Default for SelfTestConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 10,
+            allow_failures: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass(String),
+    Fail(String),
+    Skip(String),
+}
+
+impl Outcome {
+    fn label(&self) -> &'static str {
+        match self {
+            Outcome::Pass(_) => "PASS",
+            Outcome::Fail(_) => "FAIL",
+            Outcome::Skip(_) => "SKIP",
+        }
+    }
+
+    fn detail(&self) -> &str {
+        match self {
+            Outcome::Pass(d) | Outcome::Fail(d) | Outcome::Skip(d) => d,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// Runs every check in order, logs the matrix and fails when a check did,
+/// unless failures are allowed.
+pub async fn run(config: &Config, self_test: SelfTestConfig) -> Result<Vec<CheckResult>> {
+    info!("🧪 Running startup self-test");
+    let timeout = Duration::from_secs(self_test.timeout_secs);
+    let mut results = Vec::new();
+
+    match KalshiAuth::create_auth(&config.kalshi) {
+        Ok(auth) => {
+            let auth = Arc::new(auth);
+            let api = KalshiApi::new(auth.clone());
+            results.push(check("kalshi_auth", timeout, kalshi_auth(&api)).await);
+            let market = check("kalshi_market", timeout, kalshi_market(&api, &config.kalshi)).await;
+            let ticker = match &market.outcome {
+                Outcome::Pass(ticker) => Some(ticker.clone()),
+                _ => None,
+            };
+            results.push(market);
+            results.push(check("kalshi_subscribe", timeout, kalshi_subscribe(auth, ticker)).await);
+        }
+        Err(e) => results.push(CheckResult {
+            name: "kalshi_auth",
+            outcome: Outcome::Fail(e.to_string()),
+            elapsed: Duration::ZERO,
+        }),
+    }
+
+    results.push(check("binance_auth", timeout, binance_auth(config.binance.as_ref())).await);
+    results.push(check("binance_subscribe", timeout, binance_subscribe(config.binance.as_ref())).await);
+    results.push(check("database", timeout, database(config)).await);
+    results.push(check("notification", timeout, notification()).await);
+
+    report(&results);
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|r| matches!(r.outcome, Outcome::Fail(_)))
+        .map(|r| r.name)
+        .collect();
+    if failed.is_empty() {
+        info!("🧪 Self-test passed");
+    } else if self_test.allow_failures {
+        warn!("🧪 Self-test failed ({}), starting anyway", failed.join(", "));
+    } else {
+        return Err(Error::Other(format!("Self-test failed: {}", failed.join(", "))));
+    }
+    Ok(results)
+}
+
+async fn check(name: &'static str, timeout: Duration, test: impl Future<Output = Result<Outcome>>) -> CheckResult {
+    let started = Instant::now();
+    let outcome = match tokio::time::timeout(timeout, test).await {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(e)) => Outcome::Fail(e.to_string()),
+        Err(_) => Outcome::Fail(format!("timed out after {}s", timeout.as_secs())),
+    };
+    CheckResult {
+        name,
+        outcome,
+        elapsed: started.elapsed(),
+    }
+}
+
+fn report(results: &[CheckResult]) {
+    let width = results.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    for result in results {
+        let line = format!(
+            "🧪 {} {:<width$} {:>6}ms  {}",
+            result.outcome.label(),
+            result.name,
+            result.elapsed.as_millis(),
+            result.outcome.detail(),
+            width = width
+        );
+        match result.outcome {
+            Outcome::Fail(_) => error!("{}", line),
+            _ => info!("{}", line),
+        }
+    }
+}
+
+async fn kalshi_auth(api: &KalshiApi) -> Result<Outcome> {
+    let positions = api.get_positions().await?;
+    Ok(Outcome::Pass(format!("{} positions", positions.len())))
+}
+
+/// The ticker of an open market of the first tracked series.
+async fn kalshi_market(api: &KalshiApi, config: &KalshiConfig) -> Result<Outcome> {
+    let Some(series) = config.tracked_symbols.first() else {
+        return Ok(Outcome::Skip("no tracked series".to_string()));
+    };
+    let resp = api.fetch_markets(Some("open"), Some(series), None, None, Some(1)).await?;
+    match resp.markets.into_iter().next() {
+        Some(market) => Ok(Outcome::Pass(market.ticker)),
+        None => Ok(Outcome::Fail(format!("no open {} market", series))),
+    }
+}
+
+async fn kalshi_subscribe(auth: Arc<KalshiAuth>, ticker: Option<String>) -> Result<Outcome> {
+    let Some(ticker) = ticker else {
+        return Ok(Outcome::Skip("no market to subscribe to".to_string()));
+    };
+    let mut ws = KalshiWebSocket::new(KALSHI_WS_URL, auth);
+    ws.connect().await?;
+    ws.subscribe_tickers(vec![ticker.clone()]).await?;
+    let outcome = loop {
+        match ws.recv().await? {
+            Some(msg) if msg.is_subscribed() => break Outcome::Pass(format!("subscribed to {}", ticker)),
+            Some(msg) if msg.error.is_some() => {
+                break Outcome::Fail(msg.error.unwrap_or_default());
+            }
+            Some(_) => continue,
+            None => break Outcome::Fail("connection closed".to_string()),
+        }
+    };
+    let _ = ws.disconnect().await;
+    Ok(outcome)
+}
+
+async fn binance_auth(config: Option<&BinanceConfig>) -> Result<Outcome> {
+    let Some(config) = config else {
+        return Ok(Outcome::Skip("Binance not configured".to_string()));
+    };
+    let Some(private_key_path) = &config.private_key_path else {
+        return Ok(Outcome::Skip("no BINANCE_PRIVATE_KEY_PATH, key checked on subscribe".to_string()));
+    };
+    let rest = BinanceRest::new(config.market)
+        .with_api_key(config.api_key.clone())
+        .with_private_key(private_key_path)?;
+    let path = match config.market {
+        BinanceMarket::Spot => "/api/v3/account",
+        BinanceMarket::Futures => "/fapi/v2/account",
+    };
+    let _: serde_json::Value = rest.get_signed(path, "").await?;
+    Ok(Outcome::Pass("signed account request".to_string()))
+}
+
+#[cfg(feature = "sbe")]
+async fn binance_subscribe(config: Option<&BinanceConfig>) -> Result<Outcome> {
+    let Some(config) = config else {
+        return Ok(Outcome::Skip("Binance not configured".to_string()));
+    };
+    let Some(symbol) = config.tracked_symbols.first() else {
+        return Ok(Outcome::Skip("no tracked symbols".to_string()));
+    };
+    let mut client = BinanceClient::new(config.clone());
+    client.connect(std::slice::from_ref(symbol)).await?;
+    let outcome = loop {
+        match client.recv_raw().await? {
+            Some(Message::Binary(_)) | Some(Message::Text(_)) => {
+                break Outcome::Pass(format!("data on {}", symbol));
+            }
+            Some(_) => continue,
+            None => break Outcome::Fail("connection closed".to_string()),
+        }
+    };
+    let _ = client.disconnect().await;
+    Ok(outcome)
+}
+
+#[cfg(not(feature = "sbe"))]
+async fn binance_subscribe(_config: Option<&BinanceConfig>) -> Result<Outcome> {
+    Ok(Outcome::Skip("built without the sbe feature".to_string()))
+}
+
+/// Writes a lease row and reads it back through the lease query.
+async fn database(config: &Config) -> Result<Outcome> {
+    let Some(database) = &config.database else {
+        return Ok(Outcome::Skip("DATABASE_URL not set".to_string()));
+    };
+    let db = Db::new(&database.url).await?;
+    db.create_leader_lease_table().await?;
+    let holder = format!("self-test-{}", Utc::now().timestamp_micros());
+    let held = db.try_acquire_lease(DB_CHECK_LEASE, &holder, 5).await?;
+    db.release_lease(DB_CHECK_LEASE, &holder).await?;
+    if held {
+        Ok(Outcome::Pass("lease row written and read".to_string()))
+    } else {
+        Ok(Outcome::Fail("lease row read back with another holder".to_string()))
+    }
+}
+
+/// Posts a notice to the status board, where every alert consumer reads.
+async fn notification() -> Result<Outcome> {
+    let event_id = EventId::new(EventKind::Notice);
+    status::board().record_alert(AlertRecord {
+        event_id: event_id.to_string(),
+        timestamp: Utc::now(),
+        symbol: "self-test".to_string(),
+        kalshi_series: None,
+        message: "Self-test notification".to_string(),
+        imbalance_ratio: None,
+        tags: tags::current().clone(),
+        calendar_event: None,
+        direction: None,
+    });
+    Ok(Outcome::Pass(format!("notice {} posted", event_id)))
+}
@@ -0,0 +1,163 @@
+//! Bearer token auth for the admin API.
+//!
+//! Tokens from `ADMIN_READ_TOKENS` may read every `GET` route, so a
+//! dashboard can poll state; tokens from `ADMIN_OPERATOR_TOKENS` may also
+//! call the routes that change something (any other method), such as
+//! steering a replay. `/health` stays open for probes, and so does the
+//! static dashboard page, which sends the token from its URL fragment
+//! (`/ui#token=...`) with its API calls. With neither var set the API is
+//! open, as before.
+
+use std::collections::HashMap;
+#[cfg(feature = "http")]
+use std::sync::Arc;
+
+#[cfg(feature = "http")]
+use axum::extract::{Request, State};
+#[cfg(feature = "http")]
+use axum::http::{header, StatusCode};
+#[cfg(feature = "http")]
+use axum::middleware::{self, Next};
+#[cfg(feature = "http")]
+use axum::response::{IntoResponse, Response};
+#[cfg(feature = "http")]
+use axum::Router;
+use http::Method;
+#[cfg(feature = "http")]
+use tracing::warn;
+
+use crate::error::{Error, Result};
+
+/// Shorter tokens are too easy to guess.
+pub const MIN_TOKEN_LEN: usize = 16;
+
+/// Paths served without a token; they expose no state.
+#[cfg(feature = "http")]
+const OPEN_PATHS: &[&str] = &["/health", "/ui"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    /// `GET` routes only
+    Read,
+    /// Every route
+    Operator,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Read => "read",
+            Role::Operator => "operator",
+        }
+    }
+
+    /// The role a request needs: reads for safe methods, operator otherwise.
+    pub fn required_for(method: &Method) -> Self {
+        match *method {
+            Method::GET | Method::HEAD | Method::OPTIONS => Role::Read,
+            _ => Role::Operator,
+        }
+    }
+}
+
+/// Token -> role. Empty means auth is off.
+#[derive(Clone, Default)]
+pub struct AdminAuth {
+    tokens: HashMap<String, Role>,
+}
+
+impl std::fmt::Debug for AdminAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminAuth").field("tokens", &self.tokens.len()).finish()
+    }
+}
+
+impl AdminAuth {
+    /// Reads the comma-separated `ADMIN_READ_TOKENS` and
+    /// `ADMIN_OPERATOR_TOKENS`. A token listed in both is an operator.
+    pub fn from_env() -> Result<Self> {
+        let mut auth = Self::default();
+        for (var, role) in [("ADMIN_READ_TOKENS", Role::Read), ("ADMIN_OPERATOR_TOKENS", Role::Operator)] {
+            let Ok(v) = crate::config::var(var) else {
+                continue;
+            };
+            for token in v.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                if token.len() < MIN_TOKEN_LEN {
+                    return Err(Error::Config(format!(
+                        "Invalid {}: tokens must be at least {} characters",
+                        var, MIN_TOKEN_LEN
+                    )));
+                }
+                auth.insert(token, role);
+            }
+        }
+        Ok(auth)
+    }
+
+    pub fn insert(&mut self, token: &str, role: Role) {
+        let entry = self.tokens.entry(token.to_string()).or_insert(role);
+        *entry = (*entry).max(role);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// The role `token` grants, comparing in constant time per token.
+    pub fn role_of(&self, token: &str) -> Option<Role> {
+        self.tokens
+            .iter()
+            .filter(|(known, _)| constant_time_eq(known.as_bytes(), token.as_bytes()))
+            .map(|(_, role)| *role)
+            .max()
+    }
+
+    /// `router` with every route but [`OPEN_PATHS`] behind the tokens; unchanged
+    /// when auth is off.
+    #[cfg(feature = "http")]
+    pub fn protect(&self, router: Router) -> Router {
+        if !self.is_enabled() {
+            return router;
+        }
+        router.layer(middleware::from_fn_with_state(Arc::new(self.clone()), authorize))
+    }
+}
+
+#[cfg(feature = "http")]
+async fn authorize(State(auth): State<Arc<AdminAuth>>, request: Request, next: Next) -> Response {
+    if OPEN_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    let required = Role::required_for(request.method());
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match token.and_then(|t| auth.role_of(t.trim())) {
+        Some(role) if role >= required => next.run(request).await,
+        Some(role) => {
+            warn!(
+                "Refused {} {} to a {} token, needs {}",
+                request.method(),
+                request.uri().path(),
+                role.as_str(),
+                required.as_str()
+            );
+            (StatusCode::FORBIDDEN, "operator token required").into_response()
+        }
+        None => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "missing or unknown token",
+        )
+            .into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
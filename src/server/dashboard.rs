@@ -0,0 +1,131 @@
+//! Read-only web dashboard: a single static page polling the JSON endpoints
+//! below. Mounted next to [`super::HttpServer::router`].
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+
+use crate::exchanges::kalshi::KalshiOrderbook;
+use crate::exchanges::PriceUpdate;
+use crate::state::{BinanceState, Exposure, KalshiState, PolymarketState, PortfolioState, ProbabilityGap};
+use crate::status::{self, AlertRecord, MonitorOutcome};
+
+const INDEX_HTML: &str = include_str!("dashboard.html");
+
+/// Levels per side returned by `/api/books`.
+const BOOK_DEPTH: usize = 10;
+
+#[derive(Clone)]
+pub struct DashboardState {
+    pub kalshi: Arc<KalshiState>,
+    pub binance: Option<Arc<BinanceState>>,
+    pub polymarket: Option<Arc<PolymarketState>>,
+    pub portfolio: Arc<PortfolioState>,
+}
+
+pub fn router(state: DashboardState) -> Router {
+    Router::new()
+        .route("/ui", get(index))
+        .route("/api/prices", get(prices))
+        .route("/api/books", get(books))
+        .route("/api/alerts", get(alerts))
+        .route("/api/monitors", get(monitors))
+        .route("/api/exposure", get(exposure))
+        .route("/api/probability-gaps", get(probability_gaps))
+        .with_state(state)
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn prices(State(state): State<DashboardState>) -> Json<Vec<PriceUpdate>> {
+    let mut prices: Vec<PriceUpdate> = state
+        .binance
+        .iter()
+        .flat_map(|b| b.best_bid_ask.iter().map(|e| e.value().clone()).collect::<Vec<_>>())
+        .collect();
+    prices.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    Json(prices)
+}
+
+async fn books(State(state): State<DashboardState>) -> Json<Vec<KalshiOrderbook>> {
+    let mut books: Vec<KalshiOrderbook> = state
+        .kalshi
+        .orderbooks
+        .iter()
+        .map(|e| {
+            let mut book = e.value().clone();
+            book.yes_bids.truncate(BOOK_DEPTH);
+            book.yes_asks.truncate(BOOK_DEPTH);
+            book.no_bids.truncate(BOOK_DEPTH);
+            book.no_asks.truncate(BOOK_DEPTH);
+            book
+        })
+        .collect();
+    books.sort_by(|a, b| a.market_ticker.cmp(&b.market_ticker));
+    Json(books)
+}
+
+/// Polymarket against Kalshi implied probability, per linked market.
+async fn probability_gaps(State(state): State<DashboardState>) -> Json<Vec<ProbabilityGap>> {
+    Json(
+        state
+            .polymarket
+            .as_ref()
+            .map(|p| p.probability_gaps(&state.kalshi))
+            .unwrap_or_default(),
+    )
+}
+
+async fn alerts() -> Json<Vec<AlertRecord>> {
+    Json(status::board().recent_alerts())
+}
+
+#[derive(Serialize)]
+struct MonitorsView {
+    active: Vec<ActiveMonitor>,
+    recent: Vec<MonitorOutcome>,
+}
+
+#[derive(Serialize)]
+struct ActiveMonitor {
+    id: String,
+    description: String,
+}
+
+async fn monitors() -> Json<MonitorsView> {
+    let board = status::board();
+    Json(MonitorsView {
+        active: board
+            .active_monitors()
+            .into_iter()
+            .map(|(id, description)| ActiveMonitor { id, description })
+            .collect(),
+        recent: board.recent_outcomes(),
+    })
+}
+
+#[derive(Serialize)]
+struct ExposureView {
+    underlyings: Vec<Exposure>,
+    worst_case_loss: f64,
+}
+
+async fn exposure(State(state): State<DashboardState>) -> Json<ExposureView> {
+    let mut underlyings: Vec<Exposure> = state
+        .portfolio
+        .exposures
+        .iter()
+        .map(|e| e.value().clone())
+        .collect();
+    underlyings.sort_by(|a, b| a.underlying.cmp(&b.underlying));
+    Json(ExposureView {
+        underlyings,
+        worst_case_loss: state.portfolio.worst_case_loss(),
+    })
+}
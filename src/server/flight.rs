@@ -0,0 +1,216 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint,
+    FlightInfo, HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use futures_util::stream::{self, BoxStream};
+use futures_util::{StreamExt, TryStreamExt};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{error, info};
+
+use crate::config::FlightConfig;
+use crate::db::main::{Db, MarketDataRow};
+use crate::error::{Error, Result};
+
+/// Arrow Flight endpoint over the `market_data` table.
+///
+/// Every ticker stored in the database is a flight: `ListFlights` enumerates
+/// them, `GetFlightInfo` takes a descriptor path of `[ticker]`, and `DoGet`
+/// takes the ticker as the ticket and streams its rows as record batches.
+pub struct FlightServer {
+    db: Arc<Db>,
+    schema: SchemaRef,
+}
+
+impl FlightServer {
+    pub fn new(db: Arc<Db>) -> Self {
+        Self {
+            db,
+            schema: Arc::new(market_data_schema()),
+        }
+    }
+
+    pub fn spawn(config: &FlightConfig, db: Arc<Db>) -> Result<()> {
+        let addr: SocketAddr = config
+            .bind_addr
+            .parse()
+            .map_err(|e| Error::Config(format!("Invalid FLIGHT_BIND_ADDR {}: {}", config.bind_addr, e)))?;
+        let service = FlightServiceServer::new(Self::new(db));
+
+        info!("🏹 Arrow Flight server listening on {}", addr);
+        tokio::spawn(async move {
+            if let Err(e) = Server::builder().add_service(service).serve(addr).await {
+                error!("Arrow Flight server error: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    fn flight_info(&self, ticker: &str) -> std::result::Result<FlightInfo, Status> {
+        let info = FlightInfo::new()
+            .try_with_schema(&self.schema)
+            .map_err(|e| Status::internal(e.to_string()))?
+            .with_descriptor(FlightDescriptor::new_path(vec![ticker.to_string()]))
+            .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new(ticker.to_string())));
+        Ok(info)
+    }
+}
+
+pub fn market_data_schema() -> Schema {
+    Schema::new(vec![
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("ticker", DataType::Utf8, false),
+        Field::new("asset", DataType::Utf8, false),
+        Field::new("yes_ask", DataType::Float64, false),
+        Field::new("yes_bid", DataType::Float64, false),
+        Field::new("no_ask", DataType::Float64, false),
+        Field::new("no_bid", DataType::Float64, false),
+    ])
+}
+
+pub fn market_data_batch(
+    schema: SchemaRef,
+    rows: &[MarketDataRow],
+) -> std::result::Result<RecordBatch, FlightError> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(
+            TimestampMicrosecondArray::from(
+                rows.iter().map(|r| r.timestamp.timestamp_micros()).collect::<Vec<_>>(),
+            )
+            .with_timezone("UTC"),
+        ),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.ticker.as_str()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.asset.as_str()))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| r.yes_ask))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| r.yes_bid))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| r.no_ask))),
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(|r| r.no_bid))),
+    ];
+
+    RecordBatch::try_new(schema, columns).map_err(FlightError::Arrow)
+}
+
+fn descriptor_ticker(descriptor: &FlightDescriptor) -> std::result::Result<String, Status> {
+    descriptor
+        .path
+        .first()
+        .cloned()
+        .ok_or_else(|| Status::invalid_argument("Descriptor path must be [ticker]"))
+}
+
+#[tonic::async_trait]
+impl FlightService for FlightServer {
+    type HandshakeStream = BoxStream<'static, std::result::Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, std::result::Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, std::result::Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, std::result::Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, std::result::Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, std::result::Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, std::result::Result<FlightData, Status>>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> std::result::Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("Handshake is not required"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> std::result::Result<Response<Self::ListFlightsStream>, Status> {
+        let tickers = self
+            .db
+            .fetch_all_tickers()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        let infos: Vec<_> = tickers.iter().map(|t| self.flight_info(t)).collect();
+        Ok(Response::new(stream::iter(infos).boxed()))
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> std::result::Result<Response<FlightInfo>, Status> {
+        let ticker = descriptor_ticker(request.get_ref())?;
+        Ok(Response::new(self.flight_info(&ticker)?))
+    }
+
+    async fn poll_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> std::result::Result<Response<PollInfo>, Status> {
+        Err(Status::unimplemented("Use GetFlightInfo"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> std::result::Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("Schema is included in FlightInfo"))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> std::result::Result<Response<Self::DoGetStream>, Status> {
+        let ticker = String::from_utf8(request.into_inner().ticket.to_vec())
+            .map_err(|_| Status::invalid_argument("Ticket must be a UTF-8 ticker"))?;
+
+        let rows = self
+            .db
+            .fetch_ticker_market_data(&ticker)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        info!("🏹 Serving {} market data rows for {} over Flight", rows.len(), ticker);
+
+        let batch = market_data_batch(self.schema.clone(), &rows);
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(self.schema.clone())
+            .build(stream::iter(vec![batch]))
+            .map_err(Status::from);
+
+        Ok(Response::new(stream.boxed()))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> std::result::Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("Flight endpoint is read-only"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> std::result::Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("No actions supported"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> std::result::Result<Response<Self::ListActionsStream>, Status> {
+        Ok(Response::new(stream::empty().boxed()))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> std::result::Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("Flight endpoint is read-only"))
+    }
+}
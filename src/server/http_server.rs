@@ -0,0 +1,70 @@
+//! The HTTP server: health, metrics, status and the kill switch, plus
+//! whatever routes are merged in.
+
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use crate::config::ServerConfig;
+use crate::error::{Error, Result};
+use crate::execution::risk;
+use crate::metrics::{registry, DASHBOARD_JSON};
+use crate::status::{self, StatusReport};
+
+pub struct HttpServer;
+
+impl HttpServer {
+    pub fn router() -> Router {
+        Router::new()
+            .route("/health", get(health))
+            .route("/metrics", get(metrics))
+            .route("/dashboard.json", get(dashboard))
+            .route("/status", get(status))
+            .merge(risk::router())
+    }
+
+    pub async fn spawn(config: &ServerConfig) -> Result<()> {
+        Self::serve(config, Self::router()).await
+    }
+
+    /// Serves `router`, typically [`HttpServer::router`] merged with extra routes.
+    pub async fn serve(config: &ServerConfig, router: Router) -> Result<()> {
+        let listener = TcpListener::bind(&config.bind_addr)
+            .await
+            .map_err(|e| Error::Other(format!("Failed to bind {}: {}", config.bind_addr, e)))?;
+        info!("📈 HTTP server listening on {}", config.bind_addr);
+        if config.auth.is_enabled() {
+            info!("🔐 Admin API requires a read or operator token");
+        }
+        let router = config.auth.protect(router);
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router).await {
+                error!("HTTP server error: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+async fn health() -> &'static str {
+    "ok"
+}
+
+async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        registry().render(),
+    )
+}
+
+async fn status() -> Json<StatusReport> {
+    Json(status::board().report())
+}
+
+async fn dashboard() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], DASHBOARD_JSON)
+}
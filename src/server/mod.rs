@@ -0,0 +1,12 @@
+pub mod auth;
+#[cfg(feature = "dashboard")]
+pub mod dashboard;
+#[cfg(feature = "grpc")]
+pub mod flight;
+#[cfg(feature = "http")]
+mod http_server;
+
+#[cfg(feature = "grpc")]
+pub use flight::FlightServer;
+#[cfg(feature = "http")]
+pub use http_server::HttpServer;
@@ -0,0 +1,99 @@
+//! Graceful shutdown on SIGINT or SIGTERM.
+//!
+//! [`coordinator`] broadcasts the stop to every task that subscribed: the
+//! Binance client closes its WebSocket, writers drain their queues into the
+//! database. Tasks that must finish before the process exits register their
+//! handle with [`Shutdown::track`], and [`Shutdown::drain`] waits for them.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+pub struct Shutdown {
+    tx: broadcast::Sender<()>,
+    triggered: AtomicBool,
+    tasks: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+}
+
+impl Shutdown {
+    fn new() -> Self {
+        let (tx, _) = broadcast::channel(1);
+        Self {
+            tx,
+            triggered: AtomicBool::new(false),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.tx.subscribe()
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.triggered.load(Ordering::SeqCst)
+    }
+
+    /// Tells every subscriber to stop; later calls do nothing.
+    pub fn trigger(&self) {
+        if !self.triggered.swap(true, Ordering::SeqCst) {
+            let _ = self.tx.send(());
+        }
+    }
+
+    /// Has [`drain`](Self::drain) wait for `handle` before the process exits.
+    pub fn track(&self, name: &'static str, handle: JoinHandle<()>) {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner()).push((name, handle));
+    }
+
+    /// Waits up to `timeout` for the tracked tasks, logging any left behind.
+    pub async fn drain(&self, timeout: Duration) {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+        let deadline = tokio::time::Instant::now() + timeout;
+        for (name, handle) in tasks {
+            match tokio::time::timeout_at(deadline, handle).await {
+                Ok(Ok(())) => info!("✅ {} stopped", name),
+                Ok(Err(e)) => warn!("{} failed while stopping: {}", name, e),
+                Err(_) => warn!("{} did not stop within {}s", name, timeout.as_secs()),
+            }
+        }
+    }
+}
+
+pub fn coordinator() -> &'static Shutdown {
+    static SHUTDOWN: OnceLock<Shutdown> = OnceLock::new();
+    SHUTDOWN.get_or_init(Shutdown::new)
+}
+
+/// Resolves once shutdown is triggered, straight away when it already was,
+/// so receivers subscribed after the broadcast still stop.
+pub async fn requested(rx: &mut broadcast::Receiver<()>) {
+    if coordinator().is_triggered() {
+        return;
+    }
+    let _ = rx.recv().await;
+}
+
+/// Resolves on SIGTERM (what systemd and container runtimes send) or Ctrl-C.
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => {}
+                    _ = tokio::signal::ctrl_c() => {}
+                }
+                return;
+            }
+            Err(e) => warn!("Failed to install SIGTERM handler: {}", e),
+        }
+    }
+
+    let _ = tokio::signal::ctrl_c().await;
+}
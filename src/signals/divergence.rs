@@ -0,0 +1,73 @@
+//! The same pair quoted at least `max_bps` apart on two venues.
+//!
+//! Pairs are matched across venues by their name without separators, as
+//! the latency races do, and a venue's mid is only compared while it is
+//! fresher than [`MAX_QUOTE_AGE_MS`].
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::{spot_mid, AlertKind, Signal, SignalAlert};
+use crate::exchanges::event::{Exchange, MarketEvent};
+use crate::latency_race::normalize;
+
+/// Older mids no longer say where a venue is quoting.
+pub const MAX_QUOTE_AGE_MS: i64 = 5_000;
+
+pub struct DivergenceSignal {
+    max_bps: f64,
+    /// Latest mid per pair and venue
+    mids: HashMap<String, HashMap<Exchange, (DateTime<Utc>, f64)>>,
+}
+
+impl DivergenceSignal {
+    pub fn new(max_bps: f64) -> Self {
+        Self {
+            max_bps,
+            mids: HashMap::new(),
+        }
+    }
+}
+
+impl Signal for DivergenceSignal {
+    fn name(&self) -> &'static str {
+        "divergence"
+    }
+
+    /// Raises the venue the quote is furthest from, if past the threshold.
+    fn on_event(&mut self, event: &MarketEvent) -> Vec<SignalAlert> {
+        let MarketEvent::Quote(quote) = event else {
+            return Vec::new();
+        };
+        let Some(mid) = spot_mid(quote) else {
+            return Vec::new();
+        };
+        let (name, max_bps) = (self.name(), self.max_bps);
+        let meta = &quote.meta;
+        let venues = self.mids.entry(normalize(&meta.instrument)).or_default();
+        venues.insert(meta.exchange, (meta.timestamp, mid));
+
+        venues
+            .iter()
+            .filter(|(exchange, (ts, _))| {
+                **exchange != meta.exchange && (meta.timestamp - *ts).num_milliseconds().abs() <= MAX_QUOTE_AGE_MS
+            })
+            .map(|(&other, &(_, other_mid))| (other, other_mid, (mid - other_mid) / other_mid * 10_000.0))
+            .filter(|(_, _, bps)| bps.abs() >= max_bps)
+            .max_by(|a, b| a.2.abs().total_cmp(&b.2.abs()))
+            .map(|(other, other_mid, divergence_bps)| {
+                SignalAlert::new(
+                    name,
+                    meta,
+                    AlertKind::Divergence {
+                        other,
+                        other_mid,
+                        divergence_bps,
+                    },
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+}
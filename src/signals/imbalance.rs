@@ -0,0 +1,44 @@
+//! Depth imbalance of full book snapshots, with the same sums and ratio
+//! threshold as the Binance client's alerts.
+
+use super::{AlertKind, Signal, SignalAlert};
+use crate::config::ImbalanceConfig;
+use crate::exchanges::binance::sbe::events::depth::DepthImbalance;
+use crate::exchanges::event::MarketEvent;
+
+pub struct ImbalanceSignal {
+    config: ImbalanceConfig,
+}
+
+impl ImbalanceSignal {
+    pub fn new(config: ImbalanceConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Signal for ImbalanceSignal {
+    fn name(&self) -> &'static str {
+        "imbalance"
+    }
+
+    /// Raises the most lopsided depth past the ratio, if any.
+    fn on_event(&mut self, event: &MarketEvent) -> Vec<SignalAlert> {
+        let MarketEvent::BookSnapshot(book) = event else {
+            return Vec::new();
+        };
+        let Some(imbalance) = DepthImbalance::from_levels(
+            book.bids.iter().map(|l| (l.price, l.quantity)),
+            book.asks.iter().map(|l| (l.price, l.quantity)),
+            &self.config,
+        ) else {
+            return Vec::new();
+        };
+        [("N_5", imbalance.top_5), ("N_10", imbalance.top_10), ("All", imbalance.all)]
+            .into_iter()
+            .filter(|(_, ratio)| *ratio > self.config.alert_ratio)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(depth, ratio)| SignalAlert::new(self.name(), &book.meta, AlertKind::Imbalance { depth, ratio }))
+            .into_iter()
+            .collect()
+    }
+}
@@ -0,0 +1,323 @@
+//! Pluggable signals over the merged event stream.
+//!
+//! Every venue's [`MarketEvent`]s reach the [`SignalEngine`] like any other
+//! sink, and each registered [`Signal`] sees all of them in arrival order.
+//! A signal keeps whatever state it needs and returns typed
+//! [`SignalAlert`]s; the engine holds back repeats per signal and
+//! instrument, then logs, counts and posts each alert to the status board,
+//! and publishes it back to the sinks as a [`MarketEvent::Alert`].
+//!
+//! Built-in signals are registered from `SIGNALS`, a comma-separated list
+//! of [`SignalKind`] names tuned by the `SIGNAL_*` variables (see
+//! [`SignalsConfig`]):
+//!
+//! - `imbalance`: bid/ask depth ratio of book snapshots, on any venue
+//! - `momentum`: a mid moving more than a threshold within a window
+//! - `spread`: a top of book wider than a threshold
+//! - `divergence`: the same pair quoted apart on two venues
+//!
+//! The Binance client keeps raising and storing its own imbalance alerts,
+//! correlated with Kalshi; the `imbalance` signal is the venue-neutral form
+//! for books it does not see. Other strategies are added through
+//! [`SignalEngine::register`].
+
+pub mod divergence;
+pub mod imbalance;
+pub mod momentum;
+pub mod spread;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use crate::calendar;
+use crate::config::SignalsConfig;
+use crate::error::{Error, Result};
+use crate::exchanges::event::{AlertEvent, EventMeta, Exchange, MarketEvent, QuoteEvent};
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::sinks::SINK_CHANNEL_BUFFER;
+use crate::state::KalshiState;
+use crate::status::{self, AlertDirection, AlertRecord};
+use crate::tags;
+use crate::utils::event_id::{EventId, EventKind};
+
+pub use divergence::DivergenceSignal;
+pub use imbalance::ImbalanceSignal;
+pub use momentum::MomentumSignal;
+pub use spread::SpreadSignal;
+
+/// A strategy fed every event of every venue.
+pub trait Signal: Send {
+    /// Short name used in logs, metric labels and `SIGNALS`.
+    fn name(&self) -> &'static str;
+
+    /// Updates the signal's state with `event` and returns what it raised.
+    fn on_event(&mut self, event: &MarketEvent) -> Vec<SignalAlert>;
+}
+
+/// What a signal saw, with the values behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertKind {
+    Imbalance {
+        /// `N_5`, `N_10` or `All`
+        depth: &'static str,
+        ratio: f64,
+    },
+    Momentum {
+        /// Signed mid move over the window
+        move_bps: f64,
+        window_ms: u64,
+    },
+    Spread {
+        spread_bps: f64,
+    },
+    Divergence {
+        other: Exchange,
+        other_mid: f64,
+        /// Signed distance of this venue's mid from the other's
+        divergence_bps: f64,
+    },
+}
+
+impl AlertKind {
+    /// The way the underlying is pointed, for kinds that carry one.
+    pub fn direction(&self) -> Option<AlertDirection> {
+        let signed = match self {
+            AlertKind::Imbalance { ratio, .. } => ratio.ln(),
+            AlertKind::Momentum { move_bps, .. } => *move_bps,
+            AlertKind::Spread { .. } | AlertKind::Divergence { .. } => return None,
+        };
+        if signed > 0.0 {
+            Some(AlertDirection::Up)
+        } else if signed < 0.0 {
+            Some(AlertDirection::Down)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertKind::Imbalance { depth, ratio } => write!(f, "{} depth imbalance {:.1}", depth, ratio),
+            AlertKind::Momentum { move_bps, window_ms } => {
+                write!(f, "mid moved {:+.1} bps within {}ms", move_bps, window_ms)
+            }
+            AlertKind::Spread { spread_bps } => write!(f, "spread {:.1} bps", spread_bps),
+            AlertKind::Divergence {
+                other,
+                other_mid,
+                divergence_bps,
+            } => write!(f, "mid {:+.1} bps from {} ({})", divergence_bps, other, other_mid),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignalAlert {
+    pub signal: &'static str,
+    pub exchange: Exchange,
+    pub instrument: String,
+    pub series: Option<String>,
+    /// Event time of the event that raised it
+    pub timestamp: DateTime<Utc>,
+    pub kind: AlertKind,
+}
+
+impl SignalAlert {
+    pub fn new(signal: &'static str, meta: &EventMeta, kind: AlertKind) -> Self {
+        Self {
+            signal,
+            exchange: meta.exchange,
+            instrument: meta.instrument.clone(),
+            series: meta.series.clone(),
+            timestamp: meta.timestamp,
+            kind,
+        }
+    }
+}
+
+/// The built-in signals, as named in `SIGNALS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalKind {
+    Imbalance,
+    Momentum,
+    Spread,
+    Divergence,
+}
+
+impl SignalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalKind::Imbalance => "imbalance",
+            SignalKind::Momentum => "momentum",
+            SignalKind::Spread => "spread",
+            SignalKind::Divergence => "divergence",
+        }
+    }
+
+    pub fn build(&self, config: &SignalsConfig) -> Box<dyn Signal> {
+        match self {
+            SignalKind::Imbalance => Box::new(ImbalanceSignal::new(config.imbalance)),
+            SignalKind::Momentum => Box::new(MomentumSignal::new(config.momentum_bps, config.momentum_window_ms)),
+            SignalKind::Spread => Box::new(SpreadSignal::new(config.spread_bps)),
+            SignalKind::Divergence => Box::new(DivergenceSignal::new(config.divergence_bps)),
+        }
+    }
+}
+
+impl FromStr for SignalKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "imbalance" => Ok(SignalKind::Imbalance),
+            "momentum" => Ok(SignalKind::Momentum),
+            "spread" => Ok(SignalKind::Spread),
+            "divergence" => Ok(SignalKind::Divergence),
+            other => Err(Error::Config(format!(
+                "Unknown signal '{}', expected imbalance, momentum, spread or divergence",
+                other
+            ))),
+        }
+    }
+}
+
+/// Mid of a spot quote with both sides; `None` for binary contracts, whose
+/// prices are probabilities rather than a price of the pair.
+pub(crate) fn spot_mid(quote: &QuoteEvent) -> Option<f64> {
+    if quote.binary.is_some() || quote.bid <= 0.0 || quote.ask < quote.bid {
+        return None;
+    }
+    Some((quote.bid + quote.ask) / 2.0)
+}
+
+pub struct SignalEngine {
+    signals: Vec<Box<dyn Signal>>,
+    cooldown_secs: u64,
+    /// Last alert per signal and instrument
+    last_alert: HashMap<(&'static str, String), DateTime<Utc>>,
+    events_tx: Option<mpsc::Sender<MarketEvent>>,
+    /// Current market per Kalshi series, named on published alerts
+    kalshi: Option<Arc<KalshiState>>,
+}
+
+impl SignalEngine {
+    /// An engine running the signals listed in `config`.
+    pub fn new(config: &SignalsConfig) -> Self {
+        Self {
+            signals: config.signals.iter().map(|kind| kind.build(config)).collect(),
+            cooldown_secs: config.cooldown_secs,
+            last_alert: HashMap::new(),
+            events_tx: None,
+            kalshi: None,
+        }
+    }
+
+    /// Publishes alerts as [`MarketEvent::Alert`] to `events_tx`. Not the
+    /// engine's own input, which would feed its alerts back to it.
+    pub fn with_events(mut self, events_tx: Option<mpsc::Sender<MarketEvent>>) -> Self {
+        self.events_tx = events_tx;
+        self
+    }
+
+    pub fn with_kalshi(mut self, kalshi: Option<Arc<KalshiState>>) -> Self {
+        self.kalshi = kalshi;
+        self
+    }
+
+    /// Adds a signal beyond the built-in ones.
+    pub fn register(mut self, signal: Box<dyn Signal>) -> Self {
+        self.signals.push(signal);
+        self
+    }
+
+    /// Starts the engine; feed it every venue's events like a sink.
+    pub fn spawn(self) -> mpsc::Sender<MarketEvent> {
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue("signals", &tx);
+        tokio::spawn(self.run(rx));
+        tx
+    }
+
+    async fn run(mut self, mut rx: mpsc::Receiver<MarketEvent>) {
+        let names: Vec<&str> = self.signals.iter().map(|s| s.name()).collect();
+        info!("📡 Signals running: {}", names.join(", "));
+        while let Some(event) = rx.recv().await {
+            for alert in self.process(&event) {
+                self.emit(&alert);
+            }
+        }
+        info!("Signal engine shutting down");
+    }
+
+    /// Runs `event` through every signal, returning the alerts past their
+    /// cooldown.
+    pub fn process(&mut self, event: &MarketEvent) -> Vec<SignalAlert> {
+        let mut alerts = Vec::new();
+        for signal in &mut self.signals {
+            alerts.extend(signal.on_event(event));
+        }
+        alerts.retain(|alert| {
+            let key = (alert.signal, alert.instrument.clone());
+            match self.last_alert.get(&key) {
+                Some(last) if (alert.timestamp - *last).num_seconds() < self.cooldown_secs as i64 => false,
+                _ => {
+                    self.last_alert.insert(key, alert.timestamp);
+                    true
+                }
+            }
+        });
+        alerts
+    }
+
+    fn emit(&self, alert: &SignalAlert) {
+        let event_id = EventId::new(EventKind::Alert);
+        info!(
+            "ALERT [{}]: {} on {} {}: {}",
+            event_id, alert.signal, alert.exchange, alert.instrument, alert.kind
+        );
+        metrics::inc(names::SIGNAL_ALERTS_TOTAL, &[("signal", alert.signal)]);
+        let series = alert
+            .series
+            .clone()
+            .or_else(|| instruments::registry().kalshi_series(&alert.instrument));
+        let message = format!("{} on {}: {}", alert.signal, alert.exchange, alert.kind);
+        if let Some(events_tx) = &self.events_tx {
+            let kalshi_ticker = series
+                .as_ref()
+                .zip(self.kalshi.as_ref())
+                .and_then(|(series, kalshi)| kalshi.current_market_for_series(series));
+            let mut meta = EventMeta::new(alert.exchange, alert.instrument.clone(), alert.timestamp);
+            meta.series = series.clone();
+            let event = MarketEvent::Alert(AlertEvent {
+                meta,
+                event_id: event_id.to_string(),
+                message: message.clone(),
+                direction: alert.kind.direction(),
+                kalshi_ticker,
+            });
+            if let Err(e) = events_tx.try_send(event) {
+                error!("Failed to publish signal alert: {}", e);
+            }
+        }
+        status::board().record_alert(AlertRecord {
+            event_id: event_id.to_string(),
+            timestamp: alert.timestamp,
+            symbol: alert.instrument.clone(),
+            kalshi_series: series,
+            message,
+            imbalance_ratio: None,
+            tags: tags::current().clone(),
+            calendar_event: calendar::active_event(),
+            direction: alert.kind.direction(),
+        });
+    }
+}
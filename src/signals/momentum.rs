@@ -0,0 +1,70 @@
+//! Mid moves of at least `move_bps` within a window, per venue and pair.
+//!
+//! A move that alerts clears the pair's history, so the next alert needs a
+//! fresh move rather than the same one seen from a later quote.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::{spot_mid, AlertKind, Signal, SignalAlert};
+use crate::exchanges::event::{Exchange, MarketEvent};
+
+type Mids = VecDeque<(DateTime<Utc>, f64)>;
+
+pub struct MomentumSignal {
+    move_bps: f64,
+    window_ms: u64,
+    /// Mids inside the window per venue and instrument, oldest first
+    history: HashMap<(Exchange, String), Mids>,
+}
+
+impl MomentumSignal {
+    pub fn new(move_bps: f64, window_ms: u64) -> Self {
+        Self {
+            move_bps,
+            window_ms,
+            history: HashMap::new(),
+        }
+    }
+}
+
+impl Signal for MomentumSignal {
+    fn name(&self) -> &'static str {
+        "momentum"
+    }
+
+    fn on_event(&mut self, event: &MarketEvent) -> Vec<SignalAlert> {
+        let MarketEvent::Quote(quote) = event else {
+            return Vec::new();
+        };
+        let Some(mid) = spot_mid(quote) else {
+            return Vec::new();
+        };
+        let meta = &quote.meta;
+        let key = (meta.exchange, meta.instrument.clone());
+        let history = self.history.entry(key).or_default();
+        let cutoff = meta.timestamp - Duration::milliseconds(self.window_ms as i64);
+        while history.front().is_some_and(|(ts, _)| *ts < cutoff) {
+            history.pop_front();
+        }
+        history.push_back((meta.timestamp, mid));
+
+        let Some(&(_, first)) = history.front() else {
+            return Vec::new();
+        };
+        let move_bps = (mid - first) / first * 10_000.0;
+        if move_bps.abs() < self.move_bps {
+            return Vec::new();
+        }
+        history.clear();
+        vec![SignalAlert::new(
+            self.name(),
+            meta,
+            AlertKind::Momentum {
+                move_bps,
+                window_ms: self.window_ms,
+            },
+        )]
+    }
+}
@@ -0,0 +1,34 @@
+//! Tops of book wider than `max_bps` of the mid.
+
+use super::{spot_mid, AlertKind, Signal, SignalAlert};
+use crate::exchanges::event::MarketEvent;
+
+pub struct SpreadSignal {
+    max_bps: f64,
+}
+
+impl SpreadSignal {
+    pub fn new(max_bps: f64) -> Self {
+        Self { max_bps }
+    }
+}
+
+impl Signal for SpreadSignal {
+    fn name(&self) -> &'static str {
+        "spread"
+    }
+
+    fn on_event(&mut self, event: &MarketEvent) -> Vec<SignalAlert> {
+        let MarketEvent::Quote(quote) = event else {
+            return Vec::new();
+        };
+        let Some(mid) = spot_mid(quote) else {
+            return Vec::new();
+        };
+        let spread_bps = (quote.ask - quote.bid) / mid * 10_000.0;
+        if spread_bps <= self.max_bps {
+            return Vec::new();
+        }
+        vec![SignalAlert::new(self.name(), &quote.meta, AlertKind::Spread { spread_bps })]
+    }
+}
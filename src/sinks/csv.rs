@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::interval;
+use tracing::{error, info, warn};
+
+use super::SINK_CHANNEL_BUFFER;
+use crate::config::CsvExportConfig;
+use crate::encryption;
+use crate::error::{ErrorContext, Result, ResultExt};
+use crate::exchanges::event::{BinaryQuote, EventMeta, MarketEvent, QuoteEvent, TradeEvent};
+use crate::exchanges::TradeSide;
+use crate::leader::LeaderHandle;
+use crate::metrics::{self, names};
+use crate::status::{self, AlertRecord};
+use crate::tags;
+use crate::timezone;
+
+const TRADE_HEADER: &str = "timestamp,received_at,exchange,instrument,trade_id,price,quantity,side,account,strategy\n";
+const QUOTE_HEADER: &str = "timestamp,received_at,exchange,instrument,bid,bid_qty,ask,ask_qty,account,strategy\n";
+const KALSHI_QUOTE_HEADER: &str = "timestamp,received_at,ticker,series,yes_bid,yes_ask,no_bid,no_ask,\
+                                   yes_ask_depth,no_ask_depth,account,strategy\n";
+const ALERT_HEADER: &str = "timestamp,event_id,symbol,series,imbalance_ratio,calendar_event,account,strategy,message\n";
+
+/// Writes trades, spot quotes, Kalshi contract quotes and alerts to CSV
+/// files under `CSV_EXPORT_DIR`, one file per display-timezone date and
+/// symbol:
+///
+/// ```text
+/// <dir>/20240301/trades/binance_BTCUSDT.csv
+/// <dir>/20240301/quotes/okx_BTC-USDT.csv
+/// <dir>/20240301/kalshi_quotes/KXBTCD-24MAR0117-T62000.csv
+/// <dir>/20240301/alerts/BTCUSDT.csv
+/// ```
+///
+/// Timestamps are RFC 3339 UTC, so `pd.read_csv(path, parse_dates=[0])`
+/// reads a file as is. Every row ends with this instance's `account` and
+/// `strategy` tags. Rows are buffered per file and appended every
+/// `CSV_EXPORT_FLUSH_INTERVAL_MS`; with `ENCRYPTION_KEY` set each append is
+/// sealed as a chunk of its file, like the other reports.
+pub struct CsvExporter {
+    dir: PathBuf,
+    /// Alerts are written only while leading, like the market data fed in
+    leader: LeaderHandle,
+}
+
+impl CsvExporter {
+    pub fn spawn(config: &CsvExportConfig, leader: LeaderHandle) -> mpsc::Sender<MarketEvent> {
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue("csv_sink", &tx);
+        info!("📄 Exporting market data and alerts as CSV to {}", config.dir);
+        let exporter = Self {
+            dir: PathBuf::from(&config.dir),
+            leader,
+        };
+        tokio::spawn(exporter.run(rx, status::board().subscribe_alerts(), config.flush_interval_ms));
+        tx
+    }
+
+    async fn run(
+        self,
+        mut rx: mpsc::Receiver<MarketEvent>,
+        mut alerts: broadcast::Receiver<AlertRecord>,
+        flush_interval_ms: u64,
+    ) {
+        let mut pending = Pending::default();
+        let mut alerts_open = true;
+        let mut flush_interval = interval(Duration::from_millis(flush_interval_ms));
+
+        loop {
+            tokio::select! {
+                maybe_event = rx.recv() => match maybe_event {
+                    Some(event) => self.push_event(&mut pending, &event),
+                    None => {
+                        self.flush(&mut pending).await;
+                        info!("CSV exporter shutting down");
+                        break;
+                    }
+                },
+                alert = alerts.recv(), if alerts_open => match alert {
+                    Ok(alert) if self.leader.is_leader() => self.push_alert(&mut pending, &alert),
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("CSV exporter fell behind, skipped {} alerts", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => alerts_open = false,
+                },
+                _ = flush_interval.tick() => {
+                    self.flush(&mut pending).await;
+                }
+            }
+        }
+    }
+
+    fn push_event(&self, pending: &mut Pending, event: &MarketEvent) {
+        match event {
+            MarketEvent::Trade(trade) => {
+                let path = self.path(trade.meta.timestamp, "trades", &venue_file(&trade.meta));
+                pending.push(path, TRADE_HEADER, trade_row(trade));
+            }
+            MarketEvent::Quote(quote @ QuoteEvent { binary: Some(binary), .. }) => {
+                let path = self.path(quote.meta.timestamp, "kalshi_quotes", &file_name(&quote.meta.instrument));
+                pending.push(path, KALSHI_QUOTE_HEADER, kalshi_quote_row(quote, binary));
+            }
+            MarketEvent::Quote(quote) => {
+                let path = self.path(quote.meta.timestamp, "quotes", &venue_file(&quote.meta));
+                pending.push(path, QUOTE_HEADER, quote_row(quote));
+            }
+            // Alerts are taken from the status board, which also carries
+            // those raised outside the event stream
+            MarketEvent::BookSnapshot(_)
+            | MarketEvent::BookDelta(_)
+            | MarketEvent::Lifecycle(_)
+            | MarketEvent::Alert(_)
+            | MarketEvent::TopOfBookChanged(_) => {}
+        }
+    }
+
+    fn push_alert(&self, pending: &mut Pending, alert: &AlertRecord) {
+        let path = self.path(alert.timestamp, "alerts", &file_name(&alert.symbol));
+        pending.push(path, ALERT_HEADER, alert_row(alert));
+    }
+
+    fn path(&self, ts: DateTime<Utc>, kind: &str, file: &str) -> PathBuf {
+        self.dir.join(timezone::file_date(ts)).join(kind).join(format!("{}.csv", file))
+    }
+
+    async fn flush(&self, pending: &mut Pending) {
+        if pending.files.is_empty() {
+            return;
+        }
+        let files = std::mem::take(&mut pending.files);
+        let written = tokio::task::spawn_blocking(move || {
+            let mut written = 0;
+            for (path, (header, rows, count)) in files {
+                match append(&path, header, &rows) {
+                    Ok(()) => written += count,
+                    Err(e) => {
+                        error!("Failed to append {} CSV rows: {}", count, e.chain());
+                        metrics::inc(names::CSV_WRITE_ERRORS_TOTAL, &[]);
+                    }
+                }
+            }
+            written
+        })
+        .await;
+        match written {
+            Ok(count) => metrics::add(names::CSV_ROWS_WRITTEN_TOTAL, &[], count as u64),
+            Err(e) => error!("CSV flush task failed: {}", e),
+        }
+    }
+}
+
+/// Rows waiting for the next flush, with the header each file starts with.
+#[derive(Default)]
+struct Pending {
+    files: BTreeMap<PathBuf, (&'static str, String, usize)>,
+}
+
+impl Pending {
+    fn push(&mut self, path: PathBuf, header: &'static str, row: String) {
+        let (_, rows, count) = self.files.entry(path).or_insert_with(|| (header, String::new(), 0));
+        rows.push_str(&row);
+        *count += 1;
+    }
+}
+
+fn append(path: &Path, header: &str, rows: &str) -> Result<()> {
+    let context = || ErrorContext::new(format!("write CSV file {}", path.display()));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(context)?;
+    }
+    encryption::append_report(path, header, rows).with_context(context)
+}
+
+/// `<exchange>_<instrument>`, as the same pair trades on several venues.
+fn venue_file(meta: &EventMeta) -> String {
+    file_name(&format!("{}_{}", meta.exchange, meta.instrument))
+}
+
+/// `name` with characters that can't appear in a file name replaced.
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+/// Quotes `value` if it contains a separator, quote or line break.
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// This instance's `account,strategy`, ending every market data row.
+fn tag_fields() -> String {
+    format!("{},{}", field(tags::account()), field(tags::strategy()))
+}
+
+fn trade_row(trade: &TradeEvent) -> String {
+    let meta = &trade.meta;
+    let side = match trade.side {
+        TradeSide::Buy => "buy",
+        TradeSide::Sell => "sell",
+        TradeSide::Unknown => "",
+    };
+    format!(
+        "{},{},{},{},{},{},{},{},{}\n",
+        meta.timestamp.to_rfc3339(),
+        meta.received_at.to_rfc3339(),
+        meta.exchange,
+        field(&meta.instrument),
+        trade.trade_id,
+        trade.price,
+        trade.quantity,
+        side,
+        tag_fields()
+    )
+}
+
+fn quote_row(quote: &QuoteEvent) -> String {
+    let meta = &quote.meta;
+    format!(
+        "{},{},{},{},{},{},{},{},{}\n",
+        meta.timestamp.to_rfc3339(),
+        meta.received_at.to_rfc3339(),
+        meta.exchange,
+        field(&meta.instrument),
+        quote.bid,
+        quote.bid_qty,
+        quote.ask,
+        quote.ask_qty,
+        tag_fields()
+    )
+}
+
+fn kalshi_quote_row(quote: &QuoteEvent, binary: &BinaryQuote) -> String {
+    let meta = &quote.meta;
+    format!(
+        "{},{},{},{},{},{},{},{},{},{},{}\n",
+        meta.timestamp.to_rfc3339(),
+        meta.received_at.to_rfc3339(),
+        field(&meta.instrument),
+        field(meta.series.as_deref().unwrap_or_default()),
+        quote.bid,
+        quote.ask,
+        binary.no_bid,
+        binary.no_ask,
+        binary.yes_ask_depth,
+        binary.no_ask_depth,
+        tag_fields()
+    )
+}
+
+fn alert_row(alert: &AlertRecord) -> String {
+    format!(
+        "{},{},{},{},{},{},{},{},{}\n",
+        alert.timestamp.to_rfc3339(),
+        field(&alert.event_id),
+        field(&alert.symbol),
+        field(alert.kalshi_series.as_deref().unwrap_or_default()),
+        optional(alert.imbalance_ratio),
+        field(alert.calendar_event.as_deref().unwrap_or_default()),
+        field(&alert.tags.account),
+        field(&alert.tags.strategy),
+        field(&alert.message)
+    )
+}
@@ -0,0 +1,467 @@
+use std::time::Duration;
+
+use chrono::DateTime;
+use duckdb::{params, Connection};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use super::SINK_CHANNEL_BUFFER;
+use crate::config::DuckDbConfig;
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::event::{AlertEvent, Exchange, LifecycleEvent, MarketEvent, QuoteEvent};
+use crate::resample::{self, OddsBar, OddsSample};
+use crate::status;
+use crate::tags;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+
+const DUCKDB_BATCH_SIZE: usize = 1000;
+
+const CREATE_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS market_data (
+    ts_micros BIGINT NOT NULL,
+    ticker VARCHAR NOT NULL,
+    asset VARCHAR NOT NULL,
+    yes_ask DOUBLE,
+    yes_bid DOUBLE,
+    no_ask DOUBLE,
+    no_bid DOUBLE,
+    yes_ask_qty BIGINT,
+    no_ask_qty BIGINT,
+    close_micros BIGINT,
+    account VARCHAR NOT NULL DEFAULT 'default',
+    strategy VARCHAR NOT NULL DEFAULT 'default'
+);
+ALTER TABLE market_data ADD COLUMN IF NOT EXISTS account VARCHAR DEFAULT 'default';
+ALTER TABLE market_data ADD COLUMN IF NOT EXISTS strategy VARCHAR DEFAULT 'default';
+CREATE TABLE IF NOT EXISTS odds_bars (
+    interval_secs BIGINT NOT NULL,
+    bucket_micros BIGINT NOT NULL,
+    ticker VARCHAR NOT NULL,
+    asset VARCHAR NOT NULL,
+    yes_bid DOUBLE,
+    yes_ask DOUBLE,
+    no_bid DOUBLE,
+    no_ask DOUBLE,
+    samples INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS alerts (
+    ts_micros BIGINT NOT NULL,
+    event_id VARCHAR NOT NULL,
+    exchange VARCHAR NOT NULL,
+    symbol VARCHAR NOT NULL,
+    direction VARCHAR,
+    ticker VARCHAR,
+    message VARCHAR NOT NULL
+);
+CREATE TABLE IF NOT EXISTS market_results (
+    ts_micros BIGINT NOT NULL,
+    ticker VARCHAR NOT NULL,
+    status VARCHAR NOT NULL,
+    result VARCHAR
+);
+"#;
+
+#[derive(Debug, Clone)]
+pub struct TickerSummary {
+    pub ticker: String,
+    pub asset: String,
+    pub rows: i64,
+    pub first_micros: i64,
+    pub last_micros: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct OddsCurvePoint {
+    pub asset: String,
+    pub seconds_to_close: i64,
+    pub avg_yes_ask: f64,
+    pub avg_yes_bid: f64,
+    pub samples: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct FinalOdds {
+    pub ticker: String,
+    pub asset: String,
+    pub yes_bid: f64,
+    pub yes_ask: f64,
+    pub seconds_to_close: Option<i64>,
+}
+
+/// How the mapped Kalshi market moved after an alert. Odds are YES mids,
+/// `None` where no tick was captured by then.
+#[derive(Debug, Clone)]
+pub struct AlertOutcome {
+    pub event_id: String,
+    pub ts_micros: i64,
+    pub symbol: String,
+    pub direction: Option<String>,
+    pub ticker: String,
+    pub yes_at_alert: Option<f64>,
+    pub yes_after: Option<f64>,
+    pub yes_final: Option<f64>,
+    /// Settlement result from the market's lifecycle, once determined
+    pub result: Option<String>,
+}
+
+/// Embedded DuckDB file holding captured ticks, alerts and Kalshi market
+/// results, queryable without any external database.
+pub struct DuckDbStore {
+    conn: Connection,
+}
+
+impl DuckDbStore {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| ErrorContext::new(format!("open DuckDB {}", path)))?;
+        conn.execute_batch(CREATE_TABLES)
+            .with_context(|| ErrorContext::new("create DuckDB tables"))?;
+        Ok(Self { conn })
+    }
+
+    /// Spawns a writer thread appending every Kalshi contract quote, alert
+    /// and Kalshi lifecycle change to the DuckDB file; other events are
+    /// ignored.
+    pub fn spawn_writer(config: &DuckDbConfig) -> Result<mpsc::Sender<MarketEvent>> {
+        let store = Self::open(&config.path)?;
+        info!("🦆 Persisting ticks to DuckDB at {}", config.path);
+
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue("duckdb_sink", &tx);
+        std::thread::Builder::new()
+            .name("duckdb-writer".into())
+            .spawn(move || store.run(rx))
+            .map_err(|e| Error::Sink(format!("Failed to spawn DuckDB thread: {}", e)))?;
+        Ok(tx)
+    }
+
+    fn run(self, mut rx: mpsc::Receiver<MarketEvent>) {
+        let mut batch: Vec<QuoteEvent> = Vec::with_capacity(DUCKDB_BATCH_SIZE);
+
+        while let Some(event) = rx.blocking_recv() {
+            self.route(event, &mut batch);
+            while batch.len() < DUCKDB_BATCH_SIZE {
+                match rx.try_recv() {
+                    Ok(event) => self.route(event, &mut batch),
+                    Err(_) => break,
+                }
+            }
+            if batch.is_empty() {
+                continue;
+            }
+
+            let count = batch.len();
+            if let Err(e) = self.append(&batch) {
+                error!("Failed to append {} ticks to DuckDB: {}", count, e.chain());
+                metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", "duckdb_market_data")]);
+                notify::publish(Notification::DbFailure {
+                    table: "duckdb_market_data".to_string(),
+                    error: e.to_string(),
+                });
+            } else {
+                metrics::add(
+                    names::DB_ROWS_WRITTEN_TOTAL,
+                    &[("table", "duckdb_market_data")],
+                    count as u64,
+                );
+            }
+            batch.clear();
+        }
+        info!("DuckDB writer shutting down");
+    }
+
+    /// Batches contract quotes; alerts and lifecycle changes are rare
+    /// enough to write as they come.
+    fn route(&self, event: MarketEvent, batch: &mut Vec<QuoteEvent>) {
+        let (table, result) = match event {
+            MarketEvent::Quote(quote) if quote.binary.is_some() => {
+                batch.push(quote);
+                return;
+            }
+            MarketEvent::Alert(alert) => ("duckdb_alerts", self.append_alert(&alert)),
+            MarketEvent::Lifecycle(lifecycle) if lifecycle.meta.exchange == Exchange::Kalshi => {
+                ("duckdb_market_results", self.append_result(&lifecycle))
+            }
+            _ => return,
+        };
+        match result {
+            Ok(()) => metrics::inc(names::DB_ROWS_WRITTEN_TOTAL, &[("table", table)]),
+            Err(e) => {
+                error!("Failed to write {} row to DuckDB: {}", table, e.chain());
+                metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", table)]);
+            }
+        }
+    }
+
+    pub fn append_alert(&self, alert: &AlertEvent) -> Result<()> {
+        let meta = &alert.meta;
+        self.conn
+            .execute(
+                "INSERT INTO alerts VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    meta.timestamp.timestamp_micros(),
+                    alert.event_id,
+                    meta.exchange.as_str(),
+                    meta.instrument,
+                    alert.direction.map(|d| d.as_str()),
+                    alert.kalshi_ticker,
+                    alert.message,
+                ],
+            )
+            .with_context(|| ErrorContext::new("append alert").with_symbol(meta.instrument.clone()))?;
+        Ok(())
+    }
+
+    pub fn append_result(&self, lifecycle: &LifecycleEvent) -> Result<()> {
+        let meta = &lifecycle.meta;
+        self.conn
+            .execute(
+                "INSERT INTO market_results VALUES (?, ?, ?, ?)",
+                params![
+                    meta.timestamp.timestamp_micros(),
+                    meta.instrument,
+                    lifecycle.status,
+                    lifecycle.result,
+                ],
+            )
+            .with_context(|| ErrorContext::new("append market result").with_symbol(meta.instrument.clone()))?;
+        Ok(())
+    }
+
+    /// Appends contract quotes under this instance's tags; quotes without a
+    /// binary side are skipped.
+    pub fn append(&self, quotes: &[QuoteEvent]) -> Result<()> {
+        let mut appender = self.conn.appender("market_data")?;
+
+        for quote in quotes {
+            let Some(binary) = &quote.binary else { continue };
+            let meta = &quote.meta;
+            appender
+                .append_row(params![
+                    meta.timestamp.timestamp_micros(),
+                    meta.instrument,
+                    meta.series.as_deref().unwrap_or(&meta.instrument),
+                    quote.ask,
+                    quote.bid,
+                    binary.no_ask,
+                    binary.no_bid,
+                    binary.yes_ask_depth,
+                    binary.no_ask_depth,
+                    meta.expires_at.map(|ct| ct.timestamp_micros()),
+                    tags::account(),
+                    tags::strategy(),
+                ])
+                .with_context(|| ErrorContext::new("append tick").with_symbol(meta.instrument.clone()))?;
+        }
+
+        appender.flush()?;
+        Ok(())
+    }
+
+    pub fn ticker_summary(&self) -> Result<Vec<TickerSummary>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT ticker, asset, COUNT(*), MIN(ts_micros), MAX(ts_micros)
+                 FROM market_data
+                 GROUP BY ticker, asset
+                 ORDER BY MIN(ts_micros)",
+            )?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(TickerSummary {
+                    ticker: row.get(0)?,
+                    asset: row.get(1)?,
+                    rows: row.get(2)?,
+                    first_micros: row.get(3)?,
+                    last_micros: row.get(4)?,
+                })
+            })?;
+
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Average YES odds per asset bucketed by seconds remaining until close.
+    pub fn odds_curve(&self, bucket_secs: i64) -> Result<Vec<OddsCurvePoint>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT asset,
+                        CAST(floor((close_micros - ts_micros) / 1000000.0 / ?) * ? AS BIGINT) AS secs_to_close,
+                        AVG(yes_ask), AVG(yes_bid), COUNT(*)
+                 FROM market_data
+                 WHERE close_micros IS NOT NULL AND close_micros >= ts_micros
+                 GROUP BY asset, secs_to_close
+                 ORDER BY asset, secs_to_close DESC",
+            )?;
+
+        let rows = stmt
+            .query_map(params![bucket_secs, bucket_secs], |row| {
+                Ok(OddsCurvePoint {
+                    asset: row.get(0)?,
+                    seconds_to_close: row.get(1)?,
+                    avg_yes_ask: row.get(2)?,
+                    avg_yes_bid: row.get(3)?,
+                    samples: row.get(4)?,
+                })
+            })?;
+
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Last observed quote of every market, i.e. where odds stood at close.
+    pub fn final_odds(&self) -> Result<Vec<FinalOdds>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT ticker, asset, arg_max(yes_bid, ts_micros), arg_max(yes_ask, ts_micros),
+                        CAST((MAX(close_micros) - MAX(ts_micros)) / 1000000 AS BIGINT)
+                 FROM market_data
+                 GROUP BY ticker, asset
+                 ORDER BY MAX(ts_micros)",
+            )?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(FinalOdds {
+                    ticker: row.get(0)?,
+                    asset: row.get(1)?,
+                    yes_bid: row.get(2)?,
+                    yes_ask: row.get(3)?,
+                    seconds_to_close: row.get(4)?,
+                })
+            })?;
+
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Every alert mapped to a Kalshi market, with the market's YES odds
+    /// when it fired, `horizon_secs` later and at the last captured tick,
+    /// and how the market settled.
+    pub fn alert_outcomes(&self, horizon_secs: i64) -> Result<Vec<AlertOutcome>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT a.event_id, a.ts_micros, a.symbol, a.direction, a.ticker,
+                        (SELECT (m.yes_bid + m.yes_ask) / 2 FROM market_data m
+                         WHERE m.ticker = a.ticker AND m.ts_micros <= a.ts_micros
+                         ORDER BY m.ts_micros DESC LIMIT 1),
+                        (SELECT (m.yes_bid + m.yes_ask) / 2 FROM market_data m
+                         WHERE m.ticker = a.ticker AND m.ts_micros <= a.ts_micros + ? * 1000000
+                         ORDER BY m.ts_micros DESC LIMIT 1),
+                        (SELECT (m.yes_bid + m.yes_ask) / 2 FROM market_data m
+                         WHERE m.ticker = a.ticker
+                         ORDER BY m.ts_micros DESC LIMIT 1),
+                        (SELECT r.result FROM market_results r
+                         WHERE r.ticker = a.ticker AND r.result IS NOT NULL
+                         ORDER BY r.ts_micros DESC LIMIT 1)
+                 FROM alerts a
+                 WHERE a.ticker IS NOT NULL
+                 ORDER BY a.ts_micros",
+            )?;
+
+        let rows = stmt
+            .query_map(params![horizon_secs], |row| {
+                Ok(AlertOutcome {
+                    event_id: row.get(0)?,
+                    ts_micros: row.get(1)?,
+                    symbol: row.get(2)?,
+                    direction: row.get(3)?,
+                    ticker: row.get(4)?,
+                    yes_at_alert: row.get(5)?,
+                    yes_after: row.get(6)?,
+                    yes_final: row.get(7)?,
+                    result: row.get(8)?,
+                })
+            })?;
+
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Resamples every captured tick onto an `interval_secs` grid with
+    /// forward fill; see [`resample`].
+    pub fn resample_odds(&self, interval_secs: i64) -> Result<Vec<OddsBar>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT ticker, asset, ts_micros, yes_bid, yes_ask, no_bid, no_ask
+                 FROM market_data
+                 WHERE yes_bid IS NOT NULL AND yes_ask IS NOT NULL
+                 ORDER BY ts_micros",
+            )?;
+
+        let samples = stmt
+            .query_map([], |row| {
+                let ts_micros: i64 = row.get(2)?;
+                Ok(OddsSample {
+                    ticker: row.get(0)?,
+                    asset: row.get(1)?,
+                    timestamp: DateTime::from_timestamp_micros(ts_micros).unwrap_or_default(),
+                    yes_bid: row.get(3)?,
+                    yes_ask: row.get(4)?,
+                    no_bid: row.get::<_, Option<f64>>(5)?.unwrap_or_default(),
+                    no_ask: row.get::<_, Option<f64>>(6)?.unwrap_or_default(),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(resample::resample(samples, Duration::from_secs(interval_secs.max(1) as u64)))
+    }
+
+    /// Replaces the stored bars for `interval_secs` with `bars`.
+    pub fn store_odds_bars(&self, interval_secs: i64, bars: &[OddsBar]) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM odds_bars WHERE interval_secs = ?", params![interval_secs])?;
+
+        let mut appender = self.conn.appender("odds_bars")?;
+        for bar in bars {
+            appender
+                .append_row(params![
+                    interval_secs,
+                    bar.bucket_start.timestamp_micros(),
+                    bar.ticker,
+                    bar.asset,
+                    bar.yes_bid,
+                    bar.yes_ask,
+                    bar.no_bid,
+                    bar.no_ask,
+                    bar.samples,
+                ])
+                .with_context(|| ErrorContext::new("append odds bar").with_symbol(bar.ticker.clone()))?;
+        }
+        appender.flush()?;
+        Ok(())
+    }
+
+    /// Bars previously stored for `interval_secs`, in time order.
+    pub fn odds_bars(&self, interval_secs: i64) -> Result<Vec<OddsBar>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT ticker, asset, bucket_micros, yes_bid, yes_ask, no_bid, no_ask, samples
+                 FROM odds_bars
+                 WHERE interval_secs = ?
+                 ORDER BY bucket_micros, ticker",
+            )?;
+
+        let rows = stmt
+            .query_map(params![interval_secs], |row| {
+                let bucket_micros: i64 = row.get(2)?;
+                Ok(OddsBar {
+                    ticker: row.get(0)?,
+                    asset: row.get(1)?,
+                    bucket_start: DateTime::from_timestamp_micros(bucket_micros).unwrap_or_default(),
+                    yes_bid: row.get(3)?,
+                    yes_ask: row.get(4)?,
+                    no_bid: row.get(5)?,
+                    no_ask: row.get(6)?,
+                    samples: row.get(7)?,
+                })
+            })?;
+
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+}
+
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use reqwest::Client as HttpClient;
+use tokio::sync::mpsc;
+use tokio::time::interval;
+use tracing::{error, info};
+
+use super::SINK_CHANNEL_BUFFER;
+use crate::config::InfluxConfig;
+use crate::error::{ErrorContext, Result, ResultExt};
+use crate::exchanges::event::{BinaryQuote, MarketEvent, QuoteEvent};
+use crate::status;
+use crate::tags;
+use crate::metrics::{self, names};
+use crate::notify::{self, Notification};
+use crate::utils::http::ensure_success;
+
+const MEASUREMENT: &str = "kalshi_tick";
+
+/// Writes Kalshi contract quotes to InfluxDB v2 using the line protocol. Points are
+/// buffered and flushed in batches by size or interval, so tick-rate writes
+/// cost one HTTP request per batch.
+pub struct InfluxWriter {
+    http: HttpClient,
+    write_url: String,
+    token: String,
+}
+
+impl InfluxWriter {
+    pub fn spawn(config: &InfluxConfig) -> mpsc::Sender<MarketEvent> {
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue("influx_sink", &tx);
+        let writer = Self {
+            http: HttpClient::new(),
+            write_url: format!(
+                "{}/api/v2/write?org={}&bucket={}&precision=us",
+                config.url.trim_end_matches('/'),
+                config.org,
+                config.bucket
+            ),
+            token: config.token.clone(),
+        };
+        tokio::spawn(writer.run(rx, config.batch_size, config.flush_interval_ms));
+        tx
+    }
+
+    async fn run(self, mut rx: mpsc::Receiver<MarketEvent>, batch_size: usize, flush_interval_ms: u64) {
+        let mut batch: Vec<String> = Vec::with_capacity(batch_size);
+        let mut flush_interval = interval(Duration::from_millis(flush_interval_ms));
+
+        loop {
+            tokio::select! {
+                maybe_update = rx.recv() => {
+                    match maybe_update {
+                        Some(MarketEvent::Quote(quote)) => {
+                            if let Some(binary) = &quote.binary {
+                                batch.push(to_line(&quote, binary));
+                            }
+                            if batch.len() >= batch_size {
+                                self.flush(&mut batch).await;
+                            }
+                        }
+                        Some(_) => {}
+                        None => {
+                            self.flush(&mut batch).await;
+                            info!("Influx writer shutting down");
+                            break;
+                        }
+                    }
+                }
+                _ = flush_interval.tick() => {
+                    self.flush(&mut batch).await;
+                }
+            }
+        }
+    }
+
+    async fn flush(&self, batch: &mut Vec<String>) {
+        if batch.is_empty() {
+            return;
+        }
+
+        let count = batch.len();
+        let body = batch.join("\n");
+        batch.clear();
+
+        if let Err(e) = self.write(body).await {
+            error!("Failed to write {} points to InfluxDB: {}", count, e.chain());
+            metrics::inc(names::DB_WRITE_ERRORS_TOTAL, &[("table", MEASUREMENT)]);
+            notify::publish(Notification::DbFailure {
+                table: MEASUREMENT.to_string(),
+                error: e.to_string(),
+            });
+        } else {
+            metrics::add(names::DB_ROWS_WRITTEN_TOTAL, &[("table", MEASUREMENT)], count as u64);
+        }
+    }
+
+    async fn write(&self, body: String) -> Result<()> {
+        let resp = self
+            .http
+            .post(&self.write_url)
+            .header("Authorization", format!("Token {}", self.token))
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(body)
+            .send()
+            .await;
+
+        let context = || ErrorContext::new("write points").with_endpoint(self.write_url.clone());
+        ensure_success(resp.with_context(context)?)
+            .await
+            .with_context(context)?;
+        Ok(())
+    }
+}
+
+fn escape_tag(value: &str) -> String {
+    value
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+fn to_line(quote: &QuoteEvent, binary: &BinaryQuote) -> String {
+    let meta = &quote.meta;
+    format!(
+        "{},account={},asset={},strategy={},ticker={} yes_ask={},yes_bid={},no_ask={},no_bid={},yes_ask_qty={}i,no_ask_qty={}i {}",
+        MEASUREMENT,
+        escape_tag(tags::account()),
+        escape_tag(meta.series.as_deref().unwrap_or(&meta.instrument)),
+        escape_tag(tags::strategy()),
+        escape_tag(&meta.instrument),
+        quote.ask,
+        quote.bid,
+        binary.no_ask,
+        binary.no_bid,
+        binary.yes_ask_depth,
+        binary.no_ask_depth,
+        meta.timestamp.timestamp_micros()
+    )
+}
+
+/// Flux query returning ticks for `asset` downsampled to `every` (e.g. `1s`,
+/// `1m`) over `range` (e.g. `-1h`), last value per window.
+pub fn downsample_query(bucket: &str, asset: &str, range: &str, every: &str) -> String {
+    format!(
+        r#"from(bucket: "{bucket}")
+  |> range(start: {range})
+  |> filter(fn: (r) => r._measurement == "{MEASUREMENT}" and r.asset == "{asset}")
+  |> aggregateWindow(every: {every}, fn: last, createEmpty: false)
+  |> pivot(rowKey: ["_time"], columnKey: ["_field"], valueColumn: "_value")"#
+    )
+}
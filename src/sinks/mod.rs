@@ -0,0 +1,19 @@
+pub mod csv;
+#[cfg(feature = "duckdb")]
+pub mod duckdb;
+pub mod influx;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "zmq")]
+pub mod zmq;
+
+pub use self::csv::CsvExporter;
+#[cfg(feature = "duckdb")]
+pub use self::duckdb::DuckDbStore;
+pub use self::influx::InfluxWriter;
+#[cfg(feature = "parquet")]
+pub use self::parquet::ParquetWriter;
+#[cfg(feature = "zmq")]
+pub use self::zmq::ZmqPublisher;
+
+pub const SINK_CHANNEL_BUFFER: usize = 10_000;
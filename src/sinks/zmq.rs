@@ -0,0 +1,114 @@
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use super::SINK_CHANNEL_BUFFER;
+use crate::config::ZmqConfig;
+use crate::error::{Error, Result};
+use crate::exchanges::event::{BinaryQuote, MarketEvent, QuoteEvent};
+use crate::exchanges::schema::WireFormat;
+use crate::status;
+
+const ZMQ_SEND_HWM: i32 = 100_000;
+
+/// Compact frame published after the topic part. Prices are dollars, timestamps
+/// are microseconds since the Unix epoch.
+#[derive(Debug, Serialize)]
+struct TickFrame<'a> {
+    ticker: &'a str,
+    asset: &'a str,
+    timestamp_micros: i64,
+    yes_ask: f64,
+    yes_bid: f64,
+    no_ask: f64,
+    no_bid: f64,
+    yes_ask_qty: i64,
+    no_ask_qty: i64,
+}
+
+impl<'a> TickFrame<'a> {
+    fn new(quote: &'a QuoteEvent, binary: &BinaryQuote) -> Self {
+        Self {
+            ticker: &quote.meta.instrument,
+            asset: quote.meta.series.as_deref().unwrap_or(&quote.meta.instrument),
+            timestamp_micros: quote.meta.timestamp.timestamp_micros(),
+            yes_ask: quote.ask,
+            yes_bid: quote.bid,
+            no_ask: binary.no_ask,
+            no_bid: binary.no_bid,
+            yes_ask_qty: binary.yes_ask_depth,
+            no_ask_qty: binary.no_ask_depth,
+        }
+    }
+}
+
+/// Publishes market events on a ZeroMQ PUB socket as two-part messages.
+/// Kalshi contract quotes go out as topic `kalshi.tick.<ASSET>` followed by a
+/// bincode encoded [`TickFrame`]; every other event as
+/// `<exchange>.<kind>.<instrument>` followed by the [`MarketEvent`] in the
+/// configured [`WireFormat`] (versioned JSON unless `ZMQ_WIRE_FORMAT=bincode`).
+/// Subscribers filter by topic prefix.
+pub struct ZmqPublisher;
+
+impl ZmqPublisher {
+    pub fn spawn(config: &ZmqConfig) -> Result<mpsc::Sender<MarketEvent>> {
+        let context = zmq::Context::new();
+        let socket = context
+            .socket(zmq::PUB)
+            .map_err(|e| Error::Sink(format!("Failed to create ZMQ socket: {}", e)))?;
+        socket
+            .set_sndhwm(ZMQ_SEND_HWM)
+            .map_err(|e| Error::Sink(format!("Failed to set ZMQ HWM: {}", e)))?;
+        socket
+            .bind(&config.endpoint)
+            .map_err(|e| Error::Sink(format!("Failed to bind ZMQ {}: {}", config.endpoint, e)))?;
+
+        info!("📣 ZMQ publisher bound to {} ({} events)", config.endpoint, config.format);
+
+        let (tx, rx) = mpsc::channel::<MarketEvent>(SINK_CHANNEL_BUFFER);
+        status::board().register_queue("zmq_sink", &tx);
+        let format = config.format;
+        std::thread::Builder::new()
+            .name("zmq-publisher".into())
+            .spawn(move || Self::run(context, socket, format, rx))
+            .map_err(|e| Error::Sink(format!("Failed to spawn ZMQ thread: {}", e)))?;
+
+        Ok(tx)
+    }
+
+    fn run(
+        _context: zmq::Context,
+        socket: zmq::Socket,
+        format: WireFormat,
+        mut rx: mpsc::Receiver<MarketEvent>,
+    ) {
+        while let Some(event) = rx.blocking_recv() {
+            let (topic, encoded) = match &event {
+                MarketEvent::Quote(quote @ QuoteEvent { binary: Some(binary), .. }) => {
+                    let frame = TickFrame::new(quote, binary);
+                    let payload = bincode::serialize(&frame).map_err(|e| e.to_string());
+                    (format!("kalshi.tick.{}", frame.asset), payload)
+                }
+                _ => {
+                    let meta = event.meta();
+                    (
+                        format!("{}.{}.{}", meta.exchange, event.kind(), meta.instrument),
+                        format.encode(&event).map_err(|e| e.to_string()),
+                    )
+                }
+            };
+            let payload = match encoded {
+                Ok(p) => p,
+                Err(e) => {
+                    warn!("Failed to encode {} frame: {}", event.kind(), e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = socket.send_multipart([topic.into_bytes(), payload], zmq::DONTWAIT) {
+                error!("Failed to publish ZMQ frame: {}", e);
+            }
+        }
+        info!("ZMQ publisher shutting down");
+    }
+}
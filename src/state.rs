@@ -1,12 +1,38 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
+use serde::Serialize;
+use tracing::info;
 
+use crate::exchanges::binance::iceberg::Iceberg;
+use crate::exchanges::binance::orderbook::LocalOrderBook;
+use crate::exchanges::binance::rest::Kline;
+use crate::config::ImbalanceConfig;
+use crate::exchanges::binance::sbe::events::depth::{DepthImbalance, ImbalanceLean};
+use crate::exchanges::kalshi::reference::LastTrade;
 use crate::exchanges::kalshi::{KalshiMarket, KalshiOrderbook, KalshiTicker};
+use crate::exchanges::polymarket::PolymarketBook;
+use crate::exchanges::{PriceLevel, PriceUpdate};
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::status;
 
 #[derive(Clone)]
 pub struct KalshiState {
     pub tracked_markets: DashMap<String, KalshiMarket>,
     pub orderbooks: DashMap<String, KalshiOrderbook>,
     pub tickers: DashMap<String, KalshiTicker>,
+    pub last_trades: DashMap<String, LastTrade>,
+    /// Latest YES-side depth imbalance per market, while its book has asks
+    pub imbalances: DashMap<String, DepthImbalance>,
+    /// Markets that closed or rotated out, with when; their entries above are
+    /// kept for a grace period and then evicted.
+    retired: DashMap<String, Instant>,
 }
 
 impl KalshiState {
@@ -15,7 +41,97 @@ impl KalshiState {
             tracked_markets: DashMap::new(),
             orderbooks: DashMap::new(),
             tickers: DashMap::new(),
+            last_trades: DashMap::new(),
+            imbalances: DashMap::new(),
+            retired: DashMap::new(),
+        }
+    }
+
+    /// Starts the eviction clock for a closed or rotated-out market.
+    pub fn retire_market(&self, market_ticker: &str) {
+        self.retired
+            .entry(market_ticker.to_string())
+            .or_insert_with(Instant::now);
+    }
+
+    pub fn is_retired(&self, market_ticker: &str) -> bool {
+        self.retired.contains_key(market_ticker)
+    }
+
+    /// Stops the eviction clock, for a market that is tracked again.
+    pub fn reinstate_market(&self, market_ticker: &str) {
+        self.retired.remove(market_ticker);
+    }
+
+    /// Drops every entry of markets retired more than `ttl` ago and returns
+    /// their tickers. Books and tickers left behind for markets that were
+    /// never tracked or retired are retired here, so they go on a later pass.
+    pub fn evict_retired(&self, ttl: Duration) -> Vec<String> {
+        let orphans: Vec<String> = self
+            .orderbooks
+            .iter()
+            .map(|e| e.key().clone())
+            .chain(self.tickers.iter().map(|e| e.key().clone()))
+            .chain(self.last_trades.iter().map(|e| e.key().clone()))
+            .filter(|t| !self.tracked_markets.contains_key(t))
+            .collect();
+        for ticker in orphans {
+            self.retire_market(&ticker);
+        }
+
+        let expired: Vec<String> = self
+            .retired
+            .iter()
+            .filter(|e| e.value().elapsed() >= ttl)
+            .map(|e| e.key().clone())
+            .collect();
+
+        for ticker in &expired {
+            self.retired.remove(ticker);
+            for (map, evicted) in [
+                ("tracked_markets", self.tracked_markets.remove(ticker).is_some()),
+                ("orderbooks", self.orderbooks.remove(ticker).is_some()),
+                ("tickers", self.tickers.remove(ticker).is_some()),
+                ("last_trades", self.last_trades.remove(ticker).is_some()),
+                ("imbalances", self.imbalances.remove(ticker).is_some()),
+            ] {
+                if evicted {
+                    metrics::inc(names::STATE_EVICTIONS_TOTAL, &[("map", map)]);
+                }
+            }
         }
+
+        for (map, len) in [
+            ("tracked_markets", self.tracked_markets.len()),
+            ("orderbooks", self.orderbooks.len()),
+            ("tickers", self.tickers.len()),
+            ("last_trades", self.last_trades.len()),
+            ("imbalances", self.imbalances.len()),
+            ("retired", self.retired.len()),
+        ] {
+            metrics::set(names::STATE_ENTRIES, &[("map", map)], len as f64);
+        }
+
+        expired
+    }
+
+    /// The live market of `series` closing soonest, i.e. the one an alert on
+    /// the underlying is most relevant to.
+    pub fn current_market_for_series(&self, series: &str) -> Option<String> {
+        let prefix = format!("{}-", series.to_uppercase());
+        self.tracked_markets
+            .iter()
+            .filter(|m| m.key().starts_with(&prefix) && !self.retired.contains_key(m.key()))
+            .min_by_key(|m| (m.close_time.is_none(), m.close_time.clone()))
+            .map(|m| m.key().clone())
+    }
+
+    /// When `market_ticker` closes, if tracked and its close time parses.
+    pub fn close_time(&self, market_ticker: &str) -> Option<DateTime<Utc>> {
+        self.tracked_markets
+            .get(market_ticker)
+            .and_then(|m| m.close_time.as_ref().and_then(|ct| DateTime::parse_from_rfc3339(ct).ok()))
+            .map(|dt| dt.to_utc())
     }
 
     pub fn get_top_bid(&self, market_ticker: &str) -> Option<f64> {
@@ -37,6 +153,10 @@ impl KalshiState {
     pub fn get_orderbook(&self, market_ticker: &str) -> Option<KalshiOrderbook> {
         self.orderbooks.get(market_ticker).map(|entry| entry.value().clone())
     }
+
+    pub fn get_imbalance(&self, market_ticker: &str) -> Option<DepthImbalance> {
+        self.imbalances.get(market_ticker).map(|entry| *entry.value())
+    }
 }
 
 impl Default for KalshiState {
@@ -45,3 +165,309 @@ impl Default for KalshiState {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct BinanceState {
+    /// Latest best bid/ask per symbol, seeded over REST on startup and then
+    /// kept current from the `bestBidAsk` stream.
+    pub best_bid_ask: DashMap<String, PriceUpdate>,
+    /// Levels currently flagged as probable icebergs per symbol, refreshed
+    /// on every depth snapshot.
+    pub icebergs: DashMap<String, Vec<Iceberg>>,
+    /// Latest depth imbalance per symbol
+    pub imbalances: DashMap<String, DepthImbalance>,
+    /// Candles backfilled over REST on startup, oldest first
+    pub klines: DashMap<String, Vec<Kline>>,
+    /// Full books kept from depth diffs when `BINANCE_LOCAL_BOOK` is set
+    pub books: DashMap<String, LocalOrderBook>,
+}
+
+impl BinanceState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_price(&self, symbol: &str) -> Option<PriceUpdate> {
+        self.best_bid_ask.get(symbol).map(|entry| entry.value().clone())
+    }
+
+    pub fn get_imbalance(&self, symbol: &str) -> Option<DepthImbalance> {
+        self.imbalances.get(symbol).map(|entry| *entry.value())
+    }
+
+    pub fn get_icebergs(&self, symbol: &str) -> Vec<Iceberg> {
+        self.icebergs.get(symbol).map(|entry| entry.value().clone()).unwrap_or_default()
+    }
+
+    /// Top `depth` levels a side of the symbol's local book, `None` until
+    /// it is synced.
+    pub fn get_book(&self, symbol: &str, depth: usize) -> Option<(Vec<PriceLevel>, Vec<PriceLevel>)> {
+        let book = self.books.get(symbol)?;
+        book.is_synced().then(|| (book.bids(depth), book.asks(depth)))
+    }
+
+    pub fn get_klines(&self, symbol: &str) -> Vec<Kline> {
+        self.klines.get(symbol).map(|entry| entry.value().clone()).unwrap_or_default()
+    }
+}
+
+/// A Binance pair's depth imbalance next to that of the current market of
+/// the Kalshi series it is mapped to in `SYMBOL_MAP`, for strategies that
+/// act on both venues' books.
+#[derive(Debug, Clone)]
+pub struct CrossVenueImbalance {
+    pub symbol: String,
+    pub binance: Option<DepthImbalance>,
+    pub kalshi_ticker: Option<String>,
+    pub kalshi: Option<DepthImbalance>,
+}
+
+impl CrossVenueImbalance {
+    pub fn new(symbol: &str, binance: &BinanceState, kalshi: &KalshiState) -> Self {
+        let kalshi_ticker = instruments::registry()
+            .kalshi_series(symbol)
+            .and_then(|series| kalshi.current_market_for_series(&series));
+        Self {
+            symbol: symbol.to_string(),
+            binance: binance.get_imbalance(symbol),
+            kalshi: kalshi_ticker.as_deref().and_then(|t| kalshi.get_imbalance(t)),
+            kalshi_ticker,
+        }
+    }
+
+    /// The side both books lean to past their own ratios, when they agree:
+    /// Binance bids over asks alongside Kalshi YES bids over YES asks reads
+    /// as [`ImbalanceLean::Bid`].
+    pub fn agreement(&self, binance: &ImbalanceConfig, kalshi: &ImbalanceConfig) -> Option<ImbalanceLean> {
+        let binance_lean = self.binance?.lean(binance.alert_ratio)?;
+        let kalshi_lean = self.kalshi?.lean(kalshi.alert_ratio)?;
+        (binance_lean == kalshi_lean).then_some(binance_lean)
+    }
+}
+
+#[derive(Default)]
+pub struct PolymarketState {
+    /// YES books by condition id
+    pub books: DashMap<String, PolymarketBook>,
+    /// Kalshi market ticker, or series for its current market, each
+    /// Polymarket market is compared with, by condition id
+    pub links: DashMap<String, String>,
+}
+
+/// Implied YES probabilities of the same event on both prediction venues.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbabilityGap {
+    pub condition_id: String,
+    pub kalshi_ticker: String,
+    pub polymarket: f64,
+    pub kalshi: f64,
+    /// Polymarket minus Kalshi
+    pub gap: f64,
+}
+
+impl PolymarketState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn implied_probability(&self, condition_id: &str) -> Option<f64> {
+        self.books.get(condition_id)?.implied_probability()
+    }
+
+    /// The gap of every linked market with a two-sided book on both venues.
+    pub fn probability_gaps(&self, kalshi: &KalshiState) -> Vec<ProbabilityGap> {
+        let mut gaps: Vec<ProbabilityGap> = self
+            .links
+            .iter()
+            .filter_map(|link| {
+                let kalshi_ticker = if link.value().contains('-') {
+                    link.value().clone()
+                } else {
+                    kalshi.current_market_for_series(link.value())?
+                };
+                let polymarket = self.implied_probability(link.key())?;
+                let kalshi_mid = (kalshi.get_top_bid(&kalshi_ticker)? + kalshi.get_top_ask(&kalshi_ticker)?) / 2.0;
+                Some(ProbabilityGap {
+                    condition_id: link.key().clone(),
+                    kalshi_ticker,
+                    polymarket,
+                    kalshi: kalshi_mid,
+                    gap: polymarket - kalshi_mid,
+                })
+            })
+            .collect();
+        gaps.sort_by(|a, b| a.condition_id.cmp(&b.condition_id));
+        gaps
+    }
+}
+
+/// What the Kalshi positions on one underlying, plus any Binance hedge of
+/// it, are exposed to. Keyed by the linked Binance symbol, or by the Kalshi
+/// series when there is none.
+#[derive(Debug, Clone, Serialize)]
+pub struct Exposure {
+    pub underlying: String,
+    pub spot: Option<f64>,
+    pub markets: usize,
+    pub contracts: u64,
+    /// Base asset units the Kalshi positions move like; negative is short.
+    pub kalshi_delta: f64,
+    pub hedge: f64,
+    pub net_delta: f64,
+    /// Dollars paid for the positions
+    pub cost: f64,
+    /// Largest loss over every settlement outcome, not counting hedge P&L.
+    pub worst_case_loss: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct PortfolioState {
+    pub exposures: DashMap<String, Exposure>,
+    /// Base asset quantity held as hedge, by Binance symbol; negative is short.
+    pub hedges: DashMap<String, f64>,
+}
+
+impl PortfolioState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn hedge(&self, symbol: &str) -> f64 {
+        self.hedges.get(symbol).map(|h| *h).unwrap_or_default()
+    }
+
+    pub fn worst_case_loss(&self) -> f64 {
+        self.exposures.iter().map(|e| e.worst_case_loss).sum()
+    }
+}
+
+static SESSION: OnceLock<SessionStats> = OnceLock::new();
+
+/// Counters since the process started, shared by every client.
+pub fn session() -> &'static SessionStats {
+    SESSION.get_or_init(SessionStats::new)
+}
+
+pub struct SessionStats {
+    started_at: Instant,
+    /// Message count by type, by exchange
+    messages: DashMap<&'static str, HashMap<String, u64>>,
+    alerts: AtomicU64,
+    reconnects: DashMap<&'static str, u64>,
+    /// Frames or payloads that could not be decoded, by exchange
+    decode_errors: DashMap<&'static str, u64>,
+    /// Alerts held back or downgraded, by reason
+    suppressions: DashMap<&'static str, u64>,
+}
+
+impl SessionStats {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            messages: DashMap::new(),
+            alerts: AtomicU64::new(0),
+            reconnects: DashMap::new(),
+            decode_errors: DashMap::new(),
+            suppressions: DashMap::new(),
+        }
+    }
+
+    pub fn record_message(&self, exchange: &'static str, msg_type: &str) {
+        let mut by_type = self.messages.entry(exchange).or_default();
+        match by_type.get_mut(msg_type) {
+            Some(count) => *count += 1,
+            None => {
+                by_type.insert(msg_type.to_string(), 1);
+            }
+        }
+    }
+
+    pub fn record_alert(&self) {
+        self.alerts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_reconnect(&self, exchange: &'static str) {
+        *self.reconnects.entry(exchange).or_default() += 1;
+    }
+
+    pub fn record_decode_error(&self, exchange: &'static str) {
+        *self.decode_errors.entry(exchange).or_default() += 1;
+    }
+
+    pub fn record_suppression(&self, reason: &'static str) {
+        *self.suppressions.entry(reason).or_default() += 1;
+    }
+
+    pub fn snapshot(&self) -> SessionSnapshot {
+        let totals = |map: &DashMap<&'static str, u64>| -> BTreeMap<String, u64> {
+            map.iter().map(|e| (e.key().to_string(), *e.value())).collect()
+        };
+        SessionSnapshot {
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            messages: self
+                .messages
+                .iter()
+                .map(|e| (e.key().to_string(), e.value().iter().map(|(k, v)| (k.clone(), *v)).collect()))
+                .collect(),
+            alerts: self.alerts.load(Ordering::Relaxed),
+            reconnects: totals(&self.reconnects),
+            decode_errors: totals(&self.decode_errors),
+            suppressions: totals(&self.suppressions),
+        }
+    }
+
+    /// Logs a [`SessionSnapshot`] line every `interval`.
+    pub fn spawn_logger(interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick fires at once, before anything has been counted
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                info!("{}", session().snapshot());
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionSnapshot {
+    pub uptime_secs: u64,
+    /// Message count by type, by exchange
+    pub messages: BTreeMap<String, BTreeMap<String, u64>>,
+    pub alerts: u64,
+    pub reconnects: BTreeMap<String, u64>,
+    pub decode_errors: BTreeMap<String, u64>,
+    /// Alerts suppressed or downgraded, by reason
+    pub suppressions: BTreeMap<String, u64>,
+}
+
+/// One compact line, e.g. `📊 Up 0h 05m 00s | kalshi 1200 (orderbook_delta
+/// 1180, trade 20) | 2 alerts | reconnects kalshi 1 | decode errors none |
+/// suppressed close_blackout 3`.
+impl fmt::Display for SessionSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |map: &BTreeMap<String, u64>| -> String {
+            if map.is_empty() {
+                return "none".to_string();
+            }
+            map.iter().map(|(k, v)| format!("{} {}", k, v)).collect::<Vec<_>>().join(", ")
+        };
+        write!(f, "📊 Up {} |", status::format_duration(self.uptime_secs))?;
+        if self.messages.is_empty() {
+            write!(f, " no messages |")?;
+        }
+        for (exchange, by_type) in &self.messages {
+            let total: u64 = by_type.values().sum();
+            write!(f, " {} {} ({}) |", exchange, total, counts(by_type))?;
+        }
+        write!(
+            f,
+            " {} alerts | reconnects {} | decode errors {} | suppressed {}",
+            self.alerts,
+            counts(&self.reconnects),
+            counts(&self.decode_errors),
+            counts(&self.suppressions)
+        )
+    }
+}
@@ -0,0 +1,424 @@
+//! Process-wide operational status, served at `/status` on the admin API and
+//! printed by `white-shark status`.
+//!
+//! Connection state and message ages are read back from the metrics registry;
+//! everything else is registered here by the component that owns it.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::error::{ErrorContext, Result, ResultExt};
+use crate::exchanges::acks::SubscriptionView;
+use crate::metrics::{names, registry};
+use crate::state;
+use crate::tags::{self, Tags};
+use crate::utils::http::ensure_success;
+
+const EXCHANGES: &[&str] = &["kalshi", "binance"];
+const RECENT_CAPACITY: usize = 100;
+/// Alerts a slow subscriber may fall behind by before it skips ahead.
+const ALERT_BROADCAST_CAPACITY: usize = 256;
+
+type DepthFn = Box<dyn Fn() -> Option<usize> + Send + Sync>;
+
+pub struct StatusBoard {
+    started_at: Instant,
+    queues: DashMap<&'static str, DepthFn>,
+    /// Market ticker -> series ticker.
+    tracked_markets: DashMap<String, String>,
+    /// Monitor id -> short description.
+    monitors: DashMap<String, String>,
+    recent_alerts: Mutex<VecDeque<AlertRecord>>,
+    /// Every recorded alert, for components acting on them.
+    alert_tx: broadcast::Sender<AlertRecord>,
+    recent_outcomes: Mutex<VecDeque<MonitorOutcome>>,
+    /// Exchange -> its latest subscription reconciliation.
+    subscriptions: DashMap<String, SubscriptionView>,
+    /// Active Kalshi announcements that concern us, newest first.
+    notices: Mutex<Vec<NoticeView>>,
+    /// Whether Kalshi reports trading open, once polled.
+    kalshi_trading_active: Mutex<Option<bool>>,
+}
+
+impl StatusBoard {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            queues: DashMap::new(),
+            tracked_markets: DashMap::new(),
+            monitors: DashMap::new(),
+            recent_alerts: Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)),
+            alert_tx: broadcast::channel(ALERT_BROADCAST_CAPACITY).0,
+            recent_outcomes: Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)),
+            subscriptions: DashMap::new(),
+            notices: Mutex::new(Vec::new()),
+            kalshi_trading_active: Mutex::new(None),
+        }
+    }
+
+    /// Reports the backlog of `tx`'s channel. Holds only a weak sender so
+    /// registration never keeps a channel open.
+    pub fn register_queue<T: Send + 'static>(&self, name: &'static str, tx: &mpsc::Sender<T>) {
+        let weak = tx.downgrade();
+        self.queues.insert(
+            name,
+            Box::new(move || weak.upgrade().map(|tx| tx.max_capacity() - tx.capacity())),
+        );
+    }
+
+    pub fn track_market(&self, market_ticker: &str, series_ticker: &str) {
+        self.tracked_markets
+            .insert(market_ticker.to_string(), series_ticker.to_string());
+    }
+
+    pub fn untrack_market(&self, market_ticker: &str) {
+        self.tracked_markets.remove(market_ticker);
+    }
+
+    /// Tracked market tickers of `series_ticker`.
+    pub fn markets_of_series(&self, series_ticker: &str) -> Vec<String> {
+        self.tracked_markets
+            .iter()
+            .filter(|entry| entry.value() == series_ticker)
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
+    pub fn monitor_started(&self, id: &str, description: &str) {
+        self.monitors.insert(id.to_string(), description.to_string());
+    }
+
+    pub fn monitor_finished(&self, id: &str, outcome: &str) {
+        let description = self.monitors.remove(id).map(|(_, d)| d).unwrap_or_default();
+        push_capped(
+            &self.recent_outcomes,
+            MonitorOutcome {
+                id: id.to_string(),
+                description,
+                outcome: outcome.to_string(),
+                finished_at: Utc::now(),
+            },
+        );
+    }
+
+    pub fn set_subscriptions(&self, view: SubscriptionView) {
+        self.subscriptions.insert(view.exchange.clone(), view);
+    }
+
+    pub fn set_notices(&self, notices: Vec<NoticeView>) {
+        *self.notices.lock().unwrap_or_else(|e| e.into_inner()) = notices;
+    }
+
+    pub fn set_kalshi_trading_active(&self, active: bool) {
+        *self.kalshi_trading_active.lock().unwrap_or_else(|e| e.into_inner()) = Some(active);
+    }
+
+    /// Tracked series tickers, without duplicates.
+    pub fn tracked_series(&self) -> Vec<String> {
+        let mut series: Vec<String> = self.tracked_markets.iter().map(|e| e.value().clone()).collect();
+        series.sort();
+        series.dedup();
+        series
+    }
+
+    pub fn record_alert(&self, alert: AlertRecord) {
+        state::session().record_alert();
+        let _ = self.alert_tx.send(alert.clone());
+        push_capped(&self.recent_alerts, alert);
+    }
+
+    /// Lists `alert` among the recent alerts without broadcasting or
+    /// counting it, so no notifier, writer or executor acts on it.
+    pub fn record_downgraded_alert(&self, alert: AlertRecord) {
+        push_capped(&self.recent_alerts, alert);
+    }
+
+    /// Alerts recorded from now on.
+    pub fn subscribe_alerts(&self) -> broadcast::Receiver<AlertRecord> {
+        self.alert_tx.subscribe()
+    }
+
+    /// Most recent first.
+    pub fn recent_alerts(&self) -> Vec<AlertRecord> {
+        let alerts = self.recent_alerts.lock().unwrap_or_else(|e| e.into_inner());
+        alerts.iter().rev().cloned().collect()
+    }
+
+    /// Most recent first.
+    pub fn recent_outcomes(&self) -> Vec<MonitorOutcome> {
+        let outcomes = self.recent_outcomes.lock().unwrap_or_else(|e| e.into_inner());
+        outcomes.iter().rev().cloned().collect()
+    }
+
+    pub fn active_monitors(&self) -> BTreeMap<String, String> {
+        self.monitors
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect()
+    }
+
+    pub fn report(&self) -> StatusReport {
+        let now = Utc::now().timestamp() as f64;
+        let connections = EXCHANGES
+            .iter()
+            .filter_map(|&exchange| {
+                let labels = [("exchange", exchange)];
+                let connected = registry().gauge_value(names::WS_CONNECTED, &labels)?;
+                let last_message = registry().gauge_value(names::WS_LAST_MESSAGE_TIMESTAMP, &labels);
+                Some(ConnectionStatus {
+                    exchange: exchange.to_string(),
+                    connected: connected > 0.0,
+                    last_message_age_secs: last_message.map(|ts| (now - ts).max(0.0)),
+                    reconnects: registry().counter_value(names::WS_RECONNECTS_TOTAL, &labels),
+                })
+            })
+            .collect();
+
+        let mut tracked_markets: Vec<TrackedMarket> = self
+            .tracked_markets
+            .iter()
+            .map(|e| TrackedMarket {
+                market_ticker: e.key().clone(),
+                series_ticker: e.value().clone(),
+            })
+            .collect();
+        tracked_markets.sort_by(|a, b| a.market_ticker.cmp(&b.market_ticker));
+
+        let queue_depths = self
+            .queues
+            .iter()
+            .filter_map(|e| Some((e.key().to_string(), (e.value())()?)))
+            .collect();
+
+        let active_monitors = self.active_monitors();
+
+        let mut subscriptions: Vec<SubscriptionView> =
+            self.subscriptions.iter().map(|e| e.value().clone()).collect();
+        subscriptions.sort_by(|a, b| a.exchange.cmp(&b.exchange));
+
+        StatusReport {
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            connections,
+            tracked_markets,
+            queue_depths,
+            active_monitors,
+            subscriptions,
+            tags: tags::current().clone(),
+            notices: self.notices.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            kalshi_trading_active: *self.kalshi_trading_active.lock().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+}
+
+fn push_capped<T>(queue: &Mutex<VecDeque<T>>, item: T) {
+    let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+    if queue.len() >= RECENT_CAPACITY {
+        queue.pop_front();
+    }
+    queue.push_back(item);
+}
+
+static BOARD: OnceLock<StatusBoard> = OnceLock::new();
+
+pub fn board() -> &'static StatusBoard {
+    BOARD.get_or_init(StatusBoard::new)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusReport {
+    pub uptime_secs: u64,
+    pub connections: Vec<ConnectionStatus>,
+    pub tracked_markets: Vec<TrackedMarket>,
+    pub queue_depths: BTreeMap<String, usize>,
+    pub active_monitors: BTreeMap<String, String>,
+    #[serde(default)]
+    pub subscriptions: Vec<SubscriptionView>,
+    #[serde(default)]
+    pub tags: Tags,
+    #[serde(default)]
+    pub notices: Vec<NoticeView>,
+    #[serde(default)]
+    pub kalshi_trading_active: Option<bool>,
+}
+
+/// A Kalshi announcement that concerns us.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoticeView {
+    /// `halt`, `rule_change` or `exchange`
+    pub kind: String,
+    /// Tracked series it names, if any.
+    pub series: Option<String>,
+    pub message: String,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStatus {
+    pub exchange: String,
+    pub connected: bool,
+    pub last_message_age_secs: Option<f64>,
+    pub reconnects: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRecord {
+    pub event_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    /// Kalshi series the alerting symbol is mapped to in `SYMBOL_MAP`.
+    #[serde(default)]
+    pub kalshi_series: Option<String>,
+    pub message: String,
+    /// Depth imbalance ratio behind the alert, for imbalance alerts
+    #[serde(default)]
+    pub imbalance_ratio: Option<f64>,
+    #[serde(default)]
+    pub tags: Tags,
+    /// Calendar event whose window the alert fired in
+    #[serde(default)]
+    pub calendar_event: Option<String>,
+    /// Which way the alert points the underlying, where its source can tell
+    #[serde(default)]
+    pub direction: Option<AlertDirection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertDirection {
+    Up,
+    Down,
+}
+
+impl AlertDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertDirection::Up => "up",
+            AlertDirection::Down => "down",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorOutcome {
+    pub id: String,
+    pub description: String,
+    pub outcome: String,
+    pub finished_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedMarket {
+    pub market_ticker: String,
+    pub series_ticker: String,
+}
+
+impl StatusReport {
+    /// Fetches the report from a running instance's admin API.
+    /// Sends `ADMIN_TOKEN` as a bearer token when set.
+    pub async fn fetch(addr: &str) -> Result<Self> {
+        let url = format!("http://{}/status", addr);
+        let context = || ErrorContext::new("fetch status").with_endpoint(url.clone());
+
+        let mut request = reqwest::Client::new().get(&url);
+        if let Ok(token) = crate::config::var("ADMIN_TOKEN") {
+            request = request.bearer_auth(token);
+        }
+        let resp = request.send().await.with_context(context)?;
+        let resp = ensure_success(resp).await.with_context(context)?;
+        resp.json().await.with_context(context)
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("🦈 Up {}\n", format_duration(self.uptime_secs)));
+        if !self.tags.is_default() {
+            out.push_str(&format!("🏷️ Account {}, strategy {}\n", self.tags.account, self.tags.strategy));
+        }
+
+        if self.kalshi_trading_active == Some(false) {
+            out.push_str("⛔ Kalshi trading is paused\n");
+        }
+        if !self.notices.is_empty() {
+            out.push_str("\nKalshi notices:\n");
+            for n in &self.notices {
+                out.push_str(&format!(
+                    "  [{}] {}{}\n",
+                    n.kind,
+                    n.series.as_deref().map(|s| format!("{}: ", s)).unwrap_or_default(),
+                    n.message
+                ));
+            }
+        }
+
+        out.push_str("\nConnections:\n");
+        if self.connections.is_empty() {
+            out.push_str("  (none)\n");
+        }
+        for c in &self.connections {
+            let age = c
+                .last_message_age_secs
+                .map(|s| format!("{:.1}s ago", s))
+                .unwrap_or_else(|| "never".to_string());
+            out.push_str(&format!(
+                "  {:<8} {:<13} last message {:<12} reconnects {}\n",
+                c.exchange,
+                if c.connected { "🟢 connected" } else { "🔴 down" },
+                age,
+                c.reconnects
+            ));
+        }
+
+        out.push_str(&format!("\nTracked markets ({}):\n", self.tracked_markets.len()));
+        for m in &self.tracked_markets {
+            out.push_str(&format!("  {} ({})\n", m.market_ticker, m.series_ticker));
+        }
+
+        out.push_str("\nQueue depths:\n");
+        for (name, depth) in &self.queue_depths {
+            out.push_str(&format!("  {:<20} {}\n", name, depth));
+        }
+
+        out.push_str(&format!("\nActive monitors ({}):\n", self.active_monitors.len()));
+        for (id, description) in &self.active_monitors {
+            out.push_str(&format!("  {} {}\n", id, description));
+        }
+
+        out.push_str("\nSubscriptions:\n");
+        for s in &self.subscriptions {
+            out.push_str(&format!(
+                "  {:<8} {} intended, {} confirmed, {} pending\n",
+                s.exchange,
+                s.intended,
+                s.confirmed,
+                s.pending.len()
+            ));
+            if !s.missing.is_empty() {
+                out.push_str(&format!("    missing: {}\n", s.missing.join(", ")));
+            }
+            if !s.unexpected.is_empty() {
+                out.push_str(&format!("    unexpected: {}\n", s.unexpected.join(", ")));
+            }
+            for p in &s.pending {
+                out.push_str(&format!(
+                    "    #{} {} {:?} attempt {}, {:.1}s ago\n",
+                    p.id,
+                    p.kind.as_str(),
+                    p.topics,
+                    p.attempts,
+                    p.age_secs
+                ));
+            }
+        }
+        out
+    }
+}
+
+pub(crate) fn format_duration(secs: u64) -> String {
+    format!("{}h {:02}m {:02}s", secs / 3600, (secs % 3600) / 60, secs % 60)
+}
@@ -0,0 +1,338 @@
+//! Synthetic Binance and Kalshi feeds for exercising the pipeline without
+//! recorded data.
+//!
+//! [`SyntheticFeed`] yields [`RawFrame`]s in the exact wire formats the
+//! exchanges send (SBE for Binance, JSON for Kalshi), so the output can be fed
+//! to the same decoders, written to a recording, or played back with the
+//! replay tooling.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+
+use crate::exchanges::binance::sbe::encoder::{
+    encode_best_bid_ask, encode_depth_snapshot, encode_trades, EncodedTrade, Scale,
+};
+use crate::recorder::{FrameSource, RawFrame};
+
+#[derive(Debug, Clone)]
+pub struct SyntheticConfig {
+    pub seed: u64,
+    pub start_micros: i64,
+    pub tick_interval_ms: u64,
+    pub binance_symbol: String,
+    pub start_price: f64,
+    pub tick_size: f64,
+    /// Standard deviation of log returns per second of simulated time
+    pub volatility: f64,
+    pub depth_levels: usize,
+    pub base_level_qty: f64,
+    /// Chance per tick that an imbalance episode starts
+    pub imbalance_probability: f64,
+    /// Quantity multiplier applied to the heavy side during an episode
+    pub imbalance_ratio: f64,
+    pub imbalance_ticks: u32,
+    pub kalshi_market_ticker: String,
+    pub kalshi_strike: f64,
+    /// Price move, as a fraction of the strike, that moves Kalshi odds by ~46 points
+    pub kalshi_sensitivity: f64,
+    pub kalshi_every_ticks: u32,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            start_micros: chrono::Utc::now().timestamp_micros(),
+            tick_interval_ms: 100,
+            binance_symbol: "BTCUSDT".to_string(),
+            start_price: 100_000.0,
+            tick_size: 0.01,
+            volatility: 0.0003,
+            depth_levels: 20,
+            base_level_qty: 0.5,
+            imbalance_probability: 0.002,
+            imbalance_ratio: 150.0,
+            imbalance_ticks: 50,
+            kalshi_market_ticker: "KXBTC15M-SYNTH-00".to_string(),
+            kalshi_strike: 100_000.0,
+            kalshi_sensitivity: 0.002,
+            kalshi_every_ticks: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EpisodeSide {
+    Bid,
+    Ask,
+}
+
+struct Episode {
+    side: EpisodeSide,
+    remaining: u32,
+}
+
+/// Infinite iterator of synthetic frames in timestamp order.
+pub struct SyntheticFeed {
+    config: SyntheticConfig,
+    rng: StdRng,
+    scale: Scale,
+    now_micros: i64,
+    tick: u64,
+    mid: f64,
+    book_update_id: i64,
+    trade_id: i64,
+    episode: Option<Episode>,
+    kalshi_yes: Option<BTreeMap<i64, i64>>,
+    kalshi_no: BTreeMap<i64, i64>,
+    kalshi_seq: u64,
+    pending: VecDeque<RawFrame>,
+}
+
+impl SyntheticFeed {
+    pub fn new(config: SyntheticConfig) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(config.seed),
+            scale: Scale::default(),
+            now_micros: config.start_micros,
+            tick: 0,
+            mid: config.start_price,
+            book_update_id: 1,
+            trade_id: 1,
+            episode: None,
+            kalshi_yes: None,
+            kalshi_no: BTreeMap::new(),
+            kalshi_seq: 0,
+            pending: VecDeque::new(),
+            config,
+        }
+    }
+
+    /// Standard normal sample (Box-Muller).
+    fn normal(&mut self) -> f64 {
+        let u1: f64 = self.rng.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = self.rng.gen();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    fn step(&mut self) {
+        self.tick += 1;
+        self.now_micros += self.config.tick_interval_ms as i64 * 1000;
+
+        if self.episode.is_none() && self.rng.gen_bool(self.config.imbalance_probability) {
+            let side = if self.rng.gen_bool(0.5) { EpisodeSide::Bid } else { EpisodeSide::Ask };
+            self.episode = Some(Episode {
+                side,
+                remaining: self.config.imbalance_ticks,
+            });
+        }
+
+        // Episodes lean the walk towards the heavy side of the book.
+        let dt = self.config.tick_interval_ms as f64 / 1000.0;
+        let sigma = self.config.volatility * dt.sqrt();
+        let drift = match self.episode.as_ref().map(|e| e.side) {
+            Some(EpisodeSide::Bid) => sigma * 0.5,
+            Some(EpisodeSide::Ask) => -sigma * 0.5,
+            None => 0.0,
+        };
+        let z = self.normal();
+        self.mid *= (drift + sigma * z).exp();
+
+        self.emit_binance();
+        if self.tick.is_multiple_of(self.config.kalshi_every_ticks.max(1) as u64) {
+            self.emit_kalshi();
+        }
+
+        if let Some(episode) = self.episode.as_mut() {
+            episode.remaining = episode.remaining.saturating_sub(1);
+            if episode.remaining == 0 {
+                self.episode = None;
+            }
+        }
+    }
+
+    fn push(&mut self, source: FrameSource, data: Vec<u8>) {
+        self.pending.push_back(RawFrame {
+            timestamp_micros: self.now_micros,
+            source,
+            data,
+        });
+    }
+
+    fn emit_binance(&mut self) {
+        let tick = self.config.tick_size;
+        let best_bid = (self.mid / tick).floor() * tick;
+        let best_ask = best_bid + tick;
+        let (bid_mult, ask_mult) = match self.episode.as_ref().map(|e| e.side) {
+            Some(EpisodeSide::Bid) => (self.config.imbalance_ratio, 1.0),
+            Some(EpisodeSide::Ask) => (1.0, self.config.imbalance_ratio),
+            None => (1.0, 1.0),
+        };
+
+        let mut bids = Vec::with_capacity(self.config.depth_levels);
+        let mut asks = Vec::with_capacity(self.config.depth_levels);
+        for level in 0..self.config.depth_levels {
+            let depth_factor = 1.0 + level as f64 * 0.3;
+            let bid_qty = self.config.base_level_qty * depth_factor * self.rng.gen_range(0.5..1.5);
+            let ask_qty = self.config.base_level_qty * depth_factor * self.rng.gen_range(0.5..1.5);
+            bids.push((best_bid - level as f64 * tick, bid_qty * bid_mult));
+            asks.push((best_ask + level as f64 * tick, ask_qty * ask_mult));
+        }
+
+        let symbol = self.config.binance_symbol.clone();
+        self.book_update_id += 1;
+        let frame = encode_best_bid_ask(
+            &symbol,
+            self.now_micros,
+            self.book_update_id,
+            self.scale,
+            bids[0].0,
+            bids[0].1,
+            asks[0].0,
+            asks[0].1,
+        );
+        self.push(FrameSource::BinanceSbe, frame);
+
+        let frame = encode_depth_snapshot(
+            &symbol,
+            self.now_micros,
+            self.book_update_id,
+            self.scale,
+            &bids,
+            &asks,
+        );
+        self.push(FrameSource::BinanceSbe, frame);
+
+        let buy_probability = match self.episode.as_ref().map(|e| e.side) {
+            Some(EpisodeSide::Bid) => 0.8,
+            Some(EpisodeSide::Ask) => 0.2,
+            None => 0.5,
+        };
+        let count = self.rng.gen_range(0..4);
+        let trades: Vec<EncodedTrade> = (0..count)
+            .map(|_| {
+                let is_buy = self.rng.gen_bool(buy_probability);
+                self.trade_id += 1;
+                EncodedTrade {
+                    id: self.trade_id,
+                    price: if is_buy { best_ask } else { best_bid },
+                    qty: self.config.base_level_qty * self.rng.gen_range(0.01..0.5),
+                    is_buyer_maker: !is_buy,
+                }
+            })
+            .collect();
+        if !trades.is_empty() {
+            let frame = encode_trades(&symbol, self.now_micros, self.scale, &trades);
+            self.push(FrameSource::BinanceSbe, frame);
+        }
+    }
+
+    /// Fair YES probability in cents, clamped to the tradable 1..=99 range.
+    fn kalshi_fair_cents(&self) -> i64 {
+        let x = (self.mid - self.config.kalshi_strike)
+            / (self.config.kalshi_strike * self.config.kalshi_sensitivity);
+        let p = 1.0 / (1.0 + (-x).exp());
+        ((p * 100.0).round() as i64).clamp(1, 99)
+    }
+
+    fn kalshi_target_book(&mut self, fair: i64) -> (BTreeMap<i64, i64>, BTreeMap<i64, i64>) {
+        let mut yes = BTreeMap::new();
+        let mut no = BTreeMap::new();
+        for offset in 1..=5 {
+            let yes_price = fair - offset;
+            if yes_price >= 1 {
+                yes.insert(yes_price, self.rng.gen_range(10..500));
+            }
+            let no_price = 100 - fair - offset;
+            if no_price >= 1 {
+                no.insert(no_price, self.rng.gen_range(10..500));
+            }
+        }
+        (yes, no)
+    }
+
+    fn emit_kalshi(&mut self) {
+        let fair = self.kalshi_fair_cents();
+        let (yes, no) = self.kalshi_target_book(fair);
+        let ticker = self.config.kalshi_market_ticker.clone();
+
+        let previous_yes = match self.kalshi_yes.replace(yes.clone()) {
+            Some(previous) => previous,
+            None => {
+                let levels = |book: &BTreeMap<i64, i64>| {
+                    book.iter()
+                        .map(|(cents, qty)| json!([dollars(*cents), qty]))
+                        .collect::<Vec<_>>()
+                };
+                self.kalshi_seq += 1;
+                let msg = json!({
+                    "type": "orderbook_snapshot",
+                    "sid": 1,
+                    "seq": self.kalshi_seq,
+                    "msg": {
+                        "market_ticker": ticker,
+                        "yes_dollars": levels(&yes),
+                        "no_dollars": levels(&no),
+                    }
+                });
+                self.push(FrameSource::KalshiJson, msg.to_string().into_bytes());
+                self.kalshi_no = no;
+                return;
+            }
+        };
+
+        let previous_no = std::mem::replace(&mut self.kalshi_no, no.clone());
+        for (side, before, after) in [("yes", previous_yes, yes), ("no", previous_no, no)] {
+            for (cents, delta) in book_diff(&before, &after) {
+                self.kalshi_seq += 1;
+                let msg = json!({
+                    "type": "orderbook_delta",
+                    "sid": 1,
+                    "seq": self.kalshi_seq,
+                    "msg": {
+                        "market_ticker": ticker,
+                        "price_dollars": dollars(cents),
+                        "delta": delta,
+                        "side": side,
+                    }
+                });
+                self.push(FrameSource::KalshiJson, msg.to_string().into_bytes());
+            }
+        }
+    }
+}
+
+impl Iterator for SyntheticFeed {
+    type Item = RawFrame;
+
+    fn next(&mut self) -> Option<RawFrame> {
+        while self.pending.is_empty() {
+            self.step();
+        }
+        self.pending.pop_front()
+    }
+}
+
+fn dollars(cents: i64) -> String {
+    format!("{:.4}", cents as f64 / 100.0)
+}
+
+/// Quantity changes turning `before` into `after`, per price level.
+fn book_diff(before: &BTreeMap<i64, i64>, after: &BTreeMap<i64, i64>) -> Vec<(i64, i64)> {
+    let mut deltas = Vec::new();
+    for (price, qty) in before {
+        let new_qty = after.get(price).copied().unwrap_or(0);
+        if new_qty != *qty {
+            deltas.push((*price, new_qty - qty));
+        }
+    }
+    for (price, qty) in after {
+        if !before.contains_key(price) {
+            deltas.push((*price, *qty));
+        }
+    }
+    deltas
+}
@@ -0,0 +1,54 @@
+//! systemd `Type=notify` integration.
+//!
+//! Outside systemd (`NOTIFY_SOCKET` unset) every call here is a no-op. Under
+//! systemd we report `READY=1` once the feeds are about to start, `STOPPING=1`
+//! when a stop signal arrives, and, when the unit sets `WatchdogSec=`, pet
+//! the watchdog at half its interval for as long as the processing loop keeps
+//! beating the [`HeartbeatHandle`]. A hung loop stops the pets and systemd
+//! restarts the service.
+
+use std::time::Duration;
+
+use sd_notify::NotifyState;
+use tracing::{info, warn};
+
+use crate::heartbeat::HeartbeatHandle;
+
+pub fn notify_ready() {
+    notify(&[NotifyState::Ready, NotifyState::Status("Streaming market data")]);
+}
+
+pub fn notify_stopping() {
+    notify(&[NotifyState::Stopping, NotifyState::Status("Shutting down")]);
+}
+
+/// Starts petting the systemd watchdog if the unit enables it.
+pub fn spawn_watchdog(heartbeat: HeartbeatHandle) {
+    let mut usec = 0;
+    if !sd_notify::watchdog_enabled(false, &mut usec) {
+        return;
+    }
+
+    let timeout = Duration::from_micros(usec);
+    info!("🐕 systemd watchdog enabled, timeout {}s", timeout.as_secs());
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(timeout / 2);
+        loop {
+            ticker.tick().await;
+            // Skip the pet if the loop hasn't made progress within the
+            // timeout, so systemd sees the hang.
+            match heartbeat.last_beat_age() {
+                Some(age) if age < timeout => notify(&[NotifyState::Watchdog]),
+                Some(age) => warn!("No heartbeat for {}s, withholding watchdog ping", age.as_secs()),
+                // Still starting up; the unit's start timeout covers this.
+                None => notify(&[NotifyState::Watchdog]),
+            }
+        }
+    });
+}
+
+fn notify(states: &[NotifyState]) {
+    if let Err(e) = sd_notify::notify(false, states) {
+        warn!("Failed to notify systemd: {}", e);
+    }
+}
@@ -0,0 +1,85 @@
+//! Account and strategy the process runs for, stamped on everything it
+//! persists or reports: database rows, alerts, event journals and the
+//! status report.
+//!
+//! Deployments that run several accounts or strategies against the same
+//! database, recordings or dashboards set `ACCOUNT_ID` and `STRATEGY_ID`
+//! per instance so each stream can be analyzed on its own. Both default to
+//! `default`. Like [`timezone`](crate::timezone), offline tools that never
+//! call [`init`] read the same env vars lazily.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+pub const DEFAULT_TAG: &str = "default";
+/// Width of the tag columns in the database.
+pub const MAX_TAG_LEN: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tags {
+    #[serde(default = "default_tag")]
+    pub account: String,
+    #[serde(default = "default_tag")]
+    pub strategy: String,
+}
+
+fn default_tag() -> String {
+    DEFAULT_TAG.to_string()
+}
+
+impl Default for Tags {
+    fn default() -> Self {
+        Self {
+            account: default_tag(),
+            strategy: default_tag(),
+        }
+    }
+}
+
+impl Tags {
+    /// Reads `ACCOUNT_ID` and `STRATEGY_ID`: up to [`MAX_TAG_LEN`] ASCII
+    /// letters, digits, `-`, `_` or `.`.
+    pub fn from_env() -> Result<Self> {
+        let read = |name: &str| match crate::config::var(name) {
+            Ok(v) if is_valid(&v) => Ok(v),
+            Ok(v) => Err(Error::Config(format!("Invalid {} '{}'", name, v))),
+            Err(_) => Ok(default_tag()),
+        };
+        Ok(Self {
+            account: read("ACCOUNT_ID")?,
+            strategy: read("STRATEGY_ID")?,
+        })
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn is_valid(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.len() <= MAX_TAG_LEN
+        && tag.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+static TAGS: OnceLock<Tags> = OnceLock::new();
+
+/// Sets the tags for the life of the process; later calls are ignored.
+pub fn init(tags: Tags) {
+    let _ = TAGS.set(tags);
+}
+
+pub fn current() -> &'static Tags {
+    TAGS.get_or_init(|| Tags::from_env().unwrap_or_default())
+}
+
+pub fn account() -> &'static str {
+    &current().account
+}
+
+pub fn strategy() -> &'static str {
+    &current().strategy
+}
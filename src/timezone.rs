@@ -0,0 +1,49 @@
+//! Display and market-hours timezones from [`TimezoneConfig`].
+//!
+//! Everything is stored and passed around in UTC; these only decide how a
+//! timestamp is shown and when schedules tied to local time fire. Offline
+//! tools that never call [`init`] read the same env vars lazily.
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::config::TimezoneConfig;
+
+static TIMEZONES: OnceLock<TimezoneConfig> = OnceLock::new();
+
+/// Sets the timezones for the life of the process; later calls are ignored.
+pub fn init(config: TimezoneConfig) {
+    let _ = TIMEZONES.set(config);
+}
+
+fn config() -> &'static TimezoneConfig {
+    TIMEZONES.get_or_init(|| TimezoneConfig::from_env().unwrap_or_default())
+}
+
+pub fn display() -> Tz {
+    config().display
+}
+
+pub fn market() -> Tz {
+    config().market
+}
+
+/// `ts` in the display timezone, e.g. `2024-03-01 09:30:00 EST`.
+pub fn format(ts: DateTime<Utc>) -> String {
+    ts.with_timezone(&display()).format("%Y-%m-%d %H:%M:%S %Z").to_string()
+}
+
+/// Like [`format`] for microseconds since the epoch, falling back to the raw
+/// number if out of range.
+pub fn format_micros(micros: i64) -> String {
+    DateTime::from_timestamp_micros(micros)
+        .map(format)
+        .unwrap_or_else(|| micros.to_string())
+}
+
+/// Display-timezone date for report file names, e.g. `20240301`.
+pub fn file_date(ts: DateTime<Utc>) -> String {
+    ts.with_timezone(&display()).format("%Y%m%d").to_string()
+}
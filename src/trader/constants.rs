@@ -13,4 +13,16 @@ pub const ORDER_COOLDOWN_SECS: u64 = 5;
 
 pub const EXIT_ASK_THRESHOLD: f64 = 0.85;
 
-pub const MAX_CANCEL_CHUNK_SIZE: usize = 20;
\ No newline at end of file
+pub const MAX_CANCEL_CHUNK_SIZE: usize = 20;
+/// Distances below mid, in cents, at which virtual quotes are parked to
+/// estimate fill probability.
+pub const QUOTE_DISTANCES_CENTS: [u32; 6] = [0, 1, 2, 3, 5, 10];
+
+/// How long a virtual quote may rest before it counts as unfilled.
+pub const FILL_HORIZON_SECS: i64 = 60;
+
+/// Resolved virtual quotes needed before a distance's fill rate is trusted.
+pub const FILL_CURVE_MIN_SAMPLES: u64 = 50;
+
+/// Fill probability the ladder's recommended quoting distance must reach.
+pub const MIN_QUOTE_FILL_PROBABILITY: f64 = 0.5;
@@ -1,41 +1,61 @@
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 
 use tracing::info;
 
 use super::main::OrderDecision;
-use super::positions::{FillStatus, PositionManager};
-use crate::error::Result;
+use super::positions::PositionManager;
+use crate::config::RiskConfig;
+use crate::error::{Error, Result};
 use crate::exchanges::kalshi::{OrderSide, OrderType};
 use crate::exchanges::kalshi::api::KalshiApi;
 use crate::exchanges::kalshi::models::OrderAction;
+use crate::execution::risk::RiskManager;
+use crate::metrics::{self, names};
 use crate::trader::constants::MAX_CANCEL_CHUNK_SIZE;
+use crate::utils::event_id::EventId;
 
+/// Sends the trader's decisions, every order checked against the same
+/// [`risk`](crate::execution::risk) limits and kill switch as alert orders.
 pub struct OrderExecutor {
     api: Arc<KalshiApi>,
     positions: PositionManager,
+    risk: RiskManager,
 }
 
 impl OrderExecutor {
-    pub fn new(api: Arc<KalshiApi>, positions: PositionManager) -> Self {
-        Self { api, positions }
+    pub fn new(api: Arc<KalshiApi>, positions: PositionManager, risk: RiskConfig) -> Self {
+        Self {
+            risk: RiskManager::new(risk, &positions.portfolio()),
+            api,
+            positions,
+        }
     }
 
-    pub async fn execute(&self, decision: OrderDecision) -> Result<()> {
+    pub async fn execute(&mut self, decision: OrderDecision) -> Result<()> {
         match decision {
             OrderDecision::CancelAll => self.cancel_all().await,
             OrderDecision::Place {
+                ref event_id,
+                leg,
                 ref ticker,
                 side,
                 price,
                 contracts,
                 order_type,
-            } => self.place_order(ticker, side, price, contracts, order_type).await,
+            } => {
+                self.place_order(event_id, leg, ticker, side, price, contracts, order_type)
+                    .await
+            }
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn place_order(
-        &self,
+        &mut self,
+        event_id: &EventId,
+        leg: usize,
         ticker: &str,
         side: OrderSide,
         price: f64,
@@ -44,12 +64,29 @@ impl OrderExecutor {
     ) -> Result<()> {
         let price_cents = (price * 100.0) as u64;
 
+        let delta = match side {
+            OrderSide::Yes => contracts as i64,
+            OrderSide::No => -(contracts as i64),
+        };
+        let resting = self.positions.resting(ticker);
+        let open_orders = self.positions.open_order_ids().len();
+        let portfolio = self.positions.portfolio();
+        if let Err(breach) = self.risk.check(ticker, delta, resting, open_orders, &portfolio) {
+            metrics::inc(names::RISK_REJECTIONS_TOTAL, &[("limit", breach.limit())]);
+            return Err(Error::Other(format!("Order on {} refused: {}", ticker, breach)));
+        }
+
         info!(
             "Executing {:?} order: {} {:?} {}x @ {}c",
             order_type, ticker, side, contracts, price_cents
         );
 
-        let resp = self
+        let side_label = format!("{:?}", side).to_lowercase();
+        let type_label = format!("{:?}", order_type).to_lowercase();
+        let labels = [("side", side_label.as_str()), ("order_type", type_label.as_str())];
+
+        let started = Instant::now();
+        let result = self
             .api
             .create_order(
                 ticker,
@@ -58,28 +95,28 @@ impl OrderExecutor {
                 contracts,
                 price_cents,
                 order_type,
+                Some(event_id.child(leg)),
             )
-            .await?;
+            .await;
+        metrics::observe(names::ORDER_LATENCY_SECONDS, &[], started.elapsed().as_secs_f64());
+        metrics::inc(names::ORDERS_TOTAL, &labels);
 
-        let order = &resp.order;
-        let status = if order.remaining_count > 0 {
-            FillStatus::Open
-        } else {
-            FillStatus::Filled
-        };
+        let resp = result.inspect_err(|_| metrics::inc(names::ORDER_ERRORS_TOTAL, &[]))?;
 
+        let order = &resp.order;
         if order.fill_count > 0 || order.remaining_count > 0 {
             info!(
                 "Order {}: filled={}, remaining={}",
                 order.order_id, order.fill_count, order.remaining_count
             );
             self.positions.add_fill(
+                event_id,
                 ticker,
                 side,
                 order.order_id.clone(),
                 order.fill_count as u64,
                 price,
-                status,
+                order.remaining_count as u64,
             );
         }
 
@@ -115,6 +152,7 @@ impl OrderExecutor {
         for chunk in to_cancel.chunks(MAX_CANCEL_CHUNK_SIZE) {
             let resp = self.api.batch_cancel_orders(chunk).await?;
 
+            metrics::add(names::CANCELLED_ORDERS_TOTAL, &[], resp.orders.len() as u64);
             for cancelled in &resp.orders {
                 self.positions.mark_cancelled(&cancelled.order_id);
                 info!(
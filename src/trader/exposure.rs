@@ -0,0 +1,249 @@
+//! Portfolio view of the Kalshi positions, grouped by underlying.
+//!
+//! A YES contract on "above strike K at close" is worth about `N(d)` with
+//! `d = (ln(S/K) - σ²T/2) / (σ√T)`, so its sensitivity to the underlying is
+//! `φ(d) / (S σ √T)` units of the base asset per contract; NO contracts have
+//! the opposite sign. Worst-case loss is taken over every settlement price
+//! band the strikes carve out, since positions on one underlying settle
+//! together.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::error;
+
+use super::positions::{Holding, PositionManager};
+use crate::config::ExposureConfig;
+use crate::exchanges::binance::api::BinanceApi;
+use crate::exchanges::kalshi::models::{KalshiMarket, OrderSide};
+use crate::instruments;
+use crate::metrics::{self, names};
+use crate::state::{Exposure, KalshiState, PortfolioState};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+struct Leg {
+    side: OrderSide,
+    contracts: u64,
+    strike: Option<f64>,
+    close_time: Option<DateTime<Utc>>,
+}
+
+pub struct ExposureTracker {
+    config: ExposureConfig,
+    positions: PositionManager,
+    kalshi: Arc<KalshiState>,
+    portfolio: Arc<PortfolioState>,
+    binance: BinanceApi,
+}
+
+impl ExposureTracker {
+    pub fn spawn(
+        config: ExposureConfig,
+        positions: PositionManager,
+        kalshi: Arc<KalshiState>,
+        portfolio: Arc<PortfolioState>,
+    ) {
+        let tracker = Self {
+            config,
+            positions,
+            kalshi,
+            portfolio,
+            binance: BinanceApi::new(),
+        };
+        tokio::spawn(tracker.run());
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+        loop {
+            ticker.tick().await;
+            self.refresh().await;
+        }
+    }
+
+    async fn refresh(&self) {
+        let now = Utc::now();
+        let mut groups: HashMap<(String, bool), Vec<Holding>> = HashMap::new();
+        for holding in self.positions.holdings() {
+            let series = holding.ticker.split('-').next().unwrap_or(&holding.ticker).to_string();
+            let key = match instruments::registry().binance_symbol(&series) {
+                Some(symbol) => (symbol, true),
+                None => (series, false),
+            };
+            groups.entry(key).or_default().push(holding);
+        }
+
+        let mut seen = Vec::new();
+        for ((underlying, linked), holdings) in groups {
+            let spot = if linked { self.spot(&underlying).await } else { None };
+            let legs: Vec<Leg> = holdings.iter().map(|h| self.leg(h)).collect();
+
+            let kalshi_delta = match spot {
+                Some(spot) => legs
+                    .iter()
+                    .map(|leg| {
+                        let (Some(strike), Some(close_time)) = (leg.strike, leg.close_time) else {
+                            return 0.0;
+                        };
+                        let years = years_until(close_time, now);
+                        let delta = binary_delta(spot, strike, self.config.volatility, years) * leg.contracts as f64;
+                        match leg.side {
+                            OrderSide::Yes => delta,
+                            OrderSide::No => -delta,
+                        }
+                    })
+                    .sum(),
+                None => 0.0,
+            };
+            let hedge = self.portfolio.hedge(&underlying);
+            let cost: f64 = holdings.iter().map(|h| h.cost).sum();
+
+            let exposure = Exposure {
+                underlying: underlying.clone(),
+                spot,
+                markets: holdings.len(),
+                contracts: holdings.iter().map(|h| h.contracts).sum(),
+                kalshi_delta,
+                hedge,
+                net_delta: kalshi_delta + hedge,
+                cost,
+                worst_case_loss: (cost - worst_case_payout(&legs)).max(0.0),
+                updated_at: now,
+            };
+            metrics::set(names::PORTFOLIO_NET_DELTA, &[("underlying", &underlying)], exposure.net_delta);
+            metrics::set(
+                names::PORTFOLIO_WORST_CASE_LOSS,
+                &[("underlying", &underlying)],
+                exposure.worst_case_loss,
+            );
+            self.portfolio.exposures.insert(underlying.clone(), exposure);
+            seen.push(underlying);
+        }
+
+        // Keep hedged underlyings visible until the hedge is unwound.
+        self.portfolio.exposures.retain(|underlying, exposure| {
+            if seen.contains(underlying) {
+                return true;
+            }
+            exposure.hedge = self.portfolio.hedge(underlying);
+            exposure.kalshi_delta = 0.0;
+            exposure.net_delta = exposure.hedge;
+            exposure.cost = 0.0;
+            exposure.worst_case_loss = 0.0;
+            exposure.markets = 0;
+            exposure.contracts = 0;
+            exposure.updated_at = now;
+            metrics::set(names::PORTFOLIO_NET_DELTA, &[("underlying", underlying)], exposure.net_delta);
+            metrics::set(names::PORTFOLIO_WORST_CASE_LOSS, &[("underlying", underlying)], 0.0);
+            exposure.hedge != 0.0
+        });
+    }
+
+    async fn spot(&self, symbol: &str) -> Option<f64> {
+        match self.binance.fetch_book_ticker(symbol).await {
+            Ok(update) => Some((update.bid? + update.ask?) / 2.0),
+            Err(e) => {
+                error!("Failed to price {} for exposure: {}", symbol, e);
+                None
+            }
+        }
+    }
+
+    fn leg(&self, holding: &Holding) -> Leg {
+        let (strike, close_time) = self
+            .kalshi
+            .tracked_markets
+            .get(&holding.ticker)
+            .map(|m| market_terms(&m))
+            .unwrap_or((None, None));
+        Leg {
+            side: holding.side,
+            contracts: holding.contracts,
+            strike,
+            close_time,
+        }
+    }
+}
+
+/// Strike and close time of an "above strike" market, where listed.
+pub fn market_terms(market: &KalshiMarket) -> (Option<f64>, Option<DateTime<Utc>>) {
+    let strike = market.extra.get("floor_strike").and_then(|v| v.as_f64());
+    let close_time = market
+        .close_time
+        .as_ref()
+        .and_then(|ct| DateTime::parse_from_rfc3339(ct).ok())
+        .map(|ct| ct.with_timezone(&Utc));
+    (strike, close_time)
+}
+
+/// Smallest total payout over every settlement price. Legs without a known
+/// strike are assumed to lose.
+fn worst_case_payout(legs: &[Leg]) -> f64 {
+    let mut strikes: Vec<f64> = legs.iter().filter_map(|l| l.strike).collect();
+    strikes.sort_by(|a, b| a.total_cmp(b));
+    strikes.dedup();
+
+    // One settlement price per band: each strike stands for the band up to
+    // and including it (YES on "above K" loses at exactly K), and infinity
+    // for the band above the highest.
+    let mut prices = strikes;
+    prices.push(f64::INFINITY);
+
+    prices
+        .iter()
+        .map(|&settle| {
+            legs.iter()
+                .filter(|leg| match (leg.strike, leg.side) {
+                    (Some(strike), OrderSide::Yes) => settle > strike,
+                    (Some(strike), OrderSide::No) => settle <= strike,
+                    (None, _) => false,
+                })
+                .map(|leg| leg.contracts as f64)
+                .sum::<f64>()
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Base asset units per contract of a YES binary on "above `strike`";
+/// 0 once the market has closed.
+pub fn binary_delta(spot: f64, strike: f64, volatility: f64, years: f64) -> f64 {
+    if years <= 0.0 || spot <= 0.0 || strike <= 0.0 || volatility <= 0.0 {
+        return 0.0;
+    }
+    let vol_sqrt_t = volatility * years.sqrt();
+    let d = ((spot / strike).ln() - vol_sqrt_t * vol_sqrt_t / 2.0) / vol_sqrt_t;
+    let pdf = (-d * d / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    pdf / (spot * vol_sqrt_t)
+}
+
+/// Model value of a YES binary on "above `strike`", `N(d)`; the payout
+/// itself once the market has closed.
+pub fn binary_price(spot: f64, strike: f64, volatility: f64, years: f64) -> f64 {
+    if years <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        return if spot > strike { 1.0 } else { 0.0 };
+    }
+    let vol_sqrt_t = volatility * years.sqrt();
+    let d = ((spot / strike).ln() - vol_sqrt_t * vol_sqrt_t / 2.0) / vol_sqrt_t;
+    normal_cdf(d)
+}
+
+/// Standard normal CDF (Abramowitz & Stegun 7.1.26, error below 1e-7).
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * z);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// Years from `now` until `close_time`, negative once it has passed.
+pub fn years_until(close_time: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    (close_time - now).num_milliseconds() as f64 / 1000.0 / SECONDS_PER_YEAR
+}
@@ -0,0 +1,161 @@
+//! Spread-capture estimator for resting quotes.
+//!
+//! On every tick a virtual buy order is parked at each distance in
+//! [`QUOTE_DISTANCES_CENTS`] below the mid, for both YES and NO, unless one
+//! is already resting there for that ticker. A virtual order counts as filled
+//! once the opposite ask comes down to its price, and as missed once it has
+//! rested [`FILL_HORIZON_SECS`] or the market closed. The resolved orders make
+//! up a fill-probability curve per side, which the maker strategy reads to
+//! pick how far from mid it can quote and still get filled.
+//!
+//! Fills are inferred from top of book only, so queue position is ignored:
+//! the curve is an upper bound on what a real order at that price would get.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::exchanges::kalshi::models::OrderSide;
+use crate::exchanges::kalshi::TickUpdate;
+use crate::metrics::{self, names};
+
+use super::constants::{FILL_CURVE_MIN_SAMPLES, FILL_HORIZON_SECS, QUOTE_DISTANCES_CENTS};
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    resolved: u64,
+    filled: u64,
+    fill_secs: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RestingQuote {
+    price: f64,
+    placed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillPoint {
+    pub distance_cents: u32,
+    /// Virtual orders at this distance that filled or expired.
+    pub samples: u64,
+    pub probability: f64,
+    /// Mean seconds from placement to fill, over filled orders only.
+    pub mean_fill_secs: Option<f64>,
+}
+
+#[derive(Default)]
+pub struct FillEstimator {
+    resting: HashMap<(String, OrderSide, u32), RestingQuote>,
+    buckets: HashMap<(OrderSide, u32), Bucket>,
+}
+
+impl FillEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves resting virtual orders against `tick` and parks new ones
+    /// where none is resting.
+    pub fn observe(&mut self, tick: &TickUpdate) {
+        if !(tick.yes_bid > 0.0 && tick.yes_ask > 0.0 && tick.yes_ask >= tick.yes_bid) {
+            return;
+        }
+        let yes_mid = (tick.yes_bid + tick.yes_ask) / 2.0;
+
+        for (side, mid, ask) in [
+            (OrderSide::Yes, yes_mid, tick.yes_ask),
+            (OrderSide::No, 1.0 - yes_mid, tick.no_ask),
+        ] {
+            for &distance in QUOTE_DISTANCES_CENTS.iter() {
+                let key = (tick.ticker.clone(), side, distance);
+                if let Some(quote) = self.resting.get(&key).copied() {
+                    let rested = (tick.timestamp - quote.placed_at).num_milliseconds().max(0) as f64 / 1000.0;
+                    if ask > 0.0 && ask <= quote.price {
+                        self.resolve(side, distance, Some(rested));
+                    } else if rested >= FILL_HORIZON_SECS as f64 {
+                        self.resolve(side, distance, None);
+                    } else {
+                        continue;
+                    }
+                    self.resting.remove(&key);
+                }
+
+                let price = mid - distance as f64 / 100.0;
+                if price > 0.0 {
+                    self.resting.insert(key, RestingQuote { price, placed_at: tick.timestamp });
+                }
+            }
+        }
+    }
+
+    /// Counts virtual orders on a closed market as missed and forgets them.
+    pub fn close_market(&mut self, ticker: &str) {
+        let closed: Vec<(String, OrderSide, u32)> = self
+            .resting
+            .keys()
+            .filter(|(t, _, _)| t == ticker)
+            .cloned()
+            .collect();
+        for key in closed {
+            self.resting.remove(&key);
+            self.resolve(key.1, key.2, None);
+        }
+    }
+
+    fn resolve(&mut self, side: OrderSide, distance: u32, fill_secs: Option<f64>) {
+        let bucket = self.buckets.entry((side, distance)).or_default();
+        bucket.resolved += 1;
+        if let Some(secs) = fill_secs {
+            bucket.filled += 1;
+            bucket.fill_secs += secs;
+            metrics::observe(names::QUOTE_TIME_TO_FILL_SECONDS, &[("side", side_label(side))], secs);
+        }
+
+        let distance_label = distance.to_string();
+        metrics::set(
+            names::QUOTE_FILL_PROBABILITY,
+            &[("side", side_label(side)), ("distance_cents", distance_label.as_str())],
+            bucket.filled as f64 / bucket.resolved as f64,
+        );
+    }
+
+    /// Fill probability by distance from mid, nearest first.
+    pub fn curve(&self, side: OrderSide) -> Vec<FillPoint> {
+        QUOTE_DISTANCES_CENTS
+            .iter()
+            .map(|&distance| {
+                let bucket = self.buckets.get(&(side, distance)).copied().unwrap_or_default();
+                FillPoint {
+                    distance_cents: distance,
+                    samples: bucket.resolved,
+                    probability: if bucket.resolved > 0 {
+                        bucket.filled as f64 / bucket.resolved as f64
+                    } else {
+                        0.0
+                    },
+                    mean_fill_secs: (bucket.filled > 0).then(|| bucket.fill_secs / bucket.filled as f64),
+                }
+            })
+            .collect()
+    }
+
+    /// The widest distance from mid, in cents, whose fill probability is at
+    /// least `min_probability`. `None` until every distance up to it has
+    /// [`FILL_CURVE_MIN_SAMPLES`] resolved orders.
+    pub fn quote_distance(&self, side: OrderSide, min_probability: f64) -> Option<u32> {
+        self.curve(side)
+            .into_iter()
+            .take_while(|p| p.samples >= FILL_CURVE_MIN_SAMPLES)
+            .filter(|p| p.probability >= min_probability)
+            .map(|p| p.distance_cents)
+            .last()
+    }
+}
+
+fn side_label(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Yes => "yes",
+        OrderSide::No => "no",
+    }
+}
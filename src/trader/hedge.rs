@@ -0,0 +1,98 @@
+//! Approximate delta hedging of Kalshi positions on Binance.
+//!
+//! Targets come from the portfolio view kept by
+//! [`super::exposure::ExposureTracker`]: each linked underlying is hedged to
+//! `-ratio` times the Kalshi delta, trading the difference once it exceeds
+//! the rebalance threshold.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{error, info};
+
+use super::venue::{BinanceVenue, ExecutionVenue, LogVenue, VenueOrder};
+use crate::config::HedgeConfig;
+use crate::error::Result;
+use crate::exchanges::TradeSide;
+use crate::metrics::{self, names};
+use crate::state::PortfolioState;
+
+pub struct Hedger {
+    config: HedgeConfig,
+    portfolio: Arc<PortfolioState>,
+    venue: Box<dyn ExecutionVenue>,
+}
+
+impl Hedger {
+    pub fn spawn(config: &HedgeConfig, portfolio: Arc<PortfolioState>) -> Result<()> {
+        let venue: Box<dyn ExecutionVenue> = match (config.execute, &config.api_key, &config.private_key_path) {
+            (true, Some(api_key), Some(key_path)) => {
+                Box::new(BinanceVenue::new(config.market, api_key, key_path)?)
+            }
+            _ => Box::new(LogVenue),
+        };
+        info!(
+            "🛡️ Hedging Kalshi positions via {} (ratio {}, rebalance at {})",
+            venue.name(),
+            config.ratio,
+            config.rebalance_threshold
+        );
+
+        let hedger = Self {
+            config: config.clone(),
+            portfolio,
+            venue,
+        };
+        tokio::spawn(hedger.run());
+        Ok(())
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+        loop {
+            ticker.tick().await;
+            self.rebalance().await;
+        }
+    }
+
+    async fn rebalance(&self) {
+        // Underlyings without a spot price aren't linked to Binance, or
+        // couldn't be priced this round; leave their hedge alone.
+        let targets: Vec<(String, f64)> = self
+            .portfolio
+            .exposures
+            .iter()
+            .filter(|e| e.spot.is_some() || e.contracts == 0)
+            .map(|e| (e.underlying.clone(), -e.kalshi_delta * self.config.ratio))
+            .collect();
+
+        for (symbol, target) in targets {
+            let held = self.portfolio.hedge(&symbol);
+            let diff = target - held;
+            if diff.abs() < self.config.rebalance_threshold {
+                continue;
+            }
+
+            let order = VenueOrder {
+                symbol: symbol.clone(),
+                side: if diff > 0.0 { TradeSide::Buy } else { TradeSide::Sell },
+                quantity: diff.abs(),
+            };
+            let side_label = if diff > 0.0 { "buy" } else { "sell" };
+            match self.venue.submit(&order).await {
+                Ok(()) => {
+                    metrics::inc(
+                        names::HEDGE_ORDERS_TOTAL,
+                        &[("symbol", &symbol), ("side", side_label), ("venue", self.venue.name())],
+                    );
+                    metrics::set(names::HEDGE_POSITION, &[("symbol", &symbol)], target);
+                    self.portfolio.hedges.insert(symbol, target);
+                }
+                Err(e) => {
+                    error!("Hedge order on {} failed: {}", symbol, e.chain());
+                    metrics::inc(names::ORDER_ERRORS_TOTAL, &[]);
+                }
+            }
+        }
+    }
+}
@@ -2,24 +2,34 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 
-use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tokio::sync::{mpsc, watch};
+use tracing::{error, info, info_span, warn, Instrument};
 
+use crate::config::{QuoteSkewConfig, RiskConfig};
 use crate::exchanges::kalshi::api::KalshiApi;
 use crate::exchanges::kalshi::models::{OrderSide, OrderType};
 use crate::exchanges::kalshi::TickUpdate;
+use crate::execution::risk;
+use crate::metrics::{self, names};
+use crate::status;
+use crate::utils::event_id::{EventId, EventKind};
 use crate::utils::trade::get_contract_size;
 
 use super::constants::{
     CANCEL_BEFORE_CLOSE_SECS, EXIT_ASK_THRESHOLD, FILL_OR_KILL_ORDER_PRICE, LADDER_PRICES,
-    LEVEL_1_CONTRACTS, ORDER_COOLDOWN_SECS, TRADING_CHANNEL_BUFFER,
+    LEVEL_1_CONTRACTS, MIN_QUOTE_FILL_PROBABILITY, ORDER_COOLDOWN_SECS, TRADING_CHANNEL_BUFFER,
 };
 use super::executor::OrderExecutor;
+use super::fill_model::FillEstimator;
 use super::positions::PositionManager;
 
 #[derive(Debug)]
 pub enum OrderDecision {
     Place {
+        /// The signal this order belongs to; shared by an entry and its ladder.
+        event_id: EventId,
+        /// Position of this order within the signal, used for the client order id.
+        leg: usize,
         ticker: String,
         side: OrderSide,
         price: f64,
@@ -35,39 +45,99 @@ pub struct Trader {
     latest_ticks: HashMap<String, TickUpdate>,
     laddered_tickers: HashSet<String>,
     cooldowns: HashMap<String, Instant>,
+    fills: FillEstimator,
+    skew: QuoteSkewConfig,
     should_exit: bool,
+    /// Set while the kill switch is engaged; ticks are ignored
+    halted: bool,
 }
 
 impl Trader {
-    pub fn new(api: Arc<KalshiApi>) -> Self {
-        let positions = PositionManager::new();
-        let executor = OrderExecutor::new(api, positions.clone());
+    pub fn new(
+        api: Arc<KalshiApi>,
+        positions: PositionManager,
+        risk: RiskConfig,
+        skew: QuoteSkewConfig,
+    ) -> Self {
+        let executor = OrderExecutor::new(api, positions.clone(), risk);
         Self {
             positions,
             executor,
             latest_ticks: HashMap::new(),
             laddered_tickers: HashSet::new(),
             cooldowns: HashMap::new(),
+            fills: FillEstimator::new(),
+            skew,
             should_exit: false,
+            halted: risk::kill_switch().reason().is_some(),
         }
     }
 
-    pub fn spawn(api: Arc<KalshiApi>) -> mpsc::Sender<TickUpdate> {
+    /// Starts the trader; a new skew sent on `skew` applies from the next tick.
+    /// Engaging the kill switch cancels its resting orders and stops it
+    /// until the switch is released.
+    pub fn spawn(
+        api: Arc<KalshiApi>,
+        positions: PositionManager,
+        risk: RiskConfig,
+        skew: watch::Receiver<QuoteSkewConfig>,
+    ) -> mpsc::Sender<TickUpdate> {
         let (tx, rx) = mpsc::channel::<TickUpdate>(TRADING_CHANNEL_BUFFER);
-        let trader = Self::new(api);
-        tokio::spawn(trader.run(rx));
+        status::board().register_queue("trading", &tx);
+        let trader = Self::new(api, positions, risk, *skew.borrow());
+        tokio::spawn(trader.run(rx, skew, risk::kill_switch().subscribe()));
         tx
     }
 
-    async fn run(mut self, mut rx: mpsc::Receiver<TickUpdate>) {
+    async fn run(
+        mut self,
+        mut rx: mpsc::Receiver<TickUpdate>,
+        mut skew: watch::Receiver<QuoteSkewConfig>,
+        mut kill: watch::Receiver<Option<String>>,
+    ) {
         info!("Trading engine started");
-        while let Some(tick) = rx.recv().await {
-            self.on_tick(&tick).await;
+        loop {
+            tokio::select! {
+                tick = rx.recv() => {
+                    let Some(tick) = tick else {
+                        break;
+                    };
+                    if skew.has_changed().unwrap_or(false) {
+                        self.skew = *skew.borrow_and_update();
+                        info!("🔁 Quote skew now {:?}", self.skew);
+                    }
+                    self.on_tick(&tick).await;
+                }
+                Ok(()) = kill.changed() => {
+                    let engaged = kill.borrow_and_update().is_some();
+                    self.on_kill_switch(engaged).await;
+                }
+            }
         }
         info!("Trading engine shutting down");
     }
 
+    /// Cancels the resting ladder and stops trading while the kill switch
+    /// is engaged; trading resumes once it is released.
+    pub async fn on_kill_switch(&mut self, engaged: bool) {
+        if engaged == self.halted {
+            return;
+        }
+        self.halted = engaged;
+        if !engaged {
+            info!("Kill switch released, trading resumes");
+            return;
+        }
+        warn!("🛑 Kill switch engaged, trader stopped");
+        if let Err(e) = self.executor.execute(OrderDecision::CancelAll).await {
+            error!("Cancelling the trader's orders failed: {}", e);
+        }
+    }
+
     async fn on_tick(&mut self, tick: &TickUpdate) {
+        if self.halted {
+            return;
+        }
         if self.should_exit {
             if !self.latest_ticks.contains_key(&tick.ticker) {
                 self.cleanup();
@@ -77,6 +147,9 @@ impl Trader {
             }
         }
 
+        self.prune_closed(tick);
+        self.fills.observe(tick);
+        self.record_inventory(tick);
         self.latest_ticks
             .insert(tick.ticker.clone(), tick.clone());
 
@@ -96,9 +169,24 @@ impl Trader {
                 OrderDecision::Place { ticker, .. } => Some(ticker.clone()),
             };
             
-            info!("Decision: {:?}", decision);
-            if let Err(e) = self.executor.execute(decision).await {
-                error!("Order execution failed: {}", e);
+            let span = match &decision {
+                OrderDecision::Place { event_id, .. } => info_span!("trade", event_id = %event_id),
+                OrderDecision::CancelAll => info_span!("cancel_all"),
+            };
+            let executor = &mut self.executor;
+            let failed = async move {
+                info!("Decision: {:?}", decision);
+                match executor.execute(decision).await {
+                    Ok(()) => false,
+                    Err(e) => {
+                        error!("Order execution failed: {}", e);
+                        true
+                    }
+                }
+            }
+            .instrument(span)
+            .await;
+            if failed {
                 if let Some(t) = ticker {
                     warn!("Cooling down ticker {} for {}s after failure", t, ORDER_COOLDOWN_SECS);
                     self.cooldowns.insert(t, Instant::now());
@@ -128,9 +216,10 @@ impl Trader {
         if let Some(pos) = self.positions.get(&tick.ticker) {
             if !self.laddered_tickers.contains(&tick.ticker) {
                 let side = pos.side;
+                let event_id = pos.event_id.clone();
                 drop(pos);
                 self.laddered_tickers.insert(tick.ticker.clone());
-                return self.build_ladder(&tick.ticker, side);
+                return self.build_ladder(&tick.ticker, side, event_id);
             }
             return vec![];
         }
@@ -140,7 +229,11 @@ impl Trader {
         }
 
         if let Some(side) = self.entry_side(tick) {
+            let event_id = EventId::new(EventKind::Trade);
+            info!("📈 Entry signal {} on {} ({:?})", event_id, tick.ticker, side);
             return vec![OrderDecision::Place {
+                event_id,
+                leg: 0,
                 ticker: tick.ticker.clone(),
                 side,
                 price: FILL_OR_KILL_ORDER_PRICE,
@@ -152,6 +245,31 @@ impl Trader {
         vec![]
     }
 
+    /// Forgets markets that closed before `tick` and cooldowns that ran out,
+    /// so per-ticker state doesn't accumulate across market rollovers.
+    fn prune_closed(&mut self, tick: &TickUpdate) {
+        let closed: Vec<String> = self
+            .latest_ticks
+            .values()
+            .filter(|t| t.close_time.is_some_and(|ct| ct <= tick.timestamp))
+            .map(|t| t.ticker.clone())
+            .collect();
+        for ticker in &closed {
+            self.fills.close_market(ticker);
+            self.latest_ticks.remove(ticker);
+            self.laddered_tickers.remove(ticker);
+            metrics::inc(names::STATE_EVICTIONS_TOTAL, &[("map", "trader_ticks")]);
+        }
+
+        let before = self.cooldowns.len();
+        self.cooldowns
+            .retain(|_, since| since.elapsed().as_secs() < ORDER_COOLDOWN_SECS);
+        let expired = before - self.cooldowns.len();
+        if expired > 0 {
+            metrics::add(names::STATE_EVICTIONS_TOTAL, &[("map", "trader_cooldowns")], expired as u64);
+        }
+    }
+
     fn cleanup(&mut self) {
         self.latest_ticks.clear();
         self.laddered_tickers.clear();
@@ -199,10 +317,54 @@ impl Trader {
         })
     }
 
-    fn build_ladder(&self, ticker: &str, side: OrderSide) -> Vec<OrderDecision> {
+    /// Widest distance below mid, in cents, that resting quotes on `side`
+    /// have historically been filled at often enough to be worth placing.
+    pub fn quote_distance(&self, side: OrderSide) -> Option<u32> {
+        self.fills.quote_distance(side, MIN_QUOTE_FILL_PROBABILITY)
+    }
+
+    fn record_inventory(&self, tick: &TickUpdate) {
+        let Some((side, contracts)) = self.positions.inventory(&tick.ticker) else {
+            return;
+        };
+        let side_label = format!("{:?}", side).to_lowercase();
+        metrics::set(
+            names::INVENTORY_CONTRACTS,
+            &[("market", &tick.asset), ("side", side_label.as_str())],
+            contracts as f64,
+        );
+    }
+
+    /// Ladder prices after leaning away from the inventory already held on
+    /// the side: every leg moves down by the configured skew per contract
+    /// and legs pushed to 0 are dropped. At `max_inventory` there is no
+    /// ladder at all.
+    fn skewed_ladder(&self, ticker: &str) -> Vec<f64> {
+        let held = self.positions.inventory(ticker).map_or(0, |(_, contracts)| contracts);
+        if self.skew.max_inventory.is_some_and(|max| held >= max) {
+            info!("Holding {} contracts on {}, at inventory limit, not laddering", held, ticker);
+            return Vec::new();
+        }
+
+        let skew_cents = (self.skew.cents_per_contract * held as f64).round();
         LADDER_PRICES
             .iter()
-            .map(|&price| OrderDecision::Place {
+            .map(|&price| ((price * 100.0).round() - skew_cents) / 100.0)
+            .filter(|&price| price >= 0.01)
+            .collect()
+    }
+
+    fn build_ladder(&self, ticker: &str, side: OrderSide, event_id: EventId) -> Vec<OrderDecision> {
+        match self.quote_distance(side) {
+            Some(distance) => info!("Quoting {:?} on {} up to {}c below mid", side, ticker, distance),
+            None => info!("Not enough fill history yet to size quoting distance on {}", ticker),
+        }
+        self.skewed_ladder(ticker)
+            .into_iter()
+            .enumerate()
+            .map(|(i, price)| OrderDecision::Place {
+                event_id: event_id.clone(),
+                leg: i + 1,
                 ticker: ticker.to_string(),
                 side,
                 price,
@@ -1,4 +1,9 @@
 pub mod constants;
 pub mod executor;
+pub mod exposure;
+pub mod fill_model;
+pub mod hedge;
 pub mod main;
-pub mod positions;
\ No newline at end of file
+pub mod positions;
+pub mod stress;
+pub mod venue;
\ No newline at end of file
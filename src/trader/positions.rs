@@ -4,6 +4,8 @@ use dashmap::{DashMap, mapref::one::Ref};
 use tracing::info;
 
 use crate::exchanges::kalshi::models::OrderSide;
+use crate::portfolio::Portfolio;
+use crate::utils::event_id::EventId;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FillStatus {
@@ -16,44 +18,79 @@ pub enum FillStatus {
 pub struct FillEntry {
     pub order_id: String,
     pub price: f64,
+    /// Filled on arrival
     pub contracts: u64,
+    /// Still resting, until cancelled
+    pub remaining: u64,
     pub status: FillStatus,
 }
 
 #[derive(Debug, Clone)]
 pub struct Position {
+    /// The entry signal that opened this position.
+    pub event_id: EventId,
     pub side: OrderSide,
     pub entries: Vec<FillEntry>,
 }
 
 #[derive(Debug, Clone)]
+pub struct Holding {
+    pub ticker: String,
+    pub side: OrderSide,
+    pub contracts: u64,
+    /// Dollars paid, at the average price
+    pub cost: f64,
+}
+
+/// Orders the trader placed, by ticker. What they filled is netted in the
+/// shared [`Portfolio`], which inventory and holdings are read from.
+#[derive(Clone)]
 pub struct PositionManager {
     positions: Arc<DashMap<String, Position>>,
+    portfolio: Arc<Portfolio>,
 }
 
 impl PositionManager {
-    pub fn new() -> Self {
+    pub fn new(portfolio: Arc<Portfolio>) -> Self {
         Self {
             positions: Arc::new(DashMap::new()),
+            portfolio,
         }
     }
 
+    pub fn portfolio(&self) -> Arc<Portfolio> {
+        self.portfolio.clone()
+    }
+
+    /// Records an order placed on `ticker`: `contracts` filled on arrival
+    /// and `remaining` left resting, which leaves it open.
+    #[allow(clippy::too_many_arguments)]
     pub fn add_fill(
         &self,
+        event_id: &EventId,
         ticker: &str,
         side: OrderSide,
         order_id: String,
         contracts: u64,
         price: f64,
-        status: FillStatus,
+        remaining: u64,
     ) {
+        if contracts > 0 {
+            let yes_price = match side {
+                OrderSide::Yes => price,
+                OrderSide::No => 1.0 - price,
+            };
+            self.portfolio.record(ticker, side, false, contracts as i64, yes_price);
+        }
         let fill = FillEntry {
             order_id,
             price,
             contracts,
-            status,
+            remaining,
+            status: if remaining > 0 { FillStatus::Open } else { FillStatus::Filled },
         };
-        let mut position = self.positions.entry(ticker.to_string()).or_insert(Position {
+        let mut position = self.positions.entry(ticker.to_string()).or_insert_with(|| Position {
+            event_id: event_id.clone(),
             side,
             entries: Vec::new(),
         });
@@ -66,6 +103,7 @@ impl PositionManager {
             for entry in pos.entries.iter_mut() {
                 if entry.order_id == order_id {
                     entry.status = FillStatus::Cancelled;
+                    entry.remaining = 0;
                     return;
                 }
             }
@@ -97,6 +135,56 @@ impl PositionManager {
         ids
     }
 
+    /// Contracts our open orders on `ticker` would still add, as YES
+    /// contracts: negative on the NO side.
+    pub fn resting(&self, ticker: &str) -> i64 {
+        let Some(pos) = self.positions.get(ticker) else {
+            return 0;
+        };
+        let remaining: u64 = pos
+            .entries
+            .iter()
+            .filter(|e| e.status == FillStatus::Open)
+            .map(|e| e.remaining)
+            .sum();
+        match pos.side {
+            OrderSide::Yes => remaining as i64,
+            OrderSide::No => -(remaining as i64),
+        }
+    }
+
+    /// Contracts held on `ticker`, and on which side.
+    pub fn inventory(&self, ticker: &str) -> Option<(OrderSide, u64)> {
+        match self.portfolio.position(ticker) {
+            0 => None,
+            held if held > 0 => Some((OrderSide::Yes, held as u64)),
+            held => Some((OrderSide::No, held.unsigned_abs())),
+        }
+    }
+
+    /// Every market with contracts held.
+    pub fn holdings(&self) -> Vec<Holding> {
+        self.portfolio
+            .positions()
+            .into_iter()
+            .filter(|p| p.position != 0)
+            .map(|p| {
+                let contracts = p.position.unsigned_abs();
+                let (side, price) = if p.position > 0 {
+                    (OrderSide::Yes, p.avg_price)
+                } else {
+                    (OrderSide::No, 1.0 - p.avg_price)
+                };
+                Holding {
+                    ticker: p.ticker,
+                    side,
+                    contracts,
+                    cost: price * contracts as f64,
+                }
+            })
+            .collect()
+    }
+
     pub fn cleanup(&self) {
         self.positions.clear();
     }
@@ -0,0 +1,182 @@
+//! Scenario stress tests of the Kalshi book.
+//!
+//! Each scenario moves every linked underlying by the same percentage and
+//! scales the volatility, reprices the held binaries with the model in
+//! [`super::exposure`], and shifts their marks by the model change. Results
+//! are checked against loss and delta limits.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+
+use super::exposure::{binary_delta, binary_price, years_until};
+use super::positions::Holding;
+use crate::exchanges::kalshi::models::OrderSide;
+
+/// A held market with what's needed to reprice it.
+#[derive(Debug, Clone)]
+pub struct StressLeg {
+    pub holding: Holding,
+    /// Linked Binance symbol, or the Kalshi series when there is none.
+    pub underlying: String,
+    pub strike: Option<f64>,
+    pub close_time: Option<DateTime<Utc>>,
+    /// Current book mid of the held side, in dollars per contract
+    pub mark: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shock {
+    /// Relative move of every underlying, e.g. -0.05 for -5%
+    pub spot: f64,
+    /// Relative change of volatility, e.g. 0.2 for +20%
+    pub volatility: f64,
+}
+
+impl Shock {
+    /// Every combination of spot `-x, 0, +x` and volatility `-y, 0, +y`,
+    /// without the unshocked base case.
+    pub fn grid(spot: f64, volatility: f64) -> Vec<Shock> {
+        let mut shocks = Vec::new();
+        for s in [-spot, 0.0, spot] {
+            for v in [-volatility, 0.0, volatility] {
+                let shock = Shock { spot: s, volatility: v };
+                if (s != 0.0 || v != 0.0) && !shocks.contains(&shock) {
+                    shocks.push(shock);
+                }
+            }
+        }
+        shocks
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StressLimits {
+    /// Largest acceptable portfolio loss in dollars
+    pub max_loss: Option<f64>,
+    /// Largest acceptable absolute delta per underlying, in base units
+    pub max_delta: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnderlyingResult {
+    pub underlying: String,
+    pub pnl: f64,
+    pub delta: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScenarioResult {
+    pub shock: Shock,
+    pub pnl: f64,
+    pub underlyings: Vec<UnderlyingResult>,
+    pub breaches: Vec<String>,
+}
+
+pub struct StressTest {
+    legs: Vec<StressLeg>,
+    spots: HashMap<String, f64>,
+    volatility: f64,
+    now: DateTime<Utc>,
+}
+
+impl StressTest {
+    pub fn new(legs: Vec<StressLeg>, spots: HashMap<String, f64>, volatility: f64) -> Self {
+        Self {
+            legs,
+            spots,
+            volatility,
+            now: Utc::now(),
+        }
+    }
+
+    pub fn legs(&self) -> &[StressLeg] {
+        &self.legs
+    }
+
+    /// Legs the model can't reprice: no strike, close time or spot. They
+    /// keep their mark in every scenario.
+    pub fn unpriced(&self) -> Vec<&StressLeg> {
+        self.legs.iter().filter(|leg| self.terms(leg).is_none()).collect()
+    }
+
+    /// Current value of the book at its marks, falling back to the model
+    /// where a market has no quotes.
+    pub fn mark_value(&self) -> f64 {
+        self.legs
+            .iter()
+            .map(|leg| {
+                let per_contract = leg.mark.or_else(|| {
+                    self.terms(leg)
+                        .map(|(spot, strike, years)| side_price(leg.holding.side, spot, strike, self.volatility, years))
+                });
+                per_contract.unwrap_or(0.0) * leg.holding.contracts as f64
+            })
+            .sum()
+    }
+
+    pub fn run(&self, shock: Shock, limits: &StressLimits) -> ScenarioResult {
+        let volatility = (self.volatility * (1.0 + shock.volatility)).max(0.0);
+        let mut by_underlying: BTreeMap<&str, UnderlyingResult> = BTreeMap::new();
+
+        for leg in &self.legs {
+            let result = by_underlying.entry(&leg.underlying).or_insert_with(|| UnderlyingResult {
+                underlying: leg.underlying.clone(),
+                pnl: 0.0,
+                delta: 0.0,
+            });
+            let Some((spot, strike, years)) = self.terms(leg) else {
+                continue;
+            };
+            let shocked_spot = spot * (1.0 + shock.spot);
+            let contracts = leg.holding.contracts as f64;
+
+            let before = side_price(leg.holding.side, spot, strike, self.volatility, years);
+            let after = side_price(leg.holding.side, shocked_spot, strike, volatility, years);
+            result.pnl += (after - before) * contracts;
+
+            let delta = binary_delta(shocked_spot, strike, volatility, years) * contracts;
+            result.delta += match leg.holding.side {
+                OrderSide::Yes => delta,
+                OrderSide::No => -delta,
+            };
+        }
+
+        let underlyings: Vec<UnderlyingResult> = by_underlying.into_values().collect();
+        let pnl: f64 = underlyings.iter().map(|u| u.pnl).sum();
+
+        let mut breaches = Vec::new();
+        if let Some(max_loss) = limits.max_loss {
+            if -pnl > max_loss {
+                breaches.push(format!("loss ${:.2} over limit ${:.2}", -pnl, max_loss));
+            }
+        }
+        if let Some(max_delta) = limits.max_delta {
+            for u in &underlyings {
+                if u.delta.abs() > max_delta {
+                    breaches.push(format!("{} delta {:.4} over limit {}", u.underlying, u.delta, max_delta));
+                }
+            }
+        }
+
+        ScenarioResult {
+            shock,
+            pnl,
+            underlyings,
+            breaches,
+        }
+    }
+
+    fn terms(&self, leg: &StressLeg) -> Option<(f64, f64, f64)> {
+        let spot = *self.spots.get(&leg.underlying)?;
+        Some((spot, leg.strike?, years_until(leg.close_time?, self.now)))
+    }
+}
+
+fn side_price(side: OrderSide, spot: f64, strike: f64, volatility: f64, years: f64) -> f64 {
+    let yes = binary_price(spot, strike, volatility, years);
+    match side {
+        OrderSide::Yes => yes,
+        OrderSide::No => 1.0 - yes,
+    }
+}
@@ -0,0 +1,158 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use reqwest::Client as HttpClient;
+use tracing::info;
+
+use crate::error::{Error, ErrorContext, Result, ResultExt};
+use crate::exchanges::binance::auth::BinanceSigner;
+use crate::exchanges::TradeSide;
+use crate::instruments;
+use crate::utils::http::ensure_success;
+
+#[derive(Debug, Clone)]
+pub struct VenueOrder {
+    pub symbol: String,
+    pub side: TradeSide,
+    /// Base asset quantity, always positive.
+    pub quantity: f64,
+}
+
+/// Somewhere hedge orders can be sent. Implementations only need market
+/// orders; sizing and timing are decided by the caller.
+#[async_trait]
+pub trait ExecutionVenue: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn submit(&self, order: &VenueOrder) -> Result<()>;
+}
+
+/// Logs orders instead of sending them, for running the hedger dry.
+pub struct LogVenue;
+
+#[async_trait]
+impl ExecutionVenue for LogVenue {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    async fn submit(&self, order: &VenueOrder) -> Result<()> {
+        info!(
+            "🧾 [dry run] {:?} {} {}",
+            order.side,
+            instruments::registry().binance(&order.symbol).quantity(order.quantity),
+            order.symbol
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinanceMarket {
+    Spot,
+    /// USDⓈ-M perpetuals, which can be shorted without holding the asset.
+    Futures,
+}
+
+impl FromStr for BinanceMarket {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "spot" => Ok(BinanceMarket::Spot),
+            "futures" | "usdm" => Ok(BinanceMarket::Futures),
+            other => Err(Error::Config(format!(
+                "Unknown Binance market '{}', expected spot or futures",
+                other
+            ))),
+        }
+    }
+}
+
+impl BinanceMarket {
+    fn base_url(&self) -> &'static str {
+        match self {
+            BinanceMarket::Spot => "https://api.binance.com",
+            BinanceMarket::Futures => "https://fapi.binance.com",
+        }
+    }
+
+    fn order_path(&self) -> &'static str {
+        match self {
+            BinanceMarket::Spot => "/api/v3/order",
+            BinanceMarket::Futures => "/fapi/v1/order",
+        }
+    }
+}
+
+/// Market orders on Binance, signed with an RSA API key.
+pub struct BinanceVenue {
+    http: HttpClient,
+    market: BinanceMarket,
+    api_key: String,
+    signer: BinanceSigner,
+}
+
+impl BinanceVenue {
+    pub fn new(market: BinanceMarket, api_key: &str, private_key_path: &str) -> Result<Self> {
+        Ok(Self {
+            http: HttpClient::new(),
+            market,
+            api_key: api_key.to_string(),
+            signer: BinanceSigner::from_file(private_key_path)?,
+        })
+    }
+}
+
+#[async_trait]
+impl ExecutionVenue for BinanceVenue {
+    fn name(&self) -> &'static str {
+        match self.market {
+            BinanceMarket::Spot => "binance_spot",
+            BinanceMarket::Futures => "binance_futures",
+        }
+    }
+
+    async fn submit(&self, order: &VenueOrder) -> Result<()> {
+        let side = match order.side {
+            TradeSide::Buy => "BUY",
+            TradeSide::Sell => "SELL",
+            TradeSide::Unknown => {
+                return Err(Error::Other(format!("Hedge order on {} has no side", order.symbol)))
+            }
+        };
+        let quantity = instruments::registry().binance(&order.symbol).quantity(order.quantity);
+        let payload = format!(
+            "symbol={}&side={}&type=MARKET&quantity={}&timestamp={}",
+            order.symbol,
+            side,
+            quantity,
+            chrono::Utc::now().timestamp_millis()
+        );
+        let url = format!(
+            "{}{}?{}&signature={}",
+            self.market.base_url(),
+            self.market.order_path(),
+            payload,
+            self.signer.sign(&payload)
+        );
+        let context = || {
+            ErrorContext::new("submit hedge order")
+                .with_exchange("binance")
+                .with_endpoint(format!("POST {}", self.market.order_path()))
+                .with_symbol(order.symbol.clone())
+        };
+
+        let resp = self
+            .http
+            .post(&url)
+            .header("X-MBX-APIKEY", &self.api_key)
+            .send()
+            .await
+            .with_context(context)?;
+        ensure_success(resp).await.with_context(context)?;
+
+        info!("🛡️ Hedged on {}: {} {} {}", self.name(), side, quantity, order.symbol);
+        Ok(())
+    }
+}
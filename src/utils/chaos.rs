@@ -0,0 +1,78 @@
+//! Fault injection for exercising reconnect and gap handling against live feeds.
+//!
+//! Only active when the crate is built with the `chaos` feature and a
+//! `CHAOS_*` variable is set; see [`crate::config::ChaosConfig`].
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tracing::warn;
+
+use crate::config::ChaosConfig;
+use crate::exchanges::binance::sbe::types::MessageHeader;
+use crate::metrics::{self, names};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    None,
+    /// Tear the connection down as if the server had gone away.
+    Disconnect,
+}
+
+/// Per-connection fault injector. Create a fresh one on every connect so
+/// `drop_after_messages` counts from the start of each connection.
+pub struct FaultInjector {
+    config: ChaosConfig,
+    exchange: &'static str,
+    messages: u64,
+    rng: StdRng,
+}
+
+impl FaultInjector {
+    pub fn new(config: ChaosConfig, exchange: &'static str) -> Self {
+        warn!("🐒 Chaos fault injection enabled for {}: {:?}", exchange, config);
+        Self {
+            config,
+            exchange,
+            messages: 0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Called for every inbound data frame before it is decoded. Applies the
+    /// configured delay and reports whether the connection should be dropped.
+    pub async fn on_frame(&mut self) -> Fault {
+        self.messages += 1;
+
+        if let Some(limit) = self.config.drop_after_messages {
+            if self.messages > limit {
+                warn!("🐒 Dropping {} connection after {} messages", self.exchange, limit);
+                self.count("disconnect");
+                return Fault::Disconnect;
+            }
+        }
+
+        if self.config.delay_ms > 0 && self.rng.gen_bool(self.config.delay_probability) {
+            self.count("delay");
+            tokio::time::sleep(Duration::from_millis(self.config.delay_ms)).await;
+        }
+
+        Fault::None
+    }
+
+    /// Overwrites the template id of an SBE header with an unknown value.
+    pub fn corrupt_sbe(&mut self, data: &mut [u8]) {
+        if data.len() < MessageHeader::SIZE
+            || !self.rng.gen_bool(self.config.corrupt_sbe_probability)
+        {
+            return;
+        }
+        data[2..4].copy_from_slice(&u16::MAX.to_le_bytes());
+        self.count("corrupt_sbe");
+    }
+
+    fn count(&self, fault: &str) {
+        metrics::inc(names::CHAOS_FAULTS_TOTAL, &[("exchange", self.exchange), ("fault", fault)]);
+    }
+}
@@ -0,0 +1,62 @@
+//! Correlation ids for alerts and trades.
+//!
+//! An id is minted once, where a signal is first raised, and carried on
+//! everything derived from it (log lines, order client ids, DB rows) so one
+//! signal can be followed end to end with a single grep or query.
+
+use std::fmt;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Alert,
+    Monitor,
+    Trade,
+    /// An exchange notice, alerted on but never traded
+    Notice,
+}
+
+impl EventKind {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            EventKind::Alert => "alr",
+            EventKind::Monitor => "mon",
+            EventKind::Trade => "trd",
+            EventKind::Notice => "ntc",
+        }
+    }
+}
+
+/// `<kind>-<unix micros, hex>-<random, hex>`, e.g. `trd-6123f0a8b1c2d-9f3e21aa`.
+/// Ids sort by creation time within a kind.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EventId(String);
+
+impl EventId {
+    pub fn new(kind: EventKind) -> Self {
+        Self(format!(
+            "{}-{:x}-{:08x}",
+            kind.prefix(),
+            Utc::now().timestamp_micros(),
+            rand::random::<u32>()
+        ))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Id for the `n`th item derived from this event, e.g. one leg of a ladder.
+    pub fn child(&self, n: usize) -> String {
+        format!("{}-{}", self.0, n)
+    }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,16 @@
+use reqwest::Response;
+
+use crate::error::{Error, Result};
+
+/// Turns a non-2xx response into [`Error::HttpStatus`] carrying the body.
+pub async fn ensure_success(resp: Response) -> Result<Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let body = resp.text().await.unwrap_or_default();
+    Err(Error::HttpStatus {
+        status: status.as_u16(),
+        body,
+    })
+}
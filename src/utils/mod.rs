@@ -1,3 +1,8 @@
+pub mod chaos;
+pub mod event_id;
+pub mod http;
+pub mod numeric;
+pub mod shard;
 pub mod trade;
 pub mod websocket;
 
@@ -0,0 +1,65 @@
+//! Strict parsing of the decimal strings exchanges send prices and sizes as.
+//!
+//! `str::parse::<f64>` also takes `inf`, `NaN`, exponents and surrounding
+//! whitespace, none of which is a valid price. Only plain `-?digits[.digits]`
+//! is accepted here, read as an integer mantissa and a decimal scale so no
+//! locale or float formatting quirk can change the value. Callers that used
+//! to drop or zero a bad value report it through [`checked`] instead.
+
+use tracing::warn;
+
+use crate::error::{Error, Result};
+use crate::metrics::{self, names};
+
+/// Digits after the point beyond which a value is rejected rather than
+/// rounded; no venue quotes finer than 1e-18.
+const MAX_SCALE: u32 = 18;
+
+/// Parses a plain decimal string, `None` if it is anything else.
+pub fn parse_decimal(value: &str) -> Option<f64> {
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    if frac_part.len() as u32 > MAX_SCALE {
+        return None;
+    }
+
+    let mut mantissa: u128 = 0;
+    for b in int_part.bytes().chain(frac_part.bytes()) {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        mantissa = mantissa.checked_mul(10)?.checked_add((b - b'0') as u128)?;
+    }
+    let value = mantissa as f64 / 10f64.powi(frac_part.len() as i32);
+    Some(if negative { -value } else { value })
+}
+
+/// [`parse_decimal`] as a `Result`, naming the field on failure.
+pub fn parse_field(field: &str, value: &str) -> Result<f64> {
+    parse_decimal(value).ok_or_else(|| Error::Other(format!("Invalid {} '{}'", field, value)))
+}
+
+/// [`parse_decimal`] for payload fields that are skipped when invalid: the
+/// bad value is logged and counted as a data-quality error.
+pub fn checked(exchange: &'static str, field: &'static str, value: &str) -> Option<f64> {
+    let parsed = parse_decimal(value);
+    if parsed.is_none() {
+        report(exchange, field, value);
+    }
+    parsed
+}
+
+/// Counts a malformed `field` from `exchange`.
+pub fn report(exchange: &'static str, field: &'static str, value: &str) {
+    metrics::inc(
+        names::DATA_QUALITY_ERRORS_TOTAL,
+        &[("exchange", exchange), ("field", field)],
+    );
+    warn!("🧮 Invalid {} {} '{}'", exchange, field, value);
+}
@@ -0,0 +1,17 @@
+//! Deterministic assignment of instruments to instances.
+
+/// FNV-1a, chosen over `DefaultHasher` because its output is stable across
+/// Rust releases and platforms, so every instance agrees on the split.
+fn fnv1a(key: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// The shard in `0..count` that owns `key`. Case-insensitive.
+pub fn shard_of(key: &str, count: u32) -> u32 {
+    (fnv1a(&key.to_ascii_uppercase()) % count.max(1) as u64) as u32
+}
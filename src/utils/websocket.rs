@@ -7,11 +7,13 @@ use tokio::net::TcpStream;
 use tokio::time::timeout;
 use tokio_tungstenite::{
     connect_async,
+    tungstenite::protocol::frame::coding::CloseCode,
+    tungstenite::protocol::CloseFrame,
     tungstenite::Message,
     MaybeTlsStream, WebSocketStream,
 };
 
-use crate::error::{Error, Result};
+use crate::error::{Error, ErrorContext, Result, ResultExt};
 
 /// WebSocket connection wrapper with common functionality
 pub struct WsConnection {
@@ -45,7 +47,7 @@ impl WsConnection {
         )
         .await
         .map_err(|_| Error::Connection("Connection timeout".into()))?
-        .map_err(|e| Error::WebSocket(e.to_string()))?;
+        .with_context(|| ErrorContext::new("connect").with_endpoint(self.url.clone()))?;
 
         self.stream = Some(stream);
         Ok(())
@@ -56,8 +58,7 @@ impl WsConnection {
         if let Some(stream) = &mut self.stream {
             stream
                 .send(Message::Text(msg.to_string()))
-                .await
-                .map_err(|e| Error::WebSocket(e.to_string()))?;
+                .await?;
             Ok(())
         } else {
             Err(Error::WebSocket("Not connected".into()))
@@ -69,8 +70,7 @@ impl WsConnection {
         if let Some(stream) = &mut self.stream {
             stream
                 .send(Message::Binary(data))
-                .await
-                .map_err(|e| Error::WebSocket(e.to_string()))?;
+                .await?;
             Ok(())
         } else {
             Err(Error::WebSocket("Not connected".into()))
@@ -82,7 +82,7 @@ impl WsConnection {
         if let Some(stream) = &mut self.stream {
             match timeout(self.read_timeout, stream.next()).await {
                 Ok(Some(Ok(msg))) => Ok(Some(msg)),
-                Ok(Some(Err(e))) => Err(Error::WebSocket(e.to_string())),
+                Ok(Some(Err(e))) => Err(e.into()),
                 Ok(None) => Ok(None),
                 Err(_) => Err(Error::WebSocket("Read timeout".into())),
             }
@@ -96,8 +96,7 @@ impl WsConnection {
         if let Some(stream) = &mut self.stream {
             stream
                 .close(None)
-                .await
-                .map_err(|e| Error::WebSocket(e.to_string()))?;
+                .await?;
         }
         self.stream = None;
         Ok(())
@@ -136,3 +135,106 @@ impl ReconnectStrategy {
         Duration::from_secs_f64(delay_secs.min(self.max_delay.as_secs_f64()))
     }
 }
+
+/// Why the server closed a WebSocket, derived from the close frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloseReason {
+    /// 1000, or the stream ended without a close frame.
+    Normal,
+    /// 1001 / 1012: server shutting down or restarting (e.g. Binance's 24h cutoff).
+    GoingAway,
+    /// 1008 with a rate limit reason, or 1013 "try again later".
+    RateLimited(String),
+    /// 1008 for anything else: bad auth, invalid subscription, banned key.
+    PolicyViolation(String),
+    /// 1011: internal server error.
+    ServerError(String),
+    Other { code: u16, reason: String },
+}
+
+impl CloseReason {
+    pub fn from_frame(frame: Option<&CloseFrame<'_>>) -> Self {
+        let Some(frame) = frame else {
+            return CloseReason::Normal;
+        };
+        let reason = frame.reason.to_string();
+        match frame.code {
+            CloseCode::Normal => CloseReason::Normal,
+            CloseCode::Away | CloseCode::Restart => CloseReason::GoingAway,
+            CloseCode::Again => CloseReason::RateLimited(reason),
+            CloseCode::Policy if is_rate_limit_reason(&reason) => CloseReason::RateLimited(reason),
+            CloseCode::Policy => CloseReason::PolicyViolation(reason),
+            CloseCode::Error => CloseReason::ServerError(reason),
+            code => CloseReason::Other { code: code.into(), reason },
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloseReason::Normal => "normal",
+            CloseReason::GoingAway => "going_away",
+            CloseReason::RateLimited(_) => "rate_limited",
+            CloseReason::PolicyViolation(_) => "policy_violation",
+            CloseReason::ServerError(_) => "server_error",
+            CloseReason::Other { .. } => "other",
+        }
+    }
+
+    pub fn reconnect_policy(&self) -> ReconnectPolicy {
+        match self {
+            CloseReason::GoingAway => ReconnectPolicy::Immediate,
+            CloseReason::RateLimited(_) => {
+                ReconnectPolicy::Delayed(Duration::from_secs(RATE_LIMIT_COOLDOWN_SECS))
+            }
+            CloseReason::PolicyViolation(_) => ReconnectPolicy::Abort,
+            CloseReason::Normal | CloseReason::ServerError(_) | CloseReason::Other { .. } => {
+                ReconnectPolicy::Backoff
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseReason::Normal => write!(f, "normal closure"),
+            CloseReason::GoingAway => write!(f, "server going away"),
+            CloseReason::RateLimited(r) => write!(f, "rate limited ({})", r),
+            CloseReason::PolicyViolation(r) => write!(f, "policy violation ({})", r),
+            CloseReason::ServerError(r) => write!(f, "server error ({})", r),
+            CloseReason::Other { code, reason } => write!(f, "code {} ({})", code, reason),
+        }
+    }
+}
+
+/// How long to stay away after the server closes us for rate limiting.
+pub const RATE_LIMIT_COOLDOWN_SECS: u64 = 60;
+
+fn is_rate_limit_reason(reason: &str) -> bool {
+    let reason = reason.to_ascii_lowercase();
+    reason.contains("rate") || reason.contains("too many") || reason.contains("limit")
+}
+
+/// What a client should do after its connection is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconnectPolicy {
+    /// Reconnect right away and reset the backoff.
+    Immediate,
+    /// Reconnect using the client's exponential backoff.
+    Backoff,
+    /// Wait a fixed cooldown before reconnecting.
+    Delayed(Duration),
+    /// Reconnecting will not help; stop and alert.
+    Abort,
+}
+
+impl ReconnectPolicy {
+    /// The policy for an error returned by a connection loop. Only server
+    /// close frames get special treatment; everything else backs off.
+    pub fn for_error(err: &Error) -> Self {
+        match err.root() {
+            Error::Closed { reason, .. } => reason.reconnect_policy(),
+            _ => ReconnectPolicy::Backoff,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Imbalance alerts inside the blackout before the mapped Kalshi market
+//! closes: suppressed ones are only counted, downgraded ones are listed on
+//! the status board but never broadcast, and neither is handed on. Books
+//! heavy on either side alert, each in its own direction.
+//!
+//! `cargo test --test close_blackout`
+//!
+//! Session stats and the status board are process-wide, so the counting
+//! checks run in one test.
+
+use chrono::{Duration, Utc};
+
+use white_shark::config::ImbalanceConfig;
+use white_shark::exchanges::binance::sbe::events::depth::{
+    report_imbalance, BlackoutAction, DepthImbalance, SUPPRESSED_NEAR_CLOSE,
+};
+use white_shark::state;
+use white_shark::status::{self, AlertDirection};
+
+/// Far past the default alert ratio at every depth
+fn lopsided() -> DepthImbalance {
+    DepthImbalance {
+        top_5: 500.0,
+        top_10: 500.0,
+        all: 500.0,
+        bid_sums: (500.0, 500.0, 500.0),
+        ask_sums: (1.0, 1.0, 1.0),
+    }
+}
+
+fn blackout(secs: u64, action: BlackoutAction) -> ImbalanceConfig {
+    ImbalanceConfig {
+        close_blackout_secs: secs,
+        close_blackout_action: action,
+        ..ImbalanceConfig::default()
+    }
+}
+
+fn suppressed() -> u64 {
+    state::session()
+        .snapshot()
+        .suppressions
+        .get(SUPPRESSED_NEAR_CLOSE)
+        .copied()
+        .unwrap_or(0)
+}
+
+fn listed(symbol: &str) -> Vec<String> {
+    status::board()
+        .recent_alerts()
+        .into_iter()
+        .filter(|a| a.symbol == symbol)
+        .map(|a| a.message)
+        .collect()
+}
+
+#[test]
+fn blackout_window_bounds() {
+    let config = blackout(300, BlackoutAction::Suppress);
+    assert!(config.in_close_blackout(Duration::seconds(299)));
+    assert!(config.in_close_blackout(Duration::seconds(0)));
+    assert!(config.in_close_blackout(Duration::seconds(-30)), "past close still counts");
+    assert!(!config.in_close_blackout(Duration::seconds(300)));
+    assert!(!config.in_close_blackout(Duration::hours(2)));
+
+    let off = blackout(0, BlackoutAction::Suppress);
+    assert!(!off.in_close_blackout(Duration::seconds(-30)));
+}
+
+#[test]
+fn blackout_action_parses() {
+    assert_eq!("suppress".parse::<BlackoutAction>().unwrap(), BlackoutAction::Suppress);
+    assert_eq!(" Downgrade ".parse::<BlackoutAction>().unwrap(), BlackoutAction::Downgrade);
+    assert!("mute".parse::<BlackoutAction>().is_err());
+}
+
+#[test]
+fn alerts_inside_the_blackout_are_held_back() {
+    // Books heavy on asks alert down
+    let imbalance = DepthImbalance {
+        top_5: 0.002,
+        top_10: 0.5,
+        all: 1.0,
+        bid_sums: (1.0, 50.0, 100.0),
+        ask_sums: (500.0, 100.0, 100.0),
+    };
+    let raised = report_imbalance("ASKUSDT", Utc::now(), &imbalance, &ImbalanceConfig::default(), &[], None);
+    assert_eq!(raised.len(), 1);
+    assert_eq!(raised[0].depth, "N_5");
+
+    let ask_alerts: Vec<(Option<AlertDirection>, String)> = status::board()
+        .recent_alerts()
+        .into_iter()
+        .filter(|a| a.symbol == "ASKUSDT")
+        .map(|a| (a.direction, a.message))
+        .collect();
+    assert_eq!(
+        ask_alerts,
+        vec![(Some(AlertDirection::Down), "N_5 depth imbalance 1/500.0".to_string())]
+    );
+
+    let now = Utc::now();
+    let mut alerts = status::board().subscribe_alerts();
+
+    // Outside the window, or with no Kalshi market known, alerts go out
+    let config = blackout(300, BlackoutAction::Suppress);
+    let raised = report_imbalance("OPENUSDT", now, &lopsided(), &config, &[], Some(Duration::hours(1)));
+    assert_eq!(raised.len(), 3);
+    let raised = report_imbalance("OPENUSDT", now, &lopsided(), &config, &[], None);
+    assert_eq!(raised.len(), 3);
+    let broadcast = std::iter::from_fn(|| alerts.try_recv().ok())
+        .filter(|a| a.symbol == "OPENUSDT")
+        .count();
+    assert_eq!(broadcast, 6);
+
+    // Suppressed: nothing raised, listed or broadcast, one count per depth
+    let before = suppressed();
+    let sessions_alerts = state::session().snapshot().alerts;
+    let raised = report_imbalance("MUTEUSDT", now, &lopsided(), &config, &[], Some(Duration::seconds(60)));
+    assert!(raised.is_empty());
+    assert!(listed("MUTEUSDT").is_empty());
+    assert_eq!(suppressed() - before, 3);
+
+    // Downgraded: listed as near close, but not raised or broadcast, and
+    // counted as suppressed rather than as alerts
+    let config = blackout(300, BlackoutAction::Downgrade);
+    let before = suppressed();
+    let raised = report_imbalance("LATEUSDT", now, &lopsided(), &config, &[], Some(Duration::seconds(60)));
+    assert!(raised.is_empty());
+    let messages = listed("LATEUSDT");
+    assert_eq!(messages.len(), 3);
+    assert!(messages.iter().all(|m| m.contains("downgraded, Kalshi market closes in 60s")));
+    assert_eq!(suppressed() - before, 3);
+
+    assert!(std::iter::from_fn(|| alerts.try_recv().ok()).all(|a| a.symbol == "OPENUSDT"));
+    assert_eq!(state::session().snapshot().alerts, sessions_alerts);
+}
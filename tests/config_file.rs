@@ -0,0 +1,54 @@
+//! Config file values layered around the environment: the environment wins
+//! over the file, overrides win over both, applying the file again picks up
+//! removed keys, a reload that fails keeps the values it replaced, and the
+//! process environment itself is never written.
+//!
+//! `cargo test --test config_file`
+//!
+//! The layers are process-wide, so the checks run in one test.
+
+use std::env::VarError;
+
+use white_shark::config::{self, Config};
+
+#[test]
+fn file_values_fill_in_what_the_environment_lacks() {
+    let path = std::env::temp_dir().join(format!("white-shark-config-{}.toml", std::process::id()));
+    let path_str = path.to_str().unwrap();
+
+    std::fs::write(
+        &path,
+        r#"
+path = "from-file"
+
+[config_test]
+only_in_file = "a"
+list = ["x", "y"]
+"#,
+    )
+    .unwrap();
+    Config::apply_file(path_str).unwrap();
+
+    assert_eq!(config::var("CONFIG_TEST_ONLY_IN_FILE").unwrap(), "a");
+    assert_eq!(config::var("CONFIG_TEST_LIST").unwrap(), "x,y");
+    assert_eq!(config::var("PATH").unwrap(), std::env::var("PATH").unwrap(), "the environment wins");
+    assert!(std::env::var("CONFIG_TEST_ONLY_IN_FILE").is_err(), "the environment is left alone");
+
+    Config::override_var("CONFIG_TEST_LIST", "z");
+    assert_eq!(config::var("CONFIG_TEST_LIST").unwrap(), "z");
+
+    std::fs::write(&path, "[config_test]\nlist = [\"x\"]\n").unwrap();
+    Config::apply_file(path_str).unwrap();
+    assert_eq!(config::var("CONFIG_TEST_ONLY_IN_FILE"), Err(VarError::NotPresent));
+    assert_eq!(config::var("CONFIG_TEST_LIST").unwrap(), "z", "overrides outlive a new read");
+
+    // Neither a file that doesn't parse nor one without a Kalshi private
+    // key replaces what is in place
+    std::fs::write(&path, "[config_test\n").unwrap();
+    assert!(Config::reload(Some(path_str)).is_err());
+    std::fs::write(&path, "[config_test]\nonly_in_file = \"b\"\n[kalshi]\napi_key_id = \"key\"\n").unwrap();
+    assert!(Config::reload(Some(path_str)).is_err());
+    assert_eq!(config::var("CONFIG_TEST_ONLY_IN_FILE"), Err(VarError::NotPresent));
+
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,42 @@
+//! Sealed reports that grow by appends: every append adds a chunk without
+//! touching what the file already holds, and a chunk torn by a crash is cut
+//! off by the next append instead of hiding the ones after it.
+//!
+//! `cargo test --test encrypted_reports`
+//!
+//! The key is process-wide, so the checks run in one test.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use white_shark::encryption::{self, EncryptionKey, CHUNKED_FILE_MAGIC};
+
+#[test]
+fn appends_seal_chunks_and_survive_a_torn_tail() {
+    encryption::init(Some(EncryptionKey::from_bytes(&[7u8; 32]).unwrap()));
+    let path = std::env::temp_dir().join(format!("white-shark-report-{}.csv", std::process::id()));
+    std::fs::remove_file(&path).ok();
+
+    encryption::append_report(&path, "a,b\n", "1,2\n").unwrap();
+    let first = std::fs::read(&path).unwrap();
+    assert!(first.starts_with(CHUNKED_FILE_MAGIC));
+
+    encryption::append_report(&path, "a,b\n", "3,4\n").unwrap();
+    let second = std::fs::read(&path).unwrap();
+    assert!(second.starts_with(&first), "earlier chunks are left as they were");
+    assert_eq!(encryption::read_file(&path).unwrap(), b"a,b\n1,2\n3,4\n");
+
+    // A crash in the middle of writing the next chunk
+    OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(&[40, 0, 0, 0, 1, 2, 3])
+        .unwrap();
+    assert_eq!(encryption::read_file(&path).unwrap(), b"a,b\n1,2\n3,4\n");
+
+    encryption::append_report(&path, "a,b\n", "5,6\n").unwrap();
+    assert_eq!(encryption::read_file(&path).unwrap(), b"a,b\n1,2\n3,4\n5,6\n");
+
+    std::fs::remove_file(&path).ok();
+}
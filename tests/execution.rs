@@ -0,0 +1,265 @@
+//! Alert execution against a scripted backend: which alerts become orders
+//! and on which side, how fills shrink them, including ones filled on
+//! arrival, and what an expired order the exchange did not confirm
+//! cancelling turns into.
+//!
+//! `cargo test --test execution`
+//!
+//! Markets are tracked on the process-wide status board, so every test
+//! uses series of its own.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use white_shark::config::{ExecutionConfig, RiskConfig};
+use white_shark::error::Result;
+use white_shark::exchanges::kalshi::models::{KalshiFill, OrderSide};
+use white_shark::execution::backend::{CancelledOrder, OrderBackend, OrderRequest, PlacedOrder};
+use white_shark::execution::AlertExecutor;
+use white_shark::leader::LeaderHandle;
+use white_shark::portfolio::Portfolio;
+use white_shark::status::{self, AlertDirection, AlertRecord};
+use white_shark::tags::Tags;
+use white_shark::utils::event_id::{EventId, EventKind};
+
+#[derive(Default)]
+struct Exchange {
+    placed: Vec<(String, OrderRequest)>,
+    /// Cancels the exchange leaves out of its response
+    unconfirmed: HashSet<String>,
+    /// What a status lookup reports still resting
+    resting: HashMap<String, u64>,
+    /// Contracts every order takes from the book on arrival
+    fill_on_arrival: u64,
+}
+
+#[derive(Clone, Default)]
+struct ScriptedBackend(Arc<Mutex<Exchange>>);
+
+impl ScriptedBackend {
+    fn placed(&self) -> Vec<(String, OrderRequest)> {
+        self.0.lock().unwrap().placed.clone()
+    }
+
+    fn order_id(&self, market_ticker: &str) -> String {
+        self.placed()
+            .into_iter()
+            .find(|(_, request)| request.market_ticker == market_ticker)
+            .map(|(id, _)| id)
+            .expect("order placed")
+    }
+}
+
+#[async_trait]
+impl OrderBackend for ScriptedBackend {
+    fn name(&self) -> &'static str {
+        "scripted"
+    }
+
+    async fn place(&self, order: &OrderRequest) -> Result<PlacedOrder> {
+        let mut exchange = self.0.lock().unwrap();
+        let order_id = format!("order-{}", exchange.placed.len() + 1);
+        exchange.placed.push((order_id.clone(), order.clone()));
+        let filled = exchange.fill_on_arrival.min(order.contracts);
+        exchange.resting.insert(order_id.clone(), order.contracts - filled);
+        Ok(PlacedOrder {
+            order_id,
+            filled,
+            remaining: order.contracts - filled,
+        })
+    }
+
+    async fn cancel(&self, order_ids: &[&str]) -> Result<Vec<CancelledOrder>> {
+        let exchange = self.0.lock().unwrap();
+        Ok(order_ids
+            .iter()
+            .filter(|id| !exchange.unconfirmed.contains(**id))
+            .map(|id| CancelledOrder {
+                order_id: id.to_string(),
+                reduced_by: exchange.resting.get(*id).copied().unwrap_or(0),
+            })
+            .collect())
+    }
+
+    async fn remaining(&self, order_id: &str) -> Result<Option<u64>> {
+        Ok(self.0.lock().unwrap().resting.get(order_id).copied())
+    }
+}
+
+fn config(order_ttl_secs: u64) -> ExecutionConfig {
+    ExecutionConfig {
+        side: OrderSide::Yes,
+        price_cents: 40,
+        contracts: 5,
+        order_ttl_secs,
+        cooldown_secs: 60,
+        paper: false,
+        risk: RiskConfig::default(),
+    }
+}
+
+fn executor(backend: &ScriptedBackend, portfolio: Arc<Portfolio>, order_ttl_secs: u64) -> AlertExecutor {
+    AlertExecutor::new(
+        Box::new(backend.clone()),
+        portfolio,
+        config(order_ttl_secs),
+        LeaderHandle::always(),
+    )
+}
+
+fn track(market_ticker: &str, series: &str) {
+    status::board().track_market(market_ticker, series);
+}
+
+fn alert(series: Option<&str>, direction: Option<AlertDirection>) -> AlertRecord {
+    AlertRecord {
+        event_id: EventId::new(EventKind::Alert).to_string(),
+        timestamp: Utc::now(),
+        symbol: "BTCUSDT".to_string(),
+        kalshi_series: series.map(str::to_string),
+        message: "test alert".to_string(),
+        imbalance_ratio: None,
+        tags: Tags::default(),
+        calendar_event: None,
+        direction,
+    }
+}
+
+fn fill(order_id: &str, market_ticker: &str, count: i64) -> KalshiFill {
+    KalshiFill {
+        trade_id: format!("{}-{}", order_id, count),
+        order_id: order_id.to_string(),
+        market_ticker: market_ticker.to_string(),
+        is_taker: false,
+        side: OrderSide::Yes,
+        yes_price: Some(40),
+        count,
+        action: Some("buy".to_string()),
+        ts: Some(Utc::now().timestamp()),
+    }
+}
+
+#[tokio::test]
+async fn alerts_buy_the_side_they_point_to() {
+    track("KXEXUP-26OCT16-T1", "KXEXUP");
+    track("KXEXDOWN-26OCT16-T1", "KXEXDOWN");
+    track("KXEXFLAT-26OCT16-T1", "KXEXFLAT");
+    let backend = ScriptedBackend::default();
+    let mut executor = executor(&backend, Arc::new(Portfolio::new("scripted")), 30);
+
+    executor.on_alert(&alert(Some("KXEXUP"), Some(AlertDirection::Up))).await;
+    executor.on_alert(&alert(Some("KXEXDOWN"), Some(AlertDirection::Down))).await;
+    // No direction, no series, or an order already resting: not traded
+    executor.on_alert(&alert(Some("KXEXFLAT"), None)).await;
+    executor.on_alert(&alert(None, Some(AlertDirection::Up))).await;
+    executor.on_alert(&alert(Some("KXEXUP"), Some(AlertDirection::Down))).await;
+
+    let sides: Vec<(String, OrderSide)> = backend
+        .placed()
+        .into_iter()
+        .map(|(_, request)| (request.market_ticker, request.side))
+        .collect();
+    assert_eq!(
+        sides,
+        vec![
+            ("KXEXUP-26OCT16-T1".to_string(), OrderSide::Yes),
+            ("KXEXDOWN-26OCT16-T1".to_string(), OrderSide::No),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn fills_shrink_and_then_drop_the_order() {
+    let market = "KXEXFILL-26OCT16-T1";
+    track(market, "KXEXFILL");
+    let backend = ScriptedBackend::default();
+    let portfolio = Arc::new(Portfolio::new("scripted"));
+    let mut executor = executor(&backend, portfolio.clone(), 30);
+
+    executor.on_alert(&alert(Some("KXEXFILL"), Some(AlertDirection::Up))).await;
+    let order_id = backend.order_id(market);
+    assert_eq!(executor.resting_on(market), 5);
+
+    executor.on_fill(&fill(&order_id, market, 2));
+    assert_eq!(executor.resting_on(market), 3);
+    assert_eq!(portfolio.position(market), 2);
+
+    executor.on_fill(&fill(&order_id, market, 3));
+    assert_eq!(executor.resting_on(market), 0);
+    assert_eq!(portfolio.position(market), 5);
+
+    // A fill of an order we don't know still moves the portfolio
+    executor.on_fill(&fill("elsewhere", market, 1));
+    assert_eq!(portfolio.position(market), 6);
+
+    // Nothing rests any more, but the market is still cooling down
+    executor.on_alert(&alert(Some("KXEXFILL"), Some(AlertDirection::Up))).await;
+    assert_eq!(backend.placed().len(), 1);
+}
+
+#[tokio::test]
+async fn fills_on_arrival_are_not_counted_twice() {
+    let market = "KXEXTAKE-26OCT16-T1";
+    track(market, "KXEXTAKE");
+    let backend = ScriptedBackend::default();
+    backend.0.lock().unwrap().fill_on_arrival = 2;
+    let portfolio = Arc::new(Portfolio::new("scripted"));
+    let mut executor = executor(&backend, portfolio.clone(), 30);
+
+    executor.on_alert(&alert(Some("KXEXTAKE"), Some(AlertDirection::Up))).await;
+    let order_id = backend.order_id(market);
+    assert_eq!(executor.resting_on(market), 3);
+
+    // The taker fill of what filled on arrival comes through the channel too
+    let taken = KalshiFill {
+        is_taker: true,
+        ..fill(&order_id, market, 2)
+    };
+    executor.on_fill(&taken);
+    assert_eq!(executor.resting_on(market), 3);
+    assert_eq!(portfolio.position(market), 2);
+
+    executor.on_fill(&fill(&order_id, market, 2));
+    assert_eq!(executor.resting_on(market), 1, "still rests, so the TTL can cancel it");
+    assert_eq!(portfolio.position(market), 4);
+}
+
+#[tokio::test]
+async fn unconfirmed_cancels_are_looked_up_not_dropped() {
+    let confirmed = "KXEXCXLA-26OCT16-T1";
+    let still_resting = "KXEXCXLB-26OCT16-T1";
+    let gone = "KXEXCXLC-26OCT16-T1";
+    track(confirmed, "KXEXCXLA");
+    track(still_resting, "KXEXCXLB");
+    track(gone, "KXEXCXLC");
+    let backend = ScriptedBackend::default();
+    let mut executor = executor(&backend, Arc::new(Portfolio::new("scripted")), 1);
+
+    for series in ["KXEXCXLA", "KXEXCXLB", "KXEXCXLC"] {
+        executor.on_alert(&alert(Some(series), Some(AlertDirection::Up))).await;
+    }
+    {
+        let mut exchange = backend.0.lock().unwrap();
+        let still_resting_id = exchange.placed[1].0.clone();
+        let gone_id = exchange.placed[2].0.clone();
+        exchange.unconfirmed.insert(still_resting_id.clone());
+        exchange.unconfirmed.insert(gone_id.clone());
+        // Filled elsewhere before the cancel reached it
+        exchange.resting.remove(&gone_id);
+        exchange.resting.insert(still_resting_id, 4);
+    }
+
+    // Nothing has rested for the TTL yet
+    executor.cancel_expired().await;
+    assert_eq!(executor.resting_on(confirmed), 5);
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    executor.cancel_expired().await;
+    assert_eq!(executor.resting_on(confirmed), 0);
+    assert_eq!(executor.resting_on(still_resting), 4, "kept with what still rests");
+    assert_eq!(executor.resting_on(gone), 0);
+}
@@ -0,0 +1,21 @@
+use white_shark::fixtures::{check_fields, decoded_fields, load_corpus};
+
+const CORPUS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus");
+
+#[test]
+fn corpus_frames_decode_to_expected_fields() {
+    let fixtures = load_corpus(CORPUS_DIR).expect("Failed to load corpus");
+    assert!(!fixtures.is_empty(), "No fixtures found in {}", CORPUS_DIR);
+
+    let failures: Vec<String> = fixtures
+        .iter()
+        .filter_map(|fixture| {
+            let result = decoded_fields(fixture.source, &fixture.data)
+                .map_err(|e| e.to_string())
+                .and_then(|actual| check_fields(&fixture.expected, &actual));
+            result.err().map(|e| format!("{}: {}", fixture.name, e))
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "Golden decoding mismatches:\n{}", failures.join("\n"));
+}
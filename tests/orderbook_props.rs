@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use proptest::prelude::*;
+
+use white_shark::exchanges::kalshi::models::{
+    KalshiOrderbook, KalshiOrderbookDelta, KalshiOrderbookSnapshot, OrderbookLevel,
+};
+
+const MARKET: &str = "KXBTC15M-TEST-00";
+
+fn dollars(cents: i64) -> String {
+    format!("{:.4}", cents as f64 / 100.0)
+}
+
+fn cents(price: f64) -> i64 {
+    (price * 100.0).round() as i64
+}
+
+/// One side of a snapshot: unique prices in cents with positive quantities.
+fn side() -> impl Strategy<Value = BTreeMap<i64, i64>> {
+    prop::collection::btree_map(1i64..=99, 1i64..=10_000, 0..15)
+}
+
+fn delta() -> impl Strategy<Value = (bool, i64, i64)> {
+    (any::<bool>(), 1i64..=99, -5_000i64..=5_000).prop_filter("zero delta", |(_, _, d)| *d != 0)
+}
+
+fn snapshot(yes: &BTreeMap<i64, i64>, no: &BTreeMap<i64, i64>) -> KalshiOrderbookSnapshot {
+    let levels = |side: &BTreeMap<i64, i64>| side.iter().map(|(p, q)| (dollars(*p), *q)).collect();
+    KalshiOrderbookSnapshot {
+        market_ticker: MARKET.to_string(),
+        yes_dollars: levels(yes),
+        no_dollars: levels(no),
+    }
+}
+
+/// Reference semantics of a delta on one side of the book.
+fn apply_to_model(side: &mut BTreeMap<i64, i64>, price: i64, delta: i64) {
+    match side.get(&price).copied() {
+        Some(qty) if qty + delta <= 0 => {
+            side.remove(&price);
+        }
+        Some(qty) => {
+            side.insert(price, qty + delta);
+        }
+        None if delta > 0 => {
+            side.insert(price, delta);
+        }
+        None => {}
+    }
+}
+
+fn as_model(levels: &[OrderbookLevel]) -> BTreeMap<i64, i64> {
+    levels.iter().map(|l| (cents(l.price), l.quantity)).collect()
+}
+
+fn assert_invariants(book: &KalshiOrderbook) -> Result<(), TestCaseError> {
+    for (name, levels, descending) in [
+        ("yes_bids", &book.yes_bids, true),
+        ("no_bids", &book.no_bids, true),
+        ("yes_asks", &book.yes_asks, false),
+        ("no_asks", &book.no_asks, false),
+    ] {
+        for pair in levels.windows(2) {
+            let ordered = if descending {
+                pair[0].price > pair[1].price
+            } else {
+                pair[0].price < pair[1].price
+            };
+            prop_assert!(ordered, "{} not strictly sorted: {:?}", name, levels);
+        }
+        for level in levels.iter() {
+            prop_assert!(level.quantity > 0, "{} has non-positive quantity: {:?}", name, level);
+        }
+    }
+
+    // A YES ask is the mirror of a NO bid, and vice versa.
+    prop_assert_eq!(book.yes_asks.len(), book.no_bids.len());
+    prop_assert_eq!(book.no_asks.len(), book.yes_bids.len());
+    for ask in &book.yes_asks {
+        let mirrored = book
+            .no_bids
+            .iter()
+            .any(|bid| (bid.price - (1.0 - ask.price)).abs() < 1e-9 && bid.quantity == ask.quantity);
+        prop_assert!(mirrored, "yes ask {:?} has no matching no bid", ask);
+    }
+    for ask in &book.no_asks {
+        let mirrored = book
+            .yes_bids
+            .iter()
+            .any(|bid| (bid.price - (1.0 - ask.price)).abs() < 1e-9 && bid.quantity == ask.quantity);
+        prop_assert!(mirrored, "no ask {:?} has no matching yes bid", ask);
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn snapshot_then_deltas_keep_book_consistent(
+        yes in side(),
+        no in side(),
+        deltas in prop::collection::vec(delta(), 0..100),
+    ) {
+        let mut book = KalshiOrderbook::new_empty(MARKET.to_string());
+        book.apply_snapshot(snapshot(&yes, &no));
+        assert_invariants(&book)?;
+
+        let (mut model_yes, mut model_no) = (yes, no);
+        for (is_yes, price, qty) in deltas {
+            let delta = KalshiOrderbookDelta {
+                market_ticker: MARKET.to_string(),
+                price_dollars: dollars(price),
+                delta: qty,
+                side: if is_yes { "yes" } else { "no" }.to_string(),
+            };
+            prop_assert!(book.apply_delta(&delta).is_ok());
+            apply_to_model(if is_yes { &mut model_yes } else { &mut model_no }, price, qty);
+
+            assert_invariants(&book)?;
+            prop_assert_eq!(&as_model(&book.yes_bids), &model_yes);
+            prop_assert_eq!(&as_model(&book.no_bids), &model_no);
+        }
+    }
+
+    #[test]
+    fn top_of_book_matches_best_levels(yes in side(), no in side()) {
+        let mut book = KalshiOrderbook::new_empty(MARKET.to_string());
+        book.apply_snapshot(snapshot(&yes, &no));
+
+        let best_yes_bid = yes.keys().next_back().map(|c| *c as f64 / 100.0).unwrap_or(0.0);
+        let best_no_bid = no.keys().next_back().map(|c| *c as f64 / 100.0).unwrap_or(0.0);
+        prop_assert!((book.top_yes_bid() - best_yes_bid).abs() < 1e-9);
+        prop_assert!((book.top_no_bid() - best_no_bid).abs() < 1e-9);
+        if !no.is_empty() {
+            prop_assert!((book.top_yes_ask() - (1.0 - best_no_bid)).abs() < 1e-9);
+        }
+        if !yes.is_empty() {
+            prop_assert!((book.top_no_ask() - (1.0 - best_yes_bid)).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Outage scenario: the connection drops in the middle of a market window.
+//!
+//! Frames sent while the connection is down are lost, and after the
+//! resubscribe the stream repeats some frames that were already applied.
+//! Books rebuilt through the normal recovery path (gap detection, snapshot
+//! resync and sequence dedup) must end up where an uninterrupted run does.
+//!
+//! The Kalshi side replays the recording in `tests/scenarios/connection_drop`
+//! through the client's message handling.
+//!
+//! `cargo test --test outage_connection_drop`
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+
+use white_shark::exchanges::binance::orderbook::{DepthDiff, DiffOutcome, LocalOrderBook};
+use white_shark::exchanges::binance::rest::DepthSnapshot;
+use white_shark::exchanges::dedup::SequenceFilter;
+use white_shark::exchanges::kalshi::models::{KalshiMarket, KalshiOrderbook, OrderbookLevel};
+use white_shark::exchanges::kalshi::replay::KalshiFeed;
+use white_shark::exchanges::PriceLevel;
+use white_shark::replay::{ReplayOptions, Replayer};
+
+const SYMBOL: &str = "BTCUSDT";
+const SEED: u64 = 4040;
+
+/// Diffs in the window, the one the connection drops after, and how many
+/// are sent while it is down.
+const DIFFS: usize = 600;
+const DROP_AT: usize = 280;
+const OUTAGE: usize = 45;
+/// Already-applied frames the stream repeats after the resubscribe
+const REPLAYED: usize = 12;
+/// Diffs buffered before the REST snapshot comes back
+const SNAPSHOT_LAG: usize = 8;
+
+/// Bid and ask (or YES and NO) levels as price, quantity pairs
+type Sides<T> = (Vec<(T, T)>, Vec<(T, T)>);
+
+fn binance_diffs(rng: &mut StdRng, first_id: i64) -> Vec<DepthDiff> {
+    let mut next_id = first_id;
+    (0..DIFFS)
+        .map(|_| {
+            let span = rng.gen_range(0..3);
+            let mut side = |base: f64, sign: f64| -> Vec<(f64, f64)> {
+                (0..rng.gen_range(1..4))
+                    .map(|_| {
+                        let price = base + sign * rng.gen_range(0..20) as f64 * 0.01;
+                        let qty = if rng.gen_bool(0.2) { 0.0 } else { rng.gen_range(1..500) as f64 / 100.0 };
+                        (price, qty)
+                    })
+                    .collect()
+            };
+            let diff = DepthDiff {
+                first_update_id: next_id,
+                final_update_id: next_id + span,
+                prev_final_update_id: None,
+                bids: side(99.99, -1.0),
+                asks: side(100.01, 1.0),
+            };
+            next_id += span + 1;
+            diff
+        })
+        .collect()
+}
+
+fn initial_snapshot(last_update_id: i64) -> DepthSnapshot {
+    DepthSnapshot {
+        symbol: SYMBOL.to_string(),
+        last_update_id,
+        bids: (0..20).map(|i| (99.99 - i as f64 * 0.01, 1.0 + i as f64)).collect(),
+        asks: (0..20).map(|i| (100.01 + i as f64 * 0.01, 1.0 + i as f64)).collect(),
+    }
+}
+
+/// What a REST snapshot taken from `book` would return.
+fn snapshot_of(book: &LocalOrderBook) -> DepthSnapshot {
+    let levels = |levels: Vec<PriceLevel>| levels.iter().map(|l| (l.price, l.quantity)).collect();
+    DepthSnapshot {
+        symbol: SYMBOL.to_string(),
+        last_update_id: book.last_update_id().expect("book is synced"),
+        bids: levels(book.bids(usize::MAX)),
+        asks: levels(book.asks(usize::MAX)),
+    }
+}
+
+fn levels(book: &LocalOrderBook) -> Sides<f64> {
+    let pairs = |levels: Vec<PriceLevel>| levels.iter().map(|l| (l.price, l.quantity)).collect();
+    (pairs(book.bids(usize::MAX)), pairs(book.asks(usize::MAX)))
+}
+
+#[test]
+fn binance_book_resyncs_after_drop_mid_window() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let snapshot = initial_snapshot(1_000);
+    let diffs = binance_diffs(&mut rng, 1_001);
+
+    // Uninterrupted run, keeping the book as of every diff for the REST
+    // snapshots the recovering run fetches
+    let mut baseline = LocalOrderBook::new(SYMBOL);
+    assert!(baseline.sync(&snapshot));
+    let mut history = Vec::with_capacity(DIFFS);
+    for diff in &diffs {
+        assert_eq!(baseline.apply_diff(diff.clone()), DiffOutcome::Applied);
+        history.push(baseline.clone());
+    }
+
+    let mut book = LocalOrderBook::new(SYMBOL);
+    assert!(book.sync(&snapshot));
+    for diff in &diffs[..DROP_AT] {
+        assert_eq!(book.apply_diff(diff.clone()), DiffOutcome::Applied);
+    }
+
+    // Connection drops; diffs[DROP_AT..resume] never arrive
+    let resume = DROP_AT + OUTAGE;
+
+    // Repeated frames are already reflected in the book and change nothing
+    let before_replay = levels(&book);
+    for diff in &diffs[DROP_AT - REPLAYED..DROP_AT] {
+        assert_eq!(book.apply_diff(diff.clone()), DiffOutcome::Applied);
+    }
+    assert_eq!(levels(&book), before_replay);
+
+    // The first diff after the gap resets the book and asks for a snapshot
+    assert_eq!(book.apply_diff(diffs[resume].clone()), DiffOutcome::NeedsSnapshot);
+    assert!(!book.is_synced());
+
+    // A snapshot from before the outage cannot bridge the gap
+    assert!(!book.sync(&snapshot_of(&history[DROP_AT - 1])));
+    assert!(!book.is_synced());
+    book.snapshot_failed();
+
+    // Diffs keep arriving while the retry is pending
+    for diff in &diffs[resume + 1..resume + SNAPSHOT_LAG] {
+        assert_eq!(book.apply_diff(diff.clone()), DiffOutcome::Buffered);
+    }
+
+    // The retried snapshot lands inside the buffered range
+    assert!(book.sync(&snapshot_of(&history[resume + SNAPSHOT_LAG / 2])));
+    assert_eq!(book.last_update_id(), history[resume + SNAPSHOT_LAG - 1].last_update_id());
+
+    for diff in &diffs[resume + SNAPSHOT_LAG..] {
+        assert_eq!(book.apply_diff(diff.clone()), DiffOutcome::Applied);
+    }
+    assert_eq!(book.last_update_id(), baseline.last_update_id());
+    assert_eq!(levels(&book), levels(&baseline));
+}
+
+#[test]
+fn binance_trades_replayed_after_resubscribe_are_dropped() {
+    let mut filter: SequenceFilter<String> = SequenceFilter::new("binance", "trade");
+    let mut accepted = Vec::new();
+    let ids: Vec<i64> = (1..=DIFFS as i64).collect();
+    let resume = DROP_AT + OUTAGE;
+
+    let delivered = ids[..DROP_AT]
+        .iter()
+        .chain(&ids[DROP_AT - REPLAYED..DROP_AT])
+        .chain(&ids[resume..]);
+    for id in delivered {
+        if !filter.is_duplicate(SYMBOL, *id) {
+            accepted.push(*id);
+        }
+    }
+
+    let expected: Vec<i64> = ids[..DROP_AT].iter().chain(&ids[resume..]).copied().collect();
+    assert_eq!(accepted, expected);
+}
+
+/// Kalshi's recorded side of the drop; see the test below.
+const KALSHI_RECORDING: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/scenarios/connection_drop");
+const SERIES: &str = "KXBTC15M";
+const MARKET: &str = "KXBTC15M-26OCT161200-00";
+
+fn kalshi_market() -> KalshiMarket {
+    serde_json::from_value(json!({
+        "ticker": MARKET,
+        "status": "active",
+        "close_time": "2026-10-16T12:00:00Z",
+        "series_ticker": SERIES,
+    }))
+    .expect("valid market")
+}
+
+fn kalshi_levels(book: &KalshiOrderbook) -> Sides<i64> {
+    let cents = |levels: &[OrderbookLevel]| {
+        let mut levels: Vec<(i64, i64)> = levels
+            .iter()
+            .map(|l| ((l.price * 100.0).round() as i64, l.quantity))
+            .collect();
+        levels.sort();
+        levels
+    };
+    (cents(&book.yes_bids), cents(&book.no_bids))
+}
+
+/// The recording drops the connection after a snapshot and two deltas, one
+/// of them sent twice. Two deltas are lost while it is down; the new
+/// connection resubscribes under the same sid with a snapshot that includes
+/// them, then repeats a delta of its own. Kalshi deltas are relative, so
+/// the repeats would be counted twice if they got past the sequence filter,
+/// and the second snapshot would be dropped if its `seq` were checked
+/// against the first connection's.
+#[tokio::test]
+async fn kalshi_book_recovers_from_resubscribe_snapshot() {
+    let mut frames = Replayer::new(ReplayOptions {
+        dir: KALSHI_RECORDING.to_string(),
+        from_micros: None,
+        to_micros: None,
+        speed: 0.0,
+        skip_gaps_ms: None,
+        symbols: Vec::new(),
+    })
+    .spawn()
+    .expect("recording opens");
+
+    let mut feed = KalshiFeed::new();
+    feed.set_market(SERIES, &kalshi_market());
+    let mut replayed = 0;
+    while let Some(frame) = frames.recv().await {
+        feed.apply(&frame).await.expect("recorded frame applies");
+        replayed += 1;
+    }
+    assert_eq!(replayed, 12);
+
+    let state = feed.state();
+    let book = state.get_orderbook(MARKET).expect("book rebuilt");
+    assert_eq!(
+        kalshi_levels(&book),
+        (vec![(40, 100), (42, 50), (44, 10)], vec![(50, 50), (53, 40)])
+    );
+    let cents = |price: Option<f64>| price.map(|p| (p * 100.0).round() as i64);
+    assert_eq!(cents(state.get_top_bid(MARKET)), Some(44));
+    assert_eq!(cents(state.get_top_ask(MARKET)), Some(47));
+}
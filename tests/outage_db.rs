@@ -0,0 +1,125 @@
+//! Outage scenario: the database goes away while imbalance alerts are being
+//! persisted.
+//!
+//! The alert writer must ride out the outage: alerts raised while the
+//! table is unreachable are counted as write errors and dropped, and the
+//! ones raised after it comes back are stored without a restart.
+//!
+//! The outage is simulated by renaming `imbalance_alerts` away and back, so
+//! this needs a scratch database nothing else is writing to, given as
+//! `SCENARIO_DATABASE_URL`; the scenario is skipped without one.
+//!
+//! `SCENARIO_DATABASE_URL=postgres://... cargo test --test outage_db`
+
+#![cfg(feature = "db")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, Statement};
+use tokio::sync::mpsc;
+
+use white_shark::config::Persistence;
+use white_shark::db::main::Db;
+use white_shark::exchanges::binance::alerts::ImbalanceAlertWriter;
+use white_shark::exchanges::binance::sbe::events::depth::ImbalanceAlert;
+use white_shark::leader::LeaderHandle;
+use white_shark::metrics::{names, registry};
+use white_shark::status::AlertDirection;
+use white_shark::utils::event_id::{EventId, EventKind};
+
+const TABLE: &str = "imbalance_alerts";
+const OUTAGE_TABLE: &str = "imbalance_alerts_outage";
+const BEFORE: usize = 3;
+const DURING: usize = 4;
+const AFTER: usize = 5;
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn alert(symbol: &str) -> ImbalanceAlert {
+    ImbalanceAlert {
+        event_id: EventId::new(EventKind::Alert),
+        timestamp: Utc::now(),
+        symbol: symbol.to_string(),
+        depth: "N_5",
+        ratio: 5.0,
+        direction: AlertDirection::Up,
+        message: "N_5 depth imbalance 5.0".to_string(),
+        bid_qty: 50.0,
+        ask_qty: 10.0,
+        icebergs: Vec::new(),
+    }
+}
+
+async fn send(tx: &mpsc::Sender<ImbalanceAlert>, symbol: &str, count: usize) {
+    for _ in 0..count {
+        tx.send(alert(symbol)).await.expect("writer running");
+    }
+}
+
+async fn execute(db: &Db, sql: &str) {
+    db.connection().execute_unprepared(sql).await.expect(sql);
+}
+
+/// Rows stored for `symbol`, which is unique to the run.
+async fn stored(db: &Db, symbol: &str) -> i64 {
+    let sql = format!("SELECT COUNT(*) AS n FROM {} WHERE symbol = '{}'", TABLE, symbol);
+    let row = db
+        .connection()
+        .query_one(Statement::from_string(db.backend(), sql))
+        .await
+        .expect("count stored alerts")
+        .expect("count row");
+    row.try_get("", "n").expect("count column")
+}
+
+fn write_errors() -> u64 {
+    registry().counter_value(names::DB_WRITE_ERRORS_TOTAL, &[("table", TABLE)])
+}
+
+async fn settle<F, Fut>(what: &str, mut done: F)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = bool>,
+{
+    let deadline = tokio::time::Instant::now() + SETTLE_TIMEOUT;
+    while !done().await {
+        assert!(tokio::time::Instant::now() < deadline, "timed out waiting for {}", what);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn alert_writer_recovers_after_database_outage() {
+    let Ok(url) = std::env::var("SCENARIO_DATABASE_URL") else {
+        eprintln!("SCENARIO_DATABASE_URL not set, skipping");
+        return;
+    };
+    let db = Arc::new(Db::new(&url).await.expect("connect to scenario database"));
+    db.create_imbalance_alerts_table().await.expect("create table");
+    let symbol = format!("SCENARIO{}", Utc::now().timestamp_micros());
+
+    let tx = ImbalanceAlertWriter::spawn(db.clone(), None, Persistence::All, LeaderHandle::always());
+    let (scratch, symbol) = (db.as_ref(), symbol.as_str());
+
+    send(&tx, symbol, BEFORE).await;
+    settle("alerts before the outage", || async move { stored(scratch, symbol).await == BEFORE as i64 }).await;
+
+    let errors_before = write_errors();
+    execute(scratch, &format!("ALTER TABLE {} RENAME TO {}", TABLE, OUTAGE_TABLE)).await;
+    send(&tx, symbol, DURING).await;
+    settle("write errors during the outage", || async move {
+        write_errors() >= errors_before + DURING as u64
+    })
+    .await;
+    execute(scratch, &format!("ALTER TABLE {} RENAME TO {}", OUTAGE_TABLE, TABLE)).await;
+
+    send(&tx, symbol, AFTER).await;
+    settle("alerts after the outage", || async move {
+        stored(scratch, symbol).await == (BEFORE + AFTER) as i64
+    })
+    .await;
+    assert_eq!(write_errors(), errors_before + DURING as u64);
+
+    execute(scratch, &format!("DELETE FROM {} WHERE symbol = '{}'", TABLE, symbol)).await;
+}
@@ -0,0 +1,153 @@
+//! Outage scenario: a Kalshi market rolls over while an alert on it is
+//! still being monitored.
+//!
+//! The closing market is retired rather than dropped, so the monitor keeps
+//! reading its book through the grace period while new alerts already map
+//! to the next market. Nothing of the old market is left once the grace
+//! period is over.
+//!
+//! The rollover itself replays the recording in `tests/scenarios/rollover`
+//! through the client's message handling.
+//!
+//! `cargo test --test outage_kalshi_rollover`
+//!
+//! The status board is process-wide, so only the first test uses it.
+
+use std::time::Duration;
+
+use serde_json::json;
+
+use white_shark::exchanges::kalshi::models::{KalshiMarket, KalshiOrderbook, KalshiOrderbookSnapshot};
+use white_shark::exchanges::kalshi::replay::KalshiFeed;
+use white_shark::replay::{ReplayOptions, Replayer};
+use white_shark::state::KalshiState;
+use white_shark::status;
+
+const RECORDING: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/scenarios/rollover");
+const SERIES: &str = "KXBTC15M";
+const CLOSING: &str = "KXBTC15M-26OCT161200-00";
+const NEXT: &str = "KXBTC15M-26OCT161215-15";
+const MONITOR: &str = "alert-rollover";
+/// Long enough that nothing expires while the scenario runs
+const GRACE: Duration = Duration::from_secs(3600);
+
+fn market(ticker: &str, close_time: &str) -> KalshiMarket {
+    serde_json::from_value(json!({
+        "ticker": ticker,
+        "status": "active",
+        "close_time": close_time,
+        "series_ticker": SERIES,
+    }))
+    .expect("valid market")
+}
+
+fn track(state: &KalshiState, ticker: &str, close_time: &str, yes_cents: i64) {
+    state.tracked_markets.insert(ticker.to_string(), market(ticker, close_time));
+    let mut book = KalshiOrderbook::new_empty(ticker.to_string());
+    book.apply_snapshot(KalshiOrderbookSnapshot {
+        market_ticker: ticker.to_string(),
+        yes_dollars: vec![(format!("{:.4}", yes_cents as f64 / 100.0), 10)],
+        no_dollars: vec![(format!("{:.4}", (99 - yes_cents) as f64 / 100.0), 10)],
+    });
+    state.orderbooks.insert(ticker.to_string(), book);
+}
+
+fn top_bid_cents(state: &KalshiState, ticker: &str) -> Option<i64> {
+    state.get_top_bid(ticker).map(|p| (p * 100.0).round() as i64)
+}
+
+/// Replays `tests/scenarios/rollover`: CLOSING's book and a delta, its
+/// `determined` lifecycle message, the orderbook channel moving to NEXT
+/// under a new sid, and a last CLOSING delta still in flight on the old one.
+/// Discovery is stood in for by setting NEXT once CLOSING is retired.
+#[tokio::test]
+async fn monitor_survives_rollover_until_grace_period_ends() {
+    let mut frames = Replayer::new(ReplayOptions {
+        dir: RECORDING.to_string(),
+        from_micros: None,
+        to_micros: None,
+        speed: 0.0,
+        skip_gaps_ms: None,
+        symbols: Vec::new(),
+    })
+    .spawn()
+    .expect("recording opens");
+
+    let mut feed = KalshiFeed::new();
+    let state = feed.state();
+    feed.set_market(SERIES, &market(CLOSING, "2026-10-16T12:00:00Z"));
+    assert_eq!(state.current_market_for_series(SERIES).as_deref(), Some(CLOSING));
+    status::board().monitor_started(MONITOR, &format!("imbalance on {}", CLOSING));
+
+    let mut rolled_over = false;
+    while let Some(frame) = frames.recv().await {
+        feed.apply(&frame).await.expect("recorded frame applies");
+        if !rolled_over && state.is_retired(CLOSING) {
+            // New alerts map to nothing until the next market is set
+            assert_eq!(state.current_market_for_series(SERIES), None);
+            assert!(status::board().markets_of_series(SERIES).is_empty());
+            feed.set_market(SERIES, &market(NEXT, "2026-10-16T12:15:00Z"));
+            rolled_over = true;
+        }
+    }
+    assert!(rolled_over, "CLOSING was never retired");
+
+    // New alerts map to the next market, and only it is traded
+    assert_eq!(state.current_market_for_series(SERIES).as_deref(), Some(NEXT));
+    assert_eq!(status::board().markets_of_series(SERIES), vec![NEXT.to_string()]);
+    assert_eq!(top_bid_cents(&state, NEXT), Some(56));
+
+    // The monitor keeps reading the closed market's book, which the late
+    // delta left alone
+    assert!(status::board().active_monitors().contains_key(MONITOR));
+    assert!(state.is_retired(CLOSING));
+    assert_eq!(top_bid_cents(&state, CLOSING), Some(41));
+    assert!(state.evict_retired(GRACE).is_empty());
+    assert_eq!(top_bid_cents(&state, CLOSING), Some(41));
+
+    status::board().monitor_finished(MONITOR, "rolled over");
+    assert!(!status::board().active_monitors().contains_key(MONITOR));
+    assert!(status::board()
+        .recent_outcomes()
+        .iter()
+        .any(|o| o.id == MONITOR && o.outcome == "rolled over"));
+
+    // Grace period over: the closed market is gone, the next one untouched
+    assert_eq!(state.evict_retired(Duration::ZERO), vec![CLOSING.to_string()]);
+    assert!(!state.tracked_markets.contains_key(CLOSING));
+    assert!(state.get_orderbook(CLOSING).is_none());
+    assert!(!state.is_retired(CLOSING));
+    assert_eq!(state.current_market_for_series(SERIES).as_deref(), Some(NEXT));
+    assert_eq!(top_bid_cents(&state, NEXT), Some(56));
+}
+
+#[test]
+fn late_data_for_a_rolled_over_market_is_evicted() {
+    let state = KalshiState::new();
+    track(&state, NEXT, "2026-10-16T12:15:00Z", 55);
+
+    // A delta for the old market, in flight across the rollover, recreates
+    // its book after it was evicted
+    state
+        .orderbooks
+        .insert(CLOSING.to_string(), KalshiOrderbook::new_empty(CLOSING.to_string()));
+
+    assert_eq!(state.evict_retired(Duration::ZERO), vec![CLOSING.to_string()]);
+    assert!(state.get_orderbook(CLOSING).is_none());
+    assert_eq!(state.current_market_for_series(SERIES).as_deref(), Some(NEXT));
+}
+
+#[test]
+fn reopened_market_is_reinstated() {
+    let state = KalshiState::new();
+    track(&state, CLOSING, "2026-10-16T12:00:00Z", 40);
+
+    state.retire_market(CLOSING);
+    assert_eq!(state.current_market_for_series(SERIES), None);
+
+    // A pause lifted before the grace period ends
+    state.reinstate_market(CLOSING);
+    assert_eq!(state.current_market_for_series(SERIES).as_deref(), Some(CLOSING));
+    assert!(state.evict_retired(Duration::ZERO).is_empty());
+    assert_eq!(top_bid_cents(&state, CLOSING), Some(40));
+}
@@ -0,0 +1,115 @@
+//! Paper matching against a scripted Kalshi book: asks taken on arrival
+//! are not taken again, size added later is, a trade through the limit
+//! fills what remains, and a full fills queue is waited out rather than
+//! dropped.
+//!
+//! `cargo test --test paper`
+//!
+//! Resting orders are matched every 250ms, so the tests wait a few rounds.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::mpsc;
+
+use white_shark::exchanges::kalshi::models::{KalshiFill, KalshiOrderbook, OrderSide, OrderbookLevel};
+use white_shark::exchanges::kalshi::reference::LastTrade;
+use white_shark::execution::backend::{OrderBackend, OrderRequest};
+use white_shark::execution::paper::PaperBackend;
+use white_shark::state::KalshiState;
+
+const MARKET: &str = "KXPAPER-26OCT16-T1";
+
+fn book(yes_asks: &[(f64, i64)]) -> KalshiOrderbook {
+    KalshiOrderbook {
+        market_ticker: MARKET.to_string(),
+        yes_bids: Vec::new(),
+        yes_asks: yes_asks
+            .iter()
+            .map(|&(price, quantity)| OrderbookLevel { price, quantity })
+            .collect(),
+        no_bids: Vec::new(),
+        no_asks: Vec::new(),
+    }
+}
+
+fn request(contracts: u64) -> OrderRequest {
+    OrderRequest {
+        market_ticker: MARKET.to_string(),
+        side: OrderSide::Yes,
+        price_cents: 40,
+        contracts,
+        client_order_id: "paper-test".to_string(),
+    }
+}
+
+fn backend(state: &Arc<KalshiState>, capacity: usize) -> (PaperBackend, mpsc::Receiver<KalshiFill>) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let backend = PaperBackend::new(state.clone());
+    backend.attach_fills(tx);
+    (backend, rx)
+}
+
+/// Fills delivered over a few match rounds, as (count, is_taker).
+async fn drain(rx: &mut mpsc::Receiver<KalshiFill>) -> Vec<(i64, bool)> {
+    let mut fills = Vec::new();
+    while let Ok(Some(fill)) = tokio::time::timeout(Duration::from_millis(700), rx.recv()).await {
+        fills.push((fill.count, fill.is_taker));
+    }
+    fills
+}
+
+#[tokio::test]
+async fn taken_asks_fill_once_and_new_size_fills_again() {
+    let state = Arc::new(KalshiState::new());
+    state.orderbooks.insert(MARKET.to_string(), book(&[(0.38, 1), (0.40, 2), (0.45, 10)]));
+    let (backend, mut rx) = backend(&state, 16);
+
+    let placed = backend.place(&request(5)).await.unwrap();
+    assert_eq!((placed.filled, placed.remaining), (3, 2));
+    assert_eq!(drain(&mut rx).await, vec![(1, true), (2, true)], "the book still shows what we took");
+    assert_eq!(backend.remaining(&placed.order_id).await.unwrap(), Some(2));
+
+    // One more contract offered at 40c
+    state.orderbooks.insert(MARKET.to_string(), book(&[(0.38, 1), (0.40, 3), (0.45, 10)]));
+    assert_eq!(drain(&mut rx).await, vec![(1, false)]);
+    assert_eq!(backend.remaining(&placed.order_id).await.unwrap(), Some(1));
+}
+
+#[tokio::test]
+async fn a_trade_through_the_limit_fills_the_rest() {
+    let state = Arc::new(KalshiState::new());
+    state.orderbooks.insert(MARKET.to_string(), book(&[(0.45, 10)]));
+    let (backend, mut rx) = backend(&state, 16);
+
+    let placed = backend.place(&request(5)).await.unwrap();
+    assert_eq!(placed.remaining, 5);
+    assert!(drain(&mut rx).await.is_empty());
+
+    state.last_trades.insert(
+        MARKET.to_string(),
+        LastTrade {
+            yes_price: 0.35,
+            at: Utc::now(),
+        },
+    );
+    assert_eq!(drain(&mut rx).await, vec![(5, false)]);
+    assert_eq!(backend.remaining(&placed.order_id).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn a_full_queue_delays_fills_instead_of_dropping_them() {
+    let state = Arc::new(KalshiState::new());
+    state.orderbooks.insert(MARKET.to_string(), book(&[(0.40, 10)]));
+    let (backend, mut rx) = backend(&state, 1);
+
+    for _ in 0..4 {
+        backend.place(&request(2)).await.unwrap();
+    }
+    // Nobody reads while the match loop has more fills than the queue holds
+    tokio::time::sleep(Duration::from_millis(600)).await;
+
+    let filled: i64 = drain(&mut rx).await.iter().map(|(count, _)| count).sum();
+    assert_eq!(filled, 8);
+}
@@ -0,0 +1,77 @@
+//! Positions netted from fills in YES terms: opening averages the price,
+//! a partial close realizes against that average and keeps it, and a fill
+//! through flat realizes the old position and opens the rest at its own
+//! price. The trader's orders land in the same portfolio.
+//!
+//! `cargo test --test portfolio`
+
+use std::sync::Arc;
+
+use white_shark::exchanges::kalshi::models::OrderSide;
+use white_shark::portfolio::{MarketPosition, Portfolio};
+use white_shark::trader::positions::PositionManager;
+use white_shark::utils::event_id::{EventId, EventKind};
+
+const MARKET: &str = "KXPNL-26OCT16-T1";
+
+fn only(portfolio: &Portfolio) -> MarketPosition {
+    let mut positions = portfolio.positions();
+    assert_eq!(positions.len(), 1);
+    positions.remove(0)
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn opens_partially_closes_and_flips_through_flat() {
+    let portfolio = Portfolio::new("test");
+
+    portfolio.record(MARKET, OrderSide::Yes, false, 2, 0.40);
+    portfolio.record(MARKET, OrderSide::Yes, false, 2, 0.50);
+    let p = only(&portfolio);
+    assert_eq!(p.position, 4);
+    assert!(close(p.avg_price, 0.45));
+    assert!(close(p.realized_pnl, 0.0));
+
+    // Selling 1 YES at 60c realizes 15c and leaves the average alone
+    portfolio.record(MARKET, OrderSide::Yes, true, 1, 0.60);
+    let p = only(&portfolio);
+    assert_eq!(p.position, 3);
+    assert!(close(p.avg_price, 0.45));
+    assert!(close(p.realized_pnl, 0.15));
+
+    // Buying 5 NO at 70c is selling 5 YES at 30c: 3 close, 2 open short
+    portfolio.record(MARKET, OrderSide::No, false, 5, 0.30);
+    let p = only(&portfolio);
+    assert_eq!(p.position, -2);
+    assert!(close(p.avg_price, 0.30));
+    assert!(close(p.realized_pnl, 0.15 - 3.0 * 0.15));
+
+    portfolio.record(MARKET, OrderSide::No, true, 2, 0.20);
+    let p = only(&portfolio);
+    assert_eq!(p.position, 0);
+    assert!(close(p.avg_price, 0.0));
+    assert!(close(p.realized_pnl, -0.30 + 2.0 * 0.10));
+    assert!(close(portfolio.total_pnl(), -0.10));
+}
+
+#[test]
+fn the_trader_nets_into_the_shared_portfolio() {
+    let portfolio = Arc::new(Portfolio::new("test"));
+    let positions = PositionManager::new(portfolio.clone());
+    let event_id = EventId::new(EventKind::Trade);
+
+    positions.add_fill(&event_id, MARKET, OrderSide::No, "a".into(), 3, 0.20, 0);
+    // Resting, nothing filled yet
+    positions.add_fill(&event_id, MARKET, OrderSide::No, "b".into(), 0, 0.15, 5);
+
+    assert_eq!(portfolio.position(MARKET), -3);
+    assert_eq!(positions.inventory(MARKET), Some((OrderSide::No, 3)));
+    assert_eq!(positions.open_order_ids_for(MARKET), vec!["b".to_string()]);
+    assert_eq!(positions.resting(MARKET), -5);
+    let holdings = positions.holdings();
+    assert_eq!(holdings.len(), 1);
+    assert!(close(holdings[0].cost, 0.60));
+}
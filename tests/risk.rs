@@ -0,0 +1,98 @@
+//! Pre-trade limits against a portfolio: the kill switch stops every
+//! order until released, positions Kalshi reported at startup count
+//! towards the position limit, open orders are capped, and the daily loss
+//! engages the kill switch and is counted afresh once the market day
+//! turns over.
+//!
+//! `cargo test --test risk`
+//!
+//! The kill switch is process-wide, so the checks run in one test.
+
+use chrono::{Days, Utc};
+
+use white_shark::config::RiskConfig;
+use white_shark::exchanges::kalshi::models::{KalshiFill, KalshiMarketPosition, OrderSide};
+use white_shark::execution::risk::{kill_switch, Breach, RiskManager};
+use white_shark::portfolio::Portfolio;
+use white_shark::timezone;
+
+const MARKET: &str = "KXRISK-26OCT16-T1";
+
+fn fill(action: &str, count: i64, yes_cents: i64) -> KalshiFill {
+    KalshiFill {
+        trade_id: format!("{}-{}-{}", action, count, yes_cents),
+        order_id: "risk-test".to_string(),
+        market_ticker: MARKET.to_string(),
+        is_taker: false,
+        side: OrderSide::Yes,
+        yes_price: Some(yes_cents),
+        count,
+        action: Some(action.to_string()),
+        ts: Some(Utc::now().timestamp()),
+    }
+}
+
+#[test]
+fn limits_count_existing_holdings_and_the_market_day() {
+    let portfolio = Portfolio::new("kalshi");
+    // 8 NO bought at 30c before the restart
+    portfolio.seed(&[KalshiMarketPosition {
+        ticker: MARKET.to_string(),
+        position: -8,
+        market_exposure: 240,
+        realized_pnl: 0,
+        fees_paid: 0,
+    }]);
+    assert_eq!(portfolio.position(MARKET), -8);
+    assert!(portfolio.total_pnl().abs() < 1e-9, "held at cost until marked");
+
+    let config = RiskConfig {
+        max_position: Some(10),
+        max_open_orders: Some(2),
+        max_daily_loss: Some(1.5),
+    };
+    let mut risk = RiskManager::new(config, &portfolio);
+
+    // Positions, counting what already rests
+    assert_eq!(risk.check(MARKET, -2, 0, 0, &portfolio), Ok(()));
+    assert_eq!(
+        risk.check(MARKET, -2, -1, 0, &portfolio),
+        Err(Breach::Position {
+            market: MARKET.to_string(),
+            would_hold: -11,
+            max: 10
+        })
+    );
+    assert_eq!(risk.check(MARKET, 18, 0, 0, &portfolio), Ok(()), "buying back towards flat");
+    assert_eq!(risk.check(MARKET, 5, 0, 2, &portfolio), Err(Breach::OpenOrders { open: 2, max: 2 }));
+
+    kill_switch().engage("test halt");
+    kill_switch().engage("a later reason");
+    assert_eq!(
+        risk.check(MARKET, 1, 0, 0, &portfolio),
+        Err(Breach::Halted("test halt".to_string()))
+    );
+    kill_switch().release();
+    assert_eq!(risk.check(MARKET, 1, 0, 0, &portfolio), Ok(()));
+
+    // Closed at 85c YES, 15c worse than the 70c it opened at
+    portfolio.record_fill(&fill("buy", 8, 85));
+    assert_eq!(portfolio.position(MARKET), 0);
+    assert_eq!(risk.check_daily_loss(&portfolio), None, "$1.20 lost");
+    portfolio.record_fill(&fill("buy", 1, 50));
+    portfolio.record_fill(&fill("sell", 1, 10));
+    let Some(Breach::DailyLoss { loss, .. }) = risk.check_daily_loss(&portfolio) else {
+        panic!("$1.60 lost breaches");
+    };
+    assert!((loss - 1.6).abs() < 1e-9);
+    assert!(kill_switch().reason().is_some(), "the loss engaged the kill switch");
+    assert!(matches!(risk.check(MARKET, 1, 0, 0, &portfolio), Err(Breach::Halted(_))));
+    kill_switch().release();
+
+    // A new market day starts from what the last one lost
+    let today = Utc::now().with_timezone(&timezone::market()).date_naive();
+    let tomorrow = today.checked_add_days(Days::new(1)).unwrap();
+    assert_eq!(risk.check_daily_loss_on(tomorrow, &portfolio), None);
+    assert_eq!(risk.check(MARKET, 1, 0, 0, &portfolio), Ok(()));
+    assert!(kill_switch().reason().is_none());
+}
@@ -0,0 +1,64 @@
+//! The trader's orders go through the same kill switch as alert orders:
+//! while it is engaged an order is refused before anything reaches
+//! Kalshi, and releasing it lets orders through the limits again.
+//!
+//! `cargo test --test trader_risk`
+//!
+//! The kill switch is process-wide, so the checks run in one test.
+
+use std::sync::Arc;
+
+use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+use rsa::RsaPrivateKey;
+
+use white_shark::config::RiskConfig;
+use white_shark::exchanges::kalshi::api::KalshiApi;
+use white_shark::exchanges::kalshi::auth::KalshiAuth;
+use white_shark::exchanges::kalshi::models::{OrderSide, OrderType};
+use white_shark::execution::risk::kill_switch;
+use white_shark::portfolio::Portfolio;
+use white_shark::trader::executor::OrderExecutor;
+use white_shark::trader::main::OrderDecision;
+use white_shark::trader::positions::PositionManager;
+use white_shark::utils::event_id::{EventId, EventKind};
+
+const MARKET: &str = "KXTRADERRISK-26OCT16-T1";
+
+fn api() -> Arc<KalshiApi> {
+    let key = RsaPrivateKey::new(&mut rand::rngs::OsRng, 1024).unwrap();
+    let pem = key.to_pkcs8_pem(LineEnding::LF).unwrap();
+    Arc::new(KalshiApi::new(Arc::new(KalshiAuth::from_pem_content("test", &pem).unwrap())))
+}
+
+fn order(contracts: u64) -> OrderDecision {
+    OrderDecision::Place {
+        event_id: EventId::new(EventKind::Trade),
+        leg: 0,
+        ticker: MARKET.to_string(),
+        side: OrderSide::Yes,
+        price: 0.99,
+        contracts,
+        order_type: OrderType::Market,
+    }
+}
+
+#[tokio::test]
+async fn an_engaged_kill_switch_refuses_trader_orders() {
+    let positions = PositionManager::new(Arc::new(Portfolio::new("kalshi")));
+    let config = RiskConfig {
+        max_position: Some(5),
+        ..Default::default()
+    };
+    let mut executor = OrderExecutor::new(api(), positions.clone(), config);
+
+    kill_switch().engage("test halt");
+    let err = executor.execute(order(1)).await.unwrap_err();
+    assert!(err.to_string().contains("kill switch engaged (test halt)"), "{}", err);
+    assert!(positions.get(MARKET).is_none(), "nothing was placed");
+
+    // Released, the limits still apply before Kalshi is asked
+    kill_switch().release();
+    let err = executor.execute(order(6)).await.unwrap_err();
+    assert!(err.to_string().contains("would hold 6 contracts"), "{}", err);
+    assert!(positions.get(MARKET).is_none());
+}
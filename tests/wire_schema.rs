@@ -0,0 +1,156 @@
+//! The checked-in bincode layout of `market_event` against the build: the
+//! file for the current version exists, and every `MarketEvent` variant and
+//! `Exchange` is listed under the index bincode actually writes for it.
+//! Adding a variant without a new layout file fails here.
+//!
+//! `cargo test --test wire_schema`
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+
+use white_shark::exchanges::event::{
+    AlertEvent, BookEvent, KalshiTopOfBookChanged, LifecycleEvent, QuoteEvent, TopOfBook, TradeEvent,
+};
+use white_shark::exchanges::schema::{encode_compact, Versioned};
+use white_shark::exchanges::{EventMeta, Exchange, MarketEvent, TradeSide};
+
+/// Variant name -> index of `enum name` in the layout file.
+fn layout_enum(layout: &str, name: &str) -> BTreeMap<String, u32> {
+    let header = format!("enum {} {{", name);
+    let body = layout
+        .split_once(&header)
+        .and_then(|(_, rest)| rest.split_once('}'))
+        .map(|(body, _)| body)
+        .unwrap_or_else(|| panic!("enum {} not in the layout", name));
+    body.lines()
+        .filter_map(|line| {
+            let (index, variant) = line.trim().split_once(' ')?;
+            let variant = variant.split('(').next()?.trim();
+            Some((variant.to_string(), index.parse().ok()?))
+        })
+        .collect()
+}
+
+fn meta(exchange: Exchange) -> EventMeta {
+    EventMeta::new(exchange, "KXBTCD-26OCT16-T1", Utc::now())
+}
+
+/// One event of every variant; the match makes a new variant fail to
+/// compile here until it is added.
+fn events() -> Vec<(&'static str, MarketEvent)> {
+    let book = || BookEvent {
+        meta: meta(Exchange::Kalshi),
+        bids: Vec::new(),
+        asks: Vec::new(),
+    };
+    let top = TopOfBook {
+        bid: 0.4,
+        bid_qty: 1.0,
+        ask: 0.5,
+        ask_qty: 1.0,
+    };
+    let events = vec![
+        MarketEvent::Trade(TradeEvent {
+            meta: meta(Exchange::Kalshi),
+            trade_id: 1,
+            price: 0.5,
+            quantity: 1.0,
+            side: TradeSide::Buy,
+        }),
+        MarketEvent::Quote(QuoteEvent {
+            meta: meta(Exchange::Kalshi),
+            bid: 0.4,
+            bid_qty: 1.0,
+            ask: 0.5,
+            ask_qty: 1.0,
+            binary: None,
+        }),
+        MarketEvent::BookSnapshot(book()),
+        MarketEvent::BookDelta(book()),
+        MarketEvent::Lifecycle(LifecycleEvent {
+            meta: meta(Exchange::Kalshi),
+            status: "open".to_string(),
+            result: None,
+        }),
+        MarketEvent::Alert(AlertEvent {
+            meta: meta(Exchange::Binance),
+            event_id: "alert".to_string(),
+            message: "test".to_string(),
+            direction: None,
+            kalshi_ticker: None,
+        }),
+        MarketEvent::TopOfBookChanged(KalshiTopOfBookChanged {
+            meta: meta(Exchange::Kalshi),
+            previous: top,
+            current: top,
+        }),
+    ];
+    events
+        .into_iter()
+        .map(|event| {
+            let name = match &event {
+                MarketEvent::Trade(_) => "Trade",
+                MarketEvent::Quote(_) => "Quote",
+                MarketEvent::BookSnapshot(_) => "BookSnapshot",
+                MarketEvent::BookDelta(_) => "BookDelta",
+                MarketEvent::Lifecycle(_) => "Lifecycle",
+                MarketEvent::Alert(_) => "Alert",
+                MarketEvent::TopOfBookChanged(_) => "TopOfBookChanged",
+            };
+            (name, event)
+        })
+        .collect()
+}
+
+fn exchanges() -> Vec<(&'static str, Exchange)> {
+    [Exchange::Kalshi, Exchange::Binance, Exchange::Okx, Exchange::Polymarket]
+        .into_iter()
+        .map(|exchange| {
+            let name = match exchange {
+                Exchange::Kalshi => "Kalshi",
+                Exchange::Binance => "Binance",
+                Exchange::Okx => "Okx",
+                Exchange::Polymarket => "Polymarket",
+            };
+            (name, exchange)
+        })
+        .collect()
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn market_event_layout_matches_the_build() {
+    let path = format!(
+        "{}/schema/market_event.v{}.txt",
+        env!("CARGO_MANIFEST_DIR"),
+        MarketEvent::VERSION
+    );
+    let layout = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    assert!(layout.contains(&format!("u32 version = {}", MarketEvent::VERSION)));
+
+    // Version prefix, then the variant index
+    let variants = layout_enum(&layout, "MarketEvent");
+    let events = events();
+    assert_eq!(variants.len(), events.len(), "MarketEvent variants in {}", path);
+    for (name, event) in &events {
+        let bytes = encode_compact(event).unwrap();
+        assert_eq!(variants.get(*name), Some(&u32_at(&bytes, 4)), "MarketEvent::{}", name);
+    }
+
+    // A trade's meta leads with the exchange, after the variant index
+    let listed = layout_enum(&layout, "Exchange");
+    let exchanges = exchanges();
+    assert_eq!(listed.len(), exchanges.len(), "Exchange variants in {}", path);
+    for (name, exchange) in exchanges {
+        let MarketEvent::Trade(mut trade) = events[0].1.clone() else {
+            unreachable!();
+        };
+        trade.meta.exchange = exchange;
+        let bytes = encode_compact(&MarketEvent::Trade(trade)).unwrap();
+        assert_eq!(listed.get(name), Some(&u32_at(&bytes, 8)), "Exchange::{}", name);
+    }
+}